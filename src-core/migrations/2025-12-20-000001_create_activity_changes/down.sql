@@ -0,0 +1,5 @@
+DROP TRIGGER IF EXISTS activity_changes_no_delete;
+DROP TRIGGER IF EXISTS activity_changes_no_update;
+DROP INDEX IF EXISTS idx_activity_changes_batch_id;
+DROP INDEX IF EXISTS idx_activity_changes_activity_id;
+DROP TABLE IF EXISTS activity_changes;
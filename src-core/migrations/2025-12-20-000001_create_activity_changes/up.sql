@@ -0,0 +1,31 @@
+-- Append-only change log for activity mutations.
+-- Each row stores the full activity row before and after the mutation as JSON so
+-- that any single change, or a whole bulk mutation (same batch_id), can be reverted.
+-- There is intentionally no foreign key to activities: history must outlive deletes.
+CREATE TABLE IF NOT EXISTS activity_changes (
+    id TEXT NOT NULL PRIMARY KEY,
+    activity_id TEXT NOT NULL,
+    batch_id TEXT,
+    change_type TEXT NOT NULL,
+    before_state TEXT,
+    after_state TEXT,
+    actor TEXT NOT NULL,
+    changed_at TEXT NOT NULL,
+    reverted_change_id TEXT
+);
+
+CREATE INDEX idx_activity_changes_activity_id ON activity_changes(activity_id);
+CREATE INDEX idx_activity_changes_batch_id ON activity_changes(batch_id);
+
+-- Enforce append-only semantics at the database level
+CREATE TRIGGER activity_changes_no_update
+BEFORE UPDATE ON activity_changes
+BEGIN
+    SELECT RAISE(ABORT, 'activity_changes is append-only');
+END;
+
+CREATE TRIGGER activity_changes_no_delete
+BEFORE DELETE ON activity_changes
+BEGIN
+    SELECT RAISE(ABORT, 'activity_changes is append-only');
+END;
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_account_tags_tag_id;
+DROP INDEX IF EXISTS idx_activity_tags_tag_id;
+DROP TABLE IF EXISTS account_tags;
+DROP TABLE IF EXISTS activity_tags;
+DROP TABLE IF EXISTS tags;
@@ -0,0 +1,28 @@
+-- User-defined tags that group activities and accounts across the portfolio
+CREATE TABLE IF NOT EXISTS tags (
+    id TEXT NOT NULL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    color TEXT,
+    description TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS activity_tags (
+    activity_id TEXT NOT NULL,
+    tag_id TEXT NOT NULL,
+    PRIMARY KEY (activity_id, tag_id),
+    FOREIGN KEY (activity_id) REFERENCES activities(id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS account_tags (
+    account_id TEXT NOT NULL,
+    tag_id TEXT NOT NULL,
+    PRIMARY KEY (account_id, tag_id),
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_activity_tags_tag_id ON activity_tags(tag_id);
+CREATE INDEX idx_account_tags_tag_id ON account_tags(tag_id);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_activity_attachments_content_hash;
+DROP INDEX IF EXISTS idx_activity_attachments_activity_id;
+DROP TABLE IF EXISTS activity_attachments;
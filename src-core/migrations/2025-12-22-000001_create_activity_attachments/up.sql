@@ -0,0 +1,16 @@
+-- Files attached to activities (contract notes, receipts, transfer screenshots).
+-- File contents live on disk under <data dir>/attachments, addressed by their SHA-256,
+-- so identical files attached to several activities are stored once.
+CREATE TABLE IF NOT EXISTS activity_attachments (
+    id TEXT NOT NULL PRIMARY KEY,
+    activity_id TEXT NOT NULL,
+    content_hash TEXT NOT NULL,
+    file_name TEXT NOT NULL,
+    mime_type TEXT,
+    size_bytes BIGINT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (activity_id) REFERENCES activities(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_activity_attachments_activity_id ON activity_attachments(activity_id);
+CREATE INDEX idx_activity_attachments_content_hash ON activity_attachments(content_hash);
@@ -0,0 +1,11 @@
+-- Links the two legs of an internal transfer between accounts.
+-- Paired legs move the source lots (with their acquisition dates and costs)
+-- into the destination account instead of opening new lots at the transfer price.
+CREATE TABLE IF NOT EXISTS activity_transfer_pairs (
+    id TEXT NOT NULL PRIMARY KEY,
+    transfer_out_activity_id TEXT NOT NULL UNIQUE,
+    transfer_in_activity_id TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (transfer_out_activity_id) REFERENCES activities(id) ON DELETE CASCADE,
+    FOREIGN KEY (transfer_in_activity_id) REFERENCES activities(id) ON DELETE CASCADE
+);
@@ -0,0 +1,7 @@
+-- Benchmark symbol an account (or the TOTAL portfolio) is compared against.
+-- Accounts without a row are compared against VNINDEX.
+CREATE TABLE IF NOT EXISTS account_benchmarks (
+    account_id TEXT NOT NULL PRIMARY KEY,
+    symbol TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
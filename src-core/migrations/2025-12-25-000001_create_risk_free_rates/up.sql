@@ -0,0 +1,11 @@
+-- Dated annual risk-free rates (e.g. 12-month VND deposit rate, US T-bill rate).
+-- A rate applies from its effective date until the next entry of the same series.
+CREATE TABLE IF NOT EXISTS risk_free_rates (
+    id TEXT NOT NULL PRIMARY KEY,
+    series TEXT NOT NULL,
+    effective_date DATE NOT NULL,
+    annual_rate TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    UNIQUE (series, effective_date)
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS composite_portfolio_members;
+DROP TABLE IF EXISTS composite_portfolios;
@@ -0,0 +1,17 @@
+-- Named sets of accounts and account groups measured as a single portfolio
+CREATE TABLE IF NOT EXISTS composite_portfolios (
+    id TEXT NOT NULL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    description TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+-- member_type is 'account' (member_id = accounts.id) or 'group' (member_id = accounts.group)
+CREATE TABLE IF NOT EXISTS composite_portfolio_members (
+    composite_id TEXT NOT NULL,
+    member_type TEXT NOT NULL,
+    member_id TEXT NOT NULL,
+    PRIMARY KEY (composite_id, member_type, member_id),
+    FOREIGN KEY (composite_id) REFERENCES composite_portfolios(id) ON DELETE CASCADE
+);
@@ -0,0 +1,11 @@
+DROP TRIGGER IF EXISTS valuation_checkpoints_fx_delete;
+DROP TRIGGER IF EXISTS valuation_checkpoints_fx_update;
+DROP TRIGGER IF EXISTS valuation_checkpoints_fx_insert;
+DROP TRIGGER IF EXISTS valuation_checkpoints_quote_delete;
+DROP TRIGGER IF EXISTS valuation_checkpoints_quote_update;
+DROP TRIGGER IF EXISTS valuation_checkpoints_quote_insert;
+DROP TRIGGER IF EXISTS valuation_checkpoints_account_active;
+DROP TRIGGER IF EXISTS valuation_checkpoints_activity_delete;
+DROP TRIGGER IF EXISTS valuation_checkpoints_activity_update;
+DROP TRIGGER IF EXISTS valuation_checkpoints_activity_insert;
+DROP TABLE IF EXISTS valuation_checkpoints;
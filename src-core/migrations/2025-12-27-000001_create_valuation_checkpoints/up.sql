@@ -0,0 +1,204 @@
+-- Per-account recalculation checkpoints. Holdings snapshots and daily valuations are
+-- known to be correct up to the *_calculated_through dates, except from the
+-- *_dirty_from dates onward, which the triggers below move back whenever an input
+-- changes. Rebuilds start at the dirty date instead of the first activity.
+-- The 'TOTAL' row tracks the aggregate portfolio.
+CREATE TABLE IF NOT EXISTS valuation_checkpoints (
+    account_id TEXT NOT NULL PRIMARY KEY,
+    holdings_dirty_from DATE,
+    valuation_dirty_from DATE,
+    holdings_calculated_through DATE,
+    valuation_calculated_through DATE,
+    updated_at TEXT NOT NULL
+);
+
+-- Activities change the holdings of their account, and of TOTAL, from their date on.
+CREATE TRIGGER valuation_checkpoints_activity_insert
+AFTER INSERT ON activities
+BEGIN
+    INSERT OR IGNORE INTO valuation_checkpoints (account_id, updated_at)
+    VALUES (NEW.account_id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+           ('TOTAL', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+    UPDATE valuation_checkpoints
+    SET holdings_dirty_from = CASE
+            WHEN holdings_dirty_from IS NULL OR holdings_dirty_from > date(NEW.activity_date)
+            THEN date(NEW.activity_date) ELSE holdings_dirty_from END,
+        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    WHERE account_id IN (NEW.account_id, 'TOTAL');
+END;
+
+CREATE TRIGGER valuation_checkpoints_activity_update
+AFTER UPDATE ON activities
+WHEN OLD.account_id IS NOT NEW.account_id
+    OR OLD.asset_id IS NOT NEW.asset_id
+    OR OLD.activity_type IS NOT NEW.activity_type
+    OR OLD.activity_date IS NOT NEW.activity_date
+    OR OLD.quantity IS NOT NEW.quantity
+    OR OLD.unit_price IS NOT NEW.unit_price
+    OR OLD.currency IS NOT NEW.currency
+    OR OLD.fee IS NOT NEW.fee
+    OR OLD.amount IS NOT NEW.amount
+    OR OLD.is_draft IS NOT NEW.is_draft
+BEGIN
+    INSERT OR IGNORE INTO valuation_checkpoints (account_id, updated_at)
+    VALUES (OLD.account_id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+           (NEW.account_id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+           ('TOTAL', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+    UPDATE valuation_checkpoints
+    SET holdings_dirty_from = CASE
+            WHEN holdings_dirty_from IS NULL OR holdings_dirty_from > date(OLD.activity_date)
+            THEN date(OLD.activity_date) ELSE holdings_dirty_from END,
+        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    WHERE account_id IN (OLD.account_id, 'TOTAL');
+    UPDATE valuation_checkpoints
+    SET holdings_dirty_from = CASE
+            WHEN holdings_dirty_from IS NULL OR holdings_dirty_from > date(NEW.activity_date)
+            THEN date(NEW.activity_date) ELSE holdings_dirty_from END,
+        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    WHERE account_id IN (NEW.account_id, 'TOTAL');
+END;
+
+CREATE TRIGGER valuation_checkpoints_activity_delete
+AFTER DELETE ON activities
+BEGIN
+    INSERT OR IGNORE INTO valuation_checkpoints (account_id, updated_at)
+    VALUES (OLD.account_id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+           ('TOTAL', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+    UPDATE valuation_checkpoints
+    SET holdings_dirty_from = CASE
+            WHEN holdings_dirty_from IS NULL OR holdings_dirty_from > date(OLD.activity_date)
+            THEN date(OLD.activity_date) ELSE holdings_dirty_from END,
+        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    WHERE account_id IN (OLD.account_id, 'TOTAL');
+END;
+
+-- Toggling an account in or out of the portfolio changes TOTAL over its whole history,
+-- and a reactivated account was skipped while inactive.
+CREATE TRIGGER valuation_checkpoints_account_active
+AFTER UPDATE OF is_active ON accounts
+WHEN OLD.is_active IS NOT NEW.is_active
+    AND EXISTS (SELECT 1 FROM activities WHERE account_id = NEW.id)
+BEGIN
+    INSERT OR IGNORE INTO valuation_checkpoints (account_id, updated_at)
+    VALUES (NEW.id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+           ('TOTAL', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+    UPDATE valuation_checkpoints
+    SET holdings_dirty_from = (SELECT MIN(date(activity_date)) FROM activities WHERE account_id = NEW.id),
+        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    WHERE account_id IN (NEW.id, 'TOTAL')
+        AND (holdings_dirty_from IS NULL
+            OR holdings_dirty_from > (SELECT MIN(date(activity_date)) FROM activities WHERE account_id = NEW.id));
+END;
+
+-- A security quote changes the valuation of the accounts that ever traded it.
+CREATE TRIGGER valuation_checkpoints_quote_insert
+AFTER INSERT ON quotes
+WHEN EXISTS (SELECT 1 FROM activities WHERE asset_id = NEW.symbol)
+BEGIN
+    INSERT OR IGNORE INTO valuation_checkpoints (account_id, updated_at)
+    SELECT DISTINCT account_id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    FROM activities WHERE asset_id = NEW.symbol
+    UNION SELECT 'TOTAL', strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+    UPDATE valuation_checkpoints
+    SET valuation_dirty_from = CASE
+            WHEN valuation_dirty_from IS NULL OR valuation_dirty_from > date(NEW.timestamp)
+            THEN date(NEW.timestamp) ELSE valuation_dirty_from END,
+        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    WHERE account_id = 'TOTAL'
+        OR account_id IN (SELECT account_id FROM activities WHERE asset_id = NEW.symbol);
+END;
+
+CREATE TRIGGER valuation_checkpoints_quote_update
+AFTER UPDATE ON quotes
+WHEN (OLD.close IS NOT NEW.close
+        OR OLD.adjclose IS NOT NEW.adjclose
+        OR OLD.timestamp IS NOT NEW.timestamp
+        OR OLD.symbol IS NOT NEW.symbol)
+    AND EXISTS (SELECT 1 FROM activities WHERE asset_id IN (OLD.symbol, NEW.symbol))
+BEGIN
+    INSERT OR IGNORE INTO valuation_checkpoints (account_id, updated_at)
+    SELECT DISTINCT account_id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    FROM activities WHERE asset_id IN (OLD.symbol, NEW.symbol)
+    UNION SELECT 'TOTAL', strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+    UPDATE valuation_checkpoints
+    SET valuation_dirty_from = CASE
+            WHEN valuation_dirty_from IS NULL
+                OR valuation_dirty_from > min(date(OLD.timestamp), date(NEW.timestamp))
+            THEN min(date(OLD.timestamp), date(NEW.timestamp)) ELSE valuation_dirty_from END,
+        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    WHERE account_id = 'TOTAL'
+        OR account_id IN (SELECT account_id FROM activities WHERE asset_id IN (OLD.symbol, NEW.symbol));
+END;
+
+CREATE TRIGGER valuation_checkpoints_quote_delete
+AFTER DELETE ON quotes
+WHEN EXISTS (SELECT 1 FROM activities WHERE asset_id = OLD.symbol)
+BEGIN
+    INSERT OR IGNORE INTO valuation_checkpoints (account_id, updated_at)
+    SELECT DISTINCT account_id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    FROM activities WHERE asset_id = OLD.symbol
+    UNION SELECT 'TOTAL', strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+    UPDATE valuation_checkpoints
+    SET valuation_dirty_from = CASE
+            WHEN valuation_dirty_from IS NULL OR valuation_dirty_from > date(OLD.timestamp)
+            THEN date(OLD.timestamp) ELSE valuation_dirty_from END,
+        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    WHERE account_id = 'TOTAL'
+        OR account_id IN (SELECT account_id FROM activities WHERE asset_id = OLD.symbol);
+END;
+
+-- FX rates are stored as quotes of FOREX assets. They feed both the holdings
+-- calculation (activity conversion) and valuation of every account.
+CREATE TRIGGER valuation_checkpoints_fx_insert
+AFTER INSERT ON quotes
+WHEN EXISTS (SELECT 1 FROM assets WHERE id = NEW.symbol AND asset_type = 'FOREX')
+BEGIN
+    INSERT OR IGNORE INTO valuation_checkpoints (account_id, updated_at)
+    SELECT id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now') FROM accounts
+    UNION SELECT 'TOTAL', strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+    UPDATE valuation_checkpoints
+    SET holdings_dirty_from = CASE
+            WHEN holdings_dirty_from IS NULL OR holdings_dirty_from > date(NEW.timestamp)
+            THEN date(NEW.timestamp) ELSE holdings_dirty_from END,
+        valuation_dirty_from = CASE
+            WHEN valuation_dirty_from IS NULL OR valuation_dirty_from > date(NEW.timestamp)
+            THEN date(NEW.timestamp) ELSE valuation_dirty_from END,
+        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+END;
+
+CREATE TRIGGER valuation_checkpoints_fx_update
+AFTER UPDATE ON quotes
+WHEN (OLD.close IS NOT NEW.close OR OLD.timestamp IS NOT NEW.timestamp)
+    AND EXISTS (SELECT 1 FROM assets WHERE id = NEW.symbol AND asset_type = 'FOREX')
+BEGIN
+    INSERT OR IGNORE INTO valuation_checkpoints (account_id, updated_at)
+    SELECT id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now') FROM accounts
+    UNION SELECT 'TOTAL', strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+    UPDATE valuation_checkpoints
+    SET holdings_dirty_from = CASE
+            WHEN holdings_dirty_from IS NULL
+                OR holdings_dirty_from > min(date(OLD.timestamp), date(NEW.timestamp))
+            THEN min(date(OLD.timestamp), date(NEW.timestamp)) ELSE holdings_dirty_from END,
+        valuation_dirty_from = CASE
+            WHEN valuation_dirty_from IS NULL
+                OR valuation_dirty_from > min(date(OLD.timestamp), date(NEW.timestamp))
+            THEN min(date(OLD.timestamp), date(NEW.timestamp)) ELSE valuation_dirty_from END,
+        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+END;
+
+CREATE TRIGGER valuation_checkpoints_fx_delete
+AFTER DELETE ON quotes
+WHEN EXISTS (SELECT 1 FROM assets WHERE id = OLD.symbol AND asset_type = 'FOREX')
+BEGIN
+    INSERT OR IGNORE INTO valuation_checkpoints (account_id, updated_at)
+    SELECT id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now') FROM accounts
+    UNION SELECT 'TOTAL', strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+    UPDATE valuation_checkpoints
+    SET holdings_dirty_from = CASE
+            WHEN holdings_dirty_from IS NULL OR holdings_dirty_from > date(OLD.timestamp)
+            THEN date(OLD.timestamp) ELSE holdings_dirty_from END,
+        valuation_dirty_from = CASE
+            WHEN valuation_dirty_from IS NULL OR valuation_dirty_from > date(OLD.timestamp)
+            THEN date(OLD.timestamp) ELSE valuation_dirty_from END,
+        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+END;
@@ -0,0 +1,5 @@
+DROP TRIGGER IF EXISTS reconciliations_activity_delete;
+DROP TRIGGER IF EXISTS reconciliations_activity_update;
+DROP TRIGGER IF EXISTS reconciliations_activity_insert;
+DROP TABLE IF EXISTS reconciliation_lines;
+DROP TABLE IF EXISTS reconciliations;
@@ -0,0 +1,81 @@
+-- Broker statements compared with the calculated holdings of an account on a date.
+-- status is 'matched' (no differences when saved), 'mismatched', or 'broken' when a
+-- matched statement is invalidated by a later change to a non-draft activity on or before it.
+CREATE TABLE IF NOT EXISTS reconciliations (
+    id TEXT NOT NULL PRIMARY KEY,
+    account_id TEXT NOT NULL,
+    statement_date DATE NOT NULL,
+    status TEXT NOT NULL,
+    notes TEXT,
+    broken_at TEXT,
+    broken_by_activity_id TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    UNIQUE (account_id, statement_date),
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+
+-- One reported balance per cash currency (line_type 'cash', code = currency) or
+-- position (line_type 'position', code = asset_id), with the calculated value it was
+-- compared against. Amounts are decimal strings.
+CREATE TABLE IF NOT EXISTS reconciliation_lines (
+    reconciliation_id TEXT NOT NULL,
+    line_type TEXT NOT NULL,
+    code TEXT NOT NULL,
+    reported TEXT NOT NULL,
+    calculated TEXT NOT NULL,
+    PRIMARY KEY (reconciliation_id, line_type, code),
+    FOREIGN KEY (reconciliation_id) REFERENCES reconciliations(id) ON DELETE CASCADE
+);
+
+CREATE TRIGGER reconciliations_activity_insert
+AFTER INSERT ON activities
+BEGIN
+    UPDATE reconciliations
+    SET status = 'broken',
+        broken_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'),
+        broken_by_activity_id = NEW.id,
+        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    WHERE status = 'matched'
+      AND account_id = NEW.account_id
+      AND statement_date >= date(NEW.activity_date)
+      AND NEW.is_draft = 0;
+END;
+
+CREATE TRIGGER reconciliations_activity_update
+AFTER UPDATE ON activities
+WHEN OLD.account_id IS NOT NEW.account_id
+    OR OLD.asset_id IS NOT NEW.asset_id
+    OR OLD.activity_type IS NOT NEW.activity_type
+    OR OLD.activity_date IS NOT NEW.activity_date
+    OR OLD.quantity IS NOT NEW.quantity
+    OR OLD.unit_price IS NOT NEW.unit_price
+    OR OLD.currency IS NOT NEW.currency
+    OR OLD.fee IS NOT NEW.fee
+    OR OLD.amount IS NOT NEW.amount
+    OR OLD.is_draft IS NOT NEW.is_draft
+BEGIN
+    UPDATE reconciliations
+    SET status = 'broken',
+        broken_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'),
+        broken_by_activity_id = NEW.id,
+        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    WHERE status = 'matched'
+      AND ((account_id = OLD.account_id AND statement_date >= date(OLD.activity_date))
+        OR (account_id = NEW.account_id AND statement_date >= date(NEW.activity_date)))
+      AND (OLD.is_draft = 0 OR NEW.is_draft = 0);
+END;
+
+CREATE TRIGGER reconciliations_activity_delete
+AFTER DELETE ON activities
+BEGIN
+    UPDATE reconciliations
+    SET status = 'broken',
+        broken_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'),
+        broken_by_activity_id = OLD.id,
+        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    WHERE status = 'matched'
+      AND account_id = OLD.account_id
+      AND statement_date >= date(OLD.activity_date)
+      AND OLD.is_draft = 0;
+END;
@@ -0,0 +1,26 @@
+-- Margin lending terms of an account. The loan balance itself is derived from
+-- negative cash in the account currency; these rows only describe how the broker
+-- charges for it and when it calls the loan. Rates and ratios are decimal strings.
+CREATE TABLE IF NOT EXISTS margin_accounts (
+    account_id TEXT NOT NULL PRIMARY KEY,
+    annual_interest_rate TEXT NOT NULL,
+    call_ratio TEXT NOT NULL,
+    force_sell_ratio TEXT,
+    warning_buffer TEXT NOT NULL,
+    default_haircut TEXT NOT NULL,
+    interest_start_date DATE NOT NULL,
+    accrued_through DATE,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+
+-- Per-symbol haircuts overriding the account default, as published in the broker's
+-- marginable securities list.
+CREATE TABLE IF NOT EXISTS margin_haircuts (
+    account_id TEXT NOT NULL,
+    asset_id TEXT NOT NULL,
+    haircut TEXT NOT NULL,
+    PRIMARY KEY (account_id, asset_id),
+    FOREIGN KEY (account_id) REFERENCES margin_accounts(account_id) ON DELETE CASCADE
+);
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS retirement_scenarios;
@@ -0,0 +1,10 @@
+-- Saved retirement planner scenarios. inputs holds the planner inputs and result the
+-- plan calculated when the scenario was last saved, both as JSON.
+CREATE TABLE IF NOT EXISTS retirement_scenarios (
+    id TEXT NOT NULL PRIMARY KEY,
+    name TEXT NOT NULL,
+    inputs TEXT NOT NULL,
+    result TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
@@ -0,0 +1,2 @@
+-- Goals with a priority take part in the waterfall allocation, lowest number first
+ALTER TABLE goals ADD COLUMN priority INTEGER DEFAULT NULL;
@@ -0,0 +1,5 @@
+ALTER TABLE contribution_limits DROP COLUMN lifetime_cap;
+ALTER TABLE contribution_limits DROP COLUMN restore_withdrawals;
+ALTER TABLE contribution_limits DROP COLUMN carry_forward;
+ALTER TABLE contribution_limits DROP COLUMN window_months;
+ALTER TABLE contribution_limits DROP COLUMN limit_type;
@@ -0,0 +1,6 @@
+-- Rolling windows, carry-forward, withdrawal restoration and lifetime caps for contribution limits
+ALTER TABLE contribution_limits ADD COLUMN limit_type TEXT NOT NULL DEFAULT 'CALENDAR_YEAR';
+ALTER TABLE contribution_limits ADD COLUMN window_months INTEGER NULL;
+ALTER TABLE contribution_limits ADD COLUMN carry_forward BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE contribution_limits ADD COLUMN restore_withdrawals BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE contribution_limits ADD COLUMN lifetime_cap DOUBLE NULL;
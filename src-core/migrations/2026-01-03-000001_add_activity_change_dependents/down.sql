@@ -0,0 +1 @@
+ALTER TABLE activity_changes DROP COLUMN dependents_state;
@@ -0,0 +1,3 @@
+-- Rows removed by ON DELETE CASCADE along with a deleted activity (tags, attachments,
+-- transfer pair), stored as JSON on its DELETE change so a revert can restore them.
+ALTER TABLE activity_changes ADD COLUMN dependents_state TEXT;
@@ -0,0 +1,20 @@
+DROP TRIGGER IF EXISTS valuation_checkpoints_account_active;
+CREATE TRIGGER valuation_checkpoints_account_active
+AFTER UPDATE OF is_active ON accounts
+WHEN OLD.is_active IS NOT NEW.is_active
+    AND EXISTS (SELECT 1 FROM activities WHERE account_id = NEW.id)
+BEGIN
+    INSERT OR IGNORE INTO valuation_checkpoints (account_id, updated_at)
+    VALUES (NEW.id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+           ('TOTAL', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+    UPDATE valuation_checkpoints
+    SET holdings_dirty_from = (SELECT MIN(date(activity_date)) FROM activities WHERE account_id = NEW.id),
+        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    WHERE account_id IN (NEW.id, 'TOTAL')
+        AND (holdings_dirty_from IS NULL
+            OR holdings_dirty_from > (SELECT MIN(date(activity_date)) FROM activities WHERE account_id = NEW.id));
+END;
+DROP TRIGGER IF EXISTS valuation_checkpoints_valuation_dirty_version;
+DROP TRIGGER IF EXISTS valuation_checkpoints_holdings_dirty_version;
+ALTER TABLE valuation_checkpoints DROP COLUMN valuation_dirty_version;
+ALTER TABLE valuation_checkpoints DROP COLUMN holdings_dirty_version;
@@ -0,0 +1,45 @@
+-- Dirty dates only ever move back, so a change dated on or after the current dirty date
+-- leaves them untouched. A rebuild could then clear a date that a change made while it
+-- ran still needed. Every change now bumps a version, and a finished rebuild clears the
+-- dirty date only if the version it started from is unchanged.
+ALTER TABLE valuation_checkpoints ADD COLUMN holdings_dirty_version BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE valuation_checkpoints ADD COLUMN valuation_dirty_version BIGINT NOT NULL DEFAULT 0;
+
+-- The activity, quote and FX triggers SET the dirty date on every change, even when its
+-- value stays the same, which fires these.
+CREATE TRIGGER valuation_checkpoints_holdings_dirty_version
+AFTER UPDATE OF holdings_dirty_from ON valuation_checkpoints
+BEGIN
+    UPDATE valuation_checkpoints
+    SET holdings_dirty_version = holdings_dirty_version + 1
+    WHERE account_id = NEW.account_id;
+END;
+
+CREATE TRIGGER valuation_checkpoints_valuation_dirty_version
+AFTER UPDATE OF valuation_dirty_from ON valuation_checkpoints
+BEGIN
+    UPDATE valuation_checkpoints
+    SET valuation_dirty_version = valuation_dirty_version + 1
+    WHERE account_id = NEW.account_id;
+END;
+
+-- Only updated rows whose dirty date moved back; toggling an account must also count as
+-- a change when the date stays.
+DROP TRIGGER IF EXISTS valuation_checkpoints_account_active;
+CREATE TRIGGER valuation_checkpoints_account_active
+AFTER UPDATE OF is_active ON accounts
+WHEN OLD.is_active IS NOT NEW.is_active
+    AND EXISTS (SELECT 1 FROM activities WHERE account_id = NEW.id)
+BEGIN
+    INSERT OR IGNORE INTO valuation_checkpoints (account_id, updated_at)
+    VALUES (NEW.id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+           ('TOTAL', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+    UPDATE valuation_checkpoints
+    SET holdings_dirty_from = CASE
+            WHEN holdings_dirty_from IS NULL
+                OR holdings_dirty_from > (SELECT MIN(date(activity_date)) FROM activities WHERE account_id = NEW.id)
+            THEN (SELECT MIN(date(activity_date)) FROM activities WHERE account_id = NEW.id)
+            ELSE holdings_dirty_from END,
+        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    WHERE account_id IN (NEW.id, 'TOTAL');
+END;
@@ -0,0 +1,56 @@
+-- The column cannot be dropped while a trigger reads it
+DROP TRIGGER IF EXISTS valuation_checkpoints_activity_update;
+DROP TRIGGER IF EXISTS valuation_checkpoints_fx_update;
+
+ALTER TABLE activities DROP COLUMN fx_rate_side;
+
+CREATE TRIGGER valuation_checkpoints_activity_update
+AFTER UPDATE ON activities
+WHEN OLD.account_id IS NOT NEW.account_id
+    OR OLD.asset_id IS NOT NEW.asset_id
+    OR OLD.activity_type IS NOT NEW.activity_type
+    OR OLD.activity_date IS NOT NEW.activity_date
+    OR OLD.quantity IS NOT NEW.quantity
+    OR OLD.unit_price IS NOT NEW.unit_price
+    OR OLD.currency IS NOT NEW.currency
+    OR OLD.fee IS NOT NEW.fee
+    OR OLD.amount IS NOT NEW.amount
+    OR OLD.is_draft IS NOT NEW.is_draft
+BEGIN
+    INSERT OR IGNORE INTO valuation_checkpoints (account_id, updated_at)
+    VALUES (OLD.account_id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+           (NEW.account_id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+           ('TOTAL', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+    UPDATE valuation_checkpoints
+    SET holdings_dirty_from = CASE
+            WHEN holdings_dirty_from IS NULL OR holdings_dirty_from > date(OLD.activity_date)
+            THEN date(OLD.activity_date) ELSE holdings_dirty_from END,
+        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    WHERE account_id IN (OLD.account_id, 'TOTAL');
+    UPDATE valuation_checkpoints
+    SET holdings_dirty_from = CASE
+            WHEN holdings_dirty_from IS NULL OR holdings_dirty_from > date(NEW.activity_date)
+            THEN date(NEW.activity_date) ELSE holdings_dirty_from END,
+        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    WHERE account_id IN (NEW.account_id, 'TOTAL');
+END;
+
+CREATE TRIGGER valuation_checkpoints_fx_update
+AFTER UPDATE ON quotes
+WHEN (OLD.close IS NOT NEW.close OR OLD.timestamp IS NOT NEW.timestamp)
+    AND EXISTS (SELECT 1 FROM assets WHERE id = NEW.symbol AND asset_type = 'FOREX')
+BEGIN
+    INSERT OR IGNORE INTO valuation_checkpoints (account_id, updated_at)
+    SELECT id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now') FROM accounts
+    UNION SELECT 'TOTAL', strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+    UPDATE valuation_checkpoints
+    SET holdings_dirty_from = CASE
+            WHEN holdings_dirty_from IS NULL
+                OR holdings_dirty_from > min(date(OLD.timestamp), date(NEW.timestamp))
+            THEN min(date(OLD.timestamp), date(NEW.timestamp)) ELSE holdings_dirty_from END,
+        valuation_dirty_from = CASE
+            WHEN valuation_dirty_from IS NULL
+                OR valuation_dirty_from > min(date(OLD.timestamp), date(NEW.timestamp))
+            THEN min(date(OLD.timestamp), date(NEW.timestamp)) ELSE valuation_dirty_from END,
+        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+END;
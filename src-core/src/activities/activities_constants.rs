@@ -55,3 +55,13 @@ pub const TRADING_ACTIVITY_TYPES: [&str; 5] = [
 
 /// Income activity types
 pub const INCOME_ACTIVITY_TYPES: [&str; 2] = [ACTIVITY_TYPE_DIVIDEND, ACTIVITY_TYPE_INTEREST];
+
+/// Change log entry types recorded in `activity_changes`
+pub const ACTIVITY_CHANGE_CREATE: &str = "CREATE";
+pub const ACTIVITY_CHANGE_UPDATE: &str = "UPDATE";
+pub const ACTIVITY_CHANGE_DELETE: &str = "DELETE";
+
+/// Actors recorded against activity changes
+pub const ACTIVITY_CHANGE_ACTOR_USER: &str = "user";
+pub const ACTIVITY_CHANGE_ACTOR_IMPORT: &str = "import";
+pub const ACTIVITY_CHANGE_ACTOR_REVERT: &str = "revert";
@@ -1,4 +1,6 @@
 use crate::activities::activities_errors::ActivityError;
+use crate::attachments::ActivityAttachment;
+use crate::fx::FxRateSide;
 use crate::Result;
 use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use diesel::prelude::*;
@@ -59,6 +61,9 @@ pub struct Activity {
     pub amount: Option<Decimal>,
     pub is_draft: bool,
     pub comment: Option<String>,
+    /// Side of the FX rate the activity converts to its account currency at; mid when unset
+    #[serde(default)]
+    pub fx_rate_side: Option<FxRateSide>,
     #[serde(with = "timestamp_format")]
     pub created_at: DateTime<Utc>,
     #[serde(with = "timestamp_format")]
@@ -98,6 +103,8 @@ pub struct ActivityDB {
     pub comment: Option<String>,
     pub created_at: String,
     pub updated_at: String,
+    #[serde(default)]
+    pub fx_rate_side: Option<String>,
 }
 
 /// Input model for creating a new activity
@@ -116,6 +123,9 @@ pub struct NewActivity {
     pub amount: Option<Decimal>,
     pub is_draft: bool,
     pub comment: Option<String>,
+    /// See `Activity::fx_rate_side`
+    #[serde(default)]
+    pub fx_rate_side: Option<FxRateSide>,
 }
 
 impl NewActivity {
@@ -166,6 +176,9 @@ pub struct ActivityUpdate {
     pub amount: Option<Decimal>,
     pub is_draft: bool,
     pub comment: Option<String>,
+    /// See `Activity::fx_rate_side`
+    #[serde(default)]
+    pub fx_rate_side: Option<FxRateSide>,
 }
 
 impl ActivityUpdate {
@@ -241,6 +254,253 @@ pub struct ActivityBulkIdentifierMapping {
     pub activity_id: String,
 }
 
+/// Database model for an entry of the append-only activity change log
+#[derive(Queryable, Insertable, Selectable, Debug, Clone)]
+#[diesel(table_name = crate::schema::activity_changes)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct ActivityChangeDB {
+    pub id: String,
+    pub activity_id: String,
+    pub batch_id: Option<String>,
+    pub change_type: String,
+    pub before_state: Option<String>,
+    pub after_state: Option<String>,
+    pub actor: String,
+    pub changed_at: String,
+    pub reverted_change_id: Option<String>,
+    pub dependents_state: Option<String>,
+}
+
+impl ActivityChangeDB {
+    /// Builds a change log entry from the activity rows before and after a mutation
+    pub fn new(
+        change_type: &str,
+        before: Option<&ActivityDB>,
+        after: Option<&ActivityDB>,
+        batch_id: Option<String>,
+        actor: &str,
+    ) -> Result<Self> {
+        let activity_id = after.or(before).map(|a| a.id.clone()).ok_or_else(|| {
+            ActivityError::InvalidData(
+                "An activity change needs a before or after state".to_string(),
+            )
+        })?;
+
+        Ok(Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            activity_id,
+            batch_id,
+            change_type: change_type.to_string(),
+            before_state: before.map(serde_json::to_string).transpose()?,
+            after_state: after.map(serde_json::to_string).transpose()?,
+            actor: actor.to_string(),
+            changed_at: Utc::now().to_rfc3339(),
+            reverted_change_id: None,
+            dependents_state: None,
+        })
+    }
+
+    /// Attaches the rows a delete cascaded to; empty dependents are not stored
+    pub fn with_dependents(mut self, dependents: &ActivityDependents) -> Result<Self> {
+        if !dependents.is_empty() {
+            self.dependents_state = Some(serde_json::to_string(dependents)?);
+        }
+        Ok(self)
+    }
+
+    pub fn dependents(&self) -> Result<ActivityDependents> {
+        Ok(self
+            .dependents_state
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()?
+            .unwrap_or_default())
+    }
+
+    pub fn before_activity(&self) -> Result<Option<ActivityDB>> {
+        Ok(self
+            .before_state
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()?)
+    }
+
+    pub fn after_activity(&self) -> Result<Option<ActivityDB>> {
+        Ok(self
+            .after_state
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()?)
+    }
+}
+
+/// Rows that `ON DELETE CASCADE` removes together with an activity
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityDependents {
+    pub tag_ids: Vec<String>,
+    /// Their content stays on disk while the change is logged, see
+    /// `AttachmentRepository::remove_unreferenced_content`
+    pub attachments: Vec<ActivityAttachment>,
+    pub transfer_pair: Option<ActivityTransferPairDB>,
+}
+
+impl ActivityDependents {
+    pub fn is_empty(&self) -> bool {
+        self.tag_ids.is_empty() && self.attachments.is_empty() && self.transfer_pair.is_none()
+    }
+}
+
+/// Domain model for a recorded activity mutation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityChange {
+    pub id: String,
+    pub activity_id: String,
+    pub batch_id: Option<String>,
+    pub change_type: String,
+    pub before: Option<Activity>,
+    pub after: Option<Activity>,
+    pub actor: String,
+    #[serde(with = "timestamp_format")]
+    pub changed_at: DateTime<Utc>,
+    pub reverted_change_id: Option<String>,
+}
+
+impl From<ActivityChangeDB> for ActivityChange {
+    fn from(db: ActivityChangeDB) -> Self {
+        let before = db.before_activity().unwrap_or_else(|e| {
+            log::error!("Failed to parse before_state of change '{}': {}", db.id, e);
+            None
+        });
+        let after = db.after_activity().unwrap_or_else(|e| {
+            log::error!("Failed to parse after_state of change '{}': {}", db.id, e);
+            None
+        });
+
+        Self {
+            id: db.id,
+            activity_id: db.activity_id,
+            batch_id: db.batch_id,
+            change_type: db.change_type,
+            before: before.map(Activity::from),
+            after: after.map(Activity::from),
+            actor: db.actor,
+            changed_at: DateTime::parse_from_rfc3339(&db.changed_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|e| {
+                    log::error!("Failed to parse changed_at '{}': {}", db.changed_at, e);
+                    Utc::now()
+                }),
+            reverted_change_id: db.reverted_change_id,
+        }
+    }
+}
+
+/// Input model for moving cash or a holding between two accounts as one paired transfer.
+/// The fee is charged on the TRANSFER_OUT leg.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NewPairedTransfer {
+    pub from_account_id: String,
+    pub to_account_id: String,
+    pub asset_id: String,
+    pub activity_date: String,
+    pub quantity: Option<Decimal>,
+    pub unit_price: Option<Decimal>,
+    pub currency: String,
+    pub fee: Option<Decimal>,
+    pub amount: Option<Decimal>,
+    pub is_draft: bool,
+    pub comment: Option<String>,
+    /// See `Activity::fx_rate_side`
+    #[serde(default)]
+    pub fx_rate_side: Option<FxRateSide>,
+}
+
+impl NewPairedTransfer {
+    /// Validates the transfer data
+    pub fn validate(&self) -> std::result::Result<(), ActivityError> {
+        if self.from_account_id.trim().is_empty() || self.to_account_id.trim().is_empty() {
+            return Err(ActivityError::InvalidData(
+                "Both source and destination accounts are required".to_string(),
+            ));
+        }
+        if self.from_account_id == self.to_account_id {
+            return Err(ActivityError::InvalidData(
+                "Source and destination accounts must differ".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Splits the transfer into its TRANSFER_OUT and TRANSFER_IN legs
+    pub fn into_legs(self) -> (NewActivity, NewActivity) {
+        let transfer_out = NewActivity {
+            id: None,
+            account_id: self.from_account_id,
+            asset_id: self.asset_id.clone(),
+            activity_type: ActivityType::TransferOut.as_str().to_string(),
+            activity_date: self.activity_date.clone(),
+            quantity: self.quantity,
+            unit_price: self.unit_price,
+            currency: self.currency.clone(),
+            fee: self.fee,
+            amount: self.amount,
+            is_draft: self.is_draft,
+            comment: self.comment.clone(),
+            fx_rate_side: self.fx_rate_side,
+        };
+        let transfer_in = NewActivity {
+            id: None,
+            account_id: self.to_account_id,
+            asset_id: self.asset_id,
+            activity_type: ActivityType::TransferIn.as_str().to_string(),
+            activity_date: self.activity_date,
+            quantity: self.quantity,
+            unit_price: self.unit_price,
+            currency: self.currency,
+            fee: None,
+            amount: self.amount,
+            is_draft: self.is_draft,
+            comment: self.comment,
+            fx_rate_side: self.fx_rate_side,
+        };
+        (transfer_out, transfer_in)
+    }
+}
+
+/// Database model linking the two legs of a paired transfer
+#[derive(Queryable, Insertable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = crate::schema::activity_transfer_pairs)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct ActivityTransferPairDB {
+    pub id: String,
+    pub transfer_out_activity_id: String,
+    pub transfer_in_activity_id: String,
+    pub created_at: String,
+}
+
+/// Domain model for a paired transfer, with the accounts of both legs resolved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityTransferPair {
+    pub id: String,
+    pub transfer_out_activity_id: String,
+    pub transfer_in_activity_id: String,
+    pub from_account_id: String,
+    pub to_account_id: String,
+}
+
+/// Result of creating a paired transfer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairedTransfer {
+    pub id: String,
+    pub transfer_out: Activity,
+    pub transfer_in: Activity,
+}
+
 /// Model for activity details including related data
 #[derive(Queryable, QueryableByName, Serialize, Deserialize, Clone, Debug)]
 #[diesel(check_for_backend(diesel::sqlite::Sqlite))]
@@ -361,13 +621,107 @@ pub struct ActivityImport {
 }
 
 /// Model for sorting activities
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Sort {
     pub id: String,
     pub desc: bool,
 }
 
+/// Filters applied when searching activities. Every filter is optional and
+/// filters are combined with AND.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivitySearchFilters {
+    pub account_ids: Option<Vec<String>>,
+    pub activity_types: Option<Vec<String>>,
+    pub asset_id_keyword: Option<String>,
+    /// Inclusive start of the activity date range
+    pub date_from: Option<NaiveDate>,
+    /// Inclusive end of the activity date range
+    pub date_to: Option<NaiveDate>,
+    /// Minimum activity amount, in activity currency
+    pub min_amount: Option<Decimal>,
+    /// Maximum activity amount, in activity currency
+    pub max_amount: Option<Decimal>,
+    pub currency: Option<String>,
+    pub is_draft: Option<bool>,
+    /// Whitespace separated terms that must all appear in the comment
+    pub comment_keyword: Option<String>,
+    /// Matches activities carrying any of these tags, directly or through their account
+    pub tag_ids: Option<Vec<String>>,
+}
+
+/// Output format of an activity export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActivityExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+/// Flattened activity row written by exports
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityExportRow {
+    pub id: String,
+    pub date: String,
+    pub account_id: String,
+    pub account_name: String,
+    pub activity_type: String,
+    pub asset_symbol: String,
+    pub asset_name: Option<String>,
+    pub quantity: String,
+    pub unit_price: String,
+    pub amount: Option<String>,
+    pub fee: String,
+    pub currency: String,
+    pub is_draft: bool,
+    pub comment: Option<String>,
+}
+
+impl ActivityExportRow {
+    /// Column headers, in field order, used for CSV exports
+    pub const HEADERS: [&'static str; 14] = [
+        "id",
+        "date",
+        "accountId",
+        "accountName",
+        "activityType",
+        "assetSymbol",
+        "assetName",
+        "quantity",
+        "unitPrice",
+        "amount",
+        "fee",
+        "currency",
+        "isDraft",
+        "comment",
+    ];
+}
+
+impl From<ActivityDetails> for ActivityExportRow {
+    fn from(details: ActivityDetails) -> Self {
+        Self {
+            id: details.id,
+            date: details.date,
+            account_id: details.account_id,
+            account_name: details.account_name,
+            activity_type: details.activity_type,
+            asset_symbol: details.asset_symbol,
+            asset_name: details.asset_name,
+            quantity: details.quantity,
+            unit_price: details.unit_price,
+            amount: details.amount,
+            fee: details.fee,
+            currency: details.currency,
+            is_draft: details.is_draft,
+            comment: details.comment,
+        }
+    }
+}
+
 /// Model for activity import profile mapping
 #[derive(
     Debug, Clone, Serialize, Deserialize, Queryable, Identifiable, AsChangeset, Insertable,
@@ -592,6 +946,11 @@ impl From<ActivityDB> for Activity {
                 .map(|s| parse_decimal_string_tolerant(&s, "amount")),
             is_draft: db.is_draft,
             comment: db.comment,
+            fx_rate_side: db.fx_rate_side.and_then(|side| {
+                side.parse()
+                    .inspect_err(|e| log::error!("Failed to parse fx_rate_side: {}", e))
+                    .ok()
+            }),
             created_at: DateTime::parse_from_rfc3339(&db.created_at)
                 .map(|dt| dt.with_timezone(&Utc))
                 .unwrap_or_else(|e| {
@@ -681,6 +1040,7 @@ impl From<NewActivity> for ActivityDB {
             comment: domain.comment,
             created_at: now.to_rfc3339(),
             updated_at: now.to_rfc3339(),
+            fx_rate_side: domain.fx_rate_side.map(|side| side.as_str().to_string()),
         }
     }
 }
@@ -756,6 +1116,7 @@ impl From<ActivityUpdate> for ActivityDB {
             comment: domain.comment,
             created_at: now.to_rfc3339(), // This should ideally preserve original created_at. Need to fetch before update.
             updated_at: now.to_rfc3339(),
+            fx_rate_side: domain.fx_rate_side.map(|side| side.as_str().to_string()),
         }
     }
 }
@@ -763,8 +1124,14 @@ impl From<ActivityUpdate> for ActivityDB {
 #[serde(rename_all = "camelCase")]
 #[diesel(table_name = crate::schema::activities)]
 pub struct IncomeData {
+    /// Month of the payment (`YYYY-MM`)
     #[diesel(sql_type = diesel::sql_types::Text)]
     pub date: String,
+    /// Day of the payment (`YYYY-MM-DD`)
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub payment_date: String,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub account_id: String,
     #[diesel(sql_type = diesel::sql_types::Text)]
     pub income_type: String,
     #[diesel(sql_type = diesel::sql_types::Text)]
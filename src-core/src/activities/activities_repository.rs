@@ -4,6 +4,7 @@ use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
 use diesel::sqlite::SqliteConnection;
 use rust_decimal::Decimal;
+use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 use uuid::Uuid;
@@ -12,12 +13,17 @@ use super::activities_traits::ActivityRepositoryTrait;
 use crate::activities::activities_constants::*;
 use crate::activities::activities_errors::ActivityError;
 use crate::activities::activities_model::*;
+use crate::attachments::ActivityAttachment;
 use crate::db::{get_connection, WriteHandle};
-use crate::schema::{accounts, activities, activity_import_profiles, assets};
+use crate::schema::{
+    account_tags, accounts, activities, activity_attachments, activity_changes,
+    activity_import_profiles, activity_tags, activity_transfer_pairs, assets, tags,
+};
+use crate::tags::ActivityTagDB;
 use crate::{Error, Result};
 use async_trait::async_trait;
 use diesel::dsl::min;
-use num_traits::Zero;
+use num_traits::{ToPrimitive, Zero};
 
 /// Repository for managing activity data in the database
 pub struct ActivityRepository {
@@ -33,6 +39,118 @@ impl ActivityRepository {
     }
 }
 
+/// Appends an entry to the activity change log within the caller's transaction
+fn record_activity_change(
+    conn: &mut SqliteConnection,
+    change_type: &str,
+    before: Option<&ActivityDB>,
+    after: Option<&ActivityDB>,
+    batch_id: Option<String>,
+    actor: &str,
+) -> Result<()> {
+    let change = ActivityChangeDB::new(change_type, before, after, batch_id, actor)?;
+    diesel::insert_into(activity_changes::table)
+        .values(&change)
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Escapes LIKE wildcards so a search term matches literally; pair with `.escape('\\')`
+fn escape_like(term: &str) -> String {
+    term.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Deletes an activity and builds its DELETE log entry. The tags, attachments and
+/// transfer pair removed by the cascade are captured first so a revert can restore them.
+fn delete_activity_with_dependents(
+    conn: &mut SqliteConnection,
+    activity: &ActivityDB,
+    batch_id: Option<String>,
+    actor: &str,
+) -> Result<ActivityChangeDB> {
+    let dependents = ActivityDependents {
+        tag_ids: activity_tags::table
+            .filter(activity_tags::activity_id.eq(&activity.id))
+            .select(activity_tags::tag_id)
+            .load::<String>(conn)?,
+        attachments: activity_attachments::table
+            .filter(activity_attachments::activity_id.eq(&activity.id))
+            .select(ActivityAttachment::as_select())
+            .load::<ActivityAttachment>(conn)?,
+        transfer_pair: activity_transfer_pairs::table
+            .filter(
+                activity_transfer_pairs::transfer_out_activity_id
+                    .eq(&activity.id)
+                    .or(activity_transfer_pairs::transfer_in_activity_id.eq(&activity.id)),
+            )
+            .select(ActivityTransferPairDB::as_select())
+            .first::<ActivityTransferPairDB>(conn)
+            .optional()?,
+    };
+
+    diesel::delete(activities::table.find(&activity.id)).execute(conn)?;
+    ActivityChangeDB::new(
+        ACTIVITY_CHANGE_DELETE,
+        Some(activity),
+        None,
+        batch_id,
+        actor,
+    )?
+    .with_dependents(&dependents)
+}
+
+/// Re-links a restored activity to the tags it had when it was deleted.
+/// Tags removed since then are skipped.
+fn restore_activity_tags(
+    conn: &mut SqliteConnection,
+    activity_id: &str,
+    tag_ids: &[String],
+) -> Result<()> {
+    let existing_tag_ids = tags::table
+        .filter(tags::id.eq_any(tag_ids))
+        .select(tags::id)
+        .load::<String>(conn)?;
+    let links: Vec<ActivityTagDB> = existing_tag_ids
+        .into_iter()
+        .map(|tag_id| ActivityTagDB {
+            activity_id: activity_id.to_string(),
+            tag_id,
+        })
+        .collect();
+    diesel::insert_or_ignore_into(activity_tags::table)
+        .values(&links)
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Re-creates a transfer pair once both of its legs exist again and neither is paired
+fn restore_transfer_pair(conn: &mut SqliteConnection, pair: &ActivityTransferPairDB) -> Result<()> {
+    let legs = [
+        pair.transfer_out_activity_id.as_str(),
+        pair.transfer_in_activity_id.as_str(),
+    ];
+    let existing_legs: i64 = activities::table
+        .filter(activities::id.eq_any(legs))
+        .count()
+        .get_result(conn)?;
+    let paired_legs: i64 = activity_transfer_pairs::table
+        .filter(
+            activity_transfer_pairs::transfer_out_activity_id
+                .eq_any(legs)
+                .or(activity_transfer_pairs::transfer_in_activity_id.eq_any(legs)),
+        )
+        .count()
+        .get_result(conn)?;
+    if existing_legs == 2 && paired_legs == 0 {
+        diesel::insert_into(activity_transfer_pairs::table)
+            .values(pair)
+            .execute(conn)?;
+    }
+    Ok(())
+}
+
 // Implement the trait for the repository
 #[async_trait]
 impl ActivityRepositoryTrait for ActivityRepository {
@@ -89,17 +207,21 @@ impl ActivityRepositoryTrait for ActivityRepository {
 
     fn search_activities(
         &self,
-        page: i64,                                 // Page number, 1-based
-        page_size: i64,                            // Number of items per page
-        account_id_filter: Option<Vec<String>>,    // Optional account_id filter
-        activity_type_filter: Option<Vec<String>>, // Optional activity_type filter
-        asset_id_keyword: Option<String>,          // Optional asset_id keyword for search
-        sort: Option<Sort>,                        // Optional sort
+        page: i64,                      // Page number, 1-based
+        page_size: i64,                 // Number of items per page
+        filters: ActivitySearchFilters, // Optional filters, combined with AND
+        sort: Option<Sort>,             // Optional sort
     ) -> Result<ActivitySearchResponse> {
         let mut conn = get_connection(&self.pool)?;
 
         let offset = page * page_size;
 
+        // Amount of an activity: the explicit amount for cash activities, otherwise quantity * unit price
+        let amount_sql =
+            "CASE WHEN activities.amount IS NOT NULL AND CAST(activities.amount AS REAL) <> 0 \
+             THEN CAST(activities.amount AS REAL) \
+             ELSE CAST(activities.quantity AS REAL) * CAST(activities.unit_price AS REAL) END";
+
         // Function to create base query
         let create_base_query = |_conn: &SqliteConnection| {
             let mut query = activities::table
@@ -108,17 +230,69 @@ impl ActivityRepositoryTrait for ActivityRepository {
                 .filter(accounts::is_active.eq(true))
                 .into_boxed();
 
-            if let Some(ref account_ids) = account_id_filter {
+            if let Some(ref account_ids) = filters.account_ids {
                 query = query.filter(activities::account_id.eq_any(account_ids));
             }
-            if let Some(ref activity_types) = activity_type_filter {
+            if let Some(ref activity_types) = filters.activity_types {
                 query = query.filter(activities::activity_type.eq_any(activity_types));
             }
-            if let Some(ref keyword) = asset_id_keyword {
+            if let Some(ref keyword) = filters.asset_id_keyword {
                 query = query.filter(assets::id.like(format!("%{}%", keyword)));
             }
+            if let Some(date_from) = filters.date_from {
+                let start = Utc.from_utc_datetime(&date_from.and_hms_opt(0, 0, 0).unwrap());
+                query = query.filter(activities::activity_date.ge(start.to_rfc3339()));
+            }
+            if let Some(date_to) = filters.date_to {
+                // Exclusive upper bound at the start of the following day
+                let end = Utc.from_utc_datetime(
+                    &(date_to + chrono::Duration::days(1))
+                        .and_hms_opt(0, 0, 0)
+                        .unwrap(),
+                );
+                query = query.filter(activities::activity_date.lt(end.to_rfc3339()));
+            }
+            if let Some(min_amount) = filters.min_amount.and_then(|d| d.to_f64()) {
+                query = query.filter(
+                    diesel::dsl::sql::<diesel::sql_types::Double>(amount_sql).ge(min_amount),
+                );
+            }
+            if let Some(max_amount) = filters.max_amount.and_then(|d| d.to_f64()) {
+                query = query.filter(
+                    diesel::dsl::sql::<diesel::sql_types::Double>(amount_sql).le(max_amount),
+                );
+            }
+            if let Some(ref currency) = filters.currency {
+                query = query.filter(activities::currency.eq(currency));
+            }
+            if let Some(is_draft) = filters.is_draft {
+                query = query.filter(activities::is_draft.eq(is_draft));
+            }
+            if let Some(ref keyword) = filters.comment_keyword {
+                for term in keyword.split_whitespace() {
+                    query = query.filter(
+                        activities::comment
+                            .like(format!("%{}%", escape_like(term)))
+                            .escape('\\'),
+                    );
+                }
+            }
+            if let Some(ref tag_ids) = filters.tag_ids {
+                let tagged_activities = activity_tags::table
+                    .filter(activity_tags::tag_id.eq_any(tag_ids))
+                    .select(activity_tags::activity_id);
+                let tagged_accounts = account_tags::table
+                    .filter(account_tags::tag_id.eq_any(tag_ids))
+                    .select(account_tags::account_id);
+                query = query.filter(
+                    activities::id
+                        .eq_any(tagged_activities)
+                        .or(activities::account_id.eq_any(tagged_accounts)),
+                );
+            }
 
-            // Apply sorting
+            // Apply sorting. Every order ends on the id so pages never share or skip
+            // rows, which exports rely on when paging with LIMIT/OFFSET
             if let Some(ref sort) = sort {
                 match sort.id.as_str() {
                     "date" => {
@@ -126,39 +300,45 @@ impl ActivityRepositoryTrait for ActivityRepository {
                             query = query.order((
                                 activities::activity_date.desc(),
                                 activities::created_at.asc(),
+                                activities::id.asc(),
                             ));
                         } else {
                             query = query.order((
                                 activities::activity_date.asc(),
                                 activities::created_at.asc(),
+                                activities::id.asc(),
                             ));
                         }
                     }
                     "activityType" => {
                         if sort.desc {
-                            query = query.order(activities::activity_type.desc());
+                            query = query
+                                .order((activities::activity_type.desc(), activities::id.asc()));
                         } else {
-                            query = query.order(activities::activity_type.asc());
+                            query = query
+                                .order((activities::activity_type.asc(), activities::id.asc()));
                         }
                     }
                     "assetSymbol" => {
                         if sort.desc {
-                            query = query.order(activities::asset_id.desc());
+                            query =
+                                query.order((activities::asset_id.desc(), activities::id.asc()));
                         } else {
-                            query = query.order(activities::asset_id.asc());
+                            query = query.order((activities::asset_id.asc(), activities::id.asc()));
                         }
                     }
                     "accountName" => {
                         if sort.desc {
-                            query = query.order(accounts::name.desc());
+                            query = query.order((accounts::name.desc(), activities::id.asc()));
                         } else {
-                            query = query.order(accounts::name.asc());
+                            query = query.order((accounts::name.asc(), activities::id.asc()));
                         }
                     }
                     _ => {
                         query = query.order((
                             activities::activity_date.desc(),
                             activities::created_at.asc(),
+                            activities::id.asc(),
                         ))
                     } // Default order
                 }
@@ -166,6 +346,7 @@ impl ActivityRepositoryTrait for ActivityRepository {
                 query = query.order((
                     activities::activity_date.desc(),
                     activities::created_at.asc(),
+                    activities::id.asc(),
                 )); // Default order
             }
 
@@ -221,6 +402,14 @@ impl ActivityRepositoryTrait for ActivityRepository {
                 let inserted_activity = diesel::insert_into(activities::table)
                     .values(&activity_to_insert)
                     .get_result::<ActivityDB>(conn)?;
+                record_activity_change(
+                    conn,
+                    ACTIVITY_CHANGE_CREATE,
+                    None,
+                    Some(&inserted_activity),
+                    None,
+                    ACTIVITY_CHANGE_ACTOR_USER,
+                )?;
                 Ok(Activity::from(inserted_activity))
             })
             .await
@@ -239,13 +428,21 @@ impl ActivityRepositoryTrait for ActivityRepository {
                     .find(&activity_id_owned)
                     .first::<ActivityDB>(conn)?;
 
-                activity_to_update.created_at = existing.created_at;
+                activity_to_update.created_at = existing.created_at.clone();
                 activity_to_update.updated_at = chrono::Utc::now().to_rfc3339();
 
                 let updated_activity =
                     diesel::update(activities::table.find(&activity_to_update.id))
                         .set(&activity_to_update)
                         .get_result::<ActivityDB>(conn)?;
+                record_activity_change(
+                    conn,
+                    ACTIVITY_CHANGE_UPDATE,
+                    Some(&existing),
+                    Some(&updated_activity),
+                    None,
+                    ACTIVITY_CHANGE_ACTOR_USER,
+                )?;
                 Ok(Activity::from(updated_activity))
             })
             .await
@@ -258,7 +455,14 @@ impl ActivityRepositoryTrait for ActivityRepository {
                     .select(ActivityDB::as_select())
                     .find(&activity_id)
                     .first::<ActivityDB>(conn)?;
-                diesel::delete(activities::table.filter(activities::id.eq(&activity_id)))
+                let change = delete_activity_with_dependents(
+                    conn,
+                    &activity,
+                    None,
+                    ACTIVITY_CHANGE_ACTOR_USER,
+                )?;
+                diesel::insert_into(activity_changes::table)
+                    .values(&change)
                     .execute(conn)?;
                 Ok(activity.into())
             })
@@ -275,13 +479,22 @@ impl ActivityRepositoryTrait for ActivityRepository {
             .exec(
                 move |conn: &mut SqliteConnection| -> Result<ActivityBulkMutationResult> {
                     let mut outcome = ActivityBulkMutationResult::default();
+                    // Every change of a bulk mutation shares a batch id so it can be reverted as a whole
+                    let batch_id = Uuid::new_v4().to_string();
 
                     for delete_id in delete_ids {
                         let activity_db = activities::table
                             .select(ActivityDB::as_select())
                             .find(&delete_id)
                             .first::<ActivityDB>(conn)?;
-                        diesel::delete(activities::table.filter(activities::id.eq(&delete_id)))
+                        let change = delete_activity_with_dependents(
+                            conn,
+                            &activity_db,
+                            Some(batch_id.clone()),
+                            ACTIVITY_CHANGE_ACTOR_USER,
+                        )?;
+                        diesel::insert_into(activity_changes::table)
+                            .values(&change)
                             .execute(conn)?;
                         outcome.deleted.push(Activity::from(activity_db));
                     }
@@ -294,13 +507,21 @@ impl ActivityRepositoryTrait for ActivityRepository {
                             .find(&activity_db.id)
                             .first::<ActivityDB>(conn)?;
 
-                        activity_db.created_at = existing.created_at;
+                        activity_db.created_at = existing.created_at.clone();
                         activity_db.updated_at = chrono::Utc::now().to_rfc3339();
 
                         let updated_activity =
                             diesel::update(activities::table.find(&activity_db.id))
                                 .set(&activity_db)
                                 .get_result::<ActivityDB>(conn)?;
+                        record_activity_change(
+                            conn,
+                            ACTIVITY_CHANGE_UPDATE,
+                            Some(&existing),
+                            Some(&updated_activity),
+                            Some(batch_id.clone()),
+                            ACTIVITY_CHANGE_ACTOR_USER,
+                        )?;
                         outcome.updated.push(Activity::from(updated_activity));
                     }
 
@@ -317,6 +538,14 @@ impl ActivityRepositoryTrait for ActivityRepository {
                         let inserted_activity = diesel::insert_into(activities::table)
                             .values(&activity_db)
                             .get_result::<ActivityDB>(conn)?;
+                        record_activity_change(
+                            conn,
+                            ACTIVITY_CHANGE_CREATE,
+                            None,
+                            Some(&inserted_activity),
+                            Some(batch_id.clone()),
+                            ACTIVITY_CHANGE_ACTOR_USER,
+                        )?;
                         outcome
                             .created
                             .push(Activity::from(inserted_activity.clone()));
@@ -334,6 +563,234 @@ impl ActivityRepositoryTrait for ActivityRepository {
             .await
     }
 
+    async fn create_paired_transfer(
+        &self,
+        transfer_out: NewActivity,
+        transfer_in: NewActivity,
+    ) -> Result<PairedTransfer> {
+        transfer_out.validate()?;
+        transfer_in.validate()?;
+        let out_db: ActivityDB = transfer_out.into();
+        let in_db: ActivityDB = transfer_in.into();
+
+        self.writer
+            .exec(
+                move |conn: &mut SqliteConnection| -> Result<PairedTransfer> {
+                    // Both legs share a batch id so reverting the batch undoes the whole transfer
+                    let batch_id = Uuid::new_v4().to_string();
+                    let mut insert_leg = |mut activity_db: ActivityDB| -> Result<Activity> {
+                        activity_db.id = Uuid::new_v4().to_string();
+                        let inserted_activity = diesel::insert_into(activities::table)
+                            .values(&activity_db)
+                            .get_result::<ActivityDB>(conn)?;
+                        record_activity_change(
+                            conn,
+                            ACTIVITY_CHANGE_CREATE,
+                            None,
+                            Some(&inserted_activity),
+                            Some(batch_id.clone()),
+                            ACTIVITY_CHANGE_ACTOR_USER,
+                        )?;
+                        Ok(Activity::from(inserted_activity))
+                    };
+                    let transfer_out = insert_leg(out_db)?;
+                    let transfer_in = insert_leg(in_db)?;
+
+                    let pair = ActivityTransferPairDB {
+                        id: Uuid::new_v4().to_string(),
+                        transfer_out_activity_id: transfer_out.id.clone(),
+                        transfer_in_activity_id: transfer_in.id.clone(),
+                        created_at: Utc::now().to_rfc3339(),
+                    };
+                    diesel::insert_into(activity_transfer_pairs::table)
+                        .values(&pair)
+                        .execute(conn)?;
+
+                    Ok(PairedTransfer {
+                        id: pair.id,
+                        transfer_out,
+                        transfer_in,
+                    })
+                },
+            )
+            .await
+    }
+
+    fn get_transfer_pairs(&self) -> Result<Vec<ActivityTransferPair>> {
+        let mut conn = get_connection(&self.pool)?;
+
+        let pairs = activity_transfer_pairs::table
+            .select(ActivityTransferPairDB::as_select())
+            .load::<ActivityTransferPairDB>(&mut conn)?;
+        if pairs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let leg_ids: Vec<&String> = pairs
+            .iter()
+            .flat_map(|p| [&p.transfer_out_activity_id, &p.transfer_in_activity_id])
+            .collect();
+        let leg_accounts: HashMap<String, String> = activities::table
+            .filter(activities::id.eq_any(leg_ids))
+            .select((activities::id, activities::account_id))
+            .load::<(String, String)>(&mut conn)?
+            .into_iter()
+            .collect();
+
+        Ok(pairs
+            .into_iter()
+            .filter_map(|p| {
+                let from_account_id = leg_accounts.get(&p.transfer_out_activity_id)?.clone();
+                let to_account_id = leg_accounts.get(&p.transfer_in_activity_id)?.clone();
+                Some(ActivityTransferPair {
+                    id: p.id,
+                    transfer_out_activity_id: p.transfer_out_activity_id,
+                    transfer_in_activity_id: p.transfer_in_activity_id,
+                    from_account_id,
+                    to_account_id,
+                })
+            })
+            .collect())
+    }
+
+    fn get_activity_changes(&self, activity_id: &str) -> Result<Vec<ActivityChange>> {
+        let mut conn = get_connection(&self.pool)?;
+
+        let changes_db = activity_changes::table
+            .filter(activity_changes::activity_id.eq(activity_id))
+            .select(ActivityChangeDB::as_select())
+            .order(activity_changes::changed_at.desc())
+            .load::<ActivityChangeDB>(&mut conn)?;
+
+        Ok(changes_db.into_iter().map(ActivityChange::from).collect())
+    }
+
+    fn get_activity_changes_by_batch(&self, batch_id: &str) -> Result<Vec<ActivityChange>> {
+        let mut conn = get_connection(&self.pool)?;
+
+        let changes_db = activity_changes::table
+            .filter(activity_changes::batch_id.eq(batch_id))
+            .select(ActivityChangeDB::as_select())
+            .order(activity_changes::changed_at.desc())
+            .load::<ActivityChangeDB>(&mut conn)?;
+
+        Ok(changes_db.into_iter().map(ActivityChange::from).collect())
+    }
+
+    /// Restores the `before` state of each change in a single transaction.
+    /// Every restore is itself appended to the change log under a new batch id,
+    /// so a revert can be reverted again.
+    async fn revert_activity_changes(
+        &self,
+        change_ids: Vec<String>,
+    ) -> Result<ActivityBulkMutationResult> {
+        self.writer
+            .exec(
+                move |conn: &mut SqliteConnection| -> Result<ActivityBulkMutationResult> {
+                    let mut changes = activity_changes::table
+                        .filter(activity_changes::id.eq_any(&change_ids))
+                        .select(ActivityChangeDB::as_select())
+                        .load::<ActivityChangeDB>(conn)?;
+
+                    if changes.len() != change_ids.len() {
+                        return Err(ActivityError::NotFound(format!(
+                            "Activity change(s) not found: {}",
+                            change_ids.join(", ")
+                        ))
+                        .into());
+                    }
+
+                    // Undo newest changes first so chained edits unwind in order
+                    changes.sort_by(|a, b| b.changed_at.cmp(&a.changed_at));
+
+                    let mut outcome = ActivityBulkMutationResult::default();
+                    let batch_id = Uuid::new_v4().to_string();
+                    let now = chrono::Utc::now().to_rfc3339();
+                    let mut transfer_pairs = Vec::new();
+
+                    for change in changes {
+                        let current = activities::table
+                            .select(ActivityDB::as_select())
+                            .find(&change.activity_id)
+                            .first::<ActivityDB>(conn)
+                            .optional()?;
+
+                        if current != change.after_activity()? {
+                            return Err(ActivityError::InvalidData(format!(
+                                "Activity {} was modified after change {}. Revert the newer changes first.",
+                                change.activity_id, change.id
+                            ))
+                            .into());
+                        }
+
+                        let mut reverted = match (change.before_activity()?, current) {
+                            (None, Some(existing)) => {
+                                let entry = delete_activity_with_dependents(
+                                    conn,
+                                    &existing,
+                                    Some(batch_id.clone()),
+                                    ACTIVITY_CHANGE_ACTOR_REVERT,
+                                )?;
+                                outcome.deleted.push(Activity::from(existing));
+                                entry
+                            }
+                            (Some(mut restored), None) => {
+                                let dependents = change.dependents()?;
+                                restored.updated_at = now.clone();
+                                let inserted = diesel::insert_into(activities::table)
+                                    .values(&restored)
+                                    .get_result::<ActivityDB>(conn)?;
+                                restore_activity_tags(conn, &inserted.id, &dependents.tag_ids)?;
+                                diesel::insert_or_ignore_into(activity_attachments::table)
+                                    .values(&dependents.attachments)
+                                    .execute(conn)?;
+                                transfer_pairs.extend(dependents.transfer_pair);
+                                let entry = ActivityChangeDB::new(
+                                    ACTIVITY_CHANGE_CREATE,
+                                    None,
+                                    Some(&inserted),
+                                    Some(batch_id.clone()),
+                                    ACTIVITY_CHANGE_ACTOR_REVERT,
+                                )?;
+                                outcome.created.push(Activity::from(inserted));
+                                entry
+                            }
+                            (Some(mut restored), Some(existing)) => {
+                                restored.updated_at = now.clone();
+                                let updated = diesel::update(activities::table.find(&existing.id))
+                                    .set(&restored)
+                                    .get_result::<ActivityDB>(conn)?;
+                                let entry = ActivityChangeDB::new(
+                                    ACTIVITY_CHANGE_UPDATE,
+                                    Some(&existing),
+                                    Some(&updated),
+                                    Some(batch_id.clone()),
+                                    ACTIVITY_CHANGE_ACTOR_REVERT,
+                                )?;
+                                outcome.updated.push(Activity::from(updated));
+                                entry
+                            }
+                            (None, None) => continue,
+                        };
+
+                        // Link the new log entry back to the change it undid
+                        reverted.reverted_change_id = Some(change.id.clone());
+                        diesel::insert_into(activity_changes::table)
+                            .values(&reverted)
+                            .execute(conn)?;
+                    }
+
+                    // Pairs need both legs back, which may only happen later in the batch
+                    for pair in &transfer_pairs {
+                        restore_transfer_pair(conn, pair)?;
+                    }
+
+                    Ok(outcome)
+                },
+            )
+            .await
+    }
+
     /// Retrieves activities by account ID
     fn get_activities_by_account_id(&self, account_id: &String) -> Result<Vec<Activity>> {
         let mut conn = get_connection(&self.pool)?;
@@ -364,6 +821,21 @@ impl ActivityRepositoryTrait for ActivityRepository {
         Ok(activities_db.into_iter().map(Activity::from).collect())
     }
 
+    fn get_activities_by_tag(&self, tag_id: &str) -> Result<Vec<Activity>> {
+        let mut conn = get_connection(&self.pool)?;
+
+        let activities_db = activities::table
+            .inner_join(accounts::table.on(activities::account_id.eq(accounts::id)))
+            .inner_join(activity_tags::table.on(activity_tags::activity_id.eq(activities::id)))
+            .filter(accounts::is_active.eq(true))
+            .filter(activity_tags::tag_id.eq(tag_id))
+            .select(ActivityDB::as_select())
+            .order((activities::activity_date.asc(), activities::id.asc()))
+            .load::<ActivityDB>(&mut conn)?;
+
+        Ok(activities_db.into_iter().map(Activity::from).collect())
+    }
+
     /// Calculates the average cost for an asset in an account
     fn calculate_average_cost(&self, account_id: &str, asset_id: &str) -> Result<Decimal> {
         let mut conn = get_connection(&self.pool)?;
@@ -454,62 +926,63 @@ impl ActivityRepositoryTrait for ActivityRepository {
                 let num_inserted = diesel::insert_into(activities::table)
                     .values(&activities_db_owned)
                     .execute(conn)?;
+                let batch_id = Uuid::new_v4().to_string();
+                for inserted in &activities_db_owned {
+                    record_activity_change(
+                        conn,
+                        ACTIVITY_CHANGE_CREATE,
+                        None,
+                        Some(inserted),
+                        Some(batch_id.clone()),
+                        ACTIVITY_CHANGE_ACTOR_IMPORT,
+                    )?;
+                }
                 Ok(num_inserted)
             })
             .await
     }
 
-    /// Retrieves deposit activities for specified accounts within a year as raw data
+    /// Retrieves the deposit activities of active accounts within a period, oldest first
     fn get_deposit_activities(
         &self,
         account_ids: &[String],
         start_date: NaiveDateTime,
         end_date: NaiveDateTime,
-    ) -> Result<Vec<(String, Decimal, Decimal, String, Option<Decimal>)>> {
+    ) -> Result<Vec<Activity>> {
         let mut conn = get_connection(&self.pool)?;
 
-        // Use a proper join with explicit ON condition
-        let results = activities::table
+        let activities_db = activities::table
             .inner_join(accounts::table.on(activities::account_id.eq(accounts::id)))
             .filter(accounts::id.eq_any(account_ids))
             .filter(accounts::is_active.eq(true))
-            .filter(activities::activity_type.eq("DEPOSIT"))
+            .filter(activities::activity_type.eq(ACTIVITY_TYPE_DEPOSIT))
             .filter(activities::activity_date.between(
                 Utc.from_utc_datetime(&start_date).to_rfc3339(),
                 Utc.from_utc_datetime(&end_date).to_rfc3339(),
             ))
-            .select((
-                activities::account_id,
-                activities::quantity,
-                activities::unit_price,
-                activities::currency,
-                activities::amount,
-            ))
-            .load::<(String, String, String, String, Option<String>)>(&mut conn)
+            .select(ActivityDB::as_select())
+            .order(activities::activity_date.asc())
+            .load::<ActivityDB>(&mut conn)
             .map_err(ActivityError::from)?;
 
-        // Convert string values to Decimal
-        let converted_results = results
-            .into_iter()
-            .map(|(account_id, quantity, unit_price, currency, amount)| {
-                Ok((
-                    account_id,
-                    Decimal::from_str(&quantity)?,
-                    Decimal::from_str(&unit_price)?,
-                    currency,
-                    amount.map(|a| Decimal::from_str(&a)).transpose()?,
-                ))
-            })
-            .collect::<std::result::Result<Vec<_>, rust_decimal::Error>>()
-            .map_err(|e| ActivityError::DatabaseError(e.to_string()))?;
-
-        Ok(converted_results)
+        Ok(activities_db.into_iter().map(Activity::from).collect())
     }
 
-    fn get_income_activities_data(&self) -> Result<Vec<IncomeData>> {
+    fn get_income_activities_data(&self, tag_id: Option<&str>) -> Result<Vec<IncomeData>> {
         let mut conn = get_connection(&self.pool)?;
 
-        let query = "SELECT strftime('%Y-%m', a.activity_date) as date,
+        // A tag matches income recorded on a tagged activity or in a tagged account
+        let tag_clause = if tag_id.is_some() {
+            "AND (a.id IN (SELECT activity_id FROM activity_tags WHERE tag_id = ?1)
+                  OR a.account_id IN (SELECT account_id FROM account_tags WHERE tag_id = ?1))"
+        } else {
+            ""
+        };
+
+        let query = format!(
+            "SELECT strftime('%Y-%m', a.activity_date) as date,
+             strftime('%Y-%m-%d', a.activity_date) as payment_date,
+             a.account_id,
              a.activity_type as income_type,
              a.asset_id as symbol,
              COALESCE(ast.name, 'Unknown') as symbol_name,
@@ -520,7 +993,10 @@ impl ActivityRepositoryTrait for ActivityRepository {
              INNER JOIN accounts acc ON a.account_id = acc.id
              WHERE a.activity_type IN ('DIVIDEND', 'INTEREST', 'OTHER_INCOME')
              AND acc.is_active = 1
-             ORDER BY a.activity_date";
+             {}
+             ORDER BY a.activity_date",
+            tag_clause
+        );
 
         // Define a struct to hold the raw query results
         #[derive(QueryableByName, Debug)]
@@ -528,6 +1004,10 @@ impl ActivityRepositoryTrait for ActivityRepository {
             #[diesel(sql_type = diesel::sql_types::Text)]
             pub date: String,
             #[diesel(sql_type = diesel::sql_types::Text)]
+            pub payment_date: String,
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            pub account_id: String,
+            #[diesel(sql_type = diesel::sql_types::Text)]
             pub income_type: String,
             #[diesel(sql_type = diesel::sql_types::Text)]
             pub symbol: String,
@@ -539,9 +1019,13 @@ impl ActivityRepositoryTrait for ActivityRepository {
             pub amount: String,
         }
 
-        let raw_results = diesel::sql_query(query)
-            .load::<RawIncomeData>(&mut conn)
-            .map_err(ActivityError::from)?;
+        let raw_results = match tag_id {
+            Some(tag_id) => diesel::sql_query(query)
+                .bind::<diesel::sql_types::Text, _>(tag_id)
+                .load::<RawIncomeData>(&mut conn),
+            None => diesel::sql_query(query).load::<RawIncomeData>(&mut conn),
+        }
+        .map_err(ActivityError::from)?;
 
         // Transform raw results into IncomeData
         let results = raw_results
@@ -550,6 +1034,8 @@ impl ActivityRepositoryTrait for ActivityRepository {
                 let amount = Decimal::from_str(&raw.amount).unwrap_or_else(|_| Decimal::zero());
                 Ok(IncomeData {
                     date: raw.date,
+                    payment_date: raw.payment_date,
+                    account_id: raw.account_id,
                     income_type: raw.income_type,
                     symbol: raw.symbol,
                     symbol_name: raw.symbol_name,
@@ -0,0 +1,592 @@
+use rust_decimal_macros::dec;
+
+use super::activities_constants::*;
+use super::activities_model::{
+    Activity, ActivityExportFormat, ActivitySearchFilters, ActivityUpdate, NewActivity, Sort,
+};
+use super::activities_repository::ActivityRepository;
+use super::activities_service::{write_activity_export, EXPORT_PAGE_SIZE};
+use super::activities_traits::ActivityRepositoryTrait;
+use crate::attachments::{AttachmentRepository, AttachmentRepositoryTrait};
+use crate::fx::FxRateSide;
+use crate::test_utils::{date, TestDb};
+
+const CASH_USD: &str = "$CASH-USD";
+
+fn setup() -> (TestDb, ActivityRepository) {
+    let db = TestDb::new();
+    db.insert_account("acc-1", "USD");
+    db.insert_account("acc-2", "USD");
+    db.insert_asset(CASH_USD, "USD");
+    let repository = ActivityRepository::new(db.pool.clone(), db.writer.clone());
+    (db, repository)
+}
+
+fn deposit(account_id: &str, amount: rust_decimal::Decimal) -> NewActivity {
+    NewActivity {
+        id: None,
+        account_id: account_id.to_string(),
+        asset_id: CASH_USD.to_string(),
+        activity_type: ACTIVITY_TYPE_DEPOSIT.to_string(),
+        activity_date: "2024-01-15".to_string(),
+        quantity: None,
+        unit_price: None,
+        currency: "USD".to_string(),
+        fee: None,
+        amount: Some(amount),
+        is_draft: false,
+        comment: None,
+        fx_rate_side: None,
+    }
+}
+
+fn update_of(activity: &Activity, amount: rust_decimal::Decimal) -> ActivityUpdate {
+    ActivityUpdate {
+        id: activity.id.clone(),
+        account_id: activity.account_id.clone(),
+        asset_id: activity.asset_id.clone(),
+        activity_type: activity.activity_type.clone(),
+        activity_date: "2024-01-15".to_string(),
+        quantity: Some(activity.quantity),
+        unit_price: Some(activity.unit_price),
+        currency: activity.currency.clone(),
+        fee: Some(activity.fee),
+        amount: Some(amount),
+        is_draft: activity.is_draft,
+        comment: activity.comment.clone(),
+        fx_rate_side: None,
+    }
+}
+
+/// Id of the newest change recorded for an activity
+fn latest_change_id(repository: &ActivityRepository, activity_id: &str) -> String {
+    repository.get_activity_changes(activity_id).unwrap()[0]
+        .id
+        .clone()
+}
+
+#[tokio::test]
+async fn test_revert_single_update_restores_previous_state() {
+    let (_db, repository) = setup();
+    let created = repository
+        .create_activity(deposit("acc-1", dec!(100)))
+        .await
+        .unwrap();
+    repository
+        .update_activity(update_of(&created, dec!(250)))
+        .await
+        .unwrap();
+
+    let change_id = latest_change_id(&repository, &created.id);
+    let result = repository
+        .revert_activity_changes(vec![change_id.clone()])
+        .await
+        .unwrap();
+
+    assert_eq!(result.updated.len(), 1);
+    let restored = repository.get_activity(&created.id).unwrap();
+    assert_eq!(restored.amount, Some(dec!(100)));
+
+    let history = repository.get_activity_changes(&created.id).unwrap();
+    assert_eq!(history.len(), 3);
+    assert_eq!(history[0].actor, ACTIVITY_CHANGE_ACTOR_REVERT);
+    assert_eq!(
+        history[0].reverted_change_id.as_deref(),
+        Some(change_id.as_str())
+    );
+}
+
+#[tokio::test]
+async fn test_revert_batch_undoes_whole_bulk_mutation() {
+    let (_db, repository) = setup();
+    let kept = repository
+        .create_activity(deposit("acc-1", dec!(100)))
+        .await
+        .unwrap();
+    let bulk = repository
+        .bulk_mutate_activities(
+            vec![deposit("acc-2", dec!(50))],
+            vec![update_of(&kept, dec!(300))],
+            vec![],
+        )
+        .await
+        .unwrap();
+    let created_id = bulk.created[0].id.clone();
+
+    let batch_id = repository.get_activity_changes(&created_id).unwrap()[0]
+        .batch_id
+        .clone()
+        .unwrap();
+    let change_ids = repository
+        .get_activity_changes_by_batch(&batch_id)
+        .unwrap()
+        .into_iter()
+        .map(|c| c.id)
+        .collect();
+    let result = repository
+        .revert_activity_changes(change_ids)
+        .await
+        .unwrap();
+
+    assert_eq!(result.deleted.len(), 1);
+    assert_eq!(result.updated.len(), 1);
+    assert!(repository.get_activity(&created_id).is_err());
+    assert_eq!(
+        repository.get_activity(&kept.id).unwrap().amount,
+        Some(dec!(100))
+    );
+}
+
+#[tokio::test]
+async fn test_revert_conflicts_with_later_edit() {
+    let (_db, repository) = setup();
+    let created = repository
+        .create_activity(deposit("acc-1", dec!(100)))
+        .await
+        .unwrap();
+    repository
+        .update_activity(update_of(&created, dec!(200)))
+        .await
+        .unwrap();
+    let first_edit = latest_change_id(&repository, &created.id);
+    repository
+        .update_activity(update_of(&created, dec!(300)))
+        .await
+        .unwrap();
+
+    let err = repository
+        .revert_activity_changes(vec![first_edit])
+        .await
+        .unwrap_err();
+
+    assert!(err.to_string().contains("Revert the newer changes first"));
+    assert_eq!(
+        repository.get_activity(&created.id).unwrap().amount,
+        Some(dec!(300))
+    );
+}
+
+#[tokio::test]
+async fn test_revert_of_revert_reapplies_change() {
+    let (_db, repository) = setup();
+    let created = repository
+        .create_activity(deposit("acc-1", dec!(100)))
+        .await
+        .unwrap();
+    repository
+        .update_activity(update_of(&created, dec!(200)))
+        .await
+        .unwrap();
+
+    let edit = latest_change_id(&repository, &created.id);
+    repository
+        .revert_activity_changes(vec![edit])
+        .await
+        .unwrap();
+    let revert = latest_change_id(&repository, &created.id);
+    repository
+        .revert_activity_changes(vec![revert])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        repository.get_activity(&created.id).unwrap().amount,
+        Some(dec!(200))
+    );
+}
+
+#[tokio::test]
+async fn test_revert_delete_restores_tags_and_transfer_pair() {
+    let (db, repository) = setup();
+    db.insert_tag("tag-1");
+    let transfer = repository
+        .create_paired_transfer(
+            NewActivity {
+                activity_type: ACTIVITY_TYPE_TRANSFER_OUT.to_string(),
+                ..deposit("acc-1", dec!(100))
+            },
+            NewActivity {
+                activity_type: ACTIVITY_TYPE_TRANSFER_IN.to_string(),
+                ..deposit("acc-2", dec!(100))
+            },
+        )
+        .await
+        .unwrap();
+    let out_id = transfer.transfer_out.id.clone();
+    db.execute(&format!(
+        "INSERT INTO activity_tags (activity_id, tag_id) VALUES ('{out_id}', 'tag-1');"
+    ));
+
+    repository.delete_activity(out_id.clone()).await.unwrap();
+    assert!(repository.get_transfer_pairs().unwrap().is_empty());
+
+    let delete = latest_change_id(&repository, &out_id);
+    repository
+        .revert_activity_changes(vec![delete])
+        .await
+        .unwrap();
+
+    let pairs = repository.get_transfer_pairs().unwrap();
+    assert_eq!(pairs.len(), 1);
+    assert_eq!(pairs[0].id, transfer.id);
+    let tagged = repository
+        .search_activities(
+            0,
+            10,
+            ActivitySearchFilters {
+                tag_ids: Some(vec!["tag-1".to_string()]),
+                ..Default::default()
+            },
+            None,
+        )
+        .unwrap();
+    assert_eq!(tagged.data.len(), 1);
+    assert_eq!(tagged.data[0].id, out_id);
+}
+
+#[tokio::test]
+async fn test_revert_delete_restores_attachments() {
+    let (db, repository) = setup();
+    let created = repository
+        .create_activity(deposit("acc-1", dec!(100)))
+        .await
+        .unwrap();
+    db.execute(&format!(
+        "INSERT INTO activity_attachments (id, activity_id, content_hash, file_name, size_bytes, created_at)
+         VALUES ('att-1', '{}', 'hash', 'receipt.pdf', 3, '2024-01-15T00:00:00Z');",
+        created.id
+    ));
+
+    repository
+        .delete_activity(created.id.clone())
+        .await
+        .unwrap();
+    let delete = latest_change_id(&repository, &created.id);
+    repository
+        .revert_activity_changes(vec![delete])
+        .await
+        .unwrap();
+
+    let restored = AttachmentRepository::new(db.pool.clone(), db.writer.clone())
+        .get_attachments_by_activity(&created.id)
+        .unwrap();
+    assert_eq!(restored.len(), 1);
+    assert_eq!(restored[0].id, "att-1");
+    assert_eq!(restored[0].content_hash, "hash");
+}
+
+#[tokio::test]
+async fn test_fx_rate_side_is_stored_and_defaults_to_unset() {
+    let (_db, repository) = setup();
+
+    let unset = repository
+        .create_activity(deposit("acc-1", dec!(100)))
+        .await
+        .unwrap();
+    let at_ask = repository
+        .create_activity(NewActivity {
+            fx_rate_side: Some(FxRateSide::Ask),
+            ..deposit("acc-1", dec!(100))
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(
+        repository.get_activity(&unset.id).unwrap().fx_rate_side,
+        None
+    );
+    assert_eq!(
+        repository.get_activity(&at_ask.id).unwrap().fx_rate_side,
+        Some(FxRateSide::Ask)
+    );
+}
+
+/// Seeds deposits of 100 on Jan 10, 250 on Feb 10 and 400 on Mar 10 with distinct comments
+async fn seed_search_activities(repository: &ActivityRepository) -> Vec<Activity> {
+    let seeds = [
+        ("acc-1", dec!(100), "2024-01-10", "salary january"),
+        ("acc-1", dec!(250), "2024-02-10", "bonus 100% paid"),
+        ("acc-2", dec!(400), "2024-03-10", "salary march"),
+    ];
+    let mut created = Vec::new();
+    for (account_id, amount, date, comment) in seeds {
+        created.push(
+            repository
+                .create_activity(NewActivity {
+                    activity_date: date.to_string(),
+                    comment: Some(comment.to_string()),
+                    ..deposit(account_id, amount)
+                })
+                .await
+                .unwrap(),
+        );
+    }
+    created
+}
+
+fn search_ids(repository: &ActivityRepository, filters: ActivitySearchFilters) -> Vec<String> {
+    let mut ids: Vec<String> = repository
+        .search_activities(0, 10, filters, None)
+        .unwrap()
+        .data
+        .into_iter()
+        .map(|a| a.id)
+        .collect();
+    ids.sort();
+    ids
+}
+
+fn sorted_ids(activities: &[&Activity]) -> Vec<String> {
+    let mut ids: Vec<String> = activities.iter().map(|a| a.id.clone()).collect();
+    ids.sort();
+    ids
+}
+
+#[tokio::test]
+async fn test_search_pages_break_sort_ties_by_id() {
+    let (_db, repository) = setup();
+    let seeded = seed_search_activities(&repository).await;
+
+    // All seeded activities are deposits, so the sort key ties on every row
+    let mut paged = Vec::new();
+    for page in 0..3 {
+        let response = repository
+            .search_activities(
+                page,
+                1,
+                ActivitySearchFilters::default(),
+                Some(Sort {
+                    id: "activityType".to_string(),
+                    desc: true,
+                }),
+            )
+            .unwrap();
+        paged.extend(response.data.into_iter().map(|a| a.id));
+    }
+
+    assert_eq!(paged, sorted_ids(&seeded.iter().collect::<Vec<_>>()));
+}
+
+#[tokio::test]
+async fn test_search_filters_by_amount_range() {
+    let (_db, repository) = setup();
+    let seeded = seed_search_activities(&repository).await;
+
+    let ids = search_ids(
+        &repository,
+        ActivitySearchFilters {
+            min_amount: Some(dec!(200)),
+            max_amount: Some(dec!(400)),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(ids, sorted_ids(&[&seeded[1], &seeded[2]]));
+}
+
+#[tokio::test]
+async fn test_search_filters_by_inclusive_date_range() {
+    let (_db, repository) = setup();
+    let seeded = seed_search_activities(&repository).await;
+
+    let ids = search_ids(
+        &repository,
+        ActivitySearchFilters {
+            date_from: chrono::NaiveDate::from_ymd_opt(2024, 1, 10),
+            date_to: chrono::NaiveDate::from_ymd_opt(2024, 2, 10),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(ids, sorted_ids(&[&seeded[0], &seeded[1]]));
+}
+
+#[tokio::test]
+async fn test_search_filters_by_comment_terms_literally() {
+    let (_db, repository) = setup();
+    let seeded = seed_search_activities(&repository).await;
+
+    let all_terms = search_ids(
+        &repository,
+        ActivitySearchFilters {
+            comment_keyword: Some("salary march".to_string()),
+            ..Default::default()
+        },
+    );
+    assert_eq!(all_terms, sorted_ids(&[&seeded[2]]));
+
+    // `%` and `_` are not wildcards
+    let percent = search_ids(
+        &repository,
+        ActivitySearchFilters {
+            comment_keyword: Some("0%".to_string()),
+            ..Default::default()
+        },
+    );
+    assert_eq!(percent, sorted_ids(&[&seeded[1]]));
+    for wildcard in ["y%m", "salary_"] {
+        let ids = search_ids(
+            &repository,
+            ActivitySearchFilters {
+                comment_keyword: Some(wildcard.to_string()),
+                ..Default::default()
+            },
+        );
+        assert!(ids.is_empty(), "{} matched {:?}", wildcard, ids);
+    }
+}
+
+#[tokio::test]
+async fn test_search_filters_by_activity_and_account_tags() {
+    let (db, repository) = setup();
+    let seeded = seed_search_activities(&repository).await;
+    db.insert_tag("tag-1");
+    db.execute(&format!(
+        "INSERT INTO activity_tags (activity_id, tag_id) VALUES ('{}', 'tag-1');
+         INSERT INTO account_tags (account_id, tag_id) VALUES ('acc-2', 'tag-1');",
+        seeded[0].id
+    ));
+
+    let ids = search_ids(
+        &repository,
+        ActivitySearchFilters {
+            tag_ids: Some(vec!["tag-1".to_string()]),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(ids, sorted_ids(&[&seeded[0], &seeded[2]]));
+}
+
+#[tokio::test]
+async fn test_activities_by_tag_skips_account_tags() {
+    let (db, repository) = setup();
+    let seeded = seed_search_activities(&repository).await;
+    db.insert_tag("tag-1");
+    db.execute(&format!(
+        "INSERT INTO activity_tags (activity_id, tag_id) VALUES ('{}', 'tag-1'), ('{}', 'tag-1');
+         INSERT INTO account_tags (account_id, tag_id) VALUES ('acc-2', 'tag-1');",
+        seeded[1].id, seeded[0].id
+    ));
+
+    let tagged = repository.get_activities_by_tag("tag-1").unwrap();
+
+    let ids: Vec<&str> = tagged.iter().map(|a| a.id.as_str()).collect();
+    assert_eq!(ids, vec![seeded[0].id.as_str(), seeded[1].id.as_str()]);
+}
+
+#[tokio::test]
+async fn test_income_data_filters_by_activity_and_account_tags() {
+    let (db, repository) = setup();
+    db.insert_tag("tag-1");
+    let mut dividends = Vec::new();
+    for account_id in ["acc-1", "acc-1", "acc-2"] {
+        dividends.push(
+            repository
+                .create_activity(NewActivity {
+                    activity_type: ACTIVITY_TYPE_DIVIDEND.to_string(),
+                    ..deposit(account_id, dec!(10))
+                })
+                .await
+                .unwrap(),
+        );
+    }
+    db.execute(&format!(
+        "INSERT INTO activity_tags (activity_id, tag_id) VALUES ('{}', 'tag-1');
+         INSERT INTO account_tags (account_id, tag_id) VALUES ('acc-2', 'tag-1');",
+        dividends[0].id
+    ));
+
+    let all = repository.get_income_activities_data(None).unwrap();
+    let tagged = repository
+        .get_income_activities_data(Some("tag-1"))
+        .unwrap();
+
+    assert_eq!(all.len(), 3);
+    let mut tagged_accounts: Vec<&str> = tagged.iter().map(|i| i.account_id.as_str()).collect();
+    tagged_accounts.sort();
+    assert_eq!(tagged_accounts, vec!["acc-1", "acc-2"]);
+}
+
+#[tokio::test]
+async fn test_deposit_activities_filters_by_type_and_period() {
+    let (_db, repository) = setup();
+    let seeded = seed_search_activities(&repository).await;
+    repository
+        .create_activity(NewActivity {
+            activity_type: ACTIVITY_TYPE_DIVIDEND.to_string(),
+            activity_date: "2024-02-12".to_string(),
+            ..deposit("acc-1", dec!(10))
+        })
+        .await
+        .unwrap();
+
+    let deposits = repository
+        .get_deposit_activities(
+            &["acc-1".to_string(), "acc-2".to_string()],
+            date(2024, 2, 1).and_hms_opt(0, 0, 0).unwrap(),
+            date(2024, 12, 31).and_hms_opt(23, 59, 59).unwrap(),
+        )
+        .unwrap();
+
+    let ids: Vec<&str> = deposits.iter().map(|a| a.id.as_str()).collect();
+    assert_eq!(ids, vec![seeded[1].id.as_str(), seeded[2].id.as_str()]);
+}
+
+fn export(repository: &ActivityRepository, format: ActivityExportFormat) -> (usize, String) {
+    let mut buffer = Vec::new();
+    let exported = write_activity_export(format, &mut buffer, |page| {
+        repository
+            .search_activities(
+                page,
+                EXPORT_PAGE_SIZE,
+                ActivitySearchFilters::default(),
+                None,
+            )
+            .map(|response| response.data)
+    })
+    .unwrap();
+    (exported, String::from_utf8(buffer).unwrap())
+}
+
+#[tokio::test]
+async fn test_export_csv_writes_header_and_one_row_per_activity() {
+    let (_db, repository) = setup();
+    seed_search_activities(&repository).await;
+
+    let (exported, csv) = export(&repository, ActivityExportFormat::Csv);
+
+    assert_eq!(exported, 3);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(
+        lines[0],
+        "id,date,accountId,accountName,activityType,assetSymbol,assetName,quantity,unitPrice,amount,fee,currency,isDraft,comment"
+    );
+    assert!(csv.contains("bonus 100% paid"));
+}
+
+#[tokio::test]
+async fn test_export_json_writes_array_of_rows() {
+    let (_db, repository) = setup();
+    seed_search_activities(&repository).await;
+
+    let (exported, json) = export(&repository, ActivityExportFormat::Json);
+
+    assert_eq!(exported, 3);
+    let rows: Vec<serde_json::Value> = serde_json::from_str(&json).unwrap();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0]["activityType"], ACTIVITY_TYPE_DEPOSIT);
+    assert!(rows[0]["accountName"].is_string());
+}
+
+#[test]
+fn test_export_stops_at_failing_page() {
+    let mut buffer = Vec::new();
+    let result = write_activity_export(ActivityExportFormat::Json, &mut buffer, |_| {
+        Err(crate::Error::Unexpected("page failed".to_string()))
+    });
+
+    assert!(result.is_err());
+    assert_eq!(buffer, b"[");
+}
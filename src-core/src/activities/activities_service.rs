@@ -1,5 +1,6 @@
 use chrono::Utc;
-use log::debug;
+use log::{debug, warn};
+use std::io::Write;
 use std::sync::Arc;
 
 use crate::accounts::{Account, AccountServiceTrait};
@@ -10,10 +11,18 @@ use crate::market_data::MarketDataServiceTrait;
 use crate::market_data::market_data_model::{Quote, DataSource};
 use crate::Result;
 use crate::assets::AssetServiceTrait;
-use crate::fx::FxServiceTrait;
+use crate::attachments::AttachmentServiceTrait;
+use crate::fx::{FxRateSide, FxServiceTrait, TransferFxCost, TransferFxRates};
+use crate::constants::{CASH_ASSET_PREFIX, PORTFOLIO_TOTAL_ACCOUNT_ID};
+use crate::portfolio::snapshot::SnapshotServiceTrait;
+use crate::portfolio::valuation::ValuationServiceTrait;
+use std::collections::HashMap;
 use uuid::Uuid;
 use chrono::DateTime;
 
+/// Number of activities fetched per query while exporting
+pub(crate) const EXPORT_PAGE_SIZE: i64 = 500;
+
 /// Service for managing activities
 pub struct ActivityService {
     activity_repository: Arc<dyn ActivityRepositoryTrait>,
@@ -21,16 +30,23 @@ pub struct ActivityService {
     asset_service: Arc<dyn AssetServiceTrait>,
     fx_service: Arc<dyn FxServiceTrait>,
     market_data_service: Arc<dyn MarketDataServiceTrait>,
+    snapshot_service: Arc<dyn SnapshotServiceTrait>,
+    valuation_service: Arc<dyn ValuationServiceTrait>,
+    attachment_service: Arc<dyn AttachmentServiceTrait>,
 }
 
 impl ActivityService {
     /// Creates a new ActivityService instance with injected dependencies
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         activity_repository: Arc<dyn ActivityRepositoryTrait>,
         account_service: Arc<dyn AccountServiceTrait>,
         asset_service: Arc<dyn AssetServiceTrait>,
         fx_service: Arc<dyn FxServiceTrait>,
         market_data_service: Arc<dyn MarketDataServiceTrait>,
+        snapshot_service: Arc<dyn SnapshotServiceTrait>,
+        valuation_service: Arc<dyn ValuationServiceTrait>,
+        attachment_service: Arc<dyn AttachmentServiceTrait>,
     ) -> Self {
         Self {
             activity_repository,
@@ -38,6 +54,64 @@ impl ActivityService {
             asset_service,
             fx_service,
             market_data_service,
+            snapshot_service,
+            valuation_service,
+            attachment_service,
+        }
+    }
+
+    /// Deleting an activity cascades to its attachment rows; drops the files
+    /// nothing references anymore.
+    async fn remove_orphaned_attachments(&self) {
+        if let Err(e) = self.attachment_service.remove_unreferenced_content().await {
+            warn!("Failed to remove unreferenced attachment files: {}", e);
+        }
+    }
+
+    /// Drops attachment files orphaned by a revert, then rebuilds snapshots and
+    /// valuations of the accounts it touched, plus TOTAL. A revert can move history
+    /// anywhere in time, so the rebuild is always full.
+    async fn finish_revert(&self, result: &ActivityBulkMutationResult) {
+        if !result.deleted.is_empty() {
+            self.remove_orphaned_attachments().await;
+        }
+
+        let mut account_ids: Vec<String> = result
+            .created
+            .iter()
+            .chain(result.updated.iter())
+            .chain(result.deleted.iter())
+            .map(|a| a.account_id.clone())
+            .collect();
+        account_ids.sort();
+        account_ids.dedup();
+        if account_ids.is_empty() {
+            return;
+        }
+
+        if let Err(e) = self
+            .snapshot_service
+            .force_recalculate_holdings_snapshots(Some(&account_ids))
+            .await
+        {
+            warn!("Failed to recalculate holdings snapshots after revert: {}", e);
+        }
+        if let Err(e) = self.snapshot_service.calculate_total_portfolio_snapshots().await {
+            warn!("Failed to recalculate total portfolio snapshots after revert: {}", e);
+        }
+
+        account_ids.push(PORTFOLIO_TOTAL_ACCOUNT_ID.to_string());
+        for account_id in account_ids {
+            if let Err(e) = self
+                .valuation_service
+                .calculate_valuation_history(&account_id, true)
+                .await
+            {
+                warn!(
+                    "Failed to recalculate valuation history for {} after revert: {}",
+                    account_id, e
+                );
+            }
         }
     }
 
@@ -137,19 +211,11 @@ impl ActivityServiceTrait for ActivityService {
         &self,
         page: i64,
         page_size: i64,
-        account_id_filter: Option<Vec<String>>,
-        activity_type_filter: Option<Vec<String>>,
-        asset_id_keyword: Option<String>,
+        filters: ActivitySearchFilters,
         sort: Option<Sort>,
     ) -> Result<ActivitySearchResponse> {
-        self.activity_repository.search_activities(
-            page,
-            page_size,
-            account_id_filter,
-            activity_type_filter,
-            asset_id_keyword,
-            sort,
-        )
+        self.activity_repository
+            .search_activities(page, page_size, filters, sort)
     }
 
     /// Creates a new activity
@@ -166,7 +232,9 @@ impl ActivityServiceTrait for ActivityService {
 
     /// Deletes an activity
     async fn delete_activity(&self, activity_id: String) -> Result<Activity> {
-        self.activity_repository.delete_activity(activity_id).await
+        let deleted = self.activity_repository.delete_activity(activity_id).await?;
+        self.remove_orphaned_attachments().await;
+        Ok(deleted)
     }
 
     async fn bulk_mutate_activities(
@@ -229,11 +297,133 @@ impl ActivityServiceTrait for ActivityService {
             .activity_repository
             .bulk_mutate_activities(prepared_creates, prepared_updates, valid_delete_ids)
             .await?;
+        if !persisted.deleted.is_empty() {
+            self.remove_orphaned_attachments().await;
+        }
 
         persisted.errors = errors;
         Ok(persisted)
     }
 
+    /// Creates both legs of an internal transfer and links them
+    async fn create_paired_transfer(&self, transfer: NewPairedTransfer) -> Result<PairedTransfer> {
+        transfer.validate()?;
+        let (transfer_out, mut transfer_in) = transfer.into_legs();
+
+        let transfer_out = self.prepare_new_activity(transfer_out).await?;
+        // The incoming lots keep the currency resolved for the source leg
+        transfer_in.currency = transfer_out.currency.clone();
+        let transfer_in = self.prepare_new_activity(transfer_in).await?;
+
+        self.activity_repository
+            .create_paired_transfer(transfer_out, transfer_in)
+            .await
+    }
+
+    /// Compares each cross-currency cash transfer with its value at the mid rate
+    fn get_transfer_fx_costs(&self, base_currency: &str) -> Result<Vec<TransferFxCost>> {
+        let accounts: HashMap<String, Account> = self
+            .account_service
+            .get_all_accounts()?
+            .into_iter()
+            .map(|a| (a.id.clone(), a))
+            .collect();
+
+        let mut costs = Vec::new();
+        for pair in self.activity_repository.get_transfer_pairs()? {
+            let (Some(from), Some(to)) = (
+                accounts.get(&pair.from_account_id),
+                accounts.get(&pair.to_account_id),
+            ) else {
+                continue;
+            };
+            let transfer = self
+                .activity_repository
+                .get_activity(&pair.transfer_out_activity_id)?;
+            if !transfer.asset_id.starts_with(CASH_ASSET_PREFIX)
+                || (transfer.currency == from.currency && transfer.currency == to.currency)
+            {
+                continue;
+            }
+
+            let date = transfer.activity_date.naive_utc().date();
+            let rate = |from_ccy: &str, to_ccy: &str, side: FxRateSide| {
+                self.fx_service
+                    .get_exchange_rate_for_date_side(from_ccy, to_ccy, date, side)
+            };
+            let rates = (|| -> Result<TransferFxRates> {
+                Ok(TransferFxRates {
+                    out_mid: rate(&transfer.currency, &from.currency, FxRateSide::Mid)?,
+                    out_ask: rate(&transfer.currency, &from.currency, FxRateSide::Ask)?,
+                    in_mid: rate(&transfer.currency, &to.currency, FxRateSide::Mid)?,
+                    in_bid: rate(&transfer.currency, &to.currency, FxRateSide::Bid)?,
+                    out_to_base: rate(&from.currency, base_currency, FxRateSide::Mid)?,
+                    in_to_base: rate(&to.currency, base_currency, FxRateSide::Mid)?,
+                })
+            })();
+            let rates = match rates {
+                Ok(rates) => rates,
+                Err(e) => {
+                    warn!("Skipping FX cost of transfer {}: {}", pair.id, e);
+                    continue;
+                }
+            };
+
+            costs.push(TransferFxCost::calculate(
+                &pair.id,
+                date,
+                &transfer.currency,
+                transfer.amount.unwrap_or_default(),
+                (from.id.as_str(), from.currency.as_str()),
+                (to.id.as_str(), to.currency.as_str()),
+                base_currency,
+                &rates,
+            ));
+        }
+
+        costs.sort_by_key(|c| std::cmp::Reverse(c.date));
+        Ok(costs)
+    }
+
+    /// Lists the recorded changes of an activity, newest first
+    fn get_activity_history(&self, activity_id: &str) -> Result<Vec<ActivityChange>> {
+        self.activity_repository.get_activity_changes(activity_id)
+    }
+
+    /// Reverts a single recorded change
+    async fn revert_activity_change(
+        &self,
+        change_id: String,
+    ) -> Result<ActivityBulkMutationResult> {
+        let result = self
+            .activity_repository
+            .revert_activity_changes(vec![change_id])
+            .await?;
+        self.finish_revert(&result).await;
+        Ok(result)
+    }
+
+    /// Reverts every change recorded for a bulk mutation or import
+    async fn revert_activity_batch(&self, batch_id: String) -> Result<ActivityBulkMutationResult> {
+        let changes = self
+            .activity_repository
+            .get_activity_changes_by_batch(&batch_id)?;
+        if changes.is_empty() {
+            return Err(ActivityError::NotFound(format!(
+                "No activity changes recorded for batch {}",
+                batch_id
+            ))
+            .into());
+        }
+
+        let result = self
+            .activity_repository
+            .revert_activity_changes(changes.into_iter().map(|c| c.id).collect())
+            .await?;
+        self.finish_revert(&result).await;
+        Ok(result)
+    }
+
     /// Verifies the activities import from CSV file
     async fn check_activities_import(
         &self,
@@ -366,6 +556,7 @@ impl ActivityServiceTrait for ActivityService {
                 amount: activity.amount,
                 is_draft: activity.is_draft,
                 comment: activity.comment.clone(),
+                fx_rate_side: None,
             })
             .collect();
 
@@ -443,4 +634,80 @@ impl ActivityServiceTrait for ActivityService {
             .await?;
         Ok(mapping_data)
     }
+
+    /// Streams matching activities to the writer without loading the full result set
+    fn export_activities(
+        &self,
+        filters: ActivitySearchFilters,
+        sort: Option<Sort>,
+        format: ActivityExportFormat,
+        writer: &mut dyn Write,
+    ) -> Result<usize> {
+        let exported = write_activity_export(format, writer, |page| {
+            self.activity_repository
+                .search_activities(page, EXPORT_PAGE_SIZE, filters.clone(), sort.clone())
+                .map(|response| response.data)
+        })?;
+        debug!("Exported {} activities as {:?}", exported, format);
+        Ok(exported)
+    }
+}
+
+/// Writes the pages returned by `next_page` until one comes back short of
+/// `EXPORT_PAGE_SIZE`. A failing page aborts the export with its error.
+pub(crate) fn write_activity_export(
+    format: ActivityExportFormat,
+    writer: &mut dyn Write,
+    mut next_page: impl FnMut(i64) -> Result<Vec<ActivityDetails>>,
+) -> Result<usize> {
+    let mut exported = 0usize;
+    let mut page = 0i64;
+
+    match format {
+        ActivityExportFormat::Csv => {
+            let mut csv_writer = csv::WriterBuilder::new()
+                .has_headers(false)
+                .from_writer(writer);
+            csv_writer
+                .write_record(ActivityExportRow::HEADERS)
+                .map_err(std::io::Error::from)?;
+            loop {
+                let rows = next_page(page)?;
+                let fetched = rows.len();
+                for details in rows {
+                    csv_writer
+                        .serialize(ActivityExportRow::from(details))
+                        .map_err(std::io::Error::from)?;
+                    exported += 1;
+                }
+                if fetched < EXPORT_PAGE_SIZE as usize {
+                    break;
+                }
+                page += 1;
+            }
+            csv_writer.flush()?;
+        }
+        ActivityExportFormat::Json => {
+            writer.write_all(b"[")?;
+            loop {
+                let rows = next_page(page)?;
+                let fetched = rows.len();
+                for details in rows {
+                    if exported > 0 {
+                        writer.write_all(b",")?;
+                    }
+                    serde_json::to_writer(&mut *writer, &ActivityExportRow::from(details))?;
+                    exported += 1;
+                }
+                if fetched < EXPORT_PAGE_SIZE as usize {
+                    break;
+                }
+                page += 1;
+            }
+            writer.write_all(b"]")?;
+            writer.flush()?;
+        }
+    }
+
+    Ok(exported)
 }
@@ -1,10 +1,12 @@
 use super::activities_model::*;
+use crate::fx::TransferFxCost;
 use crate::Result;
 use async_trait::async_trait;
 use chrono::DateTime;
 use chrono::NaiveDateTime;
 use chrono::Utc;
 use rust_decimal::Decimal; // Assuming Result is defined in activities_model or activities_errors
+use std::io::Write;
 
 /// Trait defining the contract for Activity repository operations.
 #[async_trait]
@@ -13,6 +15,8 @@ pub trait ActivityRepositoryTrait: Send + Sync {
     fn get_activities(&self) -> Result<Vec<Activity>>;
     fn get_activities_by_account_id(&self, account_id: &String) -> Result<Vec<Activity>>;
     fn get_activities_by_account_ids(&self, account_ids: &[String]) -> Result<Vec<Activity>>;
+    /// Activities in active accounts that carry the tag themselves, oldest first
+    fn get_activities_by_tag(&self, tag_id: &str) -> Result<Vec<Activity>>;
     fn get_trading_activities(&self) -> Result<Vec<Activity>>;
     fn get_income_activities(&self) -> Result<Vec<Activity>>;
     fn get_deposit_activities(
@@ -20,14 +24,12 @@ pub trait ActivityRepositoryTrait: Send + Sync {
         account_ids: &[String],
         start_date: NaiveDateTime,
         end_date: NaiveDateTime,
-    ) -> Result<Vec<(String, Decimal, Decimal, String, Option<Decimal>)>>;
+    ) -> Result<Vec<Activity>>;
     fn search_activities(
         &self,
         page: i64,
         page_size: i64,
-        account_id_filter: Option<Vec<String>>,
-        activity_type_filter: Option<Vec<String>>,
-        asset_id_keyword: Option<String>,
+        filters: ActivitySearchFilters,
         sort: Option<Sort>,
     ) -> Result<ActivitySearchResponse>;
     async fn create_activity(&self, new_activity: NewActivity) -> Result<Activity>;
@@ -39,6 +41,19 @@ pub trait ActivityRepositoryTrait: Send + Sync {
         updates: Vec<ActivityUpdate>,
         delete_ids: Vec<String>,
     ) -> Result<ActivityBulkMutationResult>;
+    /// Inserts both legs of a transfer and the link between them in one transaction.
+    async fn create_paired_transfer(
+        &self,
+        transfer_out: NewActivity,
+        transfer_in: NewActivity,
+    ) -> Result<PairedTransfer>;
+    fn get_transfer_pairs(&self) -> Result<Vec<ActivityTransferPair>>;
+    fn get_activity_changes(&self, activity_id: &str) -> Result<Vec<ActivityChange>>;
+    fn get_activity_changes_by_batch(&self, batch_id: &str) -> Result<Vec<ActivityChange>>;
+    async fn revert_activity_changes(
+        &self,
+        change_ids: Vec<String>,
+    ) -> Result<ActivityBulkMutationResult>;
     async fn create_activities(&self, activities: Vec<NewActivity>) -> Result<usize>;
     fn get_first_activity_date(
         &self,
@@ -48,7 +63,7 @@ pub trait ActivityRepositoryTrait: Send + Sync {
     async fn save_import_mapping(&self, mapping: &ImportMapping) -> Result<()>;
     // Add other repository methods if necessary, e.g., calculate_average_cost, get_deposit_activities
     fn calculate_average_cost(&self, account_id: &str, asset_id: &str) -> Result<Decimal>;
-    fn get_income_activities_data(&self) -> Result<Vec<IncomeData>>;
+    fn get_income_activities_data(&self, tag_id: Option<&str>) -> Result<Vec<IncomeData>>;
     fn get_first_activity_date_overall(&self) -> Result<DateTime<Utc>>;
 }
 
@@ -65,9 +80,7 @@ pub trait ActivityServiceTrait: Send + Sync {
         &self,
         page: i64,
         page_size: i64,
-        account_id_filter: Option<Vec<String>>,
-        activity_type_filter: Option<Vec<String>>,
-        asset_id_keyword: Option<String>,
+        filters: ActivitySearchFilters,
         sort: Option<Sort>,
     ) -> Result<ActivitySearchResponse>;
     fn get_first_activity_date(
@@ -82,6 +95,17 @@ pub trait ActivityServiceTrait: Send + Sync {
         &self,
         request: ActivityBulkMutationRequest,
     ) -> Result<ActivityBulkMutationResult>;
+    /// Records a transfer between two accounts as a linked TRANSFER_OUT/TRANSFER_IN pair,
+    /// so the destination receives the source lots with their original dates and costs.
+    async fn create_paired_transfer(&self, transfer: NewPairedTransfer) -> Result<PairedTransfer>;
+    /// FX spread paid on cash transfers between accounts in different currencies,
+    /// valued in `base_currency`.
+    fn get_transfer_fx_costs(&self, base_currency: &str) -> Result<Vec<TransferFxCost>>;
+    fn get_activity_history(&self, activity_id: &str) -> Result<Vec<ActivityChange>>;
+    /// Reverts one change, then fully rebuilds snapshots and valuations of the affected accounts
+    async fn revert_activity_change(&self, change_id: String)
+        -> Result<ActivityBulkMutationResult>;
+    async fn revert_activity_batch(&self, batch_id: String) -> Result<ActivityBulkMutationResult>;
     async fn check_activities_import(
         &self,
         account_id: String,
@@ -96,4 +120,13 @@ pub trait ActivityServiceTrait: Send + Sync {
         &self,
         mapping_data: ImportMappingData,
     ) -> Result<ImportMappingData>;
+    /// Writes every activity matching the filters to `writer`, page by page.
+    /// Returns the number of exported rows.
+    fn export_activities(
+        &self,
+        filters: ActivitySearchFilters,
+        sort: Option<Sort>,
+        format: ActivityExportFormat,
+        writer: &mut dyn Write,
+    ) -> Result<usize>;
 }
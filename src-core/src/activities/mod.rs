@@ -2,6 +2,8 @@ pub(crate) mod activities_constants;
 pub(crate) mod activities_errors;
 pub(crate) mod activities_model;
 pub(crate) mod activities_repository;
+#[cfg(test)]
+mod activities_repository_tests;
 pub(crate) mod activities_service;
 pub(crate) mod activities_traits;
 
@@ -9,9 +11,11 @@ pub use activities_constants::*;
 pub use activities_errors::ActivityError;
 pub use activities_model::{
     Activity, ActivityBulkIdentifierMapping, ActivityBulkMutationError,
-    ActivityBulkMutationRequest, ActivityBulkMutationResult, ActivityDB, ActivityDetails,
-    ActivityImport, ActivitySearchResponse, ActivitySearchResponseMeta, ActivityType,
-    ActivityUpdate, ImportMapping, ImportMappingData, NewActivity, Sort,
+    ActivityBulkMutationRequest, ActivityBulkMutationResult, ActivityChange, ActivityChangeDB,
+    ActivityDB, ActivityDetails, ActivityExportFormat, ActivityExportRow, ActivityImport,
+    ActivitySearchFilters, ActivitySearchResponse, ActivitySearchResponseMeta,
+    ActivityTransferPair, ActivityTransferPairDB, ActivityType, ActivityUpdate, ImportMapping,
+    ImportMappingData, NewActivity, NewPairedTransfer, PairedTransfer, Sort,
 };
 pub use activities_repository::ActivityRepository;
 pub use activities_service::ActivityService;
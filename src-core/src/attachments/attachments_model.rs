@@ -0,0 +1,30 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Metadata of a file attached to an activity. The content itself is stored
+/// on disk, addressed by `content_hash`.
+#[derive(
+    Queryable, Insertable, Selectable, Identifiable, Serialize, Deserialize, Debug, Clone, PartialEq,
+)]
+#[diesel(table_name = crate::schema::activity_attachments)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityAttachment {
+    pub id: String,
+    pub activity_id: String,
+    /// Lowercase hex SHA-256 of the file content
+    pub content_hash: String,
+    pub file_name: String,
+    pub mime_type: Option<String>,
+    pub size_bytes: i64,
+    pub created_at: String,
+}
+
+/// Input model for attaching a file to an activity
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NewActivityAttachment {
+    pub activity_id: String,
+    pub file_name: String,
+    pub mime_type: Option<String>,
+}
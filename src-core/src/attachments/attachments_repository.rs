@@ -0,0 +1,120 @@
+use async_trait::async_trait;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::sqlite::SqliteConnection;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use super::attachments_model::ActivityAttachment;
+use super::attachments_traits::AttachmentRepositoryTrait;
+use super::content_store::ContentStore;
+use crate::activities::activities_model::ActivityDependents;
+use crate::db::{get_connection, WriteHandle};
+use crate::errors::{Error, Result};
+use crate::schema::{activity_attachments, activity_changes};
+
+pub struct AttachmentRepository {
+    pool: Arc<Pool<ConnectionManager<SqliteConnection>>>,
+    writer: WriteHandle,
+}
+
+impl AttachmentRepository {
+    pub fn new(pool: Arc<Pool<ConnectionManager<SqliteConnection>>>, writer: WriteHandle) -> Self {
+        AttachmentRepository { pool, writer }
+    }
+}
+
+/// Content hashes of current attachments and of the attachments of deleted
+/// activities in the change log, which a revert restores
+fn referenced_content_hashes(conn: &mut SqliteConnection) -> Result<HashSet<String>> {
+    let mut referenced: HashSet<String> = activity_attachments::table
+        .select(activity_attachments::content_hash)
+        .distinct()
+        .load::<String>(conn)?
+        .into_iter()
+        .collect();
+    let logged = activity_changes::table
+        .filter(activity_changes::dependents_state.is_not_null())
+        .select(activity_changes::dependents_state)
+        .load::<Option<String>>(conn)?;
+    for state in logged.into_iter().flatten() {
+        let dependents: ActivityDependents = serde_json::from_str(&state)?;
+        referenced.extend(dependents.attachments.into_iter().map(|a| a.content_hash));
+    }
+    Ok(referenced)
+}
+
+#[async_trait]
+impl AttachmentRepositoryTrait for AttachmentRepository {
+    fn get_attachment(&self, attachment_id: &str) -> Result<ActivityAttachment> {
+        let mut conn = get_connection(&self.pool)?;
+        activity_attachments::table
+            .find(attachment_id)
+            .first::<ActivityAttachment>(&mut conn)
+            .map_err(Error::from)
+    }
+
+    fn get_attachments_by_activity(&self, activity_id: &str) -> Result<Vec<ActivityAttachment>> {
+        let mut conn = get_connection(&self.pool)?;
+        activity_attachments::table
+            .filter(activity_attachments::activity_id.eq(activity_id))
+            .order(activity_attachments::created_at.asc())
+            .load::<ActivityAttachment>(&mut conn)
+            .map_err(Error::from)
+    }
+
+    async fn create_attachment(
+        &self,
+        attachment: ActivityAttachment,
+        content: Vec<u8>,
+        store: &ContentStore,
+    ) -> Result<ActivityAttachment> {
+        let store = store.clone();
+        self.writer
+            .exec(
+                move |conn: &mut SqliteConnection| -> Result<ActivityAttachment> {
+                    store.put(&attachment.content_hash, &content)?;
+                    diesel::insert_into(activity_attachments::table)
+                        .values(&attachment)
+                        .execute(conn)?;
+                    Ok(attachment)
+                },
+            )
+            .await
+    }
+
+    async fn delete_attachment(
+        &self,
+        attachment_id: &str,
+        store: &ContentStore,
+    ) -> Result<ActivityAttachment> {
+        let attachment_id_owned = attachment_id.to_string();
+        let store = store.clone();
+        self.writer
+            .exec(
+                move |conn: &mut SqliteConnection| -> Result<ActivityAttachment> {
+                    let attachment = activity_attachments::table
+                        .find(&attachment_id_owned)
+                        .first::<ActivityAttachment>(conn)?;
+                    diesel::delete(activity_attachments::table.find(&attachment_id_owned))
+                        .execute(conn)?;
+
+                    if !referenced_content_hashes(conn)?.contains(&attachment.content_hash) {
+                        store.remove(&attachment.content_hash)?;
+                    }
+                    Ok(attachment)
+                },
+            )
+            .await
+    }
+
+    async fn remove_unreferenced_content(&self, store: &ContentStore) -> Result<usize> {
+        let store = store.clone();
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<usize> {
+                let referenced = referenced_content_hashes(conn)?;
+                Ok(store.remove_unreferenced(&referenced)?)
+            })
+            .await
+    }
+}
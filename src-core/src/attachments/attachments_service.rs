@@ -0,0 +1,214 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use log::debug;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::attachments_model::{ActivityAttachment, NewActivityAttachment};
+use super::attachments_traits::{AttachmentRepositoryTrait, AttachmentServiceTrait};
+use super::content_store::ContentStore;
+use crate::activities::ActivityRepositoryTrait;
+use crate::errors::{Error, Result, ValidationError};
+
+/// Largest file accepted as an attachment
+pub const MAX_ATTACHMENT_SIZE_BYTES: usize = 25 * 1024 * 1024;
+
+pub struct AttachmentService {
+    attachment_repository: Arc<dyn AttachmentRepositoryTrait>,
+    activity_repository: Arc<dyn ActivityRepositoryTrait>,
+    content_store: ContentStore,
+}
+
+impl AttachmentService {
+    /// `attachments_dir` is the content store root, see `db::get_attachments_dir`.
+    pub fn new(
+        attachment_repository: Arc<dyn AttachmentRepositoryTrait>,
+        activity_repository: Arc<dyn ActivityRepositoryTrait>,
+        attachments_dir: PathBuf,
+    ) -> Self {
+        AttachmentService {
+            attachment_repository,
+            activity_repository,
+            content_store: ContentStore::new(attachments_dir),
+        }
+    }
+}
+
+#[async_trait]
+impl AttachmentServiceTrait for AttachmentService {
+    fn get_attachments(&self, activity_id: &str) -> Result<Vec<ActivityAttachment>> {
+        self.attachment_repository
+            .get_attachments_by_activity(activity_id)
+    }
+
+    fn get_attachment(&self, attachment_id: &str) -> Result<ActivityAttachment> {
+        self.attachment_repository.get_attachment(attachment_id)
+    }
+
+    fn get_attachment_path(&self, attachment_id: &str) -> Result<PathBuf> {
+        let attachment = self.attachment_repository.get_attachment(attachment_id)?;
+        Ok(self.content_store.path(&attachment.content_hash))
+    }
+
+    fn read_attachment(&self, attachment_id: &str) -> Result<(ActivityAttachment, Vec<u8>)> {
+        let attachment = self.attachment_repository.get_attachment(attachment_id)?;
+        let content = fs::read(self.content_store.path(&attachment.content_hash))?;
+        Ok((attachment, content))
+    }
+
+    async fn add_attachment(
+        &self,
+        new_attachment: NewActivityAttachment,
+        content: Vec<u8>,
+    ) -> Result<ActivityAttachment> {
+        let file_name = new_attachment.file_name.trim().to_string();
+        if file_name.is_empty() {
+            return Err(Error::Validation(ValidationError::MissingField(
+                "fileName".to_string(),
+            )));
+        }
+        if content.is_empty() {
+            return Err(Error::Validation(ValidationError::InvalidInput(
+                "Attachment is empty".to_string(),
+            )));
+        }
+        if content.len() > MAX_ATTACHMENT_SIZE_BYTES {
+            return Err(Error::Validation(ValidationError::InvalidInput(format!(
+                "Attachment exceeds the {} MB limit",
+                MAX_ATTACHMENT_SIZE_BYTES / (1024 * 1024)
+            ))));
+        }
+
+        // Fail before touching the disk if the activity does not exist
+        self.activity_repository
+            .get_activity(&new_attachment.activity_id)?;
+
+        let content_hash = format!("{:x}", Sha256::digest(&content));
+
+        let attachment = ActivityAttachment {
+            id: Uuid::new_v4().to_string(),
+            activity_id: new_attachment.activity_id,
+            content_hash,
+            file_name,
+            mime_type: new_attachment.mime_type,
+            size_bytes: content.len() as i64,
+            created_at: Utc::now().to_rfc3339(),
+        };
+        self.attachment_repository
+            .create_attachment(attachment, content, &self.content_store)
+            .await
+    }
+
+    async fn delete_attachment(&self, attachment_id: &str) -> Result<ActivityAttachment> {
+        self.attachment_repository
+            .delete_attachment(attachment_id, &self.content_store)
+            .await
+    }
+
+    async fn remove_unreferenced_content(&self) -> Result<usize> {
+        let removed = self
+            .attachment_repository
+            .remove_unreferenced_content(&self.content_store)
+            .await?;
+        if removed > 0 {
+            debug!("Removed {} unreferenced attachment files", removed);
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activities::ActivityRepository;
+    use crate::attachments::AttachmentRepository;
+    use crate::test_utils::TestDb;
+
+    const ACTIVITY_ID: &str = "act-1";
+
+    fn setup() -> (TestDb, Arc<ActivityRepository>, AttachmentService, PathBuf) {
+        let db = TestDb::new();
+        db.insert_account("acc-1", "USD");
+        db.insert_asset("$CASH-USD", "USD");
+        db.execute(&format!(
+            "INSERT INTO activities (id, account_id, asset_id, activity_type, activity_date, quantity, unit_price, currency, fee, amount, is_draft, created_at, updated_at)
+             VALUES ('{ACTIVITY_ID}', 'acc-1', '$CASH-USD', 'DEPOSIT', '2024-01-15T00:00:00+00:00', '0', '0', 'USD', '0', '100', 0, '2024-01-15T00:00:00+00:00', '2024-01-15T00:00:00+00:00');"
+        ));
+        let activity_repository =
+            Arc::new(ActivityRepository::new(db.pool.clone(), db.writer.clone()));
+        let attachments_dir =
+            std::env::temp_dir().join(format!("wealthvn-attachments-{}", Uuid::new_v4()));
+        let service = AttachmentService::new(
+            Arc::new(AttachmentRepository::new(
+                db.pool.clone(),
+                db.writer.clone(),
+            )),
+            activity_repository.clone(),
+            attachments_dir.clone(),
+        );
+        (db, activity_repository, service, attachments_dir)
+    }
+
+    fn receipt(file_name: &str) -> NewActivityAttachment {
+        NewActivityAttachment {
+            activity_id: ACTIVITY_ID.to_string(),
+            file_name: file_name.to_string(),
+            mime_type: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_keeps_content_shared_with_another_attachment() {
+        let (_db, _, service, attachments_dir) = setup();
+        let first = service
+            .add_attachment(receipt("a.pdf"), b"same".to_vec())
+            .await
+            .unwrap();
+        let second = service
+            .add_attachment(receipt("b.pdf"), b"same".to_vec())
+            .await
+            .unwrap();
+        let path = service.get_attachment_path(&first.id).unwrap();
+
+        service.delete_attachment(&first.id).await.unwrap();
+        assert!(path.exists());
+        service.delete_attachment(&second.id).await.unwrap();
+        assert!(!path.exists());
+        let _ = fs::remove_dir_all(attachments_dir);
+    }
+
+    #[tokio::test]
+    async fn test_activity_delete_keeps_content_until_reverted() {
+        let (_db, activity_repository, service, attachments_dir) = setup();
+        let attachment = service
+            .add_attachment(receipt("a.pdf"), b"content".to_vec())
+            .await
+            .unwrap();
+        let path = service.get_attachment_path(&attachment.id).unwrap();
+
+        activity_repository
+            .delete_activity(ACTIVITY_ID.to_string())
+            .await
+            .unwrap();
+        assert_eq!(service.remove_unreferenced_content().await.unwrap(), 0);
+        assert!(path.exists());
+
+        let delete = activity_repository
+            .get_activity_changes(ACTIVITY_ID)
+            .unwrap()[0]
+            .id
+            .clone();
+        activity_repository
+            .revert_activity_changes(vec![delete])
+            .await
+            .unwrap();
+        let (restored, content) = service.read_attachment(&attachment.id).unwrap();
+        assert_eq!(restored, attachment);
+        assert_eq!(content, b"content");
+        let _ = fs::remove_dir_all(attachments_dir);
+    }
+}
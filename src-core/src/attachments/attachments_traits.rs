@@ -0,0 +1,49 @@
+use super::attachments_model::{ActivityAttachment, NewActivityAttachment};
+use super::content_store::ContentStore;
+use crate::errors::Result;
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+/// Trait defining the contract for attachment metadata persistence.
+#[async_trait]
+pub trait AttachmentRepositoryTrait: Send + Sync {
+    fn get_attachment(&self, attachment_id: &str) -> Result<ActivityAttachment>;
+    fn get_attachments_by_activity(&self, activity_id: &str) -> Result<Vec<ActivityAttachment>>;
+    /// Stores the content and inserts the row in one writer job
+    async fn create_attachment(
+        &self,
+        attachment: ActivityAttachment,
+        content: Vec<u8>,
+        store: &ContentStore,
+    ) -> Result<ActivityAttachment>;
+    /// Deletes the row, and its content once no other row references it, in one writer job
+    async fn delete_attachment(
+        &self,
+        attachment_id: &str,
+        store: &ContentStore,
+    ) -> Result<ActivityAttachment>;
+    /// Deletes stored content no row references, e.g. after activities were deleted.
+    /// Attachments of deleted activities still in the change log count as references.
+    /// Returns the number of files removed.
+    async fn remove_unreferenced_content(&self, store: &ContentStore) -> Result<usize>;
+}
+
+/// Trait defining the contract for attachment service operations.
+#[async_trait]
+pub trait AttachmentServiceTrait: Send + Sync {
+    fn get_attachments(&self, activity_id: &str) -> Result<Vec<ActivityAttachment>>;
+    fn get_attachment(&self, attachment_id: &str) -> Result<ActivityAttachment>;
+    /// Absolute path of the stored content of an attachment
+    fn get_attachment_path(&self, attachment_id: &str) -> Result<PathBuf>;
+    fn read_attachment(&self, attachment_id: &str) -> Result<(ActivityAttachment, Vec<u8>)>;
+    async fn add_attachment(
+        &self,
+        new_attachment: NewActivityAttachment,
+        content: Vec<u8>,
+    ) -> Result<ActivityAttachment>;
+    /// Removes the attachment and its stored file once nothing else references it
+    async fn delete_attachment(&self, attachment_id: &str) -> Result<ActivityAttachment>;
+    /// Removes stored files left without an attachment, keeping those a revert of a
+    /// deleted activity would restore
+    async fn remove_unreferenced_content(&self) -> Result<usize>;
+}
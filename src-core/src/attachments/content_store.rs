@@ -0,0 +1,131 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use log::{debug, warn};
+use uuid::Uuid;
+
+/// Content-addressed file store for attachment bodies. Files are named by the
+/// SHA-256 of their content and sharded by its first two hex characters.
+///
+/// Only call the mutating methods from a writer job, so that checking whether
+/// content is still referenced and removing it cannot interleave with an insert.
+#[derive(Debug, Clone)]
+pub struct ContentStore {
+    root: PathBuf,
+}
+
+impl ContentStore {
+    /// `root` is the store directory, see `db::get_attachments_dir`
+    pub fn new(root: PathBuf) -> Self {
+        ContentStore { root }
+    }
+
+    pub fn path(&self, content_hash: &str) -> PathBuf {
+        self.root.join(&content_hash[..2]).join(content_hash)
+    }
+
+    /// Stores the content unless a file with the same hash already exists
+    pub fn put(&self, content_hash: &str, content: &[u8]) -> io::Result<()> {
+        let path = self.path(content_hash);
+        if path.exists() {
+            debug!("Attachment content {} already stored", content_hash);
+            return Ok(());
+        }
+        let parent = path.parent().unwrap_or(Path::new("."));
+        fs::create_dir_all(parent)?;
+        // Write to a temporary file first so a crash never leaves a truncated blob behind
+        let tmp_path = parent.join(format!("{}.tmp-{}", content_hash, Uuid::new_v4()));
+        fs::write(&tmp_path, content)?;
+        fs::rename(&tmp_path, &path)
+    }
+
+    pub fn remove(&self, content_hash: &str) -> io::Result<()> {
+        match fs::remove_file(self.path(content_hash)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Deletes every stored file whose name is not in `referenced`, including
+    /// temporary files left by an interrupted write. Returns the number removed.
+    pub fn remove_unreferenced(&self, referenced: &HashSet<String>) -> io::Result<usize> {
+        if !self.root.exists() {
+            return Ok(0);
+        }
+        let mut removed = 0;
+        for shard in fs::read_dir(&self.root)? {
+            let shard = shard?;
+            if !shard.file_type()?.is_dir() {
+                continue;
+            }
+            for entry in fs::read_dir(shard.path())? {
+                let entry = entry?;
+                let name = entry.file_name().to_string_lossy().to_string();
+                if referenced.contains(&name) {
+                    continue;
+                }
+                match fs::remove_file(entry.path()) {
+                    Ok(()) => removed += 1,
+                    Err(e) => warn!(
+                        "Failed to remove unreferenced attachment content {}: {}",
+                        entry.path().display(),
+                        e
+                    ),
+                }
+            }
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store() -> ContentStore {
+        ContentStore::new(std::env::temp_dir().join(format!("wealthvn-store-{}", Uuid::new_v4())))
+    }
+
+    #[test]
+    fn test_put_shards_by_hash_prefix_and_keeps_existing_content() {
+        let store = temp_store();
+        store.put("abcdef", b"first").unwrap();
+        store.put("abcdef", b"second").unwrap();
+
+        let path = store.path("abcdef");
+        assert!(path.ends_with("ab/abcdef"));
+        assert_eq!(fs::read(&path).unwrap(), b"first");
+        fs::remove_dir_all(&store.root).unwrap();
+    }
+
+    #[test]
+    fn test_remove_tolerates_missing_content() {
+        let store = temp_store();
+        store.put("abcdef", b"content").unwrap();
+
+        store.remove("abcdef").unwrap();
+        store.remove("abcdef").unwrap();
+
+        assert!(!store.path("abcdef").exists());
+        fs::remove_dir_all(&store.root).unwrap();
+    }
+
+    #[test]
+    fn test_remove_unreferenced_keeps_referenced_content() {
+        let store = temp_store();
+        assert_eq!(store.remove_unreferenced(&HashSet::new()).unwrap(), 0);
+        store.put("aa11", b"kept").unwrap();
+        store.put("bb22", b"orphan").unwrap();
+        fs::write(store.root.join("aa").join("aa33.tmp-1"), b"partial").unwrap();
+
+        let referenced = HashSet::from(["aa11".to_string()]);
+        let removed = store.remove_unreferenced(&referenced).unwrap();
+
+        assert_eq!(removed, 2);
+        assert!(store.path("aa11").exists());
+        assert!(!store.path("bb22").exists());
+        fs::remove_dir_all(&store.root).unwrap();
+    }
+}
@@ -0,0 +1,11 @@
+mod attachments_model;
+mod attachments_repository;
+mod attachments_service;
+mod attachments_traits;
+mod content_store;
+
+pub use attachments_model::{ActivityAttachment, NewActivityAttachment};
+pub use attachments_repository::AttachmentRepository;
+pub use attachments_service::{AttachmentService, MAX_ATTACHMENT_SIZE_BYTES};
+pub use attachments_traits::{AttachmentRepositoryTrait, AttachmentServiceTrait};
+pub use content_store::ContentStore;
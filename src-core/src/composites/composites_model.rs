@@ -0,0 +1,119 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Kind of entry in a composite portfolio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompositeMemberType {
+    /// A single account, referenced by its ID
+    Account,
+    /// Every account whose `group` matches the member ID
+    Group,
+}
+
+impl CompositeMemberType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompositeMemberType::Account => "account",
+            CompositeMemberType::Group => "group",
+        }
+    }
+}
+
+impl FromStr for CompositeMemberType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "account" => Ok(CompositeMemberType::Account),
+            "group" => Ok(CompositeMemberType::Group),
+            _ => Err(format!("Unknown composite member type: {}", s)),
+        }
+    }
+}
+
+/// An account or account group included in a composite portfolio
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct CompositeMember {
+    pub member_type: CompositeMemberType,
+    pub member_id: String,
+}
+
+/// A named set of accounts and account groups valued and measured as one portfolio
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CompositePortfolio {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub members: Vec<CompositeMember>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Input model for creating a new composite portfolio
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NewCompositePortfolio {
+    pub id: Option<String>,
+    pub name: String,
+    pub description: Option<String>,
+    pub members: Vec<CompositeMember>,
+}
+
+/// Input model for updating a composite portfolio; the member list is replaced
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CompositePortfolioUpdate {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub members: Vec<CompositeMember>,
+}
+
+/// Database model for composite portfolios
+#[derive(Queryable, Insertable, Selectable, Debug, Clone)]
+#[diesel(table_name = crate::schema::composite_portfolios)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct CompositePortfolioDB {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Database model for composite portfolio members
+#[derive(Queryable, Insertable, Selectable, Debug, Clone)]
+#[diesel(table_name = crate::schema::composite_portfolio_members)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct CompositeMemberDB {
+    pub composite_id: String,
+    pub member_type: String,
+    pub member_id: String,
+}
+
+impl CompositePortfolio {
+    pub(crate) fn from_db(db: CompositePortfolioDB, members: Vec<CompositeMemberDB>) -> Self {
+        CompositePortfolio {
+            id: db.id,
+            name: db.name,
+            description: db.description,
+            members: members
+                .into_iter()
+                .filter_map(|m| {
+                    CompositeMemberType::from_str(&m.member_type)
+                        .ok()
+                        .map(|member_type| CompositeMember {
+                            member_type,
+                            member_id: m.member_id,
+                        })
+                })
+                .collect(),
+            created_at: db.created_at,
+            updated_at: db.updated_at,
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::sqlite::SqliteConnection;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::composites_model::{
+    CompositeMember, CompositeMemberDB, CompositeMemberType, CompositePortfolio,
+    CompositePortfolioDB, CompositePortfolioUpdate, NewCompositePortfolio,
+};
+use super::composites_traits::CompositeRepositoryTrait;
+use crate::db::{get_connection, WriteHandle};
+use crate::errors::{Error, Result};
+use crate::schema::{accounts, composite_portfolio_members, composite_portfolios};
+
+pub struct CompositeRepository {
+    pool: Arc<Pool<ConnectionManager<SqliteConnection>>>,
+    writer: WriteHandle,
+}
+
+impl CompositeRepository {
+    pub fn new(pool: Arc<Pool<ConnectionManager<SqliteConnection>>>, writer: WriteHandle) -> Self {
+        CompositeRepository { pool, writer }
+    }
+}
+
+fn load_composite(conn: &mut SqliteConnection, composite_id: &str) -> Result<CompositePortfolio> {
+    let composite = composite_portfolios::table
+        .find(composite_id)
+        .first::<CompositePortfolioDB>(conn)?;
+    let members = composite_portfolio_members::table
+        .filter(composite_portfolio_members::composite_id.eq(composite_id))
+        .order((
+            composite_portfolio_members::member_type.asc(),
+            composite_portfolio_members::member_id.asc(),
+        ))
+        .load::<CompositeMemberDB>(conn)?;
+    Ok(CompositePortfolio::from_db(composite, members))
+}
+
+fn replace_members(
+    conn: &mut SqliteConnection,
+    composite_id: &str,
+    members: &[CompositeMember],
+) -> Result<()> {
+    diesel::delete(
+        composite_portfolio_members::table
+            .filter(composite_portfolio_members::composite_id.eq(composite_id)),
+    )
+    .execute(conn)?;
+    let rows: Vec<CompositeMemberDB> = members
+        .iter()
+        .map(|member| CompositeMemberDB {
+            composite_id: composite_id.to_string(),
+            member_type: member.member_type.as_str().to_string(),
+            member_id: member.member_id.clone(),
+        })
+        .collect();
+    if !rows.is_empty() {
+        diesel::insert_or_ignore_into(composite_portfolio_members::table)
+            .values(&rows)
+            .execute(conn)?;
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl CompositeRepositoryTrait for CompositeRepository {
+    fn get_composites(&self) -> Result<Vec<CompositePortfolio>> {
+        let mut conn = get_connection(&self.pool)?;
+        let composites = composite_portfolios::table
+            .order(composite_portfolios::name.asc())
+            .load::<CompositePortfolioDB>(&mut conn)?;
+        let members = composite_portfolio_members::table
+            .order((
+                composite_portfolio_members::member_type.asc(),
+                composite_portfolio_members::member_id.asc(),
+            ))
+            .load::<CompositeMemberDB>(&mut conn)?;
+
+        Ok(composites
+            .into_iter()
+            .map(|composite| {
+                let own_members = members
+                    .iter()
+                    .filter(|m| m.composite_id == composite.id)
+                    .cloned()
+                    .collect();
+                CompositePortfolio::from_db(composite, own_members)
+            })
+            .collect())
+    }
+
+    fn get_composite(&self, composite_id: &str) -> Result<CompositePortfolio> {
+        let mut conn = get_connection(&self.pool)?;
+        load_composite(&mut conn, composite_id)
+    }
+
+    async fn create_composite(
+        &self,
+        new_composite: NewCompositePortfolio,
+    ) -> Result<CompositePortfolio> {
+        self.writer
+            .exec(
+                move |conn: &mut SqliteConnection| -> Result<CompositePortfolio> {
+                    let now = Utc::now().to_rfc3339();
+                    let composite = CompositePortfolioDB {
+                        id: new_composite
+                            .id
+                            .unwrap_or_else(|| Uuid::new_v4().to_string()),
+                        name: new_composite.name,
+                        description: new_composite.description,
+                        created_at: now.clone(),
+                        updated_at: now,
+                    };
+                    diesel::insert_into(composite_portfolios::table)
+                        .values(&composite)
+                        .execute(conn)?;
+                    replace_members(conn, &composite.id, &new_composite.members)?;
+                    load_composite(conn, &composite.id)
+                },
+            )
+            .await
+    }
+
+    async fn update_composite(
+        &self,
+        update: CompositePortfolioUpdate,
+    ) -> Result<CompositePortfolio> {
+        self.writer
+            .exec(
+                move |conn: &mut SqliteConnection| -> Result<CompositePortfolio> {
+                    diesel::update(composite_portfolios::table.find(&update.id))
+                        .set((
+                            composite_portfolios::name.eq(&update.name),
+                            composite_portfolios::description.eq(&update.description),
+                            composite_portfolios::updated_at.eq(Utc::now().to_rfc3339()),
+                        ))
+                        .execute(conn)?;
+                    replace_members(conn, &update.id, &update.members)?;
+                    load_composite(conn, &update.id)
+                },
+            )
+            .await
+    }
+
+    async fn delete_composite(&self, composite_id: &str) -> Result<usize> {
+        let composite_id_owned = composite_id.to_string();
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<usize> {
+                // Members are removed by ON DELETE CASCADE
+                diesel::delete(composite_portfolios::table.find(composite_id_owned))
+                    .execute(conn)
+                    .map_err(Error::from)
+            })
+            .await
+    }
+
+    fn get_account_ids_for_composite(&self, composite_id: &str) -> Result<Vec<String>> {
+        let mut conn = get_connection(&self.pool)?;
+        let composite = load_composite(&mut conn, composite_id)?;
+
+        let mut member_account_ids: Vec<String> = Vec::new();
+        let mut groups: Vec<String> = Vec::new();
+        for member in composite.members {
+            match member.member_type {
+                CompositeMemberType::Account => member_account_ids.push(member.member_id),
+                CompositeMemberType::Group => groups.push(member.member_id),
+            }
+        }
+
+        // Inactive accounts drop out of the composite like they drop out of the TOTAL portfolio
+        let account_ids: BTreeSet<String> = accounts::table
+            .filter(
+                accounts::id
+                    .eq_any(&member_account_ids)
+                    .or(accounts::group.eq_any(&groups)),
+            )
+            .filter(accounts::is_active.eq(true))
+            .select(accounts::id)
+            .load::<String>(&mut conn)?
+            .into_iter()
+            .collect();
+
+        Ok(account_ids.into_iter().collect())
+    }
+}
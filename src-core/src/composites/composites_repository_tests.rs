@@ -0,0 +1,68 @@
+use super::{
+    CompositeMember, CompositeMemberType, CompositeRepository, CompositeRepositoryTrait,
+    NewCompositePortfolio,
+};
+use crate::test_utils::TestDb;
+
+fn member(member_type: CompositeMemberType, member_id: &str) -> CompositeMember {
+    CompositeMember {
+        member_type,
+        member_id: member_id.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_composite_members_resolve_to_active_accounts() {
+    let db = TestDb::new();
+    for id in ["acc-direct", "acc-grouped", "acc-closed", "acc-other"] {
+        db.insert_account(id, "USD");
+    }
+    db.execute(
+        "UPDATE accounts SET \"group\" = 'Retirement' WHERE id IN ('acc-grouped', 'acc-closed');
+         UPDATE accounts SET is_active = 0 WHERE id = 'acc-closed';",
+    );
+    let repository = CompositeRepository::new(db.pool.clone(), db.writer.clone());
+    let composite = repository
+        .create_composite(NewCompositePortfolio {
+            id: None,
+            name: "Family".to_string(),
+            description: None,
+            members: vec![
+                member(CompositeMemberType::Account, "acc-direct"),
+                member(CompositeMemberType::Account, "acc-grouped"),
+                member(CompositeMemberType::Group, "Retirement"),
+            ],
+        })
+        .await
+        .unwrap();
+
+    let account_ids = repository
+        .get_account_ids_for_composite(&composite.id)
+        .unwrap();
+
+    // The grouped account is listed once; the inactive one is left out
+    assert_eq!(account_ids, vec!["acc-direct", "acc-grouped"]);
+}
+
+#[tokio::test]
+async fn test_inactive_direct_member_is_skipped() {
+    let db = TestDb::new();
+    db.insert_account("acc-closed", "USD");
+    db.execute("UPDATE accounts SET is_active = 0 WHERE id = 'acc-closed';");
+    let repository = CompositeRepository::new(db.pool.clone(), db.writer.clone());
+    let composite = repository
+        .create_composite(NewCompositePortfolio {
+            id: None,
+            name: "Closed".to_string(),
+            description: None,
+            members: vec![member(CompositeMemberType::Account, "acc-closed")],
+        })
+        .await
+        .unwrap();
+
+    let account_ids = repository
+        .get_account_ids_for_composite(&composite.id)
+        .unwrap();
+
+    assert!(account_ids.is_empty());
+}
@@ -0,0 +1,115 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use log::debug;
+
+use super::composites_model::{
+    CompositeMember, CompositePortfolio, CompositePortfolioUpdate, NewCompositePortfolio,
+};
+use super::composites_traits::{CompositeRepositoryTrait, CompositeServiceTrait};
+use crate::constants::PORTFOLIO_TOTAL_ACCOUNT_ID;
+use crate::errors::{Error, Result, ValidationError};
+
+pub struct CompositeService {
+    composite_repository: Arc<dyn CompositeRepositoryTrait>,
+}
+
+impl CompositeService {
+    pub fn new(composite_repository: Arc<dyn CompositeRepositoryTrait>) -> Self {
+        CompositeService {
+            composite_repository,
+        }
+    }
+
+    fn normalize_name(name: &str) -> Result<String> {
+        let trimmed = name.trim();
+        if trimmed.is_empty() {
+            return Err(Error::Validation(ValidationError::MissingField(
+                "name".to_string(),
+            )));
+        }
+        Ok(trimmed.to_string())
+    }
+
+    /// Trims member IDs and drops blanks, duplicates and the TOTAL virtual account,
+    /// which would double count every other member.
+    fn normalize_members(members: Vec<CompositeMember>) -> Result<Vec<CompositeMember>> {
+        let mut seen = HashSet::new();
+        let normalized: Vec<CompositeMember> = members
+            .into_iter()
+            .map(|member| CompositeMember {
+                member_id: member.member_id.trim().to_string(),
+                ..member
+            })
+            .filter(|member| {
+                !member.member_id.is_empty()
+                    && member.member_id != PORTFOLIO_TOTAL_ACCOUNT_ID
+                    && seen.insert(member.clone())
+            })
+            .collect();
+        if normalized.is_empty() {
+            return Err(Error::Validation(ValidationError::InvalidInput(
+                "A composite portfolio needs at least one account or group".to_string(),
+            )));
+        }
+        Ok(normalized)
+    }
+}
+
+#[async_trait]
+impl CompositeServiceTrait for CompositeService {
+    fn get_composites(&self) -> Result<Vec<CompositePortfolio>> {
+        self.composite_repository.get_composites()
+    }
+
+    fn get_composite(&self, composite_id: &str) -> Result<CompositePortfolio> {
+        self.composite_repository.get_composite(composite_id)
+    }
+
+    async fn create_composite(
+        &self,
+        new_composite: NewCompositePortfolio,
+    ) -> Result<CompositePortfolio> {
+        let name = Self::normalize_name(&new_composite.name)?;
+        let members = Self::normalize_members(new_composite.members)?;
+        debug!(
+            "Creating composite portfolio '{}' with {} members",
+            name,
+            members.len()
+        );
+        self.composite_repository
+            .create_composite(NewCompositePortfolio {
+                name,
+                members,
+                ..new_composite
+            })
+            .await
+    }
+
+    async fn update_composite(
+        &self,
+        update: CompositePortfolioUpdate,
+    ) -> Result<CompositePortfolio> {
+        let name = Self::normalize_name(&update.name)?;
+        let members = Self::normalize_members(update.members)?;
+        self.composite_repository
+            .update_composite(CompositePortfolioUpdate {
+                name,
+                members,
+                ..update
+            })
+            .await
+    }
+
+    async fn delete_composite(&self, composite_id: &str) -> Result<usize> {
+        self.composite_repository
+            .delete_composite(composite_id)
+            .await
+    }
+
+    fn get_account_ids_for_composite(&self, composite_id: &str) -> Result<Vec<String>> {
+        self.composite_repository
+            .get_account_ids_for_composite(composite_id)
+    }
+}
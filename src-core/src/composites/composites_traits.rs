@@ -0,0 +1,41 @@
+use super::composites_model::{
+    CompositePortfolio, CompositePortfolioUpdate, NewCompositePortfolio,
+};
+use crate::errors::Result;
+use async_trait::async_trait;
+
+/// Trait defining the contract for composite portfolio repository operations.
+#[async_trait]
+pub trait CompositeRepositoryTrait: Send + Sync {
+    fn get_composites(&self) -> Result<Vec<CompositePortfolio>>;
+    fn get_composite(&self, composite_id: &str) -> Result<CompositePortfolio>;
+    async fn create_composite(
+        &self,
+        new_composite: NewCompositePortfolio,
+    ) -> Result<CompositePortfolio>;
+    async fn update_composite(
+        &self,
+        update: CompositePortfolioUpdate,
+    ) -> Result<CompositePortfolio>;
+    async fn delete_composite(&self, composite_id: &str) -> Result<usize>;
+    /// Resolves the members of a composite to the IDs of the active accounts they cover,
+    /// expanding groups to their current accounts
+    fn get_account_ids_for_composite(&self, composite_id: &str) -> Result<Vec<String>>;
+}
+
+/// Trait defining the contract for composite portfolio service operations.
+#[async_trait]
+pub trait CompositeServiceTrait: Send + Sync {
+    fn get_composites(&self) -> Result<Vec<CompositePortfolio>>;
+    fn get_composite(&self, composite_id: &str) -> Result<CompositePortfolio>;
+    async fn create_composite(
+        &self,
+        new_composite: NewCompositePortfolio,
+    ) -> Result<CompositePortfolio>;
+    async fn update_composite(
+        &self,
+        update: CompositePortfolioUpdate,
+    ) -> Result<CompositePortfolio>;
+    async fn delete_composite(&self, composite_id: &str) -> Result<usize>;
+    fn get_account_ids_for_composite(&self, composite_id: &str) -> Result<Vec<String>>;
+}
@@ -0,0 +1,15 @@
+mod composites_model;
+mod composites_repository;
+mod composites_service;
+mod composites_traits;
+
+#[cfg(test)]
+mod composites_repository_tests;
+
+pub use composites_model::{
+    CompositeMember, CompositeMemberDB, CompositeMemberType, CompositePortfolio,
+    CompositePortfolioDB, CompositePortfolioUpdate, NewCompositePortfolio,
+};
+pub use composites_repository::CompositeRepository;
+pub use composites_service::CompositeService;
+pub use composites_traits::{CompositeRepositoryTrait, CompositeServiceTrait};
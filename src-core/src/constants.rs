@@ -1,6 +1,12 @@
 /// Total account ID
 pub const PORTFOLIO_TOTAL_ACCOUNT_ID: &str = "TOTAL";
 
+/// Benchmark used for accounts without a configured one
+pub const DEFAULT_BENCHMARK_SYMBOL: &str = "VNINDEX";
+
+/// Risk-free rate series used for Sharpe and Sortino ratios until another is chosen
+pub const DEFAULT_RISK_FREE_RATE_SERIES: &str = "VN_DEPOSIT_12M";
+
 /// Cash asset ID prefix
 pub const CASH_ASSET_PREFIX: &str = "$CASH";
 
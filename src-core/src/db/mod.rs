@@ -2,7 +2,7 @@ use chrono::Local;
 use log::{error, info, warn};
 use std::fs;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use diesel::connection::{Connection, SimpleConnection};
@@ -14,6 +14,9 @@ use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use crate::errors::{DatabaseError, Error, Result};
 
 const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
+const ATTACHMENTS_DIR_NAME: &str = "attachments";
+/// Extension of a backup packed with its attachments, see `create_backup_bundle`
+pub const BACKUP_BUNDLE_EXTENSION: &str = "zip";
 
 pub type DbPool = r2d2::Pool<ConnectionManager<SqliteConnection>>;
 pub type DbConnection = PooledConnection<ConnectionManager<SqliteConnection>>;
@@ -104,6 +107,15 @@ pub fn get_db_path(input: &str) -> String {
     }
 }
 
+/// Directory holding content-addressed activity attachments, next to the database file
+pub fn get_attachments_dir(app_data_dir: &str) -> PathBuf {
+    let db_path = get_db_path(app_data_dir);
+    Path::new(&db_path)
+        .parent()
+        .unwrap_or(Path::new(app_data_dir))
+        .join(ATTACHMENTS_DIR_NAME)
+}
+
 pub fn create_backup_path(app_data_dir: &str) -> Result<String> {
     let backup_dir = Path::new(app_data_dir).join("backups");
     fs::create_dir_all(&backup_dir).map_err(|e| {
@@ -153,10 +165,119 @@ pub fn backup_database(app_data_dir: &str) -> Result<String> {
         })?;
     }
 
-    info!("Database backup created successfully (including WAL/SHM files if present)");
+    backup_attachments(app_data_dir, &backup_path)?;
+
+    info!(
+        "Database backup created successfully (including WAL/SHM files and attachments if present)"
+    );
     Ok(backup_path)
 }
 
+/// Copies the attachments directory next to a database backup file, as `<backup>-attachments`
+pub fn backup_attachments(app_data_dir: &str, backup_path: &str) -> Result<()> {
+    let attachments_source = get_attachments_dir(app_data_dir);
+    if !attachments_source.exists() {
+        return Ok(());
+    }
+    let attachments_target = PathBuf::from(format!("{}-{}", backup_path, ATTACHMENTS_DIR_NAME));
+    copy_dir_recursive(&attachments_source, &attachments_target).map_err(|e| {
+        error!("Failed to copy attachments: {}", e);
+        Error::Database(DatabaseError::BackupFailed(e.to_string()))
+    })
+}
+
+/// Packs a database backup, its WAL/SHM files and its attachments copy into one zip
+/// next to it, so the backup can be downloaded as a single file. Returns the zip path.
+pub fn create_backup_bundle(backup_path: &str) -> Result<String> {
+    let backup_failed = |e: &dyn std::fmt::Display| {
+        error!("Failed to create backup bundle: {}", e);
+        Error::Database(DatabaseError::BackupFailed(e.to_string()))
+    };
+    let db_file_name = Path::new(backup_path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| backup_failed(&"Invalid backup path"))?;
+    let bundle_path = Path::new(backup_path).with_extension(BACKUP_BUNDLE_EXTENSION);
+
+    let file = fs::File::create(&bundle_path).map_err(|e| backup_failed(&e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::FileOptions::default();
+    for suffix in ["", "-wal", "-shm"] {
+        let source = format!("{}{}", backup_path, suffix);
+        if !Path::new(&source).exists() {
+            continue;
+        }
+        zip.start_file(format!("{}{}", db_file_name, suffix), options)
+            .map_err(|e| backup_failed(&e))?;
+        let mut content = fs::File::open(&source).map_err(|e| backup_failed(&e))?;
+        io::copy(&mut content, &mut zip).map_err(|e| backup_failed(&e))?;
+    }
+
+    let attachments = PathBuf::from(format!("{}-{}", backup_path, ATTACHMENTS_DIR_NAME));
+    if attachments.exists() {
+        zip_dir_recursive(&mut zip, &attachments, ATTACHMENTS_DIR_NAME, options)
+            .map_err(|e| backup_failed(&e))?;
+    }
+    zip.finish().map_err(|e| backup_failed(&e))?;
+
+    Ok(bundle_path.to_string_lossy().to_string())
+}
+
+/// Extracts a bundle made by `create_backup_bundle` under the backups directory and
+/// returns the path of the database inside, with attachments at `<db>-attachments`.
+fn unpack_backup_bundle(app_data_dir: &str, bundle_path: &str) -> Result<String> {
+    let restore_failed = |e: &dyn std::fmt::Display| {
+        error!("Failed to unpack backup bundle {}: {}", bundle_path, e);
+        Error::Database(DatabaseError::RestoreFailed(e.to_string()))
+    };
+    let target_dir = Path::new(app_data_dir).join("backups").join(format!(
+        "restore_{}",
+        Local::now().format("%Y%m%d_%H%M%S")
+    ));
+    let file = fs::File::open(bundle_path).map_err(|e| restore_failed(&e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| restore_failed(&e))?;
+
+    let mut db_path = None;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| restore_failed(&e))?;
+        if entry.is_dir() {
+            continue;
+        }
+        // `enclosed_name` rejects absolute paths and `..` components
+        let Some(name) = entry.enclosed_name().map(Path::to_path_buf) else {
+            return Err(restore_failed(&format!("Unsafe entry '{}'", entry.name())));
+        };
+        let target = match name.strip_prefix(ATTACHMENTS_DIR_NAME) {
+            Ok(relative) => target_dir.join("attachments").join(relative),
+            Err(_) => target_dir.join(&name),
+        };
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| restore_failed(&e))?;
+        }
+        let mut out = fs::File::create(&target).map_err(|e| restore_failed(&e))?;
+        io::copy(&mut entry, &mut out).map_err(|e| restore_failed(&e))?;
+
+        let is_database = name.components().count() == 1
+            && !name.to_string_lossy().ends_with("-wal")
+            && !name.to_string_lossy().ends_with("-shm");
+        if is_database {
+            db_path = Some(target);
+        }
+    }
+
+    let db_path = db_path.ok_or_else(|| restore_failed(&"No database in backup bundle"))?;
+    let attachments = target_dir.join("attachments");
+    if attachments.exists() {
+        let laid_out = PathBuf::from(format!(
+            "{}-{}",
+            db_path.to_string_lossy(),
+            ATTACHMENTS_DIR_NAME
+        ));
+        fs::rename(&attachments, &laid_out).map_err(|e| restore_failed(&e))?;
+    }
+    Ok(db_path.to_string_lossy().to_string())
+}
+
 pub fn restore_database(app_data_dir: &str, backup_file_path: &str) -> Result<()> {
     let db_path = get_db_path(app_data_dir);
 
@@ -172,6 +293,17 @@ pub fn restore_database(app_data_dir: &str, backup_file_path: &str) -> Result<()
         )));
     }
 
+    let unpacked_path;
+    let backup_file_path = if Path::new(backup_file_path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(BACKUP_BUNDLE_EXTENSION))
+    {
+        unpacked_path = unpack_backup_bundle(app_data_dir, backup_file_path)?;
+        unpacked_path.as_str()
+    } else {
+        backup_file_path
+    };
+
     // Create backup of current database before restore
     let restore_backup_path = format!(
         "{}.pre-restore-{}",
@@ -265,6 +397,19 @@ pub fn restore_database(app_data_dir: &str, backup_file_path: &str) -> Result<()
         }
     }
 
+    // Restore attachment files. Content is addressed by hash, so merging into the
+    // current directory never overwrites a different file.
+    let backup_attachments_path =
+        PathBuf::from(format!("{}-{}", backup_file_path, ATTACHMENTS_DIR_NAME));
+    if backup_attachments_path.exists() {
+        copy_dir_recursive(&backup_attachments_path, &get_attachments_dir(app_data_dir)).map_err(
+            |e| {
+                error!("Failed to restore attachments: {}", e);
+                Error::Database(DatabaseError::RestoreFailed(e.to_string()))
+            },
+        )?;
+    }
+
     // Ensure desired journal mode; recreate WAL after restore for consistency
     if let Ok(mut conn) = SqliteConnection::establish(&db_path) {
         let _ = conn.batch_execute("PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON;");
@@ -405,6 +550,41 @@ fn copy_with_retries(
     Err(Error::Database(DatabaseError::BackupFailed(e.to_string())))
 }
 
+/// Adds the files under `src` to the zip, named `<prefix>/<relative path>`
+fn zip_dir_recursive(
+    zip: &mut zip::ZipWriter<fs::File>,
+    src: &Path,
+    prefix: &str,
+    options: zip::write::FileOptions,
+) -> zip::result::ZipResult<()> {
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let name = format!("{}/{}", prefix, entry.file_name().to_string_lossy());
+        if entry.file_type()?.is_dir() {
+            zip_dir_recursive(zip, &entry.path(), &name, options)?;
+        } else {
+            zip.start_file(name, options)?;
+            io::copy(&mut fs::File::open(entry.path())?, zip)?;
+        }
+    }
+    Ok(())
+}
+
+/// Recursively copy a directory, skipping files that already exist at the destination.
+fn copy_dir_recursive(src: &Path, dst: &Path) -> io::Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let target = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_recursive(&entry.path(), &target)?;
+        } else if !target.exists() {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
 /// Trait for executing database transactions
 pub trait DbTransactionExecutor {
     /// Execute operations within a transaction and return the result
@@ -440,3 +620,53 @@ impl DbTransactionExecutor for Arc<DbPool> {
         (**self).execute(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// App data directory with a database file and one stored attachment
+    fn app_data_dir(db_content: &[u8]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("wealthvn-backup-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join(ATTACHMENTS_DIR_NAME).join("ab")).unwrap();
+        fs::write(dir.join("app.db"), db_content).unwrap();
+        fs::write(dir.join(ATTACHMENTS_DIR_NAME).join("ab").join("abcd"), b"receipt").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_backup_bundle_round_trips_database_and_attachments() {
+        let dir = app_data_dir(b"original");
+        let dir_str = dir.to_str().unwrap();
+
+        let backup_path = backup_database(dir_str).unwrap();
+        let bundle_path = create_backup_bundle(&backup_path).unwrap();
+        assert!(bundle_path.ends_with(".zip"));
+
+        fs::write(dir.join("app.db"), b"changed").unwrap();
+        fs::remove_dir_all(dir.join(ATTACHMENTS_DIR_NAME)).unwrap();
+
+        restore_database(dir_str, &bundle_path).unwrap();
+
+        assert_eq!(fs::read(dir.join("app.db")).unwrap(), b"original");
+        assert_eq!(
+            fs::read(dir.join(ATTACHMENTS_DIR_NAME).join("ab").join("abcd")).unwrap(),
+            b"receipt"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_restore_from_plain_database_backup_still_works() {
+        let dir = app_data_dir(b"original");
+        let dir_str = dir.to_str().unwrap();
+
+        let backup_path = backup_database(dir_str).unwrap();
+        fs::write(dir.join("app.db"), b"changed").unwrap();
+
+        restore_database(dir_str, &backup_path).unwrap();
+
+        assert_eq!(fs::read(dir.join("app.db")).unwrap(), b"original");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
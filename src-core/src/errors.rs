@@ -116,6 +116,11 @@ pub enum CalculatorError {
     },
     #[error("Lot not found during operation (this should not happen): Lot ID {lot_id}")]
     LotNotFound { lot_id: String },
+    #[error("No lots received from the source leg of paired transfer {pair_id} for activity {activity_id}")]
+    TransferLotsMissing {
+        pair_id: String,
+        activity_id: String,
+    },
     #[error("Unsupported activity type: {0}")]
     UnsupportedActivityType(String),
     #[error("Calculation failed: {0}")]
@@ -1,13 +1,74 @@
 use crate::fx::fx_errors::FxError;
-use crate::fx::fx_model::ExchangeRate;
+use crate::fx::fx_model::{ExchangeRate, FxRateSide};
 use chrono::NaiveDate;
 use rust_decimal::Decimal;
 use std::collections::{HashMap, HashSet};
 
+/// Bid, mid and ask rates of a currency pair on one day
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SideRates {
+    bid: Decimal,
+    mid: Decimal,
+    ask: Decimal,
+}
+
+impl SideRates {
+    fn flat(rate: Decimal) -> Self {
+        Self {
+            bid: rate,
+            mid: rate,
+            ask: rate,
+        }
+    }
+
+    fn of(rate: &ExchangeRate) -> Self {
+        Self {
+            bid: rate.rate_for(FxRateSide::Bid),
+            mid: rate.rate,
+            ask: rate.rate_for(FxRateSide::Ask),
+        }
+    }
+
+    fn get(&self, side: FxRateSide) -> Decimal {
+        match side {
+            FxRateSide::Bid => self.bid,
+            FxRateSide::Mid => self.mid,
+            FxRateSide::Ask => self.ask,
+        }
+    }
+
+    /// Rates of the opposite direction; the inverse bid is one over the ask.
+    /// Must not be called with a zero mid rate.
+    fn inverse(&self) -> Self {
+        let mid = Decimal::ONE / self.mid;
+        let invert = |rate: Decimal| {
+            if rate.is_zero() {
+                mid
+            } else {
+                Decimal::ONE / rate
+            }
+        };
+        Self {
+            bid: invert(self.ask),
+            mid,
+            ask: invert(self.bid),
+        }
+    }
+
+    /// Rates of converting through this pair and then `next`
+    fn then(&self, next: &SideRates) -> Self {
+        Self {
+            bid: self.bid * next.bid,
+            mid: self.mid * next.mid,
+            ask: self.ask * next.ask,
+        }
+    }
+}
+
 /// A calculator for currency conversions, supporting historical rates based on the latest rate per day.
 pub struct CurrencyConverter {
-    // Date -> (From, To) -> Rate
-    historical_rates: HashMap<NaiveDate, HashMap<(String, String), Decimal>>,
+    // Date -> (From, To) -> Rates
+    historical_rates: HashMap<NaiveDate, HashMap<(String, String), SideRates>>,
     sorted_dates: Vec<NaiveDate>, // Keep track of dates in sorted order
 }
 
@@ -70,7 +131,7 @@ impl CurrencyConverter {
         // Now process the latest rates selected for each date
         for (date, chosen_rates_for_date) in latest_rates_by_date {
             // The chosen_rates_for_date map now contains only the latest rate per pair for this date.
-            let mut rate_map: HashMap<(String, String), Decimal> = HashMap::new();
+            let mut rate_map: HashMap<(String, String), SideRates> = HashMap::new();
             let mut currencies: HashSet<String> = HashSet::new();
 
             // Add direct and inverse rates from the chosen rates.
@@ -79,9 +140,9 @@ impl CurrencyConverter {
                 currencies.insert(rate.from_currency.clone());
                 currencies.insert(rate.to_currency.clone());
 
-                let forward_rate = rate.rate;
+                let forward_rate = SideRates::of(&rate);
                 // Check for zero rate before division
-                if forward_rate.is_zero() {
+                if forward_rate.mid.is_zero() {
                     // Decide how to handle zero rate (e.g., log error, skip inverse)
                     log::error!(
                         "Zero exchange rate encountered for {}/{} on {}. Cannot calculate inverse.",
@@ -96,7 +157,7 @@ impl CurrencyConverter {
                     );
                     continue; // Skip inverse calculation for this rate
                 }
-                let inverse_rate = Decimal::ONE / forward_rate;
+                let inverse_rate = forward_rate.inverse();
 
                 rate_map.insert(
                     (rate.from_currency.clone(), rate.to_currency.clone()), // Use original case
@@ -116,7 +177,7 @@ impl CurrencyConverter {
                         // Add identity rate
                         rate_map.insert(
                             (currencies_vec[i].clone(), currencies_vec[j].clone()),
-                            Decimal::ONE,
+                            SideRates::flat(Decimal::ONE),
                         );
                         continue;
                     }
@@ -135,13 +196,13 @@ impl CurrencyConverter {
                                 rate_map.get(&(from.clone(), via.clone())),
                                 rate_map.get(&(via.clone(), to.clone())),
                             ) {
-                                let transitive_rate = rate1 * rate2;
+                                let transitive_rate = rate1.then(rate2);
                                 rate_map.insert((from.clone(), to.clone()), transitive_rate);
                                 // Add inverse transitive rate as well
-                                if !transitive_rate.is_zero() {
+                                if !transitive_rate.mid.is_zero() {
                                     rate_map.insert(
                                         (to.clone(), from.clone()),
-                                        Decimal::ONE / transitive_rate,
+                                        transitive_rate.inverse(),
                                     );
                                 } else {
                                     log::warn!("Zero transitive rate calculated for {}->{} via {} on {}. Cannot store inverse.", from, to, via, date);
@@ -158,7 +219,7 @@ impl CurrencyConverter {
             for currency in &currencies_vec {
                 rate_map
                     .entry((currency.clone(), currency.clone()))
-                    .or_insert(Decimal::ONE);
+                    .or_insert(SideRates::flat(Decimal::ONE));
             }
 
             self.historical_rates.insert(date, rate_map);
@@ -177,6 +238,17 @@ impl CurrencyConverter {
         from_currency: &str,
         to_currency: &str,
         date: NaiveDate,
+    ) -> Result<Decimal, FxError> {
+        self.get_rate_for_side(from_currency, to_currency, date, FxRateSide::Mid)
+    }
+
+    /// Gets the given side of the exchange rate between two currencies on a specific date.
+    pub fn get_rate_for_side(
+        &self,
+        from_currency: &str,
+        to_currency: &str,
+        date: NaiveDate,
+        side: FxRateSide,
     ) -> Result<Decimal, FxError> {
         if from_currency == to_currency {
             return Ok(Decimal::ONE);
@@ -187,7 +259,7 @@ impl CurrencyConverter {
             .and_then(|rate_map| {
                 rate_map.get(&(from_currency.to_string(), to_currency.to_string()))
             })
-            .cloned()
+            .map(|rates| rates.get(side))
             .ok_or_else(|| {
                 FxError::RateNotFound(format!(
                     "No exchange rate found for {}/{} on {}",
@@ -202,6 +274,18 @@ impl CurrencyConverter {
         from_currency: &str,
         to_currency: &str,
         date: NaiveDate,
+    ) -> Result<Decimal, FxError> {
+        self.get_rate_nearest_for_side(from_currency, to_currency, date, FxRateSide::Mid)
+    }
+
+    /// Gets the given side of the exchange rate between two currencies on the nearest
+    /// available date.
+    pub fn get_rate_nearest_for_side(
+        &self,
+        from_currency: &str,
+        to_currency: &str,
+        date: NaiveDate,
+        side: FxRateSide,
     ) -> Result<Decimal, FxError> {
         if from_currency == to_currency {
             return Ok(Decimal::ONE);
@@ -242,7 +326,7 @@ impl CurrencyConverter {
         };
 
         // Use the existing get_rate function with the closest date
-        self.get_rate(from_currency, to_currency, closest_date)
+        self.get_rate_for_side(from_currency, to_currency, closest_date, side)
     }
 
     /// Converts an amount from one currency to another on a specific date.
@@ -261,6 +345,23 @@ impl CurrencyConverter {
         Ok(amount * rate)
     }
 
+    /// Converts an amount from one currency to another on a specific date using the given side.
+    pub fn convert_amount_for_side(
+        &self,
+        amount: Decimal,
+        from_currency: &str,
+        to_currency: &str,
+        date: NaiveDate,
+        side: FxRateSide,
+    ) -> Result<Decimal, FxError> {
+        if from_currency == to_currency {
+            return Ok(amount);
+        }
+
+        let rate = self.get_rate_for_side(from_currency, to_currency, date, side)?;
+        Ok(amount * rate)
+    }
+
     /// Converts an amount from one currency to another on the nearest available date.
     pub fn convert_amount_nearest(
         &self,
@@ -284,6 +385,24 @@ mod tests {
     use crate::market_data::market_data_model::DataSource;
     use chrono::{NaiveDate, TimeZone, Utc};
 
+    fn spread_rate(from: &str, to: &str, bid: Decimal, mid: Decimal, ask: Decimal) -> ExchangeRate {
+        ExchangeRate {
+            id: ExchangeRate::make_fx_symbol(from, to),
+            from_currency: from.to_string(),
+            to_currency: to.to_string(),
+            rate: mid,
+            bid: Some(bid),
+            ask: Some(ask),
+            source: DataSource::VnFx,
+            timestamp: Utc.from_utc_datetime(
+                &NaiveDate::from_ymd_opt(2024, 1, 15)
+                    .unwrap()
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap(),
+            ),
+        }
+    }
+
     fn test_exchange_rates() -> Vec<ExchangeRate> {
         // Helper to create DateTime<Utc> from NaiveDate
         let dt = |y, m, d, h, min, s| {
@@ -302,6 +421,8 @@ mod tests {
                 from_currency: "USD".to_string(),
                 to_currency: "EUR".to_string(),
                 rate: Decimal::new(84, 2), // Earlier rate
+                bid: None,
+                ask: None,
                 source: DataSource::Manual,
                 timestamp: dt(2023, 10, 26, 10, 0, 0),
             },
@@ -310,6 +431,8 @@ mod tests {
                 from_currency: "USD".to_string(),
                 to_currency: "EUR".to_string(),
                 rate: Decimal::new(85, 2), // LATEST rate for this pair on this day
+                bid: None,
+                ask: None,
                 source: DataSource::Manual,
                 timestamp: dt(2023, 10, 26, 15, 0, 0),
             },
@@ -318,6 +441,8 @@ mod tests {
                 from_currency: "EUR".to_string(),
                 to_currency: "GBP".to_string(),
                 rate: Decimal::new(90, 2),
+                bid: None,
+                ask: None,
                 source: DataSource::Manual,
                 timestamp: dt(2023, 10, 26, 12, 0, 0), // Only rate for this pair/day
             },
@@ -327,6 +452,8 @@ mod tests {
                 from_currency: "USD".to_string(),
                 to_currency: "EUR".to_string(),
                 rate: Decimal::new(86, 2),
+                bid: None,
+                ask: None,
                 source: DataSource::Manual,
                 timestamp: dt(2023, 10, 27, 11, 0, 0),
             },
@@ -335,6 +462,8 @@ mod tests {
                 from_currency: "EUR".to_string(),
                 to_currency: "GBP".to_string(),
                 rate: Decimal::new(91, 2),
+                bid: None,
+                ask: None,
                 source: DataSource::Manual,
                 timestamp: dt(2023, 10, 27, 13, 0, 0),
             },
@@ -344,6 +473,8 @@ mod tests {
                 from_currency: "USD".to_string(),
                 to_currency: "EUR".to_string(),
                 rate: Decimal::new(87, 2),
+                bid: None,
+                ask: None,
                 source: DataSource::Manual,
                 timestamp: dt(2023, 10, 28, 9, 0, 0),
             },
@@ -352,6 +483,8 @@ mod tests {
                 from_currency: "EUR".to_string(),
                 to_currency: "GBP".to_string(),
                 rate: Decimal::new(915, 3), // 0.915, earlier
+                bid: None,
+                ask: None,
                 source: DataSource::Manual,
                 timestamp: dt(2023, 10, 28, 10, 0, 0),
             },
@@ -360,6 +493,8 @@ mod tests {
                 from_currency: "EUR".to_string(),
                 to_currency: "GBP".to_string(),
                 rate: Decimal::new(92, 2), // LATEST rate
+                bid: None,
+                ask: None,
                 source: DataSource::Manual,
                 timestamp: dt(2023, 10, 28, 16, 0, 0),
             },
@@ -458,4 +593,84 @@ mod tests {
             .unwrap();
         assert_eq!(converted_amount, Decimal::new(87, 0)); // Should use 2023-10-28
     }
+
+    #[test]
+    fn test_side_rates_direct_and_inverse() {
+        let rates = vec![spread_rate(
+            "USD",
+            "VND",
+            Decimal::from(24200),
+            Decimal::from(24370),
+            Decimal::from(24540),
+        )];
+        let converter = CurrencyConverter::new(rates).unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+
+        let sell_usd = converter
+            .convert_amount_for_side(Decimal::ONE_HUNDRED, "USD", "VND", date, FxRateSide::Bid)
+            .unwrap();
+        assert_eq!(sell_usd, Decimal::from(2420000));
+        assert_eq!(
+            converter.get_rate("USD", "VND", date).unwrap(),
+            Decimal::from(24370)
+        );
+
+        // Buying USD with VND costs the ask
+        let buy_usd = converter
+            .convert_amount_for_side(Decimal::from(2454000), "VND", "USD", date, FxRateSide::Bid)
+            .unwrap();
+        assert_eq!(buy_usd.round_dp(6), Decimal::ONE_HUNDRED);
+        assert_eq!(
+            converter
+                .get_rate_for_side("VND", "USD", date, FxRateSide::Ask)
+                .unwrap(),
+            Decimal::ONE / Decimal::from(24200)
+        );
+    }
+
+    #[test]
+    fn test_side_rates_transitive_and_without_spread() {
+        let mut rates = vec![spread_rate(
+            "USD",
+            "VND",
+            Decimal::from(24000),
+            Decimal::from(24500),
+            Decimal::from(25000),
+        )];
+        let mut eur_usd = spread_rate(
+            "EUR",
+            "USD",
+            Decimal::ZERO,
+            Decimal::new(11, 1),
+            Decimal::ZERO,
+        );
+        eur_usd.bid = None;
+        eur_usd.ask = None;
+        rates.push(eur_usd);
+        let converter = CurrencyConverter::new(rates).unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+
+        // EUR/USD has no spread, so its bid and ask are the mid rate
+        assert_eq!(
+            converter
+                .get_rate_nearest_for_side("EUR", "USD", date, FxRateSide::Ask)
+                .unwrap(),
+            Decimal::new(11, 1)
+        );
+        // The cross rate may be derived through an inverse, so compare at 6 dp
+        assert_eq!(
+            converter
+                .get_rate_for_side("EUR", "VND", date, FxRateSide::Bid)
+                .unwrap()
+                .round_dp(6),
+            Decimal::new(11, 1) * Decimal::from(24000)
+        );
+        assert_eq!(
+            converter
+                .get_rate_for_side("EUR", "VND", date, FxRateSide::Ask)
+                .unwrap()
+                .round_dp(6),
+            Decimal::new(11, 1) * Decimal::from(25000)
+        );
+    }
 }
@@ -1,7 +1,46 @@
+use crate::fx::fx_errors::FxError;
 use crate::market_data::market_data_model::{DataSource, Quote};
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Which side of an exchange rate a conversion uses.
+///
+/// For a conversion from one currency to another, `Bid` is the lower rate a bank
+/// pays when buying the source currency (e.g. liquidating a foreign balance),
+/// `Ask` the higher rate it charges, and `Mid` the midpoint used for valuation.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum FxRateSide {
+    Bid,
+    #[default]
+    Mid,
+    Ask,
+}
+
+impl FxRateSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FxRateSide::Bid => "bid",
+            FxRateSide::Mid => "mid",
+            FxRateSide::Ask => "ask",
+        }
+    }
+}
+
+impl FromStr for FxRateSide {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bid" => Ok(FxRateSide::Bid),
+            "mid" => Ok(FxRateSide::Mid),
+            "ask" => Ok(FxRateSide::Ask),
+            _ => Err(format!("Unknown FX rate side: {}", s)),
+        }
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -9,26 +48,68 @@ pub struct ExchangeRate {
     pub id: String,
     pub from_currency: String,
     pub to_currency: String,
+    /// Mid rate
     #[serde(serialize_with = "serialize_decimal_6")]
     pub rate: Decimal,
+    #[serde(default, serialize_with = "serialize_option_decimal_6")]
+    pub bid: Option<Decimal>,
+    #[serde(default, serialize_with = "serialize_option_decimal_6")]
+    pub ask: Option<Decimal>,
     pub source: DataSource,
     pub timestamp: DateTime<Utc>,
 }
 
 impl ExchangeRate {
+    /// Builds a rate from an FX quote. Sources that quote a spread (VND bank rates
+    /// and manual rates) store the bid as low and the ask as high; for market
+    /// sources low and high are the day's range and are ignored.
     pub fn from_quote(quote: &Quote) -> Self {
         let (from_currency, to_currency) = Self::parse_fx_symbol(&quote.symbol);
+        let quotes_spread = matches!(quote.data_source, DataSource::VnFx | DataSource::Manual)
+            && quote.low > Decimal::ZERO
+            && quote.low <= quote.close
+            && quote.close <= quote.high;
+        let (bid, ask) = if quotes_spread {
+            (Some(quote.low), Some(quote.high))
+        } else {
+            (None, None)
+        };
 
         ExchangeRate {
             id: Self::make_fx_symbol(&from_currency, &to_currency),
             from_currency,
             to_currency,
             rate: quote.close,
+            bid,
+            ask,
             source: quote.data_source.clone(),
             timestamp: quote.timestamp,
         }
     }
 
+    /// Rate of the given side; the mid rate when no spread is known
+    pub fn rate_for(&self, side: FxRateSide) -> Decimal {
+        match side {
+            FxRateSide::Bid => self.bid.unwrap_or(self.rate),
+            FxRateSide::Mid => self.rate,
+            FxRateSide::Ask => self.ask.unwrap_or(self.rate),
+        }
+    }
+
+    /// Rate of the opposite direction: the inverse bid is one over the ask
+    pub fn inverse(&self) -> Self {
+        ExchangeRate {
+            id: Self::make_fx_symbol(&self.to_currency, &self.from_currency),
+            from_currency: self.to_currency.clone(),
+            to_currency: self.from_currency.clone(),
+            rate: Decimal::ONE / self.rate,
+            bid: self.ask.map(|ask| Decimal::ONE / ask),
+            ask: self.bid.map(|bid| Decimal::ONE / bid),
+            source: self.source.clone(),
+            timestamp: self.timestamp,
+        }
+    }
+
     pub fn to_quote(&self) -> Quote {
         let formatted_date = self.timestamp.format("%Y%m%d").to_string();
         let symbol = Self::make_fx_symbol(&self.from_currency, &self.to_currency);
@@ -37,8 +118,8 @@ impl ExchangeRate {
             symbol,
             timestamp: self.timestamp,
             open: self.rate.clone(),
-            high: self.rate.clone(),
-            low: self.rate.clone(),
+            high: self.ask.unwrap_or(self.rate),
+            low: self.bid.unwrap_or(self.rate),
             close: self.rate.clone(),
             adjclose: self.rate.clone(),
             volume: Decimal::ZERO,
@@ -70,6 +151,19 @@ where
     serializer.serialize_str(&rounded.to_string())
 }
 
+fn serialize_option_decimal_6<S>(
+    decimal: &Option<Decimal>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match decimal {
+        Some(value) => serialize_decimal_6(value, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct NewExchangeRate {
@@ -77,10 +171,35 @@ pub struct NewExchangeRate {
     pub to_currency: String,
     #[serde(serialize_with = "serialize_decimal_6")]
     pub rate: Decimal,
+    #[serde(default, serialize_with = "serialize_option_decimal_6")]
+    pub bid: Option<Decimal>,
+    #[serde(default, serialize_with = "serialize_option_decimal_6")]
+    pub ask: Option<Decimal>,
     pub source: DataSource,
 }
 
 impl NewExchangeRate {
+    /// Checks that a quoted spread brackets the mid rate
+    pub fn validate(&self) -> Result<(), FxError> {
+        if self.rate <= Decimal::ZERO {
+            return Err(FxError::InvalidRate(format!(
+                "{}/{} rate must be positive",
+                self.from_currency, self.to_currency
+            )));
+        }
+        if self
+            .bid
+            .is_some_and(|bid| bid <= Decimal::ZERO || bid > self.rate)
+            || self.ask.is_some_and(|ask| ask < self.rate)
+        {
+            return Err(FxError::InvalidRate(format!(
+                "{}/{} bid must not exceed the rate and ask must not be below it",
+                self.from_currency, self.to_currency
+            )));
+        }
+        Ok(())
+    }
+
     pub fn to_quote(&self) -> Quote {
         let now = Utc::now();
         let formatted_date = now.format("%Y%m%d").to_string();
@@ -90,8 +209,8 @@ impl NewExchangeRate {
             symbol,
             timestamp: now,
             open: self.rate.clone(),
-            high: self.rate.clone(),
-            low: self.rate.clone(),
+            high: self.ask.unwrap_or(self.rate),
+            low: self.bid.unwrap_or(self.rate),
             close: self.rate.clone(),
             adjclose: self.rate.clone(),
             volume: Decimal::ZERO,
@@ -133,6 +133,8 @@ impl FxRepository {
                     from_currency,
                     to_currency,
                     rate: Decimal::ZERO,
+                    bid: None,
+                    ask: None,
                     source: DataSource::from(asset.data_source.as_str()),
                     timestamp,
                 });
@@ -256,6 +258,8 @@ impl FxRepository {
                     .on_conflict((quotes::symbol, quotes::timestamp, quotes::data_source))
                     .do_update()
                     .set((
+                        quotes::high.eq(quote_db.high.clone()),
+                        quotes::low.eq(quote_db.low.clone()),
                         quotes::close.eq(quote_db.close.clone()),
                         quotes::data_source.eq(&quote_db.data_source),
                     ))
@@ -318,6 +322,8 @@ impl FxRepository {
                     .filter(quotes::symbol.eq(&quote_db.symbol))
                     .filter(quotes::timestamp.eq(&quote_db.timestamp))
                     .set((
+                        quotes::high.eq(quote_db.high.clone()),
+                        quotes::low.eq(quote_db.low.clone()),
                         quotes::close.eq(quote_db.close.clone()),
                         quotes::data_source.eq(&quote_db.data_source),
                     ))
@@ -1,6 +1,6 @@
 use super::currency_converter::CurrencyConverter;
 use super::fx_errors::FxError;
-use super::fx_model::{ExchangeRate, NewExchangeRate};
+use super::fx_model::{ExchangeRate, FxRateSide, NewExchangeRate};
 use super::fx_traits::{FxRepositoryTrait, FxServiceTrait};
 use crate::errors::Result;
 use crate::fx::currency::{denormalization_multiplier, normalize_currency_code};
@@ -75,18 +75,7 @@ impl FxService {
                     .repository
                     .get_latest_exchange_rate_by_symbol(&inverse_symbol)?
                 {
-                    Some(inverse_rate) => {
-                        let direct_rate = ExchangeRate {
-                            id: ExchangeRate::make_fx_symbol(from, to),
-                            from_currency: from.to_string(),
-                            to_currency: to.to_string(),
-                            rate: Decimal::ONE / inverse_rate.rate,
-                            source: inverse_rate.source,
-                            timestamp: inverse_rate.timestamp,
-                        };
-
-                        Ok(direct_rate)
-                    }
+                    Some(inverse_rate) => Ok(inverse_rate.inverse()),
                     None => Err(FxError::RateNotFound(format!(
                         "Exchange rate not found for {}/{}",
                         from, to
@@ -144,6 +133,7 @@ impl FxService {
         from: &str,
         to: &str,
         date: NaiveDate,
+        side: FxRateSide,
     ) -> Result<Decimal> {
         if from == to {
             return Ok(Decimal::ONE);
@@ -151,7 +141,7 @@ impl FxService {
 
         if let Ok(converter_lock) = self.converter.read() {
             if let Some(converter) = &*converter_lock {
-                if let Ok(rate) = converter.get_rate_nearest(from, to, date) {
+                if let Ok(rate) = converter.get_rate_nearest_for_side(from, to, date, side) {
                     return Ok(rate);
                 }
             }
@@ -168,7 +158,7 @@ impl FxService {
             fallback_date
         );
 
-        Ok(latest_rate.rate)
+        Ok(latest_rate.rate_for(side))
     }
 }
 
@@ -181,6 +171,8 @@ impl FxServiceTrait for FxService {
     }
 
     async fn add_exchange_rate(&self, new_rate: NewExchangeRate) -> Result<ExchangeRate> {
+        new_rate.validate()?;
+
         // First register the currency pair
         self.register_currency_pair_manual(&new_rate.from_currency, &new_rate.to_currency)
             .await?;
@@ -190,6 +182,8 @@ impl FxServiceTrait for FxService {
             from_currency: new_rate.from_currency,
             to_currency: new_rate.to_currency,
             rate: new_rate.rate,
+            bid: new_rate.bid,
+            ask: new_rate.ask,
             source: new_rate.source,
             timestamp: Utc::now(),
         };
@@ -230,6 +224,8 @@ impl FxServiceTrait for FxService {
             from_currency: from.to_string(),
             to_currency: to.to_string(),
             rate,
+            bid: None,
+            ask: None,
             source: Self::DEFAULT_DATA_SOURCE,
         };
         self.add_exchange_rate(new_rate).await
@@ -264,6 +260,16 @@ impl FxServiceTrait for FxService {
         from_currency: &str,
         to_currency: &str,
         date: NaiveDate,
+    ) -> Result<Decimal> {
+        self.get_exchange_rate_for_date_side(from_currency, to_currency, date, FxRateSide::Mid)
+    }
+
+    fn get_exchange_rate_for_date_side(
+        &self,
+        from_currency: &str,
+        to_currency: &str,
+        date: NaiveDate,
+        side: FxRateSide,
     ) -> Result<Decimal> {
         // Check for valid currency codes
         if from_currency.len() != 3 || !from_currency.chars().all(|c| c.is_alphabetic()) {
@@ -290,7 +296,7 @@ impl FxServiceTrait for FxService {
         }
 
         let base_rate =
-            self.get_rate_for_date_between_normalized(normalized_from, normalized_to, date)?;
+            self.get_rate_for_date_between_normalized(normalized_from, normalized_to, date, side)?;
 
         Ok(source_multiplier * base_rate * target_multiplier)
     }
@@ -324,6 +330,22 @@ impl FxServiceTrait for FxService {
         Ok(amount * rate)
     }
 
+    fn convert_currency_for_date_side(
+        &self,
+        amount: Decimal,
+        from_currency: &str,
+        to_currency: &str,
+        date: NaiveDate,
+        side: FxRateSide,
+    ) -> Result<Decimal> {
+        if from_currency.eq(to_currency) {
+            return Ok(amount);
+        }
+
+        let rate = self.get_exchange_rate_for_date_side(from_currency, to_currency, date, side)?;
+        Ok(amount * rate)
+    }
+
     fn get_latest_exchange_rates(&self) -> Result<Vec<ExchangeRate>> {
         self.repository.get_latest_exchange_rates()
     }
@@ -355,7 +377,7 @@ impl FxServiceTrait for FxService {
         // Create FX asset and add default rate if no rate exists
         if existing_rate.is_none() {
             self.repository
-                .create_fx_asset(&normalized_from, &normalized_to, DataSource::Yahoo.as_str())
+                .create_fx_asset(normalized_from, normalized_to, DataSource::Yahoo.as_str())
                 .await?;
         }
 
@@ -0,0 +1,179 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Rates applied to the two legs of a cross-currency transfer.
+///
+/// The source leg pays for the conversion at the ask, the destination leg
+/// receives it at the bid. Account currencies are valued in base at mid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransferFxRates {
+    /// Transfer currency to source account currency, mid
+    pub out_mid: Decimal,
+    /// Transfer currency to source account currency, ask
+    pub out_ask: Decimal,
+    /// Transfer currency to destination account currency, mid
+    pub in_mid: Decimal,
+    /// Transfer currency to destination account currency, bid
+    pub in_bid: Decimal,
+    /// Source account currency to base currency, mid
+    pub out_to_base: Decimal,
+    /// Destination account currency to base currency, mid
+    pub in_to_base: Decimal,
+}
+
+/// FX spread paid on a paired transfer between accounts in different currencies
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferFxCost {
+    pub pair_id: String,
+    pub date: NaiveDate,
+    /// Currency the transfer is recorded in
+    pub currency: String,
+    /// Amount in the transfer currency
+    pub amount: Decimal,
+    pub from_account_id: String,
+    pub from_currency: String,
+    pub to_account_id: String,
+    pub to_currency: String,
+    /// Debited from the source account, in its currency
+    pub amount_sent: Decimal,
+    /// Credited to the destination account, in its currency
+    pub amount_received: Decimal,
+    /// What the destination would have received at the mid rate
+    pub amount_received_at_mid: Decimal,
+    pub base_currency: String,
+    /// Spread cost of both legs, in base currency
+    pub spread_cost: Decimal,
+    /// Spread cost as a percentage of the transfer value at mid
+    pub spread_pct: Decimal,
+}
+
+impl TransferFxCost {
+    /// Builds the cost of a transfer of `amount` from its legs' rates
+    #[allow(clippy::too_many_arguments)]
+    pub fn calculate(
+        pair_id: &str,
+        date: NaiveDate,
+        currency: &str,
+        amount: Decimal,
+        from: (&str, &str),
+        to: (&str, &str),
+        base_currency: &str,
+        rates: &TransferFxRates,
+    ) -> Self {
+        let amount_sent = amount * rates.out_ask;
+        let amount_received = amount * rates.in_bid;
+        let amount_received_at_mid = amount * rates.in_mid;
+
+        let out_cost = (amount_sent - amount * rates.out_mid) * rates.out_to_base;
+        let in_cost = (amount_received_at_mid - amount_received) * rates.in_to_base;
+        let spread_cost = out_cost + in_cost;
+
+        let value_at_mid = amount_received_at_mid * rates.in_to_base;
+        let spread_pct = if value_at_mid.is_zero() {
+            Decimal::ZERO
+        } else {
+            spread_cost / value_at_mid * Decimal::ONE_HUNDRED
+        };
+
+        Self {
+            pair_id: pair_id.to_string(),
+            date,
+            currency: currency.to_string(),
+            amount,
+            from_account_id: from.0.to_string(),
+            from_currency: from.1.to_string(),
+            to_account_id: to.0.to_string(),
+            to_currency: to.1.to_string(),
+            amount_sent,
+            amount_received,
+            amount_received_at_mid,
+            base_currency: base_currency.to_string(),
+            spread_cost,
+            spread_pct,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()
+    }
+
+    #[test]
+    fn test_cost_of_usd_to_vnd_transfer() {
+        // 1,000 USD from a USD account into a VND account, base VND
+        let rates = TransferFxRates {
+            out_mid: Decimal::ONE,
+            out_ask: Decimal::ONE,
+            in_mid: dec!(24370),
+            in_bid: dec!(24200),
+            out_to_base: dec!(24370),
+            in_to_base: Decimal::ONE,
+        };
+        let cost = TransferFxCost::calculate(
+            "pair-1",
+            date(),
+            "USD",
+            dec!(1000),
+            ("usd-acc", "USD"),
+            ("vnd-acc", "VND"),
+            "VND",
+            &rates,
+        );
+
+        assert_eq!(cost.amount_sent, dec!(1000));
+        assert_eq!(cost.amount_received, dec!(24200000));
+        assert_eq!(cost.amount_received_at_mid, dec!(24370000));
+        assert_eq!(cost.spread_cost, dec!(170000));
+        assert_eq!(cost.spread_pct.round_dp(4), dec!(0.6976));
+    }
+
+    #[test]
+    fn test_cost_adds_both_legs_and_is_zero_without_spread() {
+        // 100 EUR out of a USD account into a VND account, base USD
+        let rates = TransferFxRates {
+            out_mid: dec!(1.10),
+            out_ask: dec!(1.12),
+            in_mid: dec!(26000),
+            in_bid: dec!(25900),
+            out_to_base: Decimal::ONE,
+            in_to_base: dec!(0.00004),
+        };
+        let cost = TransferFxCost::calculate(
+            "pair-2",
+            date(),
+            "EUR",
+            dec!(100),
+            ("usd-acc", "USD"),
+            ("vnd-acc", "VND"),
+            "USD",
+            &rates,
+        );
+        // 2 USD on the way out, 10,000 VND (0.4 USD) on the way in
+        assert_eq!(cost.spread_cost, dec!(2.4));
+
+        let flat = TransferFxRates {
+            out_ask: rates.out_mid,
+            in_bid: rates.in_mid,
+            ..rates
+        };
+        let cost = TransferFxCost::calculate(
+            "pair-2",
+            date(),
+            "EUR",
+            dec!(100),
+            ("usd-acc", "USD"),
+            ("vnd-acc", "VND"),
+            "USD",
+            &flat,
+        );
+        assert!(cost.spread_cost.is_zero());
+        assert!(cost.spread_pct.is_zero());
+    }
+}
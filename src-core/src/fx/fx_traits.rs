@@ -1,4 +1,4 @@
-use super::fx_model::{ExchangeRate, NewExchangeRate};
+use super::fx_model::{ExchangeRate, FxRateSide, NewExchangeRate};
 use crate::errors::Result;
 use crate::market_data::market_data_model::Quote;
 use async_trait::async_trait;
@@ -54,6 +54,17 @@ pub trait FxServiceTrait: Send + Sync {
         to_currency: &str,
         date: NaiveDate,
     ) -> Result<Decimal>;
+    /// Rate of the given side for a date. Implementations without spreads use the mid rate.
+    fn get_exchange_rate_for_date_side(
+        &self,
+        from_currency: &str,
+        to_currency: &str,
+        date: NaiveDate,
+        side: FxRateSide,
+    ) -> Result<Decimal> {
+        let _ = side;
+        self.get_exchange_rate_for_date(from_currency, to_currency, date)
+    }
     fn convert_currency(
         &self,
         amount: Decimal,
@@ -67,6 +78,19 @@ pub trait FxServiceTrait: Send + Sync {
         to_currency: &str,
         date: NaiveDate,
     ) -> Result<Decimal>;
+    /// Converts at the given side of the rate for a date. Implementations without
+    /// spreads use the mid rate.
+    fn convert_currency_for_date_side(
+        &self,
+        amount: Decimal,
+        from_currency: &str,
+        to_currency: &str,
+        date: NaiveDate,
+        side: FxRateSide,
+    ) -> Result<Decimal> {
+        let _ = side;
+        self.convert_currency_for_date(amount, from_currency, to_currency, date)
+    }
     fn get_latest_exchange_rates(&self) -> Result<Vec<ExchangeRate>>;
     async fn add_exchange_rate(&self, new_rate: NewExchangeRate) -> Result<ExchangeRate>;
     async fn update_exchange_rate(
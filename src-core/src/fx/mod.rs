@@ -4,6 +4,7 @@ pub mod fx_errors;
 pub mod fx_model;
 pub mod fx_repository;
 pub mod fx_service;
+pub mod fx_spread;
 pub mod fx_traits;
 
 pub use currency::{
@@ -11,7 +12,8 @@ pub use currency::{
 };
 pub use currency_converter::CurrencyConverter;
 pub use fx_errors::FxError;
-pub use fx_model::{ExchangeRate, NewExchangeRate};
+pub use fx_model::{ExchangeRate, FxRateSide, NewExchangeRate};
 pub use fx_repository::FxRepository;
 pub use fx_service::FxService;
+pub use fx_spread::{TransferFxCost, TransferFxRates};
 pub use fx_traits::{FxRepositoryTrait, FxServiceTrait};
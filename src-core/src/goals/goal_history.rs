@@ -0,0 +1,419 @@
+//! Goal progress history from stored account valuations.
+//!
+//! An allocation's value on a date is its initial contribution plus its share of the
+//! account's growth since the allocation started, the same way the goals page charts
+//! it. When the share changes, each `allocation_versions` row only takes the growth
+//! of its own period, so earlier growth keeps the percentage it was earned under.
+
+use chrono::{Datelike, Duration, NaiveDate};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::goals::goal_progress_model::{
+    AllocationDetail, GoalHistoryInterval, GoalProgressHistory, GoalProgressSnapshot,
+};
+use crate::goals::goal_simulation::{add_months, months_between, parse_goal_date};
+use crate::goals::goal_solver::{future_value, monthly_rate};
+use crate::goals::goals_model::{AllocationVersion, Goal, GoalsAllocation};
+
+/// Values within this amount of the target curve count as on track
+const TARGET_TOLERANCE: f64 = 0.01;
+
+/// Account value in the base currency by valuation date
+pub type AccountValues = BTreeMap<NaiveDate, f64>;
+
+/// Period during which an allocation holds a fixed percentage of its account
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllocationSegment {
+    pub start: NaiveDate,
+    /// Last day of the period; open-ended when `None`
+    pub end: Option<NaiveDate>,
+    /// Share of the account, 0-100
+    pub percentage: f64,
+    pub initial_contribution: f64,
+}
+
+/// An allocation with its percentage history
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllocationTimeline {
+    pub account_id: String,
+    /// Ordered by start date
+    pub segments: Vec<AllocationSegment>,
+}
+
+impl AllocationTimeline {
+    /// Builds the timeline from the allocation's versions, ordered by start date. An
+    /// allocation without versions is a single segment over its own dates, starting
+    /// at `goal_start` when it has none.
+    pub fn new(
+        allocation: &GoalsAllocation,
+        versions: &[AllocationVersion],
+        goal_start: NaiveDate,
+    ) -> Self {
+        let segments = if versions.is_empty() {
+            let start = allocation
+                .allocation_date
+                .as_deref()
+                .or(allocation.start_date.as_deref())
+                .and_then(parse_goal_date)
+                .unwrap_or(goal_start);
+            vec![AllocationSegment {
+                start,
+                end: allocation.end_date.as_deref().and_then(parse_goal_date),
+                percentage: allocation.allocation_percentage,
+                initial_contribution: allocation.init_amount,
+            }]
+        } else {
+            let starts: Vec<NaiveDate> = versions
+                .iter()
+                .map(|v| parse_goal_date(&v.version_start_date).unwrap_or(goal_start))
+                .collect();
+            versions
+                .iter()
+                .enumerate()
+                .map(|(i, version)| AllocationSegment {
+                    start: starts[i],
+                    // An open version ends where the next one starts
+                    end: version
+                        .version_end_date
+                        .as_deref()
+                        .and_then(parse_goal_date)
+                        .or_else(|| starts.get(i + 1).copied()),
+                    percentage: version.allocation_percentage,
+                    initial_contribution: version.allocation_amount,
+                })
+                .collect()
+        };
+        Self {
+            account_id: allocation.account_id.clone(),
+            segments,
+        }
+    }
+
+    /// Allocation detail on `date`, or `None` before the allocation starts
+    fn detail_on(
+        &self,
+        values: Option<&AccountValues>,
+        date: NaiveDate,
+    ) -> Option<(f64, AllocationDetail)> {
+        let started: Vec<&AllocationSegment> =
+            self.segments.iter().filter(|s| s.start <= date).collect();
+        let active = started.last()?;
+        let value_on = |day: NaiveDate| values.map_or(0.0, |v| value_on(v, day));
+
+        let allocated_growth: f64 = started
+            .iter()
+            .map(|segment| {
+                let until = segment
+                    .end
+                    .map_or(date, |end| end.min(date))
+                    .max(segment.start);
+                (value_on(until) - value_on(segment.start)) * segment.percentage / 100.0
+            })
+            .sum();
+        let baseline = value_on(started[0].start);
+        let current = value_on(date);
+
+        let detail = AllocationDetail {
+            account_id: self.account_id.clone(),
+            percent_allocation: active.percentage.round() as i32,
+            account_value_at_goal_start: baseline,
+            account_current_value: current,
+            account_growth: current - baseline,
+            allocated_growth,
+        };
+        Some((active.initial_contribution, detail))
+    }
+}
+
+/// Latest value on or before `date`; zero before the first valuation, so an account
+/// opened after the allocation started counts all of its value as growth
+pub fn value_on(values: &AccountValues, date: NaiveDate) -> f64 {
+    values
+        .range(..=date)
+        .next_back()
+        .map_or(0.0, |(_, value)| *value)
+}
+
+/// Snapshot dates from `start` to `end`, both included
+pub fn history_dates(
+    start: NaiveDate,
+    end: NaiveDate,
+    interval: GoalHistoryInterval,
+) -> Vec<NaiveDate> {
+    let mut dates = Vec::new();
+    if end < start {
+        return dates;
+    }
+    match interval {
+        GoalHistoryInterval::Daily => {
+            let mut date = start;
+            while date <= end {
+                dates.push(date);
+                date += Duration::days(1);
+            }
+        }
+        GoalHistoryInterval::Monthly => {
+            dates.push(start);
+            let mut month_start = start.with_day(1).unwrap_or(start);
+            loop {
+                let month_end = add_months(month_start, 1) - Duration::days(1);
+                if month_end >= end {
+                    break;
+                }
+                if month_end > start {
+                    dates.push(month_end);
+                }
+                month_start = add_months(month_start, 1);
+            }
+            if end > start {
+                dates.push(end);
+            }
+        }
+    }
+    dates
+}
+
+/// Value on `date` of `start_value` growing at `annual_return` (a percentage) from
+/// `start`, with `monthly_investment` added at the end of every whole month. Growth
+/// within a month accrues daily, so the curve only steps at contributions.
+pub fn target_value(
+    start_value: f64,
+    monthly_investment: f64,
+    annual_return: f64,
+    start: NaiveDate,
+    date: NaiveDate,
+) -> f64 {
+    if date <= start {
+        return start_value;
+    }
+    let rate = monthly_rate(annual_return / 100.0);
+    let months = months_between(start, date);
+    let at_anniversary = future_value(start_value, monthly_investment, rate, months);
+
+    let anniversary = add_months(start, months);
+    let next = add_months(start, months + 1);
+    let days_in_month = (next - anniversary).num_days().max(1) as f64;
+    let fraction = (date - anniversary).num_days() as f64 / days_in_month;
+    at_anniversary * (1.0 + rate).powf(fraction)
+}
+
+/// Builds the goal's allocated value at each date against its target curve. The
+/// curve starts from the allocated value on `goal_start`.
+pub fn calculate_goal_history(
+    goal: &Goal,
+    goal_start: NaiveDate,
+    dates: &[NaiveDate],
+    interval: GoalHistoryInterval,
+    timelines: &[AllocationTimeline],
+    account_values: &HashMap<String, AccountValues>,
+) -> GoalProgressHistory {
+    let progress_on = |date: NaiveDate| {
+        let mut init_value = 0.0;
+        let mut growth = 0.0;
+        let mut details = Vec::new();
+        for timeline in timelines {
+            let values = account_values.get(&timeline.account_id);
+            if let Some((initial, detail)) = timeline.detail_on(values, date) {
+                init_value += initial;
+                growth += detail.allocated_growth;
+                details.push(detail);
+            }
+        }
+        (init_value, growth, details)
+    };
+
+    let (start_init, start_growth, _) = progress_on(goal_start);
+    let start_value = start_init + start_growth;
+    let annual_return = goal.target_return_rate.unwrap_or(0.0);
+    let monthly_investment = goal.monthly_investment.unwrap_or(0.0);
+
+    let mut snapshots = Vec::with_capacity(dates.len());
+    let mut behind_since: Option<NaiveDate> = None;
+    for date in dates {
+        let (init_value, growth, allocation_details) = progress_on(*date);
+        let current_value = init_value + growth;
+        let target = target_value(
+            start_value,
+            monthly_investment,
+            annual_return,
+            goal_start,
+            *date,
+        );
+
+        if current_value < target - TARGET_TOLERANCE {
+            behind_since.get_or_insert(*date);
+        } else {
+            behind_since = None;
+        }
+
+        snapshots.push(GoalProgressSnapshot {
+            goal_id: goal.id.clone(),
+            goal_title: goal.title.clone(),
+            query_date: date.to_string(),
+            init_value,
+            current_value,
+            growth,
+            allocation_details,
+            target_value: Some(target),
+        });
+    }
+
+    GoalProgressHistory {
+        goal_id: goal.id.clone(),
+        goal_title: goal.title.clone(),
+        start_date: goal_start.to_string(),
+        due_date: goal
+            .due_date
+            .as_deref()
+            .and_then(parse_goal_date)
+            .map(|d| d.to_string())
+            .unwrap_or_default(),
+        target_amount: goal.target_amount,
+        interval,
+        behind_since: behind_since.map(|d| d.to_string()),
+        snapshots,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{date, planned_goal};
+
+    fn allocation(percentage: f64, init_amount: f64) -> GoalsAllocation {
+        GoalsAllocation {
+            id: "a1".to_string(),
+            goal_id: "g1".to_string(),
+            account_id: "acc1".to_string(),
+            init_amount,
+            allocation_percentage: percentage,
+            allocation_date: None,
+            percent_allocation: percentage as i32,
+            start_date: Some("2025-01-01".to_string()),
+            end_date: Some("2030-01-01".to_string()),
+            allocation_amount: init_amount,
+        }
+    }
+
+    fn version(percentage: f64, start: &str, end: Option<&str>) -> AllocationVersion {
+        AllocationVersion {
+            id: format!("v-{}", start),
+            allocation_id: "a1".to_string(),
+            allocation_percentage: percentage,
+            allocation_amount: 10.0,
+            version_start_date: start.to_string(),
+            version_end_date: end.map(|e| e.to_string()),
+            created_at: start.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_history_dates() {
+        let monthly = history_dates(
+            date(2025, 1, 15),
+            date(2025, 4, 10),
+            GoalHistoryInterval::Monthly,
+        );
+        assert_eq!(
+            monthly,
+            vec![
+                date(2025, 1, 15),
+                date(2025, 1, 31),
+                date(2025, 2, 28),
+                date(2025, 3, 31),
+                date(2025, 4, 10)
+            ]
+        );
+        let daily = history_dates(
+            date(2025, 2, 27),
+            date(2025, 3, 2),
+            GoalHistoryInterval::Daily,
+        );
+        assert_eq!(daily.len(), 4);
+        assert!(history_dates(
+            date(2025, 2, 1),
+            date(2025, 1, 1),
+            GoalHistoryInterval::Daily
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_target_value_matches_solver_on_anniversaries() {
+        let start = date(2025, 1, 1);
+        let at_year = target_value(100.0, 10.0, 12.0, start, date(2026, 1, 1));
+        let expected = future_value(100.0, 10.0, monthly_rate(0.12), 12);
+        assert!((at_year - expected).abs() < 1e-9);
+
+        let mid = target_value(100.0, 0.0, 12.0, start, date(2025, 1, 16));
+        assert!(mid > 100.0 && mid < 100.0 * (1.0 + monthly_rate(0.12)));
+        assert_eq!(target_value(100.0, 10.0, 12.0, start, start), 100.0);
+    }
+
+    #[test]
+    fn test_versions_split_growth_by_percentage() {
+        let values: AccountValues = [
+            (date(2025, 1, 1), 1_000.0),
+            (date(2025, 2, 1), 1_200.0),
+            (date(2025, 3, 1), 1_500.0),
+        ]
+        .into_iter()
+        .collect();
+        let timeline = AllocationTimeline::new(
+            &allocation(50.0, 10.0),
+            &[
+                version(50.0, "2025-01-01", None),
+                version(100.0, "2025-02-01", None),
+            ],
+            date(2025, 1, 1),
+        );
+        assert_eq!(timeline.segments[0].end, Some(date(2025, 2, 1)));
+
+        let history = calculate_goal_history(
+            &planned_goal(0.0, 0.0),
+            date(2025, 1, 1),
+            &[date(2025, 1, 1), date(2025, 2, 1), date(2025, 3, 1)],
+            GoalHistoryInterval::Monthly,
+            &[timeline],
+            &HashMap::from([("acc1".to_string(), values)]),
+        );
+        let values: Vec<f64> = history.snapshots.iter().map(|s| s.current_value).collect();
+        // 10 + 50% of 200, then + 100% of 300
+        assert_eq!(values, vec![10.0, 110.0, 410.0]);
+        assert_eq!(
+            history.snapshots[2].allocation_details[0].percent_allocation,
+            100
+        );
+        assert_eq!(history.behind_since, None);
+    }
+
+    #[test]
+    fn test_behind_since_tracks_latest_shortfall() {
+        let values: AccountValues = [
+            (date(2025, 1, 1), 1_000.0),
+            (date(2025, 2, 1), 1_200.0),
+            (date(2025, 3, 1), 1_100.0),
+            (date(2025, 4, 1), 1_050.0),
+        ]
+        .into_iter()
+        .collect();
+        let timeline = AllocationTimeline::new(&allocation(100.0, 0.0), &[], date(2025, 1, 1));
+        let dates = history_dates(
+            date(2025, 1, 1),
+            date(2025, 4, 1),
+            GoalHistoryInterval::Daily,
+        );
+        let history = calculate_goal_history(
+            &planned_goal(0.0, 100.0),
+            date(2025, 1, 1),
+            &dates,
+            GoalHistoryInterval::Daily,
+            &[timeline],
+            &HashMap::from([("acc1".to_string(), values)]),
+        );
+        // Growth of 200 beats the 100 target in February, 100 falls short of 200 in March
+        assert_eq!(history.behind_since.as_deref(), Some("2025-03-01"));
+        assert_eq!(history.snapshots.last().unwrap().target_value, Some(300.0));
+        assert_eq!(history.due_date, "2030-01-01");
+    }
+}
@@ -0,0 +1,115 @@
+use chrono::{Local, NaiveDate};
+use log::debug;
+use num_traits::ToPrimitive;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::errors::{Error, Result, ValidationError};
+use crate::goals::goal_history::{
+    calculate_goal_history, history_dates, AccountValues, AllocationTimeline,
+};
+use crate::goals::goal_progress_model::{GoalHistoryInterval, GoalProgressHistory};
+use crate::goals::goal_simulation::parse_goal_date;
+use crate::goals::goals_model::Goal;
+use crate::goals::goals_traits::{GoalHistoryServiceTrait, GoalRepositoryTrait};
+use crate::portfolio::valuation::ValuationServiceTrait;
+
+pub struct GoalHistoryService {
+    goal_repo: Arc<dyn GoalRepositoryTrait>,
+    valuation_service: Arc<dyn ValuationServiceTrait>,
+}
+
+impl GoalHistoryService {
+    pub fn new(
+        goal_repo: Arc<dyn GoalRepositoryTrait>,
+        valuation_service: Arc<dyn ValuationServiceTrait>,
+    ) -> Self {
+        Self {
+            goal_repo,
+            valuation_service,
+        }
+    }
+
+    fn load_goal(&self, goal_id: &str) -> Result<Goal> {
+        self.goal_repo
+            .load_goals()?
+            .into_iter()
+            .find(|g| g.id == goal_id)
+            .ok_or_else(|| {
+                Error::Validation(ValidationError::InvalidInput(format!(
+                    "Goal {} not found",
+                    goal_id
+                )))
+            })
+    }
+
+    /// Daily values of an account in the base currency up to `end`
+    fn account_values(&self, account_id: &str, end: NaiveDate) -> Result<AccountValues> {
+        Ok(self
+            .valuation_service
+            .get_historical_valuations(account_id, None, Some(end))?
+            .into_iter()
+            .map(|v| {
+                let base_value = (v.total_value * v.fx_rate_to_base).to_f64().unwrap_or(0.0);
+                (v.valuation_date, base_value)
+            })
+            .collect())
+    }
+}
+
+impl GoalHistoryServiceTrait for GoalHistoryService {
+    fn get_goal_progress_history(
+        &self,
+        goal_id: &str,
+        interval: GoalHistoryInterval,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<GoalProgressHistory> {
+        let goal = self.load_goal(goal_id)?;
+        let goal_start = goal
+            .start_date
+            .as_deref()
+            .and_then(parse_goal_date)
+            .ok_or_else(|| {
+                Error::Validation(ValidationError::InvalidInput(
+                    "Goal must have a start_date".to_string(),
+                ))
+            })?;
+        let start = start_date.map_or(goal_start, |s| s.max(goal_start));
+        let end = end_date.unwrap_or_else(|| Local::now().date_naive());
+        if end < start {
+            return Err(Error::Validation(ValidationError::InvalidInput(format!(
+                "End date {} is before start date {}",
+                end, start
+            ))));
+        }
+
+        let mut timelines = Vec::new();
+        let mut account_values: HashMap<String, AccountValues> = HashMap::new();
+        for allocation in self.goal_repo.get_allocations_for_goal(&goal.id)? {
+            let versions = self.goal_repo.get_allocation_versions(&allocation.id)?;
+            if !account_values.contains_key(&allocation.account_id) {
+                let values = self.account_values(&allocation.account_id, end)?;
+                account_values.insert(allocation.account_id.clone(), values);
+            }
+            timelines.push(AllocationTimeline::new(&allocation, &versions, goal_start));
+        }
+
+        let dates = history_dates(start, end, interval);
+        debug!(
+            "Building {:?} history of goal {} over {} dates from {} allocation(s)",
+            interval,
+            goal.id,
+            dates.len(),
+            timelines.len()
+        );
+        Ok(calculate_goal_history(
+            &goal,
+            goal_start,
+            &dates,
+            interval,
+            &timelines,
+            &account_values,
+        ))
+    }
+}
@@ -7,7 +7,8 @@ pub struct GoalProgressSnapshot {
     pub goal_id: String,
     pub goal_title: String,
     pub query_date: String,
-    /// Initial value is always 0 under the new logic
+    /// Initial contributions of the active allocations; always 0 in
+    /// `GoalService::calculate_goal_progress_on_date`
     pub init_value: f64,
     /// Current accumulated value from growth since goal start date
     pub current_value: f64,
@@ -15,6 +16,10 @@ pub struct GoalProgressSnapshot {
     pub growth: f64,
     /// Allocation breakdown by account
     pub allocation_details: Vec<AllocationDetail>,
+    /// Value the goal should have on this date at its `target_return_rate` and
+    /// `monthly_investment`; only set in a `GoalProgressHistory`
+    #[serde(default)]
+    pub target_value: Option<f64>,
 }
 
 /// Details of how a goal is performing on a specific account
@@ -33,6 +38,16 @@ pub struct AllocationDetail {
     pub allocated_growth: f64,
 }
 
+/// Spacing of the snapshots in a `GoalProgressHistory`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GoalHistoryInterval {
+    Daily,
+    /// Month ends, plus the first and last date of the range
+    #[default]
+    Monthly,
+}
+
 /// Summary of goal across all dates (historical view)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -41,6 +56,11 @@ pub struct GoalProgressHistory {
     pub goal_title: String,
     pub start_date: String,
     pub due_date: String,
+    pub target_amount: f64,
+    pub interval: GoalHistoryInterval,
+    /// First snapshot date of the run of snapshots, up to the last one, where the
+    /// goal is below its target curve; `None` when the last snapshot is on track
+    pub behind_since: Option<String>,
     /// Snapshots at key dates
     pub snapshots: Vec<GoalProgressSnapshot>,
 }
@@ -0,0 +1,94 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Options for a Monte Carlo goal projection. Rates are annual percentages, like
+/// `Goal::target_return_rate` (`8.0` for 8%).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalProjectionOptions {
+    /// Expected annual return. When set together with `volatility`, returns are drawn
+    /// from a normal distribution instead of the allocated accounts' history.
+    pub expected_return: Option<f64>,
+    /// Annual volatility (standard deviation of returns)
+    pub volatility: Option<f64>,
+    /// Annual inflation; projected values are reported in today's money
+    pub inflation_rate: Option<f64>,
+    /// Contribution added at the end of every month; defaults to the goal's `monthly_investment`
+    pub monthly_contribution: Option<f64>,
+    /// Number of simulated paths
+    pub num_paths: Option<u32>,
+    /// Seed of the random generator; the same seed always produces the same projection
+    pub seed: Option<u64>,
+}
+
+/// Where the simulated monthly returns come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReturnSource {
+    /// Resampled from the allocated accounts' monthly return history
+    Historical,
+    /// Drawn from a normal distribution with the given mean and volatility
+    Assumed,
+}
+
+/// Projected goal value percentiles at the end of one simulated month
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalProjectionPoint {
+    pub month: u32,
+    pub date: NaiveDate,
+    pub p10: f64,
+    pub p50: f64,
+    pub p90: f64,
+}
+
+/// Result of a Monte Carlo goal projection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalProjection {
+    pub goal_id: String,
+    pub start_date: NaiveDate,
+    pub due_date: NaiveDate,
+    pub target_amount: f64,
+    /// Allocated value the simulation starts from, in the base currency
+    pub current_value: f64,
+    pub monthly_contribution: f64,
+    pub return_source: ReturnSource,
+    /// Annual mean return and volatility of the simulated returns, in percent
+    pub annual_return: f64,
+    pub annual_volatility: f64,
+    pub inflation_rate: f64,
+    pub num_paths: u32,
+    pub seed: u64,
+    /// Share of paths (0-1) whose value reaches the target by the due date
+    pub probability_of_success: f64,
+    /// One point per month, starting with the current value at month 0
+    pub paths: Vec<GoalProjectionPoint>,
+}
+
+/// Deterministic plan for a goal at its expected return, comparing the contribution
+/// it needs with the stored `monthly_investment`. Rates are annual percentages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalPlan {
+    pub goal_id: String,
+    pub as_of_date: NaiveDate,
+    pub due_date: Option<NaiveDate>,
+    /// Whole months left until the due date
+    pub months_remaining: u32,
+    pub target_amount: f64,
+    pub current_value: f64,
+    pub expected_return: f64,
+    pub monthly_investment: f64,
+    /// Contribution per month that reaches the target on the due date; `None` without
+    /// a due date in the future
+    pub required_monthly_contribution: Option<f64>,
+    /// Value at the due date when contributing `monthly_investment`
+    pub projected_value_at_due_date: Option<f64>,
+    /// When the target is reached at the current pace; `None` if it never is
+    pub projected_completion_date: Option<NaiveDate>,
+    /// Annual return needed to reach the target on time at the current pace
+    pub required_return: Option<f64>,
+    /// The stored `monthly_investment` covers the required contribution
+    pub is_on_track: bool,
+}
@@ -0,0 +1,234 @@
+use async_trait::async_trait;
+use chrono::{Local, NaiveDate};
+use log::debug;
+use num_traits::ToPrimitive;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use crate::errors::{Error, Result, ValidationError};
+use crate::goals::goal_projection_model::{
+    GoalProjection, GoalProjectionOptions, GoalProjectionPoint, ReturnSource,
+};
+use crate::goals::goal_simulation::{
+    add_months, months_between, parse_goal_date, simulate_goal, MonthlyReturns, SimulationInput,
+};
+use crate::goals::goals_model::{Goal, GoalsAllocation};
+use crate::goals::goals_traits::{GoalProjectionServiceTrait, GoalRepositoryTrait};
+use crate::portfolio::performance::PerformanceServiceTrait;
+use crate::portfolio::valuation::ValuationServiceTrait;
+
+const DEFAULT_NUM_PATHS: u32 = 1_000;
+const MAX_NUM_PATHS: u32 = 10_000;
+const DEFAULT_SEED: u64 = 20_240_101;
+/// Fewer full months than this are too thin to resample from
+const MIN_HISTORY_MONTHS: usize = 12;
+
+pub struct GoalProjectionService {
+    goal_repo: Arc<dyn GoalRepositoryTrait>,
+    valuation_service: Arc<dyn ValuationServiceTrait>,
+    performance_service: Arc<dyn PerformanceServiceTrait>,
+}
+
+impl GoalProjectionService {
+    pub fn new(
+        goal_repo: Arc<dyn GoalRepositoryTrait>,
+        valuation_service: Arc<dyn ValuationServiceTrait>,
+        performance_service: Arc<dyn PerformanceServiceTrait>,
+    ) -> Self {
+        Self {
+            goal_repo,
+            valuation_service,
+            performance_service,
+        }
+    }
+
+    fn load_goal(&self, goal_id: &str) -> Result<Goal> {
+        self.goal_repo
+            .load_goals()?
+            .into_iter()
+            .find(|g| g.id == goal_id)
+            .ok_or_else(|| {
+                Error::Validation(ValidationError::InvalidInput(format!(
+                    "Goal {} not found",
+                    goal_id
+                )))
+            })
+    }
+
+    /// Allocations of the goal active today
+    fn active_allocations(&self, goal_id: &str, today: NaiveDate) -> Result<Vec<GoalsAllocation>> {
+        Ok(self
+            .goal_repo
+            .get_allocations_for_goal(goal_id)?
+            .into_iter()
+            .filter(|a| {
+                a.allocation_percentage > 0.0
+                    && a.start_date
+                        .as_deref()
+                        .and_then(parse_goal_date)
+                        .is_none_or(|s| s <= today)
+                    && a.end_date
+                        .as_deref()
+                        .and_then(parse_goal_date)
+                        .is_none_or(|e| e >= today)
+            })
+            .collect())
+    }
+
+    /// Allocated value of each account in the base currency, the same way the goals
+    /// page computes progress: latest account value times the allocated percentage
+    fn allocated_values(&self, allocations: &[GoalsAllocation]) -> Result<HashMap<String, f64>> {
+        let account_ids: Vec<String> = allocations.iter().map(|a| a.account_id.clone()).collect();
+        let account_values: HashMap<String, f64> = self
+            .valuation_service
+            .get_latest_valuations(&account_ids)?
+            .into_iter()
+            .map(|v| {
+                let base_value = (v.total_value * v.fx_rate_to_base).to_f64().unwrap_or(0.0);
+                (v.account_id, base_value)
+            })
+            .collect();
+
+        let mut allocated: HashMap<String, f64> = HashMap::new();
+        for allocation in allocations {
+            let value = account_values
+                .get(&allocation.account_id)
+                .copied()
+                .unwrap_or(0.0);
+            *allocated.entry(allocation.account_id.clone()).or_default() +=
+                value * allocation.allocation_percentage / 100.0;
+        }
+        Ok(allocated)
+    }
+
+    /// Full calendar months of the allocated accounts' returns, blended by allocated value
+    async fn blended_monthly_returns(&self, weights: &HashMap<String, f64>) -> Result<Vec<f64>> {
+        // (year, month) -> (weighted return sum, weight sum)
+        let mut months: BTreeMap<(i32, u32), (f64, f64)> = BTreeMap::new();
+        for (account_id, weight) in weights {
+            if *weight <= 0.0 {
+                continue;
+            }
+            let returns = self
+                .performance_service
+                .calculate_periodic_returns("account", account_id, None, None)
+                .await?;
+            for month in returns.monthly.iter().filter(|m| !m.is_partial) {
+                let entry = months.entry((month.year, month.period)).or_default();
+                entry.0 += month.value.to_f64().unwrap_or(0.0) * weight;
+                entry.1 += weight;
+            }
+        }
+        Ok(months
+            .into_values()
+            .filter(|(_, weight)| *weight > 0.0)
+            .map(|(sum, weight)| sum / weight)
+            .collect())
+    }
+}
+
+#[async_trait]
+impl GoalProjectionServiceTrait for GoalProjectionService {
+    async fn project_goal(
+        &self,
+        goal_id: &str,
+        options: GoalProjectionOptions,
+    ) -> Result<GoalProjection> {
+        let goal = self.load_goal(goal_id)?;
+        let due_date = goal
+            .due_date
+            .as_deref()
+            .and_then(parse_goal_date)
+            .ok_or_else(|| {
+                Error::Validation(ValidationError::InvalidInput(
+                    "Goal must have a due date to be projected".to_string(),
+                ))
+            })?;
+        let start_date = Local::now().date_naive();
+        let months = months_between(start_date, due_date);
+        if months == 0 {
+            return Err(Error::Validation(ValidationError::InvalidInput(format!(
+                "Goal due date {} is less than a month away",
+                due_date
+            ))));
+        }
+
+        let allocations = self.active_allocations(&goal.id, start_date)?;
+        let allocated = self.allocated_values(&allocations)?;
+        let current_value: f64 = allocated.values().sum();
+
+        let (returns, return_source) = match (options.expected_return, options.volatility) {
+            (Some(expected_return), Some(volatility)) => (
+                MonthlyReturns::from_annual(expected_return / 100.0, volatility / 100.0),
+                ReturnSource::Assumed,
+            ),
+            _ => {
+                let history = self.blended_monthly_returns(&allocated).await?;
+                if history.len() < MIN_HISTORY_MONTHS {
+                    return Err(Error::Validation(ValidationError::InvalidInput(format!(
+                        "Only {} full month(s) of return history for this goal's accounts; provide an expected return and volatility instead",
+                        history.len()
+                    ))));
+                }
+                (MonthlyReturns::Bootstrap(history), ReturnSource::Historical)
+            }
+        };
+
+        let monthly_contribution = options
+            .monthly_contribution
+            .or(goal.monthly_investment)
+            .unwrap_or(0.0);
+        let inflation_rate = options.inflation_rate.unwrap_or(0.0);
+        let num_paths = options
+            .num_paths
+            .unwrap_or(DEFAULT_NUM_PATHS)
+            .clamp(1, MAX_NUM_PATHS);
+        let seed = options.seed.unwrap_or(DEFAULT_SEED);
+        let (annual_return, annual_volatility) = returns.annualized();
+
+        debug!(
+            "Projecting goal {} over {} months with {} paths ({:?} returns)",
+            goal.id, months, num_paths, return_source
+        );
+        let output = simulate_goal(&SimulationInput {
+            initial_value: current_value,
+            monthly_contribution,
+            target_amount: goal.target_amount,
+            months,
+            annual_inflation: inflation_rate / 100.0,
+            returns,
+            num_paths,
+            seed,
+        });
+
+        let paths = output
+            .percentiles
+            .into_iter()
+            .enumerate()
+            .map(|(month, (p10, p50, p90))| GoalProjectionPoint {
+                month: month as u32,
+                date: add_months(start_date, month as u32),
+                p10,
+                p50,
+                p90,
+            })
+            .collect();
+
+        Ok(GoalProjection {
+            goal_id: goal.id,
+            start_date,
+            due_date,
+            target_amount: goal.target_amount,
+            current_value,
+            monthly_contribution,
+            return_source,
+            annual_return: annual_return * 100.0,
+            annual_volatility: annual_volatility * 100.0,
+            inflation_rate,
+            num_paths,
+            seed,
+            probability_of_success: output.probability_of_success,
+            paths,
+        })
+    }
+}
@@ -0,0 +1,299 @@
+//! Seeded Monte Carlo engine for goal projections.
+//!
+//! Everything here is plain `f64` math over fractions (`0.08` for 8%); the service
+//! converts the goal's percentage fields before calling in.
+
+use chrono::{DateTime, Datelike, Local, Months, NaiveDate};
+
+/// Distribution monthly returns are drawn from
+#[derive(Debug, Clone, PartialEq)]
+pub enum MonthlyReturns {
+    /// Normally distributed with the given monthly mean and standard deviation
+    Normal { mean: f64, volatility: f64 },
+    /// Resampled uniformly, with replacement, from observed monthly returns
+    Bootstrap(Vec<f64>),
+}
+
+#[derive(Debug, Clone)]
+pub struct SimulationInput {
+    pub initial_value: f64,
+    pub monthly_contribution: f64,
+    pub target_amount: f64,
+    pub months: u32,
+    /// Annual inflation used to express values in today's money
+    pub annual_inflation: f64,
+    pub returns: MonthlyReturns,
+    pub num_paths: u32,
+    pub seed: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationOutput {
+    /// `(p10, p50, p90)` for months `0..=months`
+    pub percentiles: Vec<(f64, f64, f64)>,
+    /// Share of paths whose value at the last month reaches the target
+    pub probability_of_success: f64,
+}
+
+/// SplitMix64: tiny, fast and fully determined by its seed, so projections can be
+/// reproduced across platforms and releases.
+pub struct SimulationRng {
+    state: u64,
+}
+
+impl SimulationRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `(0, 1]`
+    pub fn next_f64(&mut self) -> f64 {
+        ((self.next_u64() >> 11) + 1) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Standard normal via the Box-Muller transform
+    pub fn next_normal(&mut self) -> f64 {
+        let u1 = self.next_f64();
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+
+    fn next_index(&mut self, len: usize) -> usize {
+        (self.next_u64() % len as u64) as usize
+    }
+}
+
+impl MonthlyReturns {
+    /// Converts an annual mean return and volatility into a monthly normal distribution
+    pub fn from_annual(annual_return: f64, annual_volatility: f64) -> Self {
+        MonthlyReturns::Normal {
+            mean: (1.0 + annual_return).powf(1.0 / 12.0) - 1.0,
+            volatility: annual_volatility / 12f64.sqrt(),
+        }
+    }
+
+    /// Annualized mean return and volatility of the distribution
+    pub fn annualized(&self) -> (f64, f64) {
+        let (mean, volatility) = match self {
+            MonthlyReturns::Normal { mean, volatility } => (*mean, *volatility),
+            MonthlyReturns::Bootstrap(samples) => mean_and_std(samples),
+        };
+        ((1.0 + mean).powi(12) - 1.0, volatility * 12f64.sqrt())
+    }
+
+    /// Draws one monthly return
+    pub fn draw(&self, rng: &mut SimulationRng) -> f64 {
+        match self {
+            MonthlyReturns::Normal { mean, volatility } => {
+                // Returns below -100% would flip the sign of the balance
+                (mean + volatility * rng.next_normal()).max(-1.0)
+            }
+            MonthlyReturns::Bootstrap(samples) if samples.is_empty() => 0.0,
+            MonthlyReturns::Bootstrap(samples) => samples[rng.next_index(samples.len())],
+        }
+    }
+}
+
+fn mean_and_std(samples: &[f64]) -> (f64, f64) {
+    if samples.is_empty() {
+        return (0.0, 0.0);
+    }
+    let n = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / n;
+    if samples.len() < 2 {
+        return (mean, 0.0);
+    }
+    let variance = samples.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, variance.sqrt())
+}
+
+/// Linear-interpolated percentile (`p` in 0-1) of an ascending slice
+pub fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = p.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+/// Runs the simulation. Each month the balance grows by a drawn return and then
+/// receives the contribution; values are deflated to today's money before the
+/// percentiles and the success probability are taken.
+pub fn simulate_goal(input: &SimulationInput) -> SimulationOutput {
+    let num_paths = input.num_paths.max(1) as usize;
+    let months = input.months as usize;
+    let mut rng = SimulationRng::new(input.seed);
+    // values[month][path]
+    let mut values = vec![vec![input.initial_value; num_paths]; months + 1];
+
+    for path in 0..num_paths {
+        let mut balance = input.initial_value;
+        for month_values in values.iter_mut().skip(1) {
+            balance = balance * (1.0 + input.returns.draw(&mut rng)) + input.monthly_contribution;
+            month_values[path] = balance;
+        }
+    }
+
+    let mut percentiles = Vec::with_capacity(months + 1);
+    let mut probability_of_success = 0.0;
+    for (month, month_values) in values.iter_mut().enumerate() {
+        let deflator = (1.0 + input.annual_inflation).powf(month as f64 / 12.0);
+        for value in month_values.iter_mut() {
+            *value /= deflator;
+        }
+        month_values.sort_by(|a, b| a.total_cmp(b));
+        percentiles.push((
+            percentile(month_values, 0.10),
+            percentile(month_values, 0.50),
+            percentile(month_values, 0.90),
+        ));
+        if month == months {
+            let reached = month_values
+                .iter()
+                .filter(|v| **v >= input.target_amount)
+                .count();
+            probability_of_success = reached as f64 / num_paths as f64;
+        }
+    }
+
+    SimulationOutput {
+        percentiles,
+        probability_of_success,
+    }
+}
+
+/// Whole calendar months from `start` to `end`; a trailing partial month is dropped
+pub fn months_between(start: NaiveDate, end: NaiveDate) -> u32 {
+    if end <= start {
+        return 0;
+    }
+    let mut months = ((end.year() - start.year()) * 12 + end.month() as i32 - start.month() as i32)
+        .max(0) as u32;
+    while months > 0 && add_months(start, months) > end {
+        months -= 1;
+    }
+    months
+}
+
+/// `date` moved forward by `months`, clamped to the end of shorter months
+pub fn add_months(date: NaiveDate, months: u32) -> NaiveDate {
+    date.checked_add_months(Months::new(months))
+        .unwrap_or(NaiveDate::MAX)
+}
+
+/// Goal dates are stored either as `YYYY-MM-DD` or as ISO timestamps written by the
+/// frontend; timestamps are read in local time so a due date keeps its calendar day.
+pub fn parse_goal_date(value: &str) -> Option<NaiveDate> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Some(timestamp.with_timezone(&Local).date_naive());
+    }
+    value
+        .get(..10)
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(returns: MonthlyReturns) -> SimulationInput {
+        SimulationInput {
+            initial_value: 100_000_000.0,
+            monthly_contribution: 5_000_000.0,
+            target_amount: 200_000_000.0,
+            months: 24,
+            annual_inflation: 0.0,
+            returns,
+            num_paths: 500,
+            seed: 42,
+        }
+    }
+
+    #[test]
+    fn test_same_seed_gives_same_projection() {
+        let returns = MonthlyReturns::from_annual(0.10, 0.20);
+        let first = simulate_goal(&input(returns.clone()));
+        let second = simulate_goal(&input(returns.clone()));
+        assert_eq!(first, second);
+
+        let mut reseeded = input(returns);
+        reseeded.seed = 7;
+        assert_ne!(first, simulate_goal(&reseeded));
+    }
+
+    #[test]
+    fn test_zero_volatility_matches_future_value_of_annuity() {
+        let monthly = 0.01;
+        let output = simulate_goal(&input(MonthlyReturns::Normal {
+            mean: monthly,
+            volatility: 0.0,
+        }));
+        let growth = (1.0f64 + monthly).powi(24);
+        let expected = 100_000_000.0 * growth + 5_000_000.0 * (growth - 1.0) / monthly;
+
+        let (p10, p50, p90) = output.percentiles[24];
+        assert!((p50 - expected).abs() < 1e-3);
+        assert!((p10 - p50).abs() < 1e-6 && (p90 - p50).abs() < 1e-6);
+        assert_eq!(output.probability_of_success, 1.0);
+    }
+
+    #[test]
+    fn test_percentiles_are_ordered_and_start_at_current_value() {
+        let output = simulate_goal(&input(MonthlyReturns::from_annual(0.08, 0.25)));
+        assert_eq!(output.percentiles.len(), 25);
+        assert_eq!(
+            output.percentiles[0],
+            (100_000_000.0, 100_000_000.0, 100_000_000.0)
+        );
+        for (p10, p50, p90) in &output.percentiles[1..] {
+            assert!(p10 < p50 && p50 < p90);
+        }
+        assert!(output.probability_of_success > 0.0 && output.probability_of_success < 1.0);
+    }
+
+    #[test]
+    fn test_bootstrap_and_inflation() {
+        let mut flat = input(MonthlyReturns::Bootstrap(vec![0.0]));
+        flat.annual_inflation = 0.04;
+        flat.months = 12;
+        let output = simulate_goal(&flat);
+
+        // 100m + 12 * 5m nominal, deflated by one year of 4% inflation
+        assert!((output.percentiles[12].1 - 160_000_000.0 / 1.04).abs() < 1e-3);
+        assert_eq!(output.probability_of_success, 0.0);
+
+        let (annual_return, annual_volatility) =
+            MonthlyReturns::Bootstrap(vec![0.01, 0.01]).annualized();
+        assert!((annual_return - (1.01f64.powi(12) - 1.0)).abs() < 1e-12);
+        assert_eq!(annual_volatility, 0.0);
+    }
+
+    #[test]
+    fn test_months_between() {
+        let d = |y, m, day| NaiveDate::from_ymd_opt(y, m, day).unwrap();
+        assert_eq!(months_between(d(2025, 1, 15), d(2026, 1, 15)), 12);
+        assert_eq!(months_between(d(2025, 1, 15), d(2026, 1, 14)), 11);
+        assert_eq!(months_between(d(2025, 1, 31), d(2025, 2, 28)), 1);
+        assert_eq!(months_between(d(2025, 3, 1), d(2025, 2, 1)), 0);
+    }
+
+    #[test]
+    fn test_parse_goal_date() {
+        assert_eq!(
+            parse_goal_date("2030-06-30"),
+            NaiveDate::from_ymd_opt(2030, 6, 30)
+        );
+        assert!(parse_goal_date("2030-06-30T00:00:00.000Z").is_some());
+        assert_eq!(parse_goal_date("soon"), None);
+    }
+}
@@ -0,0 +1,238 @@
+//! Closed-form and bisection solvers for goal planning.
+//!
+//! Contributions are made at the end of every month and rates are monthly
+//! fractions, matching the Monte Carlo engine in `goal_simulation`.
+
+use chrono::NaiveDate;
+
+use crate::goals::goal_projection_model::GoalPlan;
+use crate::goals::goal_simulation::{add_months, months_between, parse_goal_date};
+use crate::goals::goals_model::Goal;
+
+/// Projections further out than this are reported as never reaching the target
+pub const MAX_PLAN_MONTHS: u32 = 1_200;
+
+const RATE_TOLERANCE: f64 = 1e-10;
+const MAX_BISECTION_STEPS: u32 = 200;
+const MONTH_TOLERANCE: f64 = 1e-9;
+const VALUE_TOLERANCE: f64 = 1e-12;
+/// Contributions within this amount of the requirement count as on track
+const CONTRIBUTION_TOLERANCE: f64 = 0.01;
+
+/// Monthly rate equivalent to an annual rate
+pub fn monthly_rate(annual_rate: f64) -> f64 {
+    (1.0 + annual_rate).powf(1.0 / 12.0) - 1.0
+}
+
+/// Annual rate equivalent to a monthly rate
+pub fn annual_rate(monthly_rate: f64) -> f64 {
+    (1.0 + monthly_rate).powi(12) - 1.0
+}
+
+/// Value after `months` of growth at `rate` with `contribution` added each month
+pub fn future_value(present_value: f64, contribution: f64, rate: f64, months: u32) -> f64 {
+    if rate.abs() < RATE_TOLERANCE {
+        return present_value + contribution * months as f64;
+    }
+    let growth = (1.0 + rate).powi(months as i32);
+    present_value * growth + contribution * (growth - 1.0) / rate
+}
+
+/// Monthly contribution needed to grow `present_value` into `target` in `months`.
+/// Zero when growth alone gets there.
+pub fn required_contribution(present_value: f64, target: f64, rate: f64, months: u32) -> f64 {
+    if months == 0 {
+        return (target - present_value).max(0.0);
+    }
+    let shortfall = target - future_value(present_value, 0.0, rate, months);
+    if shortfall <= 0.0 {
+        return 0.0;
+    }
+    // Future value of contributing 1 per month
+    shortfall / future_value(0.0, 1.0, rate, months)
+}
+
+/// Whole months until the target is reached at the current pace, or `None` when it
+/// is not reached within `MAX_PLAN_MONTHS`
+pub fn months_to_target(
+    present_value: f64,
+    contribution: f64,
+    rate: f64,
+    target: f64,
+) -> Option<u32> {
+    if present_value >= target {
+        return Some(0);
+    }
+    let exact = if rate.abs() < RATE_TOLERANCE {
+        if contribution <= 0.0 {
+            return None;
+        }
+        (target - present_value) / contribution
+    } else {
+        // Solves present_value * g + contribution * (g - 1) / rate = target for g = (1 + rate)^n
+        let numerator = target * rate + contribution;
+        let denominator = present_value * rate + contribution;
+        if denominator == 0.0 || numerator / denominator <= 0.0 {
+            return None;
+        }
+        (numerator / denominator).ln() / (1.0 + rate).ln()
+    };
+    if !exact.is_finite() || exact < 0.0 {
+        return None;
+    }
+    // Rounding can leave the exact solution a hair above a whole month, or the
+    // value at the ceiling a hair below the target
+    let mut months = (exact - MONTH_TOLERANCE).ceil().max(0.0) as u32;
+    let shortfall_tolerance = target.abs() * VALUE_TOLERANCE;
+    while months <= MAX_PLAN_MONTHS
+        && target - future_value(present_value, contribution, rate, months) > shortfall_tolerance
+    {
+        months += 1;
+    }
+    (months <= MAX_PLAN_MONTHS).then_some(months)
+}
+
+/// Monthly return needed to reach `target` in `months` with the given contribution,
+/// or `None` when no rate between -99% and +1000% a year gets there
+pub fn required_rate(
+    present_value: f64,
+    contribution: f64,
+    target: f64,
+    months: u32,
+) -> Option<f64> {
+    if months == 0 || present_value < 0.0 || contribution < 0.0 {
+        return None;
+    }
+    let mut low = monthly_rate(-0.99);
+    let mut high = monthly_rate(10.0);
+    let gap = |rate: f64| future_value(present_value, contribution, rate, months) - target;
+    if gap(low) > 0.0 || gap(high) < 0.0 {
+        return None;
+    }
+    // Future value rises with the rate when nothing is withdrawn, so bisection converges
+    for _ in 0..MAX_BISECTION_STEPS {
+        let mid = (low + high) / 2.0;
+        if gap(mid) < 0.0 {
+            low = mid;
+        } else {
+            high = mid;
+        }
+        if high - low < RATE_TOLERANCE {
+            break;
+        }
+    }
+    Some((low + high) / 2.0)
+}
+
+/// Plans a goal from its allocated value on `as_of_date`, its `target_return_rate`
+/// and its stored `monthly_investment`
+pub fn plan_goal(goal: &Goal, current_value: f64, as_of_date: NaiveDate) -> GoalPlan {
+    let expected_return = goal.target_return_rate.unwrap_or(0.0);
+    let rate = monthly_rate(expected_return / 100.0);
+    let monthly_investment = goal.monthly_investment.unwrap_or(0.0);
+    let target = goal.target_amount;
+
+    let due_date = goal.due_date.as_deref().and_then(parse_goal_date);
+    let months_remaining = due_date.map_or(0, |due| months_between(as_of_date, due));
+    let has_time_left = months_remaining > 0;
+
+    let required_monthly_contribution =
+        has_time_left.then(|| required_contribution(current_value, target, rate, months_remaining));
+    let projected_value_at_due_date = has_time_left
+        .then(|| future_value(current_value, monthly_investment, rate, months_remaining));
+    let required_return = if has_time_left {
+        required_rate(current_value, monthly_investment, target, months_remaining)
+            .map(|r| annual_rate(r) * 100.0)
+    } else {
+        None
+    };
+    let projected_completion_date =
+        months_to_target(current_value, monthly_investment, rate, target)
+            .map(|months| add_months(as_of_date, months));
+    let is_on_track = match required_monthly_contribution {
+        Some(required) => monthly_investment >= required - CONTRIBUTION_TOLERANCE,
+        None => current_value >= target,
+    };
+
+    GoalPlan {
+        goal_id: goal.id.clone(),
+        as_of_date,
+        due_date,
+        months_remaining,
+        target_amount: target,
+        current_value,
+        expected_return,
+        monthly_investment,
+        required_monthly_contribution,
+        projected_value_at_due_date,
+        projected_completion_date,
+        required_return,
+        is_on_track,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::planned_goal;
+
+    #[test]
+    fn test_required_contribution_reaches_target() {
+        let rate = monthly_rate(0.08);
+        let contribution = required_contribution(100_000_000.0, 500_000_000.0, rate, 60);
+        let reached = future_value(100_000_000.0, contribution, rate, 60);
+        assert!((reached - 500_000_000.0).abs() < 1e-3);
+
+        assert_eq!(required_contribution(100.0, 150.0, 0.0, 10), 5.0);
+        assert_eq!(required_contribution(200.0, 150.0, 0.01, 10), 0.0);
+    }
+
+    #[test]
+    fn test_months_to_target() {
+        assert_eq!(months_to_target(0.0, 10.0, 0.0, 95.0), Some(10));
+        assert_eq!(months_to_target(100.0, 0.0, 0.0, 50.0), Some(0));
+        assert_eq!(months_to_target(0.0, 0.0, 0.0, 50.0), None);
+        // 1% a month doubles money in a little under 70 months
+        assert_eq!(months_to_target(100.0, 0.0, 0.01, 200.0), Some(70));
+        // Losing faster than contributing never gets there
+        assert_eq!(months_to_target(1_000.0, 1.0, -0.05, 2_000.0), None);
+    }
+
+    #[test]
+    fn test_required_rate_round_trips() {
+        let rate = monthly_rate(0.12);
+        let target = future_value(50_000_000.0, 2_000_000.0, rate, 36);
+        let solved = required_rate(50_000_000.0, 2_000_000.0, target, 36).unwrap();
+        assert!((annual_rate(solved) - 0.12).abs() < 1e-6);
+
+        // Unreachable even at +1000% a year
+        assert_eq!(required_rate(1.0, 0.0, 1e30, 12), None);
+    }
+
+    #[test]
+    fn test_plan_goal_on_and_off_track() {
+        let as_of = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let behind = plan_goal(&planned_goal(8.0, 5_000_000.0), 300_000_000.0, as_of);
+        assert_eq!(behind.months_remaining, 60);
+        let required = behind.required_monthly_contribution.unwrap();
+        assert!(required > 5_000_000.0);
+        assert!(!behind.is_on_track);
+        assert!(behind.projected_value_at_due_date.unwrap() < 1_000_000_000.0);
+        assert!(behind.required_return.unwrap() > 8.0);
+        assert!(behind.projected_completion_date.unwrap() > behind.due_date.unwrap());
+
+        let ahead = plan_goal(&planned_goal(8.0, required), 300_000_000.0, as_of);
+        assert!(ahead.is_on_track);
+        assert!((ahead.required_return.unwrap() - 8.0).abs() < 1e-4);
+        assert_eq!(ahead.projected_completion_date, ahead.due_date);
+
+        let overdue = plan_goal(
+            &planned_goal(8.0, 0.0),
+            300_000_000.0,
+            NaiveDate::from_ymd_opt(2031, 1, 1).unwrap(),
+        );
+        assert_eq!(overdue.months_remaining, 0);
+        assert_eq!(overdue.required_monthly_contribution, None);
+        assert!(!overdue.is_on_track);
+    }
+}
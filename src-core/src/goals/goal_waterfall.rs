@@ -0,0 +1,159 @@
+//! Priority waterfall over account values.
+//!
+//! Goals are funded one at a time in priority order: each takes what it still needs
+//! from the accounts, in the order given, before the next goal gets anything. Value
+//! left once every goal is funded stays unallocated.
+
+use crate::goals::goal_waterfall_model::{WaterfallGoal, WaterfallShare};
+use crate::goals::goals_model::Goal;
+
+/// Amounts below this are treated as nothing left to assign
+const AMOUNT_TOLERANCE: f64 = 1e-6;
+
+/// Account value the waterfall can assign, in the base currency
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaterfallAccount {
+    pub account_id: String,
+    pub total_value: f64,
+    /// Value left after manual allocations
+    pub available_value: f64,
+}
+
+/// Goals that take part in the waterfall, in priority order. Ties go to the earlier
+/// due date, then to the title.
+pub fn waterfall_goals(goals: &[Goal]) -> Vec<&Goal> {
+    let mut ordered: Vec<&Goal> = goals
+        .iter()
+        .filter(|g| g.priority.is_some() && !g.is_achieved)
+        .collect();
+    ordered.sort_by(|a, b| {
+        a.priority
+            .cmp(&b.priority)
+            .then_with(|| match (&a.due_date, &b.due_date) {
+                (Some(a), Some(b)) => a.cmp(b),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            })
+            .then_with(|| a.title.cmp(&b.title))
+    });
+    ordered
+}
+
+/// Runs the waterfall and returns each goal's funding with the surplus left over
+pub fn allocate_waterfall(
+    goals: &[Goal],
+    accounts: &[WaterfallAccount],
+) -> (Vec<WaterfallGoal>, f64) {
+    let mut remaining: Vec<f64> = accounts
+        .iter()
+        .map(|a| a.available_value.max(0.0))
+        .collect();
+
+    let funded = waterfall_goals(goals)
+        .into_iter()
+        .map(|goal| {
+            let mut need = goal.target_amount.max(0.0);
+            let mut shares = Vec::new();
+            for (account, left) in accounts.iter().zip(remaining.iter_mut()) {
+                if need <= AMOUNT_TOLERANCE {
+                    break;
+                }
+                let amount = need.min(*left);
+                if amount <= AMOUNT_TOLERANCE || account.total_value <= 0.0 {
+                    continue;
+                }
+                *left -= amount;
+                need -= amount;
+                shares.push(WaterfallShare {
+                    account_id: account.account_id.clone(),
+                    amount,
+                    percentage: amount / account.total_value * 100.0,
+                });
+            }
+            let funded_amount: f64 = shares.iter().map(|s| s.amount).sum();
+            WaterfallGoal {
+                goal_id: goal.id.clone(),
+                title: goal.title.clone(),
+                priority: goal.priority.unwrap_or_default(),
+                target_amount: goal.target_amount,
+                funded_amount,
+                is_funded: need <= AMOUNT_TOLERANCE,
+                shares,
+            }
+        })
+        .collect();
+
+    (funded, remaining.iter().sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::goal;
+
+    fn account(id: &str, total_value: f64, available_value: f64) -> WaterfallAccount {
+        WaterfallAccount {
+            account_id: id.to_string(),
+            total_value,
+            available_value,
+        }
+    }
+
+    #[test]
+    fn test_surplus_spills_to_next_priority() {
+        let goals = vec![
+            goal("education", 500.0, Some(3)),
+            goal("emergency", 100.0, Some(1)),
+            goal("house", 300.0, Some(2)),
+            goal("manual", 1_000.0, None),
+        ];
+        let accounts = vec![account("a", 250.0, 250.0), account("b", 200.0, 200.0)];
+        let (funded, unallocated) = allocate_waterfall(&goals, &accounts);
+
+        let order: Vec<&str> = funded.iter().map(|g| g.goal_id.as_str()).collect();
+        assert_eq!(order, vec!["emergency", "house", "education"]);
+        assert!(funded[0].is_funded && funded[1].is_funded);
+        assert_eq!(
+            funded[0].shares,
+            vec![WaterfallShare {
+                account_id: "a".to_string(),
+                amount: 100.0,
+                percentage: 40.0,
+            }]
+        );
+        // House takes the rest of a and 150 of b
+        assert_eq!(funded[1].shares.len(), 2);
+        assert_eq!(funded[1].shares[1].amount, 150.0);
+        assert!(!funded[2].is_funded);
+        assert_eq!(funded[2].funded_amount, 50.0);
+        assert_eq!(unallocated, 0.0);
+    }
+
+    #[test]
+    fn test_manual_allocations_reduce_available_value() {
+        let goals = vec![goal("emergency", 100.0, Some(1))];
+        // Half of the account is allocated manually elsewhere
+        let accounts = vec![account("a", 400.0, 200.0)];
+        let (funded, unallocated) = allocate_waterfall(&goals, &accounts);
+        assert_eq!(funded[0].shares[0].percentage, 25.0);
+        assert_eq!(unallocated, 100.0);
+    }
+
+    #[test]
+    fn test_ties_and_achieved_goals() {
+        let mut done = goal("done", 100.0, Some(1));
+        done.is_achieved = true;
+        let mut later = goal("later", 100.0, Some(2));
+        later.due_date = Some("2031-01-01".to_string());
+        let mut sooner = goal("sooner", 100.0, Some(2));
+        sooner.due_date = Some("2030-01-01".to_string());
+        let goals = vec![done, later, sooner];
+
+        let order: Vec<&str> = waterfall_goals(&goals)
+            .iter()
+            .map(|g| g.id.as_str())
+            .collect();
+        assert_eq!(order, vec!["sooner", "later"]);
+    }
+}
@@ -0,0 +1,41 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Part of one account assigned to a goal by the waterfall
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaterfallShare {
+    pub account_id: String,
+    /// Value assigned, in the base currency
+    pub amount: f64,
+    /// Share of the account's total value, 0-100
+    pub percentage: f64,
+}
+
+/// How far the waterfall funds one goal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaterfallGoal {
+    pub goal_id: String,
+    pub title: String,
+    pub priority: i32,
+    pub target_amount: f64,
+    pub funded_amount: f64,
+    pub is_funded: bool,
+    pub shares: Vec<WaterfallShare>,
+}
+
+/// Result of running the waterfall over the active accounts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaterfallAllocation {
+    pub as_of_date: NaiveDate,
+    /// Account value left after manual allocations, in the base currency
+    pub available_value: f64,
+    /// Surplus left after every waterfall goal is funded
+    pub unallocated_value: f64,
+    /// In priority order
+    pub goals: Vec<WaterfallGoal>,
+    /// Allocations that received a new version; empty for a preview
+    pub changed_allocation_ids: Vec<String>,
+}
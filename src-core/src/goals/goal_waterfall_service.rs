@@ -0,0 +1,298 @@
+use async_trait::async_trait;
+use chrono::{Local, NaiveDate, Utc};
+use log::{debug, info};
+use num_traits::ToPrimitive;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::accounts::AccountServiceTrait;
+use crate::errors::Result;
+use crate::goals::goal_history::AllocationTimeline;
+use crate::goals::goal_simulation::parse_goal_date;
+use crate::goals::goal_waterfall::{allocate_waterfall, WaterfallAccount};
+use crate::goals::goal_waterfall_model::WaterfallAllocation;
+use crate::goals::goals_model::{AllocationVersion, Goal, GoalsAllocation};
+use crate::goals::goals_traits::{GoalRepositoryTrait, GoalWaterfallServiceTrait};
+use crate::portfolio::valuation::ValuationServiceTrait;
+
+/// Percentage changes up to this many points keep the current version
+const PERCENTAGE_TOLERANCE: f64 = 0.01;
+
+pub struct GoalWaterfallService {
+    goal_repo: Arc<dyn GoalRepositoryTrait>,
+    account_service: Arc<dyn AccountServiceTrait>,
+    valuation_service: Arc<dyn ValuationServiceTrait>,
+}
+
+impl GoalWaterfallService {
+    pub fn new(
+        goal_repo: Arc<dyn GoalRepositoryTrait>,
+        account_service: Arc<dyn AccountServiceTrait>,
+        valuation_service: Arc<dyn ValuationServiceTrait>,
+    ) -> Self {
+        Self {
+            goal_repo,
+            account_service,
+            valuation_service,
+        }
+    }
+
+    fn is_active_on(allocation: &GoalsAllocation, date: NaiveDate) -> bool {
+        let started = allocation
+            .start_date
+            .as_deref()
+            .and_then(parse_goal_date)
+            .is_none_or(|start| start <= date);
+        let not_ended = allocation
+            .end_date
+            .as_deref()
+            .and_then(parse_goal_date)
+            .is_none_or(|end| end >= date);
+        started && not_ended
+    }
+
+    /// Active accounts with the value left after manual allocations of open goals
+    fn waterfall_accounts(
+        &self,
+        goals: &[Goal],
+        today: NaiveDate,
+    ) -> Result<Vec<WaterfallAccount>> {
+        let account_ids: Vec<String> = self
+            .account_service
+            .get_active_accounts()?
+            .into_iter()
+            .map(|a| a.id)
+            .collect();
+        if account_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let values: HashMap<String, f64> = self
+            .valuation_service
+            .get_latest_valuations(&account_ids)?
+            .into_iter()
+            .map(|v| {
+                let base_value = (v.total_value * v.fx_rate_to_base).to_f64().unwrap_or(0.0);
+                (v.account_id, base_value)
+            })
+            .collect();
+
+        let manual_goals: HashSet<&str> = goals
+            .iter()
+            .filter(|g| g.priority.is_none())
+            .map(|g| g.id.as_str())
+            .collect();
+        let mut manual_percent: HashMap<String, f64> = HashMap::new();
+        for allocation in self.goal_repo.load_allocations_for_non_achieved_goals()? {
+            if manual_goals.contains(allocation.goal_id.as_str())
+                && Self::is_active_on(&allocation, today)
+            {
+                *manual_percent.entry(allocation.account_id).or_default() +=
+                    allocation.allocation_percentage;
+            }
+        }
+
+        Ok(account_ids
+            .into_iter()
+            .map(|account_id| {
+                let total_value = values.get(&account_id).copied().unwrap_or(0.0).max(0.0);
+                let manual = manual_percent
+                    .get(&account_id)
+                    .copied()
+                    .unwrap_or(0.0)
+                    .clamp(0.0, 100.0);
+                WaterfallAccount {
+                    available_value: total_value * (100.0 - manual) / 100.0,
+                    total_value,
+                    account_id,
+                }
+            })
+            .collect())
+    }
+
+    fn run_waterfall(
+        &self,
+        today: NaiveDate,
+    ) -> Result<(Vec<Goal>, Vec<WaterfallAccount>, WaterfallAllocation)> {
+        let goals = self.goal_repo.load_goals()?;
+        let accounts = self.waterfall_accounts(&goals, today)?;
+        let (funded, unallocated_value) = allocate_waterfall(&goals, &accounts);
+        let allocation = WaterfallAllocation {
+            as_of_date: today,
+            available_value: accounts.iter().map(|a| a.available_value).sum(),
+            unallocated_value,
+            goals: funded,
+            changed_allocation_ids: Vec::new(),
+        };
+        Ok((goals, accounts, allocation))
+    }
+
+    fn new_version(
+        allocation_id: &str,
+        percentage: f64,
+        amount: f64,
+        start: String,
+        end: Option<String>,
+    ) -> AllocationVersion {
+        AllocationVersion {
+            id: Uuid::new_v4().to_string(),
+            allocation_id: allocation_id.to_string(),
+            allocation_percentage: percentage,
+            allocation_amount: amount,
+            version_start_date: start,
+            version_end_date: end,
+            created_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Moves an existing allocation to a new percentage from `today`, recording its
+    /// previous state as a version first if it has none yet
+    async fn version_allocation(
+        &self,
+        goal: &Goal,
+        allocation: &GoalsAllocation,
+        percentage: f64,
+        amount: f64,
+        today: NaiveDate,
+    ) -> Result<()> {
+        let today_str = today.to_string();
+        let versions = self.goal_repo.get_allocation_versions(&allocation.id)?;
+        if versions.is_empty() {
+            let goal_start = goal
+                .start_date
+                .as_deref()
+                .and_then(parse_goal_date)
+                .unwrap_or(today);
+            let timeline = AllocationTimeline::new(allocation, &[], goal_start);
+            if let Some(previous) = timeline.segments.first() {
+                self.goal_repo
+                    .insert_allocation_version(Self::new_version(
+                        &allocation.id,
+                        previous.percentage,
+                        previous.initial_contribution,
+                        previous.start.to_string(),
+                        Some(today_str.clone()),
+                    ))
+                    .await?;
+            }
+        } else {
+            self.goal_repo
+                .close_allocation_versions(allocation.id.clone(), today_str.clone())
+                .await?;
+        }
+        self.goal_repo
+            .insert_allocation_version(Self::new_version(
+                &allocation.id,
+                percentage,
+                amount,
+                today_str.clone(),
+                None,
+            ))
+            .await?;
+
+        let mut updated = allocation.clone();
+        updated.allocation_percentage = percentage;
+        updated.percent_allocation = percentage.round() as i32;
+        updated.init_amount = amount;
+        updated.allocation_amount = amount;
+        updated.allocation_date = Some(today_str);
+        self.goal_repo.update_allocation(updated).await?;
+        Ok(())
+    }
+
+    /// Creates the allocation of a goal on an account it had none on, from `today`
+    async fn create_allocation(
+        &self,
+        goal: &Goal,
+        account_id: &str,
+        percentage: f64,
+        amount: f64,
+        today: NaiveDate,
+    ) -> Result<String> {
+        let today_str = today.to_string();
+        let allocation = GoalsAllocation {
+            id: Uuid::new_v4().to_string(),
+            goal_id: goal.id.clone(),
+            account_id: account_id.to_string(),
+            init_amount: amount,
+            allocation_percentage: percentage,
+            allocation_date: Some(today_str.clone()),
+            percent_allocation: percentage.round() as i32,
+            start_date: Some(today_str.clone()),
+            end_date: goal.due_date.clone(),
+            allocation_amount: amount,
+        };
+        let allocation_id = allocation.id.clone();
+        self.goal_repo
+            .upsert_goal_allocations(vec![allocation])
+            .await?;
+        self.goal_repo
+            .insert_allocation_version(Self::new_version(
+                &allocation_id,
+                percentage,
+                amount,
+                today_str,
+                None,
+            ))
+            .await?;
+        Ok(allocation_id)
+    }
+}
+
+#[async_trait]
+impl GoalWaterfallServiceTrait for GoalWaterfallService {
+    fn preview_waterfall_allocation(&self) -> Result<WaterfallAllocation> {
+        let today = Local::now().date_naive();
+        let (_, _, allocation) = self.run_waterfall(today)?;
+        Ok(allocation)
+    }
+
+    async fn apply_waterfall_allocation(&self) -> Result<WaterfallAllocation> {
+        let today = Local::now().date_naive();
+        let (goals, accounts, mut result) = self.run_waterfall(today)?;
+        let goals_by_id: HashMap<&str, &Goal> = goals.iter().map(|g| (g.id.as_str(), g)).collect();
+
+        for funded in &result.goals {
+            let Some(goal) = goals_by_id.get(funded.goal_id.as_str()) else {
+                continue;
+            };
+            let existing = self.goal_repo.get_allocations_for_goal(&goal.id)?;
+            for account in &accounts {
+                let (percentage, amount) = funded
+                    .shares
+                    .iter()
+                    .find(|s| s.account_id == account.account_id)
+                    .map_or((0.0, 0.0), |s| (s.percentage, s.amount));
+                match existing.iter().find(|a| a.account_id == account.account_id) {
+                    Some(allocation) => {
+                        if (allocation.allocation_percentage - percentage).abs()
+                            <= PERCENTAGE_TOLERANCE
+                        {
+                            continue;
+                        }
+                        self.version_allocation(goal, allocation, percentage, amount, today)
+                            .await?;
+                        result.changed_allocation_ids.push(allocation.id.clone());
+                    }
+                    None if percentage > PERCENTAGE_TOLERANCE => {
+                        let allocation_id = self
+                            .create_allocation(goal, &account.account_id, percentage, amount, today)
+                            .await?;
+                        result.changed_allocation_ids.push(allocation_id);
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        if result.changed_allocation_ids.is_empty() {
+            debug!("Waterfall allocation unchanged");
+        } else {
+            info!(
+                "Waterfall allocation versioned {} allocation(s)",
+                result.changed_allocation_ids.len()
+            );
+        }
+        Ok(result)
+    }
+}
@@ -29,6 +29,10 @@ pub struct Goal {
     pub monthly_investment: Option<f64>,
     pub start_date: Option<String>,
     pub initial_actual_value: Option<f64>,
+    /// Position in the waterfall allocation, lowest first; goals without one are
+    /// allocated manually
+    #[serde(default)]
+    pub priority: Option<i32>,
 }
 
 #[derive(Insertable, Serialize, Deserialize, Debug, Clone)]
@@ -45,6 +49,10 @@ pub struct NewGoal {
     pub monthly_investment: Option<f64>,
     pub start_date: Option<String>,
     pub initial_actual_value: Option<f64>,
+    /// Position in the waterfall allocation, lowest first; goals without one are
+    /// allocated manually
+    #[serde(default)]
+    pub priority: Option<i32>,
 }
 
 #[derive(
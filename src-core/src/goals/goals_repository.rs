@@ -226,6 +226,20 @@ impl GoalRepositoryTrait for GoalRepository {
             .await
     }
 
+    async fn close_allocation_versions(&self, allocation_id_to_close: String, end_date: String) -> Result<usize> {
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<usize> {
+                Ok(diesel::update(
+                    allocation_versions::table
+                        .filter(allocation_versions::allocation_id.eq(allocation_id_to_close))
+                        .filter(allocation_versions::version_end_date.is_null()),
+                )
+                .set(allocation_versions::version_end_date.eq(Some(end_date)))
+                .execute(conn)?)
+            })
+            .await
+    }
+
     async fn update_allocation(&self, allocation: GoalsAllocation) -> Result<GoalsAllocation> {
         let allocation_id_owned = allocation.id.clone();
         let allocation_owned = allocation.clone();
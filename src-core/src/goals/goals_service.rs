@@ -1,4 +1,6 @@
 use crate::errors::Result;
+use crate::goals::goal_projection_model::GoalPlan;
+use crate::goals::goal_solver::plan_goal;
 use crate::goals::goals_model::{Goal, GoalsAllocation, NewGoal};
 use crate::goals::goals_traits::{GoalRepositoryTrait, GoalServiceTrait};
 use crate::goals::goal_progress_model::{GoalProgressSnapshot, AllocationDetail};
@@ -147,6 +149,7 @@ impl<T: GoalRepositoryTrait> GoalService<T> {
             current_value: total_growth,
             growth: total_growth, // growth = current_value - init_value = total_growth - 0
             allocation_details,
+            target_value: None,
         })
     }
 
@@ -443,6 +446,20 @@ impl<T: GoalRepositoryTrait + Send + Sync> GoalServiceTrait for GoalService<T> {
         self.validate_allocation_percentages(account_id, new_percentage, exclude_allocation_id)
     }
 
+    fn get_goal_plan(&self, goal_id: &str, current_value: f64) -> Result<GoalPlan> {
+        let goal = self
+            .goal_repo
+            .load_goals()?
+            .into_iter()
+            .find(|g| g.id == goal_id)
+            .ok_or_else(|| {
+                crate::errors::Error::Validation(crate::errors::ValidationError::InvalidInput(
+                    format!("Goal {} not found", goal_id),
+                ))
+            })?;
+        Ok(plan_goal(&goal, current_value, chrono::Local::now().date_naive()))
+    }
+
     fn get_repository(&self) -> &dyn GoalRepositoryTrait {
         self.goal_repo.as_ref()
     }
@@ -1,6 +1,10 @@
 use crate::errors::Result;
+use crate::goals::goal_progress_model::{GoalHistoryInterval, GoalProgressHistory};
+use crate::goals::goal_projection_model::{GoalPlan, GoalProjection, GoalProjectionOptions};
+use crate::goals::goal_waterfall_model::WaterfallAllocation;
 use crate::goals::goals_model::{Goal, GoalsAllocation, NewGoal, AllocationVersion};
 use async_trait::async_trait;
+use chrono::NaiveDate;
 
 /// Trait for goal repository operations
 #[async_trait]
@@ -24,6 +28,8 @@ pub trait GoalRepositoryTrait: Send + Sync {
     fn get_allocation_by_id(&self, allocation_id: &str) -> Result<GoalsAllocation>;
     fn get_allocations_for_account(&self, account_id: &str) -> Result<Vec<GoalsAllocation>>;
     async fn insert_allocation_version(&self, version: AllocationVersion) -> Result<AllocationVersion>;
+    /// Sets `end_date` on the allocation's versions that are still open
+    async fn close_allocation_versions(&self, allocation_id: String, end_date: String) -> Result<usize>;
     async fn update_allocation(&self, allocation: GoalsAllocation) -> Result<GoalsAllocation>;
     async fn delete_allocation(&self, allocation_id: String) -> Result<usize>;
     /// Reset all allocations for a specific goal to 0 and update their dates (used when goal start_date changes)
@@ -53,5 +59,43 @@ pub trait GoalServiceTrait: Send + Sync {
     fn get_unallocated_balance(&self, account_id: &str, current_account_value: f64) -> Result<f64>;
     fn validate_unallocated_balance(&self, account_id: &str, allocation_amount: f64, current_account_value: f64) -> Result<()>;
     fn validate_allocation_percentages(&self, account_id: &str, new_percentage: f64, exclude_allocation_id: Option<&str>) -> Result<()>;
+    /// Required monthly contribution, completion date at the current pace and required
+    /// return of a goal, given its allocated value today
+    fn get_goal_plan(&self, goal_id: &str, current_value: f64) -> Result<GoalPlan>;
     fn get_repository(&self) -> &dyn GoalRepositoryTrait;
 }
+
+/// Trait for Monte Carlo goal projections
+#[async_trait]
+pub trait GoalProjectionServiceTrait: Send + Sync {
+    /// Simulates the goal's allocated value from today to its due date and returns
+    /// P10/P50/P90 paths with the probability of reaching the target on time
+    async fn project_goal(
+        &self,
+        goal_id: &str,
+        options: GoalProjectionOptions,
+    ) -> Result<GoalProjection>;
+}
+
+/// Trait for goal progress history built from stored account valuations
+pub trait GoalHistoryServiceTrait: Send + Sync {
+    /// Allocated value of the goal at each date from `start_date` (at the earliest the
+    /// goal's start date) to `end_date` (today by default), against its target curve
+    fn get_goal_progress_history(
+        &self,
+        goal_id: &str,
+        interval: GoalHistoryInterval,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<GoalProgressHistory>;
+}
+
+/// Trait for the priority waterfall allocation of account value to goals
+#[async_trait]
+pub trait GoalWaterfallServiceTrait: Send + Sync {
+    /// Runs the waterfall over the active accounts without saving anything
+    fn preview_waterfall_allocation(&self) -> Result<WaterfallAllocation>;
+    /// Runs the waterfall and gives every allocation whose percentage changed a new
+    /// version starting today, creating allocations where needed
+    async fn apply_waterfall_allocation(&self) -> Result<WaterfallAllocation>;
+}
@@ -1,3 +1,12 @@
+pub mod goal_history;
+pub mod goal_history_service;
+pub mod goal_projection_model;
+pub mod goal_projection_service;
+pub mod goal_simulation;
+pub mod goal_solver;
+pub mod goal_waterfall;
+pub mod goal_waterfall_model;
+pub mod goal_waterfall_service;
 pub mod goals_model;
 pub mod goals_repository;
 pub mod goals_service;
@@ -6,6 +15,18 @@ pub mod goal_progress_model;
 
 pub use goals_repository::GoalRepository;
 pub use goals_service::GoalService;
-pub use goals_traits::{GoalRepositoryTrait, GoalServiceTrait};
-pub use goal_progress_model::{GoalProgressSnapshot, GoalProgressHistory, AllocationDetail};
+pub use goals_traits::{
+    GoalHistoryServiceTrait, GoalProjectionServiceTrait, GoalRepositoryTrait, GoalServiceTrait,
+    GoalWaterfallServiceTrait,
+};
+pub use goal_projection_model::{
+    GoalPlan, GoalProjection, GoalProjectionOptions, GoalProjectionPoint, ReturnSource,
+};
+pub use goal_history_service::GoalHistoryService;
+pub use goal_projection_service::GoalProjectionService;
+pub use goal_waterfall_model::{WaterfallAllocation, WaterfallGoal, WaterfallShare};
+pub use goal_waterfall_service::GoalWaterfallService;
+pub use goal_progress_model::{
+    AllocationDetail, GoalHistoryInterval, GoalProgressHistory, GoalProgressSnapshot,
+};
 pub use goals_model::{GoalsAllocation, AllocationVersion};
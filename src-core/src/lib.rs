@@ -2,6 +2,8 @@ pub mod accounts;
 pub mod activities;
 pub mod addons;
 pub mod assets;
+pub mod attachments;
+pub mod composites;
 pub mod constants;
 pub mod db;
 
@@ -9,11 +11,17 @@ pub mod errors;
 pub mod fx;
 pub mod goals;
 pub mod limits;
+pub mod margin;
 pub mod market_data;
 pub mod portfolio;
+pub mod reconciliation;
+pub mod retirement;
 pub mod schema;
 pub mod secrets;
 pub mod settings;
+pub mod tags;
+#[cfg(test)]
+mod test_utils;
 pub mod utils;
 pub mod vn_market;
 pub use assets::*;
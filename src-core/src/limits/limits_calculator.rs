@@ -0,0 +1,348 @@
+use chrono::{Datelike, Days, Months, NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+use crate::errors::{Error, Result, ValidationError};
+
+use super::limits_model::{
+    ContributionLimit, ContributionRoom, ContributionYear, LIMIT_TYPE_CALENDAR_YEAR,
+    LIMIT_TYPE_ROLLING,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContributionFlowKind {
+    Deposit,
+    Withdrawal,
+}
+
+/// A deposit to or withdrawal from one of a group's accounts, in the base currency
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContributionFlow {
+    pub account_id: String,
+    pub date: NaiveDate,
+    pub kind: ContributionFlowKind,
+    pub amount: Decimal,
+}
+
+fn decimal(value: f64) -> Decimal {
+    Decimal::from_f64_retain(value).unwrap_or_default()
+}
+
+/// Parses a limit start or end date, stored as an ISO timestamp or a plain date
+pub fn parse_limit_date(value: &str) -> Option<NaiveDate> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.3fZ")
+        .map(|dt| dt.date())
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y-%m-%d"))
+        .ok()
+}
+
+/// Account ids of a limit, stored comma separated
+pub fn limit_account_ids(limit: &ContributionLimit) -> Vec<String> {
+    limit
+        .account_ids
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn year_bounds(year: i32) -> Result<(NaiveDate, NaiveDate)> {
+    match (
+        NaiveDate::from_ymd_opt(year, 1, 1),
+        NaiveDate::from_ymd_opt(year, 12, 31),
+    ) {
+        (Some(start), Some(end)) => Ok((start, end)),
+        _ => Err(Error::Validation(ValidationError::InvalidInput(format!(
+            "Invalid contribution year {}",
+            year
+        )))),
+    }
+}
+
+/// Checks the settings that depend on the limit type
+pub fn validate_limit_settings(
+    limit_type: &str,
+    window_months: Option<i32>,
+    lifetime_cap: Option<f64>,
+) -> Result<()> {
+    match limit_type {
+        LIMIT_TYPE_CALENDAR_YEAR => {}
+        LIMIT_TYPE_ROLLING => {
+            if window_months.is_none_or(|m| m <= 0) {
+                return Err(Error::Validation(ValidationError::InvalidInput(
+                    "A rolling limit needs a window of at least one month".to_string(),
+                )));
+            }
+        }
+        other => {
+            return Err(Error::Validation(ValidationError::InvalidInput(format!(
+                "Unknown contribution limit type {}",
+                other
+            ))));
+        }
+    }
+    if lifetime_cap.is_some_and(|cap| cap < 0.0) {
+        return Err(Error::Validation(ValidationError::InvalidInput(
+            "Lifetime cap cannot be negative".to_string(),
+        )));
+    }
+    Ok(())
+}
+
+/// First and last day of the period a limit applies to on `as_of`
+pub fn limit_period(limit: &ContributionLimit, as_of: NaiveDate) -> Result<(NaiveDate, NaiveDate)> {
+    validate_limit_settings(&limit.limit_type, limit.window_months, limit.lifetime_cap)?;
+    if limit.limit_type == LIMIT_TYPE_ROLLING {
+        let months = limit.window_months.unwrap_or_default() as u32;
+        let start = as_of - Months::new(months) + Days::new(1);
+        return Ok((start, as_of));
+    }
+    let start = limit.start_date.as_deref().and_then(parse_limit_date);
+    let end = limit.end_date.as_deref().and_then(parse_limit_date);
+    match (start, end) {
+        (Some(start), Some(end)) => Ok((start, end)),
+        _ => year_bounds(limit.contribution_year),
+    }
+}
+
+fn sum_flows(
+    flows: &[ContributionFlow],
+    kind: ContributionFlowKind,
+    include: impl Fn(NaiveDate) -> bool,
+) -> Decimal {
+    flows
+        .iter()
+        .filter(|f| f.kind == kind && include(f.date))
+        .map(|f| f.amount)
+        .sum()
+}
+
+/// Calculates the room of `limit` on `as_of` from the flows of its accounts.
+///
+/// Calendar-year limits chain through the earlier years of the same group in
+/// `group_limits`: with `carry_forward` the unused (or overdrawn) room of each year
+/// moves to the next, and with `restore_withdrawals` each year's withdrawals add to
+/// the following year's room. Rolling limits only count the deposits of their window.
+pub fn calculate_contribution_room(
+    limit: &ContributionLimit,
+    group_limits: &[ContributionLimit],
+    flows: &[ContributionFlow],
+    as_of: NaiveDate,
+    base_currency: &str,
+) -> Result<ContributionRoom> {
+    let (period_start, period_end) = limit_period(limit, as_of)?;
+    let withdrawals_in_year = |year: i32| {
+        sum_flows(flows, ContributionFlowKind::Withdrawal, |d| {
+            d.year() == year
+        })
+    };
+
+    let mut years = Vec::new();
+    let mut carried_forward = Decimal::ZERO;
+    let mut restored_withdrawals = Decimal::ZERO;
+    if limit.limit_type == LIMIT_TYPE_CALENDAR_YEAR {
+        let year = period_start.year();
+        let earlier: Vec<&ContributionLimit> = group_limits
+            .iter()
+            .filter(|l| {
+                l.id != limit.id
+                    && l.group_name == limit.group_name
+                    && l.limit_type == LIMIT_TYPE_CALENDAR_YEAR
+                    && l.contribution_year < year
+            })
+            .collect();
+        let first_year = earlier
+            .iter()
+            .map(|l| l.contribution_year)
+            .min()
+            .unwrap_or(year);
+
+        if limit.carry_forward {
+            for y in first_year..year {
+                let limit_amount: Decimal = earlier
+                    .iter()
+                    .filter(|l| l.contribution_year == y)
+                    .map(|l| decimal(l.limit_amount))
+                    .sum();
+                let restored = if limit.restore_withdrawals {
+                    withdrawals_in_year(y - 1)
+                } else {
+                    Decimal::ZERO
+                };
+                let available_room = limit_amount + carried_forward + restored;
+                let deposits = sum_flows(flows, ContributionFlowKind::Deposit, |d| d.year() == y);
+                let unused_room = available_room - deposits;
+                years.push(ContributionYear {
+                    year: y,
+                    limit_amount,
+                    carried_forward,
+                    restored_withdrawals: restored,
+                    available_room,
+                    deposits,
+                    withdrawals: withdrawals_in_year(y),
+                    unused_room,
+                });
+                carried_forward = unused_room;
+            }
+        }
+        if limit.restore_withdrawals {
+            restored_withdrawals = withdrawals_in_year(year - 1);
+        }
+    }
+
+    let limit_amount = decimal(limit.limit_amount);
+    let available_room = limit_amount + carried_forward + restored_withdrawals;
+    let in_period = |d: NaiveDate| d >= period_start && d <= period_end;
+    let mut by_account: HashMap<String, Decimal> = HashMap::new();
+    for flow in flows
+        .iter()
+        .filter(|f| f.kind == ContributionFlowKind::Deposit && in_period(f.date))
+    {
+        *by_account.entry(flow.account_id.clone()).or_default() += flow.amount;
+    }
+    let contributed: Decimal = by_account.values().copied().sum();
+    let remaining_room = available_room - contributed;
+
+    let lifetime_cap = limit.lifetime_cap.map(decimal);
+    let mut lifetime_contributed =
+        sum_flows(flows, ContributionFlowKind::Deposit, |d| d <= period_end);
+    if limit.restore_withdrawals {
+        lifetime_contributed -= sum_flows(flows, ContributionFlowKind::Withdrawal, |d| {
+            d < period_start
+        });
+    }
+    let lifetime_remaining = lifetime_cap.map(|cap| cap - lifetime_contributed);
+    let over_contribution = (-remaining_room)
+        .max(lifetime_remaining.map_or(Decimal::ZERO, |r| -r))
+        .max(Decimal::ZERO);
+
+    Ok(ContributionRoom {
+        limit_id: limit.id.clone(),
+        group_name: limit.group_name.clone(),
+        limit_type: limit.limit_type.clone(),
+        base_currency: base_currency.to_string(),
+        period_start,
+        period_end,
+        limit_amount,
+        carried_forward,
+        restored_withdrawals,
+        available_room,
+        contributed,
+        remaining_room,
+        lifetime_cap,
+        lifetime_contributed,
+        lifetime_remaining,
+        over_contribution,
+        by_account,
+        years,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::date;
+    use chrono::Utc;
+    use rust_decimal_macros::dec;
+
+    fn limit(id: &str, year: i32, amount: f64) -> ContributionLimit {
+        let now = Utc::now().naive_utc();
+        ContributionLimit {
+            id: id.to_string(),
+            group_name: "TFSA".to_string(),
+            contribution_year: year,
+            limit_amount: amount,
+            account_ids: Some("a1, a2".to_string()),
+            created_at: now,
+            updated_at: now,
+            start_date: None,
+            end_date: None,
+            limit_type: LIMIT_TYPE_CALENDAR_YEAR.to_string(),
+            window_months: None,
+            carry_forward: false,
+            restore_withdrawals: false,
+            lifetime_cap: None,
+        }
+    }
+
+    fn flow(kind: ContributionFlowKind, on: NaiveDate, amount: Decimal) -> ContributionFlow {
+        ContributionFlow {
+            account_id: "a1".to_string(),
+            date: on,
+            kind,
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_limit_account_ids() {
+        assert_eq!(limit_account_ids(&limit("l", 2025, 1.0)), vec!["a1", "a2"]);
+    }
+
+    #[test]
+    fn test_carry_forward_and_restored_withdrawals() {
+        let mut current = limit("l2025", 2025, 7000.0);
+        current.carry_forward = true;
+        current.restore_withdrawals = true;
+        let group = vec![
+            limit("l2023", 2023, 6500.0),
+            limit("l2024", 2024, 7000.0),
+            current.clone(),
+        ];
+        let flows = vec![
+            flow(ContributionFlowKind::Deposit, date(2023, 3, 1), dec!(5000)),
+            flow(ContributionFlowKind::Deposit, date(2024, 3, 1), dec!(9000)),
+            flow(
+                ContributionFlowKind::Withdrawal,
+                date(2024, 8, 1),
+                dec!(2000),
+            ),
+            flow(ContributionFlowKind::Deposit, date(2025, 2, 1), dec!(4000)),
+        ];
+
+        let room = calculate_contribution_room(&current, &group, &flows, date(2025, 6, 30), "USD")
+            .unwrap();
+        assert_eq!(room.years.len(), 2);
+        assert_eq!(room.years[0].unused_room, dec!(1500));
+        // 7000 + 1500 carried - 9000 deposited
+        assert_eq!(room.years[1].unused_room, dec!(-500));
+        assert_eq!(room.carried_forward, dec!(-500));
+        assert_eq!(room.restored_withdrawals, dec!(2000));
+        assert_eq!(room.available_room, dec!(8500));
+        assert_eq!(room.contributed, dec!(4000));
+        assert_eq!(room.remaining_room, dec!(4500));
+        assert_eq!(room.over_contribution, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_rolling_window_and_lifetime_cap() {
+        let mut rolling = limit("roll", 2025, 10000.0);
+        rolling.limit_type = LIMIT_TYPE_ROLLING.to_string();
+        rolling.window_months = Some(12);
+        rolling.lifetime_cap = Some(20000.0);
+        let flows = vec![
+            flow(ContributionFlowKind::Deposit, date(2023, 5, 1), dec!(9000)),
+            flow(ContributionFlowKind::Deposit, date(2024, 6, 30), dec!(6000)),
+            flow(ContributionFlowKind::Deposit, date(2024, 7, 1), dec!(4000)),
+            flow(ContributionFlowKind::Deposit, date(2025, 6, 1), dec!(3000)),
+        ];
+
+        let room =
+            calculate_contribution_room(&rolling, &[], &flows, date(2025, 6, 30), "USD").unwrap();
+        assert_eq!(room.period_start, date(2024, 7, 1));
+        assert_eq!(room.contributed, dec!(7000));
+        assert_eq!(room.remaining_room, dec!(3000));
+        assert_eq!(room.lifetime_contributed, dec!(22000));
+        assert_eq!(room.lifetime_remaining, Some(dec!(-2000)));
+        assert_eq!(room.over_contribution, dec!(2000));
+    }
+
+    #[test]
+    fn test_rolling_limit_needs_window() {
+        let mut rolling = limit("roll", 2025, 10000.0);
+        rolling.limit_type = LIMIT_TYPE_ROLLING.to_string();
+        assert!(limit_period(&rolling, date(2025, 1, 1)).is_err());
+    }
+}
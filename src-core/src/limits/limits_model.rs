@@ -1,9 +1,20 @@
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use diesel::prelude::*;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::activities::Activity;
+
+/// Room renews every calendar year (`contribution_year`, or `start_date`..`end_date`)
+pub const LIMIT_TYPE_CALENDAR_YEAR: &str = "CALENDAR_YEAR";
+/// Room applies to the deposits of the last `window_months` months
+pub const LIMIT_TYPE_ROLLING: &str = "ROLLING";
+
+fn default_limit_type() -> String {
+    LIMIT_TYPE_CALENDAR_YEAR.to_string()
+}
+
 #[derive(Queryable, Insertable, Identifiable, Serialize, Deserialize, Debug, Clone)]
 #[diesel(table_name = crate::schema::contribution_limits)]
 #[serde(rename_all = "camelCase")]
@@ -17,6 +28,14 @@ pub struct ContributionLimit {
     pub updated_at: NaiveDateTime,
     pub start_date: Option<String>,
     pub end_date: Option<String>,
+    pub limit_type: String,
+    pub window_months: Option<i32>,
+    /// Unused room of earlier years of the same group adds to this year's room
+    pub carry_forward: bool,
+    /// Withdrawals add back to the room of the following year
+    pub restore_withdrawals: bool,
+    /// Cap on all deposits ever made to the group's accounts
+    pub lifetime_cap: Option<f64>,
 }
 
 #[derive(Insertable, AsChangeset, Serialize, Deserialize, Debug, Clone)]
@@ -30,6 +49,16 @@ pub struct NewContributionLimit {
     pub account_ids: Option<String>,
     pub start_date: Option<String>,
     pub end_date: Option<String>,
+    #[serde(default = "default_limit_type")]
+    pub limit_type: String,
+    #[serde(default)]
+    pub window_months: Option<i32>,
+    #[serde(default)]
+    pub carry_forward: bool,
+    #[serde(default)]
+    pub restore_withdrawals: bool,
+    #[serde(default)]
+    pub lifetime_cap: Option<f64>,
 }
 
 #[derive(Serialize, Debug)]
@@ -47,3 +76,70 @@ pub struct DepositsCalculation {
     pub base_currency: String,
     pub by_account: HashMap<String, AccountDeposit>,
 }
+
+/// Room of one calendar year in the carry-forward chain of a limit group
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ContributionYear {
+    pub year: i32,
+    pub limit_amount: Decimal,
+    pub carried_forward: Decimal,
+    pub restored_withdrawals: Decimal,
+    pub available_room: Decimal,
+    pub deposits: Decimal,
+    pub withdrawals: Decimal,
+    /// Room left at the end of the year; negative when over-contributed
+    pub unused_room: Decimal,
+}
+
+/// Contribution room of a limit, with every amount in the base currency converted
+/// at the exchange rate of its activity date
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ContributionRoom {
+    pub limit_id: String,
+    pub group_name: String,
+    pub limit_type: String,
+    pub base_currency: String,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub limit_amount: Decimal,
+    pub carried_forward: Decimal,
+    pub restored_withdrawals: Decimal,
+    /// `limit_amount + carried_forward + restored_withdrawals`
+    pub available_room: Decimal,
+    /// Deposits within the period
+    pub contributed: Decimal,
+    /// Negative when over-contributed
+    pub remaining_room: Decimal,
+    pub lifetime_cap: Option<Decimal>,
+    pub lifetime_contributed: Decimal,
+    pub lifetime_remaining: Option<Decimal>,
+    /// Amount above the period room or the lifetime cap, whichever is larger
+    pub over_contribution: Decimal,
+    /// Deposits within the period per account
+    pub by_account: HashMap<String, Decimal>,
+    /// Earlier years of the carry-forward chain, oldest first
+    pub years: Vec<ContributionYear>,
+}
+
+/// Raised when a deposit takes a limit group over its room
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ContributionLimitWarning {
+    pub limit_id: String,
+    pub group_name: String,
+    pub over_contribution: Decimal,
+    pub remaining_room: Decimal,
+    pub currency: String,
+    pub message: String,
+}
+
+/// A created activity with the contribution limits it went over
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityWithLimitWarnings {
+    #[serde(flatten)]
+    pub activity: Activity,
+    pub contribution_warnings: Vec<ContributionLimitWarning>,
+}
@@ -66,6 +66,12 @@ impl ContributionLimitRepositoryTrait for ContributionLimitRepository {
                         contribution_limits::account_ids.eq(new_limit_owned.account_ids),
                         contribution_limits::start_date.eq(new_limit_owned.start_date),
                         contribution_limits::end_date.eq(new_limit_owned.end_date),
+                        contribution_limits::limit_type.eq(new_limit_owned.limit_type),
+                        contribution_limits::window_months.eq(new_limit_owned.window_months),
+                        contribution_limits::carry_forward.eq(new_limit_owned.carry_forward),
+                        contribution_limits::restore_withdrawals
+                            .eq(new_limit_owned.restore_withdrawals),
+                        contribution_limits::lifetime_cap.eq(new_limit_owned.lifetime_cap),
                         contribution_limits::created_at.eq(chrono::Utc::now().naive_utc()),
                         contribution_limits::updated_at.eq(chrono::Utc::now().naive_utc()),
                     );
@@ -100,6 +106,14 @@ impl ContributionLimitRepositoryTrait for ContributionLimitRepository {
                             contribution_limits::account_ids.eq(updated_limit_owned.account_ids),
                             contribution_limits::start_date.eq(updated_limit_owned.start_date),
                             contribution_limits::end_date.eq(updated_limit_owned.end_date),
+                            contribution_limits::limit_type.eq(updated_limit_owned.limit_type),
+                            contribution_limits::window_months
+                                .eq(updated_limit_owned.window_months),
+                            contribution_limits::carry_forward
+                                .eq(updated_limit_owned.carry_forward),
+                            contribution_limits::restore_withdrawals
+                                .eq(updated_limit_owned.restore_withdrawals),
+                            contribution_limits::lifetime_cap.eq(updated_limit_owned.lifetime_cap),
                             contribution_limits::updated_at.eq(chrono::Utc::now().naive_utc()),
                         ))
                         .get_result(conn)
@@ -1,15 +1,22 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use chrono::NaiveDateTime;
+use chrono::{Local, NaiveDateTime};
+use log::debug;
 use rust_decimal::Decimal;
 
 use crate::activities::activities_traits::ActivityRepositoryTrait;
+use crate::activities::{ACTIVITY_TYPE_DEPOSIT, ACTIVITY_TYPE_WITHDRAWAL};
 use crate::errors::{Error, Result, ValidationError};
 use crate::fx::fx_traits::FxServiceTrait;
 
+use super::limits_calculator::{
+    calculate_contribution_room, limit_account_ids, limit_period, validate_limit_settings,
+    ContributionFlow, ContributionFlowKind,
+};
 use super::limits_model::{
-    AccountDeposit, ContributionLimit, DepositsCalculation, NewContributionLimit,
+    AccountDeposit, ContributionLimit, ContributionLimitWarning, ContributionRoom,
+    DepositsCalculation, NewContributionLimit, LIMIT_TYPE_CALENDAR_YEAR,
 };
 use super::limits_traits::{ContributionLimitRepositoryTrait, ContributionLimitServiceTrait};
 use async_trait::async_trait;
@@ -55,20 +62,24 @@ impl ContributionLimitService {
         let mut total_deposits = Decimal::ZERO;
         let mut deposits_by_account: HashMap<String, AccountDeposit> = HashMap::new();
 
-        for (account_id, _quantity, _unit_price, currency, amount_opt) in deposit_activities {
-            let amount = amount_opt.ok_or_else(|| {
+        for activity in deposit_activities {
+            let amount = activity.amount.ok_or_else(|| {
                 Error::Validation(ValidationError::MissingField(
                     "Amount is missing in DEPOSIT activity".to_string(),
                 ))
             })?;
 
-            let converted_amount =
-                self.fx_service
-                    .convert_currency(amount, &currency, base_currency)?;
+            let converted_amount = self.fx_service.convert_currency_for_date(
+                amount,
+                &activity.currency,
+                base_currency,
+                activity.activity_date.date_naive(),
+            )?;
 
             total_deposits += &converted_amount;
+            let currency = activity.currency;
             let account_deposit = deposits_by_account
-                .entry(account_id.clone())
+                .entry(activity.account_id)
                 .or_insert_with(|| AccountDeposit {
                     amount: Decimal::ZERO,
                     currency: currency.clone(),
@@ -86,6 +97,61 @@ impl ContributionLimitService {
             by_account: deposits_by_account,
         })
     }
+
+    /// Deposits and withdrawals of the accounts, converted at their activity date
+    fn contribution_flows(
+        &self,
+        account_ids: &[String],
+        base_currency: &str,
+    ) -> Result<Vec<ContributionFlow>> {
+        if account_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.activity_repository
+            .get_activities_by_account_ids(account_ids)?
+            .into_iter()
+            .filter_map(|activity| {
+                let kind = match activity.activity_type.as_str() {
+                    ACTIVITY_TYPE_DEPOSIT => ContributionFlowKind::Deposit,
+                    ACTIVITY_TYPE_WITHDRAWAL => ContributionFlowKind::Withdrawal,
+                    _ => return None,
+                };
+                Some((kind, activity))
+            })
+            .map(|(kind, activity)| {
+                let amount = activity.amount.ok_or_else(|| {
+                    Error::Validation(ValidationError::MissingField(format!(
+                        "Amount is missing in {} activity",
+                        activity.activity_type
+                    )))
+                })?;
+                let date = activity.activity_date.date_naive();
+                let amount = self.fx_service.convert_currency_for_date(
+                    amount,
+                    &activity.currency,
+                    base_currency,
+                    date,
+                )?;
+                Ok(ContributionFlow {
+                    account_id: activity.account_id,
+                    date,
+                    kind,
+                    amount,
+                })
+            })
+            .collect()
+    }
+
+    fn room_for_limit(
+        &self,
+        limit: &ContributionLimit,
+        group_limits: &[ContributionLimit],
+        as_of: chrono::NaiveDate,
+        base_currency: &str,
+    ) -> Result<ContributionRoom> {
+        let flows = self.contribution_flows(&limit_account_ids(limit), base_currency)?;
+        calculate_contribution_room(limit, group_limits, &flows, as_of, base_currency)
+    }
 }
 
 #[async_trait]
@@ -98,6 +164,11 @@ impl ContributionLimitServiceTrait for ContributionLimitService {
         &self,
         new_limit: NewContributionLimit,
     ) -> Result<ContributionLimit> {
+        validate_limit_settings(
+            &new_limit.limit_type,
+            new_limit.window_months,
+            new_limit.lifetime_cap,
+        )?;
         self.limit_repository
             .create_contribution_limit(new_limit)
             .await
@@ -108,6 +179,11 @@ impl ContributionLimitServiceTrait for ContributionLimitService {
         id: &str,
         updated_limit: NewContributionLimit,
     ) -> Result<ContributionLimit> {
+        validate_limit_settings(
+            &updated_limit.limit_type,
+            updated_limit.window_months,
+            updated_limit.lifetime_cap,
+        )?;
         self.limit_repository
             .update_contribution_limit(id, updated_limit)
             .await
@@ -165,4 +241,61 @@ impl ContributionLimitServiceTrait for ContributionLimitService {
             self.calculate_deposits_by_period(&account_ids, start, end, base_currency)
         }
     }
+
+    fn get_contribution_room(
+        &self,
+        limit_id: &str,
+        base_currency: &str,
+    ) -> Result<ContributionRoom> {
+        let limit = self.limit_repository.get_contribution_limit(limit_id)?;
+        let group_limits = self.limit_repository.get_contribution_limits()?;
+        let today = Local::now().date_naive();
+        self.room_for_limit(&limit, &group_limits, today, base_currency)
+    }
+
+    fn check_deposit_limits(
+        &self,
+        activity_id: &str,
+        base_currency: &str,
+    ) -> Result<Vec<ContributionLimitWarning>> {
+        let activity = self.activity_repository.get_activity(activity_id)?;
+        if activity.activity_type != ACTIVITY_TYPE_DEPOSIT {
+            return Ok(Vec::new());
+        }
+        let date = activity.activity_date.date_naive();
+        let limits = self.limit_repository.get_contribution_limits()?;
+        let mut warnings = Vec::new();
+        for limit in &limits {
+            if !limit_account_ids(limit).contains(&activity.account_id) {
+                continue;
+            }
+            if limit.limit_type == LIMIT_TYPE_CALENDAR_YEAR {
+                let (start, end) = limit_period(limit, date)?;
+                if date < start || date > end {
+                    continue;
+                }
+            }
+            let room = self.room_for_limit(limit, &limits, date, base_currency)?;
+            if room.over_contribution > Decimal::ZERO {
+                debug!(
+                    "Deposit {} goes over contribution limit {} by {}",
+                    activity.id, limit.id, room.over_contribution
+                );
+                warnings.push(ContributionLimitWarning {
+                    limit_id: limit.id.clone(),
+                    group_name: limit.group_name.clone(),
+                    over_contribution: room.over_contribution,
+                    remaining_room: room.remaining_room,
+                    currency: base_currency.to_string(),
+                    message: format!(
+                        "This deposit exceeds the {} contribution limit by {} {}",
+                        limit.group_name,
+                        room.over_contribution.round_dp(2),
+                        base_currency
+                    ),
+                });
+            }
+        }
+        Ok(warnings)
+    }
 }
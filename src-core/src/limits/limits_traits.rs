@@ -1,4 +1,7 @@
-use super::limits_model::{ContributionLimit, DepositsCalculation, NewContributionLimit};
+use super::limits_model::{
+    ContributionLimit, ContributionLimitWarning, ContributionRoom, DepositsCalculation,
+    NewContributionLimit,
+};
 use crate::errors::Result;
 use async_trait::async_trait;
 
@@ -38,5 +41,18 @@ pub trait ContributionLimitServiceTrait: Send + Sync {
         limit_id: &str,
         base_currency: &str,
     ) -> Result<DepositsCalculation>;
+    /// Room left under a limit today, including carried-forward room, restored
+    /// withdrawals and the lifetime cap
+    fn get_contribution_room(
+        &self,
+        limit_id: &str,
+        base_currency: &str,
+    ) -> Result<ContributionRoom>;
+    /// Limits a recorded deposit takes over their room; empty for other activities
+    fn check_deposit_limits(
+        &self,
+        activity_id: &str,
+        base_currency: &str,
+    ) -> Result<Vec<ContributionLimitWarning>>;
     // Note: calculate_deposits_by_period might be better as a private helper or part of the trait if needed elsewhere
 }
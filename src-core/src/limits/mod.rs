@@ -1,10 +1,13 @@
+mod limits_calculator;
 mod limits_model;
 mod limits_repository;
 mod limits_service;
 mod limits_traits;
 
 pub use limits_model::{
-    AccountDeposit, ContributionLimit, DepositsCalculation, NewContributionLimit,
+    AccountDeposit, ActivityWithLimitWarnings, ContributionLimit, ContributionLimitWarning,
+    ContributionRoom, ContributionYear, DepositsCalculation, NewContributionLimit,
+    LIMIT_TYPE_CALENDAR_YEAR, LIMIT_TYPE_ROLLING,
 };
 pub use limits_repository::ContributionLimitRepository;
 pub use limits_service::ContributionLimitService;
@@ -0,0 +1,252 @@
+use chrono::{Datelike, NaiveDate};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use super::margin_model::{InterestCharge, MarginCollateral, MarginSettings, MarginStatus};
+
+/// Vietnamese brokers quote margin rates on an actual/365 basis
+const DAYS_PER_YEAR: Decimal = dec!(365);
+/// Charges are rounded to this many decimal places before being posted
+const CHARGE_DP: u32 = 2;
+
+/// Splits a net cash balance into the cash held and the amount borrowed
+pub fn split_cash(net_cash: Decimal) -> (Decimal, Decimal) {
+    if net_cash.is_sign_negative() {
+        (Decimal::ZERO, -net_cash)
+    } else {
+        (net_cash, Decimal::ZERO)
+    }
+}
+
+/// Interest on one day's closing loan balance
+pub fn daily_interest(loan_balance: Decimal, annual_rate: Decimal) -> Decimal {
+    if loan_balance <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+    loan_balance * annual_rate / DAYS_PER_YEAR
+}
+
+/// Accrues interest on each day's loan balance and groups it into one charge per
+/// calendar month, dated on the last day accrued in that month. Months that owe
+/// nothing produce no charge.
+///
+/// `daily_loans` must be in date order with one entry per calendar day.
+pub fn interest_charges(
+    daily_loans: &[(NaiveDate, Decimal)],
+    annual_rate: Decimal,
+) -> Vec<InterestCharge> {
+    let mut charges: Vec<InterestCharge> = Vec::new();
+    let mut current: Option<(InterestCharge, Decimal)> = None;
+
+    for (date, loan) in daily_loans {
+        let interest = daily_interest(*loan, annual_rate);
+        match current.as_mut() {
+            Some((charge, accrued))
+                if charge.charge_date.year() == date.year()
+                    && charge.charge_date.month() == date.month() =>
+            {
+                charge.charge_date = *date;
+                charge.days += 1;
+                *accrued += interest;
+            }
+            _ => {
+                if let Some(done) = current.take() {
+                    push_charge(&mut charges, done);
+                }
+                current = Some((
+                    InterestCharge {
+                        from_date: *date,
+                        charge_date: *date,
+                        days: 1,
+                        amount: Decimal::ZERO,
+                    },
+                    interest,
+                ));
+            }
+        }
+    }
+    if let Some(done) = current.take() {
+        push_charge(&mut charges, done);
+    }
+    charges
+}
+
+fn push_charge(
+    charges: &mut Vec<InterestCharge>,
+    (mut charge, accrued): (InterestCharge, Decimal),
+) {
+    charge.amount = accrued.round_dp(CHARGE_DP);
+    if charge.amount > Decimal::ZERO {
+        charges.push(charge);
+    }
+}
+
+/// Values a security as collateral after the broker's haircut
+pub fn collateral(
+    asset_id: &str,
+    quantity: Decimal,
+    market_value: Decimal,
+    haircut: Decimal,
+) -> MarginCollateral {
+    let haircut = haircut.clamp(Decimal::ZERO, Decimal::ONE);
+    MarginCollateral {
+        asset_id: asset_id.to_string(),
+        quantity,
+        market_value,
+        haircut,
+        collateral_value: market_value.max(Decimal::ZERO) * (Decimal::ONE - haircut),
+    }
+}
+
+/// Share of the haircut-adjusted assets that is the investor's own money. `None`
+/// while nothing is borrowed.
+pub fn margin_ratio(
+    collateral_value: Decimal,
+    cash_balance: Decimal,
+    loan_balance: Decimal,
+) -> Option<Decimal> {
+    if loan_balance <= Decimal::ZERO {
+        return None;
+    }
+    let assets = collateral_value + cash_balance;
+    if assets <= Decimal::ZERO {
+        // Borrowed with nothing to back it: treat as fully called
+        return Some(Decimal::ZERO);
+    }
+    Some((assets - loan_balance) / assets)
+}
+
+/// Classifies a margin ratio against the broker's thresholds and describes anything
+/// the user should act on
+pub fn margin_status(
+    ratio: Option<Decimal>,
+    settings: &MarginSettings,
+) -> (MarginStatus, Option<String>) {
+    let Some(ratio) = ratio else {
+        return (MarginStatus::NoLoan, None);
+    };
+    let pct = |r: Decimal| (r * dec!(100)).round_dp(2);
+
+    if let Some(force_sell) = settings.force_sell_ratio {
+        if ratio < force_sell {
+            return (
+                MarginStatus::ForceSell,
+                Some(format!(
+                    "Margin ratio {}% is below the force-sell level of {}%; the broker may sell collateral",
+                    pct(ratio),
+                    pct(force_sell)
+                )),
+            );
+        }
+    }
+    if ratio < settings.call_ratio {
+        return (
+            MarginStatus::Call,
+            Some(format!(
+                "Margin ratio {}% is below the call level of {}%; deposit cash or sell to restore it",
+                pct(ratio),
+                pct(settings.call_ratio)
+            )),
+        );
+    }
+    if ratio < settings.call_ratio + settings.warning_buffer {
+        return (
+            MarginStatus::Warning,
+            Some(format!(
+                "Margin ratio {}% is within {} points of the call level of {}%",
+                pct(ratio),
+                pct(settings.warning_buffer),
+                pct(settings.call_ratio)
+            )),
+        );
+    }
+    (MarginStatus::Safe, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    fn settings() -> MarginSettings {
+        MarginSettings {
+            account_id: "acc1".to_string(),
+            annual_interest_rate: dec!(0.13),
+            call_ratio: dec!(0.40),
+            force_sell_ratio: Some(dec!(0.30)),
+            warning_buffer: dec!(0.05),
+            default_haircut: dec!(0.5),
+            haircuts: HashMap::from([("HPG".to_string(), dec!(0.6))]),
+            interest_start_date: d(2025, 1, 1),
+            accrued_through: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_split_cash() {
+        assert_eq!(split_cash(dec!(100)), (dec!(100), dec!(0)));
+        assert_eq!(split_cash(dec!(-250)), (dec!(0), dec!(250)));
+    }
+
+    #[test]
+    fn test_interest_charges_group_by_month_and_skip_unborrowed_days() {
+        let daily_loans: Vec<(NaiveDate, Decimal)> = vec![
+            (d(2025, 1, 30), dec!(365000000)),
+            (d(2025, 1, 31), dec!(365000000)),
+            (d(2025, 2, 1), dec!(0)),
+            (d(2025, 2, 2), dec!(730000000)),
+            (d(2025, 3, 1), dec!(-5)),
+        ];
+        let charges = interest_charges(&daily_loans, dec!(0.13));
+
+        assert_eq!(charges.len(), 2);
+        assert_eq!(charges[0].from_date, d(2025, 1, 30));
+        assert_eq!(charges[0].charge_date, d(2025, 1, 31));
+        assert_eq!(charges[0].days, 2);
+        assert_eq!(charges[0].amount, dec!(260000));
+        assert_eq!(charges[1].charge_date, d(2025, 2, 2));
+        assert_eq!(charges[1].days, 2);
+        assert_eq!(charges[1].amount, dec!(260000));
+    }
+
+    #[test]
+    fn test_collateral_applies_haircut() {
+        let s = settings();
+        let hpg = collateral("HPG", dec!(1000), dec!(25000000), s.haircut_for("HPG"));
+        let fpt = collateral("FPT", dec!(100), dec!(12000000), s.haircut_for("FPT"));
+
+        assert_eq!(hpg.collateral_value, dec!(10000000));
+        assert_eq!(fpt.collateral_value, dec!(6000000));
+    }
+
+    #[test]
+    fn test_margin_ratio_and_status() {
+        let s = settings();
+
+        assert_eq!(margin_ratio(dec!(100), dec!(50), dec!(0)), None);
+        assert_eq!(margin_status(None, &s).0, MarginStatus::NoLoan);
+
+        let safe = margin_ratio(dec!(100), dec!(0), dec!(50));
+        assert_eq!(safe, Some(dec!(0.5)));
+        assert_eq!(margin_status(safe, &s), (MarginStatus::Safe, None));
+
+        let warning = margin_ratio(dec!(100), dec!(0), dec!(58));
+        let (status, message) = margin_status(warning, &s);
+        assert_eq!(status, MarginStatus::Warning);
+        assert!(message.unwrap().contains("42"));
+
+        let call = margin_ratio(dec!(100), dec!(0), dec!(65));
+        assert_eq!(margin_status(call, &s).0, MarginStatus::Call);
+
+        let force_sell = margin_ratio(dec!(100), dec!(0), dec!(75));
+        assert_eq!(margin_status(force_sell, &s).0, MarginStatus::ForceSell);
+
+        assert_eq!(margin_ratio(dec!(0), dec!(0), dec!(10)), Some(dec!(0)));
+    }
+}
@@ -0,0 +1,175 @@
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Margin lending terms of an account.
+///
+/// Ratios and haircuts are fractions (`0.13` for 13%). A haircut is the share of a
+/// security's market value the broker does not lend against, so a symbol with a 50%
+/// loan rate has a haircut of `0.5`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginSettings {
+    pub account_id: String,
+    pub annual_interest_rate: Decimal,
+    /// The broker issues a margin call when the margin ratio falls below this
+    pub call_ratio: Decimal,
+    /// The broker sells collateral when the margin ratio falls below this
+    pub force_sell_ratio: Option<Decimal>,
+    /// A warning is raised once the margin ratio is within this distance of the call ratio
+    pub warning_buffer: Decimal,
+    /// Haircut for symbols without their own entry
+    pub default_haircut: Decimal,
+    /// asset_id -> haircut
+    #[serde(default)]
+    pub haircuts: HashMap<String, Decimal>,
+    /// First day interest accrues on
+    pub interest_start_date: NaiveDate,
+    /// Last day interest has been charged for
+    pub accrued_through: Option<NaiveDate>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl MarginSettings {
+    pub fn haircut_for(&self, asset_id: &str) -> Decimal {
+        self.haircuts
+            .get(asset_id)
+            .copied()
+            .unwrap_or(self.default_haircut)
+    }
+}
+
+/// Input model for enabling margin on an account or changing its terms
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginSettingsUpdate {
+    pub account_id: String,
+    pub annual_interest_rate: Decimal,
+    pub call_ratio: Decimal,
+    pub force_sell_ratio: Option<Decimal>,
+    pub warning_buffer: Option<Decimal>,
+    pub default_haircut: Option<Decimal>,
+    #[serde(default)]
+    pub haircuts: HashMap<String, Decimal>,
+    /// Defaults to today when margin is first enabled
+    pub interest_start_date: Option<NaiveDate>,
+}
+
+/// How close an account is to a margin call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MarginStatus {
+    /// No loan outstanding
+    NoLoan,
+    Safe,
+    /// Within the warning buffer of the call ratio
+    Warning,
+    /// Below the call ratio
+    Call,
+    /// Below the force-sell ratio
+    ForceSell,
+}
+
+/// A security counted as collateral, in the account currency
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginCollateral {
+    pub asset_id: String,
+    pub quantity: Decimal,
+    pub market_value: Decimal,
+    pub haircut: Decimal,
+    /// `market_value * (1 - haircut)`
+    pub collateral_value: Decimal,
+}
+
+/// Loan, collateral and margin ratio of an account, in the account currency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginSummary {
+    pub account_id: String,
+    pub currency: String,
+    /// Positive cash; negative cash is reported as `loan_balance` instead
+    pub cash_balance: Decimal,
+    pub loan_balance: Decimal,
+    pub collateral: Vec<MarginCollateral>,
+    pub market_value: Decimal,
+    pub collateral_value: Decimal,
+    /// `market_value + cash_balance - loan_balance`
+    pub equity: Decimal,
+    /// `(collateral_value + cash_balance - loan_balance) / (collateral_value + cash_balance)`,
+    /// `None` while nothing is borrowed
+    pub margin_ratio: Option<Decimal>,
+    pub call_ratio: Decimal,
+    pub force_sell_ratio: Option<Decimal>,
+    pub status: MarginStatus,
+    pub warning: Option<String>,
+    /// Interest accrued since `accrued_through` that has not been charged yet
+    pub unposted_interest: Decimal,
+    pub accrued_through: Option<NaiveDate>,
+}
+
+/// Interest for a run of days, charged as one FEE activity on `charge_date`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterestCharge {
+    pub from_date: NaiveDate,
+    pub charge_date: NaiveDate,
+    pub days: u32,
+    pub amount: Decimal,
+}
+
+#[derive(Queryable, Insertable, AsChangeset, Selectable, Debug, Clone)]
+#[diesel(table_name = crate::schema::margin_accounts)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[diesel(treat_none_as_null = true)]
+pub struct MarginAccountDB {
+    pub account_id: String,
+    pub annual_interest_rate: String,
+    pub call_ratio: String,
+    pub force_sell_ratio: Option<String>,
+    pub warning_buffer: String,
+    pub default_haircut: String,
+    pub interest_start_date: NaiveDate,
+    pub accrued_through: Option<NaiveDate>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Queryable, Insertable, Selectable, Debug, Clone)]
+#[diesel(table_name = crate::schema::margin_haircuts)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct MarginHaircutDB {
+    pub account_id: String,
+    pub asset_id: String,
+    pub haircut: String,
+}
+
+impl MarginSettings {
+    pub fn from_db(db: MarginAccountDB, haircuts: Vec<MarginHaircutDB>) -> Self {
+        Self {
+            account_id: db.account_id,
+            annual_interest_rate: Decimal::from_str(&db.annual_interest_rate).unwrap_or_default(),
+            call_ratio: Decimal::from_str(&db.call_ratio).unwrap_or_default(),
+            force_sell_ratio: db.force_sell_ratio.and_then(|r| Decimal::from_str(&r).ok()),
+            warning_buffer: Decimal::from_str(&db.warning_buffer).unwrap_or_default(),
+            default_haircut: Decimal::from_str(&db.default_haircut).unwrap_or_default(),
+            haircuts: haircuts
+                .into_iter()
+                .map(|h| {
+                    (
+                        h.asset_id,
+                        Decimal::from_str(&h.haircut).unwrap_or_default(),
+                    )
+                })
+                .collect(),
+            interest_start_date: db.interest_start_date,
+            accrued_through: db.accrued_through,
+            created_at: db.created_at,
+            updated_at: db.updated_at,
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::sqlite::SqliteConnection;
+use std::sync::Arc;
+
+use super::margin_model::{MarginAccountDB, MarginHaircutDB, MarginSettings};
+use super::margin_traits::MarginRepositoryTrait;
+use crate::db::{get_connection, WriteHandle};
+use crate::errors::Result;
+use crate::schema::{margin_accounts, margin_haircuts};
+
+pub struct MarginRepository {
+    pool: Arc<Pool<ConnectionManager<SqliteConnection>>>,
+    writer: WriteHandle,
+}
+
+impl MarginRepository {
+    pub fn new(pool: Arc<Pool<ConnectionManager<SqliteConnection>>>, writer: WriteHandle) -> Self {
+        MarginRepository { pool, writer }
+    }
+}
+
+fn load_settings(conn: &mut SqliteConnection, account_id: &str) -> Result<Option<MarginSettings>> {
+    let Some(account) = margin_accounts::table
+        .find(account_id)
+        .first::<MarginAccountDB>(conn)
+        .optional()?
+    else {
+        return Ok(None);
+    };
+    let haircuts = margin_haircuts::table
+        .filter(margin_haircuts::account_id.eq(account_id))
+        .load::<MarginHaircutDB>(conn)?;
+    Ok(Some(MarginSettings::from_db(account, haircuts)))
+}
+
+#[async_trait]
+impl MarginRepositoryTrait for MarginRepository {
+    fn get_margin_settings(&self, account_id: &str) -> Result<Option<MarginSettings>> {
+        let mut conn = get_connection(&self.pool)?;
+        load_settings(&mut conn, account_id)
+    }
+
+    fn list_margin_settings(&self) -> Result<Vec<MarginSettings>> {
+        let mut conn = get_connection(&self.pool)?;
+        let accounts = margin_accounts::table
+            .order(margin_accounts::account_id.asc())
+            .load::<MarginAccountDB>(&mut conn)?;
+        let haircuts = margin_haircuts::table.load::<MarginHaircutDB>(&mut conn)?;
+
+        Ok(accounts
+            .into_iter()
+            .map(|account| {
+                let own_haircuts = haircuts
+                    .iter()
+                    .filter(|h| h.account_id == account.account_id)
+                    .cloned()
+                    .collect();
+                MarginSettings::from_db(account, own_haircuts)
+            })
+            .collect())
+    }
+
+    async fn save_margin_settings(&self, settings: MarginSettings) -> Result<MarginSettings> {
+        self.writer
+            .exec(
+                move |conn: &mut SqliteConnection| -> Result<MarginSettings> {
+                    let account = MarginAccountDB {
+                        account_id: settings.account_id.clone(),
+                        annual_interest_rate: settings.annual_interest_rate.to_string(),
+                        call_ratio: settings.call_ratio.to_string(),
+                        force_sell_ratio: settings.force_sell_ratio.map(|r| r.to_string()),
+                        warning_buffer: settings.warning_buffer.to_string(),
+                        default_haircut: settings.default_haircut.to_string(),
+                        interest_start_date: settings.interest_start_date,
+                        accrued_through: settings.accrued_through,
+                        created_at: settings.created_at,
+                        updated_at: settings.updated_at,
+                    };
+                    diesel::insert_into(margin_accounts::table)
+                        .values(&account)
+                        .on_conflict(margin_accounts::account_id)
+                        .do_update()
+                        .set(&account)
+                        .execute(conn)?;
+
+                    diesel::delete(
+                        margin_haircuts::table
+                            .filter(margin_haircuts::account_id.eq(&settings.account_id)),
+                    )
+                    .execute(conn)?;
+                    let rows: Vec<MarginHaircutDB> = settings
+                        .haircuts
+                        .iter()
+                        .map(|(asset_id, haircut)| MarginHaircutDB {
+                            account_id: settings.account_id.clone(),
+                            asset_id: asset_id.clone(),
+                            haircut: haircut.to_string(),
+                        })
+                        .collect();
+                    if !rows.is_empty() {
+                        diesel::insert_into(margin_haircuts::table)
+                            .values(&rows)
+                            .execute(conn)?;
+                    }
+
+                    load_settings(conn, &settings.account_id)?
+                        .ok_or_else(|| diesel::result::Error::NotFound.into())
+                },
+            )
+            .await
+    }
+
+    async fn set_accrued_through(&self, account_id: &str, date: NaiveDate) -> Result<()> {
+        let account_id = account_id.to_string();
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<()> {
+                diesel::update(margin_accounts::table.find(&account_id))
+                    .set((
+                        margin_accounts::accrued_through.eq(Some(date)),
+                        margin_accounts::updated_at.eq(Utc::now().to_rfc3339()),
+                    ))
+                    .execute(conn)?;
+                Ok(())
+            })
+            .await
+    }
+
+    async fn delete_margin_settings(&self, account_id: &str) -> Result<usize> {
+        let account_id = account_id.to_string();
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<usize> {
+                diesel::delete(
+                    margin_haircuts::table.filter(margin_haircuts::account_id.eq(&account_id)),
+                )
+                .execute(conn)?;
+                Ok(diesel::delete(margin_accounts::table.find(&account_id)).execute(conn)?)
+            })
+            .await
+    }
+}
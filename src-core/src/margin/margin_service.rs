@@ -0,0 +1,345 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{Duration, Local, NaiveDate, Utc};
+use log::{debug, info};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use super::margin_calculator::{
+    collateral, interest_charges, margin_ratio, margin_status, split_cash,
+};
+use super::margin_model::{InterestCharge, MarginSettings, MarginSettingsUpdate, MarginSummary};
+use super::margin_traits::{MarginRepositoryTrait, MarginServiceTrait};
+use crate::accounts::AccountServiceTrait;
+use crate::activities::{Activity, ActivityServiceTrait, NewActivity, ACTIVITY_TYPE_FEE};
+use crate::constants::{CASH_ASSET_PREFIX, PORTFOLIO_TOTAL_ACCOUNT_ID};
+use crate::errors::{Error, Result, ValidationError};
+use crate::portfolio::holdings::{HoldingType, HoldingsServiceTrait};
+use crate::portfolio::snapshot::SnapshotServiceTrait;
+
+const DEFAULT_WARNING_BUFFER: Decimal = dec!(0.05);
+const DEFAULT_HAIRCUT: Decimal = dec!(0.5);
+
+pub struct MarginService {
+    margin_repository: Arc<dyn MarginRepositoryTrait>,
+    account_service: Arc<dyn AccountServiceTrait>,
+    activity_service: Arc<dyn ActivityServiceTrait>,
+    snapshot_service: Arc<dyn SnapshotServiceTrait>,
+    holdings_service: Arc<dyn HoldingsServiceTrait>,
+}
+
+impl MarginService {
+    pub fn new(
+        margin_repository: Arc<dyn MarginRepositoryTrait>,
+        account_service: Arc<dyn AccountServiceTrait>,
+        activity_service: Arc<dyn ActivityServiceTrait>,
+        snapshot_service: Arc<dyn SnapshotServiceTrait>,
+        holdings_service: Arc<dyn HoldingsServiceTrait>,
+    ) -> Self {
+        MarginService {
+            margin_repository,
+            account_service,
+            activity_service,
+            snapshot_service,
+            holdings_service,
+        }
+    }
+
+    fn require_settings(&self, account_id: &str) -> Result<MarginSettings> {
+        self.margin_repository
+            .get_margin_settings(account_id)?
+            .ok_or_else(|| {
+                Error::Validation(ValidationError::InvalidInput(format!(
+                    "Margin is not enabled for account {}",
+                    account_id
+                )))
+            })
+    }
+
+    fn validate_fraction(name: &str, value: Decimal) -> Result<()> {
+        if value < Decimal::ZERO || value > Decimal::ONE {
+            return Err(Error::Validation(ValidationError::InvalidInput(format!(
+                "{} must be between 0 and 1, got {}",
+                name, value
+            ))));
+        }
+        Ok(())
+    }
+
+    fn validate_update(update: &MarginSettingsUpdate) -> Result<()> {
+        Self::validate_fraction("annualInterestRate", update.annual_interest_rate)?;
+        Self::validate_fraction("callRatio", update.call_ratio)?;
+        if let Some(force_sell) = update.force_sell_ratio {
+            Self::validate_fraction("forceSellRatio", force_sell)?;
+            if force_sell > update.call_ratio {
+                return Err(Error::Validation(ValidationError::InvalidInput(
+                    "forceSellRatio cannot be above callRatio".to_string(),
+                )));
+            }
+        }
+        if let Some(buffer) = update.warning_buffer {
+            Self::validate_fraction("warningBuffer", buffer)?;
+        }
+        if let Some(haircut) = update.default_haircut {
+            Self::validate_fraction("defaultHaircut", haircut)?;
+        }
+        for (asset_id, haircut) in &update.haircuts {
+            Self::validate_fraction(&format!("haircut for {}", asset_id), *haircut)?;
+        }
+        Ok(())
+    }
+
+    /// Closing loan balance of every calendar day in `start..=end`, carrying the last
+    /// known balance over days without a snapshot
+    fn daily_loans(
+        &self,
+        account_id: &str,
+        currency: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, Decimal)>> {
+        let loans_by_date: HashMap<NaiveDate, Decimal> = self
+            .snapshot_service
+            .get_daily_holdings_snapshots(account_id, Some(start), Some(end))?
+            .into_iter()
+            .map(|s| {
+                let net_cash = s.cash_balances.get(currency).copied().unwrap_or_default();
+                (s.snapshot_date, split_cash(net_cash).1)
+            })
+            .collect();
+
+        let mut loans = Vec::new();
+        let mut last = Decimal::ZERO;
+        let mut date = start;
+        while date <= end {
+            if let Some(loan) = loans_by_date.get(&date) {
+                last = *loan;
+            }
+            loans.push((date, last));
+            date += Duration::days(1);
+        }
+        Ok(loans)
+    }
+
+    fn pending_charges(
+        &self,
+        settings: &MarginSettings,
+        currency: &str,
+        through: NaiveDate,
+    ) -> Result<Vec<InterestCharge>> {
+        let start = settings
+            .accrued_through
+            .map(|d| d + Duration::days(1))
+            .unwrap_or(settings.interest_start_date);
+        if start > through {
+            return Ok(Vec::new());
+        }
+        let loans = self.daily_loans(&settings.account_id, currency, start, through)?;
+        Ok(interest_charges(&loans, settings.annual_interest_rate))
+    }
+}
+
+/// Creates each interest fee and advances the account's `accrued_through` to its charge
+/// date before creating the next, so when a later fee fails the ones already posted are
+/// not charged again on retry.
+pub(super) async fn post_interest_charges(
+    activity_service: &dyn ActivityServiceTrait,
+    margin_repository: &dyn MarginRepositoryTrait,
+    account_id: &str,
+    fees: impl IntoIterator<Item = (NaiveDate, NewActivity)>,
+) -> Result<Vec<Activity>> {
+    let mut created = Vec::new();
+    for (charge_date, fee) in fees {
+        created.push(activity_service.create_activity(fee).await?);
+        margin_repository
+            .set_accrued_through(account_id, charge_date)
+            .await?;
+    }
+    Ok(created)
+}
+
+#[async_trait]
+impl MarginServiceTrait for MarginService {
+    fn get_margin_settings(&self, account_id: &str) -> Result<Option<MarginSettings>> {
+        self.margin_repository.get_margin_settings(account_id)
+    }
+
+    fn list_margin_settings(&self) -> Result<Vec<MarginSettings>> {
+        self.margin_repository.list_margin_settings()
+    }
+
+    async fn save_margin_settings(&self, update: MarginSettingsUpdate) -> Result<MarginSettings> {
+        let account_id = update.account_id.trim().to_string();
+        if account_id.is_empty() || account_id == PORTFOLIO_TOTAL_ACCOUNT_ID {
+            return Err(Error::Validation(ValidationError::InvalidInput(
+                "Margin settings need a real account".to_string(),
+            )));
+        }
+        Self::validate_update(&update)?;
+        // Fails for unknown accounts before anything is written
+        self.account_service.get_account(&account_id)?;
+
+        let existing = self.margin_repository.get_margin_settings(&account_id)?;
+        let now = Utc::now().to_rfc3339();
+        let settings = MarginSettings {
+            account_id: account_id.clone(),
+            annual_interest_rate: update.annual_interest_rate,
+            call_ratio: update.call_ratio,
+            force_sell_ratio: update.force_sell_ratio,
+            warning_buffer: update.warning_buffer.unwrap_or(DEFAULT_WARNING_BUFFER),
+            default_haircut: update.default_haircut.unwrap_or(DEFAULT_HAIRCUT),
+            haircuts: update
+                .haircuts
+                .into_iter()
+                .map(|(asset_id, haircut)| (asset_id.trim().to_string(), haircut))
+                .filter(|(asset_id, _)| !asset_id.is_empty())
+                .collect(),
+            interest_start_date: update
+                .interest_start_date
+                .or(existing.as_ref().map(|s| s.interest_start_date))
+                .unwrap_or_else(|| Local::now().date_naive()),
+            accrued_through: existing.as_ref().and_then(|s| s.accrued_through),
+            created_at: existing
+                .as_ref()
+                .map(|s| s.created_at.clone())
+                .unwrap_or_else(|| now.clone()),
+            updated_at: now,
+        };
+        self.margin_repository.save_margin_settings(settings).await
+    }
+
+    async fn delete_margin_settings(&self, account_id: &str) -> Result<usize> {
+        self.margin_repository
+            .delete_margin_settings(account_id)
+            .await
+    }
+
+    async fn get_margin_summary(&self, account_id: &str) -> Result<MarginSummary> {
+        let settings = self.require_settings(account_id)?;
+        let account = self.account_service.get_account(account_id)?;
+        // Valued in the account currency so loan and collateral are comparable
+        let holdings = self
+            .holdings_service
+            .get_holdings(account_id, &account.currency)
+            .await?;
+
+        let mut net_cash = Decimal::ZERO;
+        let mut positions = Vec::new();
+        for holding in &holdings {
+            match holding.holding_type {
+                HoldingType::Cash => net_cash += holding.market_value.base,
+                HoldingType::Security => {
+                    let asset_id = holding
+                        .instrument
+                        .as_ref()
+                        .map(|i| i.id.clone())
+                        .unwrap_or_else(|| holding.id.clone());
+                    let haircut = settings.haircut_for(&asset_id);
+                    positions.push(collateral(
+                        &asset_id,
+                        holding.quantity,
+                        holding.market_value.base,
+                        haircut,
+                    ));
+                }
+            }
+        }
+        positions.sort_by(|a, b| a.asset_id.cmp(&b.asset_id));
+
+        let (cash_balance, loan_balance) = split_cash(net_cash);
+        let market_value: Decimal = positions.iter().map(|c| c.market_value).sum();
+        let collateral_value: Decimal = positions.iter().map(|c| c.collateral_value).sum();
+        let ratio = margin_ratio(collateral_value, cash_balance, loan_balance);
+        let (status, warning) = margin_status(ratio, &settings);
+
+        let today = Local::now().date_naive();
+        let unposted_interest: Decimal = self
+            .pending_charges(&settings, &account.currency, today)?
+            .iter()
+            .map(|c| c.amount)
+            .sum();
+
+        Ok(MarginSummary {
+            account_id: account_id.to_string(),
+            currency: account.currency,
+            cash_balance,
+            loan_balance,
+            collateral: positions,
+            market_value,
+            collateral_value,
+            equity: market_value + cash_balance - loan_balance,
+            margin_ratio: ratio.map(|r| r.round_dp(6)),
+            call_ratio: settings.call_ratio,
+            force_sell_ratio: settings.force_sell_ratio,
+            status,
+            warning,
+            unposted_interest,
+            accrued_through: settings.accrued_through,
+        })
+    }
+
+    async fn accrue_margin_interest(
+        &self,
+        account_id: &str,
+        through: NaiveDate,
+    ) -> Result<Vec<Activity>> {
+        if through > Local::now().date_naive() {
+            return Err(Error::Validation(ValidationError::InvalidInput(format!(
+                "Cannot accrue interest for future date {}",
+                through
+            ))));
+        }
+        let settings = self.require_settings(account_id)?;
+        let account = self.account_service.get_account(account_id)?;
+        let charges = self.pending_charges(&settings, &account.currency, through)?;
+        debug!(
+            "Accruing margin interest for {} through {}: {} charge(s)",
+            account_id,
+            through,
+            charges.len()
+        );
+
+        let rate_pct = (settings.annual_interest_rate * dec!(100)).normalize();
+        let fees = charges.into_iter().map(|charge| {
+            let activity = NewActivity {
+                id: None,
+                account_id: account_id.to_string(),
+                asset_id: format!("{}-{}", CASH_ASSET_PREFIX, account.currency),
+                activity_type: ACTIVITY_TYPE_FEE.to_string(),
+                activity_date: charge.charge_date.format("%Y-%m-%d").to_string(),
+                quantity: None,
+                unit_price: None,
+                currency: account.currency.clone(),
+                fee: None,
+                amount: Some(charge.amount),
+                is_draft: false,
+                comment: Some(format!(
+                    "Margin interest {} to {} ({} days at {}%)",
+                    charge.from_date, charge.charge_date, charge.days, rate_pct
+                )),
+                fx_rate_side: None,
+            };
+            (charge.charge_date, activity)
+        });
+        let created = post_interest_charges(
+            self.activity_service.as_ref(),
+            self.margin_repository.as_ref(),
+            account_id,
+            fees,
+        )
+        .await?;
+
+        self.margin_repository
+            .set_accrued_through(account_id, through)
+            .await?;
+        info!(
+            "Posted {} margin interest charge(s) for account {} through {}",
+            created.len(),
+            account_id,
+            through
+        );
+        Ok(created)
+    }
+}
@@ -0,0 +1,257 @@
+use std::io::Write;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal_macros::dec;
+
+use super::margin_service::post_interest_charges;
+use super::{MarginRepository, MarginRepositoryTrait, MarginSettings};
+use crate::activities::{
+    Activity, ActivityBulkMutationRequest, ActivityBulkMutationResult, ActivityChange,
+    ActivityExportFormat, ActivityImport, ActivitySearchFilters, ActivitySearchResponse,
+    ActivityServiceTrait, ActivityUpdate, ImportMappingData, NewActivity, NewPairedTransfer,
+    PairedTransfer, Sort, ACTIVITY_TYPE_FEE,
+};
+use crate::errors::{Error, Result, ValidationError};
+use crate::fx::TransferFxCost;
+use crate::test_utils::{date, TestDb};
+
+/// Creates fees in memory and fails every create once `fail_from` fees exist
+struct MockActivityService {
+    created: Mutex<Vec<NewActivity>>,
+    fail_from: Mutex<Option<usize>>,
+}
+
+impl MockActivityService {
+    fn new(fail_from: Option<usize>) -> Self {
+        Self {
+            created: Mutex::new(Vec::new()),
+            fail_from: Mutex::new(fail_from),
+        }
+    }
+}
+
+#[async_trait]
+impl ActivityServiceTrait for MockActivityService {
+    fn get_activity(&self, _activity_id: &str) -> Result<Activity> {
+        unimplemented!()
+    }
+    fn get_activities(&self) -> Result<Vec<Activity>> {
+        unimplemented!()
+    }
+    fn get_activities_by_account_id(&self, _account_id: &String) -> Result<Vec<Activity>> {
+        unimplemented!()
+    }
+    fn get_activities_by_account_ids(&self, _account_ids: &[String]) -> Result<Vec<Activity>> {
+        unimplemented!()
+    }
+    fn get_trading_activities(&self) -> Result<Vec<Activity>> {
+        unimplemented!()
+    }
+    fn get_income_activities(&self) -> Result<Vec<Activity>> {
+        unimplemented!()
+    }
+    fn search_activities(
+        &self,
+        _page: i64,
+        _page_size: i64,
+        _filters: ActivitySearchFilters,
+        _sort: Option<Sort>,
+    ) -> Result<ActivitySearchResponse> {
+        unimplemented!()
+    }
+    fn get_first_activity_date(
+        &self,
+        _account_ids: Option<&[String]>,
+    ) -> Result<Option<DateTime<Utc>>> {
+        unimplemented!()
+    }
+    fn get_import_mapping(&self, _account_id: String) -> Result<ImportMappingData> {
+        unimplemented!()
+    }
+    async fn create_activity(&self, activity: NewActivity) -> Result<Activity> {
+        let mut created = self.created.lock().unwrap();
+        if self
+            .fail_from
+            .lock()
+            .unwrap()
+            .is_some_and(|n| created.len() >= n)
+        {
+            return Err(Error::Validation(ValidationError::InvalidInput(
+                "fee rejected".to_string(),
+            )));
+        }
+        created.push(activity.clone());
+        let now = Utc::now();
+        Ok(Activity {
+            id: format!("fee-{}", created.len()),
+            account_id: activity.account_id,
+            asset_id: activity.asset_id,
+            activity_type: activity.activity_type,
+            activity_date: now,
+            quantity: Default::default(),
+            unit_price: Default::default(),
+            currency: activity.currency,
+            fee: Default::default(),
+            amount: activity.amount,
+            is_draft: false,
+            comment: activity.comment,
+            fx_rate_side: None,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+    async fn update_activity(&self, _activity: ActivityUpdate) -> Result<Activity> {
+        unimplemented!()
+    }
+    async fn delete_activity(&self, _activity_id: String) -> Result<Activity> {
+        unimplemented!()
+    }
+    async fn bulk_mutate_activities(
+        &self,
+        _request: ActivityBulkMutationRequest,
+    ) -> Result<ActivityBulkMutationResult> {
+        unimplemented!()
+    }
+    async fn create_paired_transfer(&self, _transfer: NewPairedTransfer) -> Result<PairedTransfer> {
+        unimplemented!()
+    }
+    fn get_transfer_fx_costs(&self, _base_currency: &str) -> Result<Vec<TransferFxCost>> {
+        unimplemented!()
+    }
+    fn get_activity_history(&self, _activity_id: &str) -> Result<Vec<ActivityChange>> {
+        unimplemented!()
+    }
+    async fn revert_activity_change(
+        &self,
+        _change_id: String,
+    ) -> Result<ActivityBulkMutationResult> {
+        unimplemented!()
+    }
+    async fn revert_activity_batch(&self, _batch_id: String) -> Result<ActivityBulkMutationResult> {
+        unimplemented!()
+    }
+    async fn check_activities_import(
+        &self,
+        _account_id: String,
+        _activities: Vec<ActivityImport>,
+    ) -> Result<Vec<ActivityImport>> {
+        unimplemented!()
+    }
+    async fn import_activities(
+        &self,
+        _account_id: String,
+        _activities: Vec<ActivityImport>,
+    ) -> Result<Vec<ActivityImport>> {
+        unimplemented!()
+    }
+    async fn save_import_mapping(
+        &self,
+        _mapping_data: ImportMappingData,
+    ) -> Result<ImportMappingData> {
+        unimplemented!()
+    }
+    fn export_activities(
+        &self,
+        _filters: ActivitySearchFilters,
+        _sort: Option<Sort>,
+        _format: ActivityExportFormat,
+        _writer: &mut dyn Write,
+    ) -> Result<usize> {
+        unimplemented!()
+    }
+}
+
+async fn margin_setup() -> (TestDb, MarginRepository) {
+    let db = TestDb::new();
+    db.insert_account("acc-1", "VND");
+    let repository = MarginRepository::new(db.pool.clone(), db.writer.clone());
+    let now = Utc::now().to_rfc3339();
+    repository
+        .save_margin_settings(MarginSettings {
+            account_id: "acc-1".to_string(),
+            annual_interest_rate: dec!(0.12),
+            call_ratio: dec!(0.3),
+            force_sell_ratio: None,
+            warning_buffer: dec!(0.05),
+            default_haircut: dec!(0.5),
+            haircuts: Default::default(),
+            interest_start_date: date(2024, 1, 1),
+            accrued_through: None,
+            created_at: now.clone(),
+            updated_at: now,
+        })
+        .await
+        .unwrap();
+    (db, repository)
+}
+
+fn fee(charge_date: NaiveDate) -> (NaiveDate, NewActivity) {
+    let activity = NewActivity {
+        id: None,
+        account_id: "acc-1".to_string(),
+        asset_id: "$CASH-VND".to_string(),
+        activity_type: ACTIVITY_TYPE_FEE.to_string(),
+        activity_date: charge_date.format("%Y-%m-%d").to_string(),
+        quantity: None,
+        unit_price: None,
+        currency: "VND".to_string(),
+        fee: None,
+        amount: Some(dec!(1000)),
+        is_draft: false,
+        comment: None,
+        fx_rate_side: None,
+    };
+    (charge_date, activity)
+}
+
+fn accrued_through(repository: &MarginRepository) -> Option<NaiveDate> {
+    repository
+        .get_margin_settings("acc-1")
+        .unwrap()
+        .unwrap()
+        .accrued_through
+}
+
+#[tokio::test]
+async fn test_failed_interest_posting_is_not_charged_twice_on_retry() {
+    let (_db, repository) = margin_setup().await;
+    let charges = [date(2024, 1, 31), date(2024, 2, 29), date(2024, 3, 31)];
+    // The second fee fails to save
+    let activity_service = MockActivityService::new(Some(1));
+
+    let result = post_interest_charges(
+        &activity_service,
+        &repository,
+        "acc-1",
+        charges.iter().copied().map(fee),
+    )
+    .await;
+
+    assert!(result.is_err());
+    assert_eq!(accrued_through(&repository), Some(date(2024, 1, 31)));
+
+    // A retry only charges the months after the posted one
+    *activity_service.fail_from.lock().unwrap() = None;
+    let accrued = accrued_through(&repository).unwrap();
+    let created = post_interest_charges(
+        &activity_service,
+        &repository,
+        "acc-1",
+        charges.iter().copied().filter(|d| *d > accrued).map(fee),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(created.len(), 2);
+    let posted: Vec<String> = activity_service
+        .created
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|a| a.activity_date.clone())
+        .collect();
+    assert_eq!(posted, vec!["2024-01-31", "2024-02-29", "2024-03-31"]);
+    assert_eq!(accrued_through(&repository), Some(date(2024, 3, 31)));
+}
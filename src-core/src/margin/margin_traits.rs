@@ -0,0 +1,35 @@
+use super::margin_model::{MarginSettings, MarginSettingsUpdate, MarginSummary};
+use crate::activities::Activity;
+use crate::errors::Result;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+/// Trait defining the contract for margin account repository operations.
+#[async_trait]
+pub trait MarginRepositoryTrait: Send + Sync {
+    fn get_margin_settings(&self, account_id: &str) -> Result<Option<MarginSettings>>;
+    fn list_margin_settings(&self) -> Result<Vec<MarginSettings>>;
+    /// Inserts or replaces the terms and haircuts of an account
+    async fn save_margin_settings(&self, settings: MarginSettings) -> Result<MarginSettings>;
+    async fn set_accrued_through(&self, account_id: &str, date: NaiveDate) -> Result<()>;
+    async fn delete_margin_settings(&self, account_id: &str) -> Result<usize>;
+}
+
+/// Trait defining the contract for margin account service operations.
+#[async_trait]
+pub trait MarginServiceTrait: Send + Sync {
+    fn get_margin_settings(&self, account_id: &str) -> Result<Option<MarginSettings>>;
+    fn list_margin_settings(&self) -> Result<Vec<MarginSettings>>;
+    async fn save_margin_settings(&self, update: MarginSettingsUpdate) -> Result<MarginSettings>;
+    async fn delete_margin_settings(&self, account_id: &str) -> Result<usize>;
+    /// Current loan, collateral and margin ratio of an account, with a warning when
+    /// the ratio is near or below the call level
+    async fn get_margin_summary(&self, account_id: &str) -> Result<MarginSummary>;
+    /// Charges interest on the daily loan balance from the day after the last
+    /// accrual through `through`, creating one FEE activity per calendar month
+    async fn accrue_margin_interest(
+        &self,
+        account_id: &str,
+        through: NaiveDate,
+    ) -> Result<Vec<Activity>>;
+}
@@ -0,0 +1,18 @@
+mod margin_calculator;
+mod margin_model;
+mod margin_repository;
+mod margin_service;
+#[cfg(test)]
+mod margin_service_tests;
+mod margin_traits;
+
+pub use margin_calculator::{
+    collateral, daily_interest, interest_charges, margin_ratio, margin_status, split_cash,
+};
+pub use margin_model::{
+    InterestCharge, MarginAccountDB, MarginCollateral, MarginHaircutDB, MarginSettings,
+    MarginSettingsUpdate, MarginStatus, MarginSummary,
+};
+pub use margin_repository::MarginRepository;
+pub use margin_service::MarginService;
+pub use margin_traits::{MarginRepositoryTrait, MarginServiceTrait};
@@ -1,6 +1,6 @@
 use crate::market_data::market_data_constants::{
     DATA_SOURCE_ALPHA_VANTAGE, DATA_SOURCE_MANUAL, DATA_SOURCE_MARKET_DATA_APP,
-    DATA_SOURCE_METAL_PRICE_API, DATA_SOURCE_YAHOO, DATA_SOURCE_VN_MARKET,
+    DATA_SOURCE_METAL_PRICE_API, DATA_SOURCE_YAHOO, DATA_SOURCE_VN_MARKET, DATA_SOURCE_VN_FX,
 };
 use crate::schema::quotes;
 use chrono::{DateTime, Utc};
@@ -150,6 +150,7 @@ pub enum DataSource {
     AlphaVantage,
     MetalPriceApi,
     VnMarket,
+    VnFx,
     #[default]
     Manual,
 }
@@ -162,6 +163,7 @@ impl DataSource {
             DataSource::AlphaVantage => DATA_SOURCE_ALPHA_VANTAGE,
             DataSource::MetalPriceApi => DATA_SOURCE_METAL_PRICE_API,
             DataSource::VnMarket => DATA_SOURCE_VN_MARKET,
+            DataSource::VnFx => DATA_SOURCE_VN_FX,
             DataSource::Manual => DATA_SOURCE_MANUAL,
         }
     }
@@ -181,6 +183,7 @@ impl From<&str> for DataSource {
             DATA_SOURCE_ALPHA_VANTAGE => DataSource::AlphaVantage,
             DATA_SOURCE_METAL_PRICE_API => DataSource::MetalPriceApi,
             DATA_SOURCE_VN_MARKET => DataSource::VnMarket,
+            DATA_SOURCE_VN_FX => DataSource::VnFx,
             _ => DataSource::Manual,
         }
     }
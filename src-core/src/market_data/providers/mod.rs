@@ -5,12 +5,18 @@ pub mod marketdata_app_provider;
 pub mod metal_price_api_provider;
 pub mod models;
 pub mod provider_registry;
+// Not registered with the provider registry yet, see the module docs
+#[allow(dead_code)]
+pub mod vn_fx_provider;
 pub mod vn_market_provider;
 pub mod yahoo_provider;
 
 #[cfg(test)]
 pub mod metal_price_api_provider_test;
 
+#[cfg(test)]
+pub mod vn_fx_provider_test;
+
 #[cfg(test)]
 pub mod vn_market_provider_test;
 
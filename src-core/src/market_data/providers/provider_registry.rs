@@ -1,6 +1,6 @@
 use crate::market_data::market_data_constants::{
     DATA_SOURCE_ALPHA_VANTAGE, DATA_SOURCE_MANUAL, DATA_SOURCE_MARKET_DATA_APP,
-    DATA_SOURCE_METAL_PRICE_API, DATA_SOURCE_VN_MARKET, DATA_SOURCE_YAHOO,
+    DATA_SOURCE_METAL_PRICE_API, DATA_SOURCE_VN_FX, DATA_SOURCE_VN_MARKET, DATA_SOURCE_YAHOO,
 };
 use crate::market_data::market_data_errors::MarketDataError;
 use crate::market_data::market_data_model::{
@@ -364,6 +364,7 @@ impl ProviderRegistry {
                 DataSource::MetalPriceApi => DATA_SOURCE_METAL_PRICE_API.to_string(),
                 DataSource::MarketDataApp => DATA_SOURCE_MARKET_DATA_APP.to_string(),
                 DataSource::VnMarket => DATA_SOURCE_VN_MARKET.to_string(),
+                DataSource::VnFx => DATA_SOURCE_VN_FX.to_string(),
                 DataSource::Manual => {
                     warn!("Manual data source requested for sync, skipping: {}", quote_request.symbol);
                     continue;
@@ -0,0 +1,274 @@
+//! VND exchange rates from Vietcombank, with the SBV central rate as fallback.
+//!
+//! Handles FX symbols against VND in either direction (`USDVND=X`, `VNDUSD=X`).
+//! Quotes use the Vietcombank transfer buying rate as low (bid), the selling
+//! rate as high (ask) and their midpoint as open and close. When Vietcombank has
+//! no rates for a day, USD falls back to the SBV central rate without a spread.
+//!
+//! Not registered as a market data source yet. Neither client has been checked
+//! against recorded SBV or Vietcombank responses, and the SBV page is a JSF form
+//! that likely needs a POST carrying its view state rather than a query string.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use log::{debug, warn};
+use rust_decimal::Decimal;
+use std::collections::{BTreeSet, HashMap};
+use std::time::SystemTime;
+
+use crate::market_data::market_data_constants::DATA_SOURCE_VN_FX;
+use crate::market_data::market_data_errors::MarketDataError;
+use crate::market_data::market_data_model::{DataSource, Quote};
+use crate::market_data::providers::market_data_provider::MarketDataProvider;
+use crate::vn_market::clients::{SbvClient, VcbClient};
+use crate::vn_market::errors::VnMarketError;
+use crate::vn_market::models::fx::VndFxQuote;
+
+const VND: &str = "VND";
+const SBV_CURRENCY: &str = "USD";
+
+/// Days fetched in parallel when loading history
+const MAX_CONCURRENT_DAYS: usize = 8;
+
+/// Days searched back for the latest rates
+const LATEST_LOOKBACK_DAYS: i64 = 7;
+
+/// Foreign side of a VND FX symbol
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VndPair {
+    /// ISO code of the foreign currency
+    pub currency: String,
+    /// True for `VNDxxx=X`, i.e. units of foreign currency per VND
+    pub inverted: bool,
+}
+
+/// Parse an FX symbol against VND (e.g., "USDVND=X" or "VNDUSD=X")
+pub fn parse_vnd_pair(symbol: &str) -> Option<VndPair> {
+    let pair = symbol.to_uppercase();
+    let pair = pair.strip_suffix("=X")?;
+    if pair.len() != 6 || !pair.is_ascii() {
+        return None;
+    }
+    let (from, to) = pair.split_at(3);
+    match (from, to) {
+        (VND, VND) => None,
+        (currency, VND) => Some(VndPair {
+            currency: currency.to_string(),
+            inverted: false,
+        }),
+        (VND, currency) => Some(VndPair {
+            currency: currency.to_string(),
+            inverted: true,
+        }),
+        _ => None,
+    }
+}
+
+/// Build a quote for `symbol` from the VND rates of its foreign currency
+pub fn build_fx_quote(symbol: &str, pair: &VndPair, fx: &VndFxQuote) -> Quote {
+    let (open_close, low, high, currency) = if pair.inverted {
+        (
+            Decimal::ONE / fx.mid,
+            Decimal::ONE / fx.ask,
+            Decimal::ONE / fx.bid,
+            pair.currency.clone(),
+        )
+    } else {
+        (fx.mid, fx.bid, fx.ask, VND.to_string())
+    };
+
+    Quote {
+        id: format!("{}_{}", fx.date.format("%Y%m%d"), symbol),
+        symbol: symbol.to_string(),
+        timestamp: fx.date.and_time(chrono::NaiveTime::MIN).and_utc(),
+        open: open_close,
+        high,
+        low,
+        close: open_close,
+        adjclose: open_close,
+        volume: Decimal::ZERO,
+        currency,
+        data_source: DataSource::VnFx,
+        created_at: Utc::now(),
+    }
+}
+
+/// VND FX rates provider (Vietcombank and State Bank of Vietnam)
+pub struct VnFxProvider {
+    vcb: VcbClient,
+    sbv: SbvClient,
+}
+
+impl VnFxProvider {
+    pub fn new() -> Self {
+        Self {
+            vcb: VcbClient::new(),
+            sbv: SbvClient::new(),
+        }
+    }
+
+    /// Rates of the requested currencies for one day; empty when nothing was published,
+    /// an error when a source fails for any other reason
+    async fn rates_for_date(
+        &self,
+        date: NaiveDate,
+        currencies: &BTreeSet<String>,
+    ) -> Result<Vec<VndFxQuote>, VnMarketError> {
+        let mut quotes: Vec<VndFxQuote> = match self.vcb.get_rates(date).await {
+            Ok(rates) => rates
+                .iter()
+                .filter(|r| currencies.contains(&r.currency))
+                .filter_map(VndFxQuote::from_vcb)
+                .collect(),
+            Err(VnMarketError::NoData { .. }) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+
+        if currencies.contains(SBV_CURRENCY) && !quotes.iter().any(|q| q.currency == SBV_CURRENCY) {
+            match self.sbv.get_central_rate(date).await {
+                Ok(rate) => quotes.push(VndFxQuote::from_sbv(&rate)),
+                Err(VnMarketError::NoData { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        if quotes.is_empty() {
+            debug!("No VND rates for {}", date);
+        }
+        Ok(quotes)
+    }
+
+    /// Quotes per symbol for every day between `start` and `end` with published rates
+    async fn quotes_for_range(
+        &self,
+        pairs: &[(String, VndPair)],
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<HashMap<String, Vec<Quote>>, VnMarketError> {
+        let currencies: BTreeSet<String> = pairs.iter().map(|(_, p)| p.currency.clone()).collect();
+        let days: Vec<NaiveDate> = start.iter_days().take_while(|d| *d <= end).collect();
+
+        let daily_rates: Vec<Vec<VndFxQuote>> = stream::iter(days)
+            .map(|date| {
+                let currencies = &currencies;
+                async move { self.rates_for_date(date, currencies).await }
+            })
+            .buffer_unordered(MAX_CONCURRENT_DAYS)
+            .try_collect()
+            .await?;
+
+        let mut quotes: HashMap<String, Vec<Quote>> = HashMap::new();
+        for fx in daily_rates.iter().flatten() {
+            for (symbol, pair) in pairs.iter().filter(|(_, p)| p.currency == fx.currency) {
+                quotes
+                    .entry(symbol.clone())
+                    .or_default()
+                    .push(build_fx_quote(symbol, pair, fx));
+            }
+        }
+        for symbol_quotes in quotes.values_mut() {
+            symbol_quotes.sort_by_key(|q| q.timestamp);
+        }
+        Ok(quotes)
+    }
+}
+
+impl Default for VnFxProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl MarketDataProvider for VnFxProvider {
+    fn name(&self) -> &'static str {
+        DATA_SOURCE_VN_FX
+    }
+
+    fn priority(&self) -> u8 {
+        0
+    }
+
+    async fn get_latest_quote(
+        &self,
+        symbol: &str,
+        _fallback_currency: String,
+    ) -> Result<Quote, MarketDataError> {
+        let pair =
+            parse_vnd_pair(symbol).ok_or_else(|| MarketDataError::NotFound(symbol.to_string()))?;
+        let currencies = BTreeSet::from([pair.currency.clone()]);
+        let today = Utc::now().date_naive();
+
+        for days_back in 0..LATEST_LOOKBACK_DAYS {
+            let date = today - Duration::days(days_back);
+            if let Some(fx) = self
+                .rates_for_date(date, &currencies)
+                .await?
+                .into_iter()
+                .next()
+            {
+                return Ok(build_fx_quote(symbol, &pair, &fx));
+            }
+        }
+
+        Err(MarketDataError::NotFound(symbol.to_string()))
+    }
+
+    async fn get_historical_quotes(
+        &self,
+        symbol: &str,
+        start: SystemTime,
+        end: SystemTime,
+        _fallback_currency: String,
+    ) -> Result<Vec<Quote>, MarketDataError> {
+        let pair =
+            parse_vnd_pair(symbol).ok_or_else(|| MarketDataError::NotFound(symbol.to_string()))?;
+        let start_date = DateTime::<Utc>::from(start).date_naive();
+        let end_date = DateTime::<Utc>::from(end).date_naive();
+
+        let mut quotes = self
+            .quotes_for_range(&[(symbol.to_string(), pair)], start_date, end_date)
+            .await?;
+        Ok(quotes.remove(symbol).unwrap_or_default())
+    }
+
+    async fn get_historical_quotes_bulk(
+        &self,
+        symbols_with_currencies: &[(String, String)],
+        start: SystemTime,
+        end: SystemTime,
+    ) -> Result<(Vec<Quote>, Vec<(String, String)>), MarketDataError> {
+        let mut failed_symbols = Vec::new();
+        let mut pairs = Vec::new();
+        for (symbol, currency) in symbols_with_currencies {
+            match parse_vnd_pair(symbol) {
+                Some(pair) => pairs.push((symbol.clone(), pair)),
+                None => {
+                    warn!("{} is not a VND exchange rate symbol", symbol);
+                    failed_symbols.push((symbol.clone(), currency.clone()));
+                }
+            }
+        }
+        if pairs.is_empty() {
+            return Ok((Vec::new(), failed_symbols));
+        }
+
+        let start_date = DateTime::<Utc>::from(start).date_naive();
+        let end_date = DateTime::<Utc>::from(end).date_naive();
+        let mut quotes_by_symbol = self.quotes_for_range(&pairs, start_date, end_date).await?;
+
+        let mut results = Vec::new();
+        for (symbol, currency) in symbols_with_currencies {
+            if failed_symbols.iter().any(|(s, _)| s == symbol) {
+                continue;
+            }
+            match quotes_by_symbol.remove(symbol) {
+                Some(quotes) if !quotes.is_empty() => results.extend(quotes),
+                _ => failed_symbols.push((symbol.clone(), currency.clone())),
+            }
+        }
+
+        Ok((results, failed_symbols))
+    }
+}
@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod tests {
+    use super::super::vn_fx_provider::{build_fx_quote, parse_vnd_pair, VnFxProvider, VndPair};
+    use crate::market_data::market_data_model::DataSource;
+    use crate::market_data::MarketDataProvider;
+    use crate::vn_market::clients::sbv_client::parse_central_rate;
+    use crate::vn_market::clients::vcb_client::parse_vcb_response;
+    use crate::vn_market::models::fx::VndFxQuote;
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use std::time::{Duration, SystemTime};
+
+    /// Recorded Vietcombank response for 2024-01-15 (trimmed)
+    const VCB_FIXTURE: &str = r#"{
+        "Count": 2,
+        "Date": "2024-01-15T00:00:00",
+        "UpdatedDate": "2024-01-15T08:31:04+07:00",
+        "Data": [
+            {"currencyName": "US DOLLAR", "currencyCode": "USD", "cash": "24,170.00", "transfer": "24,200.00", "sell": "24,540.00", "icon": "usd.png"},
+            {"currencyName": "EURO", "currencyCode": "EUR", "cash": "25,953.30", "transfer": "26,215.46", "sell": "27,375.89", "icon": "eur.png"}
+        ]
+    }"#;
+
+    /// Recorded excerpt of the SBV central rate page for 2024-01-15
+    const SBV_FIXTURE: &str = r#"
+        <span>Áp dụng cho ngày 15/01/2024</span>
+        <span>1 Đô la Mỹ = 24.000 VND</span>"#;
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()
+    }
+
+    fn vcb_quote(currency: &str) -> VndFxQuote {
+        parse_vcb_response(VCB_FIXTURE, date())
+            .unwrap()
+            .iter()
+            .find(|r| r.currency == currency)
+            .and_then(VndFxQuote::from_vcb)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_vnd_pair() {
+        assert_eq!(
+            parse_vnd_pair("USDVND=X"),
+            Some(VndPair {
+                currency: "USD".to_string(),
+                inverted: false,
+            })
+        );
+        assert_eq!(
+            parse_vnd_pair("vndeur=x"),
+            Some(VndPair {
+                currency: "EUR".to_string(),
+                inverted: true,
+            })
+        );
+        assert_eq!(parse_vnd_pair("EURUSD=X"), None);
+        assert_eq!(parse_vnd_pair("VNDVND=X"), None);
+        assert_eq!(parse_vnd_pair("USDVND"), None);
+        assert_eq!(parse_vnd_pair("VNM"), None);
+    }
+
+    #[test]
+    fn test_quote_from_vcb_fixture() {
+        let pair = parse_vnd_pair("USDVND=X").unwrap();
+        let quote = build_fx_quote("USDVND=X", &pair, &vcb_quote("USD"));
+
+        assert_eq!(quote.low, dec!(24200));
+        assert_eq!(quote.high, dec!(24540));
+        assert_eq!(quote.close, dec!(24370));
+        assert_eq!(quote.open, quote.close);
+        assert_eq!(quote.currency, "VND");
+        assert_eq!(quote.data_source, DataSource::VnFx);
+        assert_eq!(quote.timestamp.date_naive(), date());
+        assert_eq!(quote.id, "20240115_USDVND=X");
+    }
+
+    #[test]
+    fn test_inverted_quote_swaps_bid_and_ask() {
+        let pair = parse_vnd_pair("VNDEUR=X").unwrap();
+        let fx = vcb_quote("EUR");
+        let quote = build_fx_quote("VNDEUR=X", &pair, &fx);
+
+        assert_eq!(quote.currency, "EUR");
+        assert_eq!(quote.close, Decimal::ONE / fx.mid);
+        assert_eq!(quote.low, Decimal::ONE / fx.ask);
+        assert_eq!(quote.high, Decimal::ONE / fx.bid);
+        assert!(quote.low < quote.close && quote.close < quote.high);
+    }
+
+    #[test]
+    fn test_quote_from_sbv_fixture_has_no_spread() {
+        let rate = parse_central_rate(SBV_FIXTURE, date()).unwrap();
+        let pair = parse_vnd_pair("USDVND=X").unwrap();
+        let quote = build_fx_quote("USDVND=X", &pair, &VndFxQuote::from_sbv(&rate));
+
+        assert_eq!(quote.close, dec!(24000));
+        assert_eq!(quote.low, quote.high);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_rejects_non_vnd_symbols() {
+        let provider = VnFxProvider::new();
+        let now = SystemTime::now();
+        let (quotes, failed) = provider
+            .get_historical_quotes_bulk(
+                &[("EURUSD=X".to_string(), "EUR".to_string())],
+                now - Duration::from_secs(86_400),
+                now,
+            )
+            .await
+            .unwrap();
+
+        assert!(quotes.is_empty());
+        assert_eq!(failed, vec![("EURUSD=X".to_string(), "EUR".to_string())]);
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires network access
+    async fn test_get_latest_quote() {
+        let provider = VnFxProvider::new();
+        let quote = provider
+            .get_latest_quote("USDVND=X", "VND".to_string())
+            .await
+            .unwrap();
+
+        assert!(quote.close > dec!(20000));
+        assert!(quote.low <= quote.close && quote.close <= quote.high);
+    }
+}
@@ -0,0 +1,305 @@
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+use super::{AssetClassification, AttributionDay, AttributionGroup, HoldingContribution};
+
+const UNCLASSIFIED: &str = "Unknown";
+
+/// Period contribution of one holding, split into price and currency effects
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HoldingEffects {
+    pub currency: String,
+    pub price_effect: Decimal,
+    pub currency_effect: Decimal,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LinkedContributions {
+    pub portfolio_return: Decimal,
+    pub holdings: HashMap<String, HoldingEffects>,
+}
+
+/// Links daily holding contributions into period contributions.
+///
+/// Each day a holding contributes its reporting-currency gain divided by the day's TWR
+/// denominator. The gain is split into the local price move at the previous FX rate and
+/// the FX move on the closing value. Daily contributions are scaled by the portfolio's
+/// growth up to that day, so that holdings plus the residual add up to the cumulative
+/// time-weighted return instead of just approximating it.
+pub fn link_daily_contributions(days: &[AttributionDay]) -> LinkedContributions {
+    let mut holdings: HashMap<String, HoldingEffects> = HashMap::new();
+    let mut growth = Decimal::ONE;
+
+    for day in days {
+        if !day.denominator.is_zero() {
+            let scale = growth / day.denominator;
+            for holding in &day.holdings {
+                let price_move = holding.quantity
+                    * (holding.price - holding.previous_price)
+                    * holding.previous_fx_rate;
+                let fx_move =
+                    holding.quantity * holding.price * (holding.fx_rate - holding.previous_fx_rate);
+
+                let effects = holdings.entry(holding.asset_id.clone()).or_default();
+                effects.currency = holding.currency.clone();
+                effects.price_effect += price_move * scale;
+                effects.currency_effect += fx_move * scale;
+            }
+        }
+        growth *= Decimal::ONE + day.portfolio_return;
+    }
+
+    LinkedContributions {
+        portfolio_return: growth - Decimal::ONE,
+        holdings,
+    }
+}
+
+/// Sums contributions into groups. `keys` returns the groups a holding belongs to with
+/// the share of its contribution each one receives. Groups are ordered by contribution,
+/// largest first.
+pub fn group_contributions<F>(holdings: &[HoldingContribution], keys: F) -> Vec<AttributionGroup>
+where
+    F: Fn(&HoldingContribution) -> Vec<(String, Decimal)>,
+{
+    let mut groups: HashMap<String, AttributionGroup> = HashMap::new();
+    for holding in holdings {
+        for (name, share) in keys(holding) {
+            let group = groups
+                .entry(name.clone())
+                .or_insert_with(|| AttributionGroup {
+                    name,
+                    price_effect: Decimal::ZERO,
+                    currency_effect: Decimal::ZERO,
+                    total_contribution: Decimal::ZERO,
+                });
+            group.price_effect += holding.price_effect * share;
+            group.currency_effect += holding.currency_effect * share;
+            group.total_contribution += holding.total_contribution * share;
+        }
+    }
+
+    let mut groups: Vec<AttributionGroup> = groups.into_values().collect();
+    groups.sort_by(|a, b| {
+        b.total_contribution
+            .cmp(&a.total_contribution)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    groups
+}
+
+pub fn group_by_asset_class(holdings: &[HoldingContribution]) -> Vec<AttributionGroup> {
+    group_contributions(holdings, |h| vec![(h.asset_class.clone(), Decimal::ONE)])
+}
+
+pub fn group_by_currency(holdings: &[HoldingContribution]) -> Vec<AttributionGroup> {
+    group_contributions(holdings, |h| vec![(h.currency.clone(), Decimal::ONE)])
+}
+
+/// Splits each holding across its sectors in proportion to the sector weights.
+/// Holdings without sector data are grouped as unknown.
+pub fn group_by_sector(
+    holdings: &[HoldingContribution],
+    classifications: &HashMap<String, AssetClassification>,
+) -> Vec<AttributionGroup> {
+    group_contributions(holdings, |h| {
+        let sectors = classifications
+            .get(&h.asset_id)
+            .map(|c| c.sectors.as_slice())
+            .unwrap_or_default();
+        let total_weight: Decimal = sectors
+            .iter()
+            .map(|(_, weight)| *weight)
+            .filter(|weight| weight.is_sign_positive())
+            .sum();
+        if total_weight.is_zero() {
+            return vec![(UNCLASSIFIED.to_string(), Decimal::ONE)];
+        }
+        sectors
+            .iter()
+            .filter(|(_, weight)| weight.is_sign_positive() && !weight.is_zero())
+            .map(|(name, weight)| (name.clone(), *weight / total_weight))
+            .collect()
+    })
+}
+
+/// Asset class label of a classification, or the unknown group
+pub fn asset_class_label(classification: Option<&AssetClassification>) -> String {
+    classification
+        .and_then(|c| c.asset_class.clone())
+        .filter(|class| !class.trim().is_empty())
+        .unwrap_or_else(|| UNCLASSIFIED.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::portfolio::attribution::HoldingDayMove;
+    use chrono::NaiveDate;
+    use rust_decimal_macros::dec;
+
+    fn holding_move(
+        asset_id: &str,
+        quantity: Decimal,
+        prices: (Decimal, Decimal),
+        fx_rates: (Decimal, Decimal),
+    ) -> HoldingDayMove {
+        HoldingDayMove {
+            asset_id: asset_id.to_string(),
+            currency: if fx_rates.0 == Decimal::ONE {
+                "VND".to_string()
+            } else {
+                "USD".to_string()
+            },
+            quantity,
+            previous_price: prices.0,
+            price: prices.1,
+            previous_fx_rate: fx_rates.0,
+            fx_rate: fx_rates.1,
+        }
+    }
+
+    fn contribution(
+        asset_id: &str,
+        class: &str,
+        currency: &str,
+        total: Decimal,
+    ) -> HoldingContribution {
+        HoldingContribution {
+            asset_id: asset_id.to_string(),
+            symbol: asset_id.to_string(),
+            name: None,
+            asset_class: class.to_string(),
+            currency: currency.to_string(),
+            start_value: Decimal::ZERO,
+            end_value: Decimal::ZERO,
+            price_effect: total,
+            currency_effect: Decimal::ZERO,
+            total_contribution: total,
+        }
+    }
+
+    #[test]
+    fn test_contributions_add_up_to_portfolio_return() {
+        // A VND stock and a USD stock, valued in VND, over two days without cash flows.
+        // Day 0: 100 x 10,000 + 10 x 100 x 25,000 = 26,000,000
+        let day1_value = dec!(100) * dec!(11000) + dec!(10) * dec!(100) * dec!(25500);
+        let day2_value = dec!(100) * dec!(10500) + dec!(10) * dec!(110) * dec!(25500);
+        let days = vec![
+            AttributionDay {
+                date: NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+                denominator: dec!(26000000),
+                portfolio_return: day1_value / dec!(26000000) - Decimal::ONE,
+                holdings: vec![
+                    holding_move(
+                        "FPT",
+                        dec!(100),
+                        (dec!(10000), dec!(11000)),
+                        (dec!(1), dec!(1)),
+                    ),
+                    holding_move(
+                        "AAPL",
+                        dec!(10),
+                        (dec!(100), dec!(100)),
+                        (dec!(25000), dec!(25500)),
+                    ),
+                ],
+            },
+            AttributionDay {
+                date: NaiveDate::from_ymd_opt(2024, 1, 3).unwrap(),
+                denominator: day1_value,
+                portfolio_return: day2_value / day1_value - Decimal::ONE,
+                holdings: vec![
+                    holding_move(
+                        "FPT",
+                        dec!(100),
+                        (dec!(11000), dec!(10500)),
+                        (dec!(1), dec!(1)),
+                    ),
+                    holding_move(
+                        "AAPL",
+                        dec!(10),
+                        (dec!(100), dec!(110)),
+                        (dec!(25500), dec!(25500)),
+                    ),
+                ],
+            },
+        ];
+
+        let linked = link_daily_contributions(&days);
+        assert_eq!(
+            linked.portfolio_return.round_dp(10),
+            (day2_value / dec!(26000000) - Decimal::ONE).round_dp(10)
+        );
+
+        let total: Decimal = linked
+            .holdings
+            .values()
+            .map(|e| e.price_effect + e.currency_effect)
+            .sum();
+        assert_eq!(total.round_dp(10), linked.portfolio_return.round_dp(10));
+
+        let fpt = &linked.holdings["FPT"];
+        assert_eq!(fpt.currency_effect, Decimal::ZERO);
+        // FPT gained 50,000 VND over the two days
+        assert_eq!(
+            fpt.price_effect.round_dp(10),
+            (dec!(50000) / dec!(26000000)).round_dp(10)
+        );
+        let aapl = &linked.holdings["AAPL"];
+        // USD rose 500 VND on 1,000 USD of stock, then the price rose 10 USD x 10 shares
+        assert_eq!(
+            aapl.currency_effect.round_dp(10),
+            (dec!(500000) / dec!(26000000)).round_dp(10)
+        );
+        assert_eq!(
+            aapl.price_effect.round_dp(10),
+            (dec!(2550000) / dec!(26000000)).round_dp(10)
+        );
+    }
+
+    #[test]
+    fn test_sector_groups_split_by_weight() {
+        let holdings = vec![
+            contribution("FPT", "Equity", "VND", dec!(0.04)),
+            contribution("VCB", "Equity", "VND", dec!(0.02)),
+            contribution("GOLD", "Commodity", "VND", dec!(-0.01)),
+        ];
+        let mut classifications = HashMap::new();
+        classifications.insert(
+            "FPT".to_string(),
+            AssetClassification {
+                sectors: vec![
+                    ("Technology".to_string(), dec!(75)),
+                    ("Telecom".to_string(), dec!(25)),
+                ],
+                ..Default::default()
+            },
+        );
+        classifications.insert(
+            "VCB".to_string(),
+            AssetClassification {
+                sectors: vec![("Financials".to_string(), dec!(1))],
+                ..Default::default()
+            },
+        );
+
+        let sectors = group_by_sector(&holdings, &classifications);
+        let find = |name: &str| {
+            sectors
+                .iter()
+                .find(|g| g.name == name)
+                .unwrap()
+                .total_contribution
+        };
+        assert_eq!(find("Technology"), dec!(0.03));
+        assert_eq!(find("Telecom"), dec!(0.01));
+        assert_eq!(find("Financials"), dec!(0.02));
+        assert_eq!(find(UNCLASSIFIED), dec!(-0.01));
+        assert_eq!(sectors[0].name, "Technology");
+
+        let classes = group_by_asset_class(&holdings);
+        assert_eq!(classes[0].name, "Equity");
+        assert_eq!(classes[0].total_contribution, dec!(0.06));
+    }
+}
@@ -0,0 +1,82 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Classification used to group holding contributions
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AssetClassification {
+    pub symbol: String,
+    pub name: Option<String>,
+    pub asset_class: Option<String>,
+    /// Sector names with their weights; weights need not add up to one
+    pub sectors: Vec<(String, Decimal)>,
+}
+
+/// One holding carried over a valuation day: the quantity held at the previous close
+/// with the local prices and FX rates (to the reporting currency) on both days.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HoldingDayMove {
+    pub asset_id: String,
+    pub currency: String,
+    pub quantity: Decimal,
+    pub previous_price: Decimal,
+    pub price: Decimal,
+    pub previous_fx_rate: Decimal,
+    pub fx_rate: Decimal,
+}
+
+/// Inputs for one valuation day of the attribution
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributionDay {
+    pub date: NaiveDate,
+    /// Previous total value plus the day's external cash flow, as in the daily TWR
+    pub denominator: Decimal,
+    /// The portfolio's time-weighted return for the day
+    pub portfolio_return: Decimal,
+    pub holdings: Vec<HoldingDayMove>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HoldingContribution {
+    pub asset_id: String,
+    pub symbol: String,
+    pub name: Option<String>,
+    pub asset_class: String,
+    pub currency: String,
+    /// Market value in the reporting currency at the start and end of the period
+    pub start_value: Decimal,
+    pub end_value: Decimal,
+    /// Contribution from the move of the local price
+    pub price_effect: Decimal,
+    /// Contribution from the move of the holding's currency against the reporting currency
+    pub currency_effect: Decimal,
+    pub total_contribution: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributionGroup {
+    pub name: String,
+    pub price_effect: Decimal,
+    pub currency_effect: Decimal,
+    pub total_contribution: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReturnAttribution {
+    pub id: String,
+    pub currency: String,
+    pub period_start_date: Option<NaiveDate>,
+    pub period_end_date: Option<NaiveDate>,
+    /// Cumulative time-weighted return of the portfolio over the period
+    pub portfolio_return: Decimal,
+    pub holdings: Vec<HoldingContribution>,
+    pub by_asset_class: Vec<AttributionGroup>,
+    pub by_sector: Vec<AttributionGroup>,
+    pub by_currency: Vec<AttributionGroup>,
+    /// Part of the return not explained by holdings: cash, income, fees and
+    /// trades made during the day
+    pub residual: Decimal,
+}
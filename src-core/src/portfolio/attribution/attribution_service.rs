@@ -0,0 +1,347 @@
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate};
+use log::{debug, warn};
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+use super::{
+    asset_class_label, group_by_asset_class, group_by_currency, group_by_sector,
+    link_daily_contributions, AssetClassification, AttributionDay, HoldingContribution,
+    HoldingDayMove, ReturnAttribution,
+};
+use crate::assets::assets_model::Sector;
+use crate::assets::AssetServiceTrait;
+use crate::constants::DECIMAL_PRECISION;
+use crate::errors::{Error, Result, ValidationError};
+use crate::fx::FxServiceTrait;
+use crate::market_data::MarketDataServiceTrait;
+use crate::portfolio::snapshot::{AccountStateSnapshot, SnapshotServiceTrait};
+use crate::portfolio::valuation::ValuationServiceTrait;
+
+// Extra days of quotes loaded before the period so its first day has a price
+const QUOTE_LOOKBACK_DAYS: i64 = 14;
+
+#[async_trait]
+pub trait AttributionServiceTrait: Send + Sync {
+    /// Explains an account's (or the TOTAL portfolio's) time-weighted return over the
+    /// period by holding, asset class, sector and currency.
+    async fn calculate_return_attribution(
+        &self,
+        account_id: &str,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<ReturnAttribution>;
+}
+
+pub struct AttributionService {
+    valuation_service: Arc<dyn ValuationServiceTrait>,
+    snapshot_service: Arc<dyn SnapshotServiceTrait>,
+    market_data_service: Arc<dyn MarketDataServiceTrait>,
+    fx_service: Arc<dyn FxServiceTrait>,
+    asset_service: Arc<dyn AssetServiceTrait>,
+}
+
+impl AttributionService {
+    pub fn new(
+        valuation_service: Arc<dyn ValuationServiceTrait>,
+        snapshot_service: Arc<dyn SnapshotServiceTrait>,
+        market_data_service: Arc<dyn MarketDataServiceTrait>,
+        fx_service: Arc<dyn FxServiceTrait>,
+        asset_service: Arc<dyn AssetServiceTrait>,
+    ) -> Self {
+        Self {
+            valuation_service,
+            snapshot_service,
+            market_data_service,
+            fx_service,
+            asset_service,
+        }
+    }
+
+    fn load_classifications(
+        &self,
+        asset_ids: &HashSet<String>,
+    ) -> Result<HashMap<String, AssetClassification>> {
+        Ok(self
+            .asset_service
+            .get_assets()?
+            .into_iter()
+            .filter(|asset| asset_ids.contains(&asset.id))
+            .map(|asset| {
+                let sectors = asset
+                    .sectors
+                    .as_deref()
+                    .and_then(|s| {
+                        serde_json::from_str::<Option<Vec<Sector>>>(s)
+                            .map_err(|e| {
+                                warn!("Failed to parse sectors for {}: {}", asset.symbol, e)
+                            })
+                            .ok()
+                            .flatten()
+                    })
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|sector| {
+                        Decimal::from_f64_retain(sector.weight).map(|weight| (sector.name, weight))
+                    })
+                    .collect();
+                let classification = AssetClassification {
+                    symbol: asset.symbol,
+                    name: asset.name,
+                    asset_class: asset.asset_class,
+                    sectors,
+                };
+                (asset.id, classification)
+            })
+            .collect())
+    }
+}
+
+/// Caches FX rates by currency and date for the period being attributed
+struct FxRates<'a> {
+    fx_service: &'a dyn FxServiceTrait,
+    to_currency: &'a str,
+    cache: HashMap<(String, NaiveDate), Decimal>,
+}
+
+impl FxRates<'_> {
+    fn rate(&mut self, from_currency: &str, date: NaiveDate) -> Result<Decimal> {
+        if from_currency == self.to_currency {
+            return Ok(Decimal::ONE);
+        }
+        if let Some(rate) = self.cache.get(&(from_currency.to_string(), date)) {
+            return Ok(*rate);
+        }
+        let rate =
+            self.fx_service
+                .get_exchange_rate_for_date(from_currency, self.to_currency, date)?;
+        self.cache.insert((from_currency.to_string(), date), rate);
+        Ok(rate)
+    }
+}
+
+/// Last known close of an asset on or before `date`
+fn price_on(
+    prices: &HashMap<String, BTreeMap<NaiveDate, Decimal>>,
+    asset_id: &str,
+    date: NaiveDate,
+) -> Option<Decimal> {
+    prices
+        .get(asset_id)?
+        .range(..=date)
+        .next_back()
+        .map(|(_, price)| *price)
+}
+
+/// Snapshot in effect at the close of `date`
+fn snapshot_on(
+    snapshots: &BTreeMap<NaiveDate, AccountStateSnapshot>,
+    date: NaiveDate,
+) -> Option<&AccountStateSnapshot> {
+    snapshots
+        .range(..=date)
+        .next_back()
+        .map(|(_, snapshot)| snapshot)
+}
+
+#[async_trait]
+impl AttributionServiceTrait for AttributionService {
+    async fn calculate_return_attribution(
+        &self,
+        account_id: &str,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<ReturnAttribution> {
+        if let (Some(start), Some(end)) = (start_date, end_date) {
+            if start > end {
+                return Err(Error::Validation(ValidationError::InvalidInput(
+                    "Start date must be before end date".to_string(),
+                )));
+            }
+        }
+
+        let valuations = self
+            .valuation_service
+            .get_historical_valuations(account_id, start_date, end_date)?;
+        let (Some(first), Some(last)) = (valuations.first(), valuations.last()) else {
+            return Ok(empty_attribution(account_id));
+        };
+        if valuations.len() < 2 {
+            debug!(
+                "Attribution for '{}': not enough valuation data ({} points)",
+                account_id,
+                valuations.len()
+            );
+            return Ok(empty_attribution(account_id));
+        }
+        let currency = first.account_currency.clone();
+        let period_start = first.valuation_date;
+        let period_end = last.valuation_date;
+
+        let snapshots: BTreeMap<NaiveDate, AccountStateSnapshot> = self
+            .snapshot_service
+            .get_daily_holdings_snapshots(account_id, Some(period_start), Some(period_end))?
+            .into_iter()
+            .map(|snapshot| (snapshot.snapshot_date, snapshot))
+            .collect();
+
+        let asset_ids: HashSet<String> = snapshots
+            .values()
+            .flat_map(|snapshot| snapshot.positions.keys().cloned())
+            .collect();
+        let mut prices: HashMap<String, BTreeMap<NaiveDate, Decimal>> = HashMap::new();
+        if !asset_ids.is_empty() {
+            let daily_quotes = self
+                .market_data_service
+                .get_daily_quotes(
+                    &asset_ids,
+                    period_start - Duration::days(QUOTE_LOOKBACK_DAYS),
+                    period_end,
+                )
+                .await?;
+            for (date, quotes) in daily_quotes {
+                for (asset_id, quote) in quotes {
+                    prices
+                        .entry(asset_id)
+                        .or_default()
+                        .insert(date, quote.close);
+                }
+            }
+        }
+
+        let mut fx_rates = FxRates {
+            fx_service: self.fx_service.as_ref(),
+            to_currency: &currency,
+            cache: HashMap::new(),
+        };
+
+        let mut days = Vec::with_capacity(valuations.len() - 1);
+        for window in valuations.windows(2) {
+            let (previous, current) = (&window[0], &window[1]);
+            let cash_flow = current.net_contribution - previous.net_contribution;
+            let denominator = previous.total_value + cash_flow;
+            let portfolio_return = if denominator.is_zero() {
+                Decimal::ZERO
+            } else {
+                current.total_value / denominator - Decimal::ONE
+            };
+
+            let mut holdings = Vec::new();
+            if let Some(snapshot) = snapshot_on(&snapshots, previous.valuation_date) {
+                for position in snapshot.positions.values() {
+                    if position.quantity.is_zero() {
+                        continue;
+                    }
+                    let (Some(previous_price), Some(price)) = (
+                        price_on(&prices, &position.asset_id, previous.valuation_date),
+                        price_on(&prices, &position.asset_id, current.valuation_date),
+                    ) else {
+                        continue;
+                    };
+                    holdings.push(HoldingDayMove {
+                        asset_id: position.asset_id.clone(),
+                        currency: position.currency.clone(),
+                        quantity: position.quantity,
+                        previous_price,
+                        price,
+                        previous_fx_rate: fx_rates
+                            .rate(&position.currency, previous.valuation_date)?,
+                        fx_rate: fx_rates.rate(&position.currency, current.valuation_date)?,
+                    });
+                }
+            }
+
+            days.push(AttributionDay {
+                date: current.valuation_date,
+                denominator,
+                portfolio_return,
+                holdings,
+            });
+        }
+
+        let linked = link_daily_contributions(&days);
+        let classifications = self.load_classifications(&asset_ids)?;
+
+        let mut market_value = |date: NaiveDate, asset_id: &str| -> Result<Decimal> {
+            let Some(position) =
+                snapshot_on(&snapshots, date).and_then(|snapshot| snapshot.positions.get(asset_id))
+            else {
+                return Ok(Decimal::ZERO);
+            };
+            let price = price_on(&prices, asset_id, date).unwrap_or(Decimal::ZERO);
+            Ok(position.quantity * price * fx_rates.rate(&position.currency, date)?)
+        };
+
+        let mut holdings = Vec::with_capacity(linked.holdings.len());
+        for (asset_id, effects) in linked.holdings {
+            let classification = classifications.get(&asset_id);
+            holdings.push(HoldingContribution {
+                symbol: classification
+                    .map(|c| c.symbol.clone())
+                    .unwrap_or_else(|| asset_id.clone()),
+                name: classification.and_then(|c| c.name.clone()),
+                asset_class: asset_class_label(classification),
+                currency: effects.currency,
+                start_value: market_value(period_start, &asset_id)?.round_dp(DECIMAL_PRECISION),
+                end_value: market_value(period_end, &asset_id)?.round_dp(DECIMAL_PRECISION),
+                price_effect: effects.price_effect,
+                currency_effect: effects.currency_effect,
+                total_contribution: effects.price_effect + effects.currency_effect,
+                asset_id,
+            });
+        }
+        holdings.sort_by(|a, b| {
+            b.total_contribution
+                .cmp(&a.total_contribution)
+                .then_with(|| a.symbol.cmp(&b.symbol))
+        });
+
+        let explained: Decimal = holdings.iter().map(|h| h.total_contribution).sum();
+        let by_asset_class = group_by_asset_class(&holdings);
+        let by_sector = group_by_sector(&holdings, &classifications);
+        let by_currency = group_by_currency(&holdings);
+
+        Ok(ReturnAttribution {
+            id: account_id.to_string(),
+            currency,
+            period_start_date: Some(period_start),
+            period_end_date: Some(period_end),
+            portfolio_return: linked.portfolio_return.round_dp(DECIMAL_PRECISION),
+            holdings: holdings.into_iter().map(round_contribution).collect(),
+            by_asset_class: by_asset_class.into_iter().map(round_group).collect(),
+            by_sector: by_sector.into_iter().map(round_group).collect(),
+            by_currency: by_currency.into_iter().map(round_group).collect(),
+            residual: (linked.portfolio_return - explained).round_dp(DECIMAL_PRECISION),
+        })
+    }
+}
+
+fn round_contribution(mut holding: HoldingContribution) -> HoldingContribution {
+    holding.price_effect = holding.price_effect.round_dp(DECIMAL_PRECISION);
+    holding.currency_effect = holding.currency_effect.round_dp(DECIMAL_PRECISION);
+    holding.total_contribution = holding.total_contribution.round_dp(DECIMAL_PRECISION);
+    holding
+}
+
+fn round_group(mut group: super::AttributionGroup) -> super::AttributionGroup {
+    group.price_effect = group.price_effect.round_dp(DECIMAL_PRECISION);
+    group.currency_effect = group.currency_effect.round_dp(DECIMAL_PRECISION);
+    group.total_contribution = group.total_contribution.round_dp(DECIMAL_PRECISION);
+    group
+}
+
+fn empty_attribution(account_id: &str) -> ReturnAttribution {
+    ReturnAttribution {
+        id: account_id.to_string(),
+        currency: String::new(),
+        period_start_date: None,
+        period_end_date: None,
+        portfolio_return: Decimal::ZERO,
+        holdings: Vec::new(),
+        by_asset_class: Vec::new(),
+        by_sector: Vec::new(),
+        by_currency: Vec::new(),
+        residual: Decimal::ZERO,
+    }
+}
@@ -0,0 +1,7 @@
+pub mod attribution_calculator;
+pub mod attribution_model;
+pub mod attribution_service;
+
+pub use attribution_calculator::*;
+pub use attribution_model::*;
+pub use attribution_service::{AttributionService, AttributionServiceTrait};
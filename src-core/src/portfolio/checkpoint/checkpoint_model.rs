@@ -0,0 +1,208 @@
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How far an account's stored holdings snapshots and daily valuations can be trusted.
+///
+/// The `*_calculated_through` dates are written after each successful rebuild. The
+/// `*_dirty_from` dates are moved back by database triggers whenever an activity, a
+/// quote or an FX rate changes, and by the snapshot service when it rewrites holdings
+/// that valuations were derived from. Each of those changes also bumps the matching
+/// `*_dirty_version`, which a rebuild compares before clearing the dirty date.
+#[derive(Queryable, Insertable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = crate::schema::valuation_checkpoints)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[serde(rename_all = "camelCase")]
+pub struct ValuationCheckpoint {
+    pub account_id: String,
+    pub holdings_dirty_from: Option<NaiveDate>,
+    pub valuation_dirty_from: Option<NaiveDate>,
+    pub holdings_calculated_through: Option<NaiveDate>,
+    pub valuation_calculated_through: Option<NaiveDate>,
+    pub updated_at: String,
+    pub holdings_dirty_version: i64,
+    pub valuation_dirty_version: i64,
+}
+
+impl ValuationCheckpoint {
+    pub fn new(account_id: &str) -> Self {
+        ValuationCheckpoint {
+            account_id: account_id.to_string(),
+            holdings_dirty_from: None,
+            valuation_dirty_from: None,
+            holdings_calculated_through: None,
+            valuation_calculated_through: None,
+            updated_at: chrono::Utc::now().to_rfc3339(),
+            holdings_dirty_version: 0,
+            valuation_dirty_version: 0,
+        }
+    }
+
+    /// First date from which holdings snapshots must be recalculated. `None` means the
+    /// holdings were never fully calculated and need a rebuild from the first activity.
+    pub fn holdings_rebuild_from(&self) -> Option<NaiveDate> {
+        rebuild_from(self.holdings_dirty_from, self.holdings_calculated_through)
+    }
+
+    /// First date from which daily valuations must be recalculated, or `None` when
+    /// there is no completed valuation run to resume from.
+    pub fn valuation_rebuild_from(&self) -> Option<NaiveDate> {
+        rebuild_from(self.valuation_dirty_from, self.valuation_calculated_through)
+    }
+}
+
+fn rebuild_from(
+    dirty_from: Option<NaiveDate>,
+    calculated_through: Option<NaiveDate>,
+) -> Option<NaiveDate> {
+    let next_day = calculated_through?.succ_opt()?;
+    Some(dirty_from.map_or(next_day, |dirty| dirty.min(next_day)))
+}
+
+/// Returns the earlier of an optional dirty date and a new one.
+pub fn earliest_dirty_date(current: Option<NaiveDate>, date: NaiveDate) -> NaiveDate {
+    current.map_or(date, |existing| existing.min(date))
+}
+
+/// One paired transfer, as seen by `align_rebuild_dates`.
+#[derive(Debug, Clone, Copy)]
+pub struct TransferSpan<'a> {
+    pub from_account_id: &'a str,
+    pub to_account_id: &'a str,
+    pub out_date: NaiveDate,
+    pub in_date: NaiveDate,
+}
+
+/// Gives accounts linked by paired transfers a common rebuild date, so both legs of a
+/// transfer are either recalculated together or both left in stored keyframes. A date
+/// falling while lots are in transit is moved back to the outgoing leg. `None` (full
+/// rebuild) wins over any date. Accounts missing from `rebuild_dates` are ignored.
+pub fn align_rebuild_dates<'a>(
+    rebuild_dates: &mut HashMap<String, Option<NaiveDate>>,
+    transfers: impl IntoIterator<Item = TransferSpan<'a>> + Clone,
+) {
+    loop {
+        let mut changed = false;
+        for transfer in transfers.clone() {
+            let (Some(&from_date), Some(&to_date)) = (
+                rebuild_dates.get(transfer.from_account_id),
+                rebuild_dates.get(transfer.to_account_id),
+            ) else {
+                continue;
+            };
+            let aligned = match (from_date, to_date) {
+                (Some(a), Some(b)) => {
+                    let date = a.min(b);
+                    if transfer.out_date < date && date <= transfer.in_date {
+                        Some(transfer.out_date)
+                    } else {
+                        Some(date)
+                    }
+                }
+                _ => None,
+            };
+            for account_id in [transfer.from_account_id, transfer.to_account_id] {
+                if rebuild_dates.get(account_id) != Some(&aligned) {
+                    rebuild_dates.insert(account_id.to_string(), aligned);
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::date;
+
+    #[test]
+    fn test_rebuild_starts_after_checkpoint_when_clean() {
+        let mut checkpoint = ValuationCheckpoint::new("A");
+        checkpoint.holdings_calculated_through = Some(date(2024, 5, 10));
+        assert_eq!(checkpoint.holdings_rebuild_from(), Some(date(2024, 5, 11)));
+    }
+
+    #[test]
+    fn test_rebuild_starts_at_dirty_date_before_checkpoint() {
+        let mut checkpoint = ValuationCheckpoint::new("A");
+        checkpoint.valuation_calculated_through = Some(date(2024, 5, 10));
+        checkpoint.valuation_dirty_from = Some(date(2023, 1, 2));
+        assert_eq!(checkpoint.valuation_rebuild_from(), Some(date(2023, 1, 2)));
+
+        // A future-dated change still requires the days after the checkpoint
+        checkpoint.valuation_dirty_from = Some(date(2024, 6, 1));
+        assert_eq!(checkpoint.valuation_rebuild_from(), Some(date(2024, 5, 11)));
+    }
+
+    #[test]
+    fn test_rebuild_is_full_without_checkpoint() {
+        let mut checkpoint = ValuationCheckpoint::new("A");
+        checkpoint.holdings_dirty_from = Some(date(2024, 1, 1));
+        assert_eq!(checkpoint.holdings_rebuild_from(), None);
+    }
+
+    fn transfer<'a>(from: &'a str, to: &'a str, on: NaiveDate) -> TransferSpan<'a> {
+        TransferSpan {
+            from_account_id: from,
+            to_account_id: to,
+            out_date: on,
+            in_date: on,
+        }
+    }
+
+    #[test]
+    fn test_transfer_linked_accounts_share_earliest_date() {
+        let mut dates = HashMap::from([
+            ("A".to_string(), Some(date(2024, 3, 1))),
+            ("B".to_string(), Some(date(2024, 1, 1))),
+            ("C".to_string(), Some(date(2024, 5, 1))),
+            ("D".to_string(), Some(date(2024, 4, 1))),
+        ]);
+        let day = date(2023, 6, 1);
+        let transfers = vec![transfer("A", "B", day), transfer("C", "A", day)];
+        align_rebuild_dates(&mut dates, transfers.iter().copied());
+
+        assert_eq!(dates["A"], Some(date(2024, 1, 1)));
+        assert_eq!(dates["B"], Some(date(2024, 1, 1)));
+        assert_eq!(dates["C"], Some(date(2024, 1, 1)));
+        assert_eq!(dates["D"], Some(date(2024, 4, 1)));
+    }
+
+    #[test]
+    fn test_full_rebuild_spreads_across_transfers() {
+        let mut dates = HashMap::from([
+            ("A".to_string(), Some(date(2024, 3, 1))),
+            ("B".to_string(), None),
+        ]);
+        let day = date(2023, 6, 1);
+        align_rebuild_dates(
+            &mut dates,
+            [transfer("A", "B", day), transfer("A", "Z", day)],
+        );
+        assert_eq!(dates["A"], None);
+        assert_eq!(dates["B"], None);
+        assert!(!dates.contains_key("Z"));
+    }
+
+    #[test]
+    fn test_rebuild_moves_back_to_transfer_in_transit() {
+        let mut dates = HashMap::from([
+            ("A".to_string(), Some(date(2024, 3, 5))),
+            ("B".to_string(), Some(date(2024, 3, 8))),
+        ]);
+        let in_transit = TransferSpan {
+            from_account_id: "A",
+            to_account_id: "B",
+            out_date: date(2024, 3, 1),
+            in_date: date(2024, 3, 6),
+        };
+        align_rebuild_dates(&mut dates, [in_transit]);
+        assert_eq!(dates["A"], Some(date(2024, 3, 1)));
+        assert_eq!(dates["B"], Some(date(2024, 3, 1)));
+    }
+}
@@ -0,0 +1,165 @@
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::sqlite::SqliteConnection;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::checkpoint_model::{earliest_dirty_date, ValuationCheckpoint};
+use crate::db::{get_connection, WriteHandle};
+use crate::errors::Result;
+use crate::schema::valuation_checkpoints;
+
+#[async_trait]
+pub trait ValuationCheckpointRepositoryTrait: Send + Sync {
+    fn get_checkpoint(&self, account_id: &str) -> Result<Option<ValuationCheckpoint>>;
+    fn get_checkpoints(
+        &self,
+        account_ids: &[String],
+    ) -> Result<HashMap<String, ValuationCheckpoint>>;
+    /// Moves the holdings dirty date of the accounts back to `from` if it is earlier.
+    async fn mark_holdings_dirty(&self, account_ids: &[String], from: NaiveDate) -> Result<()>;
+    /// Moves the valuation dirty date of the accounts back to `from` if it is earlier.
+    async fn mark_valuation_dirty(&self, account_ids: &[String], from: NaiveDate) -> Result<()>;
+    /// Records a finished holdings rebuild. The dirty date is cleared only when the dirty
+    /// version still equals `consumed_dirty_version`, read before the rebuild started, so
+    /// changes made during the rebuild are not lost.
+    async fn complete_holdings(
+        &self,
+        account_id: &str,
+        consumed_dirty_version: i64,
+        calculated_through: NaiveDate,
+    ) -> Result<()>;
+    /// Records a finished valuation rebuild, with the same rule as `complete_holdings`.
+    async fn complete_valuation(
+        &self,
+        account_id: &str,
+        consumed_dirty_version: i64,
+        calculated_through: NaiveDate,
+    ) -> Result<()>;
+}
+
+pub struct ValuationCheckpointRepository {
+    pool: Arc<Pool<ConnectionManager<SqliteConnection>>>,
+    writer: WriteHandle,
+}
+
+impl ValuationCheckpointRepository {
+    pub fn new(pool: Arc<Pool<ConnectionManager<SqliteConnection>>>, writer: WriteHandle) -> Self {
+        Self { pool, writer }
+    }
+
+    /// Loads (or creates) the checkpoints of the accounts, applies `update` and writes them back.
+    async fn update_checkpoints<F>(&self, account_ids: &[String], update: F) -> Result<()>
+    where
+        F: Fn(&mut ValuationCheckpoint) + Send + 'static,
+    {
+        if account_ids.is_empty() {
+            return Ok(());
+        }
+        let ids = account_ids.to_vec();
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<()> {
+                let mut existing: HashMap<String, ValuationCheckpoint> =
+                    valuation_checkpoints::table
+                        .filter(valuation_checkpoints::account_id.eq_any(&ids))
+                        .load::<ValuationCheckpoint>(conn)?
+                        .into_iter()
+                        .map(|checkpoint| (checkpoint.account_id.clone(), checkpoint))
+                        .collect();
+                let now = Utc::now().to_rfc3339();
+                let rows: Vec<ValuationCheckpoint> = ids
+                    .iter()
+                    .map(|id| {
+                        let mut checkpoint = existing
+                            .remove(id)
+                            .unwrap_or_else(|| ValuationCheckpoint::new(id));
+                        update(&mut checkpoint);
+                        checkpoint.updated_at = now.clone();
+                        checkpoint
+                    })
+                    .collect();
+                diesel::replace_into(valuation_checkpoints::table)
+                    .values(&rows)
+                    .execute(conn)?;
+                Ok(())
+            })
+            .await
+    }
+}
+
+#[async_trait]
+impl ValuationCheckpointRepositoryTrait for ValuationCheckpointRepository {
+    fn get_checkpoint(&self, account_id: &str) -> Result<Option<ValuationCheckpoint>> {
+        let mut conn = get_connection(&self.pool)?;
+        Ok(valuation_checkpoints::table
+            .find(account_id)
+            .first::<ValuationCheckpoint>(&mut conn)
+            .optional()?)
+    }
+
+    fn get_checkpoints(
+        &self,
+        account_ids: &[String],
+    ) -> Result<HashMap<String, ValuationCheckpoint>> {
+        if account_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let mut conn = get_connection(&self.pool)?;
+        Ok(valuation_checkpoints::table
+            .filter(valuation_checkpoints::account_id.eq_any(account_ids))
+            .load::<ValuationCheckpoint>(&mut conn)?
+            .into_iter()
+            .map(|checkpoint| (checkpoint.account_id.clone(), checkpoint))
+            .collect())
+    }
+
+    async fn mark_holdings_dirty(&self, account_ids: &[String], from: NaiveDate) -> Result<()> {
+        self.update_checkpoints(account_ids, move |checkpoint| {
+            checkpoint.holdings_dirty_from =
+                Some(earliest_dirty_date(checkpoint.holdings_dirty_from, from));
+            checkpoint.holdings_dirty_version += 1;
+        })
+        .await
+    }
+
+    async fn mark_valuation_dirty(&self, account_ids: &[String], from: NaiveDate) -> Result<()> {
+        self.update_checkpoints(account_ids, move |checkpoint| {
+            checkpoint.valuation_dirty_from =
+                Some(earliest_dirty_date(checkpoint.valuation_dirty_from, from));
+            checkpoint.valuation_dirty_version += 1;
+        })
+        .await
+    }
+
+    async fn complete_holdings(
+        &self,
+        account_id: &str,
+        consumed_dirty_version: i64,
+        calculated_through: NaiveDate,
+    ) -> Result<()> {
+        self.update_checkpoints(&[account_id.to_string()], move |checkpoint| {
+            if checkpoint.holdings_dirty_version == consumed_dirty_version {
+                checkpoint.holdings_dirty_from = None;
+            }
+            checkpoint.holdings_calculated_through = Some(calculated_through);
+        })
+        .await
+    }
+
+    async fn complete_valuation(
+        &self,
+        account_id: &str,
+        consumed_dirty_version: i64,
+        calculated_through: NaiveDate,
+    ) -> Result<()> {
+        self.update_checkpoints(&[account_id.to_string()], move |checkpoint| {
+            if checkpoint.valuation_dirty_version == consumed_dirty_version {
+                checkpoint.valuation_dirty_from = None;
+            }
+            checkpoint.valuation_calculated_through = Some(calculated_through);
+        })
+        .await
+    }
+}
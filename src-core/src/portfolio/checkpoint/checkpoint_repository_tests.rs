@@ -0,0 +1,137 @@
+use super::{ValuationCheckpointRepository, ValuationCheckpointRepositoryTrait};
+use crate::test_utils::{date, TestDb};
+
+fn insert_activity(db: &TestDb, id: &str, activity_date: &str) {
+    db.execute(&format!(
+        "INSERT INTO activities (id, account_id, asset_id, activity_type, activity_date, quantity, unit_price, currency, fee, amount, is_draft, created_at, updated_at)
+         VALUES ('{id}', 'acc-1', '$CASH-USD', 'DEPOSIT', '{activity_date}T00:00:00+00:00', '0', '0', 'USD', '0', '100', 0, '2024-01-01T00:00:00+00:00', '2024-01-01T00:00:00+00:00');"
+    ));
+}
+
+fn setup() -> (TestDb, ValuationCheckpointRepository) {
+    let db = TestDb::new();
+    db.insert_account("acc-1", "USD");
+    db.insert_asset("$CASH-USD", "USD");
+    let repository = ValuationCheckpointRepository::new(db.pool.clone(), db.writer.clone());
+    (db, repository)
+}
+
+#[tokio::test]
+async fn test_complete_holdings_keeps_edit_made_during_rebuild() {
+    let (db, repository) = setup();
+    insert_activity(&db, "act-1", "2024-01-10");
+    let started = repository.get_checkpoint("acc-1").unwrap().unwrap();
+    assert_eq!(started.holdings_dirty_from, Some(date(2024, 1, 10)));
+
+    // An edit dated after the dirty date lands while the rebuild runs: the date stays
+    // the same, but the rebuild may have read the activities before it
+    insert_activity(&db, "act-2", "2024-02-01");
+    let edited = repository.get_checkpoint("acc-1").unwrap().unwrap();
+    assert_eq!(edited.holdings_dirty_from, Some(date(2024, 1, 10)));
+    assert!(edited.holdings_dirty_version > started.holdings_dirty_version);
+
+    repository
+        .complete_holdings("acc-1", started.holdings_dirty_version, date(2024, 3, 1))
+        .await
+        .unwrap();
+    let checkpoint = repository.get_checkpoint("acc-1").unwrap().unwrap();
+    assert_eq!(checkpoint.holdings_dirty_from, Some(date(2024, 1, 10)));
+    assert_eq!(
+        checkpoint.holdings_calculated_through,
+        Some(date(2024, 3, 1))
+    );
+
+    // The next rebuild sees the edit and is allowed to clear the date
+    repository
+        .complete_holdings("acc-1", checkpoint.holdings_dirty_version, date(2024, 3, 1))
+        .await
+        .unwrap();
+    let checkpoint = repository.get_checkpoint("acc-1").unwrap().unwrap();
+    assert_eq!(checkpoint.holdings_dirty_from, None);
+}
+
+#[tokio::test]
+async fn test_complete_valuation_keeps_mark_made_during_rebuild() {
+    let (_db, repository) = setup();
+    let accounts = ["acc-1".to_string()];
+    repository
+        .mark_valuation_dirty(&accounts, date(2024, 1, 10))
+        .await
+        .unwrap();
+    let started = repository.get_checkpoint("acc-1").unwrap().unwrap();
+
+    repository
+        .mark_valuation_dirty(&accounts, date(2024, 1, 20))
+        .await
+        .unwrap();
+    repository
+        .complete_valuation("acc-1", started.valuation_dirty_version, date(2024, 3, 1))
+        .await
+        .unwrap();
+
+    let checkpoint = repository.get_checkpoint("acc-1").unwrap().unwrap();
+    assert_eq!(checkpoint.valuation_dirty_from, Some(date(2024, 1, 10)));
+}
+
+#[tokio::test]
+async fn test_fx_rate_side_change_dirties_holdings() {
+    let (db, repository) = setup();
+    insert_activity(&db, "act-1", "2024-01-10");
+    let checkpoint = repository.get_checkpoint("acc-1").unwrap().unwrap();
+    repository
+        .complete_holdings("acc-1", checkpoint.holdings_dirty_version, date(2024, 3, 1))
+        .await
+        .unwrap();
+
+    db.execute("UPDATE activities SET fx_rate_side = 'bid' WHERE id = 'act-1';");
+
+    let checkpoint = repository.get_checkpoint("acc-1").unwrap().unwrap();
+    assert_eq!(checkpoint.holdings_dirty_from, Some(date(2024, 1, 10)));
+}
+
+#[tokio::test]
+async fn test_fx_bid_and_ask_changes_dirty_checkpoints() {
+    let (db, repository) = setup();
+    db.insert_asset("EURUSD=X", "USD");
+    db.execute(
+        "UPDATE assets SET asset_type = 'FOREX' WHERE id = 'EURUSD=X';
+         INSERT INTO quotes (id, symbol, timestamp, open, high, low, close, adjclose, volume, currency, data_source, created_at)
+         VALUES ('q-1', 'EURUSD=X', '2024-01-15T00:00:00+00:00', '1.1', '1.12', '1.08', '1.1', '1.1', '0', 'USD', 'MANUAL', '2024-01-15T00:00:00+00:00');",
+    );
+
+    for column in ["low", "high"] {
+        let checkpoint = repository.get_checkpoint("acc-1").unwrap().unwrap();
+        repository
+            .complete_holdings("acc-1", checkpoint.holdings_dirty_version, date(2024, 3, 1))
+            .await
+            .unwrap();
+        repository
+            .complete_valuation(
+                "acc-1",
+                checkpoint.valuation_dirty_version,
+                date(2024, 3, 1),
+            )
+            .await
+            .unwrap();
+        let cleared = repository.get_checkpoint("acc-1").unwrap().unwrap();
+        assert_eq!(cleared.holdings_dirty_from, None);
+        assert_eq!(cleared.valuation_dirty_from, None);
+
+        // Only the bid (low) or ask (high) moves; close and timestamp stay
+        db.execute(&format!(
+            "UPDATE quotes SET {column} = '1.09' WHERE id = 'q-1';"
+        ));
+
+        let checkpoint = repository.get_checkpoint("acc-1").unwrap().unwrap();
+        assert_eq!(
+            checkpoint.holdings_dirty_from,
+            Some(date(2024, 1, 15)),
+            "{column}"
+        );
+        assert_eq!(
+            checkpoint.valuation_dirty_from,
+            Some(date(2024, 1, 15)),
+            "{column}"
+        );
+    }
+}
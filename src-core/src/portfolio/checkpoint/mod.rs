@@ -0,0 +1,8 @@
+pub mod checkpoint_model;
+pub mod checkpoint_repository;
+
+pub use checkpoint_model::*;
+pub use checkpoint_repository::*;
+
+#[cfg(test)]
+mod checkpoint_repository_tests;
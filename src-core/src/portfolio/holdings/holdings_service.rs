@@ -1,13 +1,13 @@
 use crate::assets::AssetServiceTrait;
 use crate::assets_model::{Asset, Country as AssetCountry, Sector as AssetSector};
-use crate::errors::{CalculatorError, Error as CoreError, Result};
+use crate::errors::{CalculatorError, Error as CoreError, Result, ValidationError};
 use crate::fx::currency::{get_normalization_rule, normalize_currency_code};
 use crate::portfolio::holdings::holdings_model::{
     Country, Holding, HoldingType, Instrument, MonetaryValue, Sector,
 };
-use crate::portfolio::snapshot::{self, Position, SnapshotServiceTrait};
+use crate::portfolio::snapshot::{self, AccountStateSnapshot, Position, SnapshotServiceTrait};
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
 use log::{debug, error, warn};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
@@ -21,6 +21,15 @@ use super::HoldingsValuationServiceTrait;
 pub trait HoldingsServiceTrait: Send + Sync {
     async fn get_holdings(&self, account_id: &str, base_currency: &str) -> Result<Vec<Holding>>;
 
+    /// Retrieves the holdings of an account as they stood at the end of `date`, valued with
+    /// that day's quotes and FX rates and including lot details.
+    async fn get_holdings_as_of(
+        &self,
+        account_id: &str,
+        date: NaiveDate,
+        base_currency: &str,
+    ) -> Result<Vec<Holding>>;
+
     /// Retrieves a specific holding for an account, calculates its valuation, and includes lot details.
     async fn get_holding(
         &self,
@@ -204,6 +213,205 @@ mod tests {
         assert_eq!(holding.prev_close_value.as_ref().unwrap().local, dec!(10));
         assert_eq!(holding.prev_close_value.as_ref().unwrap().base, dec!(10));
     }
+
+    struct MockAssetService;
+
+    #[async_trait]
+    impl AssetServiceTrait for MockAssetService {
+        fn get_assets(&self) -> Result<Vec<Asset>> {
+            unimplemented!()
+        }
+        fn get_asset_by_id(&self, _asset_id: &str) -> Result<Asset> {
+            unimplemented!()
+        }
+        async fn delete_asset(&self, _asset_id: &str) -> Result<()> {
+            unimplemented!()
+        }
+        async fn update_asset_profile(
+            &self,
+            _asset_id: &str,
+            _payload: crate::assets_model::UpdateAssetProfile,
+        ) -> Result<Asset> {
+            unimplemented!()
+        }
+        fn load_cash_assets(&self, _base_currency: &str) -> Result<Vec<Asset>> {
+            unimplemented!()
+        }
+        async fn create_cash_asset(&self, _currency: &str) -> Result<Asset> {
+            unimplemented!()
+        }
+        async fn create_manual_asset(&self, _symbol: &str, _currency: String) -> Result<Asset> {
+            unimplemented!()
+        }
+        async fn get_or_create_asset(
+            &self,
+            _asset_id: &str,
+            _context_currency: Option<String>,
+        ) -> Result<Asset> {
+            unimplemented!()
+        }
+        async fn update_asset_data_source(
+            &self,
+            _asset_id: &str,
+            _data_source: String,
+        ) -> Result<Asset> {
+            unimplemented!()
+        }
+        async fn get_assets_by_symbols(&self, symbols: &Vec<String>) -> Result<Vec<Asset>> {
+            Ok(symbols
+                .iter()
+                .map(|symbol| Asset {
+                    id: symbol.clone(),
+                    symbol: symbol.clone(),
+                    currency: "USD".to_string(),
+                    data_source: "MANUAL".to_string(),
+                    ..Default::default()
+                })
+                .collect())
+        }
+    }
+
+    struct MockSnapshotService {
+        snapshot: AccountStateSnapshot,
+    }
+
+    #[async_trait]
+    impl SnapshotServiceTrait for MockSnapshotService {
+        async fn calculate_holdings_snapshots(
+            &self,
+            _account_ids: Option<&[String]>,
+        ) -> Result<usize> {
+            unimplemented!()
+        }
+        async fn force_recalculate_holdings_snapshots(
+            &self,
+            _account_ids: Option<&[String]>,
+        ) -> Result<usize> {
+            unimplemented!()
+        }
+        fn get_holdings_keyframes(
+            &self,
+            _account_id: &str,
+            _start_date: Option<NaiveDate>,
+            _end_date: Option<NaiveDate>,
+        ) -> Result<Vec<AccountStateSnapshot>> {
+            unimplemented!()
+        }
+        fn get_daily_holdings_snapshots(
+            &self,
+            _account_id: &str,
+            start_date: Option<NaiveDate>,
+            _end_date: Option<NaiveDate>,
+        ) -> Result<Vec<AccountStateSnapshot>> {
+            let mut snapshot = self.snapshot.clone();
+            snapshot.snapshot_date = start_date.unwrap();
+            Ok(vec![snapshot])
+        }
+        fn get_latest_holdings_snapshot(
+            &self,
+            _account_id: &str,
+        ) -> Result<Option<AccountStateSnapshot>> {
+            unimplemented!()
+        }
+        async fn calculate_total_portfolio_snapshots(&self) -> Result<usize> {
+            unimplemented!()
+        }
+    }
+
+    // Values every holding at 1.0 per unit, or fails like a missing dated quote would
+    struct MockValuationService {
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl HoldingsValuationServiceTrait for MockValuationService {
+        async fn calculate_holdings_live_valuation(&self, _holdings: &mut [Holding]) -> Result<()> {
+            unimplemented!()
+        }
+        async fn calculate_holdings_valuation_as_of(
+            &self,
+            holdings: &mut [Holding],
+            date: NaiveDate,
+        ) -> Result<()> {
+            if self.fail {
+                return Err(crate::market_data::MarketDataError::NotFound(format!(
+                    "No quote for AAPL on {}",
+                    date
+                ))
+                .into());
+            }
+            for holding in holdings.iter_mut() {
+                holding.market_value = MonetaryValue {
+                    local: holding.quantity,
+                    base: holding.quantity,
+                };
+            }
+            Ok(())
+        }
+    }
+
+    fn holdings_service(fail_valuation: bool) -> HoldingsService {
+        let lot = Lot {
+            id: "LOT1".to_string(),
+            position_id: "POS-AAPL-acc-1".to_string(),
+            acquisition_date: Utc::now(),
+            quantity: dec!(10),
+            cost_basis: dec!(1000),
+            acquisition_price: dec!(100),
+            acquisition_fees: dec!(0),
+        };
+        let position = Position {
+            id: "POS-AAPL-acc-1".to_string(),
+            account_id: "acc-1".to_string(),
+            asset_id: "AAPL".to_string(),
+            quantity: dec!(10),
+            average_cost: dec!(100),
+            total_cost_basis: dec!(1000),
+            currency: "USD".to_string(),
+            lots: VecDeque::from(vec![lot]),
+            ..Default::default()
+        };
+        let snapshot = AccountStateSnapshot {
+            account_id: "acc-1".to_string(),
+            currency: "USD".to_string(),
+            positions: HashMap::from([("AAPL".to_string(), position)]),
+            ..Default::default()
+        };
+        HoldingsService::new(
+            Arc::new(MockAssetService),
+            Arc::new(MockSnapshotService { snapshot }),
+            Arc::new(MockValuationService {
+                fail: fail_valuation,
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_holdings_as_of_include_lots() {
+        let date = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+        let holdings = holdings_service(false)
+            .get_holdings_as_of("acc-1", date, "USD")
+            .await
+            .unwrap();
+
+        assert_eq!(holdings.len(), 1);
+        let holding = &holdings[0];
+        assert_eq!(holding.as_of_date, date);
+        let lots = holding.lots.as_ref().unwrap();
+        assert_eq!(lots.len(), 1);
+        assert_eq!(lots[0].quantity, dec!(10));
+        assert_eq!(lots[0].cost_basis, dec!(1000));
+    }
+
+    #[tokio::test]
+    async fn test_holdings_as_of_fail_when_valuation_fails() {
+        let date = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+        let result = holdings_service(true)
+            .get_holdings_as_of("acc-1", date, "USD")
+            .await;
+
+        assert!(result.is_err());
+    }
 }
 
 fn apply_factor_to_monetary_value(value: &mut MonetaryValue, factor: Decimal) {
@@ -256,52 +464,25 @@ fn normalize_holding_currency(holding: &mut Holding) {
     }
 }
 
-#[async_trait]
-impl HoldingsServiceTrait for HoldingsService {
-    async fn get_holdings(&self, account_id: &str, base_currency: &str) -> Result<Vec<Holding>> {
-        debug!(
-            "Getting holdings for account {} in base currency {}",
-            account_id, base_currency
-        );
+impl HoldingsService {
+    // Turns a holdings snapshot into valued holdings with weights. `as_of` values them on
+    // that date and keeps the lot detail; `None` values them at the latest quotes.
+    async fn build_valued_holdings(
+        &self,
+        account_id: &str,
+        snapshot: &AccountStateSnapshot,
+        base_currency: &str,
+        as_of: Option<NaiveDate>,
+    ) -> Result<Vec<Holding>> {
         let today = Utc::now().date_naive();
 
-        let latest_snapshot = match self
-            .snapshot_service
-            .get_latest_holdings_snapshot(account_id)
-        {
-            Ok(Some(snap)) => snap,
-            Ok(None) => {
-                warn!(
-                    "No calculated holdings found for account {}. Returning empty holdings list.",
-                    account_id
-                );
-                return Ok(Vec::new());
-            }
-            Err(core_error) => {
-                if matches!(core_error, CoreError::Repository(ref msg) if msg.contains("No snapshot found"))
-                {
-                    warn!(
-                        "No calculated holdings found for account {}. Returning empty holdings list.",
-                        account_id
-                    );
-                    return Ok(Vec::new());
-                } else {
-                    error!(
-                        "Failed to get latest snapshot for account {}: {}",
-                        account_id, core_error
-                    );
-                    return Err(core_error);
-                }
-            }
-        };
-
-        let snapshot_positions: Vec<snapshot::Position> = latest_snapshot
+        let snapshot_positions: Vec<snapshot::Position> = snapshot
             .positions
             .values()
             .filter(|p| p.quantity != Decimal::ZERO)
             .cloned()
             .collect();
-        let cash_balances_map: &HashMap<String, Decimal> = &latest_snapshot.cash_balances;
+        let cash_balances_map: &HashMap<String, Decimal> = &snapshot.cash_balances;
 
         let security_symbols: Vec<String> = snapshot_positions
             .iter()
@@ -399,7 +580,7 @@ impl HoldingsServiceTrait for HoldingsService {
                 instrument: instrument_view,
                 quantity: snapshot_pos.quantity,
                 open_date: Some(snapshot_pos.inception_date),
-                lots: None,
+                lots: as_of.map(|_| snapshot_pos.lots.clone()),
                 local_currency: snapshot_pos.currency.clone(),
                 base_currency: base_currency.to_string(),
                 fx_rate: None,
@@ -419,7 +600,7 @@ impl HoldingsServiceTrait for HoldingsService {
                 day_change_pct: None,
                 prev_close_value: None,
                 weight: Decimal::ZERO,
-                as_of_date: today,
+                as_of_date: as_of.unwrap_or(today),
             };
             holdings.push(holding_view);
         }
@@ -462,24 +643,32 @@ impl HoldingsServiceTrait for HoldingsService {
                     base: Decimal::ZERO,
                 }),
                 weight: Decimal::ZERO,
-                as_of_date: today,
+                as_of_date: as_of.unwrap_or(today),
             };
             holdings.push(holding_view);
         }
 
         if !holdings.is_empty() {
-            match self
-                .valuation_service
-                .calculate_holdings_live_valuation(&mut holdings)
-                .await
-            {
-                Ok(_) => (),
-                Err(e) => {
-                    error!(
-                         "Live valuation calculation failed for account {}: {}. Returning partially valued holdings.",
-                         account_id, e
-                     );
+            match as_of {
+                // A dated valuation is only meaningful when every holding could be priced
+                Some(date) => {
+                    self.valuation_service
+                        .calculate_holdings_valuation_as_of(&mut holdings, date)
+                        .await?
                 }
+                None => match self
+                    .valuation_service
+                    .calculate_holdings_live_valuation(&mut holdings)
+                    .await
+                {
+                    Ok(_) => (),
+                    Err(e) => {
+                        error!(
+                            "Live valuation calculation failed for account {}: {}. Returning partially valued holdings.",
+                            account_id, e
+                        );
+                    }
+                },
             }
         } else {
             debug!(
@@ -511,6 +700,82 @@ impl HoldingsServiceTrait for HoldingsService {
 
         Ok(holdings)
     }
+}
+
+#[async_trait]
+impl HoldingsServiceTrait for HoldingsService {
+    async fn get_holdings(&self, account_id: &str, base_currency: &str) -> Result<Vec<Holding>> {
+        debug!(
+            "Getting holdings for account {} in base currency {}",
+            account_id, base_currency
+        );
+
+        let latest_snapshot = match self
+            .snapshot_service
+            .get_latest_holdings_snapshot(account_id)
+        {
+            Ok(Some(snap)) => snap,
+            Ok(None) => {
+                warn!(
+                    "No calculated holdings found for account {}. Returning empty holdings list.",
+                    account_id
+                );
+                return Ok(Vec::new());
+            }
+            Err(core_error) => {
+                if matches!(core_error, CoreError::Repository(ref msg) if msg.contains("No snapshot found"))
+                {
+                    warn!(
+                        "No calculated holdings found for account {}. Returning empty holdings list.",
+                        account_id
+                    );
+                    return Ok(Vec::new());
+                } else {
+                    error!(
+                        "Failed to get latest snapshot for account {}: {}",
+                        account_id, core_error
+                    );
+                    return Err(core_error);
+                }
+            }
+        };
+
+        self.build_valued_holdings(account_id, &latest_snapshot, base_currency, None)
+            .await
+    }
+
+    async fn get_holdings_as_of(
+        &self,
+        account_id: &str,
+        date: NaiveDate,
+        base_currency: &str,
+    ) -> Result<Vec<Holding>> {
+        debug!(
+            "Getting holdings for account {} as of {} in base currency {}",
+            account_id, date, base_currency
+        );
+        if date > Utc::now().date_naive() {
+            return Err(CoreError::Validation(ValidationError::InvalidInput(
+                format!("Holdings date {} is in the future", date),
+            )));
+        }
+
+        let snapshot = self
+            .snapshot_service
+            .get_daily_holdings_snapshots(account_id, Some(date), Some(date))?
+            .into_iter()
+            .next();
+        let Some(snapshot) = snapshot else {
+            debug!(
+                "No holdings calculated for account {} on {}. Returning empty holdings list.",
+                account_id, date
+            );
+            return Ok(Vec::new());
+        };
+
+        self.build_valued_holdings(account_id, &snapshot, base_currency, Some(date))
+            .await
+    }
 
     async fn get_holding(
         &self,
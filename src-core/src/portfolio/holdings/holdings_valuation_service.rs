@@ -1,20 +1,30 @@
 use crate::errors::Result;
 use crate::fx::currency::{normalize_amount, normalize_currency_code};
 use crate::fx::fx_traits::FxServiceTrait;
-use crate::market_data::market_data_model::LatestQuotePair;
+use crate::market_data::market_data_model::{LatestQuotePair, Quote};
 use crate::market_data::market_data_traits::MarketDataServiceTrait;
+use crate::market_data::MarketDataError;
 use crate::portfolio::holdings::{Holding, HoldingType, MonetaryValue};
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
 use log::{debug, warn};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 #[async_trait]
 pub trait HoldingsValuationServiceTrait: Send + Sync {
     async fn calculate_holdings_live_valuation(&self, holdings: &mut [Holding]) -> Result<()>;
+
+    /// Values the holdings with the quotes and FX rates in effect on `date`.
+    /// Day change is measured against the previous calendar day. Fails when a
+    /// quote or FX rate for `date` is missing instead of valuing at zero or 1.0.
+    async fn calculate_holdings_valuation_as_of(
+        &self,
+        holdings: &mut [Holding],
+        date: NaiveDate,
+    ) -> Result<()>;
 }
 
 #[derive(Clone)]
@@ -34,15 +44,25 @@ impl HoldingsValuationService {
         }
     }
 
-    // Private helper to get FX rate with logging and fallback
+    // Private helper to get FX rate with logging and fallback.
+    // `as_of` selects the rate for that date instead of the latest one; a dated
+    // valuation has no sensible fallback, so its lookup errors are returned.
     fn get_fx_rate_or_fallback(
         &self,
         from_curr: &str,
         to_curr: &str,
+        as_of: Option<NaiveDate>,
         context_msg: &str,
-    ) -> Decimal {
-        match self.fx_service.get_latest_exchange_rate(from_curr, to_curr) {
-            Ok(rate) => rate,
+    ) -> Result<Decimal> {
+        let rate = match as_of {
+            Some(date) => self
+                .fx_service
+                .get_exchange_rate_for_date(from_curr, to_curr, date),
+            None => self.fx_service.get_latest_exchange_rate(from_curr, to_curr),
+        };
+        match rate {
+            Ok(rate) => Ok(rate),
+            Err(e) if as_of.is_some() => Err(e),
             Err(e) => {
                 warn!(
                     "{}: Error getting FX rate {}->{}: {}. Using 1.0.",
@@ -51,17 +71,13 @@ impl HoldingsValuationService {
                     to_curr,
                     e.to_string()
                 );
-                Decimal::ONE // Fallback
+                Ok(Decimal::ONE) // Fallback
             }
         }
     }
 
-    // Helper to fetch necessary market data in batches
-    async fn fetch_batch_quote_data(
-        &self,
-        holdings: &[Holding],
-    ) -> Result<HashMap<String, LatestQuotePair>> {
-        let required_symbols: Vec<String> = holdings
+    fn required_symbols(holdings: &[Holding]) -> Vec<String> {
+        holdings
             .iter()
             .filter_map(|holding| {
                 if holding.holding_type == HoldingType::Security {
@@ -70,7 +86,15 @@ impl HoldingsValuationService {
                     None // Skip cash holdings
                 }
             })
-            .collect();
+            .collect()
+    }
+
+    // Helper to fetch necessary market data in batches
+    async fn fetch_batch_quote_data(
+        &self,
+        holdings: &[Holding],
+    ) -> Result<HashMap<String, LatestQuotePair>> {
+        let required_symbols = Self::required_symbols(holdings);
 
         let latest_quote_pairs = if !required_symbols.is_empty() {
             self.market_data_service
@@ -81,6 +105,75 @@ impl HoldingsValuationService {
 
         Ok(latest_quote_pairs)
     }
+
+    // Pairs the quote in effect on `date` with the one of the day before
+    fn fetch_quote_data_as_of(
+        &self,
+        holdings: &[Holding],
+        date: NaiveDate,
+    ) -> Result<HashMap<String, LatestQuotePair>> {
+        let required_symbols: HashSet<String> =
+            Self::required_symbols(holdings).into_iter().collect();
+        if required_symbols.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let previous_day = date.pred_opt().unwrap_or(date);
+        let quotes = self
+            .market_data_service
+            .get_historical_quotes_for_symbols_in_range(&required_symbols, previous_day, date)?;
+
+        let mut quotes_by_symbol: HashMap<String, (Option<Quote>, Option<Quote>)> = HashMap::new();
+        for quote in quotes {
+            let quote_date = quote.timestamp.date_naive();
+            let entry = quotes_by_symbol.entry(quote.symbol.clone()).or_default();
+            if quote_date == date {
+                entry.0 = Some(quote);
+            } else if quote_date == previous_day {
+                entry.1 = Some(quote);
+            }
+        }
+
+        Ok(quotes_by_symbol
+            .into_iter()
+            .filter_map(|(symbol, (latest, previous))| {
+                latest.map(|latest| (symbol, LatestQuotePair { latest, previous }))
+            })
+            .collect())
+    }
+
+    fn value_holdings(
+        &self,
+        holdings: &mut [Holding],
+        quote_pairs: &HashMap<String, LatestQuotePair>,
+        as_of: Option<NaiveDate>,
+    ) -> Result<()> {
+        let today = Utc::now().date_naive();
+
+        for holding in holdings.iter_mut() {
+            match holding.holding_type {
+                HoldingType::Security => {
+                    holding.as_of_date = match as_of {
+                        Some(date) => date,
+                        None => holding
+                            .instrument
+                            .as_ref()
+                            .and_then(|i| quote_pairs.get(&i.symbol))
+                            .map(|qp| qp.latest.timestamp.date_naive())
+                            .unwrap_or(today),
+                    };
+                    let base_currency = holding.base_currency.clone();
+                    self.calculate_security_valuation(holding, &base_currency, quote_pairs, as_of)?;
+                }
+                HoldingType::Cash => {
+                    holding.as_of_date = as_of.unwrap_or(today);
+                    let base_currency = holding.base_currency.clone();
+                    self.calculate_cash_valuation(holding, &base_currency, as_of)?;
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -98,32 +191,30 @@ impl HoldingsValuationServiceTrait for HoldingsValuationService {
         let latest_quote_pairs: HashMap<String, LatestQuotePair> =
             self.fetch_batch_quote_data(holdings).await?;
 
-        let today = Utc::now().date_naive();
+        self.value_holdings(holdings, &latest_quote_pairs, None)?;
 
-        for holding in holdings.iter_mut() {
-            match holding.holding_type {
-                HoldingType::Security => {
-                    if let Some(sym) = holding.instrument.as_ref().map(|i| i.symbol.clone()) {
-                        holding.as_of_date = latest_quote_pairs
-                            .get(&sym)
-                            .map(|qp| qp.latest.timestamp.date_naive())
-                            .unwrap_or(today);
-                    } else {
-                        holding.as_of_date = today;
-                    }
-                    let base_currency = holding.base_currency.clone();
-                    self.calculate_security_valuation(holding, &base_currency, &latest_quote_pairs)
-                        .await?;
-                }
-                HoldingType::Cash => {
-                    holding.as_of_date = today;
-                    let base_currency = holding.base_currency.clone();
-                    self.calculate_cash_valuation(holding, &base_currency)?;
-                }
-            }
+        debug!("Finished calculate_holdings_live_valuation.");
+        Ok(())
+    }
+
+    async fn calculate_holdings_valuation_as_of(
+        &self,
+        holdings: &mut [Holding],
+        date: NaiveDate,
+    ) -> Result<()> {
+        if holdings.is_empty() {
+            return Ok(());
         }
+        debug!(
+            "Starting calculate_holdings_valuation_as_of {} for {} holdings.",
+            date,
+            holdings.len()
+        );
 
-        debug!("Finished calculate_holdings_live_valuation.");
+        let quote_pairs = self.fetch_quote_data_as_of(holdings, date)?;
+        self.value_holdings(holdings, &quote_pairs, Some(date))?;
+
+        debug!("Finished calculate_holdings_valuation_as_of.");
         Ok(())
     }
 }
@@ -131,11 +222,12 @@ impl HoldingsValuationServiceTrait for HoldingsValuationService {
 // --- New Helper Methods for Valuation ---
 
 impl HoldingsValuationService {
-    async fn calculate_security_valuation(
+    fn calculate_security_valuation(
         &self,
         holding: &mut Holding,
         base_currency: &str,
         latest_quote_pairs: &HashMap<String, LatestQuotePair>,
+        as_of: Option<NaiveDate>,
     ) -> Result<()> {
         let instrument = match &holding.instrument {
             Some(inst) => inst,
@@ -157,8 +249,9 @@ impl HoldingsValuationService {
         let fx_rate_local_to_base = self.get_fx_rate_or_fallback(
             pos_currency,
             base_currency,
+            as_of,
             &format!("{}: FX Local->Base", context_msg),
-        );
+        )?;
         holding.fx_rate = Some(fx_rate_local_to_base);
 
         // --- Calculate Base Cost Basis (If applicable) ---
@@ -202,8 +295,9 @@ impl HoldingsValuationService {
             let fx_rate_quote_to_base = self.get_fx_rate_or_fallback(
                 &normalized_quote_currency,
                 base_currency,
+                as_of,
                 &format!("{}: FX Quote->Base", context_msg),
-            );
+            )?;
 
             let market_price_quote_curr = latest_quote.close;
             let market_value_quote_major = normalized_price * quantity;
@@ -212,8 +306,9 @@ impl HoldingsValuationService {
             let fx_rate_quote_to_local = self.get_fx_rate_or_fallback(
                 &normalized_quote_currency,
                 pos_currency,
+                as_of,
                 &format!("{}: FX Quote->Local", context_msg),
-            );
+            )?;
 
             let market_value_local = market_value_quote_major * fx_rate_quote_to_local;
             let market_value_base = market_value_quote_major * fx_rate_quote_to_base;
@@ -304,6 +399,10 @@ impl HoldingsValuationService {
                 holding.day_change_pct = None;
                 holding.prev_close_value = None;
             }
+        } else if let Some(date) = as_of {
+            return Err(
+                MarketDataError::NotFound(format!("No quote for {} on {}", symbol, date)).into(),
+            );
         } else {
             warn!(
                 "{}: Quote pair data missing. Market valuation incomplete.",
@@ -326,7 +425,12 @@ impl HoldingsValuationService {
         Ok(())
     }
 
-    fn calculate_cash_valuation(&self, holding: &mut Holding, base_currency: &str) -> Result<()> {
+    fn calculate_cash_valuation(
+        &self,
+        holding: &mut Holding,
+        base_currency: &str,
+        as_of: Option<NaiveDate>,
+    ) -> Result<()> {
         let cash_currency = &holding.local_currency;
         let cash_amount = holding.quantity;
         let context_msg = format!("HoldingValuation [CASH {}]", cash_currency);
@@ -335,7 +439,7 @@ impl HoldingsValuationService {
         holding.price = Some(dec!(1.0));
 
         let fx_rate_cash_to_base =
-            self.get_fx_rate_or_fallback(cash_currency, base_currency, &context_msg);
+            self.get_fx_rate_or_fallback(cash_currency, base_currency, as_of, &context_msg)?;
         holding.fx_rate = Some(fx_rate_cash_to_base);
 
         let value_base = cash_amount * fx_rate_cash_to_base;
@@ -28,6 +28,7 @@ mod tests {
     #[derive(Clone, Default)]
     struct MockFxService {
         rates: Arc<Mutex<HashMap<(String, String), Decimal>>>,
+        dated_rates: Arc<Mutex<HashMap<(String, String, NaiveDate), Decimal>>>,
         should_fail: Arc<Mutex<HashMap<(String, String), bool>>>,
     }
 
@@ -40,6 +41,12 @@ mod tests {
             }
         }
 
+        fn add_dated_rate(&self, from: &str, to: &str, date: NaiveDate, rate: Decimal) {
+            let mut rates = self.dated_rates.lock().unwrap();
+            rates.insert((from.to_string(), to.to_string(), date), rate);
+            rates.insert((to.to_string(), from.to_string(), date), dec!(1) / rate);
+        }
+
         fn set_fail(&self, from: &str, to: &str, fail: bool) {
             let mut should_fail = self.should_fail.lock().unwrap();
             should_fail.insert((from.to_string(), to.to_string()), fail);
@@ -72,11 +79,23 @@ mod tests {
         }
         fn get_exchange_rate_for_date(
             &self,
-            _from_currency: &str,
-            _to_currency: &str,
-            _date: NaiveDate,
+            from_currency: &str,
+            to_currency: &str,
+            date: NaiveDate,
         ) -> Result<Decimal> {
-            unimplemented!()
+            if from_currency == to_currency {
+                return Ok(Decimal::ONE);
+            }
+            let rates = self.dated_rates.lock().unwrap();
+            rates
+                .get(&(from_currency.to_string(), to_currency.to_string(), date))
+                .copied()
+                .ok_or_else(|| {
+                    Error::Fx(crate::fx::FxError::RateNotFound(format!(
+                        "Mock rate not found for {}->{} on {}",
+                        from_currency, to_currency, date
+                    )))
+                })
         }
         fn convert_currency(
             &self,
@@ -147,6 +166,7 @@ mod tests {
     #[derive(Clone, Default)]
     struct MockMarketDataService {
         quotes: Arc<Mutex<HashMap<String, LatestQuotePair>>>,
+        historical_quotes: Arc<Mutex<Vec<Quote>>>,
         should_fail: Arc<Mutex<bool>>,
     }
 
@@ -155,6 +175,11 @@ mod tests {
             let mut quotes = self.quotes.lock().unwrap();
             quotes.insert(symbol.to_string(), LatestQuotePair { latest, previous });
         }
+
+        fn add_historical_quote(&self, symbol: &str, mut quote: Quote) {
+            quote.symbol = symbol.to_string();
+            self.historical_quotes.lock().unwrap().push(quote);
+        }
     }
 
     #[async_trait]
@@ -208,11 +233,20 @@ mod tests {
         }
         fn get_historical_quotes_for_symbols_in_range(
             &self,
-            _symbols: &HashSet<String>,
-            _start_date: NaiveDate,
-            _end_date: NaiveDate,
+            symbols: &HashSet<String>,
+            start_date: NaiveDate,
+            end_date: NaiveDate,
         ) -> Result<Vec<Quote>> {
-            unimplemented!()
+            let quotes = self.historical_quotes.lock().unwrap();
+            Ok(quotes
+                .iter()
+                .filter(|q| symbols.contains(&q.symbol))
+                .filter(|q| {
+                    let date = q.timestamp.date_naive();
+                    date >= start_date && date <= end_date
+                })
+                .cloned()
+                .collect())
         }
         async fn get_daily_quotes(
             &self,
@@ -1615,4 +1649,128 @@ mod tests {
         assert!(result.is_ok());
         assert!(holdings.is_empty()); // Should remain empty
     }
+
+    #[tokio::test]
+    async fn test_valuation_as_of_uses_quotes_and_fx_of_that_date() {
+        let (fx_service, market_data_service, valuation_service) = setup_test_env();
+        let year_end = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+
+        market_data_service
+            .add_historical_quote("AAPL", create_quote("2024-12-30", dec!(100), "USD"));
+        market_data_service
+            .add_historical_quote("AAPL", create_quote("2024-12-31", dec!(110), "USD"));
+        market_data_service
+            .add_historical_quote("AAPL", create_quote("2025-01-02", dec!(150), "USD"));
+        fx_service.add_dated_rate("USD", "CAD", year_end, dec!(1.4));
+
+        let mut holdings = vec![
+            create_holding(
+                "h_sec",
+                HoldingType::Security,
+                "AAPL",
+                dec!(10),
+                "USD",
+                "CAD",
+                Some(dec!(900)),
+                None,
+            ),
+            create_holding(
+                "h_cash",
+                HoldingType::Cash,
+                "USD",
+                dec!(50),
+                "USD",
+                "CAD",
+                Some(dec!(50)),
+                None,
+            ),
+        ];
+
+        valuation_service
+            .calculate_holdings_valuation_as_of(&mut holdings, year_end)
+            .await
+            .unwrap();
+
+        let security = &holdings[0];
+        let tolerance = dec!(0.0001);
+        assert_eq!(security.as_of_date, year_end);
+        assert_eq!(security.price, Some(dec!(110)));
+        assert_eq!(security.fx_rate, Some(dec!(1.4)));
+        assert_monetary_value_approx(
+            Some(&security.market_value),
+            dec!(1100),
+            dec!(1540),
+            tolerance,
+            "Year-end market value",
+        );
+        assert_monetary_value_approx(
+            security.day_change.as_ref(),
+            dec!(100),
+            dec!(140),
+            tolerance,
+            "Day change against 30/12",
+        );
+
+        let cash = &holdings[1];
+        assert_eq!(cash.as_of_date, year_end);
+        assert_monetary_value_approx(
+            Some(&cash.market_value),
+            dec!(50),
+            dec!(70),
+            tolerance,
+            "Year-end cash value",
+        );
+    }
+
+    #[tokio::test]
+    async fn test_valuation_as_of_fails_without_fx_rate_for_that_date() {
+        let (_fx_service, market_data_service, valuation_service) = setup_test_env();
+        let year_end = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+        market_data_service
+            .add_historical_quote("AAPL", create_quote("2024-12-31", dec!(110), "USD"));
+
+        let mut holdings = vec![create_holding(
+            "h_sec",
+            HoldingType::Security,
+            "AAPL",
+            dec!(10),
+            "USD",
+            "CAD",
+            Some(dec!(900)),
+            None,
+        )];
+
+        let result = valuation_service
+            .calculate_holdings_valuation_as_of(&mut holdings, year_end)
+            .await;
+
+        assert!(matches!(result, Err(Error::Fx(_))));
+    }
+
+    #[tokio::test]
+    async fn test_valuation_as_of_fails_without_quote_for_that_date() {
+        let (fx_service, _market_data_service, valuation_service) = setup_test_env();
+        let year_end = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+        fx_service.add_dated_rate("USD", "CAD", year_end, dec!(1.4));
+
+        let mut holdings = vec![create_holding(
+            "h_sec",
+            HoldingType::Security,
+            "AAPL",
+            dec!(10),
+            "USD",
+            "CAD",
+            Some(dec!(900)),
+            None,
+        )];
+
+        let result = valuation_service
+            .calculate_holdings_valuation_as_of(&mut holdings, year_end)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(Error::MarketData(MarketDataError::NotFound(_)))
+        ));
+    }
 }
@@ -0,0 +1,266 @@
+//! Twelve-month income forecast.
+//!
+//! Every dividend or interest payment received over the trailing twelve months is
+//! expected again on the same day one year later. Dividends are scaled from the
+//! units held when they were paid to the units held now, so positions bought or
+//! sold since then are reflected and fully sold positions drop out. Interest on
+//! cash repeats as received; interest on a security (e.g. a bond) scales like a
+//! dividend. There is no model of deposit or bond schedules, so payments that
+//! did not occur in the trailing year are not forecast.
+
+use chrono::{Datelike, Months, NaiveDate};
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::activities::activities_constants::{ACTIVITY_TYPE_DIVIDEND, ACTIVITY_TYPE_INTEREST};
+use crate::activities::activities_model::Activity;
+use crate::constants::CASH_ASSET_PREFIX;
+
+use super::income_model::{IncomeForecast, IncomeForecastEntry};
+
+/// Months covered by the forecast and by the trailing payments it repeats
+pub const FORECAST_MONTHS: u32 = 12;
+
+/// Units held per asset (asset_id -> quantity)
+pub type Quantities = HashMap<String, Decimal>;
+
+/// Units held per asset of one account by snapshot date
+pub type HoldingsHistory = BTreeMap<NaiveDate, Quantities>;
+
+/// Units of `asset_id` held on `date`, from the latest snapshot on or before it
+fn quantity_on(
+    history: Option<&HoldingsHistory>,
+    asset_id: &str,
+    date: NaiveDate,
+) -> Option<Decimal> {
+    history?
+        .range(..=date)
+        .next_back()
+        .map(|(_, quantities)| quantities.get(asset_id).copied().unwrap_or_default())
+}
+
+/// Projects the income payments of the trailing twelve months up to `as_of` one
+/// year ahead. `converted_amount` is left equal to `amount`.
+pub fn project_income(
+    payments: &[Activity],
+    holdings: &HashMap<String, HoldingsHistory>,
+    current: &HashMap<String, Quantities>,
+    as_of: NaiveDate,
+) -> Vec<IncomeForecastEntry> {
+    let window_start = as_of - Months::new(FORECAST_MONTHS);
+    let mut entries: Vec<IncomeForecastEntry> = payments
+        .iter()
+        .filter(|p| {
+            p.activity_type == ACTIVITY_TYPE_DIVIDEND || p.activity_type == ACTIVITY_TYPE_INTEREST
+        })
+        .filter_map(|payment| {
+            let source_date = payment.activity_date.date_naive();
+            if source_date <= window_start || source_date > as_of {
+                return None;
+            }
+            let amount = payment.amount.unwrap_or_default();
+            if amount <= Decimal::ZERO {
+                return None;
+            }
+
+            let (amount_per_unit, quantity, expected) =
+                if payment.asset_id.starts_with(CASH_ASSET_PREFIX) {
+                    (None, None, amount)
+                } else {
+                    let quantity = current
+                        .get(&payment.account_id)
+                        .and_then(|q| q.get(&payment.asset_id))
+                        .copied()
+                        .unwrap_or_default();
+                    if quantity <= Decimal::ZERO {
+                        return None;
+                    }
+                    let held = quantity_on(
+                        holdings.get(&payment.account_id),
+                        &payment.asset_id,
+                        source_date,
+                    )
+                    .filter(|q| *q > Decimal::ZERO);
+                    match held {
+                        Some(held) => {
+                            let per_unit = amount / held;
+                            (Some(per_unit), Some(quantity), per_unit * quantity)
+                        }
+                        None => (None, Some(quantity), amount),
+                    }
+                };
+
+            Some(IncomeForecastEntry {
+                expected_date: source_date + Months::new(FORECAST_MONTHS),
+                account_id: payment.account_id.clone(),
+                asset_id: payment.asset_id.clone(),
+                income_type: payment.activity_type.clone(),
+                source_date,
+                amount_per_unit,
+                quantity,
+                amount: expected,
+                currency: payment.currency.clone(),
+                converted_amount: expected,
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        a.expected_date
+            .cmp(&b.expected_date)
+            .then_with(|| a.asset_id.cmp(&b.asset_id))
+    });
+    entries
+}
+
+fn month_key(date: NaiveDate) -> String {
+    format!("{:04}-{:02}", date.year(), date.month())
+}
+
+/// Totals converted entries by month, type and asset. Every month from the day
+/// after `as_of` to the end of the window is present, with zero when nothing is due.
+pub fn summarize_forecast(
+    as_of: NaiveDate,
+    currency: String,
+    entries: Vec<IncomeForecastEntry>,
+) -> IncomeForecast {
+    let mut by_month = BTreeMap::new();
+    let end = as_of + Months::new(FORECAST_MONTHS);
+    let mut month = as_of
+        .succ_opt()
+        .unwrap_or(as_of)
+        .with_day(1)
+        .unwrap_or(as_of);
+    while month <= end {
+        by_month.insert(month_key(month), Decimal::ZERO);
+        month = match month.checked_add_months(Months::new(1)) {
+            Some(next) => next,
+            None => break,
+        };
+    }
+
+    let mut by_type: HashMap<String, Decimal> = HashMap::new();
+    let mut by_symbol: HashMap<String, Decimal> = HashMap::new();
+    let mut total_income = Decimal::ZERO;
+    for entry in &entries {
+        *by_month
+            .entry(month_key(entry.expected_date))
+            .or_insert(Decimal::ZERO) += entry.converted_amount;
+        *by_type.entry(entry.income_type.clone()).or_default() += entry.converted_amount;
+        *by_symbol.entry(entry.asset_id.clone()).or_default() += entry.converted_amount;
+        total_income += entry.converted_amount;
+    }
+
+    IncomeForecast {
+        as_of_date: as_of,
+        currency,
+        by_month,
+        by_type,
+        by_symbol,
+        total_income,
+        monthly_average: total_income / Decimal::from(FORECAST_MONTHS),
+        entries,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::date;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    fn payment(activity_type: &str, asset_id: &str, paid: NaiveDate, amount: Decimal) -> Activity {
+        let at = Utc.from_utc_datetime(&paid.and_hms_opt(0, 0, 0).unwrap());
+        Activity {
+            id: format!("{}-{}", asset_id, paid),
+            account_id: "acc".to_string(),
+            asset_id: asset_id.to_string(),
+            activity_type: activity_type.to_string(),
+            activity_date: at,
+            quantity: Decimal::ONE,
+            unit_price: amount,
+            currency: "VND".to_string(),
+            fee: Decimal::ZERO,
+            amount: Some(amount),
+            is_draft: false,
+            comment: None,
+            fx_rate_side: None,
+            created_at: at,
+            updated_at: at,
+        }
+    }
+
+    fn quantities(items: &[(&str, Decimal)]) -> Quantities {
+        items.iter().map(|(id, q)| (id.to_string(), *q)).collect()
+    }
+
+    #[test]
+    fn test_dividends_scale_to_current_quantity() {
+        let as_of = date(2025, 6, 30);
+        let holdings = HashMap::from([(
+            "acc".to_string(),
+            HoldingsHistory::from([
+                (
+                    date(2024, 1, 1),
+                    quantities(&[("FPT", dec!(100)), ("VNM", dec!(50))]),
+                ),
+                (
+                    date(2024, 10, 1),
+                    quantities(&[("FPT", dec!(200)), ("VNM", dec!(50))]),
+                ),
+            ]),
+        )]);
+        let current = HashMap::from([("acc".to_string(), quantities(&[("FPT", dec!(300))]))]);
+        let payments = vec![
+            payment("DIVIDEND", "FPT", date(2024, 8, 15), dec!(200000)),
+            payment("DIVIDEND", "FPT", date(2024, 12, 15), dec!(400000)),
+            // Sold since, so not expected again
+            payment("DIVIDEND", "VNM", date(2024, 9, 1), dec!(150000)),
+            // Outside the trailing year
+            payment("DIVIDEND", "FPT", date(2024, 6, 30), dec!(100000)),
+        ];
+
+        let entries = project_income(&payments, &holdings, &current, as_of);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].expected_date, date(2025, 8, 15));
+        assert_eq!(entries[0].amount_per_unit, Some(dec!(2000)));
+        assert_eq!(entries[0].amount, dec!(600000));
+        assert_eq!(entries[1].expected_date, date(2025, 12, 15));
+        assert_eq!(entries[1].amount, dec!(600000));
+    }
+
+    #[test]
+    fn test_cash_interest_repeats_as_received() {
+        let as_of = date(2025, 6, 30);
+        let payments = vec![payment(
+            "INTEREST",
+            "$CASH-VND",
+            date(2025, 3, 1),
+            dec!(50000),
+        )];
+        let entries = project_income(&payments, &HashMap::new(), &HashMap::new(), as_of);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].amount, dec!(50000));
+        assert_eq!(entries[0].quantity, None);
+    }
+
+    #[test]
+    fn test_summary_lists_every_month() {
+        let as_of = date(2025, 6, 30);
+        let payments = vec![
+            payment("INTEREST", "$CASH-VND", date(2024, 9, 1), dec!(30000)),
+            payment("INTEREST", "$CASH-VND", date(2025, 3, 1), dec!(90000)),
+        ];
+        let entries = project_income(&payments, &HashMap::new(), &HashMap::new(), as_of);
+        let forecast = summarize_forecast(as_of, "VND".to_string(), entries);
+
+        assert_eq!(forecast.by_month.len(), 12);
+        assert_eq!(forecast.by_month.keys().next().unwrap(), "2025-07");
+        assert_eq!(forecast.by_month["2025-09"], dec!(30000));
+        assert_eq!(forecast.by_month["2026-03"], dec!(90000));
+        assert_eq!(forecast.by_month["2025-10"], Decimal::ZERO);
+        assert_eq!(forecast.total_income, dec!(120000));
+        assert_eq!(forecast.monthly_average, dec!(10000));
+        assert_eq!(forecast.by_type["INTEREST"], dec!(120000));
+    }
+}
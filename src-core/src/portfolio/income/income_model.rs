@@ -1,7 +1,8 @@
 use crate::activities::activities_model::IncomeData;
+use chrono::NaiveDate;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -59,3 +60,115 @@ impl IncomeSummary {
         }
     }
 }
+
+/// One expected income payment, repeating a payment from the trailing twelve months
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomeForecastEntry {
+    pub expected_date: NaiveDate,
+    pub account_id: String,
+    pub asset_id: String,
+    pub income_type: String,
+    /// Date of the payment this one repeats
+    pub source_date: NaiveDate,
+    /// Income per unit of the paying payment; `None` for cash interest and payments
+    /// recorded before any holdings were known
+    pub amount_per_unit: Option<Decimal>,
+    /// Units held now
+    pub quantity: Option<Decimal>,
+    /// Expected amount in the payment's currency
+    pub amount: Decimal,
+    pub currency: String,
+    /// Expected amount in the base currency at today's exchange rate
+    pub converted_amount: Decimal,
+}
+
+/// Expected dividend and interest income over the next twelve months
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomeForecast {
+    pub as_of_date: NaiveDate,
+    pub currency: String,
+    /// Expected income per `YYYY-MM`, with every month of the window present
+    pub by_month: BTreeMap<String, Decimal>,
+    pub by_type: HashMap<String, Decimal>,
+    pub by_symbol: HashMap<String, Decimal>,
+    pub total_income: Decimal,
+    pub monthly_average: Decimal,
+    pub entries: Vec<IncomeForecastEntry>,
+}
+
+/// Length of the periods an `IncomeReport` groups income into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IncomeGrouping {
+    #[default]
+    Month,
+    Quarter,
+    Year,
+}
+
+/// Selection of the income an `IncomeReport` covers
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomeReportFilter {
+    /// Accounts to include; every active account when empty
+    #[serde(default)]
+    pub account_ids: Vec<String>,
+    /// First payment date included; the first payment when empty
+    pub start_date: Option<NaiveDate>,
+    /// Last payment date included; today when empty
+    pub end_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub group_by: IncomeGrouping,
+    pub tag_id: Option<String>,
+}
+
+/// Income received in one period of an `IncomeReport`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomePeriod {
+    /// `YYYY-MM`, `YYYY-Qn` or `YYYY`
+    pub period: String,
+    pub start_date: NaiveDate,
+    pub by_type: HashMap<String, Decimal>,
+    pub by_symbol: HashMap<String, Decimal>,
+    pub total_income: Decimal,
+}
+
+/// Income of one symbol with its yields over the twelve months up to the report end
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomeSymbolYield {
+    pub symbol: String,
+    pub symbol_name: String,
+    /// Income within the report range
+    pub total_income: Decimal,
+    /// Income over the twelve months up to the report end
+    pub trailing_income: Decimal,
+    /// Cost basis and market value of the position held now
+    pub cost_basis: Decimal,
+    pub market_value: Decimal,
+    /// `trailing_income / cost_basis`; `None` without a position
+    pub yield_on_cost: Option<Decimal>,
+    /// `trailing_income / market_value`; `None` without a position
+    pub current_yield: Option<Decimal>,
+}
+
+/// Income over a date range, grouped by period, with every amount converted to the
+/// base currency at the exchange rate of its payment date
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomeReport {
+    pub currency: String,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: NaiveDate,
+    pub group_by: IncomeGrouping,
+    /// Every period from the first to the last of the range, oldest first
+    pub periods: Vec<IncomePeriod>,
+    pub by_type: HashMap<String, Decimal>,
+    /// Received amounts in their own currency
+    pub by_currency: HashMap<String, Decimal>,
+    pub total_income: Decimal,
+    pub symbols: Vec<IncomeSymbolYield>,
+}
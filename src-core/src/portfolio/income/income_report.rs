@@ -0,0 +1,269 @@
+//! Income report over a date range, grouped by month, quarter or year.
+
+use chrono::{Datelike, Months, NaiveDate};
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::activities::activities_model::IncomeData;
+
+use super::income_model::{
+    IncomeGrouping, IncomePeriod, IncomeReport, IncomeReportFilter, IncomeSymbolYield,
+};
+
+/// Months of income the yields are calculated from
+const TRAILING_MONTHS: u32 = 12;
+
+/// An income payment with its amount in the base currency
+#[derive(Debug)]
+pub struct ConvertedIncome {
+    pub payment_date: NaiveDate,
+    pub data: IncomeData,
+    pub converted_amount: Decimal,
+}
+
+/// Cost basis and market value of a symbol held now, in the base currency
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SymbolPosition {
+    pub cost_basis: Decimal,
+    pub market_value: Decimal,
+}
+
+/// First day of the period `date` falls in
+pub fn period_start(date: NaiveDate, grouping: IncomeGrouping) -> NaiveDate {
+    let month = match grouping {
+        IncomeGrouping::Month => date.month(),
+        IncomeGrouping::Quarter => (date.month() - 1) / 3 * 3 + 1,
+        IncomeGrouping::Year => 1,
+    };
+    NaiveDate::from_ymd_opt(date.year(), month, 1).unwrap_or(date)
+}
+
+fn period_label(start: NaiveDate, grouping: IncomeGrouping) -> String {
+    match grouping {
+        IncomeGrouping::Month => format!("{:04}-{:02}", start.year(), start.month()),
+        IncomeGrouping::Quarter => format!("{:04}-Q{}", start.year(), (start.month() - 1) / 3 + 1),
+        IncomeGrouping::Year => format!("{:04}", start.year()),
+    }
+}
+
+fn period_months(grouping: IncomeGrouping) -> Months {
+    match grouping {
+        IncomeGrouping::Month => Months::new(1),
+        IncomeGrouping::Quarter => Months::new(3),
+        IncomeGrouping::Year => Months::new(12),
+    }
+}
+
+fn ratio(income: Decimal, base: Decimal) -> Option<Decimal> {
+    (base > Decimal::ZERO).then(|| income / base)
+}
+
+/// Groups the payments between `filter.start_date` and `end` into periods and
+/// calculates each symbol's yields from its income over the twelve months up to
+/// `end`. `items` may include payments outside the range; they only count towards
+/// the yields.
+pub fn build_income_report(
+    items: &[ConvertedIncome],
+    currency: String,
+    filter: &IncomeReportFilter,
+    end: NaiveDate,
+    positions: &HashMap<String, SymbolPosition>,
+) -> IncomeReport {
+    let grouping = filter.group_by;
+    let in_range = |date: NaiveDate| date <= end && filter.start_date.is_none_or(|s| date >= s);
+    let trailing_start = end - Months::new(TRAILING_MONTHS);
+
+    let mut periods: BTreeMap<NaiveDate, IncomePeriod> = BTreeMap::new();
+    let first = filter.start_date.or_else(|| {
+        items
+            .iter()
+            .map(|i| i.payment_date)
+            .filter(|d| *d <= end)
+            .min()
+    });
+    if let Some(first) = first {
+        let mut start = period_start(first, grouping);
+        while start <= end {
+            periods.insert(
+                start,
+                IncomePeriod {
+                    period: period_label(start, grouping),
+                    start_date: start,
+                    by_type: HashMap::new(),
+                    by_symbol: HashMap::new(),
+                    total_income: Decimal::ZERO,
+                },
+            );
+            start = match start.checked_add_months(period_months(grouping)) {
+                Some(next) => next,
+                None => break,
+            };
+        }
+    }
+
+    let mut by_type: HashMap<String, Decimal> = HashMap::new();
+    let mut by_currency: HashMap<String, Decimal> = HashMap::new();
+    let mut total_income = Decimal::ZERO;
+    // symbol -> (name, income in range, trailing income)
+    let mut symbols: HashMap<String, (String, Decimal, Decimal)> = HashMap::new();
+
+    for item in items {
+        let amount = item.converted_amount;
+        let data = &item.data;
+        if item.payment_date > trailing_start && item.payment_date <= end {
+            symbols
+                .entry(data.symbol.clone())
+                .or_insert_with(|| (data.symbol_name.clone(), Decimal::ZERO, Decimal::ZERO))
+                .2 += amount;
+        }
+        if !in_range(item.payment_date) {
+            continue;
+        }
+        if let Some(period) = periods.get_mut(&period_start(item.payment_date, grouping)) {
+            *period.by_type.entry(data.income_type.clone()).or_default() += amount;
+            *period.by_symbol.entry(data.symbol.clone()).or_default() += amount;
+            period.total_income += amount;
+        }
+        *by_type.entry(data.income_type.clone()).or_default() += amount;
+        *by_currency.entry(data.currency.clone()).or_default() += data.amount;
+        total_income += amount;
+        symbols
+            .entry(data.symbol.clone())
+            .or_insert_with(|| (data.symbol_name.clone(), Decimal::ZERO, Decimal::ZERO))
+            .1 += amount;
+    }
+
+    let mut symbols: Vec<IncomeSymbolYield> = symbols
+        .into_iter()
+        .map(|(symbol, (symbol_name, income, trailing_income))| {
+            let position = positions.get(&symbol).copied().unwrap_or_default();
+            IncomeSymbolYield {
+                symbol,
+                symbol_name,
+                total_income: income,
+                trailing_income,
+                cost_basis: position.cost_basis,
+                market_value: position.market_value,
+                yield_on_cost: ratio(trailing_income, position.cost_basis),
+                current_yield: ratio(trailing_income, position.market_value),
+            }
+        })
+        .collect();
+    symbols.sort_by(|a, b| {
+        b.total_income
+            .cmp(&a.total_income)
+            .then_with(|| a.symbol.cmp(&b.symbol))
+    });
+
+    IncomeReport {
+        currency,
+        start_date: filter.start_date,
+        end_date: end,
+        group_by: grouping,
+        periods: periods.into_values().collect(),
+        by_type,
+        by_currency,
+        total_income,
+        symbols,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::date;
+    use rust_decimal_macros::dec;
+
+    fn income(
+        symbol: &str,
+        paid: NaiveDate,
+        amount: Decimal,
+        converted: Decimal,
+    ) -> ConvertedIncome {
+        ConvertedIncome {
+            payment_date: paid,
+            data: IncomeData {
+                date: paid.format("%Y-%m").to_string(),
+                payment_date: paid.to_string(),
+                account_id: "acc".to_string(),
+                income_type: "DIVIDEND".to_string(),
+                symbol: symbol.to_string(),
+                symbol_name: symbol.to_string(),
+                currency: "USD".to_string(),
+                amount,
+            },
+            converted_amount: converted,
+        }
+    }
+
+    #[test]
+    fn test_period_start() {
+        let d = date(2025, 8, 17);
+        assert_eq!(period_start(d, IncomeGrouping::Month), date(2025, 8, 1));
+        assert_eq!(period_start(d, IncomeGrouping::Quarter), date(2025, 7, 1));
+        assert_eq!(period_start(d, IncomeGrouping::Year), date(2025, 1, 1));
+        assert_eq!(
+            period_label(date(2025, 7, 1), IncomeGrouping::Quarter),
+            "2025-Q3"
+        );
+    }
+
+    #[test]
+    fn test_report_groups_range_into_quarters() {
+        let items = vec![
+            income("AAPL", date(2024, 11, 15), dec!(10), dec!(250000)),
+            income("AAPL", date(2025, 2, 15), dec!(10), dec!(260000)),
+            income("AAPL", date(2025, 5, 15), dec!(10), dec!(270000)),
+        ];
+        let filter = IncomeReportFilter {
+            start_date: Some(date(2025, 1, 1)),
+            group_by: IncomeGrouping::Quarter,
+            ..Default::default()
+        };
+        let report = build_income_report(
+            &items,
+            "VND".to_string(),
+            &filter,
+            date(2025, 9, 30),
+            &HashMap::new(),
+        );
+
+        let labels: Vec<&str> = report.periods.iter().map(|p| p.period.as_str()).collect();
+        assert_eq!(labels, vec!["2025-Q1", "2025-Q2", "2025-Q3"]);
+        assert_eq!(report.periods[0].total_income, dec!(260000));
+        assert_eq!(report.periods[2].total_income, Decimal::ZERO);
+        assert_eq!(report.total_income, dec!(530000));
+        assert_eq!(report.by_currency["USD"], dec!(20));
+        // Trailing twelve months include the November payment before the range
+        assert_eq!(report.symbols[0].trailing_income, dec!(780000));
+        assert_eq!(report.symbols[0].yield_on_cost, None);
+    }
+
+    #[test]
+    fn test_symbol_yields() {
+        let items = vec![
+            income("VNM", date(2025, 3, 1), dec!(400000), dec!(400000)),
+            income("VNM", date(2025, 9, 1), dec!(600000), dec!(600000)),
+        ];
+        let positions = HashMap::from([(
+            "VNM".to_string(),
+            SymbolPosition {
+                cost_basis: dec!(10000000),
+                market_value: dec!(20000000),
+            },
+        )]);
+        let report = build_income_report(
+            &items,
+            "VND".to_string(),
+            &IncomeReportFilter::default(),
+            date(2025, 12, 31),
+            &positions,
+        );
+
+        assert_eq!(report.periods.len(), 10);
+        let vnm = &report.symbols[0];
+        assert_eq!(vnm.total_income, dec!(1000000));
+        assert_eq!(vnm.yield_on_cost, Some(dec!(0.1)));
+        assert_eq!(vnm.current_yield, Some(dec!(0.05)));
+    }
+}
@@ -4,24 +4,43 @@ use crate::{
         activities_errors::ActivityError, activities_model::IncomeData,
         activities_traits::ActivityRepositoryTrait,
     },
+    errors::ValidationError,
     Error, Result,
 };
-use chrono::{Datelike, NaiveDate, Utc};
+use chrono::{Datelike, Months, NaiveDate, Utc};
 
-use super::IncomeSummary;
+use super::income_forecast::{
+    project_income, summarize_forecast, HoldingsHistory, Quantities, FORECAST_MONTHS,
+};
+use super::income_report::{build_income_report, ConvertedIncome, SymbolPosition};
+use super::{IncomeForecast, IncomeReport, IncomeReportFilter, IncomeSummary};
 use crate::fx::fx_traits::FxServiceTrait;
+use crate::market_data::MarketDataServiceTrait;
+use crate::portfolio::snapshot::{Position, SnapshotServiceTrait};
 use log::{debug, error};
 use num_traits::Zero;
 use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
 // Define the trait for the income service
 pub trait IncomeServiceTrait: Send + Sync {
-    fn get_income_summary(&self) -> Result<Vec<IncomeSummary>>;
+    /// Summarizes income, optionally restricted to activities or accounts carrying a tag
+    fn get_income_summary(&self, tag_id: Option<&str>) -> Result<Vec<IncomeSummary>>;
+
+    /// Projects dividend and interest income over the next twelve months from the
+    /// trailing year of payments and the quantities held now, in the base currency
+    fn get_income_forecast(&self) -> Result<IncomeForecast>;
+
+    /// Reports income over a date range for the selected accounts, grouped by month,
+    /// quarter or year, with yield on cost and current yield per symbol
+    fn get_income_report(&self, filter: IncomeReportFilter) -> Result<IncomeReport>;
 }
 
 pub struct IncomeService {
     fx_service: Arc<dyn FxServiceTrait>,
     activity_repository: Arc<dyn ActivityRepositoryTrait>,
+    snapshot_service: Arc<dyn SnapshotServiceTrait>,
+    market_data_service: Arc<dyn MarketDataServiceTrait>,
     base_currency: Arc<RwLock<String>>,
 }
 
@@ -29,11 +48,15 @@ impl IncomeService {
     pub fn new(
         fx_service: Arc<dyn FxServiceTrait>,
         activity_repository: Arc<dyn ActivityRepositoryTrait>,
+        snapshot_service: Arc<dyn SnapshotServiceTrait>,
+        market_data_service: Arc<dyn MarketDataServiceTrait>,
         base_currency: Arc<RwLock<String>>,
     ) -> Self {
         IncomeService {
             fx_service,
             activity_repository,
+            snapshot_service,
+            market_data_service,
             base_currency,
         }
     }
@@ -45,14 +68,148 @@ impl IncomeService {
             Decimal::zero()
         }
     }
+
+    /// Payment date of an income item and its amount converted at that date's rate.
+    /// Items with an unreadable date are skipped; a missing rate fails the conversion
+    /// rather than counting the amount in its own currency.
+    fn convert_income(
+        &self,
+        data: &IncomeData,
+        base_currency: &str,
+    ) -> Result<Option<(NaiveDate, Decimal)>> {
+        let date = match NaiveDate::parse_from_str(&data.payment_date, "%Y-%m-%d") {
+            Ok(d) => d,
+            Err(e) => {
+                error!("Error parsing date {}: {:?}", data.payment_date, e);
+                return Ok(None);
+            }
+        };
+        let converted_amount = self
+            .fx_service
+            .convert_currency_for_date(data.amount, &data.currency, base_currency, date)
+            .inspect_err(|e| {
+                error!(
+                    "Error converting {} income of {} on {}: {:?}",
+                    data.currency, data.symbol, date, e
+                )
+            })?;
+        Ok(Some((date, converted_amount)))
+    }
+
+    /// Cost basis and market value in the base currency of every symbol held now in
+    /// the given accounts
+    fn symbol_positions(
+        &self,
+        account_ids: &HashSet<String>,
+        base_currency: &str,
+    ) -> Result<HashMap<String, SymbolPosition>> {
+        let mut held: HashMap<String, (Decimal, Decimal, String)> = HashMap::new();
+        for account_id in account_ids {
+            let Some(snapshot) = self
+                .snapshot_service
+                .get_latest_holdings_snapshot(account_id)?
+            else {
+                continue;
+            };
+            for (asset_id, position) in snapshot.positions {
+                let cost_basis = self.fx_service.convert_currency(
+                    position.total_cost_basis,
+                    &position.currency,
+                    base_currency,
+                )?;
+                let entry = held.entry(asset_id).or_insert((
+                    Decimal::ZERO,
+                    Decimal::ZERO,
+                    position.currency.clone(),
+                ));
+                entry.0 += position.quantity;
+                entry.1 += cost_basis;
+            }
+        }
+
+        let symbols: Vec<String> = held.keys().cloned().collect();
+        let quotes = self
+            .market_data_service
+            .get_latest_quotes_for_symbols(&symbols)?;
+        held.into_iter()
+            .map(|(asset_id, (quantity, cost_basis, currency))| {
+                let market_value = match quotes.get(&asset_id) {
+                    Some(quote) => {
+                        let local = quote.close * quantity;
+                        let quote_currency = if quote.currency.is_empty() {
+                            currency.as_str()
+                        } else {
+                            quote.currency.as_str()
+                        };
+                        self.fx_service
+                            .convert_currency(local, quote_currency, base_currency)?
+                    }
+                    None => Decimal::ZERO,
+                };
+                Ok((
+                    asset_id,
+                    SymbolPosition {
+                        cost_basis,
+                        market_value,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    /// Quantities held per account, now and at each holdings keyframe since `start`
+    fn holdings_by_account(
+        &self,
+        account_ids: &HashSet<String>,
+        start: NaiveDate,
+    ) -> Result<(
+        HashMap<String, HoldingsHistory>,
+        HashMap<String, Quantities>,
+    )> {
+        let quantities = |positions: &HashMap<String, Position>| {
+            positions
+                .iter()
+                .map(|(asset_id, position)| (asset_id.clone(), position.quantity))
+                .collect::<Quantities>()
+        };
+
+        let mut history = HashMap::new();
+        let mut current = HashMap::new();
+        for account_id in account_ids {
+            // The keyframe before `start` gives the quantities held on its first days
+            let keyframes = self
+                .snapshot_service
+                .get_holdings_keyframes(account_id, None, None)?;
+            let account_history: HoldingsHistory = keyframes
+                .iter()
+                .filter(|s| s.snapshot_date >= start)
+                .chain(
+                    keyframes
+                        .iter()
+                        .filter(|s| s.snapshot_date < start)
+                        .max_by_key(|s| s.snapshot_date),
+                )
+                .map(|s| (s.snapshot_date, quantities(&s.positions)))
+                .collect();
+            history.insert(account_id.clone(), account_history);
+
+            if let Some(latest) = self
+                .snapshot_service
+                .get_latest_holdings_snapshot(account_id)?
+            {
+                current.insert(account_id.clone(), quantities(&latest.positions));
+            }
+        }
+        Ok((history, current))
+    }
 }
 
 // Implement the trait for IncomeService
 impl IncomeServiceTrait for IncomeService {
-    fn get_income_summary(&self) -> Result<Vec<IncomeSummary>> {
+    fn get_income_summary(&self, tag_id: Option<&str>) -> Result<Vec<IncomeSummary>> {
         debug!("Getting income summary...");
 
-        let activities = match self.activity_repository.get_income_activities_data() {
+        let activities = match self.activity_repository.get_income_activities_data(tag_id) {
             Ok(activity) => activity,
             Err(e) => {
                 error!("Error getting aggregated income data: {:?}", e);
@@ -100,32 +257,16 @@ impl IncomeServiceTrait for IncomeService {
         let mut two_years_ago_summary = IncomeSummary::new("TWO_YEARS_AGO", base_currency.clone());
 
         for activity in activities {
-            let date = match NaiveDate::parse_from_str(&format!("{}-01", activity.date), "%Y-%m-%d")
-            {
-                Ok(d) => d,
-                Err(e) => {
-                    error!("Error parsing date {}: {:?}", activity.date, e);
-                    continue;
-                }
-            };
-
-            // Correctly call methods on the FxService instance within the Arc
-            let converted_amount = match self.fx_service.convert_currency(
-                activity.amount.clone(),
-                &activity.currency,
-                &base_currency,
-            ) {
-                Ok(amount) => amount,
-                Err(e) => {
-                    error!("Error converting currency: {:?}", e);
-                    // Consider if returning the original amount is the correct fallback
-                    activity.amount.clone()
-                }
+            let Some((date, converted_amount)) = self.convert_income(&activity, &base_currency)?
+            else {
+                continue;
             };
 
             // Create a copy of the activity with cloned fields to avoid ownership issues
             let activity_copy = IncomeData {
                 date: activity.date.clone(),
+                payment_date: activity.payment_date.clone(),
+                account_id: activity.account_id.clone(),
                 income_type: activity.income_type.clone(),
                 symbol: activity.symbol.clone(),
                 symbol_name: activity.symbol_name.clone(),
@@ -202,4 +343,149 @@ impl IncomeServiceTrait for IncomeService {
         debug!("Income summary calculation and rounding completed successfully");
         Ok(rounded_summaries)
     }
+
+    fn get_income_forecast(&self) -> Result<IncomeForecast> {
+        debug!("Getting income forecast...");
+
+        let base_currency = self.base_currency.read().unwrap().clone();
+        let as_of = Utc::now().naive_utc().date();
+        let window_start = as_of - Months::new(FORECAST_MONTHS);
+
+        let payments: Vec<_> = self
+            .activity_repository
+            .get_income_activities()?
+            .into_iter()
+            .filter(|a| a.activity_date.date_naive() > window_start)
+            .collect();
+        let account_ids: HashSet<String> = payments.iter().map(|a| a.account_id.clone()).collect();
+        let (holdings, current) = self.holdings_by_account(&account_ids, window_start)?;
+
+        let entries = project_income(&payments, &holdings, &current, as_of)
+            .into_iter()
+            .map(|mut entry| {
+                entry.converted_amount = match self.fx_service.convert_currency(
+                    entry.amount,
+                    &entry.currency,
+                    &base_currency,
+                ) {
+                    Ok(amount) => amount,
+                    Err(e) => {
+                        error!("Error converting currency: {:?}", e);
+                        entry.amount
+                    }
+                };
+                entry
+            })
+            .collect();
+
+        let mut forecast = summarize_forecast(as_of, base_currency, entries);
+        forecast.total_income = forecast.total_income.round_dp(DISPLAY_DECIMAL_PRECISION);
+        forecast.monthly_average = forecast.monthly_average.round_dp(DISPLAY_DECIMAL_PRECISION);
+        for val in forecast
+            .by_month
+            .values_mut()
+            .chain(forecast.by_type.values_mut())
+            .chain(forecast.by_symbol.values_mut())
+        {
+            *val = val.round_dp(DISPLAY_DECIMAL_PRECISION);
+        }
+        for entry in forecast.entries.iter_mut() {
+            entry.amount = entry.amount.round_dp(DISPLAY_DECIMAL_PRECISION);
+            entry.converted_amount = entry.converted_amount.round_dp(DISPLAY_DECIMAL_PRECISION);
+        }
+
+        debug!(
+            "Income forecast of {} payment(s) completed successfully",
+            forecast.entries.len()
+        );
+        Ok(forecast)
+    }
+
+    fn get_income_report(&self, filter: IncomeReportFilter) -> Result<IncomeReport> {
+        debug!("Getting income report for {:?}...", filter);
+
+        let end = filter
+            .end_date
+            .unwrap_or_else(|| Utc::now().naive_utc().date());
+        if let Some(start) = filter.start_date {
+            if start > end {
+                return Err(Error::Validation(ValidationError::InvalidInput(format!(
+                    "Start date {} is after end date {}",
+                    start, end
+                ))));
+            }
+        }
+
+        let activities = self
+            .activity_repository
+            .get_income_activities_data(filter.tag_id.as_deref())
+            .map_err(|e| {
+                error!("Error getting income data: {:?}", e);
+                Error::Activity(ActivityError::InvalidData(e.to_string()))
+            })?;
+
+        let base_currency = self.base_currency.read().unwrap().clone();
+        let selected: HashSet<&str> = filter.account_ids.iter().map(String::as_str).collect();
+        let mut items: Vec<ConvertedIncome> = Vec::new();
+        for data in activities
+            .into_iter()
+            .filter(|a| selected.is_empty() || selected.contains(a.account_id.as_str()))
+        {
+            if let Some((payment_date, converted_amount)) =
+                self.convert_income(&data, &base_currency)?
+            {
+                items.push(ConvertedIncome {
+                    payment_date,
+                    data,
+                    converted_amount,
+                });
+            }
+        }
+
+        // Positions of the selected accounts, or of the accounts that received income
+        let account_ids: HashSet<String> = if filter.account_ids.is_empty() {
+            items.iter().map(|i| i.data.account_id.clone()).collect()
+        } else {
+            filter.account_ids.iter().cloned().collect()
+        };
+        let positions = self.symbol_positions(&account_ids, &base_currency)?;
+
+        let mut report = build_income_report(&items, base_currency, &filter, end, &positions);
+        report.total_income = report.total_income.round_dp(DISPLAY_DECIMAL_PRECISION);
+        for period in report.periods.iter_mut() {
+            period.total_income = period.total_income.round_dp(DISPLAY_DECIMAL_PRECISION);
+            for val in period
+                .by_type
+                .values_mut()
+                .chain(period.by_symbol.values_mut())
+            {
+                *val = val.round_dp(DISPLAY_DECIMAL_PRECISION);
+            }
+        }
+        for val in report
+            .by_type
+            .values_mut()
+            .chain(report.by_currency.values_mut())
+        {
+            *val = val.round_dp(DISPLAY_DECIMAL_PRECISION);
+        }
+        for symbol in report.symbols.iter_mut() {
+            symbol.total_income = symbol.total_income.round_dp(DISPLAY_DECIMAL_PRECISION);
+            symbol.trailing_income = symbol.trailing_income.round_dp(DISPLAY_DECIMAL_PRECISION);
+            symbol.cost_basis = symbol.cost_basis.round_dp(DISPLAY_DECIMAL_PRECISION);
+            symbol.market_value = symbol.market_value.round_dp(DISPLAY_DECIMAL_PRECISION);
+            symbol.yield_on_cost = symbol
+                .yield_on_cost
+                .map(|y| y.round_dp(DISPLAY_DECIMAL_PRECISION));
+            symbol.current_yield = symbol
+                .current_yield
+                .map(|y| y.round_dp(DISPLAY_DECIMAL_PRECISION));
+        }
+
+        debug!(
+            "Income report over {} period(s) completed successfully",
+            report.periods.len()
+        );
+        Ok(report)
+    }
 }
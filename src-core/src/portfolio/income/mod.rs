@@ -1,4 +1,6 @@
+pub mod income_forecast;
 pub mod income_model;
+pub mod income_report;
 pub mod income_service;
 
 pub use income_model::*;
@@ -1,3 +1,5 @@
+pub mod attribution;
+pub mod checkpoint;
 pub mod holdings;
 pub mod income;
 pub mod performance;
@@ -0,0 +1,146 @@
+use rust_decimal::Decimal;
+use rust_decimal::MathematicalOps;
+
+use super::performance_service::TRADING_DAYS_PER_YEAR;
+
+/// Risk of a portfolio measured against its benchmark from paired daily returns
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RelativeRisk {
+    /// Annualized standard deviation of the daily return differences
+    pub tracking_error: Decimal,
+    pub beta: Option<Decimal>,
+    pub correlation: Option<Decimal>,
+}
+
+/// Computes tracking error, beta and correlation from daily returns observed on the
+/// same dates. Extra entries in the longer slice are ignored. Beta and correlation are
+/// `None` when the benchmark (or, for correlation, either series) never moves.
+pub fn calculate_relative_risk(
+    portfolio_returns: &[Decimal],
+    benchmark_returns: &[Decimal],
+) -> RelativeRisk {
+    let count = portfolio_returns.len().min(benchmark_returns.len());
+    if count < 2 {
+        return RelativeRisk {
+            tracking_error: Decimal::ZERO,
+            beta: None,
+            correlation: None,
+        };
+    }
+    let portfolio = &portfolio_returns[..count];
+    let benchmark = &benchmark_returns[..count];
+
+    let n = Decimal::from(count);
+    let mean_portfolio = portfolio.iter().sum::<Decimal>() / n;
+    let mean_benchmark = benchmark.iter().sum::<Decimal>() / n;
+    let mean_excess = mean_portfolio - mean_benchmark;
+
+    let mut covariance = Decimal::ZERO;
+    let mut variance_portfolio = Decimal::ZERO;
+    let mut variance_benchmark = Decimal::ZERO;
+    let mut variance_excess = Decimal::ZERO;
+    for (&p, &b) in portfolio.iter().zip(benchmark) {
+        let dp = p - mean_portfolio;
+        let db = b - mean_benchmark;
+        let de = (p - b) - mean_excess;
+        covariance += dp * db;
+        variance_portfolio += dp * dp;
+        variance_benchmark += db * db;
+        variance_excess += de * de;
+    }
+    // Sample statistics; the (n - 1) factors cancel in beta and correlation
+    let degrees_of_freedom = n - Decimal::ONE;
+    covariance /= degrees_of_freedom;
+    variance_portfolio /= degrees_of_freedom;
+    variance_benchmark /= degrees_of_freedom;
+    variance_excess /= degrees_of_freedom;
+
+    let annualization_factor = Decimal::from(TRADING_DAYS_PER_YEAR)
+        .sqrt()
+        .unwrap_or(Decimal::ZERO);
+    let tracking_error = variance_excess.sqrt().unwrap_or(Decimal::ZERO) * annualization_factor;
+
+    let beta = (!variance_benchmark.is_zero()).then(|| covariance / variance_benchmark);
+    let correlation = if variance_portfolio.is_zero() || variance_benchmark.is_zero() {
+        None
+    } else {
+        (variance_portfolio * variance_benchmark)
+            .sqrt()
+            .filter(|denominator| !denominator.is_zero())
+            .map(|denominator| (covariance / denominator).clamp(-Decimal::ONE, Decimal::ONE))
+    };
+
+    RelativeRisk {
+        tracking_error,
+        beta,
+        correlation,
+    }
+}
+
+/// Replays external cash flows into a benchmark: each `(cash_flow, price)` entry buys
+/// (positive flow) or sells (negative flow) units at that day's price, and the value of
+/// the units held afterwards is returned for every entry. The first entry's flow is the
+/// opening investment. Withdrawals larger than the holding empty it instead of going short.
+pub fn simulate_benchmark_investment(flows_and_prices: &[(Decimal, Decimal)]) -> Vec<Decimal> {
+    let mut units = Decimal::ZERO;
+    flows_and_prices
+        .iter()
+        .map(|&(cash_flow, price)| {
+            if price.is_zero() {
+                return Decimal::ZERO;
+            }
+            units = (units + cash_flow / price).max(Decimal::ZERO);
+            units * price
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_relative_risk_of_leveraged_benchmark() {
+        let benchmark = [dec!(0.01), dec!(-0.02), dec!(0.015), dec!(0.005)];
+        let portfolio: Vec<Decimal> = benchmark.iter().map(|r| r * dec!(2)).collect();
+
+        let risk = calculate_relative_risk(&portfolio, &benchmark);
+        assert_eq!(risk.beta.unwrap().round_dp(8), dec!(2));
+        assert_eq!(risk.correlation.unwrap().round_dp(8), dec!(1));
+        // Excess returns equal the benchmark returns, so tracking error is its volatility
+        let benchmark_only = calculate_relative_risk(&benchmark, &[Decimal::ZERO; 4]);
+        assert_eq!(
+            risk.tracking_error.round_dp(8),
+            benchmark_only.tracking_error.round_dp(8)
+        );
+        assert!(benchmark_only.beta.is_none());
+        assert!(benchmark_only.correlation.is_none());
+    }
+
+    #[test]
+    fn test_relative_risk_needs_two_observations() {
+        let risk = calculate_relative_risk(&[dec!(0.01)], &[dec!(0.02)]);
+        assert_eq!(risk.tracking_error, Decimal::ZERO);
+        assert!(risk.beta.is_none());
+    }
+
+    #[test]
+    fn test_benchmark_investment_follows_cash_flows() {
+        // 1,000 at 10, price doubles, 500 added at 20, 2,000 withdrawn at 25
+        let values = simulate_benchmark_investment(&[
+            (dec!(1000), dec!(10)),
+            (dec!(0), dec!(20)),
+            (dec!(500), dec!(20)),
+            (dec!(-2000), dec!(25)),
+        ]);
+        assert_eq!(values, vec![dec!(1000), dec!(2000), dec!(2500), dec!(1125)]);
+    }
+
+    #[test]
+    fn test_benchmark_investment_does_not_go_short() {
+        let values =
+            simulate_benchmark_investment(&[(dec!(100), dec!(10)), (dec!(-500), dec!(10))]);
+        assert_eq!(values, vec![dec!(100), dec!(0)]);
+    }
+}
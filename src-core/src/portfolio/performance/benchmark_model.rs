@@ -0,0 +1,50 @@
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use super::ReturnData;
+
+/// Database model for the benchmark configured on an account
+#[derive(Queryable, Insertable, AsChangeset, Selectable, Debug, Clone)]
+#[diesel(table_name = crate::schema::account_benchmarks)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct AccountBenchmarkDB {
+    pub account_id: String,
+    pub symbol: String,
+    pub updated_at: String,
+}
+
+/// Value of the portfolio and of the same cash flows invested in the benchmark on a date
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkValuePoint {
+    pub date: NaiveDate,
+    pub portfolio_value: Decimal,
+    pub benchmark_value: Decimal,
+}
+
+/// Portfolio performance compared against a benchmark over a date range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkComparison {
+    pub id: String,
+    pub benchmark_symbol: String,
+    pub currency: String,
+    pub period_start_date: Option<NaiveDate>,
+    pub period_end_date: Option<NaiveDate>,
+    /// Cumulative time-weighted return of the portfolio
+    pub portfolio_returns: Vec<ReturnData>,
+    /// Cumulative price return of the benchmark
+    pub benchmark_returns: Vec<ReturnData>,
+    /// "What if I had bought the benchmark instead": the portfolio's opening value and
+    /// every later contribution or withdrawal traded in the benchmark on the same day
+    pub what_if_values: Vec<BenchmarkValuePoint>,
+    pub portfolio_cumulative_return: Decimal,
+    pub benchmark_cumulative_return: Decimal,
+    pub excess_return: Decimal,
+    /// Annualized standard deviation of the daily excess returns
+    pub tracking_error: Decimal,
+    pub beta: Option<Decimal>,
+    pub correlation: Option<Decimal>,
+}
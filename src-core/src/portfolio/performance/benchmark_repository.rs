@@ -0,0 +1,70 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::sqlite::SqliteConnection;
+use std::sync::Arc;
+
+use super::benchmark_model::AccountBenchmarkDB;
+use crate::db::{get_connection, WriteHandle};
+use crate::errors::Result;
+use crate::schema::account_benchmarks;
+
+#[async_trait]
+pub trait BenchmarkRepositoryTrait: Send + Sync {
+    fn get_benchmark(&self, account_id: &str) -> Result<Option<AccountBenchmarkDB>>;
+    async fn upsert_benchmark(&self, account_id: &str, symbol: &str) -> Result<AccountBenchmarkDB>;
+    async fn delete_benchmark(&self, account_id: &str) -> Result<usize>;
+}
+
+pub struct BenchmarkRepository {
+    pool: Arc<Pool<ConnectionManager<SqliteConnection>>>,
+    writer: WriteHandle,
+}
+
+impl BenchmarkRepository {
+    pub fn new(pool: Arc<Pool<ConnectionManager<SqliteConnection>>>, writer: WriteHandle) -> Self {
+        BenchmarkRepository { pool, writer }
+    }
+}
+
+#[async_trait]
+impl BenchmarkRepositoryTrait for BenchmarkRepository {
+    fn get_benchmark(&self, account_id: &str) -> Result<Option<AccountBenchmarkDB>> {
+        let mut conn = get_connection(&self.pool)?;
+        Ok(account_benchmarks::table
+            .find(account_id)
+            .first::<AccountBenchmarkDB>(&mut conn)
+            .optional()?)
+    }
+
+    async fn upsert_benchmark(&self, account_id: &str, symbol: &str) -> Result<AccountBenchmarkDB> {
+        let benchmark = AccountBenchmarkDB {
+            account_id: account_id.to_string(),
+            symbol: symbol.to_string(),
+            updated_at: Utc::now().to_rfc3339(),
+        };
+        self.writer
+            .exec(
+                move |conn: &mut SqliteConnection| -> Result<AccountBenchmarkDB> {
+                    diesel::insert_into(account_benchmarks::table)
+                        .values(&benchmark)
+                        .on_conflict(account_benchmarks::account_id)
+                        .do_update()
+                        .set(&benchmark)
+                        .execute(conn)?;
+                    Ok(benchmark)
+                },
+            )
+            .await
+    }
+
+    async fn delete_benchmark(&self, account_id: &str) -> Result<usize> {
+        let account_id = account_id.to_string();
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<usize> {
+                Ok(diesel::delete(account_benchmarks::table.find(account_id)).execute(conn)?)
+            })
+            .await
+    }
+}
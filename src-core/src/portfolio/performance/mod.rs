@@ -1,5 +1,23 @@
+pub mod benchmark;
+pub mod benchmark_model;
+pub mod benchmark_repository;
 pub mod performance_model;
 pub mod performance_service;
+#[cfg(test)]
+mod performance_service_tests;
+pub mod periodic_returns;
+pub mod risk_free_rate_model;
+pub mod risk_free_rate_repository;
+pub mod risk_metrics;
+pub mod xirr;
 
+pub use benchmark::*;
+pub use benchmark_model::*;
+pub use benchmark_repository::*;
 pub use performance_model::*;
 pub use performance_service::*;
+pub use periodic_returns::*;
+pub use risk_free_rate_model::*;
+pub use risk_free_rate_repository::*;
+pub use risk_metrics::*;
+pub use xirr::*;
@@ -52,8 +52,79 @@ pub struct PerformanceMetrics {
     pub annualized_simple_return: Decimal,
     pub cumulative_mwr: Decimal,
     pub annualized_mwr: Decimal,
+    /// Annualized money-weighted return (XIRR) of the period's dated cash flows.
+    /// `None` when it cannot be solved or does not apply.
+    pub xirr: Option<Decimal>,
     pub volatility: Decimal,
     pub max_drawdown: Decimal,
+    /// Risk-adjusted ratios and drawdown dates; only filled for full history calculations.
+    pub risk_metrics: Option<RiskMetrics>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReturnPeriodType {
+    Month,
+    Quarter,
+    Year,
+}
+
+/// Chain-linked time-weighted return of one calendar month, quarter or year
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodicReturn {
+    pub period_type: ReturnPeriodType,
+    pub year: i32,
+    /// Month (1-12) or quarter (1-4) within the year; 1 for yearly periods
+    pub period: u32,
+    /// Observation the return is measured from (the previous period's last one)
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub value: Decimal,
+    /// The history starts or ends inside the calendar period
+    pub is_partial: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodicReturns {
+    pub id: String,
+    pub currency: String,
+    pub period_start_date: Option<NaiveDate>,
+    pub period_end_date: Option<NaiveDate>,
+    pub monthly: Vec<PeriodicReturn>,
+    pub quarterly: Vec<PeriodicReturn>,
+    pub yearly: Vec<PeriodicReturn>,
+}
+
+/// Return over a period running from `start_date` to `end_date`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodReturn {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub value: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskMetrics {
+    /// Risk-free series the excess returns were measured against
+    pub risk_free_series: String,
+    /// Average annual risk-free rate over the period; `None` when the series has no data
+    pub risk_free_rate: Option<Decimal>,
+    pub sharpe_ratio: Option<Decimal>,
+    pub sortino_ratio: Option<Decimal>,
+    pub calmar_ratio: Option<Decimal>,
+    pub downside_deviation: Decimal,
+    pub best_day: Option<PeriodReturn>,
+    pub worst_day: Option<PeriodReturn>,
+    pub best_month: Option<PeriodReturn>,
+    pub worst_month: Option<PeriodReturn>,
+    pub max_drawdown_peak_date: Option<NaiveDate>,
+    pub max_drawdown_trough_date: Option<NaiveDate>,
+    /// `None` while the portfolio is still below the peak
+    pub max_drawdown_recovery_date: Option<NaiveDate>,
 }
 
 // This struct now only holds the calculated performance metrics.
@@ -1,12 +1,26 @@
-use crate::constants::{DECIMAL_PRECISION, PORTFOLIO_TOTAL_ACCOUNT_ID};
+use crate::activities::activities_constants::{
+    ACTIVITY_TYPE_ADD_HOLDING, ACTIVITY_TYPE_BUY, ACTIVITY_TYPE_DIVIDEND, ACTIVITY_TYPE_FEE,
+    ACTIVITY_TYPE_INTEREST, ACTIVITY_TYPE_REMOVE_HOLDING, ACTIVITY_TYPE_SELL, ACTIVITY_TYPE_TAX,
+    ACTIVITY_TYPE_TRANSFER_IN, ACTIVITY_TYPE_TRANSFER_OUT,
+};
+use crate::activities::{Activity, ActivityRepositoryTrait};
+use crate::composites::CompositeRepositoryTrait;
+use crate::constants::{
+    CASH_ASSET_PREFIX, DECIMAL_PRECISION, DEFAULT_BENCHMARK_SYMBOL, DEFAULT_RISK_FREE_RATE_SERIES,
+    PORTFOLIO_TOTAL_ACCOUNT_ID,
+};
 use crate::errors::{self, Result, ValidationError};
+use crate::fx::FxServiceTrait;
 use crate::market_data::MarketDataServiceTrait;
 use crate::performance::ReturnData;
+use crate::settings::SettingsServiceTrait;
+use crate::tags::TagRepositoryTrait;
 use crate::valuation::ValuationServiceTrait;
+use crate::vn_market::VnMarketService;
 
 use async_trait::async_trait;
-use chrono::{Duration, NaiveDate};
-use std::collections::HashMap;
+use chrono::{Duration, NaiveDate, Utc};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
 
 use log::{debug, warn};
@@ -14,7 +28,15 @@ use rust_decimal::Decimal;
 use rust_decimal::MathematicalOps;
 use rust_decimal_macros::dec;
 
-use super::{PerformanceMetrics, SimplePerformanceMetrics};
+use super::{
+    best_and_worst, calculate_calmar_ratio, calculate_drawdown_details, calculate_monthly_returns,
+    calculate_periodic_returns, calculate_relative_risk, calculate_risk_adjusted_ratios,
+    calculate_xirr, find_best_and_worst_days, period_risk_free_return,
+    simulate_benchmark_investment, BenchmarkComparison, BenchmarkRepositoryTrait,
+    BenchmarkValuePoint, DatedCashFlow, DrawdownDetails, NewRiskFreeRate, PerformanceMetrics,
+    PeriodReturn, PeriodicReturns, ReturnPeriodType, RiskFreeRate, RiskFreeRateRepositoryTrait,
+    RiskMetrics, SimplePerformanceMetrics,
+};
 use crate::portfolio::valuation::DailyAccountValuation;
 
 #[async_trait]
@@ -35,6 +57,26 @@ pub trait PerformanceServiceTrait: Send + Sync {
         end_date: Option<NaiveDate>,
     ) -> Result<PerformanceMetrics>;
 
+    /// Chain-linked returns per calendar month, quarter and year for an account, tag,
+    /// composite or symbol, derived from the same daily series as `calculate_performance_history`.
+    async fn calculate_periodic_returns(
+        &self,
+        item_type: &str,
+        item_id: &str,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<PeriodicReturns>;
+
+    /// Daily valuation series of an account, or the base-currency aggregate of the
+    /// accounts covered by a tag or composite portfolio.
+    fn get_aggregated_valuation_history(
+        &self,
+        item_type: &str,
+        item_id: &str,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<DailyAccountValuation>>;
+
     /// Calculates simple performance metrics (daily returns, cumulative returns, portfolio weights) for multiple accounts.
     /// This method efficiently fetches the latest and previous day's valuations in bulk to minimize database queries.
     /// Can be used for a single account by passing a slice with one ID.
@@ -42,30 +84,498 @@ pub trait PerformanceServiceTrait: Send + Sync {
         &self,
         account_ids: &[String],
     ) -> Result<Vec<SimplePerformanceMetrics>>;
+
+    /// Compares an account, tag or composite against a benchmark over the date range. When no
+    /// symbol is given, the account's configured benchmark is used.
+    async fn calculate_benchmark_comparison(
+        &self,
+        item_type: &str,
+        item_id: &str,
+        benchmark_symbol: Option<&str>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<BenchmarkComparison>;
+
+    /// Returns the benchmark configured for an account, or the default one.
+    fn get_benchmark_symbol(&self, account_id: &str) -> Result<String>;
+
+    /// Sets the benchmark of an account; `None` restores the default. Returns the
+    /// benchmark now in effect.
+    async fn set_benchmark_symbol(
+        &self,
+        account_id: &str,
+        symbol: Option<String>,
+    ) -> Result<String>;
+
+    /// Returns the rates of a risk-free series, or of the series selected in settings.
+    fn get_risk_free_rates(&self, series: Option<&str>) -> Result<Vec<RiskFreeRate>>;
+
+    /// Adds a risk-free rate, replacing any rate of the same series on that date.
+    async fn save_risk_free_rate(&self, rate: NewRiskFreeRate) -> Result<RiskFreeRate>;
+
+    async fn delete_risk_free_rate(&self, rate_id: &str) -> Result<()>;
 }
 
 pub struct PerformanceService {
     valuation_service: Arc<dyn ValuationServiceTrait + Send + Sync>,
     market_data_service: Arc<dyn MarketDataServiceTrait + Send + Sync>,
+    tag_repository: Arc<dyn TagRepositoryTrait>,
+    benchmark_repository: Arc<dyn BenchmarkRepositoryTrait>,
+    vn_market_service: Arc<VnMarketService>,
+    risk_free_rate_repository: Arc<dyn RiskFreeRateRepositoryTrait>,
+    settings_service: Arc<dyn SettingsServiceTrait>,
+    composite_repository: Arc<dyn CompositeRepositoryTrait>,
+    activity_repository: Arc<dyn ActivityRepositoryTrait>,
+    fx_service: Arc<dyn FxServiceTrait>,
+}
+
+/// Quantity and base-currency cost of an asset bought by tagged activities
+#[derive(Default)]
+struct TaggedPosition {
+    quantity: Decimal,
+    cost_basis: Decimal,
+    currency: String,
+    last_price: Decimal,
 }
 
-const TRADING_DAYS_PER_YEAR: u32 = 252;
+/// Position-level valuation of activities that carry a tag themselves, in `base_currency`,
+/// on each of `dates` from the first tagged activity on.
+///
+/// Trades and holding adjustments move the quantity of their asset. Positions are valued at
+/// the last close on or before the day, or at their last trade price while the asset has no
+/// quote. Buying puts money into the tagged positions and selling takes it back out, so their
+/// return is what the positions earned. Income on a tagged activity is paid out of them and
+/// fees and taxes are paid into them; cash movements are ignored. Selling more than the tagged
+/// positions hold only counts the part they held.
+pub(crate) fn tagged_activity_valuations(
+    item_id: &str,
+    activities: &[Activity],
+    closes: &HashMap<String, BTreeMap<NaiveDate, Decimal>>,
+    dates: &[NaiveDate],
+    base_currency: &str,
+    mut fx_rate: impl FnMut(&str, NaiveDate) -> Result<Decimal>,
+) -> Result<Vec<DailyAccountValuation>> {
+    let mut activities: Vec<&Activity> = activities.iter().collect();
+    activities.sort_by_key(|activity| activity.activity_date);
+
+    let mut positions: BTreeMap<String, TaggedPosition> = BTreeMap::new();
+    let mut net_contribution = Decimal::ZERO;
+    let mut next_index = 0;
+    let calculated_at = Utc::now();
+    let mut valuations = Vec::with_capacity(dates.len());
+    for &date in dates {
+        while let Some(activity) = activities
+            .get(next_index)
+            .filter(|activity| activity.activity_date.naive_utc().date() <= date)
+        {
+            next_index += 1;
+            let rate = fx_rate(
+                &activity.currency,
+                activity.activity_date.naive_utc().date(),
+            )?;
+            let is_cash = activity.asset_id.starts_with(CASH_ASSET_PREFIX);
+            let quantity = activity.quantity.abs();
+            match activity.activity_type.as_str() {
+                ACTIVITY_TYPE_BUY | ACTIVITY_TYPE_ADD_HOLDING | ACTIVITY_TYPE_TRANSFER_IN
+                    if !is_cash =>
+                {
+                    let cost = (quantity * activity.unit_price + activity.fee) * rate;
+                    let position =
+                        positions
+                            .entry(activity.asset_id.clone())
+                            .or_insert_with(|| TaggedPosition {
+                                currency: activity.currency.clone(),
+                                ..Default::default()
+                            });
+                    position.quantity += quantity;
+                    position.cost_basis += cost;
+                    position.last_price = activity.unit_price;
+                    net_contribution += cost;
+                }
+                ACTIVITY_TYPE_SELL | ACTIVITY_TYPE_REMOVE_HOLDING | ACTIVITY_TYPE_TRANSFER_OUT
+                    if !is_cash =>
+                {
+                    let Some(position) = positions
+                        .get_mut(&activity.asset_id)
+                        .filter(|position| !position.quantity.is_zero() && !quantity.is_zero())
+                    else {
+                        continue;
+                    };
+                    let sold = quantity.min(position.quantity);
+                    position.cost_basis -= position.cost_basis * sold / position.quantity;
+                    position.quantity -= sold;
+                    position.last_price = activity.unit_price;
+                    let proceeds = quantity * activity.unit_price - activity.fee;
+                    net_contribution -= proceeds * sold / quantity * rate;
+                }
+                ACTIVITY_TYPE_DIVIDEND | ACTIVITY_TYPE_INTEREST => {
+                    net_contribution -=
+                        (activity.amount.unwrap_or(Decimal::ZERO) - activity.fee) * rate;
+                }
+                ACTIVITY_TYPE_FEE | ACTIVITY_TYPE_TAX => {
+                    let charge = if activity.fee.is_zero() {
+                        activity.amount.unwrap_or(Decimal::ZERO)
+                    } else {
+                        activity.fee
+                    };
+                    net_contribution += charge * rate;
+                }
+                _ => {}
+            }
+        }
+        // Nothing is tagged yet on this date
+        if next_index == 0 {
+            continue;
+        }
+
+        let mut market_value = Decimal::ZERO;
+        for (asset_id, position) in positions.iter().filter(|(_, p)| !p.quantity.is_zero()) {
+            let price = closes
+                .get(asset_id)
+                .and_then(|closes| closes.range(..=date).next_back())
+                .map_or(position.last_price, |(_, close)| *close);
+            market_value += position.quantity * price * fx_rate(&position.currency, date)?;
+        }
+        valuations.push(DailyAccountValuation {
+            id: format!("{}_{}", item_id, date),
+            account_id: item_id.to_string(),
+            valuation_date: date,
+            account_currency: base_currency.to_string(),
+            base_currency: base_currency.to_string(),
+            fx_rate_to_base: Decimal::ONE,
+            cash_balance: Decimal::ZERO,
+            investment_market_value: market_value,
+            total_value: market_value,
+            cost_basis: positions.values().map(|position| position.cost_basis).sum(),
+            net_contribution,
+            calculated_at,
+        });
+    }
+    Ok(valuations)
+}
+
+/// Sums the valuations of several accounts into one series in base currency, keyed `item_id`.
+/// On every date any member was valued, a member without a valuation that day counts with
+/// its last earlier one, so gaps in one history do not show up as drops in the total.
+pub(crate) fn aggregate_valuations(
+    item_id: &str,
+    mut histories: Vec<Vec<DailyAccountValuation>>,
+) -> Vec<DailyAccountValuation> {
+    for history in &mut histories {
+        history.sort_by_key(|point| point.valuation_date);
+    }
+    let dates: BTreeSet<NaiveDate> = histories
+        .iter()
+        .flatten()
+        .map(|point| point.valuation_date)
+        .collect();
+
+    let mut next_indexes = vec![0; histories.len()];
+    let mut aggregated = Vec::with_capacity(dates.len());
+    for date in dates {
+        let mut entry: Option<DailyAccountValuation> = None;
+        for (history, next_index) in histories.iter().zip(next_indexes.iter_mut()) {
+            while history
+                .get(*next_index)
+                .is_some_and(|point| point.valuation_date <= date)
+            {
+                *next_index += 1;
+            }
+            // Members valued only after `date` are not part of the composite yet
+            let Some(point) = next_index.checked_sub(1).map(|index| &history[index]) else {
+                continue;
+            };
+            let entry = entry.get_or_insert_with(|| DailyAccountValuation {
+                id: format!("{}_{}", item_id, date),
+                account_id: item_id.to_string(),
+                valuation_date: date,
+                account_currency: point.base_currency.clone(),
+                base_currency: point.base_currency.clone(),
+                fx_rate_to_base: Decimal::ONE,
+                cash_balance: Decimal::ZERO,
+                investment_market_value: Decimal::ZERO,
+                total_value: Decimal::ZERO,
+                cost_basis: Decimal::ZERO,
+                net_contribution: Decimal::ZERO,
+                calculated_at: point.calculated_at,
+            });
+            let fx = point.fx_rate_to_base;
+            entry.cash_balance += point.cash_balance * fx;
+            entry.investment_market_value += point.investment_market_value * fx;
+            entry.total_value += point.total_value * fx;
+            entry.cost_basis += point.cost_basis * fx;
+            entry.net_contribution += point.net_contribution * fx;
+            entry.calculated_at = entry.calculated_at.max(point.calculated_at);
+        }
+        aggregated.extend(entry);
+    }
+    aggregated
+}
+
+pub(crate) const TRADING_DAYS_PER_YEAR: u32 = 252;
 const DAYS_PER_YEAR_DECIMAL: Decimal = dec!(365.25);
 const SQRT_TRADING_DAYS_APPROX: Decimal = dec!(15.874507866); // sqrt(252)
 
+// Extra days of benchmark prices fetched before the range so its first day has a close
+const BENCHMARK_PRICE_LOOKBACK_DAYS: i64 = 14;
+
+// Extra days of quotes loaded before the range so tagged positions have a close on its first day
+const QUOTE_LOOKBACK_DAYS: i64 = 14;
+
 impl PerformanceService {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         valuation_service: Arc<dyn ValuationServiceTrait + Send + Sync>,
         market_data_service: Arc<dyn MarketDataServiceTrait + Send + Sync>,
+        tag_repository: Arc<dyn TagRepositoryTrait>,
+        benchmark_repository: Arc<dyn BenchmarkRepositoryTrait>,
+        vn_market_service: Arc<VnMarketService>,
+        risk_free_rate_repository: Arc<dyn RiskFreeRateRepositoryTrait>,
+        settings_service: Arc<dyn SettingsServiceTrait>,
+        composite_repository: Arc<dyn CompositeRepositoryTrait>,
+        activity_repository: Arc<dyn ActivityRepositoryTrait>,
+        fx_service: Arc<dyn FxServiceTrait>,
     ) -> Self {
         Self {
             valuation_service,
             market_data_service,
+            tag_repository,
+            benchmark_repository,
+            vn_market_service,
+            risk_free_rate_repository,
+            settings_service,
+            composite_repository,
+            activity_repository,
+            fx_service,
+        }
+    }
+
+    fn get_risk_free_rate_series(&self) -> Result<String> {
+        let series = self.settings_service.get_settings()?.risk_free_rate_series;
+        if series.trim().is_empty() {
+            Ok(DEFAULT_RISK_FREE_RATE_SERIES.to_string())
+        } else {
+            Ok(series)
         }
     }
 
+    /// Risk-adjusted ratios of dated daily returns measured against the risk-free series
+    /// selected in settings. Each day earns the rate in effect on the previous observation;
+    /// days before the series' first entry use that first rate.
+    fn calculate_risk_metrics(
+        &self,
+        start_date: NaiveDate,
+        dated_returns: &[(NaiveDate, Decimal)],
+        annualized_return: Decimal,
+        drawdown: &DrawdownDetails,
+    ) -> Result<RiskMetrics> {
+        let risk_free_series = self.get_risk_free_rate_series()?;
+        let rates: BTreeMap<NaiveDate, Decimal> = self
+            .risk_free_rate_repository
+            .get_rates(&risk_free_series)?
+            .into_iter()
+            .map(|rate| (rate.effective_date, rate.annual_rate))
+            .collect();
+        let first_rate = rates.values().next().copied();
+
+        let mut risk_free_returns = Vec::with_capacity(dated_returns.len());
+        let mut rate_days = Decimal::ZERO;
+        let mut total_days = Decimal::ZERO;
+        let mut previous_date = start_date;
+        for &(date, _) in dated_returns {
+            let days = (date - previous_date).num_days();
+            let annual_rate = rates
+                .range(..=previous_date)
+                .next_back()
+                .map(|(_, rate)| *rate)
+                .or(first_rate)
+                .unwrap_or(Decimal::ZERO);
+            risk_free_returns.push(period_risk_free_return(annual_rate, days));
+            rate_days += annual_rate * Decimal::from(days);
+            total_days += Decimal::from(days);
+            previous_date = date;
+        }
+        let risk_free_rate = first_rate.map(|rate| {
+            if total_days.is_zero() {
+                rate
+            } else {
+                (rate_days / total_days).round_dp(DECIMAL_PRECISION)
+            }
+        });
+
+        let daily_returns: Vec<Decimal> = dated_returns.iter().map(|(_, r)| *r).collect();
+        let ratios = calculate_risk_adjusted_ratios(&daily_returns, &risk_free_returns);
+        let (best_day, worst_day) = find_best_and_worst_days(start_date, dated_returns);
+        let (best_month, worst_month) =
+            best_and_worst(calculate_monthly_returns(start_date, dated_returns));
+        let round = |value: Decimal| value.round_dp(DECIMAL_PRECISION);
+        let round_period = |period: PeriodReturn| PeriodReturn {
+            value: round(period.value),
+            ..period
+        };
+
+        Ok(RiskMetrics {
+            risk_free_series,
+            risk_free_rate,
+            sharpe_ratio: ratios.sharpe_ratio.map(round),
+            sortino_ratio: ratios.sortino_ratio.map(round),
+            calmar_ratio: calculate_calmar_ratio(annualized_return, drawdown.max_drawdown)
+                .map(round),
+            downside_deviation: round(ratios.downside_deviation),
+            best_day: best_day.map(round_period),
+            worst_day: worst_day.map(round_period),
+            best_month: best_month.map(round_period),
+            worst_month: worst_month.map(round_period),
+            max_drawdown_peak_date: drawdown.peak_date,
+            max_drawdown_trough_date: drawdown.trough_date,
+            max_drawdown_recovery_date: drawdown.recovery_date,
+        })
+    }
+
+    /// Loads the valuation history of an account, or for a tag or composite the
+    /// base-currency aggregate of every account it covers. A tag also covers the
+    /// activities it is applied to.
+    fn get_valuation_history(
+        &self,
+        item_type: &str,
+        item_id: &str,
+        start_date_opt: Option<NaiveDate>,
+        end_date_opt: Option<NaiveDate>,
+    ) -> Result<Vec<DailyAccountValuation>> {
+        let account_ids = match item_type {
+            "tag" => return self.tag_valuation_history(item_id, start_date_opt, end_date_opt),
+            "composite" => self
+                .composite_repository
+                .get_account_ids_for_composite(item_id)?,
+            _ => {
+                return self.valuation_service.get_historical_valuations(
+                    item_id,
+                    start_date_opt,
+                    end_date_opt,
+                )
+            }
+        };
+        self.aggregate_valuation_history(item_id, &account_ids, start_date_opt, end_date_opt)
+    }
+
+    /// Sums the daily valuations of several accounts in base currency. On a date an
+    /// account has no valuation, its last earlier one counts (see `aggregate_valuations`).
+    fn aggregate_valuation_history(
+        &self,
+        item_id: &str,
+        account_ids: &[String],
+        start_date_opt: Option<NaiveDate>,
+        end_date_opt: Option<NaiveDate>,
+    ) -> Result<Vec<DailyAccountValuation>> {
+        let histories =
+            self.account_valuation_histories(account_ids, start_date_opt, end_date_opt)?;
+
+        Ok(aggregate_valuations(item_id, histories))
+    }
+
+    fn account_valuation_histories(
+        &self,
+        account_ids: &[String],
+        start_date_opt: Option<NaiveDate>,
+        end_date_opt: Option<NaiveDate>,
+    ) -> Result<Vec<Vec<DailyAccountValuation>>> {
+        account_ids
+            .iter()
+            .filter(|id| id.as_str() != PORTFOLIO_TOTAL_ACCOUNT_ID)
+            .map(|account_id| {
+                self.valuation_service.get_historical_valuations(
+                    account_id,
+                    start_date_opt,
+                    end_date_opt,
+                )
+            })
+            .collect()
+    }
+
+    /// Valuation history of a tag: its accounts as a whole, plus the positions of activities
+    /// tagged in other accounts (see `tagged_activity_valuations`).
+    fn tag_valuation_history(
+        &self,
+        tag_id: &str,
+        start_date_opt: Option<NaiveDate>,
+        end_date_opt: Option<NaiveDate>,
+    ) -> Result<Vec<DailyAccountValuation>> {
+        let account_ids = self.tag_repository.get_account_ids_by_tag(tag_id)?;
+        let mut histories =
+            self.account_valuation_histories(&account_ids, start_date_opt, end_date_opt)?;
+
+        // Activities in tagged accounts are already part of those accounts' valuations
+        let activities: Vec<Activity> = self
+            .activity_repository
+            .get_activities_by_tag(tag_id)?
+            .into_iter()
+            .filter(|activity| !activity.is_draft && !account_ids.contains(&activity.account_id))
+            .collect();
+        let Some(first_date) = activities
+            .iter()
+            .map(|activity| activity.activity_date.naive_utc().date())
+            .min()
+        else {
+            return Ok(aggregate_valuations(tag_id, histories));
+        };
+        let start_date = start_date_opt.map_or(first_date, |start| start.max(first_date));
+        let end_date = end_date_opt.unwrap_or_else(|| chrono::Local::now().naive_local().date());
+        let dates: Vec<NaiveDate> = start_date
+            .iter_days()
+            .take_while(|date| *date <= end_date)
+            .collect();
+
+        let asset_ids: HashSet<String> = activities
+            .iter()
+            .filter(|activity| !activity.asset_id.starts_with(CASH_ASSET_PREFIX))
+            .map(|activity| activity.asset_id.clone())
+            .collect();
+        let mut closes: HashMap<String, BTreeMap<NaiveDate, Decimal>> = HashMap::new();
+        if !asset_ids.is_empty() && start_date <= end_date {
+            for quote in self
+                .market_data_service
+                .get_historical_quotes_for_symbols_in_range(
+                    &asset_ids,
+                    start_date - Duration::days(QUOTE_LOOKBACK_DAYS),
+                    end_date,
+                )?
+            {
+                closes
+                    .entry(quote.symbol)
+                    .or_default()
+                    .insert(quote.timestamp.naive_utc().date(), quote.close);
+            }
+        }
+
+        let base_currency = self.settings_service.get_settings()?.base_currency;
+        let mut fx_rates: HashMap<(String, NaiveDate), Decimal> = HashMap::new();
+        histories.push(tagged_activity_valuations(
+            tag_id,
+            &activities,
+            &closes,
+            &dates,
+            &base_currency,
+            |currency, date| {
+                if currency == base_currency {
+                    return Ok(Decimal::ONE);
+                }
+                if let Some(rate) = fx_rates.get(&(currency.to_string(), date)) {
+                    return Ok(*rate);
+                }
+                let rate =
+                    self.fx_service
+                        .get_exchange_rate_for_date(currency, &base_currency, date)?;
+                fx_rates.insert((currency.to_string(), date), rate);
+                Ok(rate)
+            },
+        )?);
+
+        Ok(aggregate_valuations(tag_id, histories))
+    }
+
     fn get_account_boundary_data(
         &self,
+        item_type: &str,
         account_id: &str,
         start_date_opt: Option<NaiveDate>,
         end_date_opt: Option<NaiveDate>,
@@ -76,11 +586,8 @@ impl PerformanceService {
         NaiveDate,
         String,
     )> {
-        let full_history = self.valuation_service.get_historical_valuations(
-            account_id,
-            start_date_opt,
-            end_date_opt,
-        )?;
+        let full_history =
+            self.get_valuation_history(item_type, account_id, start_date_opt, end_date_opt)?;
 
         if full_history.len() < 2 {
             warn!(
@@ -116,6 +623,7 @@ impl PerformanceService {
     /// Internal function for calculating account performance (Full)
     async fn calculate_account_performance(
         &self,
+        item_type: &str,
         account_id: &str,
         start_date_opt: Option<NaiveDate>,
         end_date_opt: Option<NaiveDate>,
@@ -128,11 +636,8 @@ impl PerformanceService {
             }
         }
 
-        let full_history = self.valuation_service.get_historical_valuations(
-            account_id,
-            start_date_opt,
-            end_date_opt,
-        )?;
+        let full_history =
+            self.get_valuation_history(item_type, account_id, start_date_opt, end_date_opt)?;
 
         if full_history.len() < 2 {
             warn!("Performance calculation for account '{}': Not enough valuation data ({} points). Returning empty response.", account_id, full_history.len());
@@ -147,7 +652,12 @@ impl PerformanceService {
 
         let capacity = full_history.len();
         let mut returns = Vec::with_capacity(capacity);
-        let mut daily_twr_returns = Vec::with_capacity(capacity - 1);
+        let mut dated_twr_returns = Vec::with_capacity(capacity - 1);
+        // Investor view for XIRR: the opening value and contributions go in, the closing value comes out
+        let mut cash_flows = vec![DatedCashFlow::new(
+            actual_start_date,
+            -start_point.total_value,
+        )];
 
         returns.push(ReturnData {
             date: actual_start_date,
@@ -177,6 +687,10 @@ impl PerformanceService {
             let current_net_contribution = curr_point.net_contribution;
 
             let cash_flow = current_net_contribution - prev_net_contribution;
+            // Net contribution only moves with DEPOSIT, WITHDRAWAL and TRANSFER activities
+            if !cash_flow.is_zero() {
+                cash_flows.push(DatedCashFlow::new(curr_point.valuation_date, -cash_flow));
+            }
 
             let twr_period_return = {
                 let denominator = prev_total_value + cash_flow;
@@ -197,7 +711,7 @@ impl PerformanceService {
                 }
             };
 
-            daily_twr_returns.push(twr_period_return);
+            dated_twr_returns.push((curr_point.valuation_date, twr_period_return));
             cumulative_twr_value *= one + twr_period_return;
             cumulative_mwr_value *= one + mwr_period_return;
 
@@ -212,8 +726,15 @@ impl PerformanceService {
         let cumulative_twr = returns.last().map_or(Decimal::ZERO, |r| r.value);
         let annualized_twr =
             Self::calculate_annualized_return(actual_start_date, actual_end_date, cumulative_twr);
+        let daily_twr_returns: Vec<Decimal> = dated_twr_returns.iter().map(|(_, r)| *r).collect();
         let volatility = Self::calculate_volatility(&daily_twr_returns);
-        let max_drawdown = Self::calculate_max_drawdown(&daily_twr_returns);
+        let drawdown = calculate_drawdown_details(actual_start_date, &dated_twr_returns);
+        let risk_metrics = self.calculate_risk_metrics(
+            actual_start_date,
+            &dated_twr_returns,
+            annualized_twr,
+            &drawdown,
+        )?;
 
         let start_net_contribution = start_point.net_contribution;
         let end_net_contribution = end_point.net_contribution;
@@ -250,6 +771,15 @@ impl PerformanceService {
         let annualized_mwr =
             Self::calculate_annualized_return(actual_start_date, actual_end_date, cumulative_mwr);
 
+        cash_flows.push(DatedCashFlow::new(actual_end_date, end_point.total_value));
+        let xirr = calculate_xirr(&cash_flows);
+        if xirr.is_none() {
+            debug!(
+                "XIRR could not be solved for account '{}' between {} and {}",
+                account_id, actual_start_date, actual_end_date
+            );
+        }
+
         let result = PerformanceMetrics {
             id: account_id.to_string(),
             returns,
@@ -263,8 +793,10 @@ impl PerformanceService {
             annualized_simple_return: annualized_simple_return.round_dp(DECIMAL_PRECISION),
             cumulative_mwr: cumulative_mwr.round_dp(DECIMAL_PRECISION),
             annualized_mwr: annualized_mwr.round_dp(DECIMAL_PRECISION),
+            xirr: xirr.map(|rate| rate.round_dp(DECIMAL_PRECISION)),
             volatility: volatility.round_dp(DECIMAL_PRECISION),
-            max_drawdown: max_drawdown.round_dp(DECIMAL_PRECISION),
+            max_drawdown: drawdown.max_drawdown.round_dp(DECIMAL_PRECISION),
+            risk_metrics: Some(risk_metrics),
         };
 
         Ok(result)
@@ -273,6 +805,7 @@ impl PerformanceService {
     /// Internal function for calculating account performance (Summary)
     async fn calculate_account_performance_summary(
         &self,
+        item_type: &str,
         account_id: &str,
         start_date_opt: Option<NaiveDate>,
         end_date_opt: Option<NaiveDate>,
@@ -283,7 +816,7 @@ impl PerformanceService {
             NaiveDate,
             NaiveDate,
             String,
-        ) = self.get_account_boundary_data(account_id, start_date_opt, end_date_opt)?;
+        ) = self.get_account_boundary_data(item_type, account_id, start_date_opt, end_date_opt)?;
 
         let start_value = start_point.total_value;
         let end_value = end_point.total_value;
@@ -322,8 +855,10 @@ impl PerformanceService {
             annualized_simple_return: annualized_simple_return.round_dp(DECIMAL_PRECISION),
             cumulative_mwr: Decimal::ZERO,
             annualized_mwr: Decimal::ZERO,
+            xirr: None,
             volatility: Decimal::ZERO,
             max_drawdown: Decimal::ZERO,
+            risk_metrics: None,
         };
 
         Ok(result)
@@ -400,7 +935,7 @@ impl PerformanceService {
 
         let capacity = (actual_end_date - actual_start_date).num_days().max(0) as usize + 1;
         let mut returns = Vec::with_capacity(capacity);
-        let mut daily_returns = Vec::with_capacity(capacity);
+        let mut dated_returns = Vec::with_capacity(capacity);
         let mut cumulative_value = Decimal::ONE;
         let mut current_date = actual_start_date;
         let mut last_known_price = prev_price;
@@ -419,7 +954,7 @@ impl PerformanceService {
             } else {
                 (current_price / prev_price) - Decimal::ONE
             };
-            daily_returns.push(daily_return);
+            dated_returns.push((current_date, daily_return));
             cumulative_value *= Decimal::ONE + daily_return;
             let cumulative_return_to_date = cumulative_value - Decimal::ONE;
 
@@ -445,8 +980,15 @@ impl PerformanceService {
         let total_return = returns.last().map_or(Decimal::ZERO, |r| r.value);
         let annualized_return =
             Self::calculate_annualized_return(actual_start_date, actual_end_date, total_return);
+        let daily_returns: Vec<Decimal> = dated_returns.iter().map(|(_, r)| *r).collect();
         let volatility = Self::calculate_volatility(&daily_returns);
-        let max_drawdown = Self::calculate_max_drawdown(&daily_returns);
+        let drawdown = calculate_drawdown_details(actual_start_date, &dated_returns);
+        let risk_metrics = self.calculate_risk_metrics(
+            actual_start_date,
+            &dated_returns,
+            annualized_return,
+            &drawdown,
+        )?;
 
         let result = PerformanceMetrics {
             id: symbol.to_string(),
@@ -461,13 +1003,210 @@ impl PerformanceService {
             annualized_simple_return: Decimal::ZERO,
             cumulative_mwr: Decimal::ZERO,
             annualized_mwr: Decimal::ZERO,
+            xirr: None,
             volatility: volatility.round_dp(DECIMAL_PRECISION),
-            max_drawdown: max_drawdown.round_dp(DECIMAL_PRECISION),
+            max_drawdown: drawdown.max_drawdown.round_dp(DECIMAL_PRECISION),
+            risk_metrics: Some(risk_metrics),
         };
 
         Ok(result)
     }
 
+    /// Loads benchmark closes keyed by date, preferring the Vietnamese market service and
+    /// falling back to the configured market data providers.
+    async fn get_benchmark_prices(
+        &self,
+        symbol: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<BTreeMap<NaiveDate, Decimal>> {
+        let fetch_start = start_date - Duration::days(BENCHMARK_PRICE_LOOKBACK_DAYS);
+
+        match self
+            .vn_market_service
+            .get_history(symbol, fetch_start, end_date)
+            .await
+        {
+            Ok(records) if !records.is_empty() => {
+                return Ok(records
+                    .into_iter()
+                    .map(|record| (record.date, record.close))
+                    .collect());
+            }
+            Ok(_) => debug!(
+                "Benchmark '{}': no VN market history, trying market data providers",
+                symbol
+            ),
+            Err(e) => warn!(
+                "Benchmark '{}': VN market history failed ({}), trying market data providers",
+                symbol, e
+            ),
+        }
+
+        let quotes = self
+            .market_data_service
+            .get_historical_quotes_from_provider(symbol, fetch_start, end_date)
+            .await?;
+        Ok(quotes
+            .into_iter()
+            .map(|quote| (quote.timestamp.date_naive(), quote.close))
+            .collect())
+    }
+
+    /// Internal function for comparing an account, tag or composite against a benchmark.
+    /// Benchmark prices are used in their quote currency without FX conversion.
+    async fn calculate_account_benchmark_comparison(
+        &self,
+        item_type: &str,
+        item_id: &str,
+        benchmark_symbol: &str,
+        start_date_opt: Option<NaiveDate>,
+        end_date_opt: Option<NaiveDate>,
+    ) -> Result<BenchmarkComparison> {
+        let full_history =
+            self.get_valuation_history(item_type, item_id, start_date_opt, end_date_opt)?;
+
+        if full_history.len() < 2 {
+            warn!("Benchmark comparison for '{}': Not enough valuation data ({} points). Returning empty response.", item_id, full_history.len());
+            return Ok(Self::empty_benchmark_comparison(item_id, benchmark_symbol));
+        }
+
+        let start_point = full_history.first().unwrap();
+        let end_point = full_history.last().unwrap();
+        let prices = self
+            .get_benchmark_prices(
+                benchmark_symbol,
+                start_point.valuation_date,
+                end_point.valuation_date,
+            )
+            .await?;
+
+        let Some(&first_price) = prices.values().next() else {
+            return Err(errors::Error::Calculation(
+                errors::CalculatorError::Calculation(format!(
+                    "No price history found for benchmark '{}'",
+                    benchmark_symbol
+                )),
+            ));
+        };
+        // Forward-fill the last close on or before each valuation date
+        let benchmark_prices: Vec<Decimal> = full_history
+            .iter()
+            .map(|point| {
+                prices
+                    .range(..=point.valuation_date)
+                    .next_back()
+                    .map_or(first_price, |(_, price)| *price)
+            })
+            .collect();
+
+        let capacity = full_history.len();
+        let mut portfolio_returns = Vec::with_capacity(capacity);
+        let mut benchmark_returns = Vec::with_capacity(capacity);
+        let mut daily_portfolio_returns = Vec::with_capacity(capacity - 1);
+        let mut daily_benchmark_returns = Vec::with_capacity(capacity - 1);
+        let mut flows_and_prices = Vec::with_capacity(capacity);
+
+        portfolio_returns.push(ReturnData {
+            date: start_point.valuation_date,
+            value: Decimal::ZERO,
+        });
+        benchmark_returns.push(ReturnData {
+            date: start_point.valuation_date,
+            value: Decimal::ZERO,
+        });
+        flows_and_prices.push((start_point.total_value, benchmark_prices[0]));
+
+        let mut cumulative_portfolio = Decimal::ONE;
+        let mut cumulative_benchmark = Decimal::ONE;
+        for (i, window) in full_history.windows(2).enumerate() {
+            let prev_point = &window[0];
+            let curr_point = &window[1];
+            let cash_flow = curr_point.net_contribution - prev_point.net_contribution;
+
+            let denominator = prev_point.total_value + cash_flow;
+            let portfolio_return = if denominator.is_zero() {
+                Decimal::ZERO
+            } else {
+                (curr_point.total_value / denominator) - Decimal::ONE
+            };
+            let prev_price = benchmark_prices[i];
+            let curr_price = benchmark_prices[i + 1];
+            let benchmark_return = if prev_price.is_zero() {
+                Decimal::ZERO
+            } else {
+                (curr_price / prev_price) - Decimal::ONE
+            };
+
+            daily_portfolio_returns.push(portfolio_return);
+            daily_benchmark_returns.push(benchmark_return);
+            cumulative_portfolio *= Decimal::ONE + portfolio_return;
+            cumulative_benchmark *= Decimal::ONE + benchmark_return;
+            portfolio_returns.push(ReturnData {
+                date: curr_point.valuation_date,
+                value: (cumulative_portfolio - Decimal::ONE).round_dp(DECIMAL_PRECISION),
+            });
+            benchmark_returns.push(ReturnData {
+                date: curr_point.valuation_date,
+                value: (cumulative_benchmark - Decimal::ONE).round_dp(DECIMAL_PRECISION),
+            });
+            flows_and_prices.push((cash_flow, curr_price));
+        }
+
+        let what_if_values = full_history
+            .iter()
+            .zip(simulate_benchmark_investment(&flows_and_prices))
+            .map(|(point, benchmark_value)| BenchmarkValuePoint {
+                date: point.valuation_date,
+                portfolio_value: point.total_value.round_dp(DECIMAL_PRECISION),
+                benchmark_value: benchmark_value.round_dp(DECIMAL_PRECISION),
+            })
+            .collect();
+
+        let portfolio_cumulative_return = cumulative_portfolio - Decimal::ONE;
+        let benchmark_cumulative_return = cumulative_benchmark - Decimal::ONE;
+        let risk = calculate_relative_risk(&daily_portfolio_returns, &daily_benchmark_returns);
+
+        Ok(BenchmarkComparison {
+            id: item_id.to_string(),
+            benchmark_symbol: benchmark_symbol.to_string(),
+            currency: start_point.account_currency.clone(),
+            period_start_date: Some(start_point.valuation_date),
+            period_end_date: Some(end_point.valuation_date),
+            portfolio_returns,
+            benchmark_returns,
+            what_if_values,
+            portfolio_cumulative_return: portfolio_cumulative_return.round_dp(DECIMAL_PRECISION),
+            benchmark_cumulative_return: benchmark_cumulative_return.round_dp(DECIMAL_PRECISION),
+            excess_return: (portfolio_cumulative_return - benchmark_cumulative_return)
+                .round_dp(DECIMAL_PRECISION),
+            tracking_error: risk.tracking_error.round_dp(DECIMAL_PRECISION),
+            beta: risk.beta.map(|beta| beta.round_dp(DECIMAL_PRECISION)),
+            correlation: risk
+                .correlation
+                .map(|correlation| correlation.round_dp(DECIMAL_PRECISION)),
+        })
+    }
+
+    fn empty_benchmark_comparison(id: &str, benchmark_symbol: &str) -> BenchmarkComparison {
+        BenchmarkComparison {
+            id: id.to_string(),
+            benchmark_symbol: benchmark_symbol.to_string(),
+            currency: "".to_string(),
+            period_start_date: None,
+            period_end_date: None,
+            portfolio_returns: Vec::new(),
+            benchmark_returns: Vec::new(),
+            what_if_values: Vec::new(),
+            portfolio_cumulative_return: Decimal::ZERO,
+            benchmark_cumulative_return: Decimal::ZERO,
+            excess_return: Decimal::ZERO,
+            tracking_error: Decimal::ZERO,
+            beta: None,
+            correlation: None,
+        }
+    }
+
     fn empty_response(id: &str) -> PerformanceMetrics {
         PerformanceMetrics {
             id: id.to_string(),
@@ -482,8 +1221,10 @@ impl PerformanceService {
             annualized_simple_return: Decimal::ZERO,
             cumulative_mwr: Decimal::ZERO,
             annualized_mwr: Decimal::ZERO,
+            xirr: None,
             volatility: Decimal::ZERO,
             max_drawdown: Decimal::ZERO,
+            risk_metrics: None,
         }
     }
 
@@ -560,29 +1301,6 @@ impl PerformanceService {
         daily_volatility * annualization_factor
     }
 
-    fn calculate_max_drawdown(daily_returns: &[Decimal]) -> Decimal {
-        if daily_returns.is_empty() {
-            return Decimal::ZERO;
-        }
-
-        let mut cumulative_value = Decimal::ONE;
-        let mut peak_value = Decimal::ONE;
-        let mut max_drawdown = Decimal::ZERO;
-
-        for &daily_return in daily_returns {
-            cumulative_value *= Decimal::ONE + daily_return;
-            peak_value = peak_value.max(cumulative_value);
-            if peak_value.is_zero() {
-                max_drawdown = max_drawdown.max(Decimal::ONE);
-            } else {
-                let drawdown = (peak_value - cumulative_value) / peak_value;
-                max_drawdown = max_drawdown.max(drawdown);
-            }
-        }
-
-        max_drawdown.max(Decimal::ZERO)
-    }
-
     pub fn calculate_simple_performance(
         current: &DailyAccountValuation,
         previous: Option<&DailyAccountValuation>,
@@ -660,7 +1378,7 @@ impl PerformanceService {
 
 #[async_trait::async_trait]
 impl PerformanceServiceTrait for PerformanceService {
-    /// Calculates cumulative returns for a given item (account or symbol)
+    /// Calculates cumulative returns for a given item (account, tag, composite or symbol)
     async fn calculate_performance_history(
         &self,
         item_type: &str,
@@ -669,8 +1387,8 @@ impl PerformanceServiceTrait for PerformanceService {
         end_date: Option<NaiveDate>,
     ) -> Result<PerformanceMetrics> {
         match item_type {
-            "account" => {
-                self.calculate_account_performance(item_id, start_date, end_date)
+            "account" | "tag" | "composite" => {
+                self.calculate_account_performance(item_type, item_id, start_date, end_date)
                     .await
             }
             "symbol" => {
@@ -693,8 +1411,8 @@ impl PerformanceServiceTrait for PerformanceService {
         end_date: Option<NaiveDate>,
     ) -> Result<PerformanceMetrics> {
         match item_type {
-            "account" => {
-                self.calculate_account_performance_summary(item_id, start_date, end_date)
+            "account" | "tag" | "composite" => {
+                self.calculate_account_performance_summary(item_type, item_id, start_date, end_date)
                     .await
             }
             "symbol" => {
@@ -707,6 +1425,54 @@ impl PerformanceServiceTrait for PerformanceService {
         }
     }
 
+    async fn calculate_periodic_returns(
+        &self,
+        item_type: &str,
+        item_id: &str,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<PeriodicReturns> {
+        let metrics = self
+            .calculate_performance_history(item_type, item_id, start_date, end_date)
+            .await?;
+        let periods = |period_type| {
+            calculate_periodic_returns(&metrics.returns, period_type)
+                .into_iter()
+                .map(|mut period| {
+                    period.value = period.value.round_dp(DECIMAL_PRECISION);
+                    period
+                })
+                .collect::<Vec<_>>()
+        };
+
+        Ok(PeriodicReturns {
+            monthly: periods(ReturnPeriodType::Month),
+            quarterly: periods(ReturnPeriodType::Quarter),
+            yearly: periods(ReturnPeriodType::Year),
+            id: metrics.id,
+            currency: metrics.currency,
+            period_start_date: metrics.period_start_date,
+            period_end_date: metrics.period_end_date,
+        })
+    }
+
+    fn get_aggregated_valuation_history(
+        &self,
+        item_type: &str,
+        item_id: &str,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<DailyAccountValuation>> {
+        match item_type {
+            "account" | "tag" | "composite" => {
+                self.get_valuation_history(item_type, item_id, start_date, end_date)
+            }
+            _ => Err(errors::Error::Validation(ValidationError::InvalidInput(
+                "Invalid item type".to_string(),
+            ))),
+        }
+    }
+
     fn calculate_accounts_simple_performance(
         &self,
         account_ids: &[String],
@@ -793,4 +1559,95 @@ impl PerformanceServiceTrait for PerformanceService {
 
         Ok(results)
     }
+
+    async fn calculate_benchmark_comparison(
+        &self,
+        item_type: &str,
+        item_id: &str,
+        benchmark_symbol: Option<&str>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<BenchmarkComparison> {
+        if let (Some(start), Some(end)) = (start_date, end_date) {
+            if start > end {
+                return Err(errors::Error::Validation(ValidationError::InvalidInput(
+                    "Start date must be before end date".to_string(),
+                )));
+            }
+        }
+
+        let symbol = match (benchmark_symbol, item_type) {
+            (Some(symbol), _) if !symbol.trim().is_empty() => symbol.trim().to_uppercase(),
+            (_, "account") => self.get_benchmark_symbol(item_id)?,
+            (_, "tag" | "composite") => DEFAULT_BENCHMARK_SYMBOL.to_string(),
+            _ => {
+                return Err(errors::Error::Validation(ValidationError::InvalidInput(
+                    "Invalid item type".to_string(),
+                )))
+            }
+        };
+
+        match item_type {
+            "account" | "tag" | "composite" => {
+                self.calculate_account_benchmark_comparison(
+                    item_type, item_id, &symbol, start_date, end_date,
+                )
+                .await
+            }
+            _ => Err(errors::Error::Validation(ValidationError::InvalidInput(
+                "Invalid item type".to_string(),
+            ))),
+        }
+    }
+
+    fn get_benchmark_symbol(&self, account_id: &str) -> Result<String> {
+        Ok(self
+            .benchmark_repository
+            .get_benchmark(account_id)?
+            .map(|benchmark| benchmark.symbol)
+            .unwrap_or_else(|| DEFAULT_BENCHMARK_SYMBOL.to_string()))
+    }
+
+    async fn set_benchmark_symbol(
+        &self,
+        account_id: &str,
+        symbol: Option<String>,
+    ) -> Result<String> {
+        match symbol
+            .map(|s| s.trim().to_uppercase())
+            .filter(|s| !s.is_empty())
+        {
+            Some(symbol) => {
+                let benchmark = self
+                    .benchmark_repository
+                    .upsert_benchmark(account_id, &symbol)
+                    .await?;
+                Ok(benchmark.symbol)
+            }
+            None => {
+                self.benchmark_repository
+                    .delete_benchmark(account_id)
+                    .await?;
+                Ok(DEFAULT_BENCHMARK_SYMBOL.to_string())
+            }
+        }
+    }
+
+    fn get_risk_free_rates(&self, series: Option<&str>) -> Result<Vec<RiskFreeRate>> {
+        let series = match series {
+            Some(series) => series.to_string(),
+            None => self.get_risk_free_rate_series()?,
+        };
+        self.risk_free_rate_repository.get_rates(&series)
+    }
+
+    async fn save_risk_free_rate(&self, rate: NewRiskFreeRate) -> Result<RiskFreeRate> {
+        rate.validate()?;
+        self.risk_free_rate_repository.upsert_rate(rate).await
+    }
+
+    async fn delete_risk_free_rate(&self, rate_id: &str) -> Result<()> {
+        self.risk_free_rate_repository.delete_rate(rate_id).await?;
+        Ok(())
+    }
 }
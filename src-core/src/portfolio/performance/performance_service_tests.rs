@@ -0,0 +1,211 @@
+use chrono::{Datelike, NaiveDate, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::{BTreeMap, HashMap};
+
+use super::performance_service::{aggregate_valuations, tagged_activity_valuations};
+use crate::activities::activities_constants::*;
+use crate::activities::Activity;
+use crate::portfolio::valuation::DailyAccountValuation;
+use crate::test_utils::date;
+
+fn activity(
+    activity_type: &str,
+    asset_id: &str,
+    day: u32,
+    quantity: Decimal,
+    unit_price: Decimal,
+    currency: &str,
+) -> Activity {
+    let activity_date = Utc.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap();
+    Activity {
+        id: format!("{}-{}-{}", activity_type, asset_id, day),
+        account_id: "acc-1".to_string(),
+        asset_id: asset_id.to_string(),
+        activity_type: activity_type.to_string(),
+        activity_date,
+        quantity,
+        unit_price,
+        currency: currency.to_string(),
+        fee: Decimal::ZERO,
+        amount: None,
+        is_draft: false,
+        comment: None,
+        fx_rate_side: None,
+        created_at: activity_date,
+        updated_at: activity_date,
+    }
+}
+
+fn january(days: std::ops::RangeInclusive<u32>) -> Vec<NaiveDate> {
+    days.map(|day| date(2024, 1, day)).collect()
+}
+
+#[test]
+fn test_tagged_activity_valuations_value_positions_at_closes() {
+    let activities = vec![
+        Activity {
+            fee: dec!(2),
+            ..activity(ACTIVITY_TYPE_SELL, "AAA", 4, dec!(4), dec!(120), "USD")
+        },
+        Activity {
+            fee: dec!(1),
+            ..activity(ACTIVITY_TYPE_BUY, "AAA", 2, dec!(10), dec!(100), "USD")
+        },
+    ];
+    let closes = HashMap::from([(
+        "AAA".to_string(),
+        BTreeMap::from([(date(2024, 1, 3), dec!(110))]),
+    )]);
+
+    let valuations = tagged_activity_valuations(
+        "tag-1",
+        &activities,
+        &closes,
+        &january(1..=4),
+        "USD",
+        |_, _| Ok(Decimal::ONE),
+    )
+    .unwrap();
+
+    // Nothing is tagged on the 1st; the 2nd has no close yet and uses the trade price
+    let points: Vec<(u32, Decimal, Decimal, Decimal)> = valuations
+        .iter()
+        .map(|v| {
+            (
+                v.valuation_date.day(),
+                v.total_value,
+                v.net_contribution,
+                v.cost_basis,
+            )
+        })
+        .collect();
+    assert_eq!(
+        points,
+        vec![
+            (2, dec!(1000), dec!(1001), dec!(1001)),
+            (3, dec!(1100), dec!(1001), dec!(1001)),
+            (4, dec!(660), dec!(523), dec!(600.6)),
+        ]
+    );
+    assert!(valuations
+        .iter()
+        .all(|v| v.account_id == "tag-1" && v.base_currency == "USD"));
+}
+
+#[test]
+fn test_tagged_activity_valuations_convert_flows_and_skip_untagged_shares() {
+    let activities = vec![
+        activity(ACTIVITY_TYPE_BUY, "BBB", 1, dec!(5), dec!(10), "EUR"),
+        // Half of the sale is of shares the tagged activities never bought
+        activity(ACTIVITY_TYPE_SELL, "BBB", 2, dec!(10), dec!(12), "EUR"),
+        Activity {
+            amount: Some(dec!(3)),
+            ..activity(ACTIVITY_TYPE_DIVIDEND, "BBB", 2, dec!(0), dec!(0), "EUR")
+        },
+        Activity {
+            amount: Some(dec!(500)),
+            ..activity(
+                ACTIVITY_TYPE_DEPOSIT,
+                "$CASH-EUR",
+                2,
+                dec!(0),
+                dec!(0),
+                "EUR",
+            )
+        },
+    ];
+
+    let valuations = tagged_activity_valuations(
+        "tag-1",
+        &activities,
+        &HashMap::new(),
+        &january(1..=2),
+        "USD",
+        |currency, _| {
+            Ok(if currency == "EUR" {
+                dec!(2)
+            } else {
+                Decimal::ONE
+            })
+        },
+    )
+    .unwrap();
+
+    assert_eq!(valuations.len(), 2);
+    assert_eq!(valuations[0].total_value, dec!(100));
+    assert_eq!(valuations[0].net_contribution, dec!(100));
+    assert_eq!(valuations[1].total_value, Decimal::ZERO);
+    assert_eq!(valuations[1].cost_basis, Decimal::ZERO);
+    // 60 EUR of proceeds for the 5 tagged shares and 3 EUR of dividends, at 2 USD per EUR
+    assert_eq!(valuations[1].net_contribution, dec!(-26));
+}
+
+fn valuation(
+    account_id: &str,
+    day: u32,
+    total_value: Decimal,
+    fx: Decimal,
+) -> DailyAccountValuation {
+    DailyAccountValuation {
+        id: format!("{}_{}", account_id, day),
+        account_id: account_id.to_string(),
+        valuation_date: NaiveDate::from_ymd_opt(2024, 1, day).unwrap(),
+        account_currency: "USD".to_string(),
+        base_currency: "USD".to_string(),
+        fx_rate_to_base: fx,
+        cash_balance: Decimal::ZERO,
+        investment_market_value: total_value,
+        total_value,
+        cost_basis: total_value,
+        net_contribution: total_value,
+        calculated_at: Utc::now(),
+    }
+}
+
+#[test]
+fn test_aggregate_valuations_forward_fills_missing_days() {
+    // acc-2 has no valuation on the 3rd; its value from the 2nd still counts
+    let histories = vec![
+        vec![
+            valuation("acc-1", 2, dec!(100), dec!(1)),
+            valuation("acc-1", 3, dec!(110), dec!(1)),
+            valuation("acc-1", 4, dec!(120), dec!(1)),
+        ],
+        vec![
+            valuation("acc-2", 4, dec!(60), dec!(2)),
+            valuation("acc-2", 2, dec!(50), dec!(2)),
+        ],
+    ];
+
+    let aggregated = aggregate_valuations("composite-1", histories);
+
+    let totals: Vec<(u32, Decimal)> = aggregated
+        .iter()
+        .map(|v| (v.valuation_date.day(), v.total_value))
+        .collect();
+    assert_eq!(totals, vec![(2, dec!(200)), (3, dec!(210)), (4, dec!(240))]);
+    assert!(aggregated
+        .iter()
+        .all(|v| v.account_id == "composite-1" && v.fx_rate_to_base == Decimal::ONE));
+    assert_eq!(aggregated[1].id, "composite-1_2024-01-03");
+}
+
+#[test]
+fn test_aggregate_valuations_adds_members_from_their_first_valuation() {
+    let histories = vec![
+        vec![
+            valuation("acc-1", 1, dec!(100), dec!(1)),
+            valuation("acc-1", 2, dec!(100), dec!(1)),
+        ],
+        vec![valuation("acc-2", 2, dec!(40), dec!(1))],
+    ];
+
+    let aggregated = aggregate_valuations("composite-1", histories);
+
+    assert_eq!(aggregated.len(), 2);
+    assert_eq!(aggregated[0].total_value, dec!(100));
+    assert_eq!(aggregated[0].net_contribution, dec!(100));
+    assert_eq!(aggregated[1].total_value, dec!(140));
+    assert_eq!(aggregated[1].net_contribution, dec!(140));
+}
@@ -0,0 +1,185 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use rust_decimal::Decimal;
+
+use super::{PeriodicReturn, ReturnData, ReturnPeriodType};
+
+impl ReturnPeriodType {
+    /// Year and month, quarter or 1 of the calendar period containing `date`
+    fn key(self, date: NaiveDate) -> (i32, u32) {
+        match self {
+            ReturnPeriodType::Month => (date.year(), date.month()),
+            ReturnPeriodType::Quarter => (date.year(), (date.month() - 1) / 3 + 1),
+            ReturnPeriodType::Year => (date.year(), 1),
+        }
+    }
+
+    fn first_month(self, period: u32) -> u32 {
+        match self {
+            ReturnPeriodType::Month => period,
+            ReturnPeriodType::Quarter => (period - 1) * 3 + 1,
+            ReturnPeriodType::Year => 1,
+        }
+    }
+
+    fn months(self) -> u32 {
+        match self {
+            ReturnPeriodType::Month => 1,
+            ReturnPeriodType::Quarter => 3,
+            ReturnPeriodType::Year => 12,
+        }
+    }
+
+    /// First and last calendar day of a period
+    fn bounds(self, year: i32, period: u32) -> Option<(NaiveDate, NaiveDate)> {
+        let first_month = self.first_month(period);
+        let start = NaiveDate::from_ymd_opt(year, first_month, 1)?;
+        let next_month = first_month + self.months();
+        let next_start = if next_month > 12 {
+            NaiveDate::from_ymd_opt(year + 1, next_month - 12, 1)?
+        } else {
+            NaiveDate::from_ymd_opt(year, next_month, 1)?
+        };
+        Some((start, next_start - Duration::days(1)))
+    }
+}
+
+/// Splits a cumulative return series into calendar periods. Each period's return
+/// chain-links from the last observation of the previous period (or the first
+/// observation of the series) to its own last observation, so compounding all
+/// periods reproduces the cumulative return. The first and last periods are flagged
+/// as partial when the series does not cover them from their first to last day.
+pub fn calculate_periodic_returns(
+    cumulative_returns: &[ReturnData],
+    period_type: ReturnPeriodType,
+) -> Vec<PeriodicReturn> {
+    let Some(first) = cumulative_returns.first() else {
+        return Vec::new();
+    };
+
+    let mut periods: Vec<PeriodicReturn> = Vec::new();
+    let mut base_value = Decimal::ONE + first.value;
+    let mut base_date = first.date;
+    let mut current: Option<((i32, u32), NaiveDate, Decimal)> = None;
+
+    let mut close_period = |key: (i32, u32), end_date: NaiveDate, end_value: Decimal| {
+        let value = if base_value.is_zero() {
+            Decimal::ZERO
+        } else {
+            end_value / base_value - Decimal::ONE
+        };
+        periods.push(PeriodicReturn {
+            period_type,
+            year: key.0,
+            period: key.1,
+            start_date: base_date,
+            end_date,
+            value,
+            is_partial: false,
+        });
+        base_value = end_value;
+        base_date = end_date;
+    };
+
+    for point in &cumulative_returns[1..] {
+        let key = period_type.key(point.date);
+        let growth = Decimal::ONE + point.value;
+        match current {
+            Some((current_key, _, _)) if current_key == key => {
+                current = Some((key, point.date, growth));
+            }
+            Some((current_key, end_date, end_value)) => {
+                close_period(current_key, end_date, end_value);
+                current = Some((key, point.date, growth));
+            }
+            None => current = Some((key, point.date, growth)),
+        }
+    }
+    if let Some((key, end_date, end_value)) = current {
+        close_period(key, end_date, end_value);
+    }
+
+    if let Some(first_period) = periods.first_mut() {
+        if let Some((start, _)) = period_type.bounds(first_period.year, first_period.period) {
+            // A full period is measured from the last day of the period before it
+            first_period.is_partial |= first.date >= start;
+        }
+    }
+    if let Some(last_period) = periods.last_mut() {
+        if let Some((_, end)) = period_type.bounds(last_period.year, last_period.period) {
+            last_period.is_partial |= last_period.end_date < end;
+        }
+    }
+
+    periods
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn point(y: i32, m: u32, d: u32, value: Decimal) -> ReturnData {
+        ReturnData {
+            date: NaiveDate::from_ymd_opt(y, m, d).unwrap(),
+            value,
+        }
+    }
+
+    #[test]
+    fn test_monthly_returns_chain_link_to_cumulative() {
+        let returns = [
+            point(2023, 12, 31, dec!(0)),
+            point(2024, 1, 15, dec!(0.05)),
+            point(2024, 1, 31, dec!(0.1)),
+            point(2024, 2, 29, dec!(0.21)),
+            point(2024, 3, 10, dec!(0.089)),
+        ];
+        let months = calculate_periodic_returns(&returns, ReturnPeriodType::Month);
+
+        assert_eq!(months.len(), 3);
+        assert_eq!((months[0].year, months[0].period), (2024, 1));
+        assert_eq!(months[0].value, dec!(0.1));
+        assert!(!months[0].is_partial);
+        assert_eq!(months[1].value, dec!(0.1));
+        assert_eq!(
+            months[1].start_date,
+            NaiveDate::from_ymd_opt(2024, 1, 31).unwrap()
+        );
+        assert!(!months[1].is_partial);
+        assert_eq!(months[2].value, dec!(-0.1));
+        assert!(months[2].is_partial);
+
+        let compounded = months
+            .iter()
+            .fold(Decimal::ONE, |acc, m| acc * (Decimal::ONE + m.value));
+        assert_eq!(compounded - Decimal::ONE, dec!(0.089));
+    }
+
+    #[test]
+    fn test_first_period_partial_when_history_starts_inside_it() {
+        let returns = [
+            point(2024, 2, 10, dec!(0)),
+            point(2024, 3, 31, dec!(0.02)),
+            point(2024, 4, 30, dec!(0.0404)),
+        ];
+        let quarters = calculate_periodic_returns(&returns, ReturnPeriodType::Quarter);
+        assert_eq!(quarters.len(), 2);
+        assert_eq!((quarters[0].year, quarters[0].period), (2024, 1));
+        assert!(quarters[0].is_partial);
+        assert_eq!((quarters[1].year, quarters[1].period), (2024, 2));
+        assert_eq!(quarters[1].value, dec!(0.02));
+        assert!(quarters[1].is_partial);
+
+        let years = calculate_periodic_returns(&returns, ReturnPeriodType::Year);
+        assert_eq!(years.len(), 1);
+        assert_eq!(years[0].value, dec!(0.0404));
+        assert!(years[0].is_partial);
+    }
+
+    #[test]
+    fn test_no_periods_without_returns() {
+        assert!(calculate_periodic_returns(&[], ReturnPeriodType::Year).is_empty());
+        let single = [point(2024, 1, 1, dec!(0))];
+        assert!(calculate_periodic_returns(&single, ReturnPeriodType::Month).is_empty());
+    }
+}
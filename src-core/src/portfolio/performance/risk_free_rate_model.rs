@@ -0,0 +1,66 @@
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::errors::{Error, Result, ValidationError};
+
+/// Annual risk-free rate of a series, effective from `effective_date` until the next entry
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskFreeRate {
+    pub id: String,
+    pub series: String,
+    pub effective_date: NaiveDate,
+    /// Annual rate as a fraction, e.g. 0.048 for 4.8%
+    pub annual_rate: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewRiskFreeRate {
+    pub series: String,
+    pub effective_date: NaiveDate,
+    pub annual_rate: Decimal,
+}
+
+impl NewRiskFreeRate {
+    pub fn validate(&self) -> Result<()> {
+        if self.series.trim().is_empty() {
+            return Err(Error::Validation(ValidationError::InvalidInput(
+                "Risk-free rate series cannot be empty".to_string(),
+            )));
+        }
+        if self.annual_rate <= Decimal::NEGATIVE_ONE || self.annual_rate > Decimal::ONE {
+            return Err(Error::Validation(ValidationError::InvalidInput(format!(
+                "Annual risk-free rate {} must be a fraction between -1 and 1",
+                self.annual_rate
+            ))));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Queryable, Insertable, AsChangeset, Selectable, Debug, Clone)]
+#[diesel(table_name = crate::schema::risk_free_rates)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct RiskFreeRateDB {
+    pub id: String,
+    pub series: String,
+    pub effective_date: NaiveDate,
+    pub annual_rate: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<RiskFreeRateDB> for RiskFreeRate {
+    fn from(db: RiskFreeRateDB) -> Self {
+        Self {
+            id: db.id,
+            series: db.series,
+            effective_date: db.effective_date,
+            annual_rate: Decimal::from_str(&db.annual_rate).unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::sqlite::SqliteConnection;
+use std::sync::Arc;
+
+use super::risk_free_rate_model::{NewRiskFreeRate, RiskFreeRate, RiskFreeRateDB};
+use crate::db::{get_connection, WriteHandle};
+use crate::errors::Result;
+use crate::schema::risk_free_rates;
+
+#[async_trait]
+pub trait RiskFreeRateRepositoryTrait: Send + Sync {
+    /// Rates of a series ordered by effective date
+    fn get_rates(&self, series: &str) -> Result<Vec<RiskFreeRate>>;
+    async fn upsert_rate(&self, rate: NewRiskFreeRate) -> Result<RiskFreeRate>;
+    async fn delete_rate(&self, rate_id: &str) -> Result<usize>;
+}
+
+pub struct RiskFreeRateRepository {
+    pool: Arc<Pool<ConnectionManager<SqliteConnection>>>,
+    writer: WriteHandle,
+}
+
+impl RiskFreeRateRepository {
+    pub fn new(pool: Arc<Pool<ConnectionManager<SqliteConnection>>>, writer: WriteHandle) -> Self {
+        RiskFreeRateRepository { pool, writer }
+    }
+}
+
+#[async_trait]
+impl RiskFreeRateRepositoryTrait for RiskFreeRateRepository {
+    fn get_rates(&self, series: &str) -> Result<Vec<RiskFreeRate>> {
+        let mut conn = get_connection(&self.pool)?;
+        let rates = risk_free_rates::table
+            .filter(risk_free_rates::series.eq(series))
+            .order(risk_free_rates::effective_date.asc())
+            .load::<RiskFreeRateDB>(&mut conn)?;
+        Ok(rates.into_iter().map(RiskFreeRate::from).collect())
+    }
+
+    async fn upsert_rate(&self, rate: NewRiskFreeRate) -> Result<RiskFreeRate> {
+        let now = Utc::now().to_rfc3339();
+        let series = rate.series.trim().to_string();
+        let rate_db = RiskFreeRateDB {
+            id: format!("{}_{}", series, rate.effective_date),
+            series,
+            effective_date: rate.effective_date,
+            annual_rate: rate.annual_rate.to_string(),
+            created_at: now.clone(),
+            updated_at: now,
+        };
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<RiskFreeRate> {
+                diesel::insert_into(risk_free_rates::table)
+                    .values(&rate_db)
+                    .on_conflict(risk_free_rates::id)
+                    .do_update()
+                    .set((
+                        risk_free_rates::annual_rate.eq(&rate_db.annual_rate),
+                        risk_free_rates::updated_at.eq(&rate_db.updated_at),
+                    ))
+                    .execute(conn)?;
+                Ok(rate_db.into())
+            })
+            .await
+    }
+
+    async fn delete_rate(&self, rate_id: &str) -> Result<usize> {
+        let rate_id = rate_id.to_string();
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<usize> {
+                Ok(diesel::delete(risk_free_rates::table.find(rate_id)).execute(conn)?)
+            })
+            .await
+    }
+}
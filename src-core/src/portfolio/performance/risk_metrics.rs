@@ -0,0 +1,314 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal::MathematicalOps;
+use rust_decimal_macros::dec;
+
+use super::performance_service::TRADING_DAYS_PER_YEAR;
+use super::{calculate_periodic_returns, PeriodReturn, ReturnData, ReturnPeriodType};
+
+const DAYS_PER_YEAR: Decimal = dec!(365);
+
+/// Size and dates of the deepest peak-to-trough decline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DrawdownDetails {
+    pub max_drawdown: Decimal,
+    pub peak_date: Option<NaiveDate>,
+    pub trough_date: Option<NaiveDate>,
+    /// First date the previous peak was regained; `None` while still under water
+    pub recovery_date: Option<NaiveDate>,
+}
+
+/// Sharpe and Sortino ratios with the downside deviation they are based on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiskAdjustedRatios {
+    pub sharpe_ratio: Option<Decimal>,
+    pub sortino_ratio: Option<Decimal>,
+    /// Annualized root mean square of the returns that fell short of the risk-free rate
+    pub downside_deviation: Decimal,
+}
+
+/// Return of the risk-free asset over `days` calendar days at an annual `rate`.
+pub fn period_risk_free_return(annual_rate: Decimal, days: i64) -> Decimal {
+    if days <= 0 || annual_rate <= dec!(-1) {
+        return Decimal::ZERO;
+    }
+    (Decimal::ONE + annual_rate)
+        .checked_powd(Decimal::from(days) / DAYS_PER_YEAR)
+        .map_or(Decimal::ZERO, |growth| growth - Decimal::ONE)
+}
+
+/// Tracks the compounded value of `daily_returns`, each dated by the day it ends on,
+/// and reports the largest decline from a running peak. The series starts at
+/// `start_date` with a value of one.
+pub fn calculate_drawdown_details(
+    start_date: NaiveDate,
+    daily_returns: &[(NaiveDate, Decimal)],
+) -> DrawdownDetails {
+    let mut details = DrawdownDetails {
+        max_drawdown: Decimal::ZERO,
+        peak_date: None,
+        trough_date: None,
+        recovery_date: None,
+    };
+
+    let mut cumulative_value = Decimal::ONE;
+    let mut peak_value = Decimal::ONE;
+    let mut peak_date = start_date;
+    let mut max_drawdown_peak_value = Decimal::ONE;
+
+    for &(date, daily_return) in daily_returns {
+        cumulative_value *= Decimal::ONE + daily_return;
+        if cumulative_value >= peak_value {
+            peak_value = cumulative_value;
+            peak_date = date;
+            if details.trough_date.is_some()
+                && details.recovery_date.is_none()
+                && cumulative_value >= max_drawdown_peak_value
+            {
+                details.recovery_date = Some(date);
+            }
+            continue;
+        }
+
+        let drawdown = if peak_value.is_zero() {
+            Decimal::ONE
+        } else {
+            (peak_value - cumulative_value) / peak_value
+        };
+        if drawdown > details.max_drawdown {
+            details.max_drawdown = drawdown;
+            details.peak_date = Some(peak_date);
+            details.trough_date = Some(date);
+            details.recovery_date = None;
+            max_drawdown_peak_value = peak_value;
+        }
+    }
+
+    details
+}
+
+/// Annualized Sharpe and Sortino ratios from daily returns and the risk-free return
+/// earned over the same days. Both ratios are `None` with fewer than two observations
+/// or when their denominator is zero.
+pub fn calculate_risk_adjusted_ratios(
+    daily_returns: &[Decimal],
+    daily_risk_free_returns: &[Decimal],
+) -> RiskAdjustedRatios {
+    let excess_returns: Vec<Decimal> = daily_returns
+        .iter()
+        .enumerate()
+        .map(|(i, r)| r - daily_risk_free_returns.get(i).copied().unwrap_or_default())
+        .collect();
+    let count = excess_returns.len();
+    if count < 2 {
+        return RiskAdjustedRatios {
+            sharpe_ratio: None,
+            sortino_ratio: None,
+            downside_deviation: Decimal::ZERO,
+        };
+    }
+
+    let n = Decimal::from(count);
+    let mean_excess = excess_returns.iter().sum::<Decimal>() / n;
+    let variance = excess_returns
+        .iter()
+        .map(|&r| (r - mean_excess) * (r - mean_excess))
+        .sum::<Decimal>()
+        / (n - Decimal::ONE);
+    let downside_variance = excess_returns
+        .iter()
+        .filter(|r| r.is_sign_negative())
+        .map(|&r| r * r)
+        .sum::<Decimal>()
+        / n;
+
+    let annualization_factor = Decimal::from(TRADING_DAYS_PER_YEAR)
+        .sqrt()
+        .unwrap_or(Decimal::ZERO);
+    let daily_volatility = variance.sqrt().unwrap_or(Decimal::ZERO);
+    let daily_downside_deviation = downside_variance.sqrt().unwrap_or(Decimal::ZERO);
+
+    let annualized_ratio = |denominator: Decimal| {
+        (!denominator.is_zero()).then(|| mean_excess / denominator * annualization_factor)
+    };
+
+    RiskAdjustedRatios {
+        sharpe_ratio: annualized_ratio(daily_volatility),
+        sortino_ratio: annualized_ratio(daily_downside_deviation),
+        downside_deviation: daily_downside_deviation * annualization_factor,
+    }
+}
+
+/// Annualized return divided by the maximum drawdown; `None` without a drawdown.
+pub fn calculate_calmar_ratio(
+    annualized_return: Decimal,
+    max_drawdown: Decimal,
+) -> Option<Decimal> {
+    (max_drawdown > Decimal::ZERO).then(|| annualized_return / max_drawdown)
+}
+
+/// Best and worst single-day returns. Each day runs from the previous observation.
+pub fn find_best_and_worst_days(
+    start_date: NaiveDate,
+    daily_returns: &[(NaiveDate, Decimal)],
+) -> (Option<PeriodReturn>, Option<PeriodReturn>) {
+    let mut previous_date = start_date;
+    let days: Vec<PeriodReturn> = daily_returns
+        .iter()
+        .map(|&(date, value)| {
+            let period = PeriodReturn {
+                start_date: previous_date,
+                end_date: date,
+                value,
+            };
+            previous_date = date;
+            period
+        })
+        .collect();
+    best_and_worst(days)
+}
+
+/// Monthly returns of a daily return series, chain-linked by `calculate_periodic_returns`.
+/// The first month starts at `start_date` and the last ends at the final observation,
+/// so either may be partial.
+pub fn calculate_monthly_returns(
+    start_date: NaiveDate,
+    daily_returns: &[(NaiveDate, Decimal)],
+) -> Vec<PeriodReturn> {
+    let mut growth = Decimal::ONE;
+    let cumulative_returns: Vec<ReturnData> = std::iter::once(ReturnData {
+        date: start_date,
+        value: Decimal::ZERO,
+    })
+    .chain(daily_returns.iter().map(|&(date, daily_return)| {
+        growth *= Decimal::ONE + daily_return;
+        ReturnData {
+            date,
+            value: growth - Decimal::ONE,
+        }
+    }))
+    .collect();
+
+    calculate_periodic_returns(&cumulative_returns, ReturnPeriodType::Month)
+        .into_iter()
+        .map(|month| PeriodReturn {
+            start_date: month.start_date,
+            end_date: month.end_date,
+            value: month.value,
+        })
+        .collect()
+}
+
+/// Best and worst entries of a set of period returns.
+pub fn best_and_worst(
+    periods: impl IntoIterator<Item = PeriodReturn>,
+) -> (Option<PeriodReturn>, Option<PeriodReturn>) {
+    let mut best: Option<PeriodReturn> = None;
+    let mut worst: Option<PeriodReturn> = None;
+    for period in periods {
+        if best.as_ref().is_none_or(|b| period.value > b.value) {
+            best = Some(period.clone());
+        }
+        if worst.as_ref().is_none_or(|w| period.value < w.value) {
+            worst = Some(period);
+        }
+    }
+    (best, worst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::date;
+
+    #[test]
+    fn test_drawdown_dates_with_recovery() {
+        let returns = [
+            (date(2024, 1, 2), dec!(0.10)),  // 1.10 new peak
+            (date(2024, 1, 3), dec!(-0.20)), // 0.88
+            (date(2024, 1, 4), dec!(-0.25)), // 0.66 trough, 40% below peak
+            (date(2024, 1, 5), dec!(0.5)),   // 0.99
+            (date(2024, 1, 8), dec!(0.12)),  // 1.1088 recovered
+        ];
+        let details = calculate_drawdown_details(date(2024, 1, 1), &returns);
+        assert_eq!(details.max_drawdown.round_dp(8), dec!(0.4));
+        assert_eq!(details.peak_date, Some(date(2024, 1, 2)));
+        assert_eq!(details.trough_date, Some(date(2024, 1, 4)));
+        assert_eq!(details.recovery_date, Some(date(2024, 1, 8)));
+    }
+
+    #[test]
+    fn test_drawdown_without_recovery() {
+        let returns = [
+            (date(2024, 1, 2), dec!(-0.1)),
+            (date(2024, 1, 3), dec!(0.05)),
+        ];
+        let details = calculate_drawdown_details(date(2024, 1, 1), &returns);
+        assert_eq!(details.max_drawdown, dec!(0.1));
+        assert_eq!(details.peak_date, Some(date(2024, 1, 1)));
+        assert_eq!(details.trough_date, Some(date(2024, 1, 2)));
+        assert_eq!(details.recovery_date, None);
+    }
+
+    #[test]
+    fn test_sharpe_and_sortino_against_risk_free_rate() {
+        let returns = [dec!(0.02), dec!(-0.01), dec!(0.03), dec!(-0.02)];
+        let risk_free = [dec!(0.001); 4];
+        let ratios = calculate_risk_adjusted_ratios(&returns, &risk_free);
+
+        // Excess returns 0.019, -0.011, 0.029, -0.021: mean 0.004, sample sd 0.02380476
+        let sqrt_252 = Decimal::from(TRADING_DAYS_PER_YEAR).sqrt().unwrap();
+        let expected_sharpe = dec!(0.004) / dec!(0.023804761) * sqrt_252;
+        assert_eq!(
+            ratios.sharpe_ratio.unwrap().round_dp(4),
+            expected_sharpe.round_dp(4)
+        );
+        // Downside deviation: sqrt((0.011^2 + 0.021^2) / 4) = 0.01185327
+        let expected_sortino = dec!(0.004) / dec!(0.011853270) * sqrt_252;
+        assert_eq!(
+            ratios.sortino_ratio.unwrap().round_dp(4),
+            expected_sortino.round_dp(4)
+        );
+        assert_eq!(
+            ratios.downside_deviation.round_dp(6),
+            (dec!(0.011853270) * sqrt_252).round_dp(6)
+        );
+    }
+
+    #[test]
+    fn test_sortino_undefined_without_shortfall() {
+        let ratios = calculate_risk_adjusted_ratios(&[dec!(0.01), dec!(0.02)], &[]);
+        assert!(ratios.sharpe_ratio.is_some());
+        assert_eq!(ratios.sortino_ratio, None);
+        assert_eq!(ratios.downside_deviation, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_period_risk_free_return_compounds_annual_rate() {
+        assert_eq!(
+            period_risk_free_return(dec!(0.05), 365).round_dp(8),
+            dec!(0.05)
+        );
+        assert_eq!(period_risk_free_return(dec!(0.05), 0), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_monthly_returns_are_chain_linked() {
+        let returns = [
+            (date(2024, 1, 30), dec!(0.1)),
+            (date(2024, 1, 31), dec!(0.1)),
+            (date(2024, 2, 1), dec!(-0.05)),
+        ];
+        let months = calculate_monthly_returns(date(2024, 1, 29), &returns);
+        assert_eq!(months.len(), 2);
+        assert_eq!(months[0].start_date, date(2024, 1, 29));
+        assert_eq!(months[0].end_date, date(2024, 1, 31));
+        assert_eq!(months[0].value, dec!(0.21));
+        assert_eq!(months[1].start_date, date(2024, 1, 31));
+        assert_eq!(months[1].value, dec!(-0.05));
+
+        let (best, worst) = best_and_worst(months);
+        assert_eq!(best.unwrap().value, dec!(0.21));
+        assert_eq!(worst.unwrap().value, dec!(-0.05));
+    }
+}
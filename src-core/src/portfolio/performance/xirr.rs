@@ -0,0 +1,205 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal::MathematicalOps;
+use rust_decimal_macros::dec;
+
+const DAYS_PER_YEAR: Decimal = dec!(365);
+const NEWTON_INITIAL_GUESS: Decimal = dec!(0.1);
+const NEWTON_MAX_ITERATIONS: usize = 100;
+const BISECTION_MAX_ITERATIONS: usize = 300;
+const RATE_TOLERANCE: Decimal = dec!(0.0000000001);
+// Rates are searched in (-100%, RATE_UPPER_LIMIT]; anything above is reported as unsolvable
+const RATE_LOWER_BOUND: Decimal = dec!(-0.9999);
+const RATE_UPPER_LIMIT: Decimal = dec!(10000);
+
+/// A dated cash flow seen from the investor: money put in is negative, money taken out
+/// (including the closing value) is positive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DatedCashFlow {
+    pub date: NaiveDate,
+    pub amount: Decimal,
+}
+
+impl DatedCashFlow {
+    pub fn new(date: NaiveDate, amount: Decimal) -> Self {
+        Self { date, amount }
+    }
+}
+
+/// Annualized internal rate of return of irregular cash flows, using the same
+/// actual/365 convention as the spreadsheet XIRR function.
+///
+/// Solves with Newton's method and falls back to bisection when Newton diverges.
+/// Returns `None` when the flows do not contain both an inflow and an outflow,
+/// or when no rate in (-100%, 1,000,000%] zeroes the net present value.
+pub fn calculate_xirr(cash_flows: &[DatedCashFlow]) -> Option<Decimal> {
+    let first_date = cash_flows.iter().map(|cf| cf.date).min()?;
+    let has_inflow = cash_flows
+        .iter()
+        .any(|cf| cf.amount.is_sign_positive() && !cf.amount.is_zero());
+    let has_outflow = cash_flows
+        .iter()
+        .any(|cf| cf.amount.is_sign_negative() && !cf.amount.is_zero());
+    if !has_inflow || !has_outflow {
+        return None;
+    }
+
+    let flows: Vec<(Decimal, Decimal)> = cash_flows
+        .iter()
+        .filter(|cf| !cf.amount.is_zero())
+        .map(|cf| {
+            let years = Decimal::from((cf.date - first_date).num_days()) / DAYS_PER_YEAR;
+            (years, cf.amount)
+        })
+        .collect();
+
+    solve_newton(&flows).or_else(|| solve_bisection(&flows))
+}
+
+/// Net present value and its derivative at `rate`, or `None` on overflow.
+fn npv_with_derivative(flows: &[(Decimal, Decimal)], rate: Decimal) -> Option<(Decimal, Decimal)> {
+    let base = Decimal::ONE + rate;
+    if base <= Decimal::ZERO {
+        return None;
+    }
+
+    let mut npv = Decimal::ZERO;
+    let mut derivative = Decimal::ZERO;
+    for &(years, amount) in flows {
+        let discount = base.checked_powd(years)?;
+        if discount.is_zero() {
+            return None;
+        }
+        let present_value = amount.checked_div(discount)?;
+        npv = npv.checked_add(present_value)?;
+        derivative =
+            derivative.checked_sub(years.checked_mul(present_value)?.checked_div(base)?)?;
+    }
+    Some((npv, derivative))
+}
+
+fn solve_newton(flows: &[(Decimal, Decimal)]) -> Option<Decimal> {
+    let mut rate = NEWTON_INITIAL_GUESS;
+    for _ in 0..NEWTON_MAX_ITERATIONS {
+        let (npv, derivative) = npv_with_derivative(flows, rate)?;
+        if derivative.is_zero() {
+            return None;
+        }
+        let next_rate = rate - npv.checked_div(derivative)?;
+        if next_rate <= dec!(-1) || next_rate > RATE_UPPER_LIMIT {
+            return None;
+        }
+        if (next_rate - rate).abs() < RATE_TOLERANCE {
+            return Some(next_rate);
+        }
+        rate = next_rate;
+    }
+    None
+}
+
+fn solve_bisection(flows: &[(Decimal, Decimal)]) -> Option<Decimal> {
+    let npv_at = |rate: Decimal| npv_with_derivative(flows, rate).map(|(npv, _)| npv);
+
+    // Over several years (1 + rate)^years underflows near -100%. Raise the lower bound
+    // by doubling 1 + rate until the NPV can be evaluated.
+    let mut low = RATE_LOWER_BOUND;
+    let mut npv_low = loop {
+        match npv_at(low) {
+            Some(npv) => break npv,
+            None if low < Decimal::ZERO => {
+                low = ((Decimal::ONE + low) * dec!(2) - Decimal::ONE).min(Decimal::ZERO)
+            }
+            None => return None,
+        }
+    };
+    // Grow the upper bound until the NPV changes sign
+    let mut high = Decimal::ONE;
+    let mut npv_high = npv_at(high)?;
+    while npv_low.is_sign_negative() == npv_high.is_sign_negative() {
+        if high >= RATE_UPPER_LIMIT {
+            return None;
+        }
+        high *= dec!(2);
+        npv_high = npv_at(high)?;
+    }
+
+    for _ in 0..BISECTION_MAX_ITERATIONS {
+        let mid = (low + high) / dec!(2);
+        let npv_mid = npv_at(mid)?;
+        if npv_mid.is_zero() || (high - low) < RATE_TOLERANCE {
+            return Some(mid);
+        }
+        if npv_mid.is_sign_negative() == npv_low.is_sign_negative() {
+            low = mid;
+            npv_low = npv_mid;
+        } else {
+            high = mid;
+        }
+    }
+    Some((low + high) / dec!(2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flow(y: i32, m: u32, d: u32, amount: Decimal) -> DatedCashFlow {
+        DatedCashFlow::new(NaiveDate::from_ymd_opt(y, m, d).unwrap(), amount)
+    }
+
+    #[test]
+    fn test_xirr_single_period_matches_simple_return() {
+        let flows = [flow(2021, 1, 1, dec!(-1000)), flow(2022, 1, 1, dec!(1100))];
+        assert_eq!(calculate_xirr(&flows).unwrap().round_dp(8), dec!(0.1));
+    }
+
+    #[test]
+    fn test_xirr_spreadsheet_reference_example() {
+        // Reference example of the spreadsheet XIRR function: 37.3362535%
+        let flows = [
+            flow(2008, 1, 1, dec!(-10000)),
+            flow(2008, 3, 1, dec!(2750)),
+            flow(2008, 10, 30, dec!(4250)),
+            flow(2009, 2, 15, dec!(3250)),
+            flow(2009, 4, 1, dec!(2750)),
+        ];
+        assert_eq!(calculate_xirr(&flows).unwrap().round_dp(7), dec!(0.3733625));
+    }
+
+    #[test]
+    fn test_xirr_lumpy_deposit_and_loss() {
+        // 1,000 invested, 9,000 added half a year later, 9,500 back at year end.
+        // The loss falls mostly on the late deposit: IRR is about -8.9%.
+        let flows = [
+            flow(2023, 1, 1, dec!(-1000)),
+            flow(2023, 7, 2, dec!(-9000)),
+            flow(2024, 1, 1, dec!(9500)),
+        ];
+        assert_eq!(calculate_xirr(&flows).unwrap().round_dp(4), dec!(-0.0890));
+    }
+
+    #[test]
+    fn test_xirr_near_total_loss() {
+        let flows = [flow(2023, 1, 1, dec!(-1000)), flow(2024, 1, 1, dec!(1))];
+        assert_eq!(calculate_xirr(&flows).unwrap().round_dp(4), dec!(-0.999));
+    }
+
+    #[test]
+    fn test_xirr_multi_year_near_total_loss() {
+        // 99% lost over eight years: (1 + r)^(2922 / 365) = 0.01, so r is about -43.74%
+        let flows = [flow(2015, 1, 1, dec!(-1000)), flow(2023, 1, 1, dec!(10))];
+        assert_eq!(calculate_xirr(&flows).unwrap().round_dp(4), dec!(-0.4374));
+
+        // Bisection alone must also bracket the root past the underflowing lower bound
+        let years = dec!(2922) / DAYS_PER_YEAR;
+        let bisected = solve_bisection(&[(Decimal::ZERO, dec!(-1000)), (years, dec!(10))]);
+        assert_eq!(bisected.unwrap().round_dp(4), dec!(-0.4374));
+    }
+
+    #[test]
+    fn test_xirr_requires_inflow_and_outflow() {
+        assert_eq!(calculate_xirr(&[]), None);
+        let flows = [flow(2023, 1, 1, dec!(-1000)), flow(2024, 1, 1, dec!(-100))];
+        assert_eq!(calculate_xirr(&flows), None);
+    }
+}
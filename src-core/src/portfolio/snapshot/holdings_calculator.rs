@@ -1,17 +1,86 @@
-use crate::activities::{Activity, ActivityType};
+use crate::activities::{Activity, ActivityTransferPair, ActivityType};
 use crate::assets::AssetRepositoryTrait;
 use crate::constants::CASH_ASSET_PREFIX;
 use crate::errors::{CalculatorError, Error, Result};
 use crate::fx::fx_traits::FxServiceTrait;
 use crate::portfolio::snapshot::AccountStateSnapshot;
-use crate::portfolio::snapshot::Position;
+use crate::portfolio::snapshot::{Lot, Position};
 
 use chrono::{DateTime, NaiveDate, Utc};
 use log::{debug, error, warn};
 use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 use std::sync::{Arc, RwLock};
 
+/// Lots removed by the TRANSFER_OUT leg of a paired transfer, waiting for its TRANSFER_IN leg.
+#[derive(Debug, Clone)]
+struct LotsInTransit {
+    currency: String,
+    lots: Vec<Lot>,
+}
+
+/// Hands lots over between the legs of paired transfers while snapshots are calculated.
+/// A single ledger must be shared by every account calculated for the same dates,
+/// and source accounts must be calculated before destination accounts on a given date.
+/// The TOTAL portfolio holds both legs, so it needs a ledger of its own.
+#[derive(Debug, Clone, Default)]
+pub struct TransferLedger {
+    pair_ids: HashMap<String, String>, // leg activity id -> pair id
+    source_account_ids: HashMap<String, String>, // TRANSFER_IN leg activity id -> source account id
+    in_transit: HashMap<String, LotsInTransit>, // pair id -> lots
+    internal_pair_ids: HashSet<String>, // pairs with both legs in the state being calculated
+}
+
+impl TransferLedger {
+    pub fn new(pairs: &[ActivityTransferPair]) -> Self {
+        let mut ledger = Self::default();
+        for pair in pairs {
+            ledger
+                .pair_ids
+                .insert(pair.transfer_out_activity_id.clone(), pair.id.clone());
+            ledger
+                .pair_ids
+                .insert(pair.transfer_in_activity_id.clone(), pair.id.clone());
+            ledger.source_account_ids.insert(
+                pair.transfer_in_activity_id.clone(),
+                pair.from_account_id.clone(),
+            );
+        }
+        ledger
+    }
+
+    fn pair_id(&self, activity_id: &str) -> Option<&String> {
+        self.pair_ids.get(activity_id)
+    }
+
+    /// The account the lots of a TRANSFER_IN leg come from, if the activity is one.
+    pub fn source_account_id(&self, activity_id: &str) -> Option<&str> {
+        self.source_account_ids.get(activity_id).map(String::as_str)
+    }
+
+    /// Marks the pairs whose two legs are both in `activities`. Such a transfer stays inside
+    /// the calculated state (e.g. the TOTAL portfolio), so only its fees apply.
+    fn mark_internal_pairs(&mut self, activities: &[Activity]) {
+        let mut legs_seen: HashMap<&String, usize> = HashMap::new();
+        for activity in activities {
+            if let Some(pair_id) = self.pair_ids.get(&activity.id) {
+                *legs_seen.entry(pair_id).or_insert(0) += 1;
+            }
+        }
+        self.internal_pair_ids = legs_seen
+            .into_iter()
+            .filter(|(_, count)| *count == 2)
+            .map(|(pair_id, _)| pair_id.clone())
+            .collect();
+    }
+
+    fn is_internal(&self, activity_id: &str) -> bool {
+        self.pair_id(activity_id)
+            .is_some_and(|pair_id| self.internal_pair_ids.contains(pair_id))
+    }
+}
+
 /// Calculates the holding state (positions, cash, cost basis, net deposits) based on activities.
 /// It does not calculate market values or base currency conversions related to valuation.
 #[derive(Clone)]
@@ -41,6 +110,22 @@ impl HoldingsCalculator {
         previous_snapshot: &AccountStateSnapshot,
         activities_today: &[Activity], // Assumes these are for the *target* date and already split-adjusted
         target_date: NaiveDate,
+    ) -> Result<AccountStateSnapshot> {
+        self.calculate_next_holdings_with_transfers(
+            previous_snapshot,
+            activities_today,
+            target_date,
+            &mut TransferLedger::default(),
+        )
+    }
+
+    /// Same as `calculate_next_holdings`, moving the lots of paired transfers through `transfer_ledger`.
+    pub fn calculate_next_holdings_with_transfers(
+        &self,
+        previous_snapshot: &AccountStateSnapshot,
+        activities_today: &[Activity],
+        target_date: NaiveDate,
+        transfer_ledger: &mut TransferLedger,
     ) -> Result<AccountStateSnapshot> {
         debug!(
             "Calculating holdings for account {} on date {}",
@@ -55,6 +140,7 @@ impl HoldingsCalculator {
         next_state.net_contribution_base = previous_snapshot.net_contribution_base;
 
         let account_currency = next_state.currency.clone();
+        transfer_ledger.mark_internal_pairs(activities_today);
 
         for activity in activities_today {
             if activity.activity_date.naive_utc().date() != target_date {
@@ -66,8 +152,17 @@ impl HoldingsCalculator {
                 );
                 continue;
             }
-            match self.process_single_activity(activity, &mut next_state, &account_currency) {
+            match self.process_single_activity(
+                activity,
+                &mut next_state,
+                &account_currency,
+                transfer_ledger,
+            ) {
                 Ok(_) => {} // Log success if needed
+                // The destination of a paired transfer cannot be valued without the source lots
+                Err(e @ Error::Calculation(CalculatorError::TransferLotsMissing { .. })) => {
+                    return Err(e);
+                }
                 Err(e) => {
                     // Using Error::Calculation which now directly wraps CalculatorError
                     let calc_error = CalculatorError::Calculation(format!(
@@ -135,6 +230,7 @@ impl HoldingsCalculator {
         activity: &Activity,
         state: &mut AccountStateSnapshot,
         account_currency: &str,
+        transfer_ledger: &mut TransferLedger,
     ) -> Result<()> {
         let activity_type = ActivityType::from_str(&activity.activity_type).map_err(|_| {
             CalculatorError::UnsupportedActivityType(activity.activity_type.clone())
@@ -143,12 +239,14 @@ impl HoldingsCalculator {
         let activity_currency = &activity.currency;
         let activity_date = activity.activity_date.naive_utc().date();
 
-        // Convert activity amounts needed in account currency using ACTIVITY date
-        let amount_acct = match self.fx_service.convert_currency_for_date(
+        // Convert activity amounts needed in account currency using ACTIVITY date,
+        // at the side of the rate chosen on the activity (mid when unset)
+        let amount_acct = match self.fx_service.convert_currency_for_date_side(
             self.get_activity_amount(activity),
             activity_currency,
             account_currency,
             activity_date,
+            activity.fx_rate_side.unwrap_or_default(),
         ) {
             Ok(converted) => converted,
             Err(e) => {
@@ -160,11 +258,12 @@ impl HoldingsCalculator {
             }
         };
 
-        let fee_acct = match self.fx_service.convert_currency_for_date(
+        let fee_acct = match self.fx_service.convert_currency_for_date_side(
             activity.fee,
             activity_currency,
             account_currency,
             activity_date,
+            activity.fx_rate_side.unwrap_or_default(),
         ) {
             Ok(converted) => converted,
             Err(e) => {
@@ -198,12 +297,32 @@ impl HoldingsCalculator {
             ActivityType::RemoveHolding => {
                 self.handle_remove_holding(activity, state, account_currency, fee_acct)
             }
-            ActivityType::TransferIn => {
-                self.handle_transfer_in(activity, state, account_currency, amount_acct, fee_acct)
-            }
-            ActivityType::TransferOut => {
-                self.handle_transfer_out(activity, state, account_currency, amount_acct, fee_acct)
+            ActivityType::TransferIn | ActivityType::TransferOut
+                if transfer_ledger.is_internal(&activity.id) =>
+            {
+                // Both legs stay inside this state: nothing moves and nothing is contributed
+                *state
+                    .cash_balances
+                    .entry(account_currency.to_string())
+                    .or_insert(Decimal::ZERO) -= fee_acct;
+                Ok(())
             }
+            ActivityType::TransferIn => self.handle_transfer_in(
+                activity,
+                state,
+                account_currency,
+                amount_acct,
+                fee_acct,
+                transfer_ledger,
+            ),
+            ActivityType::TransferOut => self.handle_transfer_out(
+                activity,
+                state,
+                account_currency,
+                amount_acct,
+                fee_acct,
+                transfer_ledger,
+            ),
             ActivityType::Split => Ok(()),
         }
     }
@@ -244,11 +363,12 @@ impl HoldingsCalculator {
         let _cost_basis_asset_curr = position.add_lot(activity_to_use)?;
 
         // Calculate total cost in Account Currency for cash adjustment
-        let unit_price_acct = match self.fx_service.convert_currency_for_date(
+        let unit_price_acct = match self.fx_service.convert_currency_for_date_side(
             activity.unit_price,
             activity_currency,
             account_currency,
             activity_date,
+            activity.fx_rate_side.unwrap_or_default(),
         ) {
             Ok(converted) => converted,
             Err(e) => {
@@ -280,11 +400,12 @@ impl HoldingsCalculator {
         let activity_currency = &activity.currency;
         let activity_date = activity.activity_date.naive_utc().date();
 
-        let unit_price_acct = match self.fx_service.convert_currency_for_date(
+        let unit_price_acct = match self.fx_service.convert_currency_for_date_side(
             activity.unit_price,
             activity_currency,
             account_currency,
             activity_date,
+            activity.fx_rate_side.unwrap_or_default(),
         ) {
             Ok(converted) => converted,
             Err(e) => {
@@ -446,11 +567,12 @@ impl HoldingsCalculator {
         let activity_date = activity.activity_date.naive_utc().date();
 
         // Convert the determined charge amount to account currency
-        let charge_acct = match self.fx_service.convert_currency_for_date(
+        let charge_acct = match self.fx_service.convert_currency_for_date_side(
             raw_charge,
             activity_currency,
             account_currency,
             activity_date,
+            activity.fx_rate_side.unwrap_or_default(),
         ) {
             Ok(converted) => converted,
             Err(e) => {
@@ -667,6 +789,7 @@ impl HoldingsCalculator {
         account_currency: &str,
         amount_acct: Decimal, // Already converted (if cash) using activity date
         fee_acct: Decimal,    // Already converted using activity date
+        transfer_ledger: &mut TransferLedger,
     ) -> Result<()> {
         if activity.asset_id.starts_with(CASH_ASSET_PREFIX) {
             // Cash transfer
@@ -695,33 +818,60 @@ impl HoldingsCalculator {
             state.net_contribution_base += amount_base;
         } else {
             // Asset transfer
-            let activity_currency = &activity.currency;
             let activity_date = activity.activity_date.naive_utc().date();
+            let lots_in_transit = match transfer_ledger.pair_id(&activity.id).cloned() {
+                Some(pair_id) => Some(transfer_ledger.in_transit.remove(&pair_id).ok_or(
+                    CalculatorError::TransferLotsMissing {
+                        pair_id,
+                        activity_id: activity.id.clone(),
+                    },
+                )?),
+                None => None,
+            };
 
-            let position = self.get_or_create_position_mut(
-                state,
-                &activity.asset_id,
-                activity_currency,
-                activity.activity_date,
-            )?;
-
-            // Check if currency conversion is needed and handle accordingly
-            let converted_activity;
-            let activity_to_use =
-                if position.currency.is_empty() || position.currency == activity.currency {
-                    // No conversion needed, use original activity directly
-                    activity
-                } else {
-                    // Conversion needed, convert and store in local variable
-                    converted_activity = self.convert_activity_to_position_currency(
-                        activity,
-                        position,
-                        &ActivityType::TransferIn,
+            let (cost_basis_asset_curr, activity_currency) = match lots_in_transit {
+                Some(LotsInTransit { currency, lots }) => {
+                    // Paired transfer: carry over the source lots with their original dates and costs
+                    let position = self.get_or_create_position_mut(
+                        state,
+                        &activity.asset_id,
+                        &currency,
+                        activity.activity_date,
+                    )?;
+                    let cost_basis = position.receive_lots(lots, &currency, &activity.id)?;
+                    (cost_basis, currency)
+                }
+                None => {
+                    let position = self.get_or_create_position_mut(
+                        state,
+                        &activity.asset_id,
+                        &activity.currency,
+                        activity.activity_date,
                     )?;
-                    &converted_activity
-                };
 
-            let cost_basis_asset_curr = position.add_lot(activity_to_use)?;
+                    // Check if currency conversion is needed and handle accordingly
+                    let converted_activity;
+                    let activity_to_use =
+                        if position.currency.is_empty() || position.currency == activity.currency {
+                            // No conversion needed, use original activity directly
+                            activity
+                        } else {
+                            // Conversion needed, convert and store in local variable
+                            converted_activity = self.convert_activity_to_position_currency(
+                                activity,
+                                position,
+                                &ActivityType::TransferIn,
+                            )?;
+                            &converted_activity
+                        };
+
+                    (
+                        position.add_lot(activity_to_use)?,
+                        activity.currency.clone(),
+                    )
+                }
+            };
+            let activity_currency = &activity_currency;
 
             // Adjust cash for fee (already in account currency)
             *state
@@ -776,6 +926,7 @@ impl HoldingsCalculator {
         account_currency: &str,
         amount_acct: Decimal, // Already converted (if cash) using activity date
         fee_acct: Decimal,    // Already converted using activity date
+        transfer_ledger: &mut TransferLedger,
     ) -> Result<()> {
         if activity.asset_id.starts_with(CASH_ASSET_PREFIX) {
             // Cash transfer
@@ -834,8 +985,26 @@ impl HoldingsCalculator {
                             &converted_activity
                         };
 
-                    let (_qty_reduced, cost_basis_removed) =
-                        position.reduce_lots_fifo(activity_to_use.quantity)?;
+                    let cost_basis_removed = match transfer_ledger.pair_id(&activity.id).cloned() {
+                        Some(pair_id) => {
+                            // Paired transfer: hand the removed lots over to the TRANSFER_IN leg
+                            let lots = position.take_lots_fifo(activity_to_use.quantity)?;
+                            let cost_basis: Decimal = lots.iter().map(|lot| lot.cost_basis).sum();
+                            transfer_ledger.in_transit.insert(
+                                pair_id,
+                                LotsInTransit {
+                                    currency: position.currency.clone(),
+                                    lots,
+                                },
+                            );
+                            cost_basis
+                        }
+                        None => {
+                            let (_qty_reduced, cost_basis_removed) =
+                                position.reduce_lots_fifo(activity_to_use.quantity)?;
+                            cost_basis_removed
+                        }
+                    };
                     cost_basis_removed_asset_curr_opt = Some(cost_basis_removed);
                 }
             } // Borrow ends
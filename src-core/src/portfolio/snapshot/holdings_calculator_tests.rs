@@ -1,12 +1,12 @@
 // Test cases for HoldingsCalculator will go here.
 #[cfg(test)]
 mod tests {
-    use crate::activities::{Activity, ActivityType};
+    use crate::activities::{Activity, ActivityTransferPair, ActivityType};
     use crate::assets::{Asset, AssetRepositoryTrait, NewAsset, UpdateAssetProfile};
     use crate::errors::Result;
     use crate::fx::fx_traits::FxServiceTrait;
-    use crate::fx::FxError;
-    use crate::portfolio::snapshot::holdings_calculator::HoldingsCalculator;
+    use crate::fx::{FxError, FxRateSide};
+    use crate::portfolio::snapshot::holdings_calculator::{HoldingsCalculator, TransferLedger};
     use crate::portfolio::snapshot::{AccountStateSnapshot, Lot, Position};
     use async_trait;
     use chrono::{DateTime, NaiveDate, TimeZone, Utc};
@@ -108,6 +108,7 @@ mod tests {
     #[derive(Clone)]
     struct MockFxService {
         conversion_rates: HashMap<(String, String, NaiveDate), Decimal>,
+        side_rates: HashMap<(String, String, NaiveDate, FxRateSide), Decimal>,
         fail_on_purpose: bool,
     }
 
@@ -115,6 +116,7 @@ mod tests {
         fn new() -> Self {
             MockFxService {
                 conversion_rates: HashMap::new(),
+                side_rates: HashMap::new(),
                 fail_on_purpose: false,
             }
         }
@@ -134,6 +136,18 @@ mod tests {
             }
         }
 
+        fn add_side_rate(
+            &mut self,
+            from: &str,
+            to: &str,
+            date: NaiveDate,
+            side: FxRateSide,
+            rate: Decimal,
+        ) {
+            self.side_rates
+                .insert((from.to_string(), to.to_string(), date, side), rate);
+        }
+
         #[allow(dead_code)]
         fn set_fail_on_purpose(&mut self, fail: bool) {
             self.fail_on_purpose = fail;
@@ -236,6 +250,26 @@ mod tests {
                 )))),
             }
         }
+        // Bid and ask fall back to the mid rate when no side rate was added
+        fn convert_currency_for_date_side(
+            &self,
+            amount: Decimal,
+            from_currency: &str,
+            to_currency: &str,
+            date: NaiveDate,
+            side: FxRateSide,
+        ) -> Result<Decimal> {
+            let key = (
+                from_currency.to_string(),
+                to_currency.to_string(),
+                date,
+                side,
+            );
+            match self.side_rates.get(&key) {
+                Some(rate) => Ok(amount * rate),
+                None => self.convert_currency_for_date(amount, from_currency, to_currency, date),
+            }
+        }
         fn get_latest_exchange_rates(&self) -> Result<Vec<crate::fx::fx_model::ExchangeRate>> {
             Err(crate::errors::Error::Unexpected(
                 "MockFxService::get_exchange_rates not implemented".to_string(),
@@ -296,6 +330,7 @@ mod tests {
             amount: None,
             is_draft: false,
             comment: None,
+            fx_rate_side: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
@@ -327,6 +362,7 @@ mod tests {
             amount: Some(amount),
             is_draft: false,
             comment: None,
+            fx_rate_side: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
@@ -678,6 +714,40 @@ mod tests {
         assert!(next_state.positions.is_empty()); // No positions involved
     }
 
+    #[test]
+    fn test_cash_activity_converts_at_its_fx_rate_side() {
+        let mut mock_fx_service = MockFxService::new();
+        let target_date_str = "2023-01-04";
+        let target_date = NaiveDate::from_str(target_date_str).unwrap();
+        add_usd_cad_rates(&mut mock_fx_service, target_date_str); // 1.25 mid
+        mock_fx_service.add_side_rate("USD", "CAD", target_date, FxRateSide::Bid, dec!(1.23));
+
+        let base_currency = Arc::new(RwLock::new("CAD".to_string()));
+        let calculator = create_calculator(Arc::new(mock_fx_service), base_currency);
+        let previous_snapshot = create_initial_snapshot("acc_side", "CAD", "2023-01-03");
+
+        let at_mid = create_cash_activity(
+            "act_mid",
+            ActivityType::Deposit,
+            dec!(100),
+            dec!(0),
+            "USD",
+            target_date_str,
+        );
+        let at_bid = Activity {
+            id: "act_bid".to_string(),
+            fx_rate_side: Some(FxRateSide::Bid),
+            ..at_mid.clone()
+        };
+
+        let next_state = calculator
+            .calculate_next_holdings(&previous_snapshot, &[at_mid, at_bid], target_date)
+            .unwrap();
+
+        // Unset side converts at the mid rate: 100 * 1.25 + 100 * 1.23
+        assert_eq!(next_state.cash_balances.get("CAD"), Some(&dec!(248)));
+    }
+
     #[test]
     fn test_withdrawal_activity_with_fx_conversion() {
         let mut mock_fx_service = MockFxService::new();
@@ -2104,4 +2174,128 @@ mod tests {
             "Cash should be deducted in account currency (EUR)"
         );
     }
+
+    #[test]
+    fn test_paired_transfer_moves_lots_between_accounts() {
+        let account_currency = "USD";
+        let base_currency = Arc::new(RwLock::new(account_currency.to_string()));
+        let calculator = create_calculator(Arc::new(MockFxService::new()), base_currency);
+
+        // Source account builds two lots on different dates
+        let mut source = create_initial_snapshot("acc_src", account_currency, "2023-01-01");
+        source.net_contribution = dec!(5000);
+        source.net_contribution_base = dec!(5000);
+        for (id, price, date_str) in [
+            ("buy_1", dec!(100), "2023-01-02"),
+            ("buy_2", dec!(120), "2023-01-03"),
+        ] {
+            let buy = create_default_activity(
+                id,
+                ActivityType::Buy,
+                "TESTUSD",
+                dec!(10),
+                price,
+                dec!(0),
+                account_currency,
+                date_str,
+            );
+            source = calculator
+                .calculate_next_holdings(&source, &[buy], NaiveDate::from_str(date_str).unwrap())
+                .unwrap();
+        }
+        let destination = create_initial_snapshot("acc_dst", account_currency, "2023-01-09");
+
+        let transfer_date_str = "2023-01-10";
+        let transfer_date = NaiveDate::from_str(transfer_date_str).unwrap();
+        let mut transfer_out = create_default_activity(
+            "tx_out",
+            ActivityType::TransferOut,
+            "TESTUSD",
+            dec!(15),
+            dec!(0),
+            dec!(0),
+            account_currency,
+            transfer_date_str,
+        );
+        transfer_out.account_id = "acc_src".to_string();
+        let mut transfer_in = create_default_activity(
+            "tx_in",
+            ActivityType::TransferIn,
+            "TESTUSD",
+            dec!(15),
+            dec!(999), // Ignored: the lots keep their original costs
+            dec!(0),
+            account_currency,
+            transfer_date_str,
+        );
+        transfer_in.account_id = "acc_dst".to_string();
+
+        let pairs = vec![ActivityTransferPair {
+            id: "pair_1".to_string(),
+            transfer_out_activity_id: "tx_out".to_string(),
+            transfer_in_activity_id: "tx_in".to_string(),
+            from_account_id: "acc_src".to_string(),
+            to_account_id: "acc_dst".to_string(),
+        }];
+
+        // Separate accounts: the source leg hands its lots to the destination leg
+        let mut ledger = TransferLedger::new(&pairs);
+        let source_after = calculator
+            .calculate_next_holdings_with_transfers(
+                &source,
+                &[transfer_out.clone()],
+                transfer_date,
+                &mut ledger,
+            )
+            .unwrap();
+        let destination_after = calculator
+            .calculate_next_holdings_with_transfers(
+                &destination,
+                &[transfer_in.clone()],
+                transfer_date,
+                &mut ledger,
+            )
+            .unwrap();
+
+        let source_position = source_after.positions.get("TESTUSD").unwrap();
+        assert_eq!(source_position.quantity, dec!(5));
+        assert_eq!(source_position.total_cost_basis, dec!(600));
+
+        let destination_position = destination_after.positions.get("TESTUSD").unwrap();
+        assert_eq!(destination_position.quantity, dec!(15));
+        assert_eq!(destination_position.total_cost_basis, dec!(1600)); // 10 * 100 + 5 * 120
+        assert_eq!(destination_position.lots.len(), 2);
+        assert_eq!(destination_position.lots[0].id, "buy_1");
+        assert_eq!(destination_position.lots[0].acquisition_price, dec!(100));
+        assert_eq!(destination_position.lots[1].quantity, dec!(5));
+        assert_eq!(
+            destination_position.inception_date,
+            source.positions.get("TESTUSD").unwrap().inception_date
+        );
+
+        // Contributions move between the accounts and net out across the portfolio
+        assert_eq!(source_after.net_contribution_base, dec!(3400));
+        assert_eq!(destination_after.net_contribution_base, dec!(1600));
+        assert_eq!(
+            source_after.net_contribution_base + destination_after.net_contribution_base,
+            source.net_contribution_base
+        );
+
+        // Both legs in one state (the TOTAL portfolio): nothing moves, nothing is contributed
+        let total_after = calculator
+            .calculate_next_holdings_with_transfers(
+                &source,
+                &[transfer_in, transfer_out],
+                transfer_date,
+                &mut TransferLedger::new(&pairs),
+            )
+            .unwrap();
+        let total_position = total_after.positions.get("TESTUSD").unwrap();
+        assert_eq!(total_position.quantity, dec!(20));
+        assert_eq!(total_position.total_cost_basis, dec!(2200));
+        assert_eq!(
+            total_after.net_contribution_base,
+            source.net_contribution_base
+        );
+    }
 }
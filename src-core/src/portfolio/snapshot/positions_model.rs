@@ -222,6 +222,17 @@ impl Position {
         &mut self,
         quantity_to_reduce_input: Decimal,
     ) -> Result<(Decimal, Decimal)> {
+        let removed_lots = self.take_lots_fifo(quantity_to_reduce_input)?;
+        Ok((
+            removed_lots.iter().map(|lot| lot.quantity).sum(), // Keep original precision from calculation
+            removed_lots.iter().map(|lot| lot.cost_basis).sum(), // Cost basis in asset currency
+        ))
+    }
+
+    /// Removes quantity from the position using FIFO lot relief and returns the removed lot portions.
+    /// A partially consumed lot is split: the returned portion keeps the original lot's ID,
+    /// acquisition date and price, with cost basis and fees taken proportionally.
+    pub fn take_lots_fifo(&mut self, quantity_to_reduce_input: Decimal) -> Result<Vec<Lot>> {
         if !quantity_to_reduce_input.is_sign_positive() {
             return Err(CalculatorError::InvalidActivity(
                 "Quantity to reduce must be positive".to_string(),
@@ -233,7 +244,7 @@ impl Position {
 
         if !is_quantity_significant(&available_quantity) || available_quantity <= Decimal::ZERO {
             warn!("Attempting to reduce position {} which has zero/insignificant quantity {}. Skipping reduction.", self.id, available_quantity);
-            return Ok((Decimal::ZERO, Decimal::ZERO));
+            return Ok(Vec::new());
         }
 
         let mut quantity_to_reduce = quantity_to_reduce_input;
@@ -249,76 +260,78 @@ impl Position {
         let mut vec_lots: Vec<_> = self.lots.drain(..).collect();
         vec_lots.sort_by_key(|lot| lot.acquisition_date); // Ensure FIFO order
 
-        let mut lot_indices_to_remove = Vec::new();
-        let mut lot_updates = Vec::new(); // (index, new_quantity, new_cost_basis)
-        let mut actual_quantity_reduced = Decimal::ZERO;
-        // Cost basis sum will be in the Position's currency
-        let mut cost_basis_of_sold_lots_asset_currency = Decimal::ZERO;
+        let mut removed_lots = Vec::new();
+        let mut remaining_lots = Vec::with_capacity(vec_lots.len());
 
-        // Iterate over the sorted Vec
-        for (index, lot) in vec_lots.iter().enumerate() {
-            if quantity_to_reduce <= Decimal::ZERO {
-                break;
-            }
-            if lot.quantity <= Decimal::ZERO {
-                continue; // Skip empty or negative lots (shouldn't happen with proper add/split)
+        for mut lot in vec_lots {
+            if quantity_to_reduce <= Decimal::ZERO || lot.quantity <= Decimal::ZERO {
+                // Nothing left to reduce, or an empty/negative lot (shouldn't happen with proper add/split)
+                remaining_lots.push(lot);
+                continue;
             }
 
             let qty_from_this_lot = std::cmp::min(lot.quantity, quantity_to_reduce);
 
-            // Calculate cost basis removed (in asset currency) proportionally
-            let cost_basis_removed = if lot.quantity.is_zero() {
-                Decimal::ZERO
-            } else {
-                // Proportional cost basis removal (asset currency)
-                lot.cost_basis * qty_from_this_lot / lot.quantity
-            };
-
-            actual_quantity_reduced += qty_from_this_lot;
-            cost_basis_of_sold_lots_asset_currency += cost_basis_removed;
+            // Proportional cost basis and fees removal (asset currency)
+            let cost_basis_removed = lot.cost_basis * qty_from_this_lot / lot.quantity;
+            let fees_removed = lot.acquisition_fees * qty_from_this_lot / lot.quantity;
             quantity_to_reduce -= qty_from_this_lot;
 
-            let remaining_lot_qty = lot.quantity - qty_from_this_lot;
+            removed_lots.push(Lot {
+                quantity: qty_from_this_lot,
+                cost_basis: cost_basis_removed,
+                acquisition_fees: fees_removed,
+                ..lot.clone()
+            });
 
-            if remaining_lot_qty <= Decimal::ZERO || !is_quantity_significant(&remaining_lot_qty) {
-                lot_indices_to_remove.push(index);
-            } else {
-                // Calculate remaining cost basis (asset currency)
-                let remaining_lot_basis = lot.cost_basis - cost_basis_removed;
-                lot_updates.push((index, remaining_lot_qty, remaining_lot_basis));
+            let remaining_lot_qty = lot.quantity - qty_from_this_lot;
+            if remaining_lot_qty > Decimal::ZERO && is_quantity_significant(&remaining_lot_qty) {
+                lot.quantity = remaining_lot_qty;
+                lot.cost_basis -= cost_basis_removed;
+                lot.acquisition_fees -= fees_removed;
+                remaining_lots.push(lot);
             }
         }
 
-        // Apply updates to the Vec
-        for (index, new_quantity, new_cost_basis) in lot_updates {
-            if let Some(lot) = vec_lots.get_mut(index) {
-                lot.quantity = new_quantity;
-                lot.cost_basis = new_cost_basis; // Update with asset currency value
-            } else {
-                error!(
-                    "Failed to get mutable lot at index {} for position {} during update",
-                    index, self.id
-                );
+        self.lots = remaining_lots.into();
+        self.recalculate_aggregates();
+
+        Ok(removed_lots)
+    }
+
+    /// Adds lots moved from another position, keeping their acquisition dates and costs.
+    /// Lot costs must be in the Position's currency; an empty position adopts `lots_currency`.
+    /// Returns the total cost basis received in the position's currency.
+    pub fn receive_lots(
+        &mut self,
+        lots: Vec<Lot>,
+        lots_currency: &str,
+        activity_id: &str,
+    ) -> Result<Decimal> {
+        if self.currency.is_empty() {
+            self.currency = lots_currency.to_string();
+        } else if self.currency != lots_currency {
+            return Err(CalculatorError::CurrencyMismatch {
+                position_id: self.id.clone(),
+                position_currency: self.currency.clone(),
+                activity_id: activity_id.to_string(),
+                activity_currency: lots_currency.to_string(),
             }
+            .into());
         }
 
-        // Remove marked lots from the Vec efficiently
-        let mut i = 0;
-        vec_lots.retain(|_| {
-            let keep = !lot_indices_to_remove.contains(&i);
-            i += 1;
-            keep
-        });
-
-        // Convert the final Vec back to VecDeque and assign to self.lots
+        let mut cost_basis_received = Decimal::ZERO;
+        let mut vec_lots: Vec<_> = self.lots.drain(..).collect();
+        for mut lot in lots {
+            cost_basis_received += lot.cost_basis;
+            lot.position_id = self.id.clone();
+            vec_lots.push(lot);
+        }
+        vec_lots.sort_by_key(|lot| lot.acquisition_date);
         self.lots = vec_lots.into();
 
         self.recalculate_aggregates();
-
-        Ok((
-            actual_quantity_reduced, // Keep original precision from calculation
-            cost_basis_of_sold_lots_asset_currency, // Return cost basis in asset currency
-        ))
+        Ok(cost_basis_received)
     }
 
     /// Applies stock split.
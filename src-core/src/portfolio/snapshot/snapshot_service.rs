@@ -1,11 +1,14 @@
-use super::holdings_calculator::HoldingsCalculator;
+use super::holdings_calculator::{HoldingsCalculator, TransferLedger};
 use super::snapshot_repository::SnapshotRepositoryTrait;
 use crate::accounts::{Account, AccountRepositoryTrait};
-use crate::activities::{Activity, ActivityRepositoryTrait};
+use crate::activities::{Activity, ActivityRepositoryTrait, ActivityTransferPair};
 use crate::assets::AssetRepositoryTrait;
 use crate::constants::{DECIMAL_PRECISION, PORTFOLIO_TOTAL_ACCOUNT_ID};
 use crate::errors::{CalculatorError, Error, Result};
 use crate::fx::fx_traits::FxServiceTrait;
+use crate::portfolio::checkpoint::{
+    align_rebuild_dates, TransferSpan, ValuationCheckpoint, ValuationCheckpointRepositoryTrait,
+};
 use crate::portfolio::snapshot::{AccountStateSnapshot, Lot, Position};
 use crate::utils::time_utils::get_days_between;
 
@@ -19,10 +22,11 @@ use std::sync::{Arc, RwLock};
 // --- Service Trait ---
 #[async_trait]
 pub trait SnapshotServiceTrait: Send + Sync {
-    /// Calculates **holdings** snapshots incrementally for the given account IDs, starting from the last calculated date.
+    /// Calculates **holdings** snapshots incrementally for the given account IDs, resuming each account
+    /// from its valuation checkpoint (the earliest dirty date, or the day after the last calculated date).
     /// If `account_ids` is `None`, calculates for all active accounts AND the "TOTAL" portfolio.
     /// If `account_ids` is `Some`, calculates only for the specified IDs. If "TOTAL" is included, it's calculated from all activities.
-    /// If no snapshots exist, it performs a full calculation from the first activity.
+    /// Accounts without a checkpoint are calculated in full from the first activity.
     /// Snapshots generated by this method *only contain holdings information* (quantities, costs, cash).
     /// They do NOT contain valuation (market value, base currency conversions, daily gain).
     async fn calculate_holdings_snapshots(&self, account_ids: Option<&[String]>) -> Result<usize>;
@@ -65,7 +69,8 @@ pub trait SnapshotServiceTrait: Send + Sync {
     /// Calculates and stores aggregated "TOTAL" portfolio snapshots based on individual account holdings.
     /// This should typically be run after `calculate_holdings_snapshots` has processed individual accounts.
     /// It iterates through each day from the earliest activity to the present, generating a TOTAL snapshot
-    /// by aggregating individual account snapshots for that day.
+    /// by aggregating individual account snapshots for that day. When the TOTAL checkpoint has a resume
+    /// date, only the days from that date on are regenerated.
     async fn calculate_total_portfolio_snapshots(&self) -> Result<usize>;
 }
 
@@ -77,6 +82,7 @@ pub struct SnapshotService {
     account_repository: Arc<dyn AccountRepositoryTrait>,
     activity_repository: Arc<dyn ActivityRepositoryTrait>,
     snapshot_repository: Arc<dyn SnapshotRepositoryTrait>,
+    checkpoint_repository: Arc<dyn ValuationCheckpointRepositoryTrait>,
     holdings_calculator: HoldingsCalculator,
 }
 
@@ -86,8 +92,10 @@ type ActivitiesVec = Vec<Activity>;
 type ActivitiesByAccount = HashMap<String, BTreeMap<NaiveDate, Vec<Activity>>>;
 type StartSnapshotsMap = HashMap<String, AccountStateSnapshot>;
 type StartDatesMap = HashMap<String, NaiveDate>;
+type RebuildDatesMap = HashMap<String, Option<NaiveDate>>;
 
 impl SnapshotService {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         base_currency: Arc<RwLock<String>>,
         account_repository: Arc<dyn AccountRepositoryTrait>,
@@ -95,6 +103,7 @@ impl SnapshotService {
         snapshot_repository: Arc<dyn SnapshotRepositoryTrait>,
         asset_repository: Arc<dyn AssetRepositoryTrait>,
         fx_service: Arc<dyn FxServiceTrait>,
+        checkpoint_repository: Arc<dyn ValuationCheckpointRepositoryTrait>,
     ) -> Self {
         let holdings_calculator = HoldingsCalculator::new(
             fx_service.clone(),
@@ -106,6 +115,7 @@ impl SnapshotService {
             account_repository,
             activity_repository,
             snapshot_repository,
+            checkpoint_repository,
             holdings_calculator,
         }
     }
@@ -138,8 +148,9 @@ impl SnapshotService {
             account_ids_param, force_full_calculation
         );
 
+        let transfer_pairs = self.activity_repository.get_transfer_pairs()?;
         let (accounts_to_process, all_activities, min_activity_date, calculation_end_date) =
-            self.fetch_required_data(account_ids_param)?;
+            self.fetch_required_data(account_ids_param, &transfer_pairs)?;
 
         if accounts_to_process.is_empty() {
             warn!("No accounts found to process.");
@@ -169,11 +180,25 @@ impl SnapshotService {
             calculation_end_date,
         )?;
 
+        let account_ids: Vec<String> = accounts_to_process.keys().cloned().collect();
+        let checkpoints = self.checkpoint_repository.get_checkpoints(&account_ids)?;
+        let rebuild_from_dates = if force_full_calculation {
+            HashMap::new()
+        } else {
+            Self::resolve_rebuild_dates(
+                &checkpoints,
+                &accounts_to_process,
+                &all_activities,
+                &transfer_pairs,
+            )
+        };
+
         let (start_keyframes, effective_start_dates, calculation_min_date) = self
             .determine_calculation_range_and_initial_state(
                 &accounts_to_process,
                 &activities_by_account_date,
                 &account_ids_with_activity,
+                &rebuild_from_dates,
                 force_full_calculation,
                 calculation_end_date,
             )
@@ -197,6 +222,10 @@ impl SnapshotService {
             &effective_start_dates,
             calculation_min_date,
             calculation_end_date,
+            &mut TransferLedger::new(&Self::loaded_transfer_pairs(
+                &transfer_pairs,
+                &all_activities,
+            )),
         )?;
 
         // Step 8: Persist keyframe snapshots using the new clear method
@@ -225,16 +254,118 @@ impl SnapshotService {
             }
         }
 
+        // Step 9: Advance the checkpoints and invalidate what was derived from the rewritten range
+        let mut total_dirty_from: Option<NaiveDate> = None;
+        for acc_id in accounts_needing_calculation.keys() {
+            let start = *effective_start_dates.get(acc_id).unwrap();
+            let consumed_dirty_version = checkpoints
+                .get(acc_id)
+                .map_or(0, |checkpoint| checkpoint.holdings_dirty_version);
+            self.checkpoint_repository
+                .complete_holdings(acc_id, consumed_dirty_version, calculation_end_date)
+                .await?;
+            self.checkpoint_repository
+                .mark_valuation_dirty(std::slice::from_ref(acc_id), start)
+                .await?;
+            if acc_id != PORTFOLIO_TOTAL_ACCOUNT_ID {
+                total_dirty_from = Some(total_dirty_from.map_or(start, |date| date.min(start)));
+            }
+        }
+        if let Some(date) = total_dirty_from {
+            self.checkpoint_repository
+                .mark_holdings_dirty(&[PORTFOLIO_TOTAL_ACCOUNT_ID.to_string()], date)
+                .await?;
+        }
+
         Ok(keyframes_to_save.len())
     }
 
+    // --- Step 5b: Resolve the date each account resumes from ---
+    // `None` means a rebuild from the first activity: the account has no completed checkpoint,
+    // or a split being (re)calculated rescales quantities already stored in earlier keyframes.
+    // Both legs of a paired transfer resume together so the lots can be handed over.
+    fn resolve_rebuild_dates(
+        checkpoints: &HashMap<String, ValuationCheckpoint>,
+        accounts_to_process: &AccountsMap,
+        all_activities: &[Activity],
+        transfer_pairs: &[ActivityTransferPair],
+    ) -> RebuildDatesMap {
+        use crate::activities::activities_constants::ACTIVITY_TYPE_SPLIT;
+
+        let mut rebuild_dates: RebuildDatesMap = accounts_to_process
+            .keys()
+            .map(|id| {
+                let rebuild_from = checkpoints
+                    .get(id)
+                    .and_then(|checkpoint| checkpoint.holdings_rebuild_from());
+                (id.clone(), rebuild_from)
+            })
+            .collect();
+
+        for split in all_activities
+            .iter()
+            .filter(|a| a.activity_type == ACTIVITY_TYPE_SPLIT)
+        {
+            let split_date = split.activity_date.naive_utc().date();
+            let recalculated = rebuild_dates
+                .get(&split.account_id)
+                .is_some_and(|date| date.is_none_or(|d| d <= split_date));
+            if !recalculated {
+                continue;
+            }
+            for activity in all_activities
+                .iter()
+                .filter(|a| a.asset_id == split.asset_id)
+            {
+                if let Some(date) = rebuild_dates.get_mut(&activity.account_id) {
+                    *date = None;
+                }
+            }
+            if let Some(date) = rebuild_dates.get_mut(PORTFOLIO_TOTAL_ACCOUNT_ID) {
+                *date = None;
+            }
+        }
+
+        let activity_dates: HashMap<&str, NaiveDate> = all_activities
+            .iter()
+            .map(|a| (a.id.as_str(), a.activity_date.naive_utc().date()))
+            .collect();
+        let mut transfers: Vec<TransferSpan> = Vec::new();
+        for pair in transfer_pairs {
+            let (Some(&out_date), Some(&in_date)) = (
+                activity_dates.get(pair.transfer_out_activity_id.as_str()),
+                activity_dates.get(pair.transfer_in_activity_id.as_str()),
+            ) else {
+                continue;
+            };
+            transfers.push(TransferSpan {
+                from_account_id: &pair.from_account_id,
+                to_account_id: &pair.to_account_id,
+                out_date,
+                in_date,
+            });
+            // TOTAL holds both legs itself
+            transfers.push(TransferSpan {
+                from_account_id: PORTFOLIO_TOTAL_ACCOUNT_ID,
+                to_account_id: PORTFOLIO_TOTAL_ACCOUNT_ID,
+                out_date,
+                in_date,
+            });
+        }
+        align_rebuild_dates(&mut rebuild_dates, transfers.iter().copied());
+
+        rebuild_dates
+    }
+
     // --- Step 1-3: Fetch required data ---
     // Fetches accounts based on `account_ids_param`. If `account_ids_param` is None or contains "TOTAL",
     // fetches ALL active accounts and creates the virtual TOTAL account.
     // Fetches activities ONLY for the relevant accounts (specified or all).
+    // Accounts on the other side of a paired transfer are pulled in so lots can move between them.
     fn fetch_required_data(
         &self,
         account_ids_param: Option<&[String]>,
+        transfer_pairs: &[ActivityTransferPair],
     ) -> Result<(AccountsMap, ActivitiesVec, NaiveDate, NaiveDate)> {
         // ── ❶ decide if the caller explicitly asked for the virtual TOTAL ─────────────
         let calculate_total = account_ids_param
@@ -267,6 +398,36 @@ impl SnapshotService {
             }
         }
 
+        // ── ❷b follow paired transfers to their counterpart accounts ─────────────────
+        if account_ids_param.is_some() {
+            let mut pending: Vec<String> = accounts_to_process.keys().cloned().collect();
+            while let Some(acc_id) = pending.pop() {
+                for pair in transfer_pairs {
+                    let counterpart_id = if pair.from_account_id == acc_id {
+                        &pair.to_account_id
+                    } else if pair.to_account_id == acc_id {
+                        &pair.from_account_id
+                    } else {
+                        continue;
+                    };
+                    if accounts_to_process.contains_key(counterpart_id) {
+                        continue;
+                    }
+                    if let Ok(acc) = self.account_repository.get_by_id(counterpart_id) {
+                        if acc.is_active {
+                            debug!(
+                                "Including account {} as counterpart of paired transfer {}",
+                                acc.id, pair.id
+                            );
+                            account_ids_to_fetch_activities.push(acc.id.clone());
+                            pending.push(acc.id.clone());
+                            accounts_to_process.insert(acc.id.clone(), acc);
+                        }
+                    }
+                }
+            }
+        }
+
         // ── ❸ add the virtual TOTAL *only* if explicitly requested ───────────────────
         if calculate_total {
             accounts_to_process.insert(
@@ -369,6 +530,7 @@ impl SnapshotService {
         accounts_to_process: &AccountsMap, // Includes virtual TOTAL if needed
         activities_by_account_date: &ActivitiesByAccount, // Includes TOTAL key if needed
         account_ids_with_activity: &HashSet<String>, // Accounts that actually have activities
+        rebuild_from_dates: &RebuildDatesMap, // Checkpoint resume dates (empty when forcing full)
         force_full_calculation: bool,
         calculation_end_date: NaiveDate,
     ) -> Result<(StartSnapshotsMap, StartDatesMap, NaiveDate)> {
@@ -401,6 +563,32 @@ impl SnapshotService {
                     "Force full calculation: Setting effective_start_date for account {} to {}. Deletion handled by overwrite methods.",
                     acc_id, effective_start_date
                 );
+            } else if let Some(&Some(rebuild_from)) = rebuild_from_dates.get(acc_id) {
+                if rebuild_from > calculation_end_date {
+                    debug!(
+                        "Account {} is up to date (checkpoint resumes at {}).",
+                        acc_id, rebuild_from
+                    );
+                    continue;
+                }
+                let day_before = rebuild_from.pred_opt().unwrap_or(rebuild_from);
+                match self
+                    .snapshot_repository
+                    .get_latest_snapshot_before_date(acc_id, day_before)?
+                {
+                    Some(keyframe) if keyframe.snapshot_date < rebuild_from => {
+                        debug!(
+                            "Resuming account {} at {} from keyframe {}.",
+                            acc_id, rebuild_from, keyframe.snapshot_date
+                        );
+                        initial_snapshot_for_acc = Some(keyframe);
+                        effective_start_date = rebuild_from;
+                    }
+                    _ => {
+                        effective_start_date =
+                            min_activity_date_for_account.unwrap_or(calculation_end_date);
+                    }
+                }
             } else {
                 if let Some(latest_snapshot) = self
                     .snapshot_repository
@@ -481,8 +669,85 @@ impl SnapshotService {
         ))
     }
 
+    // --- Step 6b: Paired transfers whose lots can be handed over ---
+    // Only pairs with both legs loaded move lots; a leg whose counterpart belongs to an
+    // inactive account is calculated like an unpaired transfer.
+    fn loaded_transfer_pairs(
+        transfer_pairs: &[ActivityTransferPair],
+        all_activities: &[Activity],
+    ) -> Vec<ActivityTransferPair> {
+        let loaded_ids: HashSet<&str> = all_activities.iter().map(|a| a.id.as_str()).collect();
+        transfer_pairs
+            .iter()
+            .filter(|pair| {
+                loaded_ids.contains(pair.transfer_out_activity_id.as_str())
+                    && loaded_ids.contains(pair.transfer_in_activity_id.as_str())
+            })
+            .cloned()
+            .collect()
+    }
+
+    // --- Step 7a: Order the accounts calculated on a date ---
+    // The source of every paired transfer received on `date` goes before its destination,
+    // following chains such as A -> B -> C on the same day. Ties keep the account id order.
+    fn order_by_transfers<'a>(
+        accounts: Vec<(&'a String, &'a Account)>,
+        activities_by_account_date: &ActivitiesByAccount,
+        date: NaiveDate,
+        transfer_ledger: &TransferLedger,
+    ) -> Result<Vec<(&'a String, &'a Account)>> {
+        let account_ids: HashSet<&str> = accounts.iter().map(|(id, _)| id.as_str()).collect();
+        let mut sources: HashMap<&str, HashSet<&str>> = HashMap::new();
+        for (account_id, _) in &accounts {
+            if account_id.as_str() == PORTFOLIO_TOTAL_ACCOUNT_ID {
+                continue; // TOTAL keeps its own ledger
+            }
+            let activities_today = activities_by_account_date
+                .get(*account_id)
+                .and_then(|date_map| date_map.get(&date));
+            for activity in activities_today.into_iter().flatten() {
+                if let Some(source_id) = transfer_ledger.source_account_id(&activity.id) {
+                    if source_id != account_id.as_str() {
+                        if let Some(source_id) = account_ids.get(source_id) {
+                            sources
+                                .entry(account_id.as_str())
+                                .or_default()
+                                .insert(source_id);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut remaining = accounts;
+        remaining.sort_by_key(|(account_id, _)| *account_id);
+        let mut ordered = Vec::with_capacity(remaining.len());
+        let mut calculated: HashSet<&str> = HashSet::new();
+        while !remaining.is_empty() {
+            let (ready, blocked): (Vec<_>, Vec<_>) =
+                remaining.into_iter().partition(|(account_id, _)| {
+                    sources
+                        .get(account_id.as_str())
+                        .is_none_or(|ids| ids.iter().all(|id| calculated.contains(id)))
+                });
+            if ready.is_empty() {
+                let blocked_ids: Vec<&str> = blocked.iter().map(|(id, _)| id.as_str()).collect();
+                return Err(Error::Calculation(CalculatorError::Calculation(format!(
+                    "Paired transfers on {} form a cycle between accounts {}. Move one of the transfers to another date.",
+                    date,
+                    blocked_ids.join(", ")
+                ))));
+            }
+            calculated.extend(ready.iter().map(|(id, _)| id.as_str()));
+            ordered.extend(ready);
+            remaining = blocked;
+        }
+        Ok(ordered)
+    }
+
     // --- Step 7: Calculate daily holdings snapshots (in memory) and identify keyframes ---
     // Iterates through dates and calculates holdings for each account needing processing (incl. TOTAL).
+    #[allow(clippy::too_many_arguments)]
     fn calculate_daily_holdings_snapshots(
         &self,
         accounts_needing_calculation: &AccountsMap, // Actual accounts to process
@@ -491,6 +756,7 @@ impl SnapshotService {
         effective_start_dates: &StartDatesMap, // Start dates for accounts needing calculation
         calculation_min_date: NaiveDate,
         calculation_end_date: NaiveDate,
+        transfer_ledger: &mut TransferLedger, // Carries lots between the legs of paired transfers
     ) -> Result<(
         HashMap<String, AccountStateSnapshot>, // Final states
         Vec<AccountStateSnapshot>,             // Keyframes to save
@@ -498,6 +764,8 @@ impl SnapshotService {
         let mut current_holdings_snapshots = start_keyframes.clone();
         let mut keyframes_to_save: Vec<AccountStateSnapshot> = Vec::new();
         let date_range = get_days_between(calculation_min_date, calculation_end_date);
+        // TOTAL sees both legs of every transfer, so it keeps its lots in transit apart
+        let mut total_transfer_ledger = transfer_ledger.clone();
 
         for current_date in date_range {
             // Process only accounts whose effective start date is today or earlier
@@ -506,9 +774,15 @@ impl SnapshotService {
                 .filter(|(id, _)| {
                     effective_start_dates
                         .get(*id)
-                        .map_or(false, |start_date| *start_date <= current_date)
+                        .is_some_and(|start_date| *start_date <= current_date)
                 })
                 .collect();
+            let accounts_to_process_today = Self::order_by_transfers(
+                accounts_to_process_today,
+                activities_by_account_date,
+                current_date,
+                transfer_ledger,
+            )?;
 
             if accounts_to_process_today.is_empty() {
                 // This shouldn't happen if calculation_min_date was determined correctly, but handle defensively.
@@ -556,10 +830,16 @@ impl SnapshotService {
                     current_holdings_snapshot = carried_forward_state;
                 } else {
                     // Activities occurred, call the calculator
-                    match self.holdings_calculator.calculate_next_holdings(
+                    let ledger = if account_id == PORTFOLIO_TOTAL_ACCOUNT_ID {
+                        &mut total_transfer_ledger
+                    } else {
+                        &mut *transfer_ledger
+                    };
+                    match self.holdings_calculator.calculate_next_holdings_with_transfers(
                         previous_holdings_snapshot,
                         &activities_today, // Pass the already fetched activities
                         current_date,
+                        ledger,
                     ) {
                         Ok(calculated_snapshot) => {
                             // Calculator provides the new state, including updated calculated_at
@@ -569,6 +849,13 @@ impl SnapshotService {
                                 account_id, current_date
                             );
                         }
+                        Err(e @ Error::Calculation(CalculatorError::TransferLotsMissing { .. })) => {
+                            error!(
+                                "Holdings calculation failed for account {} on {}: {}",
+                                account_id, current_date, e
+                            );
+                            return Err(e);
+                        }
                         Err(e) => {
                             error!(
                                 "Holdings calculation failed for account {} on {}: {}. Carrying forward previous state.",
@@ -881,9 +1168,41 @@ impl SnapshotService {
             return Ok(0);
         }
 
-        let all_individual_keyframes = self
-            .snapshot_repository
-            .get_all_active_account_snapshots(None, None)?;
+        let today = Utc::now().naive_utc().date();
+        let total_checkpoint = self
+            .checkpoint_repository
+            .get_checkpoint(PORTFOLIO_TOTAL_ACCOUNT_ID)?;
+        let consumed_dirty_version = total_checkpoint
+            .as_ref()
+            .map_or(0, |checkpoint| checkpoint.holdings_dirty_version);
+        let resume_from = total_checkpoint
+            .as_ref()
+            .and_then(ValuationCheckpoint::holdings_rebuild_from);
+
+        if resume_from.is_some_and(|date| date > today) {
+            debug!("TOTAL portfolio snapshots are up to date.");
+            return Ok(0);
+        }
+
+        // When resuming, accounts start from their last keyframe before the resume date
+        let mut all_individual_keyframes: Vec<AccountStateSnapshot> = match resume_from {
+            Some(date) => {
+                let account_ids: Vec<String> =
+                    active_accounts.iter().map(|acc| acc.id.clone()).collect();
+                self.snapshot_repository
+                    .get_latest_snapshots_before_date(
+                        &account_ids,
+                        date.pred_opt().unwrap_or(date),
+                    )?
+                    .into_values()
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        all_individual_keyframes.extend(
+            self.snapshot_repository
+                .get_all_active_account_snapshots(resume_from, None)?,
+        );
 
         if all_individual_keyframes.is_empty() {
             warn!("No keyframes found for any active individual accounts. Cannot generate TOTAL snapshots.");
@@ -902,7 +1221,10 @@ impl SnapshotService {
             if keyframe.account_id == PORTFOLIO_TOTAL_ACCOUNT_ID {
                 continue;
             }
-            all_snapshot_dates.insert(keyframe.snapshot_date);
+            // Carried-in keyframes only seed the state on the resume date
+            let target_date = resume_from
+                .map_or(keyframe.snapshot_date, |date| date.max(keyframe.snapshot_date));
+            all_snapshot_dates.insert(target_date);
             keyframes_by_account
                 .entry(keyframe.account_id.clone())
                 .or_default()
@@ -955,11 +1277,32 @@ impl SnapshotService {
                 "Saving {} new TOTAL portfolio snapshots.",
                 total_portfolio_snapshots_to_save.len()
             );
-            self.snapshot_repository
-                .overwrite_all_snapshots_for_account(
-                    PORTFOLIO_TOTAL_ACCOUNT_ID,
-                    &total_portfolio_snapshots_to_save,
-                )
+            match resume_from {
+                Some(date) => {
+                    self.snapshot_repository
+                        .overwrite_snapshots_for_account_in_range(
+                            PORTFOLIO_TOTAL_ACCOUNT_ID,
+                            date,
+                            today,
+                            &total_portfolio_snapshots_to_save,
+                        )
+                        .await?
+                }
+                None => {
+                    self.snapshot_repository
+                        .overwrite_all_snapshots_for_account(
+                            PORTFOLIO_TOTAL_ACCOUNT_ID,
+                            &total_portfolio_snapshots_to_save,
+                        )
+                        .await?
+                }
+            }
+            let rebuilt_from = total_portfolio_snapshots_to_save[0].snapshot_date;
+            self.checkpoint_repository
+                .complete_holdings(PORTFOLIO_TOTAL_ACCOUNT_ID, consumed_dirty_version, today)
+                .await?;
+            self.checkpoint_repository
+                .mark_valuation_dirty(&[PORTFOLIO_TOTAL_ACCOUNT_ID.to_string()], rebuilt_from)
                 .await?;
             Ok(total_portfolio_snapshots_to_save.len())
         } else {
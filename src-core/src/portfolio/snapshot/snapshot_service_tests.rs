@@ -10,14 +10,17 @@ mod tests {
     use crate::accounts::{Account, AccountRepositoryTrait, AccountUpdate, NewAccount};
     use crate::activities::{
         activities_model::IncomeData as ActivityIncomeData, Activity, ActivityRepositoryTrait,
-        ActivitySearchResponse, ActivityUpdate, ImportMapping as ActivityImportMapping,
+        ActivitySearchFilters, ActivityTransferPair, ActivitySearchResponse, ActivityUpdate, ImportMapping as ActivityImportMapping,
         NewActivity, Sort as ActivitySort,
     };
     use crate::assets::{Asset, AssetRepositoryTrait, NewAsset, UpdateAssetProfile};
     use crate::constants::{DECIMAL_PRECISION, PORTFOLIO_TOTAL_ACCOUNT_ID};
-    use crate::errors::{Error, Result as AppResult};
+    use crate::errors::{CalculatorError, Error, Result as AppResult};
     use crate::fx::fx_model::{ExchangeRate, NewExchangeRate};
     use crate::fx::fx_traits::FxServiceTrait;
+    use crate::portfolio::checkpoint::{
+        earliest_dirty_date, ValuationCheckpoint, ValuationCheckpointRepositoryTrait,
+    };
     use crate::portfolio::snapshot::{
         snapshot_repository::SnapshotRepositoryTrait, AccountStateSnapshot, Lot, Position,
         SnapshotService, SnapshotServiceTrait,
@@ -342,6 +345,9 @@ mod tests {
         ) -> AppResult<Vec<Activity>> {
             Ok(Vec::new())
         }
+        fn get_activities_by_tag(&self, _tag_id: &str) -> AppResult<Vec<Activity>> {
+            unimplemented!()
+        }
         fn get_trading_activities(&self) -> AppResult<Vec<Activity>> {
             unimplemented!()
         }
@@ -353,16 +359,14 @@ mod tests {
             _account_ids: &[String],
             _start_date: NaiveDateTime,
             _end_date: NaiveDateTime,
-        ) -> AppResult<Vec<(String, Decimal, Decimal, String, Option<Decimal>)>> {
+        ) -> AppResult<Vec<Activity>> {
             unimplemented!()
         }
         fn search_activities(
             &self,
             _page: i64,
             _page_size: i64,
-            _account_id_filter: Option<Vec<String>>,
-            _activity_type_filter: Option<Vec<String>>,
-            _asset_id_keyword: Option<String>,
+            _filters: ActivitySearchFilters,
             _sort: Option<ActivitySort>,
         ) -> AppResult<ActivitySearchResponse> {
             unimplemented!()
@@ -384,6 +388,34 @@ mod tests {
         ) -> AppResult<crate::activities::ActivityBulkMutationResult> {
             unimplemented!()
         }
+        async fn create_paired_transfer(
+            &self,
+            _transfer_out: NewActivity,
+            _transfer_in: NewActivity,
+        ) -> AppResult<crate::activities::PairedTransfer> {
+            unimplemented!()
+        }
+        fn get_transfer_pairs(&self) -> AppResult<Vec<crate::activities::ActivityTransferPair>> {
+            Ok(Vec::new())
+        }
+        fn get_activity_changes(
+            &self,
+            _activity_id: &str,
+        ) -> AppResult<Vec<crate::activities::ActivityChange>> {
+            unimplemented!()
+        }
+        fn get_activity_changes_by_batch(
+            &self,
+            _batch_id: &str,
+        ) -> AppResult<Vec<crate::activities::ActivityChange>> {
+            unimplemented!()
+        }
+        async fn revert_activity_changes(
+            &self,
+            _change_ids: Vec<String>,
+        ) -> AppResult<crate::activities::ActivityBulkMutationResult> {
+            unimplemented!()
+        }
         async fn create_activities(&self, _activities: Vec<NewActivity>) -> AppResult<usize> {
             unimplemented!()
         }
@@ -405,7 +437,10 @@ mod tests {
         fn calculate_average_cost(&self, _account_id: &str, _asset_id: &str) -> AppResult<Decimal> {
             unimplemented!()
         }
-        fn get_income_activities_data(&self) -> AppResult<Vec<ActivityIncomeData>> {
+        fn get_income_activities_data(
+            &self,
+            _tag_id: Option<&str>,
+        ) -> AppResult<Vec<ActivityIncomeData>> {
             unimplemented!()
         }
         fn get_first_activity_date_overall(&self) -> AppResult<DateTime<Utc>> {
@@ -416,10 +451,18 @@ mod tests {
     #[derive(Clone, Debug)]
     struct MockActivityRepositoryWithData {
         activities: Vec<Activity>,
+        transfer_pairs: Vec<ActivityTransferPair>,
     }
     impl MockActivityRepositoryWithData {
         fn new(activities: Vec<Activity>) -> Self {
-            Self { activities }
+            Self {
+                activities,
+                transfer_pairs: Vec::new(),
+            }
+        }
+        fn with_transfer_pairs(mut self, transfer_pairs: Vec<ActivityTransferPair>) -> Self {
+            self.transfer_pairs = transfer_pairs;
+            self
         }
     }
     #[async_trait]
@@ -453,6 +496,9 @@ mod tests {
                 .filter(|a| account_ids.contains(&a.account_id))
                 .collect())
         }
+        fn get_activities_by_tag(&self, _tag_id: &str) -> AppResult<Vec<Activity>> {
+            unimplemented!()
+        }
         fn get_trading_activities(&self) -> AppResult<Vec<Activity>> {
             unimplemented!()
         }
@@ -464,16 +510,14 @@ mod tests {
             _ids: &[String],
             _s: NaiveDateTime,
             _e: NaiveDateTime,
-        ) -> AppResult<Vec<(String, Decimal, Decimal, String, Option<Decimal>)>> {
+        ) -> AppResult<Vec<Activity>> {
             unimplemented!()
         }
         fn search_activities(
             &self,
             _page: i64,
             _size: i64,
-            _acc: Option<Vec<String>>,
-            _typ: Option<Vec<String>>,
-            _kw: Option<String>,
+            _filters: ActivitySearchFilters,
             _sort: Option<ActivitySort>,
         ) -> AppResult<ActivitySearchResponse> {
             unimplemented!()
@@ -495,6 +539,34 @@ mod tests {
         ) -> AppResult<crate::activities::ActivityBulkMutationResult> {
             unimplemented!()
         }
+        async fn create_paired_transfer(
+            &self,
+            _transfer_out: NewActivity,
+            _transfer_in: NewActivity,
+        ) -> AppResult<crate::activities::PairedTransfer> {
+            unimplemented!()
+        }
+        fn get_transfer_pairs(&self) -> AppResult<Vec<ActivityTransferPair>> {
+            Ok(self.transfer_pairs.clone())
+        }
+        fn get_activity_changes(
+            &self,
+            _activity_id: &str,
+        ) -> AppResult<Vec<crate::activities::ActivityChange>> {
+            unimplemented!()
+        }
+        fn get_activity_changes_by_batch(
+            &self,
+            _batch_id: &str,
+        ) -> AppResult<Vec<crate::activities::ActivityChange>> {
+            unimplemented!()
+        }
+        async fn revert_activity_changes(
+            &self,
+            _change_ids: Vec<String>,
+        ) -> AppResult<crate::activities::ActivityBulkMutationResult> {
+            unimplemented!()
+        }
         async fn create_activities(&self, _a: Vec<NewActivity>) -> AppResult<usize> {
             unimplemented!()
         }
@@ -513,7 +585,10 @@ mod tests {
         fn calculate_average_cost(&self, _acc: &str, _asset: &str) -> AppResult<Decimal> {
             unimplemented!()
         }
-        fn get_income_activities_data(&self) -> AppResult<Vec<ActivityIncomeData>> {
+        fn get_income_activities_data(
+            &self,
+            _tag_id: Option<&str>,
+        ) -> AppResult<Vec<ActivityIncomeData>> {
             unimplemented!()
         }
         fn get_first_activity_date_overall(&self) -> AppResult<DateTime<Utc>> {
@@ -748,6 +823,101 @@ mod tests {
         }
     }
 
+    #[derive(Clone, Debug, Default)]
+    struct MockCheckpointRepository {
+        checkpoints: Arc<RwLock<HashMap<String, ValuationCheckpoint>>>,
+    }
+
+    impl MockCheckpointRepository {
+        fn new() -> Self {
+            Self::default()
+        }
+
+        fn update(&self, account_id: &str, update: impl Fn(&mut ValuationCheckpoint)) {
+            let mut store = self.checkpoints.write().unwrap();
+            let checkpoint = store
+                .entry(account_id.to_string())
+                .or_insert_with(|| ValuationCheckpoint::new(account_id));
+            update(checkpoint);
+        }
+    }
+
+    #[async_trait]
+    impl ValuationCheckpointRepositoryTrait for MockCheckpointRepository {
+        fn get_checkpoint(&self, account_id: &str) -> AppResult<Option<ValuationCheckpoint>> {
+            Ok(self.checkpoints.read().unwrap().get(account_id).cloned())
+        }
+
+        fn get_checkpoints(
+            &self,
+            account_ids: &[String],
+        ) -> AppResult<HashMap<String, ValuationCheckpoint>> {
+            let store = self.checkpoints.read().unwrap();
+            Ok(account_ids
+                .iter()
+                .filter_map(|id| store.get(id).map(|c| (id.clone(), c.clone())))
+                .collect())
+        }
+
+        async fn mark_holdings_dirty(
+            &self,
+            account_ids: &[String],
+            from: NaiveDate,
+        ) -> AppResult<()> {
+            for id in account_ids {
+                self.update(id, |c| {
+                    c.holdings_dirty_from = Some(earliest_dirty_date(c.holdings_dirty_from, from));
+                    c.holdings_dirty_version += 1;
+                });
+            }
+            Ok(())
+        }
+
+        async fn mark_valuation_dirty(
+            &self,
+            account_ids: &[String],
+            from: NaiveDate,
+        ) -> AppResult<()> {
+            for id in account_ids {
+                self.update(id, |c| {
+                    c.valuation_dirty_from = Some(earliest_dirty_date(c.valuation_dirty_from, from));
+                    c.valuation_dirty_version += 1;
+                });
+            }
+            Ok(())
+        }
+
+        async fn complete_holdings(
+            &self,
+            account_id: &str,
+            consumed_dirty_version: i64,
+            calculated_through: NaiveDate,
+        ) -> AppResult<()> {
+            self.update(account_id, |c| {
+                if c.holdings_dirty_version == consumed_dirty_version {
+                    c.holdings_dirty_from = None;
+                }
+                c.holdings_calculated_through = Some(calculated_through);
+            });
+            Ok(())
+        }
+
+        async fn complete_valuation(
+            &self,
+            account_id: &str,
+            consumed_dirty_version: i64,
+            calculated_through: NaiveDate,
+        ) -> AppResult<()> {
+            self.update(account_id, |c| {
+                if c.valuation_dirty_version == consumed_dirty_version {
+                    c.valuation_dirty_from = None;
+                }
+                c.valuation_calculated_through = Some(calculated_through);
+            });
+            Ok(())
+        }
+    }
+
     fn create_test_account(id: &str, currency: &str, name: &str) -> Account {
         Account {
             id: id.to_string(),
@@ -868,6 +1038,7 @@ mod tests {
             mock_snapshot_repo_arc.clone(),
             mock_asset_repo,
             mock_fx_service_arc.clone(),
+            Arc::new(MockCheckpointRepository::new()),
         );
 
         // Call the public method under test
@@ -1066,6 +1237,7 @@ mod tests {
             mock_snapshot_repo_arc.clone(),
             mock_asset_repo,
             mock_fx_service_arc.clone(),
+            Arc::new(MockCheckpointRepository::new()),
         );
 
         let result = snapshot_service.calculate_total_portfolio_snapshots().await;
@@ -1126,6 +1298,7 @@ mod tests {
             amount: Some(dec!(5000)),
             is_draft: false,
             comment: None,
+            fx_rate_side: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -1151,6 +1324,7 @@ mod tests {
             snapshot_repo.clone(),
             asset_repo,
             fx.clone(),
+            Arc::new(MockCheckpointRepository::new()),
         );
 
         // should insert keyframes without error
@@ -1186,6 +1360,7 @@ mod tests {
             amount: Some(amt),
             is_draft: false,
             comment: None,
+            fx_rate_side: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -1209,6 +1384,7 @@ mod tests {
             snaps.clone(),
             asset_repo,
             fx,
+            Arc::new(MockCheckpointRepository::new()),
         );
 
         // should compile & run without type errors and save ≥ 1 frame
@@ -1240,4 +1416,203 @@ mod tests {
         assert_eq!(second_frame.net_contribution, dec!(15000), "Second keyframe should reflect both deposits, ignoring the dividend for net contribution calculation.");
         assert_eq!(second_frame.snapshot_date, d2);
     }
+
+    #[tokio::test]
+    async fn test_calculate_holdings_snapshots_resumes_from_dirty_date() {
+        let base = Arc::new(RwLock::new("CAD".to_string()));
+
+        let mut account_repo = MockAccountRepository::new();
+        let acc = create_test_account("acc1", "CAD", "Resumable");
+        account_repo.add_account(acc.clone());
+
+        let d1 = NaiveDate::from_ymd_opt(2025, 5, 8).unwrap();
+        let d2 = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let deposit = |id: &str, date: NaiveDate, amt| Activity {
+            id: id.into(),
+            account_id: acc.id.clone(),
+            asset_id: "$CASH-CAD".into(),
+            activity_type: "DEPOSIT".into(),
+            activity_date: DateTime::from_naive_utc_and_offset(
+                date.and_hms_opt(0, 0, 0).unwrap(),
+                Utc,
+            ),
+            quantity: Decimal::ZERO,
+            unit_price: Decimal::ZERO,
+            currency: "CAD".into(),
+            fee: Decimal::ZERO,
+            amount: Some(amt),
+            is_draft: false,
+            comment: None,
+            fx_rate_side: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let act_repo = Arc::new(MockActivityRepositoryWithData::new(vec![
+            deposit("dep1", d1, dec!(5000)),
+            deposit("dep2", d2, dec!(10000)),
+        ]));
+
+        let snaps = Arc::new(MockSnapshotRepository::new());
+        let checkpoints = Arc::new(MockCheckpointRepository::new());
+        let svc = SnapshotService::new(
+            base,
+            Arc::new(account_repo),
+            act_repo,
+            snaps.clone(),
+            Arc::new(MockAssetRepository::new()),
+            Arc::new(MockFxService::new()),
+            checkpoints.clone(),
+        );
+
+        // Without a checkpoint the whole history is calculated
+        svc.calculate_holdings_snapshots(None).await.unwrap();
+        assert_eq!(snaps.get_saved_snapshots().len(), 2);
+        let checkpoint = checkpoints.get_checkpoint("acc1").unwrap().unwrap();
+        assert!(checkpoint.holdings_calculated_through.is_some());
+        assert_eq!(checkpoint.valuation_dirty_from, Some(d1));
+
+        // An edit on d2 only recalculates from d2, seeded by the stored d1 keyframe
+        checkpoints
+            .mark_holdings_dirty(&["acc1".to_string()], d2)
+            .await
+            .unwrap();
+        svc.calculate_holdings_snapshots(None).await.unwrap();
+
+        let frames = snaps.get_saved_snapshots();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].snapshot_date, d2);
+        assert_eq!(frames[0].net_contribution, dec!(15000));
+
+        let checkpoint = checkpoints.get_checkpoint("acc1").unwrap().unwrap();
+        assert_eq!(checkpoint.holdings_dirty_from, None);
+        assert_eq!(checkpoint.valuation_dirty_from, Some(d1));
+        let total = checkpoints
+            .get_checkpoint(PORTFOLIO_TOTAL_ACCOUNT_ID)
+            .unwrap()
+            .unwrap();
+        assert_eq!(total.holdings_dirty_from, Some(d1));
+    }
+
+    fn transfer_test_activity(
+        id: &str,
+        account_id: &str,
+        activity_type: &str,
+        date: NaiveDate,
+        unit_price: Decimal,
+    ) -> Activity {
+        Activity {
+            id: id.into(),
+            account_id: account_id.into(),
+            asset_id: "AAPL".into(),
+            activity_type: activity_type.into(),
+            activity_date: DateTime::from_naive_utc_and_offset(
+                date.and_hms_opt(0, 0, 0).unwrap(),
+                Utc,
+            ),
+            quantity: dec!(10),
+            unit_price,
+            currency: "USD".into(),
+            fee: Decimal::ZERO,
+            amount: None,
+            is_draft: false,
+            comment: None,
+            fx_rate_side: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn transfer_test_pair(id: &str, from_account_id: &str, to_account_id: &str) -> ActivityTransferPair {
+        ActivityTransferPair {
+            id: id.into(),
+            transfer_out_activity_id: format!("{}_out", id),
+            transfer_in_activity_id: format!("{}_in", id),
+            from_account_id: from_account_id.into(),
+            to_account_id: to_account_id.into(),
+        }
+    }
+
+    fn transfer_test_service(
+        account_ids: &[&str],
+        activity_repo: MockActivityRepositoryWithData,
+        snaps: Arc<MockSnapshotRepository>,
+    ) -> SnapshotService {
+        let mut account_repo = MockAccountRepository::new();
+        for id in account_ids {
+            account_repo.add_account(create_test_account(id, "USD", id));
+        }
+        SnapshotService::new(
+            Arc::new(RwLock::new("USD".to_string())),
+            Arc::new(account_repo),
+            Arc::new(activity_repo),
+            snaps,
+            Arc::new(MockAssetRepository::new()),
+            Arc::new(MockFxService::new()),
+            Arc::new(MockCheckpointRepository::new()),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_same_day_transfer_chain_carries_lots_to_the_last_account() {
+        // acc_c -> acc_b -> acc_a on one day: the ids sort opposite to the transfer order
+        let d1 = NaiveDate::from_ymd_opt(2025, 5, 8).unwrap();
+        let d2 = NaiveDate::from_ymd_opt(2025, 5, 9).unwrap();
+        let activity_repo = MockActivityRepositoryWithData::new(vec![
+            transfer_test_activity("buy", "acc_c", "BUY", d1, dec!(100)),
+            transfer_test_activity("c_to_b_out", "acc_c", "TRANSFER_OUT", d2, dec!(0)),
+            transfer_test_activity("c_to_b_in", "acc_b", "TRANSFER_IN", d2, dec!(500)),
+            transfer_test_activity("b_to_a_out", "acc_b", "TRANSFER_OUT", d2, dec!(0)),
+            transfer_test_activity("b_to_a_in", "acc_a", "TRANSFER_IN", d2, dec!(500)),
+        ])
+        .with_transfer_pairs(vec![
+            transfer_test_pair("c_to_b", "acc_c", "acc_b"),
+            transfer_test_pair("b_to_a", "acc_b", "acc_a"),
+        ]);
+        let snaps = Arc::new(MockSnapshotRepository::new());
+        let svc = transfer_test_service(&["acc_a", "acc_b", "acc_c"], activity_repo, snaps.clone());
+
+        svc.calculate_holdings_snapshots(None).await.unwrap();
+
+        let frame_on = |account_id: &str| {
+            snaps
+                .get_snapshots_by_account(account_id, Some(d2), Some(d2))
+                .unwrap()
+                .remove(0)
+        };
+        let destination = frame_on("acc_a");
+        let received = destination.positions.get("AAPL").unwrap();
+        assert_eq!(received.quantity, dec!(10));
+        assert_eq!(received.total_cost_basis, dec!(1000)); // The original lot, not the 500 price
+        assert_eq!(received.lots[0].id, "buy");
+        for account_id in ["acc_b", "acc_c"] {
+            let quantity = frame_on(account_id)
+                .positions
+                .get("AAPL")
+                .map_or(Decimal::ZERO, |p| p.quantity);
+            assert_eq!(quantity, Decimal::ZERO);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transfer_without_source_lots_fails_the_calculation() {
+        // The source account never held the asset, so no lots can reach the destination
+        let d1 = NaiveDate::from_ymd_opt(2025, 5, 8).unwrap();
+        let activity_repo = MockActivityRepositoryWithData::new(vec![
+            transfer_test_activity("pair_out", "acc_src", "TRANSFER_OUT", d1, dec!(0)),
+            transfer_test_activity("pair_in", "acc_dst", "TRANSFER_IN", d1, dec!(100)),
+        ])
+        .with_transfer_pairs(vec![transfer_test_pair("pair", "acc_src", "acc_dst")]);
+        let svc = transfer_test_service(
+            &["acc_src", "acc_dst"],
+            activity_repo,
+            Arc::new(MockSnapshotRepository::new()),
+        );
+
+        let result = svc.calculate_holdings_snapshots(None).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::Calculation(CalculatorError::TransferLotsMissing { .. }))
+        ));
+    }
 }
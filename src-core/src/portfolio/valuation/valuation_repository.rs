@@ -28,6 +28,12 @@ pub trait ValuationRepositoryTrait: Send + Sync {
     ) -> Result<Vec<DailyAccountValuation>>;
     fn load_latest_valuation_date(&self, account_id: &str) -> Result<Option<NaiveDate>>;
     async fn delete_valuations_for_account(&self, account_id: &str) -> Result<()>;
+    /// Deletes the account's valuations on or after `from_date`.
+    async fn delete_valuations_from_date(
+        &self,
+        account_id: &str,
+        from_date: NaiveDate,
+    ) -> Result<()>;
     fn get_latest_valuations(
         &self,
         input_account_ids: &[String],
@@ -142,6 +148,25 @@ impl ValuationRepositoryTrait for ValuationRepository {
             .await
     }
 
+    async fn delete_valuations_from_date(
+        &self,
+        input_account_id: &str,
+        from_date: NaiveDate,
+    ) -> Result<()> {
+        let account_id_owned = input_account_id.to_string();
+        self.writer
+            .exec(move |conn| {
+                diesel::delete(
+                    daily_account_valuation::table
+                        .filter(account_id.eq(account_id_owned))
+                        .filter(valuation_date.ge(from_date)),
+                )
+                .execute(conn)?;
+                Ok(())
+            })
+            .await
+    }
+
     fn get_latest_valuations(
         &self,
         input_account_ids: &[String],
@@ -1,14 +1,17 @@
-use crate::errors::{CalculatorError, Error as CoreError, Result as CoreResult};
+use crate::constants::PORTFOLIO_TOTAL_ACCOUNT_ID;
+use crate::errors::{CalculatorError, Error as CoreError, Result as CoreResult, ValidationError};
 use crate::fx::currency::normalize_currency_code;
+use crate::fx::fx_model::FxRateSide;
 use crate::fx::fx_traits::FxServiceTrait;
 use crate::market_data::MarketDataServiceTrait;
+use crate::portfolio::checkpoint::ValuationCheckpointRepositoryTrait;
 use crate::portfolio::snapshot::SnapshotServiceTrait;
 use crate::portfolio::valuation::valuation_calculator::calculate_valuation;
 use crate::portfolio::valuation::valuation_model::DailyAccountValuation;
 use crate::portfolio::valuation::ValuationRepositoryTrait;
 use crate::utils::time_utils;
 use async_trait::async_trait;
-use chrono::NaiveDate;
+use chrono::{Local, NaiveDate};
 use log::{debug, error, warn};
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
@@ -20,8 +23,9 @@ use super::DailyFxRateMap;
 pub trait ValuationServiceTrait: Send + Sync {
     /// Ensures the valuation history for the account is calculated and stored.
     /// If `recalculate_all` is true, existing valuation data is deleted and fully recalculated.
-    /// Otherwise, it calculates incrementally from the last stored date (inclusive)
-    /// up to the latest available snapshot date.
+    /// Otherwise, it calculates incrementally from the last stored date (inclusive), or
+    /// from the account's checkpoint dirty date if that is earlier, up to the latest
+    /// available snapshot date.
     ///
     /// Args:
     ///     account_id: The ID of the account ("TOTAL" for portfolio aggregate).
@@ -65,6 +69,16 @@ pub trait ValuationServiceTrait: Send + Sync {
         account_ids: &[String],
     ) -> CoreResult<Vec<DailyAccountValuation>>;
 
+    /// Loads the latest valuations with the account-to-base rate taken from the given
+    /// side of the FX quote, e.g. `FxRateSide::Bid` for the liquidation value.
+    /// The `TOTAL` aggregate only keeps its base currency value, so it can only be
+    /// requested at the mid rate.
+    fn get_latest_valuations_at_side(
+        &self,
+        account_ids: &[String],
+        side: FxRateSide,
+    ) -> CoreResult<Vec<DailyAccountValuation>>;
+
     fn get_valuations_on_date(
         &self,
         account_ids: &[String],
@@ -79,6 +93,7 @@ pub struct ValuationService {
     snapshot_service: Arc<dyn SnapshotServiceTrait>,
     market_data_service: Arc<dyn MarketDataServiceTrait>,
     fx_service: Arc<dyn FxServiceTrait>,
+    checkpoint_repository: Arc<dyn ValuationCheckpointRepositoryTrait>,
 }
 
 impl ValuationService {
@@ -88,6 +103,7 @@ impl ValuationService {
         snapshot_service: Arc<dyn SnapshotServiceTrait>,
         market_data_service: Arc<dyn MarketDataServiceTrait>,
         fx_service: Arc<dyn FxServiceTrait>,
+        checkpoint_repository: Arc<dyn ValuationCheckpointRepositoryTrait>,
     ) -> Self {
         Self {
             base_currency,
@@ -95,6 +111,7 @@ impl ValuationService {
             market_data_service,
             fx_service,
             valuation_repository,
+            checkpoint_repository,
         }
     }
 
@@ -152,6 +169,13 @@ impl ValuationServiceTrait for ValuationService {
         );
 
         let mut calculation_start_date: Option<NaiveDate> = None;
+        let checkpoint = self.checkpoint_repository.get_checkpoint(account_id)?;
+        let consumed_dirty_from = checkpoint
+            .as_ref()
+            .and_then(|checkpoint| checkpoint.valuation_dirty_from);
+        let consumed_dirty_version = checkpoint
+            .as_ref()
+            .map_or(0, |checkpoint| checkpoint.valuation_dirty_version);
 
         if recalculate_all {
             self.valuation_repository
@@ -163,7 +187,14 @@ impl ValuationServiceTrait for ValuationService {
                 .load_latest_valuation_date(account_id)?;
 
             if let Some(last_saved) = last_saved_date_opt {
-                calculation_start_date = Some(last_saved);
+                // Days from the dirty date on were valued with stale holdings, quotes or FX
+                let start = consumed_dirty_from.map_or(last_saved, |dirty| dirty.min(last_saved));
+                if start < last_saved {
+                    self.valuation_repository
+                        .delete_valuations_from_date(account_id, start)
+                        .await?;
+                }
+                calculation_start_date = Some(start);
             }
         }
 
@@ -178,6 +209,13 @@ impl ValuationServiceTrait for ValuationService {
             })?;
 
         if snapshots_to_process.is_empty() {
+            self.checkpoint_repository
+                .complete_valuation(
+                    account_id,
+                    consumed_dirty_version,
+                    calculation_start_date.unwrap_or_else(|| Local::now().date_naive()),
+                )
+                .await?;
             return Ok(());
         }
 
@@ -331,6 +369,9 @@ impl ValuationServiceTrait for ValuationService {
                 .save_valuations(&newly_calculated_valuations)
                 .await?;
         }
+        self.checkpoint_repository
+            .complete_valuation(account_id, consumed_dirty_version, calculation_end_date)
+            .await?;
 
         let total_duration = total_start_time.elapsed();
         debug!(
@@ -366,6 +407,41 @@ impl ValuationServiceTrait for ValuationService {
         self.valuation_repository.get_latest_valuations(account_ids)
     }
 
+    fn get_latest_valuations_at_side(
+        &self,
+        account_ids: &[String],
+        side: FxRateSide,
+    ) -> CoreResult<Vec<DailyAccountValuation>> {
+        if side == FxRateSide::Mid {
+            return self.get_latest_valuations(account_ids);
+        }
+        if account_ids
+            .iter()
+            .any(|id| id == PORTFOLIO_TOTAL_ACCOUNT_ID)
+        {
+            return Err(CoreError::Validation(ValidationError::InvalidInput(
+                format!(
+                    "The {} valuation is only available at the mid rate",
+                    PORTFOLIO_TOTAL_ACCOUNT_ID
+                ),
+            )));
+        }
+
+        let mut valuations = self.get_latest_valuations(account_ids)?;
+        for valuation in valuations
+            .iter_mut()
+            .filter(|v| v.account_currency != v.base_currency)
+        {
+            valuation.fx_rate_to_base = self.fx_service.get_exchange_rate_for_date_side(
+                &valuation.account_currency,
+                &valuation.base_currency,
+                valuation.valuation_date,
+                side,
+            )?;
+        }
+        Ok(valuations)
+    }
+
     fn get_valuations_on_date(
         &self,
         account_ids: &[String],
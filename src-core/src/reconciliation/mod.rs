@@ -0,0 +1,17 @@
+mod reconciliation_calculator;
+mod reconciliation_model;
+mod reconciliation_repository;
+mod reconciliation_service;
+mod reconciliation_traits;
+
+pub use reconciliation_calculator::compare_statement;
+pub use reconciliation_model::{
+    BrokerStatement, Reconciliation, ReconciliationDB, ReconciliationLine, ReconciliationLineDB,
+    ReconciliationLineType, ReconciliationReport, ReconciliationStatus, SuggestedActivity,
+};
+pub use reconciliation_repository::ReconciliationRepository;
+pub use reconciliation_service::ReconciliationService;
+pub use reconciliation_traits::{ReconciliationRepositoryTrait, ReconciliationServiceTrait};
+
+#[cfg(test)]
+mod reconciliation_repository_tests;
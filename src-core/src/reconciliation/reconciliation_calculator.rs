@@ -0,0 +1,429 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use super::reconciliation_model::{
+    ReconciliationLine, ReconciliationLineType, ReconciliationReport, SuggestedActivity,
+};
+use crate::activities::{
+    ACTIVITY_TYPE_ADD_HOLDING, ACTIVITY_TYPE_BUY, ACTIVITY_TYPE_DIVIDEND, ACTIVITY_TYPE_FEE,
+    ACTIVITY_TYPE_INTEREST, ACTIVITY_TYPE_REMOVE_HOLDING, ACTIVITY_TYPE_SELL, ACTIVITY_TYPE_SPLIT,
+};
+use crate::portfolio::snapshot::AccountStateSnapshot;
+
+/// Cash differences below this are rounding, not a missing activity
+const CASH_TOLERANCE: Decimal = dec!(0.01);
+/// Quantity differences below this are rounding of fractional units
+const QUANTITY_TOLERANCE: Decimal = dec!(0.000001);
+
+/// Compares reported cash balances and position quantities with the calculated
+/// snapshot for the same date and suggests activities that would close the gaps.
+///
+/// `asset_currencies` maps every reported asset the snapshot doesn't hold to its
+/// currency, used to pair a position difference with a cash difference.
+pub fn compare_statement(
+    account_id: &str,
+    statement_date: chrono::NaiveDate,
+    reported_cash: &HashMap<String, Decimal>,
+    reported_positions: &HashMap<String, Decimal>,
+    snapshot: &AccountStateSnapshot,
+    asset_currencies: &HashMap<String, String>,
+) -> ReconciliationReport {
+    let calculated_cash = &snapshot.cash_balances;
+    let calculated_quantities: HashMap<&str, Decimal> = snapshot
+        .positions
+        .iter()
+        .map(|(asset_id, position)| (asset_id.as_str(), position.quantity))
+        .collect();
+
+    let mut lines = Vec::new();
+
+    let currencies: BTreeSet<&String> =
+        reported_cash.keys().chain(calculated_cash.keys()).collect();
+    for currency in currencies {
+        let reported = reported_cash.get(currency).copied().unwrap_or_default();
+        let calculated = calculated_cash.get(currency).copied().unwrap_or_default();
+        lines.push(ReconciliationLine::new(
+            ReconciliationLineType::Cash,
+            currency,
+            reported,
+            calculated,
+        ));
+    }
+
+    // A position the broker doesn't list is only a difference if we still hold it
+    let assets: BTreeSet<&str> = reported_positions
+        .keys()
+        .map(String::as_str)
+        .chain(
+            calculated_quantities
+                .iter()
+                .filter(|(_, quantity)| !quantity.is_zero())
+                .map(|(asset_id, _)| *asset_id),
+        )
+        .collect();
+    for asset_id in assets {
+        let reported = reported_positions
+            .get(asset_id)
+            .copied()
+            .unwrap_or_default();
+        let calculated = calculated_quantities
+            .get(asset_id)
+            .copied()
+            .unwrap_or_default();
+        lines.push(ReconciliationLine::new(
+            ReconciliationLineType::Position,
+            asset_id,
+            reported,
+            calculated,
+        ));
+    }
+
+    let is_matched = lines.iter().all(|line| !is_difference(line));
+    let suggestions = suggest_activities(&lines, snapshot, asset_currencies);
+
+    ReconciliationReport {
+        account_id: account_id.to_string(),
+        statement_date,
+        lines,
+        suggestions,
+        is_matched,
+    }
+}
+
+/// Whether a line's difference exceeds the tolerance for its type
+pub fn is_difference(line: &ReconciliationLine) -> bool {
+    let tolerance = match line.line_type {
+        ReconciliationLineType::Cash => CASH_TOLERANCE,
+        ReconciliationLineType::Position => QUANTITY_TOLERANCE,
+    };
+    line.difference.abs() >= tolerance
+}
+
+/// Explains position differences first, since a missed trade also moves cash, then
+/// attributes whatever cash difference is left to income or fees.
+fn suggest_activities(
+    lines: &[ReconciliationLine],
+    snapshot: &AccountStateSnapshot,
+    asset_currencies: &HashMap<String, String>,
+) -> Vec<SuggestedActivity> {
+    let mut remaining_cash: BTreeMap<String, Decimal> = lines
+        .iter()
+        .filter(|line| line.line_type == ReconciliationLineType::Cash && is_difference(line))
+        .map(|line| (line.code.clone(), line.difference))
+        .collect();
+    let mut suggestions = Vec::new();
+
+    let currency_of = |asset_id: &str| -> Option<String> {
+        snapshot
+            .positions
+            .get(asset_id)
+            .map(|p| p.currency.clone())
+            .filter(|c| !c.is_empty())
+            .or_else(|| asset_currencies.get(asset_id).cloned())
+    };
+
+    for line in lines
+        .iter()
+        .filter(|line| line.line_type == ReconciliationLineType::Position && is_difference(line))
+    {
+        let currency = currency_of(&line.code);
+
+        if let Some(ratio) = split_ratio(line.reported, line.calculated) {
+            suggestions.push(SuggestedActivity {
+                activity_type: ACTIVITY_TYPE_SPLIT.to_string(),
+                asset_id: Some(line.code.clone()),
+                currency,
+                quantity: None,
+                amount: Some(ratio),
+                reason: format!(
+                    "Reported quantity is {} times the calculated quantity",
+                    ratio
+                ),
+            });
+            continue;
+        }
+
+        // More units and less cash (or the reverse) in the same currency looks like a trade
+        let paired_cash = currency.as_ref().and_then(|c| {
+            remaining_cash
+                .get(c)
+                .filter(|cash| cash.is_sign_negative() != line.difference.is_sign_negative())
+                .copied()
+        });
+        let (activity_type, amount, reason) = match paired_cash {
+            Some(cash) => {
+                remaining_cash.remove(currency.as_deref().unwrap_or_default());
+                let activity_type = if line.difference.is_sign_positive() {
+                    ACTIVITY_TYPE_BUY
+                } else {
+                    ACTIVITY_TYPE_SELL
+                };
+                (
+                    activity_type,
+                    Some(cash.abs()),
+                    "Quantity and cash differ in opposite directions".to_string(),
+                )
+            }
+            None => {
+                let activity_type = if line.difference.is_sign_positive() {
+                    ACTIVITY_TYPE_ADD_HOLDING
+                } else {
+                    ACTIVITY_TYPE_REMOVE_HOLDING
+                };
+                (
+                    activity_type,
+                    None,
+                    "Quantity differs without a matching cash difference".to_string(),
+                )
+            }
+        };
+        suggestions.push(SuggestedActivity {
+            activity_type: activity_type.to_string(),
+            asset_id: Some(line.code.clone()),
+            currency,
+            quantity: Some(line.difference.abs()),
+            amount,
+            reason,
+        });
+    }
+
+    for (currency, difference) in remaining_cash {
+        if difference.is_sign_negative() {
+            suggestions.push(SuggestedActivity {
+                activity_type: ACTIVITY_TYPE_FEE.to_string(),
+                asset_id: None,
+                currency: Some(currency.clone()),
+                quantity: None,
+                amount: Some(difference.abs()),
+                reason: format!("Broker reports {} less {} cash", difference.abs(), currency),
+            });
+            continue;
+        }
+
+        let mut income_assets: Vec<&str> = snapshot
+            .positions
+            .values()
+            .filter(|p| p.currency == currency && !p.quantity.is_zero())
+            .map(|p| p.asset_id.as_str())
+            .collect();
+        income_assets.sort_unstable();
+        for asset_id in income_assets {
+            suggestions.push(SuggestedActivity {
+                activity_type: ACTIVITY_TYPE_DIVIDEND.to_string(),
+                asset_id: Some(asset_id.to_string()),
+                currency: Some(currency.clone()),
+                quantity: None,
+                amount: Some(difference),
+                reason: format!(
+                    "Broker reports {} more {} cash and {} is held",
+                    difference, currency, asset_id
+                ),
+            });
+        }
+        suggestions.push(SuggestedActivity {
+            activity_type: ACTIVITY_TYPE_INTEREST.to_string(),
+            asset_id: None,
+            currency: Some(currency.clone()),
+            quantity: None,
+            amount: Some(difference),
+            reason: format!("Broker reports {} more {} cash", difference, currency),
+        });
+    }
+
+    suggestions
+}
+
+/// Returns the split ratio when the reported quantity is a whole multiple (or whole
+/// fraction) of the calculated one, as a forward or reverse split would leave it.
+fn split_ratio(reported: Decimal, calculated: Decimal) -> Option<Decimal> {
+    if reported <= Decimal::ZERO || calculated <= Decimal::ZERO {
+        return None;
+    }
+    let ratio = reported / calculated;
+    let whole = |r: Decimal| r > Decimal::ONE && (r - r.round()).abs() < QUANTITY_TOLERANCE;
+    if whole(ratio) {
+        Some(ratio.round())
+    } else if whole(Decimal::ONE / ratio) {
+        Some((Decimal::ONE / (Decimal::ONE / ratio).round()).normalize())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::portfolio::snapshot::Position;
+    use chrono::NaiveDate;
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, 30).unwrap()
+    }
+
+    fn snapshot(
+        cash: &[(&str, Decimal)],
+        positions: &[(&str, &str, Decimal)],
+    ) -> AccountStateSnapshot {
+        AccountStateSnapshot {
+            account_id: "acc1".to_string(),
+            snapshot_date: date(),
+            currency: "VND".to_string(),
+            cash_balances: cash.iter().map(|(c, a)| (c.to_string(), *a)).collect(),
+            positions: positions
+                .iter()
+                .map(|(asset_id, currency, quantity)| {
+                    (
+                        asset_id.to_string(),
+                        Position {
+                            asset_id: asset_id.to_string(),
+                            currency: currency.to_string(),
+                            quantity: *quantity,
+                            ..Default::default()
+                        },
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn map(entries: &[(&str, Decimal)]) -> HashMap<String, Decimal> {
+        entries.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+    }
+
+    #[test]
+    fn test_matching_statement_has_no_suggestions() {
+        let snap = snapshot(&[("VND", dec!(1000000))], &[("FPT", "VND", dec!(100))]);
+        let report = compare_statement(
+            "acc1",
+            date(),
+            &map(&[("VND", dec!(1000000.004))]),
+            &map(&[("FPT", dec!(100))]),
+            &snap,
+            &HashMap::new(),
+        );
+
+        assert!(report.is_matched);
+        assert_eq!(report.lines.len(), 2);
+        assert!(report.suggestions.is_empty());
+    }
+
+    #[test]
+    fn test_cash_shortfall_suggests_fee() {
+        let snap = snapshot(&[("VND", dec!(1000000))], &[]);
+        let report = compare_statement(
+            "acc1",
+            date(),
+            &map(&[("VND", dec!(980000))]),
+            &HashMap::new(),
+            &snap,
+            &HashMap::new(),
+        );
+
+        assert!(!report.is_matched);
+        assert_eq!(report.lines[0].difference, dec!(-20000));
+        assert_eq!(report.suggestions.len(), 1);
+        assert_eq!(report.suggestions[0].activity_type, ACTIVITY_TYPE_FEE);
+        assert_eq!(report.suggestions[0].amount, Some(dec!(20000)));
+    }
+
+    #[test]
+    fn test_cash_surplus_suggests_dividend_per_holding_and_interest() {
+        let snap = snapshot(
+            &[("VND", dec!(1000000)), ("USD", dec!(10))],
+            &[("FPT", "VND", dec!(100)), ("AAPL", "USD", dec!(1))],
+        );
+        let report = compare_statement(
+            "acc1",
+            date(),
+            &map(&[("VND", dec!(1150000)), ("USD", dec!(10))]),
+            &map(&[("FPT", dec!(100)), ("AAPL", dec!(1))]),
+            &snap,
+            &HashMap::new(),
+        );
+
+        let types: Vec<(&str, Option<&str>)> = report
+            .suggestions
+            .iter()
+            .map(|s| (s.activity_type.as_str(), s.asset_id.as_deref()))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                (ACTIVITY_TYPE_DIVIDEND, Some("FPT")),
+                (ACTIVITY_TYPE_INTEREST, None)
+            ]
+        );
+        assert_eq!(report.suggestions[0].amount, Some(dec!(150000)));
+    }
+
+    #[test]
+    fn test_position_and_opposite_cash_difference_suggest_trade() {
+        let snap = snapshot(&[("VND", dec!(10000000))], &[("FPT", "VND", dec!(100))]);
+        let report = compare_statement(
+            "acc1",
+            date(),
+            &map(&[("VND", dec!(7500000))]),
+            &map(&[("FPT", dec!(120))]),
+            &snap,
+            &HashMap::new(),
+        );
+
+        assert_eq!(report.suggestions.len(), 1);
+        let buy = &report.suggestions[0];
+        assert_eq!(buy.activity_type, ACTIVITY_TYPE_BUY);
+        assert_eq!(buy.quantity, Some(dec!(20)));
+        assert_eq!(buy.amount, Some(dec!(2500000)));
+    }
+
+    #[test]
+    fn test_position_missing_from_statement_and_unknown_position() {
+        let snap = snapshot(&[], &[("FPT", "VND", dec!(100)), ("OLD", "VND", dec!(0))]);
+        let report = compare_statement(
+            "acc1",
+            date(),
+            &HashMap::new(),
+            &map(&[("VNM", dec!(50))]),
+            &snap,
+            &HashMap::from([("VNM".to_string(), "VND".to_string())]),
+        );
+
+        // Fully sold positions are not compared
+        assert_eq!(report.lines.len(), 2);
+        let types: Vec<(&str, Option<&str>)> = report
+            .suggestions
+            .iter()
+            .map(|s| (s.activity_type.as_str(), s.asset_id.as_deref()))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                (ACTIVITY_TYPE_REMOVE_HOLDING, Some("FPT")),
+                (ACTIVITY_TYPE_ADD_HOLDING, Some("VNM"))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_whole_multiple_suggests_split() {
+        let snap = snapshot(&[], &[("FPT", "VND", dec!(100)), ("VNM", "VND", dec!(100))]);
+        let report = compare_statement(
+            "acc1",
+            date(),
+            &HashMap::new(),
+            &map(&[("FPT", dec!(120)), ("VNM", dec!(25))]),
+            &snap,
+            &HashMap::new(),
+        );
+
+        assert_eq!(
+            report.suggestions[0].activity_type,
+            ACTIVITY_TYPE_ADD_HOLDING
+        );
+        assert_eq!(report.suggestions[1].activity_type, ACTIVITY_TYPE_SPLIT);
+        assert_eq!(report.suggestions[1].amount, Some(dec!(0.25)));
+        assert_eq!(split_ratio(dec!(200), dec!(100)), Some(dec!(2)));
+    }
+}
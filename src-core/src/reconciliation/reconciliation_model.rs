@@ -0,0 +1,226 @@
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Outcome of a saved reconciliation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReconciliationStatus {
+    /// Every reported balance agreed with the calculated holdings
+    Matched,
+    /// At least one reported balance differed
+    Mismatched,
+    /// Was matched, but an activity on or before the statement date changed since
+    Broken,
+}
+
+impl ReconciliationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReconciliationStatus::Matched => "matched",
+            ReconciliationStatus::Mismatched => "mismatched",
+            ReconciliationStatus::Broken => "broken",
+        }
+    }
+}
+
+impl FromStr for ReconciliationStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "matched" => Ok(ReconciliationStatus::Matched),
+            "mismatched" => Ok(ReconciliationStatus::Mismatched),
+            "broken" => Ok(ReconciliationStatus::Broken),
+            _ => Err(format!("Unknown reconciliation status: {}", s)),
+        }
+    }
+}
+
+/// Whether a line compares a cash balance or a position quantity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReconciliationLineType {
+    /// `code` is a currency
+    Cash,
+    /// `code` is an asset ID
+    Position,
+}
+
+impl ReconciliationLineType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReconciliationLineType::Cash => "cash",
+            ReconciliationLineType::Position => "position",
+        }
+    }
+}
+
+impl FromStr for ReconciliationLineType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cash" => Ok(ReconciliationLineType::Cash),
+            "position" => Ok(ReconciliationLineType::Position),
+            _ => Err(format!("Unknown reconciliation line type: {}", s)),
+        }
+    }
+}
+
+/// Balances reported by the broker for an account at the end of a day, entered by
+/// hand or parsed from an imported statement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokerStatement {
+    pub account_id: String,
+    pub statement_date: NaiveDate,
+    /// currency -> cash balance
+    #[serde(default)]
+    pub cash_balances: HashMap<String, Decimal>,
+    /// asset_id -> quantity
+    #[serde(default)]
+    pub positions: HashMap<String, Decimal>,
+    pub notes: Option<String>,
+}
+
+/// A reported balance next to the calculated one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationLine {
+    pub line_type: ReconciliationLineType,
+    pub code: String,
+    pub reported: Decimal,
+    pub calculated: Decimal,
+    /// `reported - calculated`
+    pub difference: Decimal,
+}
+
+/// An activity that would explain (part of) a difference
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestedActivity {
+    pub activity_type: String,
+    pub asset_id: Option<String>,
+    pub currency: Option<String>,
+    pub quantity: Option<Decimal>,
+    pub amount: Option<Decimal>,
+    pub reason: String,
+}
+
+/// Comparison of a broker statement with the calculated holdings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationReport {
+    pub account_id: String,
+    pub statement_date: NaiveDate,
+    pub lines: Vec<ReconciliationLine>,
+    pub suggestions: Vec<SuggestedActivity>,
+    pub is_matched: bool,
+}
+
+/// A stored reconciliation checkpoint with its lines re-read from the database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reconciliation {
+    pub id: String,
+    pub account_id: String,
+    pub statement_date: NaiveDate,
+    pub status: ReconciliationStatus,
+    pub notes: Option<String>,
+    pub lines: Vec<ReconciliationLine>,
+    pub broken_at: Option<String>,
+    pub broken_by_activity_id: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Queryable, Insertable, AsChangeset, Selectable, Debug, Clone)]
+#[diesel(table_name = crate::schema::reconciliations)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[diesel(treat_none_as_null = true)]
+pub struct ReconciliationDB {
+    pub id: String,
+    pub account_id: String,
+    pub statement_date: NaiveDate,
+    pub status: String,
+    pub notes: Option<String>,
+    pub broken_at: Option<String>,
+    pub broken_by_activity_id: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Queryable, Insertable, Selectable, Debug, Clone)]
+#[diesel(table_name = crate::schema::reconciliation_lines)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct ReconciliationLineDB {
+    pub reconciliation_id: String,
+    pub line_type: String,
+    pub code: String,
+    pub reported: String,
+    pub calculated: String,
+}
+
+impl ReconciliationLine {
+    pub fn new(
+        line_type: ReconciliationLineType,
+        code: &str,
+        reported: Decimal,
+        calculated: Decimal,
+    ) -> Self {
+        Self {
+            line_type,
+            code: code.to_string(),
+            reported,
+            calculated,
+            difference: reported - calculated,
+        }
+    }
+
+    pub fn to_db(&self, reconciliation_id: &str) -> ReconciliationLineDB {
+        ReconciliationLineDB {
+            reconciliation_id: reconciliation_id.to_string(),
+            line_type: self.line_type.as_str().to_string(),
+            code: self.code.clone(),
+            reported: self.reported.to_string(),
+            calculated: self.calculated.to_string(),
+        }
+    }
+}
+
+impl From<ReconciliationLineDB> for ReconciliationLine {
+    fn from(db: ReconciliationLineDB) -> Self {
+        ReconciliationLine::new(
+            ReconciliationLineType::from_str(&db.line_type)
+                .unwrap_or(ReconciliationLineType::Position),
+            &db.code,
+            Decimal::from_str(&db.reported).unwrap_or_default(),
+            Decimal::from_str(&db.calculated).unwrap_or_default(),
+        )
+    }
+}
+
+impl Reconciliation {
+    pub fn from_db(db: ReconciliationDB, lines: Vec<ReconciliationLineDB>) -> Self {
+        let mut lines: Vec<ReconciliationLine> =
+            lines.into_iter().map(ReconciliationLine::from).collect();
+        lines.sort_by(|a, b| (a.line_type, &a.code).cmp(&(b.line_type, &b.code)));
+        Self {
+            id: db.id,
+            account_id: db.account_id,
+            statement_date: db.statement_date,
+            status: ReconciliationStatus::from_str(&db.status)
+                .unwrap_or(ReconciliationStatus::Mismatched),
+            notes: db.notes,
+            lines,
+            broken_at: db.broken_at,
+            broken_by_activity_id: db.broken_by_activity_id,
+            created_at: db.created_at,
+            updated_at: db.updated_at,
+        }
+    }
+}
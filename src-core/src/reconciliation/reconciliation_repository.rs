@@ -0,0 +1,149 @@
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::sqlite::SqliteConnection;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::reconciliation_model::{
+    Reconciliation, ReconciliationDB, ReconciliationLine, ReconciliationLineDB,
+    ReconciliationStatus,
+};
+use super::reconciliation_traits::ReconciliationRepositoryTrait;
+use crate::db::{get_connection, WriteHandle};
+use crate::errors::Result;
+use crate::schema::{reconciliation_lines, reconciliations};
+
+pub struct ReconciliationRepository {
+    pool: Arc<Pool<ConnectionManager<SqliteConnection>>>,
+    writer: WriteHandle,
+}
+
+impl ReconciliationRepository {
+    pub fn new(pool: Arc<Pool<ConnectionManager<SqliteConnection>>>, writer: WriteHandle) -> Self {
+        ReconciliationRepository { pool, writer }
+    }
+}
+
+fn load_reconciliation(
+    conn: &mut SqliteConnection,
+    reconciliation_id: &str,
+) -> Result<Reconciliation> {
+    let reconciliation = reconciliations::table
+        .find(reconciliation_id)
+        .first::<ReconciliationDB>(conn)?;
+    let lines = reconciliation_lines::table
+        .filter(reconciliation_lines::reconciliation_id.eq(reconciliation_id))
+        .load::<ReconciliationLineDB>(conn)?;
+    Ok(Reconciliation::from_db(reconciliation, lines))
+}
+
+#[async_trait]
+impl ReconciliationRepositoryTrait for ReconciliationRepository {
+    fn get_reconciliations(&self, account_id: Option<&str>) -> Result<Vec<Reconciliation>> {
+        let mut conn = get_connection(&self.pool)?;
+        let mut query = reconciliations::table
+            .order((
+                reconciliations::statement_date.desc(),
+                reconciliations::account_id.asc(),
+            ))
+            .into_boxed();
+        if let Some(account_id) = account_id {
+            query = query.filter(reconciliations::account_id.eq(account_id.to_string()));
+        }
+        let headers = query.load::<ReconciliationDB>(&mut conn)?;
+
+        let ids: Vec<&String> = headers.iter().map(|r| &r.id).collect();
+        let lines = reconciliation_lines::table
+            .filter(reconciliation_lines::reconciliation_id.eq_any(ids))
+            .load::<ReconciliationLineDB>(&mut conn)?;
+
+        Ok(headers
+            .into_iter()
+            .map(|header| {
+                let own_lines = lines
+                    .iter()
+                    .filter(|line| line.reconciliation_id == header.id)
+                    .cloned()
+                    .collect();
+                Reconciliation::from_db(header, own_lines)
+            })
+            .collect())
+    }
+
+    fn get_reconciliation(&self, reconciliation_id: &str) -> Result<Reconciliation> {
+        let mut conn = get_connection(&self.pool)?;
+        load_reconciliation(&mut conn, reconciliation_id)
+    }
+
+    async fn save_reconciliation(
+        &self,
+        account_id: String,
+        statement_date: NaiveDate,
+        status: ReconciliationStatus,
+        notes: Option<String>,
+        lines: Vec<ReconciliationLine>,
+    ) -> Result<Reconciliation> {
+        self.writer
+            .exec(
+                move |conn: &mut SqliteConnection| -> Result<Reconciliation> {
+                    let now = Utc::now().to_rfc3339();
+                    let existing = reconciliations::table
+                        .filter(reconciliations::account_id.eq(&account_id))
+                        .filter(reconciliations::statement_date.eq(statement_date))
+                        .first::<ReconciliationDB>(conn)
+                        .optional()?;
+                    let (id, created_at) = match existing {
+                        Some(existing) => (existing.id, existing.created_at),
+                        None => (Uuid::new_v4().to_string(), now.clone()),
+                    };
+
+                    let header = ReconciliationDB {
+                        id: id.clone(),
+                        account_id,
+                        statement_date,
+                        status: status.as_str().to_string(),
+                        notes,
+                        broken_at: None,
+                        broken_by_activity_id: None,
+                        created_at,
+                        updated_at: now,
+                    };
+                    diesel::replace_into(reconciliations::table)
+                        .values(&header)
+                        .execute(conn)?;
+
+                    diesel::delete(
+                        reconciliation_lines::table
+                            .filter(reconciliation_lines::reconciliation_id.eq(&id)),
+                    )
+                    .execute(conn)?;
+                    let rows: Vec<ReconciliationLineDB> =
+                        lines.iter().map(|line| line.to_db(&id)).collect();
+                    if !rows.is_empty() {
+                        diesel::insert_into(reconciliation_lines::table)
+                            .values(&rows)
+                            .execute(conn)?;
+                    }
+
+                    load_reconciliation(conn, &id)
+                },
+            )
+            .await
+    }
+
+    async fn delete_reconciliation(&self, reconciliation_id: &str) -> Result<usize> {
+        let id = reconciliation_id.to_string();
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<usize> {
+                diesel::delete(
+                    reconciliation_lines::table
+                        .filter(reconciliation_lines::reconciliation_id.eq(&id)),
+                )
+                .execute(conn)?;
+                Ok(diesel::delete(reconciliations::table.find(&id)).execute(conn)?)
+            })
+            .await
+    }
+}
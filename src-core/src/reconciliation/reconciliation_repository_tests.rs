@@ -0,0 +1,75 @@
+use chrono::NaiveDate;
+
+use super::{ReconciliationRepository, ReconciliationRepositoryTrait, ReconciliationStatus};
+use crate::test_utils::TestDb;
+
+fn insert_activity(db: &TestDb, id: &str, activity_date: &str, is_draft: bool) {
+    db.execute(&format!(
+        "INSERT INTO activities (id, account_id, asset_id, activity_type, activity_date, quantity, unit_price, currency, fee, amount, is_draft, created_at, updated_at)
+         VALUES ('{id}', 'acc-1', '$CASH-USD', 'DEPOSIT', '{activity_date}T00:00:00+00:00', '0', '0', 'USD', '0', '100', {}, '2024-01-01T00:00:00+00:00', '2024-01-01T00:00:00+00:00');",
+        is_draft as i32
+    ));
+}
+
+fn setup(db: &TestDb) -> ReconciliationRepository {
+    db.insert_account("acc-1", "USD");
+    db.insert_asset("$CASH-USD", "USD");
+    ReconciliationRepository::new(db.pool.clone(), db.writer.clone())
+}
+
+async fn save_matched(repository: &ReconciliationRepository) -> String {
+    repository
+        .save_reconciliation(
+            "acc-1".to_string(),
+            NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+            ReconciliationStatus::Matched,
+            None,
+            Vec::new(),
+        )
+        .await
+        .unwrap()
+        .id
+}
+
+#[tokio::test]
+async fn test_draft_activity_changes_keep_reconciliation_matched() {
+    let db = TestDb::new();
+    let repository = setup(&db);
+    let id = save_matched(&repository).await;
+
+    insert_activity(&db, "draft-1", "2024-03-01", true);
+    db.execute("UPDATE activities SET amount = '200' WHERE id = 'draft-1';");
+    db.execute("DELETE FROM activities WHERE id = 'draft-1';");
+
+    let reconciliation = repository.get_reconciliation(&id).unwrap();
+    assert_eq!(reconciliation.status, ReconciliationStatus::Matched);
+}
+
+#[tokio::test]
+async fn test_posted_activity_breaks_reconciliation() {
+    let db = TestDb::new();
+    let repository = setup(&db);
+    let id = save_matched(&repository).await;
+
+    insert_activity(&db, "posted-1", "2024-03-01", false);
+
+    let reconciliation = repository.get_reconciliation(&id).unwrap();
+    assert_eq!(reconciliation.status, ReconciliationStatus::Broken);
+    assert_eq!(
+        reconciliation.broken_by_activity_id.as_deref(),
+        Some("posted-1")
+    );
+}
+
+#[tokio::test]
+async fn test_deleting_posted_activity_breaks_reconciliation() {
+    let db = TestDb::new();
+    let repository = setup(&db);
+    insert_activity(&db, "posted-1", "2024-03-01", false);
+    let id = save_matched(&repository).await;
+
+    db.execute("DELETE FROM activities WHERE id = 'posted-1';");
+
+    let reconciliation = repository.get_reconciliation(&id).unwrap();
+    assert_eq!(reconciliation.status, ReconciliationStatus::Broken);
+}
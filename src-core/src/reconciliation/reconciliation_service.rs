@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{Local, NaiveDate};
+use log::debug;
+use rust_decimal::Decimal;
+
+use super::reconciliation_calculator::compare_statement;
+use super::reconciliation_model::{
+    BrokerStatement, Reconciliation, ReconciliationLineType, ReconciliationReport,
+    ReconciliationStatus,
+};
+use super::reconciliation_traits::{ReconciliationRepositoryTrait, ReconciliationServiceTrait};
+use crate::assets::AssetServiceTrait;
+use crate::constants::PORTFOLIO_TOTAL_ACCOUNT_ID;
+use crate::errors::{Error, Result, ValidationError};
+use crate::portfolio::snapshot::SnapshotServiceTrait;
+
+pub struct ReconciliationService {
+    reconciliation_repository: Arc<dyn ReconciliationRepositoryTrait>,
+    snapshot_service: Arc<dyn SnapshotServiceTrait>,
+    asset_service: Arc<dyn AssetServiceTrait>,
+}
+
+impl ReconciliationService {
+    pub fn new(
+        reconciliation_repository: Arc<dyn ReconciliationRepositoryTrait>,
+        snapshot_service: Arc<dyn SnapshotServiceTrait>,
+        asset_service: Arc<dyn AssetServiceTrait>,
+    ) -> Self {
+        ReconciliationService {
+            reconciliation_repository,
+            snapshot_service,
+            asset_service,
+        }
+    }
+
+    /// Trims codes, upper-cases currencies, and rejects statements for the TOTAL
+    /// virtual account, future dates or negative quantities.
+    fn normalize_statement(statement: BrokerStatement) -> Result<BrokerStatement> {
+        let account_id = statement.account_id.trim().to_string();
+        if account_id.is_empty() {
+            return Err(Error::Validation(ValidationError::MissingField(
+                "accountId".to_string(),
+            )));
+        }
+        if account_id == PORTFOLIO_TOTAL_ACCOUNT_ID {
+            return Err(Error::Validation(ValidationError::InvalidInput(
+                "Broker statements are reconciled per account, not for the total portfolio"
+                    .to_string(),
+            )));
+        }
+        if statement.statement_date > Local::now().date_naive() {
+            return Err(Error::Validation(ValidationError::InvalidInput(format!(
+                "Statement date {} is in the future",
+                statement.statement_date
+            ))));
+        }
+
+        let mut cash_balances: HashMap<String, Decimal> = HashMap::new();
+        for (currency, amount) in statement.cash_balances {
+            let currency = currency.trim().to_uppercase();
+            if !currency.is_empty() {
+                *cash_balances.entry(currency).or_default() += amount;
+            }
+        }
+        let mut positions: HashMap<String, Decimal> = HashMap::new();
+        for (asset_id, quantity) in statement.positions {
+            let asset_id = asset_id.trim().to_string();
+            if asset_id.is_empty() {
+                continue;
+            }
+            if quantity.is_sign_negative() && !quantity.is_zero() {
+                return Err(Error::Validation(ValidationError::InvalidInput(format!(
+                    "Reported quantity for {} cannot be negative",
+                    asset_id
+                ))));
+            }
+            *positions.entry(asset_id).or_default() += quantity;
+        }
+
+        Ok(BrokerStatement {
+            account_id,
+            cash_balances,
+            positions,
+            notes: statement
+                .notes
+                .map(|n| n.trim().to_string())
+                .filter(|n| !n.is_empty()),
+            ..statement
+        })
+    }
+
+    fn compare(
+        &self,
+        account_id: &str,
+        statement_date: NaiveDate,
+        cash_balances: &HashMap<String, Decimal>,
+        positions: &HashMap<String, Decimal>,
+    ) -> Result<ReconciliationReport> {
+        // Without a snapshot every reported balance would show up as a difference
+        let snapshot = self
+            .snapshot_service
+            .get_daily_holdings_snapshots(account_id, Some(statement_date), Some(statement_date))?
+            .into_iter()
+            .find(|s| s.snapshot_date == statement_date)
+            .ok_or_else(|| {
+                Error::Validation(ValidationError::InvalidInput(format!(
+                    "No holdings calculated for account {} on {}. Recalculate the account before reconciling.",
+                    account_id, statement_date
+                )))
+            })?;
+
+        // Only needed to pair a reported asset we don't hold with a cash difference
+        let asset_currencies: HashMap<String, String> = positions
+            .keys()
+            .filter(|asset_id| !snapshot.positions.contains_key(*asset_id))
+            .filter_map(
+                |asset_id| match self.asset_service.get_asset_by_id(asset_id) {
+                    Ok(asset) => Some((asset_id.clone(), asset.currency)),
+                    Err(e) => {
+                        debug!("Reported asset {} is not known: {}", asset_id, e);
+                        None
+                    }
+                },
+            )
+            .collect();
+
+        Ok(compare_statement(
+            account_id,
+            statement_date,
+            cash_balances,
+            positions,
+            &snapshot,
+            &asset_currencies,
+        ))
+    }
+}
+
+#[async_trait]
+impl ReconciliationServiceTrait for ReconciliationService {
+    fn get_reconciliations(&self, account_id: Option<&str>) -> Result<Vec<Reconciliation>> {
+        self.reconciliation_repository
+            .get_reconciliations(account_id)
+    }
+
+    fn get_reconciliation(&self, reconciliation_id: &str) -> Result<Reconciliation> {
+        self.reconciliation_repository
+            .get_reconciliation(reconciliation_id)
+    }
+
+    async fn preview_reconciliation(
+        &self,
+        statement: BrokerStatement,
+    ) -> Result<ReconciliationReport> {
+        let statement = Self::normalize_statement(statement)?;
+        self.compare(
+            &statement.account_id,
+            statement.statement_date,
+            &statement.cash_balances,
+            &statement.positions,
+        )
+    }
+
+    async fn save_reconciliation(&self, statement: BrokerStatement) -> Result<Reconciliation> {
+        let statement = Self::normalize_statement(statement)?;
+        let report = self.compare(
+            &statement.account_id,
+            statement.statement_date,
+            &statement.cash_balances,
+            &statement.positions,
+        )?;
+        let status = if report.is_matched {
+            ReconciliationStatus::Matched
+        } else {
+            ReconciliationStatus::Mismatched
+        };
+        self.reconciliation_repository
+            .save_reconciliation(
+                statement.account_id,
+                statement.statement_date,
+                status,
+                statement.notes,
+                report.lines,
+            )
+            .await
+    }
+
+    async fn recheck_reconciliation(&self, reconciliation_id: &str) -> Result<Reconciliation> {
+        let existing = self
+            .reconciliation_repository
+            .get_reconciliation(reconciliation_id)?;
+        let statement = BrokerStatement {
+            account_id: existing.account_id.clone(),
+            statement_date: existing.statement_date,
+            cash_balances: existing
+                .lines
+                .iter()
+                .filter(|line| line.line_type == ReconciliationLineType::Cash)
+                .map(|line| (line.code.clone(), line.reported))
+                .collect(),
+            positions: existing
+                .lines
+                .iter()
+                .filter(|line| line.line_type == ReconciliationLineType::Position)
+                .map(|line| (line.code.clone(), line.reported))
+                .collect(),
+            notes: existing.notes,
+        };
+        self.save_reconciliation(statement).await
+    }
+
+    async fn delete_reconciliation(&self, reconciliation_id: &str) -> Result<usize> {
+        self.reconciliation_repository
+            .delete_reconciliation(reconciliation_id)
+            .await
+    }
+}
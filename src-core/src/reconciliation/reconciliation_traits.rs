@@ -0,0 +1,43 @@
+use super::reconciliation_model::{
+    BrokerStatement, Reconciliation, ReconciliationLine, ReconciliationReport, ReconciliationStatus,
+};
+use crate::errors::Result;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+/// Trait defining the contract for reconciliation repository operations.
+#[async_trait]
+pub trait ReconciliationRepositoryTrait: Send + Sync {
+    fn get_reconciliations(&self, account_id: Option<&str>) -> Result<Vec<Reconciliation>>;
+    fn get_reconciliation(&self, reconciliation_id: &str) -> Result<Reconciliation>;
+    /// Inserts or replaces the reconciliation for the account and date, clearing any
+    /// earlier broken marker.
+    async fn save_reconciliation(
+        &self,
+        account_id: String,
+        statement_date: NaiveDate,
+        status: ReconciliationStatus,
+        notes: Option<String>,
+        lines: Vec<ReconciliationLine>,
+    ) -> Result<Reconciliation>;
+    async fn delete_reconciliation(&self, reconciliation_id: &str) -> Result<usize>;
+}
+
+/// Trait defining the contract for reconciliation service operations.
+#[async_trait]
+pub trait ReconciliationServiceTrait: Send + Sync {
+    fn get_reconciliations(&self, account_id: Option<&str>) -> Result<Vec<Reconciliation>>;
+    fn get_reconciliation(&self, reconciliation_id: &str) -> Result<Reconciliation>;
+    /// Compares a statement with the calculated holdings without storing it
+    async fn preview_reconciliation(
+        &self,
+        statement: BrokerStatement,
+    ) -> Result<ReconciliationReport>;
+    /// Compares a statement with the calculated holdings and stores the result as a
+    /// checkpoint for the account and date
+    async fn save_reconciliation(&self, statement: BrokerStatement) -> Result<Reconciliation>;
+    /// Compares the stored reported balances with the current calculated holdings,
+    /// after activities have been fixed
+    async fn recheck_reconciliation(&self, reconciliation_id: &str) -> Result<Reconciliation>;
+    async fn delete_reconciliation(&self, reconciliation_id: &str) -> Result<usize>;
+}
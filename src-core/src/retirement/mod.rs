@@ -0,0 +1,14 @@
+mod retirement_calculator;
+mod retirement_model;
+mod retirement_repository;
+mod retirement_service;
+mod retirement_traits;
+
+pub use retirement_calculator::{calculate_retirement_plan, real_rate};
+pub use retirement_model::{
+    NewRetirementScenario, RetirementInputs, RetirementPlan, RetirementProjectionPoint,
+    RetirementScenario, RetirementScenarioDB,
+};
+pub use retirement_repository::RetirementRepository;
+pub use retirement_service::RetirementService;
+pub use retirement_traits::{RetirementRepositoryTrait, RetirementServiceTrait};
@@ -0,0 +1,350 @@
+use super::retirement_model::{RetirementInputs, RetirementPlan, RetirementProjectionPoint};
+use crate::goals::goal_simulation::{percentile, MonthlyReturns, SimulationRng};
+use crate::goals::goal_solver::{monthly_rate, required_contribution};
+
+const RATE_TOLERANCE: f64 = 1e-10;
+
+/// Inflation-adjusted equivalent of a nominal annual rate, both as fractions
+pub fn real_rate(nominal: f64, inflation: f64) -> f64 {
+    (1.0 + nominal) / (1.0 + inflation) - 1.0
+}
+
+/// Month-by-month cash flows of a plan. Month `m` (1-based) is spent at age
+/// `current_age + (m - 1) / 12`; the first `saving_months` add the contribution and
+/// the rest withdraw spending net of the pension.
+struct Schedule {
+    current_age: u32,
+    saving_months: u32,
+    total_months: u32,
+    monthly_contribution: f64,
+    monthly_spending: f64,
+    monthly_pension: f64,
+    pension_start_age: u32,
+}
+
+impl Schedule {
+    fn new(inputs: &RetirementInputs) -> Self {
+        Self {
+            current_age: inputs.current_age,
+            saving_months: inputs.retirement_age.saturating_sub(inputs.current_age) * 12,
+            total_months: inputs.life_expectancy.saturating_sub(inputs.current_age) * 12,
+            monthly_contribution: inputs.monthly_contribution,
+            monthly_spending: inputs.monthly_spending,
+            monthly_pension: inputs.monthly_pension,
+            pension_start_age: inputs.pension_start_age.unwrap_or(inputs.retirement_age),
+        }
+    }
+
+    fn age_in_month(&self, month: u32) -> u32 {
+        self.current_age + (month - 1) / 12
+    }
+
+    /// Withdrawal in a retirement month, never negative: a pension above spending is
+    /// not reinvested
+    fn withdrawal(&self, month: u32) -> f64 {
+        let pension = if self.age_in_month(month) >= self.pension_start_age {
+            self.monthly_pension
+        } else {
+            0.0
+        };
+        (self.monthly_spending - pension).max(0.0)
+    }
+
+    fn flow(&self, month: u32) -> f64 {
+        if month <= self.saving_months {
+            self.monthly_contribution
+        } else {
+            -self.withdrawal(month)
+        }
+    }
+
+    /// Value needed at retirement to fund every withdrawal until life expectancy
+    fn required_value_at_retirement(&self, retirement_rate: f64) -> f64 {
+        let mut discount = 1.0;
+        let mut required = 0.0;
+        for month in self.saving_months + 1..=self.total_months {
+            discount /= 1.0 + retirement_rate;
+            required += self.withdrawal(month) * discount;
+        }
+        required
+    }
+}
+
+/// One run through the schedule
+struct PathResult {
+    /// Value at the end of each year of age, starting with the current value
+    yearly_values: Vec<f64>,
+    value_at_retirement: f64,
+    /// Month the money ran out in
+    depletion_month: Option<u32>,
+}
+
+fn run_path(
+    schedule: &Schedule,
+    current_value: f64,
+    mut return_for_month: impl FnMut(u32) -> f64,
+) -> PathResult {
+    let mut balance = current_value;
+    let mut yearly_values = vec![current_value];
+    let mut value_at_retirement = current_value;
+    let mut depletion_month = None;
+
+    for month in 1..=schedule.total_months {
+        if depletion_month.is_none() {
+            balance = balance * (1.0 + return_for_month(month)) + schedule.flow(month);
+            if month > schedule.saving_months && balance <= 0.0 {
+                balance = 0.0;
+                depletion_month = Some(month);
+            }
+        }
+        if month == schedule.saving_months {
+            value_at_retirement = balance;
+        }
+        if month % 12 == 0 {
+            yearly_values.push(balance);
+        }
+    }
+
+    PathResult {
+        yearly_values,
+        value_at_retirement,
+        depletion_month,
+    }
+}
+
+/// Month by which `share` of the paths have run out of money
+fn depletion_percentile(sorted_months: &[Option<u32>], share: f64) -> Option<u32> {
+    let count = ((sorted_months.len() as f64) * share).ceil().max(1.0) as usize;
+    sorted_months.get(count - 1).copied().flatten()
+}
+
+/// Level monthly withdrawal that spends `value` to zero over `months`
+fn annuity_payment(value: f64, rate: f64, months: u32) -> f64 {
+    if months == 0 {
+        return value;
+    }
+    if rate.abs() < RATE_TOLERANCE {
+        return value / months as f64;
+    }
+    value * rate / (1.0 - (1.0 + rate).powi(-(months as i32)))
+}
+
+/// Plans retirement from `current_value` in today's money. Inputs are expected to
+/// be validated: `current_age <= retirement_age <= life_expectancy`.
+pub fn calculate_retirement_plan(
+    inputs: &RetirementInputs,
+    current_value: f64,
+    current_year: i32,
+    num_paths: u32,
+    seed: u64,
+) -> RetirementPlan {
+    let inflation = inputs.inflation_rate / 100.0;
+    let saving_return = real_rate(inputs.expected_return / 100.0, inflation);
+    let retirement_return = real_rate(
+        inputs.retirement_return.unwrap_or(inputs.expected_return) / 100.0,
+        inflation,
+    );
+    let saving_rate = monthly_rate(saving_return);
+    let retirement_rate = monthly_rate(retirement_return);
+    let schedule = Schedule::new(inputs);
+    let retirement_months = schedule.total_months.saturating_sub(schedule.saving_months);
+    let year_of_month = |month: u32| current_year + ((month - 1) / 12) as i32;
+
+    // Expected returns
+    let expected = run_path(&schedule, current_value, |month| {
+        if month <= schedule.saving_months {
+            saving_rate
+        } else {
+            retirement_rate
+        }
+    });
+    let value_at_retirement = expected.value_at_retirement;
+    let sustainable_withdrawal_rate = if value_at_retirement > 0.0 {
+        annuity_payment(value_at_retirement, retirement_rate, retirement_months) * 12.0
+            / value_at_retirement
+            * 100.0
+    } else {
+        0.0
+    };
+    let planned_withdrawal_rate = (value_at_retirement > 0.0 && retirement_months > 0).then(|| {
+        schedule.withdrawal(schedule.saving_months + 1) * 12.0 / value_at_retirement * 100.0
+    });
+    let extra_monthly_saving_needed = (schedule.saving_months > 0).then(|| {
+        let required_value = schedule.required_value_at_retirement(retirement_rate);
+        let required = required_contribution(
+            current_value,
+            required_value,
+            saving_rate,
+            schedule.saving_months,
+        );
+        (required - inputs.monthly_contribution).max(0.0)
+    });
+
+    // Simulated returns
+    let saving_returns = MonthlyReturns::from_annual(saving_return, inputs.volatility / 100.0);
+    let retirement_returns =
+        MonthlyReturns::from_annual(retirement_return, inputs.volatility / 100.0);
+    let num_paths = num_paths.max(1) as usize;
+    let mut rng = SimulationRng::new(seed);
+    let years = expected.yearly_values.len();
+    let mut yearly_values = vec![Vec::with_capacity(num_paths); years];
+    let mut depletion_months = Vec::with_capacity(num_paths);
+    for _ in 0..num_paths {
+        let path = run_path(&schedule, current_value, |month| {
+            let returns = if month <= schedule.saving_months {
+                &saving_returns
+            } else {
+                &retirement_returns
+            };
+            returns.draw(&mut rng)
+        });
+        for (year, value) in path.yearly_values.into_iter().enumerate() {
+            yearly_values[year].push(value);
+        }
+        depletion_months.push(path.depletion_month);
+    }
+    // `None` (never ran out) sorts after every month
+    depletion_months.sort_by_key(|m| m.unwrap_or(u32::MAX));
+    let success_probability =
+        depletion_months.iter().filter(|m| m.is_none()).count() as f64 / num_paths as f64;
+    let depletion_p10 = depletion_percentile(&depletion_months, 0.10);
+    let depletion_p50 = depletion_percentile(&depletion_months, 0.50);
+
+    let projection = yearly_values
+        .iter_mut()
+        .enumerate()
+        .map(|(year, values)| {
+            values.sort_by(|a, b| a.total_cmp(b));
+            RetirementProjectionPoint {
+                age: inputs.current_age + year as u32,
+                year: current_year + year as i32,
+                expected_value: expected.yearly_values[year],
+                p10: percentile(values, 0.10),
+                p50: percentile(values, 0.50),
+                p90: percentile(values, 0.90),
+            }
+        })
+        .collect();
+
+    RetirementPlan {
+        current_value,
+        retirement_year: current_year + (schedule.saving_months / 12) as i32,
+        value_at_retirement,
+        sustainable_withdrawal_rate,
+        planned_withdrawal_rate,
+        depletion_age: expected.depletion_month.map(|m| schedule.age_in_month(m)),
+        depletion_year: expected.depletion_month.map(year_of_month),
+        success_probability,
+        simulated_depletion_age_p10: depletion_p10.map(|m| schedule.age_in_month(m)),
+        simulated_depletion_age_p50: depletion_p50.map(|m| schedule.age_in_month(m)),
+        simulated_depletion_year_p10: depletion_p10.map(year_of_month),
+        simulated_depletion_year_p50: depletion_p50.map(year_of_month),
+        extra_monthly_saving_needed,
+        projection,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs() -> RetirementInputs {
+        RetirementInputs {
+            current_age: 40,
+            retirement_age: 60,
+            life_expectancy: 85,
+            monthly_spending: 20_000_000.0,
+            inflation_rate: 4.0,
+            monthly_pension: 6_000_000.0,
+            pension_start_age: Some(62),
+            monthly_contribution: 10_000_000.0,
+            expected_return: 9.0,
+            retirement_return: Some(6.0),
+            volatility: 15.0,
+            current_value: None,
+            account_ids: Vec::new(),
+            num_paths: None,
+            seed: None,
+        }
+    }
+
+    #[test]
+    fn test_zero_return_plan_is_simple_arithmetic() {
+        let flat = RetirementInputs {
+            current_age: 60,
+            retirement_age: 61,
+            life_expectancy: 63,
+            monthly_spending: 10.0,
+            inflation_rate: 0.0,
+            monthly_pension: 4.0,
+            pension_start_age: Some(62),
+            monthly_contribution: 1.0,
+            expected_return: 0.0,
+            retirement_return: None,
+            volatility: 0.0,
+            ..inputs()
+        };
+        let plan = calculate_retirement_plan(&flat, 100.0, 2025, 10, 1);
+
+        // 100 + 12 saved, then 10 a month before the pension starts runs out in the
+        // twelfth month of retirement, still aged 61
+        assert_eq!(plan.value_at_retirement, 112.0);
+        assert_eq!(plan.retirement_year, 2026);
+        assert_eq!(plan.depletion_age, Some(61));
+        assert_eq!(plan.depletion_year, Some(2026));
+        // Needs 12 * 10 + 12 * 6 = 192 at retirement: 80 more over 12 months
+        assert!((plan.extra_monthly_saving_needed.unwrap() - 80.0 / 12.0).abs() < 1e-9);
+        assert_eq!(plan.success_probability, 0.0);
+        assert_eq!(plan.simulated_depletion_age_p50, Some(61));
+        assert_eq!(plan.projection.len(), 4);
+        assert_eq!(plan.projection[1].expected_value, 112.0);
+        // Two years of withdrawals over 24 months at 0% is 50% a year
+        assert!((plan.sustainable_withdrawal_rate - 12.0 / 24.0 * 100.0).abs() < 1e-9);
+        assert!((plan.planned_withdrawal_rate.unwrap() - 120.0 / 112.0 * 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_extra_saving_makes_the_money_last() {
+        let base = RetirementInputs {
+            monthly_contribution: 2_000_000.0,
+            ..inputs()
+        };
+        let plan = calculate_retirement_plan(&base, 500_000_000.0, 2025, 200, 7);
+        let extra = plan.extra_monthly_saving_needed.unwrap();
+        assert!(extra > 0.0);
+        assert!(plan.depletion_age.unwrap() < base.life_expectancy);
+
+        let topped_up = RetirementInputs {
+            monthly_contribution: base.monthly_contribution + extra + 1.0,
+            ..base.clone()
+        };
+        let funded = calculate_retirement_plan(&topped_up, 500_000_000.0, 2025, 200, 7);
+        assert_eq!(funded.depletion_age, None);
+        assert_eq!(funded.extra_monthly_saving_needed, Some(0.0));
+        assert!(funded.success_probability > plan.success_probability);
+    }
+
+    #[test]
+    fn test_simulation_is_seeded() {
+        let first = calculate_retirement_plan(&inputs(), 1_000_000_000.0, 2025, 300, 11);
+        let second = calculate_retirement_plan(&inputs(), 1_000_000_000.0, 2025, 300, 11);
+        assert_eq!(first, second);
+        for point in &first.projection[1..] {
+            assert!(point.p10 <= point.p50 && point.p50 <= point.p90);
+        }
+    }
+
+    #[test]
+    fn test_already_retired() {
+        let retired = RetirementInputs {
+            current_age: 65,
+            retirement_age: 65,
+            pension_start_age: None,
+            ..inputs()
+        };
+        let plan = calculate_retirement_plan(&retired, 3_000_000_000.0, 2025, 50, 3);
+        assert_eq!(plan.extra_monthly_saving_needed, None);
+        assert_eq!(plan.value_at_retirement, 3_000_000_000.0);
+        assert_eq!(plan.retirement_year, 2025);
+    }
+}
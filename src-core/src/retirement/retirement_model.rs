@@ -0,0 +1,135 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Inputs of the retirement planner.
+///
+/// Amounts are in today's VND and assumed to keep pace with inflation, so the plan
+/// is calculated in real terms. Rates are annual percentages (`8.0` for 8%).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetirementInputs {
+    pub current_age: u32,
+    pub retirement_age: u32,
+    pub life_expectancy: u32,
+    /// Desired spending per month in retirement
+    pub monthly_spending: f64,
+    pub inflation_rate: f64,
+    /// Expected social insurance (BHXH) pension per month
+    #[serde(default)]
+    pub monthly_pension: f64,
+    /// Age the pension starts at; defaults to `retirement_age`
+    pub pension_start_age: Option<u32>,
+    /// Saving per month until retirement
+    #[serde(default)]
+    pub monthly_contribution: f64,
+    /// Nominal return expected before retirement
+    pub expected_return: f64,
+    /// Nominal return expected after retirement; defaults to `expected_return`
+    pub retirement_return: Option<f64>,
+    /// Annual volatility used by the simulation
+    #[serde(default)]
+    pub volatility: f64,
+    /// Portfolio value to start from; when empty, the latest valuation of
+    /// `account_ids` (or of every active account) in the base currency is used
+    pub current_value: Option<f64>,
+    #[serde(default)]
+    pub account_ids: Vec<String>,
+    pub num_paths: Option<u32>,
+    pub seed: Option<u64>,
+}
+
+/// Portfolio state at the end of one year of age, in today's money
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetirementProjectionPoint {
+    pub age: u32,
+    pub year: i32,
+    /// Value under the expected returns
+    pub expected_value: f64,
+    /// Percentiles of the simulated values
+    pub p10: f64,
+    pub p50: f64,
+    pub p90: f64,
+}
+
+/// Result of the retirement planner, in today's money
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetirementPlan {
+    pub current_value: f64,
+    pub retirement_year: i32,
+    /// Value at retirement under the expected returns
+    pub value_at_retirement: f64,
+    /// Yearly withdrawal, as a percentage of the value at retirement, that lasts
+    /// exactly until `life_expectancy` under the expected returns
+    pub sustainable_withdrawal_rate: f64,
+    /// Yearly withdrawal the desired spending needs in the first year of retirement,
+    /// net of the pension, as a percentage of the value at retirement; `None` when
+    /// nothing is left at retirement
+    pub planned_withdrawal_rate: Option<f64>,
+    /// When the money runs out under the expected returns; `None` if it lasts
+    pub depletion_age: Option<u32>,
+    pub depletion_year: Option<i32>,
+    /// Share of simulated paths (0-1) where the money lasts until `life_expectancy`
+    pub success_probability: f64,
+    /// Age by which the money has run out in 10% / 50% of simulated paths; `None`
+    /// when fewer paths than that run out
+    pub simulated_depletion_age_p10: Option<u32>,
+    pub simulated_depletion_age_p50: Option<u32>,
+    pub simulated_depletion_year_p10: Option<i32>,
+    pub simulated_depletion_year_p50: Option<i32>,
+    /// Additional monthly saving until retirement that makes the money last under
+    /// the expected returns; `None` once retired
+    pub extra_monthly_saving_needed: Option<f64>,
+    pub projection: Vec<RetirementProjectionPoint>,
+}
+
+/// A named set of planner inputs with the plan calculated when it was saved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetirementScenario {
+    pub id: String,
+    pub name: String,
+    pub inputs: RetirementInputs,
+    pub result: Option<RetirementPlan>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Input model for creating or replacing a scenario
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewRetirementScenario {
+    /// Replaces the scenario with this id when given
+    pub id: Option<String>,
+    pub name: String,
+    pub inputs: RetirementInputs,
+}
+
+#[derive(Queryable, Insertable, AsChangeset, Selectable, Debug, Clone)]
+#[diesel(table_name = crate::schema::retirement_scenarios)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[diesel(treat_none_as_null = true)]
+pub struct RetirementScenarioDB {
+    pub id: String,
+    pub name: String,
+    pub inputs: String,
+    pub result: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl TryFrom<RetirementScenarioDB> for RetirementScenario {
+    type Error = serde_json::Error;
+
+    fn try_from(db: RetirementScenarioDB) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: db.id,
+            name: db.name,
+            inputs: serde_json::from_str(&db.inputs)?,
+            result: db.result.as_deref().map(serde_json::from_str).transpose()?,
+            created_at: db.created_at,
+            updated_at: db.updated_at,
+        })
+    }
+}
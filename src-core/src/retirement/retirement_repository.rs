@@ -0,0 +1,75 @@
+use async_trait::async_trait;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::sqlite::SqliteConnection;
+use std::sync::Arc;
+
+use super::retirement_model::{RetirementScenario, RetirementScenarioDB};
+use super::retirement_traits::RetirementRepositoryTrait;
+use crate::db::{get_connection, WriteHandle};
+use crate::errors::Result;
+use crate::schema::retirement_scenarios;
+
+pub struct RetirementRepository {
+    pool: Arc<Pool<ConnectionManager<SqliteConnection>>>,
+    writer: WriteHandle,
+}
+
+impl RetirementRepository {
+    pub fn new(pool: Arc<Pool<ConnectionManager<SqliteConnection>>>, writer: WriteHandle) -> Self {
+        RetirementRepository { pool, writer }
+    }
+}
+
+#[async_trait]
+impl RetirementRepositoryTrait for RetirementRepository {
+    fn get_scenarios(&self) -> Result<Vec<RetirementScenario>> {
+        let mut conn = get_connection(&self.pool)?;
+        retirement_scenarios::table
+            .order(retirement_scenarios::updated_at.desc())
+            .load::<RetirementScenarioDB>(&mut conn)?
+            .into_iter()
+            .map(|db| Ok(RetirementScenario::try_from(db)?))
+            .collect()
+    }
+
+    fn get_scenario(&self, scenario_id: &str) -> Result<RetirementScenario> {
+        let mut conn = get_connection(&self.pool)?;
+        let db = retirement_scenarios::table
+            .find(scenario_id)
+            .first::<RetirementScenarioDB>(&mut conn)?;
+        Ok(RetirementScenario::try_from(db)?)
+    }
+
+    async fn save_scenario(&self, scenario: RetirementScenarioDB) -> Result<RetirementScenario> {
+        self.writer
+            .exec(
+                move |conn: &mut SqliteConnection| -> Result<RetirementScenario> {
+                    // Keep the original creation time when replacing a scenario
+                    let created_at = retirement_scenarios::table
+                        .find(&scenario.id)
+                        .select(retirement_scenarios::created_at)
+                        .first::<String>(conn)
+                        .optional()?;
+                    let row = RetirementScenarioDB {
+                        created_at: created_at.unwrap_or(scenario.created_at),
+                        ..scenario
+                    };
+                    diesel::replace_into(retirement_scenarios::table)
+                        .values(&row)
+                        .execute(conn)?;
+                    Ok(RetirementScenario::try_from(row)?)
+                },
+            )
+            .await
+    }
+
+    async fn delete_scenario(&self, scenario_id: &str) -> Result<usize> {
+        let id = scenario_id.to_string();
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<usize> {
+                Ok(diesel::delete(retirement_scenarios::table.find(&id)).execute(conn)?)
+            })
+            .await
+    }
+}
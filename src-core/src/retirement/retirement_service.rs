@@ -0,0 +1,165 @@
+use async_trait::async_trait;
+use chrono::{Datelike, Local, Utc};
+use log::debug;
+use num_traits::ToPrimitive;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::retirement_calculator::calculate_retirement_plan;
+use super::retirement_model::{
+    NewRetirementScenario, RetirementInputs, RetirementPlan, RetirementScenario,
+    RetirementScenarioDB,
+};
+use super::retirement_traits::{RetirementRepositoryTrait, RetirementServiceTrait};
+use crate::accounts::AccountServiceTrait;
+use crate::errors::{Error, Result, ValidationError};
+use crate::portfolio::valuation::ValuationServiceTrait;
+
+const DEFAULT_NUM_PATHS: u32 = 1_000;
+const MAX_NUM_PATHS: u32 = 10_000;
+const DEFAULT_SEED: u64 = 20_240_101;
+
+pub struct RetirementService {
+    repository: Arc<dyn RetirementRepositoryTrait>,
+    account_service: Arc<dyn AccountServiceTrait>,
+    valuation_service: Arc<dyn ValuationServiceTrait>,
+}
+
+impl RetirementService {
+    pub fn new(
+        repository: Arc<dyn RetirementRepositoryTrait>,
+        account_service: Arc<dyn AccountServiceTrait>,
+        valuation_service: Arc<dyn ValuationServiceTrait>,
+    ) -> Self {
+        Self {
+            repository,
+            account_service,
+            valuation_service,
+        }
+    }
+
+    fn invalid(message: &str) -> Error {
+        Error::Validation(ValidationError::InvalidInput(message.to_string()))
+    }
+
+    fn validate(inputs: &RetirementInputs) -> Result<()> {
+        if inputs.retirement_age < inputs.current_age {
+            return Err(Self::invalid("retirementAge cannot be below currentAge"));
+        }
+        if inputs.life_expectancy <= inputs.current_age
+            || inputs.life_expectancy < inputs.retirement_age
+        {
+            return Err(Self::invalid(
+                "lifeExpectancy must be above currentAge and not below retirementAge",
+            ));
+        }
+        let amounts = [
+            ("monthlySpending", inputs.monthly_spending),
+            ("monthlyPension", inputs.monthly_pension),
+            ("monthlyContribution", inputs.monthly_contribution),
+            ("volatility", inputs.volatility),
+        ];
+        for (name, value) in amounts {
+            if !value.is_finite() || value < 0.0 {
+                return Err(Self::invalid(&format!("{} cannot be negative", name)));
+            }
+        }
+        if matches!(inputs.current_value, Some(v) if !v.is_finite() || v < 0.0) {
+            return Err(Self::invalid("currentValue cannot be negative"));
+        }
+        let rates = [
+            Some(inputs.inflation_rate),
+            Some(inputs.expected_return),
+            inputs.retirement_return,
+        ];
+        if rates
+            .into_iter()
+            .flatten()
+            .any(|r| !r.is_finite() || r <= -100.0)
+        {
+            return Err(Self::invalid("Rates must be above -100%"));
+        }
+        Ok(())
+    }
+
+    /// Starting value in the base currency: the given value, or the latest valuation
+    /// of the selected accounts (every active account when none are selected)
+    fn current_value(&self, inputs: &RetirementInputs) -> Result<f64> {
+        if let Some(value) = inputs.current_value {
+            return Ok(value);
+        }
+        let account_ids = if inputs.account_ids.is_empty() {
+            self.account_service
+                .get_active_accounts()?
+                .into_iter()
+                .map(|a| a.id)
+                .collect()
+        } else {
+            inputs.account_ids.clone()
+        };
+        if account_ids.is_empty() {
+            return Ok(0.0);
+        }
+        Ok(self
+            .valuation_service
+            .get_latest_valuations(&account_ids)?
+            .into_iter()
+            .map(|v| (v.total_value * v.fx_rate_to_base).to_f64().unwrap_or(0.0))
+            .sum())
+    }
+}
+
+#[async_trait]
+impl RetirementServiceTrait for RetirementService {
+    async fn calculate_retirement_plan(&self, inputs: RetirementInputs) -> Result<RetirementPlan> {
+        Self::validate(&inputs)?;
+        let current_value = self.current_value(&inputs)?;
+        let num_paths = inputs
+            .num_paths
+            .unwrap_or(DEFAULT_NUM_PATHS)
+            .clamp(1, MAX_NUM_PATHS);
+        let seed = inputs.seed.unwrap_or(DEFAULT_SEED);
+        debug!(
+            "Calculating retirement plan from {} with {} paths",
+            current_value, num_paths
+        );
+        Ok(calculate_retirement_plan(
+            &inputs,
+            current_value,
+            Local::now().year(),
+            num_paths,
+            seed,
+        ))
+    }
+
+    fn get_scenarios(&self) -> Result<Vec<RetirementScenario>> {
+        self.repository.get_scenarios()
+    }
+
+    fn get_scenario(&self, scenario_id: &str) -> Result<RetirementScenario> {
+        self.repository.get_scenario(scenario_id)
+    }
+
+    async fn save_scenario(&self, scenario: NewRetirementScenario) -> Result<RetirementScenario> {
+        if scenario.name.trim().is_empty() {
+            return Err(Self::invalid("Scenario name cannot be empty"));
+        }
+        let plan = self
+            .calculate_retirement_plan(scenario.inputs.clone())
+            .await?;
+        let now = Utc::now().to_rfc3339();
+        let row = RetirementScenarioDB {
+            id: scenario.id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+            name: scenario.name.trim().to_string(),
+            inputs: serde_json::to_string(&scenario.inputs)?,
+            result: Some(serde_json::to_string(&plan)?),
+            created_at: now.clone(),
+            updated_at: now,
+        };
+        self.repository.save_scenario(row).await
+    }
+
+    async fn delete_scenario(&self, scenario_id: &str) -> Result<usize> {
+        self.repository.delete_scenario(scenario_id).await
+    }
+}
@@ -0,0 +1,28 @@
+use super::retirement_model::{
+    NewRetirementScenario, RetirementInputs, RetirementPlan, RetirementScenario,
+    RetirementScenarioDB,
+};
+use crate::errors::Result;
+use async_trait::async_trait;
+
+/// Trait defining the contract for retirement scenario repository operations.
+#[async_trait]
+pub trait RetirementRepositoryTrait: Send + Sync {
+    fn get_scenarios(&self) -> Result<Vec<RetirementScenario>>;
+    fn get_scenario(&self, scenario_id: &str) -> Result<RetirementScenario>;
+    /// Inserts or replaces a scenario by id
+    async fn save_scenario(&self, scenario: RetirementScenarioDB) -> Result<RetirementScenario>;
+    async fn delete_scenario(&self, scenario_id: &str) -> Result<usize>;
+}
+
+/// Trait defining the contract for retirement planner operations.
+#[async_trait]
+pub trait RetirementServiceTrait: Send + Sync {
+    /// Runs the planner without saving anything
+    async fn calculate_retirement_plan(&self, inputs: RetirementInputs) -> Result<RetirementPlan>;
+    fn get_scenarios(&self) -> Result<Vec<RetirementScenario>>;
+    fn get_scenario(&self, scenario_id: &str) -> Result<RetirementScenario>;
+    /// Runs the planner and stores the inputs with the result
+    async fn save_scenario(&self, scenario: NewRetirementScenario) -> Result<RetirementScenario>;
+    async fn delete_scenario(&self, scenario_id: &str) -> Result<usize>;
+}
@@ -1,5 +1,20 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    account_benchmarks (account_id) {
+        account_id -> Text,
+        symbol -> Text,
+        updated_at -> Text,
+    }
+}
+
+diesel::table! {
+    account_tags (account_id, tag_id) {
+        account_id -> Text,
+        tag_id -> Text,
+    }
+}
+
 diesel::table! {
     accounts (id) {
         id -> Text,
@@ -31,6 +46,34 @@ diesel::table! {
         comment -> Nullable<Text>,
         created_at -> Text,
         updated_at -> Text,
+        fx_rate_side -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    activity_attachments (id) {
+        id -> Text,
+        activity_id -> Text,
+        content_hash -> Text,
+        file_name -> Text,
+        mime_type -> Nullable<Text>,
+        size_bytes -> BigInt,
+        created_at -> Text,
+    }
+}
+
+diesel::table! {
+    activity_changes (id) {
+        id -> Text,
+        activity_id -> Text,
+        batch_id -> Nullable<Text>,
+        change_type -> Text,
+        before_state -> Nullable<Text>,
+        after_state -> Nullable<Text>,
+        actor -> Text,
+        changed_at -> Text,
+        reverted_change_id -> Nullable<Text>,
+        dependents_state -> Nullable<Text>,
     }
 }
 
@@ -46,6 +89,22 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    activity_tags (activity_id, tag_id) {
+        activity_id -> Text,
+        tag_id -> Text,
+    }
+}
+
+diesel::table! {
+    activity_transfer_pairs (id) {
+        id -> Text,
+        transfer_out_activity_id -> Text,
+        transfer_in_activity_id -> Text,
+        created_at -> Text,
+    }
+}
+
 diesel::table! {
     app_settings (setting_key) {
         setting_key -> Text,
@@ -77,6 +136,24 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    composite_portfolio_members (composite_id, member_type, member_id) {
+        composite_id -> Text,
+        member_type -> Text,
+        member_id -> Text,
+    }
+}
+
+diesel::table! {
+    composite_portfolios (id) {
+        id -> Text,
+        name -> Text,
+        description -> Nullable<Text>,
+        created_at -> Text,
+        updated_at -> Text,
+    }
+}
+
 diesel::table! {
     contribution_limits (id) {
         id -> Text,
@@ -88,6 +165,11 @@ diesel::table! {
         updated_at -> Timestamp,
         start_date -> Nullable<Timestamp>,
         end_date -> Nullable<Timestamp>,
+        limit_type -> Text,
+        window_months -> Nullable<Integer>,
+        carry_forward -> Bool,
+        restore_withdrawals -> Bool,
+        lifetime_cap -> Nullable<Double>,
     }
 }
 
@@ -120,6 +202,7 @@ diesel::table! {
         monthly_investment -> Nullable<Double>,
         start_date -> Nullable<Text>,
         initial_actual_value -> Nullable<Double>,
+        priority -> Nullable<Integer>,
     }
 }
 
@@ -165,6 +248,29 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    margin_accounts (account_id) {
+        account_id -> Text,
+        annual_interest_rate -> Text,
+        call_ratio -> Text,
+        force_sell_ratio -> Nullable<Text>,
+        warning_buffer -> Text,
+        default_haircut -> Text,
+        interest_start_date -> Date,
+        accrued_through -> Nullable<Date>,
+        created_at -> Text,
+        updated_at -> Text,
+    }
+}
+
+diesel::table! {
+    margin_haircuts (account_id, asset_id) {
+        account_id -> Text,
+        asset_id -> Text,
+        haircut -> Text,
+    }
+}
+
 diesel::table! {
     market_data_providers (id) {
         id -> Text,
@@ -205,6 +311,76 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    reconciliation_lines (reconciliation_id, line_type, code) {
+        reconciliation_id -> Text,
+        line_type -> Text,
+        code -> Text,
+        reported -> Text,
+        calculated -> Text,
+    }
+}
+
+diesel::table! {
+    reconciliations (id) {
+        id -> Text,
+        account_id -> Text,
+        statement_date -> Date,
+        status -> Text,
+        notes -> Nullable<Text>,
+        broken_at -> Nullable<Text>,
+        broken_by_activity_id -> Nullable<Text>,
+        created_at -> Text,
+        updated_at -> Text,
+    }
+}
+
+diesel::table! {
+    retirement_scenarios (id) {
+        id -> Text,
+        name -> Text,
+        inputs -> Text,
+        result -> Nullable<Text>,
+        created_at -> Text,
+        updated_at -> Text,
+    }
+}
+
+diesel::table! {
+    risk_free_rates (id) {
+        id -> Text,
+        series -> Text,
+        effective_date -> Date,
+        annual_rate -> Text,
+        created_at -> Text,
+        updated_at -> Text,
+    }
+}
+
+diesel::table! {
+    tags (id) {
+        id -> Text,
+        name -> Text,
+        color -> Nullable<Text>,
+        description -> Nullable<Text>,
+        created_at -> Text,
+        updated_at -> Text,
+    }
+}
+
+diesel::table! {
+    valuation_checkpoints (account_id) {
+        account_id -> Text,
+        holdings_dirty_from -> Nullable<Date>,
+        valuation_dirty_from -> Nullable<Date>,
+        holdings_calculated_through -> Nullable<Date>,
+        valuation_calculated_through -> Nullable<Date>,
+        updated_at -> Text,
+        holdings_dirty_version -> BigInt,
+        valuation_dirty_version -> BigInt,
+    }
+}
+
 diesel::table! {
     vn_assets (id) {
         id -> Nullable<Text>,
@@ -254,6 +430,16 @@ diesel::joinable!(goals_allocation -> accounts (account_id));
 diesel::joinable!(goals_allocation -> goals (goal_id));
 diesel::joinable!(allocation_versions -> goals_allocation (allocation_id));
 diesel::joinable!(quotes -> assets (symbol));
+diesel::joinable!(activity_attachments -> activities (activity_id));
+diesel::joinable!(account_tags -> accounts (account_id));
+diesel::joinable!(account_tags -> tags (tag_id));
+diesel::joinable!(activity_tags -> activities (activity_id));
+diesel::joinable!(activity_tags -> tags (tag_id));
+diesel::joinable!(composite_portfolio_members -> composite_portfolios (composite_id));
+diesel::joinable!(margin_accounts -> accounts (account_id));
+diesel::joinable!(margin_haircuts -> margin_accounts (account_id));
+diesel::joinable!(reconciliations -> accounts (account_id));
+diesel::joinable!(reconciliation_lines -> reconciliations (reconciliation_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
-    accounts,activities,activity_import_profiles,app_settings,assets,contribution_limits,daily_account_valuation,goals,goals_allocation,allocation_versions,holdings_snapshots,market_data_providers,platforms,quotes,vn_assets,vn_assets_sync,vn_historical_records,);
+    account_benchmarks,account_tags,accounts,activities,activity_attachments,activity_changes,activity_import_profiles,activity_tags,activity_transfer_pairs,app_settings,assets,composite_portfolio_members,composite_portfolios,contribution_limits,daily_account_valuation,goals,goals_allocation,allocation_versions,holdings_snapshots,margin_accounts,margin_haircuts,market_data_providers,platforms,quotes,reconciliation_lines,reconciliations,retirement_scenarios,risk_free_rates,tags,valuation_checkpoints,vn_assets,vn_assets_sync,vn_historical_records,);
@@ -2,6 +2,8 @@ use diesel::prelude::*;
 use diesel::Queryable;
 use serde::{Deserialize, Serialize};
 
+use crate::constants::DEFAULT_RISK_FREE_RATE_SERIES;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Settings {
@@ -16,6 +18,7 @@ pub struct Settings {
     pub is_pro: bool,
     pub sync_enabled: bool,
     pub language: String,
+    pub risk_free_rate_series: String,
 }
 
 impl Default for Settings {
@@ -32,6 +35,7 @@ impl Default for Settings {
             is_pro: false,
             sync_enabled: true,
             language: "en".to_string(),
+            risk_free_rate_series: DEFAULT_RISK_FREE_RATE_SERIES.to_string(),
         }
     }
 }
@@ -49,6 +53,7 @@ pub struct SettingsUpdate {
     pub is_pro: Option<bool>,
     pub sync_enabled: Option<bool>,
     pub language: Option<String>,
+    pub risk_free_rate_series: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -64,6 +64,7 @@ impl SettingsRepositoryTrait for SettingsRepository {
                     settings.sync_enabled = value.parse().unwrap_or(true);
                 }
                 "language" => settings.language = value,
+                "risk_free_rate_series" => settings.risk_free_rate_series = value,
                 _ => {} // Ignore unknown settings
             }
         }
@@ -166,6 +167,15 @@ impl SettingsRepositoryTrait for SettingsRepository {
                         .execute(conn)?;
                 }
 
+                if let Some(ref risk_free_rate_series) = settings.risk_free_rate_series {
+                    diesel::replace_into(app_settings)
+                        .values(&AppSetting {
+                            setting_key: "risk_free_rate_series".to_string(),
+                            setting_value: risk_free_rate_series.trim().to_string(),
+                        })
+                        .execute(conn)?;
+                }
+
                 Ok(())
             })
             .await
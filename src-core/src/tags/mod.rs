@@ -0,0 +1,9 @@
+mod tags_model;
+mod tags_repository;
+mod tags_service;
+mod tags_traits;
+
+pub use tags_model::{AccountTagDB, ActivityTagDB, NewTag, Tag, TagUpdate};
+pub use tags_repository::TagRepository;
+pub use tags_service::TagService;
+pub use tags_traits::{TagRepositoryTrait, TagServiceTrait};
@@ -0,0 +1,52 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A user-defined label used to group activities and accounts
+#[derive(Queryable, Insertable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = crate::schema::tags)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[serde(rename_all = "camelCase")]
+pub struct Tag {
+    pub id: String,
+    pub name: String,
+    pub color: Option<String>,
+    pub description: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Input model for creating a new tag
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NewTag {
+    pub id: Option<String>,
+    pub name: String,
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Input model for updating an existing tag
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TagUpdate {
+    pub id: String,
+    pub name: String,
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Link between an activity and a tag
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = crate::schema::activity_tags)]
+pub struct ActivityTagDB {
+    pub activity_id: String,
+    pub tag_id: String,
+}
+
+/// Link between an account and a tag
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = crate::schema::account_tags)]
+pub struct AccountTagDB {
+    pub account_id: String,
+    pub tag_id: String,
+}
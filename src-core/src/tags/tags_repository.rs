@@ -0,0 +1,183 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::sqlite::SqliteConnection;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::tags_model::{AccountTagDB, ActivityTagDB, NewTag, Tag, TagUpdate};
+use super::tags_traits::TagRepositoryTrait;
+use crate::db::{get_connection, WriteHandle};
+use crate::errors::{Error, Result};
+use crate::schema::{account_tags, activity_tags, tags};
+
+pub struct TagRepository {
+    pool: Arc<Pool<ConnectionManager<SqliteConnection>>>,
+    writer: WriteHandle,
+}
+
+impl TagRepository {
+    pub fn new(pool: Arc<Pool<ConnectionManager<SqliteConnection>>>, writer: WriteHandle) -> Self {
+        TagRepository { pool, writer }
+    }
+}
+
+fn load_activity_tags(conn: &mut SqliteConnection, activity_id: &str) -> Result<Vec<Tag>> {
+    tags::table
+        .inner_join(activity_tags::table)
+        .filter(activity_tags::activity_id.eq(activity_id))
+        .select(Tag::as_select())
+        .order(tags::name.asc())
+        .load::<Tag>(conn)
+        .map_err(Error::from)
+}
+
+fn load_account_tags(conn: &mut SqliteConnection, account_id: &str) -> Result<Vec<Tag>> {
+    tags::table
+        .inner_join(account_tags::table)
+        .filter(account_tags::account_id.eq(account_id))
+        .select(Tag::as_select())
+        .order(tags::name.asc())
+        .load::<Tag>(conn)
+        .map_err(Error::from)
+}
+
+#[async_trait]
+impl TagRepositoryTrait for TagRepository {
+    fn get_tags(&self) -> Result<Vec<Tag>> {
+        let mut conn = get_connection(&self.pool)?;
+        tags::table
+            .order(tags::name.asc())
+            .load::<Tag>(&mut conn)
+            .map_err(Error::from)
+    }
+
+    fn get_tag(&self, tag_id: &str) -> Result<Tag> {
+        let mut conn = get_connection(&self.pool)?;
+        tags::table
+            .find(tag_id)
+            .first::<Tag>(&mut conn)
+            .map_err(Error::from)
+    }
+
+    async fn create_tag(&self, new_tag: NewTag) -> Result<Tag> {
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<Tag> {
+                let now = Utc::now().to_rfc3339();
+                let tag = Tag {
+                    id: new_tag.id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+                    name: new_tag.name,
+                    color: new_tag.color,
+                    description: new_tag.description,
+                    created_at: now.clone(),
+                    updated_at: now,
+                };
+                diesel::insert_into(tags::table)
+                    .values(&tag)
+                    .execute(conn)?;
+                Ok(tag)
+            })
+            .await
+    }
+
+    async fn update_tag(&self, tag_update: TagUpdate) -> Result<Tag> {
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<Tag> {
+                diesel::update(tags::table.find(&tag_update.id))
+                    .set((
+                        tags::name.eq(&tag_update.name),
+                        tags::color.eq(&tag_update.color),
+                        tags::description.eq(&tag_update.description),
+                        tags::updated_at.eq(Utc::now().to_rfc3339()),
+                    ))
+                    .execute(conn)?;
+                tags::table
+                    .find(&tag_update.id)
+                    .first::<Tag>(conn)
+                    .map_err(Error::from)
+            })
+            .await
+    }
+
+    async fn delete_tag(&self, tag_id: &str) -> Result<usize> {
+        let tag_id_owned = tag_id.to_string();
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<usize> {
+                // Links are removed by ON DELETE CASCADE
+                diesel::delete(tags::table.find(tag_id_owned))
+                    .execute(conn)
+                    .map_err(Error::from)
+            })
+            .await
+    }
+
+    fn get_activity_tags(&self, activity_id: &str) -> Result<Vec<Tag>> {
+        let mut conn = get_connection(&self.pool)?;
+        load_activity_tags(&mut conn, activity_id)
+    }
+
+    fn get_account_tags(&self, account_id: &str) -> Result<Vec<Tag>> {
+        let mut conn = get_connection(&self.pool)?;
+        load_account_tags(&mut conn, account_id)
+    }
+
+    async fn set_activity_tags(&self, activity_id: &str, tag_ids: Vec<String>) -> Result<Vec<Tag>> {
+        let activity_id_owned = activity_id.to_string();
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<Vec<Tag>> {
+                diesel::delete(
+                    activity_tags::table.filter(activity_tags::activity_id.eq(&activity_id_owned)),
+                )
+                .execute(conn)?;
+                let links: Vec<ActivityTagDB> = tag_ids
+                    .into_iter()
+                    .map(|tag_id| ActivityTagDB {
+                        activity_id: activity_id_owned.clone(),
+                        tag_id,
+                    })
+                    .collect();
+                if !links.is_empty() {
+                    diesel::insert_or_ignore_into(activity_tags::table)
+                        .values(&links)
+                        .execute(conn)?;
+                }
+                load_activity_tags(conn, &activity_id_owned)
+            })
+            .await
+    }
+
+    async fn set_account_tags(&self, account_id: &str, tag_ids: Vec<String>) -> Result<Vec<Tag>> {
+        let account_id_owned = account_id.to_string();
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<Vec<Tag>> {
+                diesel::delete(
+                    account_tags::table.filter(account_tags::account_id.eq(&account_id_owned)),
+                )
+                .execute(conn)?;
+                let links: Vec<AccountTagDB> = tag_ids
+                    .into_iter()
+                    .map(|tag_id| AccountTagDB {
+                        account_id: account_id_owned.clone(),
+                        tag_id,
+                    })
+                    .collect();
+                if !links.is_empty() {
+                    diesel::insert_or_ignore_into(account_tags::table)
+                        .values(&links)
+                        .execute(conn)?;
+                }
+                load_account_tags(conn, &account_id_owned)
+            })
+            .await
+    }
+
+    fn get_account_ids_by_tag(&self, tag_id: &str) -> Result<Vec<String>> {
+        let mut conn = get_connection(&self.pool)?;
+        account_tags::table
+            .filter(account_tags::tag_id.eq(tag_id))
+            .select(account_tags::account_id)
+            .load::<String>(&mut conn)
+            .map_err(Error::from)
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use log::debug;
+
+use super::tags_model::{NewTag, Tag, TagUpdate};
+use super::tags_traits::{TagRepositoryTrait, TagServiceTrait};
+use crate::errors::{Error, Result, ValidationError};
+
+pub struct TagService {
+    tag_repository: Arc<dyn TagRepositoryTrait>,
+}
+
+impl TagService {
+    pub fn new(tag_repository: Arc<dyn TagRepositoryTrait>) -> Self {
+        TagService { tag_repository }
+    }
+
+    fn normalize_name(name: &str) -> Result<String> {
+        let trimmed = name.trim();
+        if trimmed.is_empty() {
+            return Err(Error::Validation(ValidationError::MissingField(
+                "name".to_string(),
+            )));
+        }
+        Ok(trimmed.to_string())
+    }
+
+    /// Drops blank and duplicate IDs while keeping the caller's order
+    fn normalize_tag_ids(tag_ids: Vec<String>) -> Vec<String> {
+        let mut seen = HashSet::new();
+        tag_ids
+            .into_iter()
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty() && seen.insert(id.clone()))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl TagServiceTrait for TagService {
+    fn get_tags(&self) -> Result<Vec<Tag>> {
+        self.tag_repository.get_tags()
+    }
+
+    fn get_tag(&self, tag_id: &str) -> Result<Tag> {
+        self.tag_repository.get_tag(tag_id)
+    }
+
+    async fn create_tag(&self, new_tag: NewTag) -> Result<Tag> {
+        let name = Self::normalize_name(&new_tag.name)?;
+        debug!("Creating tag '{}'", name);
+        self.tag_repository
+            .create_tag(NewTag { name, ..new_tag })
+            .await
+    }
+
+    async fn update_tag(&self, tag_update: TagUpdate) -> Result<Tag> {
+        let name = Self::normalize_name(&tag_update.name)?;
+        self.tag_repository
+            .update_tag(TagUpdate { name, ..tag_update })
+            .await
+    }
+
+    async fn delete_tag(&self, tag_id: &str) -> Result<usize> {
+        self.tag_repository.delete_tag(tag_id).await
+    }
+
+    fn get_activity_tags(&self, activity_id: &str) -> Result<Vec<Tag>> {
+        self.tag_repository.get_activity_tags(activity_id)
+    }
+
+    fn get_account_tags(&self, account_id: &str) -> Result<Vec<Tag>> {
+        self.tag_repository.get_account_tags(account_id)
+    }
+
+    async fn set_activity_tags(&self, activity_id: &str, tag_ids: Vec<String>) -> Result<Vec<Tag>> {
+        self.tag_repository
+            .set_activity_tags(activity_id, Self::normalize_tag_ids(tag_ids))
+            .await
+    }
+
+    async fn set_account_tags(&self, account_id: &str, tag_ids: Vec<String>) -> Result<Vec<Tag>> {
+        self.tag_repository
+            .set_account_tags(account_id, Self::normalize_tag_ids(tag_ids))
+            .await
+    }
+
+    fn get_account_ids_by_tag(&self, tag_id: &str) -> Result<Vec<String>> {
+        self.tag_repository.get_account_ids_by_tag(tag_id)
+    }
+}
@@ -0,0 +1,35 @@
+use super::tags_model::{NewTag, Tag, TagUpdate};
+use crate::errors::Result;
+use async_trait::async_trait;
+
+/// Trait defining the contract for Tag repository operations.
+#[async_trait]
+pub trait TagRepositoryTrait: Send + Sync {
+    fn get_tags(&self) -> Result<Vec<Tag>>;
+    fn get_tag(&self, tag_id: &str) -> Result<Tag>;
+    async fn create_tag(&self, new_tag: NewTag) -> Result<Tag>;
+    async fn update_tag(&self, tag_update: TagUpdate) -> Result<Tag>;
+    async fn delete_tag(&self, tag_id: &str) -> Result<usize>;
+    fn get_activity_tags(&self, activity_id: &str) -> Result<Vec<Tag>>;
+    fn get_account_tags(&self, account_id: &str) -> Result<Vec<Tag>>;
+    /// Replaces the full set of tags attached to an activity
+    async fn set_activity_tags(&self, activity_id: &str, tag_ids: Vec<String>) -> Result<Vec<Tag>>;
+    /// Replaces the full set of tags attached to an account
+    async fn set_account_tags(&self, account_id: &str, tag_ids: Vec<String>) -> Result<Vec<Tag>>;
+    fn get_account_ids_by_tag(&self, tag_id: &str) -> Result<Vec<String>>;
+}
+
+/// Trait defining the contract for Tag service operations.
+#[async_trait]
+pub trait TagServiceTrait: Send + Sync {
+    fn get_tags(&self) -> Result<Vec<Tag>>;
+    fn get_tag(&self, tag_id: &str) -> Result<Tag>;
+    async fn create_tag(&self, new_tag: NewTag) -> Result<Tag>;
+    async fn update_tag(&self, tag_update: TagUpdate) -> Result<Tag>;
+    async fn delete_tag(&self, tag_id: &str) -> Result<usize>;
+    fn get_activity_tags(&self, activity_id: &str) -> Result<Vec<Tag>>;
+    fn get_account_tags(&self, account_id: &str) -> Result<Vec<Tag>>;
+    async fn set_activity_tags(&self, activity_id: &str, tag_ids: Vec<String>) -> Result<Vec<Tag>>;
+    async fn set_account_tags(&self, account_id: &str, tag_ids: Vec<String>) -> Result<Vec<Tag>>;
+    fn get_account_ids_by_tag(&self, tag_id: &str) -> Result<Vec<String>>;
+}
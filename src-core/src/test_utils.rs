@@ -0,0 +1,101 @@
+//! Shared helpers for unit tests
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use diesel::connection::SimpleConnection;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool, WriteHandle};
+use crate::goals::goals_model::Goal;
+
+pub fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+/// An open goal without a savings plan
+pub fn goal(id: &str, target_amount: f64, priority: Option<i32>) -> Goal {
+    Goal {
+        id: id.to_string(),
+        title: id.to_string(),
+        description: None,
+        target_amount,
+        is_achieved: false,
+        target_return_rate: None,
+        due_date: None,
+        monthly_investment: None,
+        start_date: None,
+        initial_actual_value: None,
+        priority,
+    }
+}
+
+/// A goal saving toward 1bn from 2025-01-01 to 2030-01-01
+pub fn planned_goal(target_return_rate: f64, monthly_investment: f64) -> Goal {
+    Goal {
+        title: "House".to_string(),
+        target_return_rate: Some(target_return_rate),
+        due_date: Some("2030-01-01".to_string()),
+        monthly_investment: Some(monthly_investment),
+        start_date: Some("2025-01-01".to_string()),
+        ..goal("g1", 1_000_000_000.0, None)
+    }
+}
+
+/// A migrated SQLite database in a temporary file, removed on drop.
+/// Needs a Tokio runtime for its writer actor.
+pub struct TestDb {
+    pub pool: Arc<DbPool>,
+    pub writer: WriteHandle,
+    path: PathBuf,
+}
+
+impl TestDb {
+    pub fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("wealthvn-test-{}.db", Uuid::new_v4()));
+        let pool = db::create_pool(path.to_str().unwrap()).unwrap();
+        db::run_migrations(&pool).unwrap();
+        let writer = db::write_actor::spawn_writer(pool.as_ref().clone());
+        Self { pool, writer, path }
+    }
+
+    /// Runs raw SQL, for seeding rows the test does not exercise
+    pub fn execute(&self, sql: &str) {
+        db::get_connection(&self.pool)
+            .unwrap()
+            .batch_execute(sql)
+            .unwrap();
+    }
+
+    pub fn insert_account(&self, id: &str, currency: &str) {
+        self.execute(&format!(
+            "INSERT INTO accounts (id, name, account_type, currency, is_default, is_active, created_at, updated_at)
+             VALUES ('{id}', '{id}', 'SECURITIES', '{currency}', 0, 1, '2024-01-01 00:00:00', '2024-01-01 00:00:00');"
+        ));
+    }
+
+    pub fn insert_asset(&self, id: &str, currency: &str) {
+        self.execute(&format!(
+            "INSERT INTO assets (id, symbol, currency, data_source, created_at, updated_at)
+             VALUES ('{id}', '{id}', '{currency}', 'MANUAL', '2024-01-01 00:00:00', '2024-01-01 00:00:00');"
+        ));
+    }
+
+    pub fn insert_tag(&self, id: &str) {
+        self.execute(&format!(
+            "INSERT INTO tags (id, name, created_at, updated_at)
+             VALUES ('{id}', '{id}', '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z');"
+        ));
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        for suffix in ["", "-wal", "-shm"] {
+            let mut file = self.path.clone().into_os_string();
+            file.push(suffix);
+            let _ = std::fs::remove_file(file);
+        }
+    }
+}
@@ -1,9 +1,13 @@
 //! API clients for Vietnamese market data providers
 
 pub mod fmarket_client;
+pub mod sbv_client;
 pub mod sjc_client;
+pub mod vcb_client;
 pub mod vci_client;
 
 pub use fmarket_client::FMarketClient;
+pub use sbv_client::SbvClient;
 pub use sjc_client::SjcClient;
+pub use vcb_client::VcbClient;
 pub use vci_client::VciClient;
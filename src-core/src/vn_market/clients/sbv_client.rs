@@ -0,0 +1,136 @@
+//! State Bank of Vietnam central exchange rate client
+
+use chrono::NaiveDate;
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::Client;
+use std::time::Duration;
+
+use crate::vn_market::errors::VnMarketError;
+use crate::vn_market::models::fx::{parse_sbv_number, SbvCentralRate};
+use crate::vn_market::utils::headers::sbv_headers;
+
+const SBV_URL: &str = "https://dttktt.sbv.gov.vn/TyGia/faces/TyGiaTrungTam.jspx";
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+
+lazy_static! {
+    /// "... áp dụng cho ngày 15/01/2024 ... 1 Đô la Mỹ = 24.000 VND"
+    static ref CENTRAL_RATE_RE: Regex = Regex::new(
+        r"(?is)ngày\s*(\d{1,2}/\d{1,2}/\d{4}).*?1\s*(?:USD|Đô\s*la\s*Mỹ)\s*=\s*([\d.,]+)"
+    )
+    .expect("Invalid SBV central rate regex");
+}
+
+/// State Bank of Vietnam central rate client
+#[derive(Clone)]
+pub struct SbvClient {
+    client: Client,
+}
+
+impl SbvClient {
+    /// Create a new SBV client
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .default_headers(sbv_headers())
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self { client }
+    }
+
+    /// Get the VND/USD central rate published for a date
+    pub async fn get_central_rate(&self, date: NaiveDate) -> Result<SbvCentralRate, VnMarketError> {
+        let response = self
+            .client
+            .get(SBV_URL)
+            // Date parameter of the public rate page; unverified against a live
+            // response, see `test_get_central_rate`
+            .query(&[("ngay", date.format("%d/%m/%Y").to_string())])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(VnMarketError::ApiError(format!(
+                "SBV request failed: {}",
+                response.status()
+            )));
+        }
+
+        let body = response.text().await?;
+        parse_central_rate(&body, date)
+    }
+}
+
+impl Default for SbvClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse the central rate for `date` from the SBV rate page.
+///
+/// The page shows the latest fixing on or before the requested date; a fixing
+/// for another date is reported as `NoData`.
+pub fn parse_central_rate(html: &str, date: NaiveDate) -> Result<SbvCentralRate, VnMarketError> {
+    let no_data = || VnMarketError::NoData {
+        symbol: "SBV".to_string(),
+        date: date.to_string(),
+    };
+
+    let captures = CENTRAL_RATE_RE.captures(html).ok_or_else(no_data)?;
+    let rate_date = NaiveDate::parse_from_str(&captures[1], "%d/%m/%Y")
+        .map_err(|e| VnMarketError::ParseError(format!("SBV rate date {}: {}", &captures[1], e)))?;
+    let rate = parse_sbv_number(&captures[2])
+        .ok_or_else(|| VnMarketError::ParseError(format!("SBV central rate: {}", &captures[2])))?;
+
+    if rate_date != date {
+        return Err(no_data());
+    }
+
+    Ok(SbvCentralRate {
+        date: rate_date,
+        rate,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::date;
+    use rust_decimal_macros::dec;
+
+    /// Hand-written from the layout of the central rate page for 2024-01-15,
+    /// not a recorded response; `test_get_central_rate` checks the live page
+    const SBV_FIXTURE: &str = r#"
+        <table class="jrPage">
+          <tr><td><span class="jrText">TỶ GIÁ TRUNG TÂM CỦA ĐỒNG VIỆT NAM VỚI ĐÔ LA MỸ</span></td></tr>
+          <tr><td><span>Áp dụng cho ngày 15/01/2024</span></td></tr>
+          <tr><td><span>1 Đô la Mỹ = 24.000 VND</span></td></tr>
+        </table>"#;
+
+    #[test]
+    fn test_parse_fixture() {
+        let rate = parse_central_rate(SBV_FIXTURE, date(2024, 1, 15)).unwrap();
+        assert_eq!(rate.date, date(2024, 1, 15));
+        assert_eq!(rate.rate, dec!(24000));
+    }
+
+    #[test]
+    fn test_other_fixing_is_no_data() {
+        let result = parse_central_rate(SBV_FIXTURE, date(2024, 1, 14));
+        assert!(matches!(result, Err(VnMarketError::NoData { .. })));
+
+        let result = parse_central_rate("<html></html>", date(2024, 1, 15));
+        assert!(matches!(result, Err(VnMarketError::NoData { .. })));
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires network access
+    async fn test_get_central_rate() {
+        let client = SbvClient::new();
+        let rate = client.get_central_rate(date(2024, 1, 15)).await.unwrap();
+
+        assert!(rate.rate > dec!(20000));
+    }
+}
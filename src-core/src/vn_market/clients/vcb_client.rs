@@ -0,0 +1,146 @@
+//! Vietcombank exchange rate API client
+
+use chrono::NaiveDate;
+use reqwest::Client;
+use std::time::Duration;
+
+use crate::vn_market::errors::VnMarketError;
+use crate::vn_market::models::fx::{VcbRate, VcbResponse};
+use crate::vn_market::utils::headers::vcb_headers;
+
+const VCB_URL: &str = "https://www.vietcombank.com.vn/api/exchangerates";
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// Vietcombank exchange rate API client
+#[derive(Clone)]
+pub struct VcbClient {
+    client: Client,
+}
+
+impl VcbClient {
+    /// Create a new Vietcombank client
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .default_headers(vcb_headers())
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self { client }
+    }
+
+    /// Get the buy/transfer/sell rates of all quoted currencies for a date
+    pub async fn get_rates(&self, date: NaiveDate) -> Result<Vec<VcbRate>, VnMarketError> {
+        let response = self
+            .client
+            .get(VCB_URL)
+            .query(&[("date", date.format("%Y-%m-%d").to_string())])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(VnMarketError::ApiError(format!(
+                "Vietcombank request failed: {}",
+                response.status()
+            )));
+        }
+
+        let body = response.text().await?;
+        parse_vcb_response(&body, date)
+    }
+}
+
+impl Default for VcbClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse a Vietcombank response body into the rates for `date`.
+///
+/// On days without a fixing (e.g. Sundays and holidays) the API repeats the
+/// previous rates under their own date; those are reported as `NoData` so the
+/// same fixing is not stored twice.
+pub fn parse_vcb_response(body: &str, date: NaiveDate) -> Result<Vec<VcbRate>, VnMarketError> {
+    let response: VcbResponse = serde_json::from_str(body)
+        .map_err(|e| VnMarketError::ParseError(format!("Vietcombank response: {}", e)))?;
+
+    let rate_date = response
+        .date
+        .get(..10)
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .ok_or_else(|| {
+            VnMarketError::ParseError(format!("Vietcombank rate date: {}", response.date))
+        })?;
+
+    if rate_date != date || response.data.is_empty() {
+        return Err(VnMarketError::NoData {
+            symbol: "VCB".to_string(),
+            date: date.to_string(),
+        });
+    }
+
+    Ok(response
+        .data
+        .iter()
+        .map(|record| VcbRate::from_record(rate_date, record))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::date;
+    use rust_decimal_macros::dec;
+
+    /// Hand-written in the shape of the API response for 2024-01-15, not a
+    /// recorded one; `test_get_rates` checks the live API
+    const VCB_FIXTURE: &str = r#"{
+        "Count": 3,
+        "Date": "2024-01-15T00:00:00",
+        "UpdatedDate": "2024-01-15T08:31:04+07:00",
+        "Data": [
+            {"currencyName": "US DOLLAR", "currencyCode": "USD", "cash": "24,170.00", "transfer": "24,200.00", "sell": "24,540.00", "icon": "usd.png"},
+            {"currencyName": "EURO", "currencyCode": "EUR", "cash": "25,953.30", "transfer": "26,215.46", "sell": "27,375.89", "icon": "eur.png"},
+            {"currencyName": "KUWAITI DINAR", "currencyCode": "KWD", "cash": "-", "transfer": "78,630.45", "sell": "81,773.55", "icon": "kwd.png"}
+        ]
+    }"#;
+
+    #[test]
+    fn test_parse_fixture() {
+        let rates = parse_vcb_response(VCB_FIXTURE, date(2024, 1, 15)).unwrap();
+        assert_eq!(rates.len(), 3);
+
+        let usd = &rates[0];
+        assert_eq!(usd.currency, "USD");
+        assert_eq!(usd.cash_buy, Some(dec!(24170)));
+        assert_eq!(usd.transfer_buy, Some(dec!(24200)));
+        assert_eq!(usd.sell, Some(dec!(24540)));
+
+        let kwd = &rates[2];
+        assert_eq!(kwd.cash_buy, None);
+        assert_eq!(kwd.buy(), Some(dec!(78630.45)));
+    }
+
+    #[test]
+    fn test_repeated_fixing_is_no_data() {
+        let result = parse_vcb_response(VCB_FIXTURE, date(2024, 1, 16));
+        assert!(matches!(result, Err(VnMarketError::NoData { .. })));
+    }
+
+    #[test]
+    fn test_invalid_body_is_parse_error() {
+        let result = parse_vcb_response("<html></html>", date(2024, 1, 15));
+        assert!(matches!(result, Err(VnMarketError::ParseError(_))));
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires network access
+    async fn test_get_rates() {
+        let client = VcbClient::new();
+        let rates = client.get_rates(date(2024, 1, 15)).await.unwrap();
+
+        let usd = rates.iter().find(|r| r.currency == "USD").unwrap();
+        assert!(usd.sell.unwrap() > dec!(20000));
+    }
+}
@@ -0,0 +1,177 @@
+//! Exchange rate models for Vietcombank and State Bank of Vietnam
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::str::FromStr;
+
+/// Exchange rate response from the Vietcombank API
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct VcbResponse {
+    /// Date the rates apply to (e.g., "2024-01-15T00:00:00")
+    pub date: String,
+
+    /// Rates per currency
+    #[serde(default)]
+    pub data: Vec<VcbRateRecord>,
+}
+
+/// Raw rate record from Vietcombank, numbers formatted as "24,080.00" or "-"
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VcbRateRecord {
+    /// ISO currency code (e.g., "USD")
+    pub currency_code: String,
+
+    /// Currency name (e.g., "US DOLLAR")
+    #[serde(default)]
+    pub currency_name: String,
+
+    /// Cash buying rate
+    #[serde(default)]
+    pub cash: Option<String>,
+
+    /// Transfer buying rate
+    #[serde(default)]
+    pub transfer: Option<String>,
+
+    /// Selling rate
+    #[serde(default)]
+    pub sell: Option<String>,
+}
+
+/// Vietcombank rates for one currency, in VND per unit
+#[derive(Debug, Clone, PartialEq)]
+pub struct VcbRate {
+    /// ISO currency code
+    pub currency: String,
+    /// Rate date
+    pub date: NaiveDate,
+    /// Cash buying rate (not quoted for every currency)
+    pub cash_buy: Option<Decimal>,
+    /// Transfer buying rate
+    pub transfer_buy: Option<Decimal>,
+    /// Selling rate
+    pub sell: Option<Decimal>,
+}
+
+impl VcbRate {
+    /// Create from a raw Vietcombank record
+    pub fn from_record(date: NaiveDate, record: &VcbRateRecord) -> Self {
+        Self {
+            currency: record.currency_code.trim().to_uppercase(),
+            date,
+            cash_buy: record.cash.as_deref().and_then(parse_vcb_number),
+            transfer_buy: record.transfer.as_deref().and_then(parse_vcb_number),
+            sell: record.sell.as_deref().and_then(parse_vcb_number),
+        }
+    }
+
+    /// Rate the bank buys the currency at (transfer, falling back to cash)
+    pub fn buy(&self) -> Option<Decimal> {
+        self.transfer_buy.or(self.cash_buy)
+    }
+}
+
+/// SBV central rate of VND against USD
+#[derive(Debug, Clone, PartialEq)]
+pub struct SbvCentralRate {
+    /// Date the rate applies to
+    pub date: NaiveDate,
+    /// VND per USD
+    pub rate: Decimal,
+}
+
+/// Processed FX quote in VND per unit of foreign currency
+#[derive(Debug, Clone, PartialEq)]
+pub struct VndFxQuote {
+    /// ISO code of the foreign currency
+    pub currency: String,
+    /// Quote date
+    pub date: NaiveDate,
+    /// Rate the bank buys the foreign currency at
+    pub bid: Decimal,
+    /// Rate the bank sells the foreign currency at
+    pub ask: Decimal,
+    /// Mid rate used for valuation
+    pub mid: Decimal,
+}
+
+impl VndFxQuote {
+    /// Create from Vietcombank rates; needs both a buying and a selling rate
+    pub fn from_vcb(rate: &VcbRate) -> Option<Self> {
+        let bid = rate.buy()?;
+        let ask = rate.sell?;
+        Some(Self {
+            currency: rate.currency.clone(),
+            date: rate.date,
+            bid,
+            ask,
+            mid: (bid + ask) / Decimal::TWO,
+        })
+    }
+
+    /// Create from the SBV central rate (no spread)
+    pub fn from_sbv(rate: &SbvCentralRate) -> Self {
+        Self {
+            currency: "USD".to_string(),
+            date: rate.date,
+            bid: rate.rate,
+            ask: rate.rate,
+            mid: rate.rate,
+        }
+    }
+}
+
+/// Parse a Vietcombank number ("24,080.00"); "-" or empty means not quoted
+pub fn parse_vcb_number(value: &str) -> Option<Decimal> {
+    let cleaned: String = value.trim().chars().filter(|c| *c != ',').collect();
+    Decimal::from_str(&cleaned)
+        .ok()
+        .filter(|v| *v > Decimal::ZERO)
+}
+
+/// Parse an SBV number in Vietnamese format ("24.265" or "24.265,50")
+pub fn parse_sbv_number(value: &str) -> Option<Decimal> {
+    let cleaned: String = value
+        .trim()
+        .chars()
+        .filter(|c| *c != '.')
+        .map(|c| if c == ',' { '.' } else { c })
+        .collect();
+    Decimal::from_str(&cleaned)
+        .ok()
+        .filter(|v| *v > Decimal::ZERO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_parse_numbers() {
+        assert_eq!(parse_vcb_number("24,080.00"), Some(dec!(24080)));
+        assert_eq!(parse_vcb_number("-"), None);
+        assert_eq!(parse_sbv_number("24.265"), Some(dec!(24265)));
+        assert_eq!(parse_sbv_number("24.265,5"), Some(dec!(24265.5)));
+    }
+
+    #[test]
+    fn test_quote_from_vcb_falls_back_to_cash() {
+        let rate = VcbRate {
+            currency: "EUR".to_string(),
+            date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            cash_buy: Some(dec!(26000)),
+            transfer_buy: None,
+            sell: Some(dec!(27000)),
+        };
+        let quote = VndFxQuote::from_vcb(&rate).unwrap();
+        assert_eq!(quote.bid, dec!(26000));
+        assert_eq!(quote.mid, dec!(26500));
+
+        let no_sell = VcbRate { sell: None, ..rate };
+        assert!(VndFxQuote::from_vcb(&no_sell).is_none());
+    }
+}
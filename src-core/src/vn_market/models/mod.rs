@@ -1,9 +1,11 @@
 //! Data models for VN Market API responses
 
 pub mod fund;
+pub mod fx;
 pub mod gold;
 pub mod stock;
 
 pub use fund::{FundInfo, NavRecord};
+pub use fx::{SbvCentralRate, VcbRate, VndFxQuote};
 pub use gold::SjcGoldPrice;
 pub use stock::{VciOhlcResponse, VciQuote, VciSymbol};
@@ -49,6 +49,30 @@ pub fn sjc_headers() -> HeaderMap {
     headers
 }
 
+/// Create headers for Vietcombank exchange rate API requests
+pub fn vcb_headers() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+    headers.insert(
+        REFERER,
+        HeaderValue::from_static("https://www.vietcombank.com.vn/vi-VN/KHCN/Cong-cu-Tien-ich/Ty-gia"),
+    );
+    headers.insert(USER_AGENT, HeaderValue::from_static(DEFAULT_USER_AGENT));
+    headers
+}
+
+/// Create headers for State Bank of Vietnam exchange rate page requests
+pub fn sbv_headers() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(ACCEPT, HeaderValue::from_static("text/html"));
+    headers.insert(
+        REFERER,
+        HeaderValue::from_static("https://dttktt.sbv.gov.vn/TyGia/faces/TyGiaTrungTam.jspx"),
+    );
+    headers.insert(USER_AGENT, HeaderValue::from_static(DEFAULT_USER_AGENT));
+    headers
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
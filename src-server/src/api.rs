@@ -9,22 +9,34 @@ use axum::http::StatusCode;
 use wealthvn_core::{
     accounts::AccountServiceTrait,
     settings::{Settings, SettingsUpdate, SettingsServiceTrait},
-    portfolio::{holdings::holdings_model::Holding, valuation::valuation_model::DailyAccountValuation, performance::PerformanceMetrics, income::IncomeSummary},
+    portfolio::{attribution::ReturnAttribution, holdings::holdings_model::Holding, valuation::valuation_model::DailyAccountValuation, performance::{BenchmarkComparison, NewRiskFreeRate, PerformanceMetrics, PeriodicReturns, RiskFreeRate}, income::{IncomeForecast, IncomeReport, IncomeReportFilter, IncomeSummary}},
     goals::goals_model::{Goal, NewGoal, GoalsAllocation},
+    goals::{GoalHistoryInterval, GoalPlan, GoalProgressHistory, GoalProjection, GoalProjectionOptions, WaterfallAllocation},
     activities::{
         ActivityBulkMutationRequest,
         ActivityBulkMutationResult,
+        ActivityChange,
+        ActivityExportFormat,
         ActivityImport,
+        ActivitySearchFilters,
         ActivitySearchResponse,
         ActivityUpdate,
         ImportMappingData,
         NewActivity,
+        NewPairedTransfer,
+        PairedTransfer,
     },
-    fx::fx_model::{ExchangeRate, NewExchangeRate},
-    limits::{ContributionLimit, NewContributionLimit, DepositsCalculation},
+    fx::{fx_model::{ExchangeRate, FxRateSide, NewExchangeRate}, TransferFxCost},
+    limits::{ActivityWithLimitWarnings, ContributionLimit, ContributionRoom, NewContributionLimit, DepositsCalculation},
     market_data::{MarketDataProviderSetting, MarketDataProviderInfo, Quote},
     assets::{Asset as CoreAsset, UpdateAssetProfile},
     secrets::SecretManager,
+    tags::{NewTag, Tag, TagUpdate},
+    composites::{CompositePortfolio, CompositePortfolioUpdate, NewCompositePortfolio},
+    reconciliation::{BrokerStatement, Reconciliation, ReconciliationReport},
+    margin::{MarginSettings, MarginSettingsUpdate, MarginSummary},
+    retirement::{NewRetirementScenario, RetirementInputs, RetirementPlan, RetirementScenario},
+    attachments::{ActivityAttachment, NewActivityAttachment, MAX_ATTACHMENT_SIZE_BYTES},
 };
 
 #[utoipa::path(get, path = "/api/v1/healthz", responses((status = 200, description = "Health")))]
@@ -88,6 +100,16 @@ async fn get_holdings(State(state): State<Arc<AppState>>, Query(q): Query<Holdin
     Ok(Json(holdings))
 }
 
+#[derive(serde::Deserialize)]
+struct HoldingsAsOfQuery { #[serde(rename = "accountId")] account_id: String, date: String }
+
+async fn get_holdings_as_of(State(state): State<Arc<AppState>>, Query(q): Query<HoldingsAsOfQuery>) -> ApiResult<Json<Vec<Holding>>> {
+    let date = chrono::NaiveDate::parse_from_str(&q.date, "%Y-%m-%d").map_err(|e| anyhow::anyhow!("Invalid date: {}", e))?;
+    let base = state.base_currency.read().unwrap().clone();
+    let holdings = state.holdings_service.get_holdings_as_of(&q.account_id, date, &base).await?;
+    Ok(Json(holdings))
+}
+
 // Historical valuations endpoint
 #[derive(serde::Deserialize)]
 struct HistoryQuery { #[serde(rename = "accountId")] account_id: String, #[serde(rename = "startDate")] start_date: Option<String>, #[serde(rename = "endDate")] end_date: Option<String> }
@@ -109,12 +131,16 @@ async fn get_historical_valuations(State(state): State<Arc<AppState>>, Query(q):
 async fn get_latest_valuations(State(state): State<Arc<AppState>>, raw: RawQuery) -> ApiResult<Json<Vec<DailyAccountValuation>>> {
     // Parse query manually for robustness (supports accountIds and accountIds[])
     let mut ids: Vec<String> = Vec::new();
+    let mut fx_side = FxRateSide::default();
     if let Some(qs) = raw.0 {
         // Collect all values for both keys
         if let Ok(pairs) = serde_urlencoded::from_str::<Vec<(String, String)>>(&qs) {
             for (k, v) in pairs {
                 if k == "accountIds" || k == "accountIds[]" {
                     ids.push(v);
+                } else if k == "fxSide" {
+                    fx_side = serde_json::from_value(serde_json::Value::String(v))
+                        .map_err(|e| anyhow::anyhow!("Invalid fxSide: {}", e))?;
                 }
             }
         }
@@ -128,7 +154,9 @@ async fn get_latest_valuations(State(state): State<Arc<AppState>>, raw: RawQuery
             .collect();
     }
     if ids.is_empty() { return Ok(Json(vec![])); }
-    let vals = state.valuation_service.get_latest_valuations(&ids)?;
+    let vals = state
+        .valuation_service
+        .get_latest_valuations_at_side(&ids, fx_side)?;
     Ok(Json(vals))
 }
 
@@ -196,12 +224,100 @@ async fn calculate_performance_summary(State(state): State<Arc<AppState>>, Json(
     Ok(Json(metrics))
 }
 
+async fn calculate_periodic_returns(State(state): State<Arc<AppState>>, Json(body): Json<PerfBody>) -> ApiResult<Json<PeriodicReturns>> {
+    let start = match &body.start_date { Some(s) => Some(chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|e| anyhow::anyhow!("Invalid startDate: {}", e))?), None => None };
+    let end = match &body.end_date { Some(s) => Some(chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|e| anyhow::anyhow!("Invalid endDate: {}", e))?), None => None };
+    let periods = state.performance_service.calculate_periodic_returns(&body.item_type, &body.item_id, start, end).await?;
+    Ok(Json(periods))
+}
+
+async fn get_aggregated_valuation_history(State(state): State<Arc<AppState>>, Json(body): Json<PerfBody>) -> ApiResult<Json<Vec<DailyAccountValuation>>> {
+    let start = match &body.start_date { Some(s) => Some(chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|e| anyhow::anyhow!("Invalid startDate: {}", e))?), None => None };
+    let end = match &body.end_date { Some(s) => Some(chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|e| anyhow::anyhow!("Invalid endDate: {}", e))?), None => None };
+    let valuations = state.performance_service.get_aggregated_valuation_history(&body.item_type, &body.item_id, start, end)?;
+    Ok(Json(valuations))
+}
+
+#[derive(serde::Deserialize)]
+struct AttributionBody {
+    #[serde(rename = "accountId")] account_id: String,
+    #[serde(rename = "startDate")] start_date: Option<String>,
+    #[serde(rename = "endDate")] end_date: Option<String>,
+}
+
+async fn calculate_return_attribution(State(state): State<Arc<AppState>>, Json(body): Json<AttributionBody>) -> ApiResult<Json<ReturnAttribution>> {
+    let start = match &body.start_date { Some(s) => Some(chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|e| anyhow::anyhow!("Invalid startDate: {}", e))?), None => None };
+    let end = match &body.end_date { Some(s) => Some(chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|e| anyhow::anyhow!("Invalid endDate: {}", e))?), None => None };
+    let attribution = state.attribution_service.calculate_return_attribution(&body.account_id, start, end).await?;
+    Ok(Json(attribution))
+}
+
+#[derive(serde::Deserialize)]
+struct BenchmarkBody {
+    #[serde(rename = "itemType")] item_type: String,
+    #[serde(rename = "itemId")] item_id: String,
+    #[serde(rename = "benchmarkSymbol")] benchmark_symbol: Option<String>,
+    #[serde(rename = "startDate")] start_date: Option<String>,
+    #[serde(rename = "endDate")] end_date: Option<String>,
+}
+
+async fn calculate_benchmark_comparison(State(state): State<Arc<AppState>>, Json(body): Json<BenchmarkBody>) -> ApiResult<Json<BenchmarkComparison>> {
+    let start = match &body.start_date { Some(s) => Some(chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|e| anyhow::anyhow!("Invalid startDate: {}", e))?), None => None };
+    let end = match &body.end_date { Some(s) => Some(chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|e| anyhow::anyhow!("Invalid endDate: {}", e))?), None => None };
+    let comparison = state.performance_service.calculate_benchmark_comparison(&body.item_type, &body.item_id, body.benchmark_symbol.as_deref(), start, end).await?;
+    Ok(Json(comparison))
+}
+
+#[derive(serde::Deserialize)]
+struct AccountBenchmarkBody { symbol: Option<String> }
+
+async fn get_account_benchmark(Path(id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<Json<String>> {
+    let symbol = state.performance_service.get_benchmark_symbol(&id)?;
+    Ok(Json(symbol))
+}
+
+async fn set_account_benchmark(Path(id): Path<String>, State(state): State<Arc<AppState>>, Json(body): Json<AccountBenchmarkBody>) -> ApiResult<Json<String>> {
+    let symbol = state.performance_service.set_benchmark_symbol(&id, body.symbol).await?;
+    Ok(Json(symbol))
+}
+
+#[derive(serde::Deserialize)]
+struct RiskFreeRatesQuery { series: Option<String> }
+
+async fn get_risk_free_rates(State(state): State<Arc<AppState>>, Query(q): Query<RiskFreeRatesQuery>) -> ApiResult<Json<Vec<RiskFreeRate>>> {
+    let rates = state.performance_service.get_risk_free_rates(q.series.as_deref())?;
+    Ok(Json(rates))
+}
+
+async fn save_risk_free_rate(State(state): State<Arc<AppState>>, Json(rate): Json<NewRiskFreeRate>) -> ApiResult<Json<RiskFreeRate>> {
+    let saved = state.performance_service.save_risk_free_rate(rate).await?;
+    Ok(Json(saved))
+}
+
+async fn delete_risk_free_rate(Path(id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<StatusCode> {
+    state.performance_service.delete_risk_free_rate(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
 // Income
-async fn get_income_summary(State(state): State<Arc<AppState>>) -> ApiResult<Json<Vec<IncomeSummary>>> {
-    let items = state.income_service.get_income_summary()?;
+#[derive(serde::Deserialize)]
+struct IncomeQuery { #[serde(rename = "tagId")] tag_id: Option<String> }
+
+async fn get_income_summary(State(state): State<Arc<AppState>>, Query(q): Query<IncomeQuery>) -> ApiResult<Json<Vec<IncomeSummary>>> {
+    let items = state.income_service.get_income_summary(q.tag_id.as_deref())?;
     Ok(Json(items))
 }
 
+async fn get_income_forecast(State(state): State<Arc<AppState>>) -> ApiResult<Json<IncomeForecast>> {
+    let forecast = state.income_service.get_income_forecast()?;
+    Ok(Json(forecast))
+}
+
+async fn get_income_report(State(state): State<Arc<AppState>>, Json(filter): Json<IncomeReportFilter>) -> ApiResult<Json<IncomeReport>> {
+    let report = state.income_service.get_income_report(filter)?;
+    Ok(Json(report))
+}
+
 // Goals endpoints
 async fn get_goals(State(state): State<Arc<AppState>>) -> ApiResult<Json<Vec<Goal>>> {
     let goals = state.goal_service.get_goals()?;
@@ -233,6 +349,45 @@ async fn update_goal_allocations(State(state): State<Arc<AppState>>, Json(allocs
     Ok(())
 }
 
+#[derive(serde::Deserialize)]
+struct GoalPlanQuery { #[serde(rename = "currentValue")] current_value: f64 }
+
+async fn get_goal_plan(Path(id): Path<String>, State(state): State<Arc<AppState>>, Query(q): Query<GoalPlanQuery>) -> ApiResult<Json<GoalPlan>> {
+    let plan = state.goal_service.get_goal_plan(&id, q.current_value)?;
+    Ok(Json(plan))
+}
+
+async fn project_goal(Path(id): Path<String>, State(state): State<Arc<AppState>>, Json(options): Json<GoalProjectionOptions>) -> ApiResult<Json<GoalProjection>> {
+    let projection = state.goal_projection_service.project_goal(&id, options).await?;
+    Ok(Json(projection))
+}
+
+#[derive(serde::Deserialize)]
+struct GoalHistoryQuery { interval: Option<GoalHistoryInterval>, #[serde(rename = "startDate")] start_date: Option<String>, #[serde(rename = "endDate")] end_date: Option<String> }
+
+async fn get_goal_progress_history(Path(id): Path<String>, State(state): State<Arc<AppState>>, Query(q): Query<GoalHistoryQuery>) -> ApiResult<Json<GoalProgressHistory>> {
+    let start = match q.start_date {
+        Some(s) => Some(chrono::NaiveDate::parse_from_str(&s, "%Y-%m-%d").map_err(|e| anyhow::anyhow!("Invalid startDate: {}", e))?),
+        None => None,
+    };
+    let end = match q.end_date {
+        Some(s) => Some(chrono::NaiveDate::parse_from_str(&s, "%Y-%m-%d").map_err(|e| anyhow::anyhow!("Invalid endDate: {}", e))?),
+        None => None,
+    };
+    let history = state.goal_history_service.get_goal_progress_history(&id, q.interval.unwrap_or_default(), start, end)?;
+    Ok(Json(history))
+}
+
+async fn preview_waterfall_allocation(State(state): State<Arc<AppState>>) -> ApiResult<Json<WaterfallAllocation>> {
+    let preview = state.goal_waterfall_service.preview_waterfall_allocation()?;
+    Ok(Json(preview))
+}
+
+async fn apply_waterfall_allocation(State(state): State<Arc<AppState>>) -> ApiResult<Json<WaterfallAllocation>> {
+    let applied = state.goal_waterfall_service.apply_waterfall_allocation().await?;
+    Ok(Json(applied))
+}
+
 // Exchange rates endpoints
 async fn get_latest_exchange_rates(State(state): State<Arc<AppState>>) -> ApiResult<Json<Vec<ExchangeRate>>> {
     let rates = state.fx_service.get_latest_exchange_rates()?;
@@ -269,50 +424,165 @@ enum StringOrVec {
     Many(Vec<String>),
 }
 
+impl SortWrapper {
+    // Normalize sort to a single value
+    fn into_sort(self) -> Option<wealthvn_core::activities::Sort> {
+        match self {
+            SortWrapper::One(s) => Some(s),
+            SortWrapper::Many(v) => v.into_iter().next(),
+        }
+    }
+}
+
+impl StringOrVec {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            StringOrVec::One(s) => vec![s],
+            StringOrVec::Many(v) => v,
+        }
+    }
+}
+
 #[derive(serde::Deserialize)]
-struct ActivitySearchBody {
-    page: i64,
-    #[serde(rename = "pageSize")] page_size: i64,
+struct ActivityFilterBody {
     #[serde(rename = "accountIdFilter")] account_id_filter: Option<StringOrVec>,
     #[serde(rename = "activityTypeFilter")] activity_type_filter: Option<StringOrVec>,
     #[serde(rename = "assetIdKeyword")] asset_id_keyword: Option<String>,
+    #[serde(rename = "dateFrom")] date_from: Option<chrono::NaiveDate>,
+    #[serde(rename = "dateTo")] date_to: Option<chrono::NaiveDate>,
+    #[serde(rename = "minAmount")] min_amount: Option<rust_decimal::Decimal>,
+    #[serde(rename = "maxAmount")] max_amount: Option<rust_decimal::Decimal>,
+    currency: Option<String>,
+    #[serde(rename = "isDraft")] is_draft: Option<bool>,
+    #[serde(rename = "commentKeyword")] comment_keyword: Option<String>,
+    #[serde(rename = "tagIds")] tag_ids: Option<StringOrVec>,
+}
+
+impl From<ActivityFilterBody> for ActivitySearchFilters {
+    fn from(body: ActivityFilterBody) -> Self {
+        ActivitySearchFilters {
+            account_ids: body.account_id_filter.map(StringOrVec::into_vec),
+            activity_types: body.activity_type_filter.map(StringOrVec::into_vec),
+            asset_id_keyword: body.asset_id_keyword,
+            date_from: body.date_from,
+            date_to: body.date_to,
+            min_amount: body.min_amount,
+            max_amount: body.max_amount,
+            currency: body.currency,
+            is_draft: body.is_draft,
+            comment_keyword: body.comment_keyword,
+            tag_ids: body.tag_ids.map(StringOrVec::into_vec),
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct ActivitySearchBody {
+    page: i64,
+    #[serde(rename = "pageSize")] page_size: i64,
+    #[serde(flatten)] filters: ActivityFilterBody,
     // Allow addons to pass either a single sort or an array (we pick the first)
     sort: Option<SortWrapper>,
 }
 
 async fn search_activities(State(state): State<Arc<AppState>>, Json(body): Json<ActivitySearchBody>) -> ApiResult<Json<ActivitySearchResponse>> {
-    // Normalize sort to a single value if provided
-    let sort_normalized: Option<wealthvn_core::activities::Sort> = match body.sort {
-        Some(SortWrapper::One(s)) => Some(s),
-        Some(SortWrapper::Many(mut v)) => v.into_iter().next(),
-        None => None,
-    };
-    let account_ids: Option<Vec<String>> = match body.account_id_filter {
-        Some(StringOrVec::One(s)) => Some(vec![s]),
-        Some(StringOrVec::Many(v)) => Some(v),
-        None => None,
-    };
-    let types: Option<Vec<String>> = match body.activity_type_filter {
-        Some(StringOrVec::One(s)) => Some(vec![s]),
-        Some(StringOrVec::Many(v)) => Some(v),
-        None => None,
-    };
     let resp = state.activity_service.search_activities(
         body.page,
         body.page_size,
-        account_ids,
-        types,
-        body.asset_id_keyword,
-        sort_normalized,
+        body.filters.into(),
+        body.sort.and_then(SortWrapper::into_sort),
     )?;
     Ok(Json(resp))
 }
 
-async fn create_activity(State(state): State<Arc<AppState>>, Json(activity): Json<NewActivity>) -> ApiResult<Json<wealthvn_core::activities::Activity>> {
+#[derive(serde::Deserialize)]
+struct ActivityExportBody {
+    #[serde(flatten)] filters: ActivityFilterBody,
+    sort: Option<SortWrapper>,
+    format: Option<ActivityExportFormat>,
+}
+
+/// A body chunk of an export, or the error that ended it
+type ExportChunk = Result<Vec<u8>, wealthvn_core::Error>;
+
+/// Forwards writes from a blocking export task to the response body stream
+struct ChannelWriter(tokio::sync::mpsc::Sender<ExportChunk>);
+
+impl std::io::Write for ChannelWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0
+            .blocking_send(Ok(buf.to_vec()))
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::BrokenPipe, "export receiver dropped"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+async fn export_activities(State(state): State<Arc<AppState>>, Json(body): Json<ActivityExportBody>) -> ApiResult<axum::response::Response> {
+    let format = body.format.unwrap_or_default();
+    let filters: ActivitySearchFilters = body.filters.into();
+    let sort = body.sort.and_then(SortWrapper::into_sort);
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<ExportChunk>(16);
+    let service = state.activity_service.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut writer = std::io::BufWriter::with_capacity(64 * 1024, ChannelWriter(tx.clone()));
+        let result = service.export_activities(filters, sort, format, &mut writer);
+        // The service flushes on success; on failure the unsent buffer is dropped
+        let _ = writer.into_parts();
+        if let Err(e) = result {
+            tracing::error!("activity export failed: {}", e);
+            let _ = tx.blocking_send(Err(e));
+        }
+    });
+    // Nothing reaches the channel before the first page is loaded and the buffer fills,
+    // so an early failure still becomes an error response instead of a 200
+    let first = match rx.recv().await {
+        Some(Ok(chunk)) => Some(chunk),
+        Some(Err(e)) => return Err(e.into()),
+        None => None,
+    };
+    // A later failure ends the stream with an error so the client sees an aborted body
+    let rest = futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|chunk| (chunk.map_err(|e| std::io::Error::other(e.to_string())), rx))
+    });
+    let stream = futures::StreamExt::chain(futures::stream::iter(first.map(Ok)), rest);
+    let (content_type, file_name) = match format {
+        ActivityExportFormat::Csv => ("text/csv; charset=utf-8", "activities.csv"),
+        ActivityExportFormat::Json => ("application/json", "activities.json"),
+    };
+    let response = axum::response::Response::builder()
+        .header(axum::http::header::CONTENT_TYPE, content_type)
+        .header(axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", file_name))
+        .body(axum::body::Body::from_stream(stream))
+        .map_err(|e| anyhow::anyhow!(e))?;
+    Ok(response)
+}
+
+async fn create_activity(State(state): State<Arc<AppState>>, Json(activity): Json<NewActivity>) -> ApiResult<Json<ActivityWithLimitWarnings>> {
     let created = state.activity_service.create_activity(activity).await?;
+    let base = state.base_currency.read().unwrap().clone();
+    // The activity is saved either way; a failed check only drops the warnings
+    let contribution_warnings = state.limits_service.check_deposit_limits(&created.id, &base).unwrap_or_else(|e| {
+        tracing::warn!("Failed to check contribution limits: {}", e);
+        Vec::new()
+    });
+    Ok(Json(ActivityWithLimitWarnings { activity: created, contribution_warnings }))
+}
+
+async fn create_paired_transfer(State(state): State<Arc<AppState>>, Json(transfer): Json<NewPairedTransfer>) -> ApiResult<Json<PairedTransfer>> {
+    let created = state.activity_service.create_paired_transfer(transfer).await?;
     Ok(Json(created))
 }
 
+async fn get_transfer_fx_costs(State(state): State<Arc<AppState>>) -> ApiResult<Json<Vec<TransferFxCost>>> {
+    let base = state.base_currency.read().unwrap().clone();
+    let costs = state.activity_service.get_transfer_fx_costs(&base)?;
+    Ok(Json(costs))
+}
+
 async fn update_activity(State(state): State<Arc<AppState>>, Json(activity): Json<ActivityUpdate>) -> ApiResult<Json<wealthvn_core::activities::Activity>> {
     let updated = state.activity_service.update_activity(activity).await?;
     Ok(Json(updated))
@@ -334,6 +604,22 @@ async fn delete_activity(Path(id): Path<String>, State(state): State<Arc<AppStat
     Ok(Json(deleted))
 }
 
+// Activity history endpoints
+async fn get_activity_history(Path(id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<Json<Vec<ActivityChange>>> {
+    let history = state.activity_service.get_activity_history(&id)?;
+    Ok(Json(history))
+}
+
+async fn revert_activity_change(Path(id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<Json<ActivityBulkMutationResult>> {
+    let result = state.activity_service.revert_activity_change(id).await?;
+    Ok(Json(result))
+}
+
+async fn revert_activity_batch(Path(id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<Json<ActivityBulkMutationResult>> {
+    let result = state.activity_service.revert_activity_batch(id).await?;
+    Ok(Json(result))
+}
+
 // Activity import endpoints
 #[derive(serde::Deserialize)]
 struct ImportCheckBody { #[serde(rename = "accountId")] account_id: String, activities: Vec<ActivityImport> }
@@ -413,6 +699,201 @@ async fn calculate_deposits_for_contribution_limit(Path(id): Path<String>, State
     Ok(Json(calc))
 }
 
+async fn get_contribution_room(Path(id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<Json<ContributionRoom>> {
+    let base = state.base_currency.read().unwrap().clone();
+    let room = state.limits_service.get_contribution_room(&id, &base)?;
+    Ok(Json(room))
+}
+
+// Tags
+#[derive(serde::Deserialize)]
+struct TagIdsBody { #[serde(rename = "tagIds")] tag_ids: Vec<String> }
+
+async fn get_tags(State(state): State<Arc<AppState>>) -> ApiResult<Json<Vec<Tag>>> {
+    let tags = state.tag_service.get_tags()?;
+    Ok(Json(tags))
+}
+
+async fn create_tag(State(state): State<Arc<AppState>>, Json(tag): Json<NewTag>) -> ApiResult<Json<Tag>> {
+    let created = state.tag_service.create_tag(tag).await?;
+    Ok(Json(created))
+}
+
+async fn update_tag(Path(id): Path<String>, State(state): State<Arc<AppState>>, Json(mut tag): Json<TagUpdate>) -> ApiResult<Json<Tag>> {
+    tag.id = id;
+    let updated = state.tag_service.update_tag(tag).await?;
+    Ok(Json(updated))
+}
+
+async fn delete_tag(Path(id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<StatusCode> {
+    state.tag_service.delete_tag(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn get_activity_tags(Path(id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<Json<Vec<Tag>>> {
+    let tags = state.tag_service.get_activity_tags(&id)?;
+    Ok(Json(tags))
+}
+
+async fn set_activity_tags(Path(id): Path<String>, State(state): State<Arc<AppState>>, Json(body): Json<TagIdsBody>) -> ApiResult<Json<Vec<Tag>>> {
+    let tags = state.tag_service.set_activity_tags(&id, body.tag_ids).await?;
+    Ok(Json(tags))
+}
+
+async fn get_account_tags(Path(id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<Json<Vec<Tag>>> {
+    let tags = state.tag_service.get_account_tags(&id)?;
+    Ok(Json(tags))
+}
+
+async fn set_account_tags(Path(id): Path<String>, State(state): State<Arc<AppState>>, Json(body): Json<TagIdsBody>) -> ApiResult<Json<Vec<Tag>>> {
+    let tags = state.tag_service.set_account_tags(&id, body.tag_ids).await?;
+    Ok(Json(tags))
+}
+
+// Composite portfolios
+async fn get_composite_portfolios(State(state): State<Arc<AppState>>) -> ApiResult<Json<Vec<CompositePortfolio>>> {
+    let composites = state.composite_service.get_composites()?;
+    Ok(Json(composites))
+}
+
+async fn create_composite_portfolio(State(state): State<Arc<AppState>>, Json(composite): Json<NewCompositePortfolio>) -> ApiResult<Json<CompositePortfolio>> {
+    let created = state.composite_service.create_composite(composite).await?;
+    Ok(Json(created))
+}
+
+async fn update_composite_portfolio(Path(id): Path<String>, State(state): State<Arc<AppState>>, Json(mut composite): Json<CompositePortfolioUpdate>) -> ApiResult<Json<CompositePortfolio>> {
+    composite.id = id;
+    let updated = state.composite_service.update_composite(composite).await?;
+    Ok(Json(updated))
+}
+
+async fn delete_composite_portfolio(Path(id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<StatusCode> {
+    state.composite_service.delete_composite(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Broker statement reconciliation
+#[derive(serde::Deserialize)]
+struct ReconciliationsQuery { #[serde(rename = "accountId")] account_id: Option<String> }
+
+async fn get_reconciliations(State(state): State<Arc<AppState>>, Query(q): Query<ReconciliationsQuery>) -> ApiResult<Json<Vec<Reconciliation>>> {
+    let items = state.reconciliation_service.get_reconciliations(q.account_id.as_deref())?;
+    Ok(Json(items))
+}
+
+async fn get_reconciliation(Path(id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<Json<Reconciliation>> {
+    let item = state.reconciliation_service.get_reconciliation(&id)?;
+    Ok(Json(item))
+}
+
+async fn preview_reconciliation(State(state): State<Arc<AppState>>, Json(statement): Json<BrokerStatement>) -> ApiResult<Json<ReconciliationReport>> {
+    let report = state.reconciliation_service.preview_reconciliation(statement).await?;
+    Ok(Json(report))
+}
+
+async fn save_reconciliation(State(state): State<Arc<AppState>>, Json(statement): Json<BrokerStatement>) -> ApiResult<Json<Reconciliation>> {
+    let saved = state.reconciliation_service.save_reconciliation(statement).await?;
+    Ok(Json(saved))
+}
+
+async fn recheck_reconciliation(Path(id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<Json<Reconciliation>> {
+    let saved = state.reconciliation_service.recheck_reconciliation(&id).await?;
+    Ok(Json(saved))
+}
+
+async fn delete_reconciliation(Path(id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<StatusCode> {
+    state.reconciliation_service.delete_reconciliation(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Margin accounts
+#[derive(serde::Deserialize)]
+struct MarginAccrueBody { #[serde(rename = "throughDate")] through_date: String }
+
+async fn get_margin_settings(Path(account_id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<Json<Option<MarginSettings>>> {
+    let settings = state.margin_service.get_margin_settings(&account_id)?;
+    Ok(Json(settings))
+}
+
+async fn save_margin_settings(State(state): State<Arc<AppState>>, Json(update): Json<MarginSettingsUpdate>) -> ApiResult<Json<MarginSettings>> {
+    let saved = state.margin_service.save_margin_settings(update).await?;
+    Ok(Json(saved))
+}
+
+async fn delete_margin_settings(Path(account_id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<StatusCode> {
+    state.margin_service.delete_margin_settings(&account_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn get_margin_summary(Path(account_id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<Json<MarginSummary>> {
+    let summary = state.margin_service.get_margin_summary(&account_id).await?;
+    Ok(Json(summary))
+}
+
+async fn accrue_margin_interest(Path(account_id): Path<String>, State(state): State<Arc<AppState>>, Json(body): Json<MarginAccrueBody>) -> ApiResult<Json<Vec<wealthvn_core::activities::Activity>>> {
+    let through = chrono::NaiveDate::parse_from_str(&body.through_date, "%Y-%m-%d").map_err(|e| anyhow::anyhow!("Invalid throughDate: {}", e))?;
+    let created = state.margin_service.accrue_margin_interest(&account_id, through).await?;
+    Ok(Json(created))
+}
+
+// Retirement planner
+async fn calculate_retirement_plan(State(state): State<Arc<AppState>>, Json(inputs): Json<RetirementInputs>) -> ApiResult<Json<RetirementPlan>> {
+    let plan = state.retirement_service.calculate_retirement_plan(inputs).await?;
+    Ok(Json(plan))
+}
+
+async fn get_retirement_scenarios(State(state): State<Arc<AppState>>) -> ApiResult<Json<Vec<RetirementScenario>>> {
+    let items = state.retirement_service.get_scenarios()?;
+    Ok(Json(items))
+}
+
+async fn get_retirement_scenario(Path(id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<Json<RetirementScenario>> {
+    let item = state.retirement_service.get_scenario(&id)?;
+    Ok(Json(item))
+}
+
+async fn save_retirement_scenario(State(state): State<Arc<AppState>>, Json(scenario): Json<NewRetirementScenario>) -> ApiResult<Json<RetirementScenario>> {
+    let saved = state.retirement_service.save_scenario(scenario).await?;
+    Ok(Json(saved))
+}
+
+async fn delete_retirement_scenario(Path(id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<StatusCode> {
+    state.retirement_service.delete_scenario(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Activity attachments
+#[derive(serde::Deserialize)]
+struct AttachmentUploadQuery { #[serde(rename = "fileName")] file_name: String }
+
+async fn get_activity_attachments(Path(id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<Json<Vec<ActivityAttachment>>> {
+    let items = state.attachment_service.get_attachments(&id)?;
+    Ok(Json(items))
+}
+
+async fn add_activity_attachment(Path(id): Path<String>, State(state): State<Arc<AppState>>, Query(q): Query<AttachmentUploadQuery>, headers: axum::http::HeaderMap, body: axum::body::Bytes) -> ApiResult<Json<ActivityAttachment>> {
+    let mime_type = headers.get(axum::http::header::CONTENT_TYPE).and_then(|v| v.to_str().ok()).map(|s| s.to_string());
+    let new_attachment = NewActivityAttachment { activity_id: id, file_name: q.file_name, mime_type };
+    let created = state.attachment_service.add_attachment(new_attachment, body.to_vec()).await?;
+    Ok(Json(created))
+}
+
+async fn download_attachment(Path(id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<axum::response::Response> {
+    let (attachment, content) = state.attachment_service.read_attachment(&id)?;
+    let content_type = attachment.mime_type.unwrap_or_else(|| "application/octet-stream".to_string());
+    let response = axum::response::Response::builder()
+        .header(axum::http::header::CONTENT_TYPE, content_type)
+        .header(axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", attachment.file_name.replace('"', "")))
+        .body(axum::body::Body::from(content))
+        .map_err(|e| anyhow::anyhow!(e))?;
+    Ok(response)
+}
+
+async fn delete_attachment(Path(id): Path<String>, State(state): State<Arc<AppState>>) -> ApiResult<Json<ActivityAttachment>> {
+    let deleted = state.attachment_service.delete_attachment(&id).await?;
+    Ok(Json(deleted))
+}
+
 // Asset profile endpoints
 #[derive(serde::Deserialize)]
 struct AssetQuery { #[serde(rename = "assetId")] asset_id: String }
@@ -530,20 +1011,35 @@ pub fn app_router(state: Arc<AppState>, config: &Config) -> Router {
         .route("/accounts/:id", put(update_account).delete(delete_account))
         .route("/settings", get(get_settings).put(update_settings))
         .route("/holdings", get(get_holdings))
+        .route("/holdings/as-of", get(get_holdings_as_of))
         .route("/valuations/history", get(get_historical_valuations))
         .route("/valuations/latest", get(get_latest_valuations))
         .route("/portfolio/update", post(update_portfolio))
         .route("/portfolio/recalculate", post(recalculate_portfolio))
         .route("/performance/history", post(calculate_performance_history))
         .route("/performance/summary", post(calculate_performance_summary))
+        .route("/performance/periodic", post(calculate_periodic_returns))
+        .route("/performance/attribution", post(calculate_return_attribution))
+        .route("/performance/valuations", post(get_aggregated_valuation_history))
+        .route("/performance/benchmark", post(calculate_benchmark_comparison))
+        .route("/risk-free-rates", get(get_risk_free_rates).post(save_risk_free_rate))
+        .route("/risk-free-rates/:id", delete(delete_risk_free_rate))
         .route("/income/summary", get(get_income_summary))
+        .route("/income/forecast", get(get_income_forecast))
+        .route("/income/report", post(get_income_report))
         .route("/exchange-rates/latest", get(get_latest_exchange_rates))
         .route("/exchange-rates", put(update_exchange_rate).post(add_exchange_rate))
         .route("/exchange-rates/:id", delete(delete_exchange_rate))
         .route("/activities/search", post(search_activities))
+        .route("/activities/export", post(export_activities))
         .route("/activities", post(create_activity).put(update_activity))
         .route("/activities/bulk", post(save_activities))
+        .route("/activities/transfers", post(create_paired_transfer))
+        .route("/activities/transfers/fx-costs", get(get_transfer_fx_costs))
         .route("/activities/:id", delete(delete_activity))
+        .route("/activities/:id/history", get(get_activity_history))
+        .route("/activities/changes/:id/revert", post(revert_activity_change))
+        .route("/activities/batches/:id/revert", post(revert_activity_batch))
         .route("/activities/import/check", post(check_activities_import))
         .route("/activities/import", post(import_activities))
         .route("/activities/import/mapping", get(get_account_import_mapping).post(save_account_import_mapping))
@@ -557,6 +1053,33 @@ pub fn app_router(state: Arc<AppState>, config: &Config) -> Router {
         .route("/limits", get(get_contribution_limits).post(create_contribution_limit))
         .route("/limits/:id", put(update_contribution_limit).delete(delete_contribution_limit))
         .route("/limits/:id/deposits", get(calculate_deposits_for_contribution_limit))
+        .route("/limits/:id/room", get(get_contribution_room))
+        .route("/tags", get(get_tags).post(create_tag))
+        .route("/tags/:id", put(update_tag).delete(delete_tag))
+        .route("/composites", get(get_composite_portfolios).post(create_composite_portfolio))
+        .route("/composites/:id", put(update_composite_portfolio).delete(delete_composite_portfolio))
+        .route("/reconciliations", get(get_reconciliations).post(save_reconciliation))
+        .route("/reconciliations/preview", post(preview_reconciliation))
+        .route("/reconciliations/:id", get(get_reconciliation).delete(delete_reconciliation))
+        .route("/reconciliations/:id/recheck", post(recheck_reconciliation))
+        .route("/margin", put(save_margin_settings))
+        .route("/margin/:account_id", get(get_margin_settings).delete(delete_margin_settings))
+        .route("/margin/:account_id/summary", get(get_margin_summary))
+        .route("/margin/:account_id/accrue", post(accrue_margin_interest))
+        .route("/retirement/plan", post(calculate_retirement_plan))
+        .route("/retirement/scenarios", get(get_retirement_scenarios).post(save_retirement_scenario))
+        .route("/retirement/scenarios/:id", get(get_retirement_scenario).delete(delete_retirement_scenario))
+        .route("/activities/:id/tags", get(get_activity_tags).put(set_activity_tags))
+        .route(
+            "/activities/:id/attachments",
+            get(get_activity_attachments)
+                .post(add_activity_attachment)
+                .layer(axum::extract::DefaultBodyLimit::max(MAX_ATTACHMENT_SIZE_BYTES)),
+        )
+        .route("/attachments/:id", delete(delete_attachment))
+        .route("/attachments/:id/content", get(download_attachment))
+        .route("/accounts/:id/tags", get(get_account_tags).put(set_account_tags))
+        .route("/accounts/:id/benchmark", get(get_account_benchmark).put(set_account_benchmark))
         .route("/assets/profile", get(get_asset_profile))
         .route("/assets/profile/:id", put(update_asset_profile))
         .route("/assets/data-source/:id", put(update_asset_data_source))
@@ -564,6 +1087,10 @@ pub fn app_router(state: Arc<AppState>, config: &Config) -> Router {
         .route("/goals/allocations", get(load_goals_allocations).post(update_goal_allocations))
         .route("/goals", get(get_goals).post(create_goal).put(update_goal))
         .route("/goals/:id", delete(delete_goal))
+        .route("/goals/:id/projection", post(project_goal))
+        .route("/goals/:id/plan", get(get_goal_plan))
+        .route("/goals/:id/history", get(get_goal_progress_history))
+        .route("/goals/waterfall", get(preview_waterfall_allocation).post(apply_waterfall_allocation))
         // Addons (web mode)
         .route("/addons/installed", get(list_installed_addons_web))
         .route("/addons/install-zip", post(install_addon_zip_web))
@@ -147,9 +147,7 @@ fn normalize_arch(arch: Option<String>) -> String {
     }
 }
 
-async fn check_update(
-    State(state): State<Arc<AppState>>,
-) -> ApiResult<Json<UpdateCheckResponse>> {
+async fn check_update(State(state): State<Arc<AppState>>) -> ApiResult<Json<UpdateCheckResponse>> {
     let current_version_str = env!("CARGO_PKG_VERSION").to_string();
     let target = normalize_target(None);
     let arch = normalize_arch(None);
@@ -175,9 +173,12 @@ async fn backup_database_route(
     State(state): State<Arc<AppState>>,
 ) -> ApiResult<Json<BackupDatabaseResponse>> {
     let data_root = state.data_root.clone();
-    let backup_path = task::spawn_blocking(move || db::backup_database(&data_root))
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to execute backup task: {}", e))??;
+    // Download a single archive holding the database and its attachments
+    let backup_path = task::spawn_blocking(move || {
+        db::backup_database(&data_root).and_then(|path| db::create_backup_bundle(&path))
+    })
+    .await
+    .map_err(|e| anyhow::anyhow!("Failed to execute backup task: {}", e))??;
 
     let filename = StdPath::new(&backup_path)
         .file_name()
@@ -261,6 +262,8 @@ async fn backup_database_to_path_route(
             })?;
         }
 
+        db::backup_attachments(&data_root, &backup_path_str)?;
+
         Ok(backup_path_str)
     })
     .await
@@ -9,15 +9,24 @@ use wealthvn_core::{
         ActivityRepository, ActivityService as CoreActivityService, ActivityServiceTrait,
     },
     assets::{AssetRepository, AssetService, AssetServiceTrait},
+    attachments::{AttachmentRepository, AttachmentService, AttachmentServiceTrait},
+    composites::{CompositeRepository, CompositeService, CompositeServiceTrait},
     db::{self, write_actor},
     fx::{FxRepository, FxService, FxServiceTrait},
-    goals::{GoalRepository, GoalService, GoalServiceTrait},
+    goals::{
+        GoalHistoryService, GoalHistoryServiceTrait, GoalProjectionService,
+        GoalProjectionServiceTrait, GoalRepository, GoalService, GoalServiceTrait,
+        GoalWaterfallService, GoalWaterfallServiceTrait,
+    },
     limits::{
         ContributionLimitRepository, ContributionLimitService, ContributionLimitServiceTrait,
     },
+    margin::{MarginRepository, MarginService, MarginServiceTrait},
     market_data::{MarketDataRepository, MarketDataService, MarketDataServiceTrait},
+    portfolio::attribution::{AttributionService, AttributionServiceTrait},
     portfolio::income::{IncomeService, IncomeServiceTrait},
     portfolio::{
+        checkpoint::ValuationCheckpointRepository,
         holdings::{
             holdings_valuation_service::HoldingsValuationService, HoldingsService,
             HoldingsServiceTrait,
@@ -25,7 +34,12 @@ use wealthvn_core::{
         snapshot::{SnapshotRepository, SnapshotService, SnapshotServiceTrait},
         valuation::{ValuationRepository, ValuationService, ValuationServiceTrait},
     },
+    reconciliation::{
+        ReconciliationRepository, ReconciliationService, ReconciliationServiceTrait,
+    },
+    retirement::{RetirementRepository, RetirementService, RetirementServiceTrait},
     settings::{settings_repository::SettingsRepository, SettingsService, SettingsServiceTrait},
+    tags::{TagRepository, TagService, TagServiceTrait},
 };
 
 #[cfg(feature = "wealthfolio-pro")]
@@ -42,11 +56,21 @@ pub struct AppState {
     pub performance_service:
         Arc<dyn wealthvn_core::portfolio::performance::PerformanceServiceTrait + Send + Sync>,
     pub income_service: Arc<dyn IncomeServiceTrait + Send + Sync>,
+    pub attribution_service: Arc<dyn AttributionServiceTrait + Send + Sync>,
     pub goal_service: Arc<dyn GoalServiceTrait + Send + Sync>,
+    pub goal_projection_service: Arc<dyn GoalProjectionServiceTrait + Send + Sync>,
+    pub goal_history_service: Arc<dyn GoalHistoryServiceTrait + Send + Sync>,
+    pub goal_waterfall_service: Arc<dyn GoalWaterfallServiceTrait + Send + Sync>,
     pub limits_service: Arc<dyn ContributionLimitServiceTrait + Send + Sync>,
     pub fx_service: Arc<dyn FxServiceTrait + Send + Sync>,
     pub activity_service: Arc<dyn ActivityServiceTrait + Send + Sync>,
     pub asset_service: Arc<dyn AssetServiceTrait + Send + Sync>,
+    pub tag_service: Arc<dyn TagServiceTrait + Send + Sync>,
+    pub composite_service: Arc<dyn CompositeServiceTrait + Send + Sync>,
+    pub reconciliation_service: Arc<dyn ReconciliationServiceTrait + Send + Sync>,
+    pub margin_service: Arc<dyn MarginServiceTrait + Send + Sync>,
+    pub retirement_service: Arc<dyn RetirementServiceTrait + Send + Sync>,
+    pub attachment_service: Arc<dyn AttachmentServiceTrait + Send + Sync>,
     pub addons_root: String,
     pub data_root: String,
     pub instance_id: String,
@@ -110,6 +134,10 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
     )?);
     let activity_repository = Arc::new(ActivityRepository::new(pool.clone(), writer.clone()));
     let snapshot_repository = Arc::new(SnapshotRepository::new(pool.clone(), writer.clone()));
+    let checkpoint_repository = Arc::new(ValuationCheckpointRepository::new(
+        pool.clone(),
+        writer.clone(),
+    ));
     let snapshot_service = Arc::new(SnapshotService::new(
         base_currency.clone(),
         account_repo.clone(),
@@ -117,6 +145,7 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
         snapshot_repository.clone(),
         asset_repository.clone(),
         fx_service.clone(),
+        checkpoint_repository.clone(),
     ));
 
     let valuation_repository = Arc::new(ValuationRepository::new(pool.clone(), writer.clone()));
@@ -126,6 +155,7 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
         snapshot_service.clone(),
         market_data_service.clone(),
         fx_service.clone(),
+        checkpoint_repository.clone(),
     ));
 
     let holdings_valuation_service = Arc::new(HoldingsValuationService::new(
@@ -138,21 +168,65 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
         holdings_valuation_service.clone(),
     ));
 
+    let tag_repository = Arc::new(TagRepository::new(pool.clone(), writer.clone()));
+    let tag_service: Arc<dyn TagServiceTrait + Send + Sync> =
+        Arc::new(TagService::new(tag_repository.clone()));
+
+    let composite_repository = Arc::new(CompositeRepository::new(pool.clone(), writer.clone()));
+    let composite_service: Arc<dyn CompositeServiceTrait + Send + Sync> =
+        Arc::new(CompositeService::new(composite_repository.clone()));
+
     let performance_service = Arc::new(
         wealthvn_core::portfolio::performance::PerformanceService::new(
             valuation_service.clone(),
             market_data_service.clone(),
+            tag_repository.clone(),
+            Arc::new(
+                wealthvn_core::portfolio::performance::BenchmarkRepository::new(
+                    pool.clone(),
+                    writer.clone(),
+                ),
+            ),
+            Arc::new(wealthvn_core::vn_market::VnMarketService::with_pool(
+                pool.as_ref().clone(),
+            )),
+            Arc::new(
+                wealthvn_core::portfolio::performance::RiskFreeRateRepository::new(
+                    pool.clone(),
+                    writer.clone(),
+                ),
+            ),
+            settings_service.clone(),
+            composite_repository.clone(),
+            activity_repository.clone(),
+            fx_service.clone(),
         ),
     );
 
     let income_service = Arc::new(IncomeService::new(
         fx_service.clone(),
         activity_repository.clone(),
+        snapshot_service.clone(),
+        market_data_service.clone(),
         base_currency.clone(),
     ));
 
     let goal_repository = Arc::new(GoalRepository::new(pool.clone(), writer.clone()));
-    let goal_service = Arc::new(GoalService::new(goal_repository));
+    let goal_service = Arc::new(GoalService::new(goal_repository.clone()));
+    let goal_projection_service: Arc<dyn GoalProjectionServiceTrait + Send + Sync> =
+        Arc::new(GoalProjectionService::new(
+            goal_repository.clone(),
+            valuation_service.clone(),
+            performance_service.clone(),
+        ));
+    let goal_history_service: Arc<dyn GoalHistoryServiceTrait + Send + Sync> =
+        Arc::new(GoalHistoryService::new(goal_repository.clone(), valuation_service.clone()));
+    let goal_waterfall_service: Arc<dyn GoalWaterfallServiceTrait + Send + Sync> =
+        Arc::new(GoalWaterfallService::new(
+            goal_repository,
+            account_service.clone(),
+            valuation_service.clone(),
+        ));
 
     let limits_repository = Arc::new(ContributionLimitRepository::new(
         pool.clone(),
@@ -165,6 +239,14 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
             activity_repository.clone(),
         ));
 
+    let attachment_repository = Arc::new(AttachmentRepository::new(pool.clone(), writer.clone()));
+    let attachment_service: Arc<dyn AttachmentServiceTrait + Send + Sync> =
+        Arc::new(AttachmentService::new(
+            attachment_repository,
+            activity_repository.clone(),
+            db::get_attachments_dir(&config.db_path),
+        ));
+
     let activity_service: Arc<dyn ActivityServiceTrait + Send + Sync> =
         Arc::new(CoreActivityService::new(
             activity_repository.clone(),
@@ -172,6 +254,45 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
             asset_service.clone(),
             fx_service.clone(),
             market_data_service.clone(),
+            snapshot_service.clone(),
+            valuation_service.clone(),
+            attachment_service.clone(),
+        ));
+
+    let attribution_service: Arc<dyn AttributionServiceTrait + Send + Sync> =
+        Arc::new(AttributionService::new(
+            valuation_service.clone(),
+            snapshot_service.clone(),
+            market_data_service.clone(),
+            fx_service.clone(),
+            asset_service.clone(),
+        ));
+
+    let reconciliation_repository =
+        Arc::new(ReconciliationRepository::new(pool.clone(), writer.clone()));
+    let reconciliation_service: Arc<dyn ReconciliationServiceTrait + Send + Sync> =
+        Arc::new(ReconciliationService::new(
+            reconciliation_repository,
+            snapshot_service.clone(),
+            asset_service.clone(),
+        ));
+
+    let margin_repository = Arc::new(MarginRepository::new(pool.clone(), writer.clone()));
+    let margin_service: Arc<dyn MarginServiceTrait + Send + Sync> =
+        Arc::new(MarginService::new(
+            margin_repository,
+            account_service.clone(),
+            activity_service.clone(),
+            snapshot_service.clone(),
+            holdings_service.clone(),
+        ));
+
+    let retirement_repository = Arc::new(RetirementRepository::new(pool.clone(), writer.clone()));
+    let retirement_service: Arc<dyn RetirementServiceTrait + Send + Sync> =
+        Arc::new(RetirementService::new(
+            retirement_repository,
+            account_service.clone(),
+            valuation_service.clone(),
         ));
 
     // Determine data root directory (parent of DB path)
@@ -191,11 +312,21 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
         snapshot_service,
         performance_service,
         income_service,
+        attribution_service,
         goal_service,
+        goal_projection_service,
+        goal_history_service,
+        goal_waterfall_service,
         limits_service,
         fx_service: fx_service.clone(),
         activity_service,
         asset_service,
+        tag_service,
+        composite_service,
+        reconciliation_service,
+        margin_service,
+        retirement_service,
+        attachment_service,
         addons_root: config.addons_root.clone(),
         data_root,
         instance_id: settings.instance_id,
@@ -1,13 +1,18 @@
+use std::fs::{self, File};
+use std::io::BufWriter;
 use std::sync::Arc;
 
 use crate::context::ServiceContext;
 use crate::events::{emit_resource_changed, ResourceEventPayload};
-use log::debug;
+use log::{debug, warn};
 use tauri::{AppHandle, State};
 use wealthvn_core::activities::{
-    Activity, ActivityBulkMutationRequest, ActivityBulkMutationResult, ActivityImport,
-    ActivitySearchResponse, ActivityUpdate, ImportMappingData, NewActivity, Sort,
+    Activity, ActivityBulkMutationRequest, ActivityBulkMutationResult, ActivityChange,
+    ActivityExportFormat, ActivityImport, ActivitySearchFilters, ActivitySearchResponse,
+    ActivityUpdate, ImportMappingData, NewActivity, NewPairedTransfer, PairedTransfer, Sort,
 };
+use wealthvn_core::fx::TransferFxCost;
+use wealthvn_core::limits::ActivityWithLimitWarnings;
 
 use serde_json::json;
 
@@ -20,24 +25,68 @@ pub async fn get_activities(
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn search_activities(
     page: i64,                                 // Page number, 1-based
     page_size: i64,                            // Number of items per page
     account_id_filter: Option<Vec<String>>,    // Optional account_id filter
     activity_type_filter: Option<Vec<String>>, // Optional activity_type filter
     asset_id_keyword: Option<String>,          // Optional asset_id keyword for search
+    filters: Option<ActivitySearchFilters>, // Optional date, amount, currency, draft and comment filters
     sort: Option<Sort>,
     state: State<'_, Arc<ServiceContext>>,
 ) -> Result<ActivitySearchResponse, String> {
     debug!("Search activities... {}, {}", page, page_size);
-    Ok(state.activity_service().search_activities(
-        page,
-        page_size,
-        account_id_filter,
-        activity_type_filter,
-        asset_id_keyword,
-        sort,
-    )?)
+    let mut filters = filters.unwrap_or_default();
+    if account_id_filter.is_some() {
+        filters.account_ids = account_id_filter;
+    }
+    if activity_type_filter.is_some() {
+        filters.activity_types = activity_type_filter;
+    }
+    if asset_id_keyword.is_some() {
+        filters.asset_id_keyword = asset_id_keyword;
+    }
+    Ok(state
+        .activity_service()
+        .search_activities(page, page_size, filters, sort)?)
+}
+
+#[tauri::command]
+pub async fn export_activities(
+    filters: Option<ActivitySearchFilters>,
+    sort: Option<Sort>,
+    format: Option<ActivityExportFormat>,
+    file_path: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<usize, String> {
+    debug!("Exporting activities to {}", file_path);
+    // Write next to the target and rename on success, so a failed export never
+    // leaves a truncated file at the chosen path
+    let part_path = format!("{}.part", file_path);
+    let exported = (|| -> Result<usize, String> {
+        let file = File::create(&part_path).map_err(|e| e.to_string())?;
+        let mut writer = BufWriter::new(file);
+        let exported = state.activity_service().export_activities(
+            filters.unwrap_or_default(),
+            sort,
+            format.unwrap_or_default(),
+            &mut writer,
+        )?;
+        writer.into_inner().map_err(|e| e.to_string())?;
+        Ok(exported)
+    })();
+
+    match exported {
+        Ok(exported) => {
+            fs::rename(&part_path, &file_path).map_err(|e| e.to_string())?;
+            Ok(exported)
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&part_path);
+            Err(e)
+        }
+    }
 }
 
 #[tauri::command]
@@ -45,9 +94,18 @@ pub async fn create_activity(
     activity: NewActivity,
     state: State<'_, Arc<ServiceContext>>,
     handle: AppHandle,
-) -> Result<Activity, String> {
+) -> Result<ActivityWithLimitWarnings, String> {
     debug!("Creating activity...");
     let result = state.activity_service().create_activity(activity).await?;
+    let base_currency = state.base_currency.read().unwrap().clone();
+    // The activity is saved either way; a failed check only drops the warnings
+    let contribution_warnings = state
+        .limits_service()
+        .check_deposit_limits(&result.id, &base_currency)
+        .unwrap_or_else(|e| {
+            warn!("Failed to check contribution limits: {}", e);
+            Vec::new()
+        });
 
     emit_resource_changed(
         &handle,
@@ -63,9 +121,54 @@ pub async fn create_activity(
         ),
     );
 
+    Ok(ActivityWithLimitWarnings {
+        activity: result,
+        contribution_warnings,
+    })
+}
+
+#[tauri::command]
+pub async fn create_paired_transfer(
+    transfer: NewPairedTransfer,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<PairedTransfer, String> {
+    debug!("Creating paired transfer...");
+    let result = state
+        .activity_service()
+        .create_paired_transfer(transfer)
+        .await?;
+
+    for leg in [&result.transfer_out, &result.transfer_in] {
+        emit_resource_changed(
+            &handle,
+            ResourceEventPayload::new(
+                "activity",
+                "created",
+                json!({
+                    "activity_id": leg.id,
+                    "account_id": leg.account_id,
+                    "currency": leg.currency,
+                    "asset_id": leg.asset_id,
+                }),
+            ),
+        );
+    }
+
     Ok(result)
 }
 
+#[tauri::command]
+pub async fn get_transfer_fx_costs(
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<TransferFxCost>, String> {
+    debug!("Fetching FX costs of transfers...");
+    let base_currency = state.base_currency.read().unwrap().clone();
+    Ok(state
+        .activity_service()
+        .get_transfer_fx_costs(&base_currency)?)
+}
+
 #[tauri::command]
 pub async fn update_activity(
     activity: ActivityUpdate,
@@ -169,6 +272,60 @@ pub async fn save_activities(
     Ok(result)
 }
 
+#[tauri::command]
+pub async fn get_activity_history(
+    activity_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<ActivityChange>, String> {
+    debug!("Fetching change history for activity: {}", activity_id);
+    Ok(state
+        .activity_service()
+        .get_activity_history(&activity_id)?)
+}
+
+#[tauri::command]
+pub async fn revert_activity_change(
+    change_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<ActivityBulkMutationResult, String> {
+    debug!("Reverting activity change: {}", change_id);
+    let result = state
+        .activity_service()
+        .revert_activity_change(change_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    emit_activity_reverted(&handle, &result);
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn revert_activity_batch(
+    batch_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<ActivityBulkMutationResult, String> {
+    debug!("Reverting activity batch: {}", batch_id);
+    let result = state
+        .activity_service()
+        .revert_activity_batch(batch_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    emit_activity_reverted(&handle, &result);
+    Ok(result)
+}
+
+/// Notifies listeners of a revert. Snapshots are already rebuilt by the service.
+fn emit_activity_reverted(handle: &AppHandle, result: &ActivityBulkMutationResult) {
+    let result_value = serde_json::to_value(result).unwrap_or_else(|_| json!({}));
+    emit_resource_changed(
+        handle,
+        ResourceEventPayload::new("activity", "reverted", result_value),
+    );
+}
+
 #[tauri::command]
 pub async fn get_account_import_mapping(
     account_id: String,
@@ -0,0 +1,116 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::{
+    context::ServiceContext,
+    events::{emit_resource_changed, ResourceEventPayload},
+};
+use log::debug;
+use serde_json::json;
+use tauri::{AppHandle, State};
+use wealthvn_core::attachments::{ActivityAttachment, NewActivityAttachment};
+
+#[tauri::command]
+pub async fn get_activity_attachments(
+    activity_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<ActivityAttachment>, String> {
+    debug!("Fetching attachments for activity {}", activity_id);
+    state
+        .attachment_service()
+        .get_attachments(&activity_id)
+        .map_err(|e| format!("Failed to load attachments: {}", e))
+}
+
+/// Attaches a file picked on the local file system to an activity.
+#[tauri::command]
+pub async fn add_activity_attachment(
+    activity_id: String,
+    file_path: String,
+    mime_type: Option<String>,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<ActivityAttachment, String> {
+    // Mobile file pickers hand out file:// URIs
+    let file_path = file_path
+        .strip_prefix("file://")
+        .unwrap_or(&file_path)
+        .to_string();
+    debug!("Attaching {} to activity {}", file_path, activity_id);
+
+    let content =
+        std::fs::read(&file_path).map_err(|e| format!("Failed to read attachment: {}", e))?;
+    let file_name = Path::new(&file_path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| "Failed to get attachment file name".to_string())?
+        .to_string();
+
+    let attachment = state
+        .attachment_service()
+        .add_attachment(
+            NewActivityAttachment {
+                activity_id,
+                file_name,
+                mime_type,
+            },
+            content,
+        )
+        .await
+        .map_err(|e| format!("Failed to add attachment: {}", e))?;
+
+    emit_resource_changed(
+        &handle,
+        ResourceEventPayload::new(
+            "attachment",
+            "created",
+            json!({
+                "attachment_id": attachment.id,
+                "activity_id": attachment.activity_id,
+            }),
+        ),
+    );
+
+    Ok(attachment)
+}
+
+#[tauri::command]
+pub async fn delete_activity_attachment(
+    attachment_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<ActivityAttachment, String> {
+    debug!("Deleting attachment {}", attachment_id);
+    let attachment = state
+        .attachment_service()
+        .delete_attachment(&attachment_id)
+        .await
+        .map_err(|e| format!("Failed to delete attachment: {}", e))?;
+
+    emit_resource_changed(
+        &handle,
+        ResourceEventPayload::new(
+            "attachment",
+            "deleted",
+            json!({
+                "attachment_id": attachment.id,
+                "activity_id": attachment.activity_id,
+            }),
+        ),
+    );
+
+    Ok(attachment)
+}
+
+/// Returns the absolute path of the stored file so the frontend can open it.
+#[tauri::command]
+pub async fn get_activity_attachment_path(
+    attachment_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<String, String> {
+    state
+        .attachment_service()
+        .get_attachment_path(&attachment_id)
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(|e| format!("Failed to locate attachment: {}", e))
+}
@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use crate::{
+    context::ServiceContext,
+    events::{emit_resource_changed, ResourceEventPayload},
+};
+use log::debug;
+use serde_json::json;
+use tauri::{AppHandle, State};
+use wealthvn_core::composites::{
+    CompositePortfolio, CompositePortfolioUpdate, NewCompositePortfolio,
+};
+
+#[tauri::command]
+pub async fn get_composite_portfolios(
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<CompositePortfolio>, String> {
+    debug!("Fetching composite portfolios...");
+    state
+        .composite_service()
+        .get_composites()
+        .map_err(|e| format!("Failed to load composite portfolios: {}", e))
+}
+
+#[tauri::command]
+pub async fn create_composite_portfolio(
+    composite: NewCompositePortfolio,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<CompositePortfolio, String> {
+    debug!("Creating composite portfolio...");
+    let composite = state
+        .composite_service()
+        .create_composite(composite)
+        .await
+        .map_err(|e| format!("Failed to create composite portfolio: {}", e))?;
+
+    emit_resource_changed(
+        &handle,
+        ResourceEventPayload::new(
+            "composite_portfolio",
+            "created",
+            json!({ "composite_id": composite.id }),
+        ),
+    );
+
+    Ok(composite)
+}
+
+#[tauri::command]
+pub async fn update_composite_portfolio(
+    composite: CompositePortfolioUpdate,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<CompositePortfolio, String> {
+    debug!("Updating composite portfolio...");
+    let composite = state
+        .composite_service()
+        .update_composite(composite)
+        .await
+        .map_err(|e| format!("Failed to update composite portfolio: {}", e))?;
+
+    emit_resource_changed(
+        &handle,
+        ResourceEventPayload::new(
+            "composite_portfolio",
+            "updated",
+            json!({ "composite_id": composite.id }),
+        ),
+    );
+
+    Ok(composite)
+}
+
+#[tauri::command]
+pub async fn delete_composite_portfolio(
+    composite_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<(), String> {
+    debug!("Deleting composite portfolio...");
+    state
+        .composite_service()
+        .delete_composite(&composite_id)
+        .await
+        .map_err(|e| format!("Failed to delete composite portfolio: {}", e))?;
+
+    emit_resource_changed(
+        &handle,
+        ResourceEventPayload::new(
+            "composite_portfolio",
+            "deleted",
+            json!({ "composite_id": composite_id }),
+        ),
+    );
+
+    Ok(())
+}
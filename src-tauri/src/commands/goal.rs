@@ -9,6 +9,10 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tauri::{AppHandle, State};
 use wealthvn_core::goals::goals_model::{Goal, GoalsAllocation, NewGoal, AllocationVersion};
+use wealthvn_core::goals::{
+    GoalHistoryInterval, GoalPlan, GoalProgressHistory, GoalProjection, GoalProjectionOptions,
+    WaterfallAllocation,
+};
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -248,3 +252,95 @@ pub async fn delete_goal_allocation(
 
     Ok(result)
 }
+
+#[tauri::command]
+pub async fn project_goal(
+    goal_id: String,
+    options: Option<GoalProjectionOptions>,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<GoalProjection, String> {
+    debug!("Projecting goal {}...", goal_id);
+    state
+        .goal_projection_service()
+        .project_goal(&goal_id, options.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_goal_plan(
+    goal_id: String,
+    current_value: f64,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<GoalPlan, String> {
+    debug!("Solving plan for goal {}...", goal_id);
+    state
+        .goal_service()
+        .get_goal_plan(&goal_id, current_value)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_goal_progress_history(
+    goal_id: String,
+    interval: Option<GoalHistoryInterval>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<GoalProgressHistory, String> {
+    debug!("Building progress history for goal {}...", goal_id);
+    let start_date = start_date
+        .map(|date_str| {
+            chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")
+                .map_err(|e| format!("Invalid start date: {}", e))
+        })
+        .transpose()?;
+    let end_date = end_date
+        .map(|date_str| {
+            chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")
+                .map_err(|e| format!("Invalid end date: {}", e))
+        })
+        .transpose()?;
+
+    state
+        .goal_history_service()
+        .get_goal_progress_history(&goal_id, interval.unwrap_or_default(), start_date, end_date)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn preview_waterfall_allocation(
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<WaterfallAllocation, String> {
+    debug!("Previewing waterfall allocation...");
+    state
+        .goal_waterfall_service()
+        .preview_waterfall_allocation()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn apply_waterfall_allocation(
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<WaterfallAllocation, String> {
+    debug!("Applying waterfall allocation...");
+    let result = state
+        .goal_waterfall_service()
+        .apply_waterfall_allocation()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !result.changed_allocation_ids.is_empty() {
+        emit_resource_changed(
+            &handle,
+            ResourceEventPayload::new(
+                "goal_allocation",
+                "updated",
+                json!({ "allocation_ids": result.changed_allocation_ids }),
+            ),
+        );
+    }
+
+    Ok(result)
+}
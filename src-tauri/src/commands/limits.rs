@@ -7,7 +7,9 @@ use crate::{
 use log::debug;
 use serde_json::json;
 use tauri::{AppHandle, State};
-use wealthvn_core::limits::{ContributionLimit, DepositsCalculation, NewContributionLimit};
+use wealthvn_core::limits::{
+    ContributionLimit, ContributionRoom, DepositsCalculation, NewContributionLimit,
+};
 
 #[tauri::command]
 pub async fn get_contribution_limits(
@@ -100,3 +102,16 @@ pub async fn calculate_deposits_for_contribution_limit(
         .calculate_deposits_for_contribution_limit(&limit_id, &base_currency)
         .map_err(|e| format!("Failed to calculate deposits for contribution limit: {}", e))
 }
+
+#[tauri::command]
+pub async fn get_contribution_room(
+    limit_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<ContributionRoom, String> {
+    debug!("Calculating contribution room...");
+    let base_currency = state.base_currency.read().unwrap();
+    state
+        .limits_service()
+        .get_contribution_room(&limit_id, &base_currency)
+        .map_err(|e| format!("Failed to calculate contribution room: {}", e))
+}
@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use crate::{
+    context::ServiceContext,
+    events::{emit_resource_changed, ResourceEventPayload},
+};
+use log::debug;
+use serde_json::json;
+use tauri::{AppHandle, State};
+use wealthvn_core::activities::Activity;
+use wealthvn_core::margin::{MarginSettings, MarginSettingsUpdate, MarginSummary};
+
+#[tauri::command]
+pub async fn get_margin_settings(
+    account_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Option<MarginSettings>, String> {
+    debug!("Fetching margin settings for account {}...", account_id);
+    state
+        .margin_service()
+        .get_margin_settings(&account_id)
+        .map_err(|e| format!("Failed to load margin settings: {}", e))
+}
+
+#[tauri::command]
+pub async fn save_margin_settings(
+    settings: MarginSettingsUpdate,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<MarginSettings, String> {
+    debug!("Saving margin settings...");
+    let settings = state
+        .margin_service()
+        .save_margin_settings(settings)
+        .await
+        .map_err(|e| format!("Failed to save margin settings: {}", e))?;
+
+    emit_resource_changed(
+        &handle,
+        ResourceEventPayload::new(
+            "margin_settings",
+            "updated",
+            json!({ "account_id": settings.account_id }),
+        ),
+    );
+
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn delete_margin_settings(
+    account_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<(), String> {
+    debug!("Disabling margin for account {}...", account_id);
+    state
+        .margin_service()
+        .delete_margin_settings(&account_id)
+        .await
+        .map_err(|e| format!("Failed to delete margin settings: {}", e))?;
+
+    emit_resource_changed(
+        &handle,
+        ResourceEventPayload::new(
+            "margin_settings",
+            "deleted",
+            json!({ "account_id": account_id }),
+        ),
+    );
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_margin_summary(
+    account_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<MarginSummary, String> {
+    debug!("Calculating margin summary for account {}...", account_id);
+    state
+        .margin_service()
+        .get_margin_summary(&account_id)
+        .await
+        .map_err(|e| format!("Failed to calculate margin summary: {}", e))
+}
+
+#[tauri::command]
+pub async fn accrue_margin_interest(
+    account_id: String,
+    through_date: String,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<Vec<Activity>, String> {
+    debug!(
+        "Accruing margin interest for account {} through {}...",
+        account_id, through_date
+    );
+    let through = chrono::NaiveDate::parse_from_str(&through_date, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date '{}': {}", through_date, e))?;
+    let created = state
+        .margin_service()
+        .accrue_margin_interest(&account_id, through)
+        .await
+        .map_err(|e| format!("Failed to accrue margin interest: {}", e))?;
+
+    // Each charge is a new activity, so the usual activity events drive recalculation
+    for activity in &created {
+        emit_resource_changed(
+            &handle,
+            ResourceEventPayload::new(
+                "activity",
+                "created",
+                json!({
+                    "activity_id": activity.id,
+                    "account_id": activity.account_id,
+                    "currency": activity.currency,
+                    "asset_id": activity.asset_id,
+                }),
+            ),
+        );
+    }
+
+    Ok(created)
+}
@@ -2,13 +2,19 @@ pub mod account;
 pub mod activity;
 pub mod addon;
 pub mod asset;
+pub mod attachment;
+pub mod composite;
 pub mod error;
 pub mod goal;
 pub mod limits;
+pub mod margin;
 pub mod market_data;
 pub mod platform;
 pub mod portfolio;
 pub mod providers_settings;
+pub mod reconciliation;
+pub mod retirement;
 pub mod secrets;
 pub mod settings;
+pub mod tag;
 pub mod utilities;
@@ -10,9 +10,14 @@ use crate::{
 use log::debug;
 use tauri::{AppHandle, State};
 use wealthvn_core::{
+    attribution::ReturnAttribution,
+    fx::FxRateSide,
     holdings::Holding,
-    income::IncomeSummary,
-    performance::{PerformanceMetrics, SimplePerformanceMetrics},
+    income::{IncomeForecast, IncomeReport, IncomeReportFilter, IncomeSummary},
+    performance::{
+        BenchmarkComparison, NewRiskFreeRate, PerformanceMetrics, PeriodicReturns, RiskFreeRate,
+        SimplePerformanceMetrics,
+    },
     valuation::DailyAccountValuation,
 };
 
@@ -72,6 +77,23 @@ pub async fn get_holding(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn get_holdings_as_of(
+    state: State<'_, Arc<ServiceContext>>,
+    account_id: String,
+    date: String,
+) -> Result<Vec<Holding>, String> {
+    debug!("Get holdings for account {} as of {}", account_id, date);
+    let as_of_date = chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date: {}", e))?;
+    let base_currency = state.get_base_currency();
+    state
+        .holdings_service()
+        .get_holdings_as_of(&account_id, as_of_date, &base_currency)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn get_historical_valuations(
     state: State<'_, Arc<ServiceContext>>,
@@ -105,6 +127,7 @@ pub async fn get_historical_valuations(
 pub async fn get_latest_valuations(
     state: State<'_, Arc<ServiceContext>>,
     account_ids: Vec<String>,
+    fx_side: Option<FxRateSide>,
 ) -> Result<Vec<DailyAccountValuation>, String> {
     debug!("Get latest valuations for accounts: {:?}", account_ids);
 
@@ -127,18 +150,42 @@ pub async fn get_latest_valuations(
 
     state
         .valuation_service()
-        .get_latest_valuations(&ids_to_process)
+        .get_latest_valuations_at_side(&ids_to_process, fx_side.unwrap_or_default())
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub async fn get_income_summary(
+    tag_id: Option<String>,
     state: State<'_, Arc<ServiceContext>>,
 ) -> Result<Vec<IncomeSummary>, String> {
     debug!("Fetching income summary...");
     state
         .income_service()
-        .get_income_summary()
+        .get_income_summary(tag_id.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_income_forecast(
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<IncomeForecast, String> {
+    debug!("Fetching income forecast...");
+    state
+        .income_service()
+        .get_income_forecast()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_income_report(
+    filter: IncomeReportFilter,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<IncomeReport, String> {
+    debug!("Fetching income report...");
+    state
+        .income_service()
+        .get_income_report(filter)
         .map_err(|e| e.to_string())
 }
 
@@ -248,3 +295,219 @@ pub async fn calculate_performance_summary(
         .await
         .map_err(|e| format!("Failed to calculate performance: {}", e.to_string()))
 }
+
+/// Calculates monthly, quarterly and yearly returns for an item (account, tag or symbol).
+#[tauri::command]
+pub async fn calculate_periodic_returns(
+    state: State<'_, Arc<ServiceContext>>,
+    item_type: String,
+    item_id: String,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> Result<PeriodicReturns, String> {
+    debug!(
+        "Calculating periodic returns for type: {}, id: {}, start: {:?}, end: {:?}",
+        item_type, item_id, start_date, end_date
+    );
+
+    let start_date_opt: Option<chrono::NaiveDate> = start_date
+        .map(|date_str| {
+            chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")
+                .map_err(|e| format!("Invalid start date format '{}': {}", date_str, e))
+        })
+        .transpose()?;
+
+    let end_date_opt: Option<chrono::NaiveDate> = end_date
+        .map(|date_str| {
+            chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")
+                .map_err(|e| format!("Invalid end date format '{}': {}", date_str, e))
+        })
+        .transpose()?;
+
+    state
+        .performance_service()
+        .calculate_periodic_returns(&item_type, &item_id, start_date_opt, end_date_opt)
+        .await
+        .map_err(|e| format!("Failed to calculate periodic returns: {}", e))
+}
+
+/// Daily valuation series of an account, or the base-currency aggregate of a tag or
+/// composite portfolio.
+#[tauri::command]
+pub async fn get_aggregated_valuation_history(
+    state: State<'_, Arc<ServiceContext>>,
+    item_type: String,
+    item_id: String,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> Result<Vec<DailyAccountValuation>, String> {
+    debug!(
+        "Fetching aggregated valuations for type: {}, id: {}, start: {:?}, end: {:?}",
+        item_type, item_id, start_date, end_date
+    );
+
+    let start_date_opt: Option<chrono::NaiveDate> = start_date
+        .map(|date_str| {
+            chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")
+                .map_err(|e| format!("Invalid start date format '{}': {}", date_str, e))
+        })
+        .transpose()?;
+
+    let end_date_opt: Option<chrono::NaiveDate> = end_date
+        .map(|date_str| {
+            chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")
+                .map_err(|e| format!("Invalid end date format '{}': {}", date_str, e))
+        })
+        .transpose()?;
+
+    state
+        .performance_service()
+        .get_aggregated_valuation_history(&item_type, &item_id, start_date_opt, end_date_opt)
+        .map_err(|e| format!("Failed to load aggregated valuations: {}", e))
+}
+
+/// Breaks an account's return over a date range down by holding, asset class, sector
+/// and currency.
+#[tauri::command]
+pub async fn calculate_return_attribution(
+    state: State<'_, Arc<ServiceContext>>,
+    account_id: String,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> Result<ReturnAttribution, String> {
+    debug!(
+        "Calculating return attribution for account: {}, start: {:?}, end: {:?}",
+        account_id, start_date, end_date
+    );
+
+    let start_date_opt: Option<chrono::NaiveDate> = start_date
+        .map(|date_str| {
+            chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")
+                .map_err(|e| format!("Invalid start date format '{}': {}", date_str, e))
+        })
+        .transpose()?;
+
+    let end_date_opt: Option<chrono::NaiveDate> = end_date
+        .map(|date_str| {
+            chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")
+                .map_err(|e| format!("Invalid end date format '{}': {}", date_str, e))
+        })
+        .transpose()?;
+
+    state
+        .attribution_service()
+        .calculate_return_attribution(&account_id, start_date_opt, end_date_opt)
+        .await
+        .map_err(|e| format!("Failed to calculate return attribution: {}", e))
+}
+
+/// Compares an account or tag with a benchmark over a given date range.
+/// Uses the account's configured benchmark when no symbol is given.
+#[tauri::command]
+pub async fn calculate_benchmark_comparison(
+    state: State<'_, Arc<ServiceContext>>,
+    item_type: String,
+    item_id: String,
+    benchmark_symbol: Option<String>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> Result<BenchmarkComparison, String> {
+    debug!(
+        "Calculating benchmark comparison for type: {}, id: {}, benchmark: {:?}",
+        item_type, item_id, benchmark_symbol
+    );
+
+    let start_date_opt: Option<chrono::NaiveDate> = start_date
+        .map(|date_str| {
+            chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")
+                .map_err(|e| format!("Invalid start date format '{}': {}", date_str, e))
+        })
+        .transpose()?;
+
+    let end_date_opt: Option<chrono::NaiveDate> = end_date
+        .map(|date_str| {
+            chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")
+                .map_err(|e| format!("Invalid end date format '{}': {}", date_str, e))
+        })
+        .transpose()?;
+
+    state
+        .performance_service()
+        .calculate_benchmark_comparison(
+            &item_type,
+            &item_id,
+            benchmark_symbol.as_deref(),
+            start_date_opt,
+            end_date_opt,
+        )
+        .await
+        .map_err(|e| format!("Failed to calculate benchmark comparison: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_account_benchmark(
+    state: State<'_, Arc<ServiceContext>>,
+    account_id: String,
+) -> Result<String, String> {
+    debug!("Fetching benchmark for account {}", account_id);
+    state
+        .performance_service()
+        .get_benchmark_symbol(&account_id)
+        .map_err(|e| format!("Failed to load account benchmark: {}", e))
+}
+
+#[tauri::command]
+pub async fn set_account_benchmark(
+    state: State<'_, Arc<ServiceContext>>,
+    account_id: String,
+    symbol: Option<String>,
+) -> Result<String, String> {
+    debug!("Setting benchmark for account {}: {:?}", account_id, symbol);
+    state
+        .performance_service()
+        .set_benchmark_symbol(&account_id, symbol)
+        .await
+        .map_err(|e| format!("Failed to set account benchmark: {}", e))
+}
+
+/// Lists the rates of a risk-free series; defaults to the series selected in settings.
+#[tauri::command]
+pub async fn get_risk_free_rates(
+    state: State<'_, Arc<ServiceContext>>,
+    series: Option<String>,
+) -> Result<Vec<RiskFreeRate>, String> {
+    debug!("Fetching risk-free rates for series {:?}", series);
+    state
+        .performance_service()
+        .get_risk_free_rates(series.as_deref())
+        .map_err(|e| format!("Failed to load risk-free rates: {}", e))
+}
+
+#[tauri::command]
+pub async fn save_risk_free_rate(
+    state: State<'_, Arc<ServiceContext>>,
+    rate: NewRiskFreeRate,
+) -> Result<RiskFreeRate, String> {
+    debug!(
+        "Saving risk-free rate {} for {} on {}",
+        rate.annual_rate, rate.series, rate.effective_date
+    );
+    state
+        .performance_service()
+        .save_risk_free_rate(rate)
+        .await
+        .map_err(|e| format!("Failed to save risk-free rate: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_risk_free_rate(
+    state: State<'_, Arc<ServiceContext>>,
+    rate_id: String,
+) -> Result<(), String> {
+    debug!("Deleting risk-free rate {}", rate_id);
+    state
+        .performance_service()
+        .delete_risk_free_rate(&rate_id)
+        .await
+        .map_err(|e| format!("Failed to delete risk-free rate: {}", e))
+}
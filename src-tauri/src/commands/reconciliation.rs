@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use crate::{
+    context::ServiceContext,
+    events::{emit_resource_changed, ResourceEventPayload},
+};
+use log::debug;
+use serde_json::json;
+use tauri::{AppHandle, State};
+use wealthvn_core::reconciliation::{BrokerStatement, Reconciliation, ReconciliationReport};
+
+#[tauri::command]
+pub async fn get_reconciliations(
+    account_id: Option<String>,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<Reconciliation>, String> {
+    debug!("Fetching reconciliations...");
+    state
+        .reconciliation_service()
+        .get_reconciliations(account_id.as_deref())
+        .map_err(|e| format!("Failed to load reconciliations: {}", e))
+}
+
+#[tauri::command]
+pub async fn preview_reconciliation(
+    statement: BrokerStatement,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<ReconciliationReport, String> {
+    debug!(
+        "Comparing broker statement for {} on {}...",
+        statement.account_id, statement.statement_date
+    );
+    state
+        .reconciliation_service()
+        .preview_reconciliation(statement)
+        .await
+        .map_err(|e| format!("Failed to compare broker statement: {}", e))
+}
+
+#[tauri::command]
+pub async fn save_reconciliation(
+    statement: BrokerStatement,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<Reconciliation, String> {
+    debug!("Saving reconciliation...");
+    let reconciliation = state
+        .reconciliation_service()
+        .save_reconciliation(statement)
+        .await
+        .map_err(|e| format!("Failed to save reconciliation: {}", e))?;
+
+    emit_resource_changed(
+        &handle,
+        ResourceEventPayload::new(
+            "reconciliation",
+            "updated",
+            json!({
+                "reconciliation_id": reconciliation.id,
+                "account_id": reconciliation.account_id,
+            }),
+        ),
+    );
+
+    Ok(reconciliation)
+}
+
+#[tauri::command]
+pub async fn recheck_reconciliation(
+    reconciliation_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<Reconciliation, String> {
+    debug!("Rechecking reconciliation {}...", reconciliation_id);
+    let reconciliation = state
+        .reconciliation_service()
+        .recheck_reconciliation(&reconciliation_id)
+        .await
+        .map_err(|e| format!("Failed to recheck reconciliation: {}", e))?;
+
+    emit_resource_changed(
+        &handle,
+        ResourceEventPayload::new(
+            "reconciliation",
+            "updated",
+            json!({
+                "reconciliation_id": reconciliation.id,
+                "account_id": reconciliation.account_id,
+            }),
+        ),
+    );
+
+    Ok(reconciliation)
+}
+
+#[tauri::command]
+pub async fn delete_reconciliation(
+    reconciliation_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<(), String> {
+    debug!("Deleting reconciliation...");
+    state
+        .reconciliation_service()
+        .delete_reconciliation(&reconciliation_id)
+        .await
+        .map_err(|e| format!("Failed to delete reconciliation: {}", e))?;
+
+    emit_resource_changed(
+        &handle,
+        ResourceEventPayload::new(
+            "reconciliation",
+            "deleted",
+            json!({ "reconciliation_id": reconciliation_id }),
+        ),
+    );
+
+    Ok(())
+}
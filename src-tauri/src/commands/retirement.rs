@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use crate::{
+    context::ServiceContext,
+    events::{emit_resource_changed, ResourceEventPayload},
+};
+use log::debug;
+use serde_json::json;
+use tauri::{AppHandle, State};
+use wealthvn_core::retirement::{
+    NewRetirementScenario, RetirementInputs, RetirementPlan, RetirementScenario,
+};
+
+#[tauri::command]
+pub async fn calculate_retirement_plan(
+    inputs: RetirementInputs,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<RetirementPlan, String> {
+    debug!("Calculating retirement plan...");
+    state
+        .retirement_service()
+        .calculate_retirement_plan(inputs)
+        .await
+        .map_err(|e| format!("Failed to calculate retirement plan: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_retirement_scenarios(
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<RetirementScenario>, String> {
+    debug!("Fetching retirement scenarios...");
+    state
+        .retirement_service()
+        .get_scenarios()
+        .map_err(|e| format!("Failed to load retirement scenarios: {}", e))
+}
+
+#[tauri::command]
+pub async fn save_retirement_scenario(
+    scenario: NewRetirementScenario,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<RetirementScenario, String> {
+    debug!("Saving retirement scenario {}...", scenario.name);
+    let scenario = state
+        .retirement_service()
+        .save_scenario(scenario)
+        .await
+        .map_err(|e| format!("Failed to save retirement scenario: {}", e))?;
+
+    emit_resource_changed(
+        &handle,
+        ResourceEventPayload::new(
+            "retirement_scenario",
+            "updated",
+            json!({ "scenario_id": scenario.id }),
+        ),
+    );
+
+    Ok(scenario)
+}
+
+#[tauri::command]
+pub async fn delete_retirement_scenario(
+    scenario_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<usize, String> {
+    debug!("Deleting retirement scenario {}...", scenario_id);
+    let deleted = state
+        .retirement_service()
+        .delete_scenario(&scenario_id)
+        .await
+        .map_err(|e| format!("Failed to delete retirement scenario: {}", e))?;
+
+    emit_resource_changed(
+        &handle,
+        ResourceEventPayload::new(
+            "retirement_scenario",
+            "deleted",
+            json!({ "scenario_id": scenario_id }),
+        ),
+    );
+
+    Ok(deleted)
+}
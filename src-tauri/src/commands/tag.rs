@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use crate::{
+    context::ServiceContext,
+    events::{emit_resource_changed, ResourceEventPayload},
+};
+use log::debug;
+use serde_json::json;
+use tauri::{AppHandle, State};
+use wealthvn_core::tags::{NewTag, Tag, TagUpdate};
+
+#[tauri::command]
+pub async fn get_tags(state: State<'_, Arc<ServiceContext>>) -> Result<Vec<Tag>, String> {
+    debug!("Fetching tags...");
+    state
+        .tag_service()
+        .get_tags()
+        .map_err(|e| format!("Failed to load tags: {}", e))
+}
+
+#[tauri::command]
+pub async fn create_tag(
+    tag: NewTag,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<Tag, String> {
+    debug!("Creating tag...");
+    let tag = state
+        .tag_service()
+        .create_tag(tag)
+        .await
+        .map_err(|e| format!("Failed to create tag: {}", e))?;
+
+    emit_resource_changed(
+        &handle,
+        ResourceEventPayload::new("tag", "created", json!({ "tag_id": tag.id })),
+    );
+
+    Ok(tag)
+}
+
+#[tauri::command]
+pub async fn update_tag(
+    tag: TagUpdate,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<Tag, String> {
+    debug!("Updating tag...");
+    let tag = state
+        .tag_service()
+        .update_tag(tag)
+        .await
+        .map_err(|e| format!("Failed to update tag: {}", e))?;
+
+    emit_resource_changed(
+        &handle,
+        ResourceEventPayload::new("tag", "updated", json!({ "tag_id": tag.id })),
+    );
+
+    Ok(tag)
+}
+
+#[tauri::command]
+pub async fn delete_tag(
+    tag_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<(), String> {
+    debug!("Deleting tag...");
+    state
+        .tag_service()
+        .delete_tag(&tag_id)
+        .await
+        .map_err(|e| format!("Failed to delete tag: {}", e))?;
+
+    emit_resource_changed(
+        &handle,
+        ResourceEventPayload::new("tag", "deleted", json!({ "tag_id": tag_id })),
+    );
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_activity_tags(
+    activity_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<Tag>, String> {
+    state
+        .tag_service()
+        .get_activity_tags(&activity_id)
+        .map_err(|e| format!("Failed to load activity tags: {}", e))
+}
+
+#[tauri::command]
+pub async fn set_activity_tags(
+    activity_id: String,
+    tag_ids: Vec<String>,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<Vec<Tag>, String> {
+    debug!("Setting tags for activity {}", activity_id);
+    let tags = state
+        .tag_service()
+        .set_activity_tags(&activity_id, tag_ids)
+        .await
+        .map_err(|e| format!("Failed to set activity tags: {}", e))?;
+
+    emit_resource_changed(
+        &handle,
+        ResourceEventPayload::new("tag", "assigned", json!({ "activity_id": activity_id })),
+    );
+
+    Ok(tags)
+}
+
+#[tauri::command]
+pub async fn get_account_tags(
+    account_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<Tag>, String> {
+    state
+        .tag_service()
+        .get_account_tags(&account_id)
+        .map_err(|e| format!("Failed to load account tags: {}", e))
+}
+
+#[tauri::command]
+pub async fn set_account_tags(
+    account_id: String,
+    tag_ids: Vec<String>,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<Vec<Tag>, String> {
+    debug!("Setting tags for account {}", account_id);
+    let tags = state
+        .tag_service()
+        .set_account_tags(&account_id, tag_ids)
+        .await
+        .map_err(|e| format!("Failed to set account tags: {}", e))?;
+
+    emit_resource_changed(
+        &handle,
+        ResourceEventPayload::new("tag", "assigned", json!({ "account_id": account_id })),
+    );
+
+    Ok(tags)
+}
@@ -66,6 +66,8 @@ pub async fn backup_database(app_handle: AppHandle) -> Result<(String, Vec<u8>),
         .to_string();
 
     let backup_path = db::backup_database(&app_data_dir).map_err(|e| e.to_string())?;
+    // Download a single archive holding the database and its attachments
+    let backup_path = db::create_backup_bundle(&backup_path).map_err(|e| e.to_string())?;
 
     // Read the backup file
     let mut file =
@@ -135,6 +137,8 @@ pub async fn backup_database_to_path(
             .map_err(|e| format!("Failed to copy SHM file: {}", e))?;
     }
 
+    db::backup_attachments(&app_data_dir, &backup_path_str).map_err(|e| e.to_string())?;
+
     Ok(backup_path_str)
 }
 
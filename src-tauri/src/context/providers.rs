@@ -3,20 +3,31 @@ use std::sync::{Arc, RwLock};
 use wealthvn_core::{
     accounts::{AccountRepository, AccountService},
     activities::{ActivityRepository, ActivityService},
+    attachments::{AttachmentRepository, AttachmentService},
+    composites::{CompositeRepository, CompositeService},
     db::{self, write_actor},
     fx::{FxRepository, FxService, FxServiceTrait},
-    goals::{GoalRepository, GoalService},
+    goals::{
+        GoalHistoryService, GoalProjectionService, GoalRepository, GoalService,
+        GoalWaterfallService,
+    },
     limits::{ContributionLimitRepository, ContributionLimitService},
+    margin::{MarginRepository, MarginService},
     market_data::{MarketDataRepository, MarketDataService, MarketDataServiceTrait},
     portfolio::{
+        attribution::AttributionService,
+        checkpoint::ValuationCheckpointRepository,
         holdings::{HoldingsService, HoldingsValuationService},
         income::IncomeService,
-        performance::PerformanceService,
+        performance::{BenchmarkRepository, PerformanceService, RiskFreeRateRepository},
     },
+    reconciliation::{ReconciliationRepository, ReconciliationService},
+    retirement::{RetirementRepository, RetirementService},
     settings::{settings_repository::SettingsRepository, SettingsService, SettingsServiceTrait},
     snapshot::{SnapshotRepository, SnapshotService},
+    tags::{TagRepository, TagService},
     valuation::{ValuationRepository, ValuationService},
-    vn_market::VnAssetsSyncService,
+    vn_market::{VnAssetsSyncService, VnMarketService},
     AssetRepository, AssetService,
 };
 
@@ -46,6 +57,18 @@ pub async fn initialize_context(
     let fx_repository = Arc::new(FxRepository::new(pool.clone(), writer.clone()));
     let snapshot_repository = Arc::new(SnapshotRepository::new(pool.clone(), writer.clone()));
     let valuation_repository = Arc::new(ValuationRepository::new(pool.clone(), writer.clone()));
+    let checkpoint_repository = Arc::new(ValuationCheckpointRepository::new(
+        pool.clone(),
+        writer.clone(),
+    ));
+    let tag_repository = Arc::new(TagRepository::new(pool.clone(), writer.clone()));
+    let attachment_repository = Arc::new(AttachmentRepository::new(pool.clone(), writer.clone()));
+    let benchmark_repository = Arc::new(BenchmarkRepository::new(pool.clone(), writer.clone()));
+    let risk_free_rate_repository =
+        Arc::new(RiskFreeRateRepository::new(pool.clone(), writer.clone()));
+    let composite_repository = Arc::new(CompositeRepository::new(pool.clone(), writer.clone()));
+    let reconciliation_repository =
+        Arc::new(ReconciliationRepository::new(pool.clone(), writer.clone()));
     // Instantiate Transaction Executor using the Arc<DbPool> directly
     let transaction_executor = pool.clone();
 
@@ -82,13 +105,6 @@ pub async fn initialize_context(
         transaction_executor.clone(),
         base_currency.clone(),
     ));
-    let activity_service = Arc::new(ActivityService::new(
-        activity_repository.clone(),
-        account_service.clone(),
-        asset_service.clone(),
-        fx_service.clone(),
-        market_data_service.clone(),
-    ));
     let goal_service = Arc::new(GoalService::new(goal_repo.clone()));
     let limits_service = Arc::new(ContributionLimitService::new(
         fx_service.clone(),
@@ -96,12 +112,6 @@ pub async fn initialize_context(
         activity_repository.clone(),
     ));
 
-    let income_service = Arc::new(IncomeService::new(
-        fx_service.clone(),
-        activity_repository.clone(),
-        base_currency.clone(),
-    ));
-
     let snapshot_service = Arc::new(SnapshotService::new(
         base_currency.clone(),
         account_repository.clone(),
@@ -109,6 +119,15 @@ pub async fn initialize_context(
         snapshot_repository.clone(),
         asset_repository.clone(),
         fx_service.clone(),
+        checkpoint_repository.clone(),
+    ));
+
+    let income_service = Arc::new(IncomeService::new(
+        fx_service.clone(),
+        activity_repository.clone(),
+        snapshot_service.clone(),
+        market_data_service.clone(),
+        base_currency.clone(),
     ));
 
     let holdings_valuation_service = Arc::new(HoldingsValuationService::new(
@@ -122,11 +141,60 @@ pub async fn initialize_context(
         snapshot_service.clone(),
         market_data_service.clone(),
         fx_service.clone(),
+        checkpoint_repository.clone(),
+    ));
+
+    let attachment_service = Arc::new(AttachmentService::new(
+        attachment_repository.clone(),
+        activity_repository.clone(),
+        db::get_attachments_dir(app_data_dir),
+    ));
+
+    let activity_service = Arc::new(ActivityService::new(
+        activity_repository.clone(),
+        account_service.clone(),
+        asset_service.clone(),
+        fx_service.clone(),
+        market_data_service.clone(),
+        snapshot_service.clone(),
+        valuation_service.clone(),
+        attachment_service.clone(),
     ));
 
     let performance_service = Arc::new(PerformanceService::new(
         valuation_service.clone(),
         market_data_service.clone(),
+        tag_repository.clone(),
+        benchmark_repository,
+        Arc::new(VnMarketService::with_pool(pool.as_ref().clone())),
+        risk_free_rate_repository,
+        settings_service.clone(),
+        composite_repository.clone(),
+        activity_repository.clone(),
+        fx_service.clone(),
+    ));
+
+    let goal_projection_service = Arc::new(GoalProjectionService::new(
+        goal_repo.clone(),
+        valuation_service.clone(),
+        performance_service.clone(),
+    ));
+    let goal_history_service = Arc::new(GoalHistoryService::new(
+        goal_repo.clone(),
+        valuation_service.clone(),
+    ));
+    let goal_waterfall_service = Arc::new(GoalWaterfallService::new(
+        goal_repo.clone(),
+        account_service.clone(),
+        valuation_service.clone(),
+    ));
+
+    let attribution_service = Arc::new(AttributionService::new(
+        valuation_service.clone(),
+        snapshot_service.clone(),
+        market_data_service.clone(),
+        fx_service.clone(),
+        asset_service.clone(),
     ));
 
     let holdings_service = Arc::new(HoldingsService::new(
@@ -135,6 +203,26 @@ pub async fn initialize_context(
         holdings_valuation_service.clone(),
     ));
 
+    let tag_service = Arc::new(TagService::new(tag_repository.clone()));
+    let composite_service = Arc::new(CompositeService::new(composite_repository.clone()));
+    let margin_service = Arc::new(MarginService::new(
+        Arc::new(MarginRepository::new(pool.clone(), writer.clone())),
+        account_service.clone(),
+        activity_service.clone(),
+        snapshot_service.clone(),
+        holdings_service.clone(),
+    ));
+    let reconciliation_service = Arc::new(ReconciliationService::new(
+        reconciliation_repository,
+        snapshot_service.clone(),
+        asset_service.clone(),
+    ));
+    let retirement_service = Arc::new(RetirementService::new(
+        Arc::new(RetirementRepository::new(pool.clone(), writer.clone())),
+        account_service.clone(),
+        valuation_service.clone(),
+    ));
+
     let vn_assets_sync_service = Arc::new(VnAssetsSyncService::new(pool.clone()));
 
     Ok(ServiceContext {
@@ -145,14 +233,24 @@ pub async fn initialize_context(
         activity_service,
         asset_service,
         goal_service,
+        goal_projection_service,
+        goal_history_service,
+        goal_waterfall_service,
         market_data_service,
         limits_service,
+        margin_service,
         fx_service,
         performance_service,
         income_service,
+        attribution_service,
         snapshot_service,
         holdings_service,
         valuation_service,
+        tag_service,
+        composite_service,
+        reconciliation_service,
+        retirement_service,
+        attachment_service,
         vn_assets_sync_service,
     })
 }
@@ -1,6 +1,7 @@
 use std::sync::{Arc, RwLock};
 use wealthvn_core::{
-    self, accounts, activities, assets, fx, goals, limits, market_data, portfolio, settings,
+    self, accounts, activities, assets, attachments, composites, fx, goals, limits, margin,
+    market_data, portfolio, reconciliation, retirement, settings, tags,
     vn_market::VnAssetsSyncService,
 };
 pub struct ServiceContext {
@@ -12,15 +13,25 @@ pub struct ServiceContext {
     pub activity_service: Arc<dyn activities::ActivityServiceTrait>,
     pub account_service: Arc<dyn accounts::AccountServiceTrait>,
     pub goal_service: Arc<dyn goals::GoalServiceTrait>,
+    pub goal_projection_service: Arc<dyn goals::GoalProjectionServiceTrait>,
+    pub goal_history_service: Arc<dyn goals::GoalHistoryServiceTrait>,
+    pub goal_waterfall_service: Arc<dyn goals::GoalWaterfallServiceTrait>,
     pub asset_service: Arc<dyn assets::AssetServiceTrait>,
     pub market_data_service: Arc<dyn market_data::MarketDataServiceTrait>,
     pub limits_service: Arc<dyn limits::ContributionLimitServiceTrait>,
+    pub margin_service: Arc<dyn margin::MarginServiceTrait>,
     pub fx_service: Arc<dyn fx::FxServiceTrait>,
     pub performance_service: Arc<dyn portfolio::performance::PerformanceServiceTrait>,
     pub income_service: Arc<dyn portfolio::income::IncomeServiceTrait>,
+    pub attribution_service: Arc<dyn portfolio::attribution::AttributionServiceTrait>,
     pub snapshot_service: Arc<dyn portfolio::snapshot::SnapshotServiceTrait>,
     pub holdings_service: Arc<dyn portfolio::holdings::HoldingsServiceTrait>,
     pub valuation_service: Arc<dyn portfolio::valuation::ValuationServiceTrait>,
+    pub tag_service: Arc<dyn tags::TagServiceTrait>,
+    pub composite_service: Arc<dyn composites::CompositeServiceTrait>,
+    pub reconciliation_service: Arc<dyn reconciliation::ReconciliationServiceTrait>,
+    pub retirement_service: Arc<dyn retirement::RetirementServiceTrait>,
+    pub attachment_service: Arc<dyn attachments::AttachmentServiceTrait>,
     pub vn_assets_sync_service: Arc<VnAssetsSyncService>,
 }
 
@@ -53,6 +64,18 @@ impl ServiceContext {
         Arc::clone(&self.goal_service)
     }
 
+    pub fn goal_projection_service(&self) -> Arc<dyn goals::GoalProjectionServiceTrait> {
+        Arc::clone(&self.goal_projection_service)
+    }
+
+    pub fn goal_history_service(&self) -> Arc<dyn goals::GoalHistoryServiceTrait> {
+        Arc::clone(&self.goal_history_service)
+    }
+
+    pub fn goal_waterfall_service(&self) -> Arc<dyn goals::GoalWaterfallServiceTrait> {
+        Arc::clone(&self.goal_waterfall_service)
+    }
+
     pub fn market_data_service(&self) -> Arc<dyn market_data::MarketDataServiceTrait> {
         Arc::clone(&self.market_data_service)
     }
@@ -61,6 +84,10 @@ impl ServiceContext {
         Arc::clone(&self.limits_service)
     }
 
+    pub fn margin_service(&self) -> Arc<dyn margin::MarginServiceTrait> {
+        Arc::clone(&self.margin_service)
+    }
+
     pub fn fx_service(&self) -> Arc<dyn fx::FxServiceTrait> {
         Arc::clone(&self.fx_service)
     }
@@ -73,6 +100,10 @@ impl ServiceContext {
         Arc::clone(&self.income_service)
     }
 
+    pub fn attribution_service(&self) -> Arc<dyn portfolio::attribution::AttributionServiceTrait> {
+        Arc::clone(&self.attribution_service)
+    }
+
     pub fn snapshot_service(&self) -> Arc<dyn portfolio::snapshot::SnapshotServiceTrait> {
         Arc::clone(&self.snapshot_service)
     }
@@ -85,6 +116,26 @@ impl ServiceContext {
         Arc::clone(&self.valuation_service)
     }
 
+    pub fn tag_service(&self) -> Arc<dyn tags::TagServiceTrait> {
+        Arc::clone(&self.tag_service)
+    }
+
+    pub fn composite_service(&self) -> Arc<dyn composites::CompositeServiceTrait> {
+        Arc::clone(&self.composite_service)
+    }
+
+    pub fn reconciliation_service(&self) -> Arc<dyn reconciliation::ReconciliationServiceTrait> {
+        Arc::clone(&self.reconciliation_service)
+    }
+
+    pub fn retirement_service(&self) -> Arc<dyn retirement::RetirementServiceTrait> {
+        Arc::clone(&self.retirement_service)
+    }
+
+    pub fn attachment_service(&self) -> Arc<dyn attachments::AttachmentServiceTrait> {
+        Arc::clone(&self.attachment_service)
+    }
+
     pub fn vn_assets_sync_service(&self) -> Arc<VnAssetsSyncService> {
         Arc::clone(&self.vn_assets_sync_service)
     }
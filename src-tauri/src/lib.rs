@@ -210,8 +210,11 @@ pub fn run() {
             commands::account::update_account,
             commands::account::delete_account,
             commands::activity::search_activities,
+            commands::activity::export_activities,
             commands::activity::get_activities,
             commands::activity::create_activity,
+            commands::activity::create_paired_transfer,
+            commands::activity::get_transfer_fx_costs,
             commands::activity::update_activity,
             commands::activity::save_activities,
             commands::activity::delete_activity,
@@ -219,6 +222,13 @@ pub fn run() {
             commands::activity::import_activities,
             commands::activity::get_account_import_mapping,
             commands::activity::save_account_import_mapping,
+            commands::activity::get_activity_history,
+            commands::activity::revert_activity_change,
+            commands::activity::revert_activity_batch,
+            commands::attachment::get_activity_attachments,
+            commands::attachment::add_activity_attachment,
+            commands::attachment::delete_activity_attachment,
+            commands::attachment::get_activity_attachment_path,
             commands::settings::get_settings,
             commands::settings::is_auto_update_check_enabled,
             commands::settings::update_settings,
@@ -237,21 +247,65 @@ pub fn run() {
             commands::goal::get_unallocated_balance,
             commands::goal::validate_allocation_percentages,
             commands::goal::get_allocation_versions,
+            commands::goal::project_goal,
+            commands::goal::get_goal_plan,
+            commands::goal::get_goal_progress_history,
+            commands::goal::preview_waterfall_allocation,
+            commands::goal::apply_waterfall_allocation,
             commands::portfolio::get_holdings,
             commands::portfolio::get_holding,
+            commands::portfolio::get_holdings_as_of,
             commands::portfolio::get_income_summary,
+            commands::portfolio::get_income_forecast,
+            commands::portfolio::get_income_report,
             commands::portfolio::get_historical_valuations,
             commands::portfolio::get_latest_valuations,
             commands::portfolio::calculate_accounts_simple_performance,
             commands::portfolio::update_portfolio,
             commands::portfolio::recalculate_portfolio,
             commands::portfolio::calculate_performance_summary,
+            commands::portfolio::calculate_periodic_returns,
+            commands::portfolio::calculate_return_attribution,
+            commands::portfolio::get_aggregated_valuation_history,
+            commands::portfolio::calculate_benchmark_comparison,
+            commands::portfolio::get_account_benchmark,
+            commands::portfolio::set_account_benchmark,
+            commands::portfolio::get_risk_free_rates,
+            commands::portfolio::save_risk_free_rate,
+            commands::portfolio::delete_risk_free_rate,
             commands::portfolio::calculate_performance_history,
             commands::limits::get_contribution_limits,
             commands::limits::create_contribution_limit,
             commands::limits::update_contribution_limit,
             commands::limits::delete_contribution_limit,
             commands::limits::calculate_deposits_for_contribution_limit,
+            commands::limits::get_contribution_room,
+            commands::tag::get_tags,
+            commands::tag::create_tag,
+            commands::tag::update_tag,
+            commands::tag::delete_tag,
+            commands::tag::get_activity_tags,
+            commands::tag::set_activity_tags,
+            commands::tag::get_account_tags,
+            commands::tag::set_account_tags,
+            commands::composite::get_composite_portfolios,
+            commands::composite::create_composite_portfolio,
+            commands::composite::update_composite_portfolio,
+            commands::composite::delete_composite_portfolio,
+            commands::margin::get_margin_settings,
+            commands::margin::save_margin_settings,
+            commands::margin::delete_margin_settings,
+            commands::margin::get_margin_summary,
+            commands::margin::accrue_margin_interest,
+            commands::reconciliation::get_reconciliations,
+            commands::reconciliation::preview_reconciliation,
+            commands::reconciliation::save_reconciliation,
+            commands::reconciliation::recheck_reconciliation,
+            commands::reconciliation::delete_reconciliation,
+            commands::retirement::calculate_retirement_plan,
+            commands::retirement::get_retirement_scenarios,
+            commands::retirement::save_retirement_scenario,
+            commands::retirement::delete_retirement_scenario,
             commands::utilities::get_app_info,
             commands::utilities::backup_database,
             commands::utilities::backup_database_to_path,
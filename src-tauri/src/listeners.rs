@@ -206,6 +206,11 @@ fn handle_account_resource_change(handle: AppHandle, event: &ResourceEventPayloa
 }
 
 fn handle_activity_resource_change(handle: AppHandle, event: &ResourceEventPayload) {
+    // The activity service already rebuilt the accounts touched by a revert
+    if event.action == "reverted" {
+        return;
+    }
+
     let context = match handle.try_state::<Arc<ServiceContext>>() {
         Some(ctx) => ctx,
         None => {
@@ -51,6 +51,7 @@ const COMMANDS: CommandMap = {
   update_activity: { method: "PUT", path: "/activities" },
   save_activities: { method: "POST", path: "/activities/bulk" },
   delete_activity: { method: "DELETE", path: "/activities" },
+  get_transfer_fx_costs: { method: "GET", path: "/activities/transfers/fx-costs" },
   // Activity import
   check_activities_import: { method: "POST", path: "/activities/import/check" },
   import_activities: { method: "POST", path: "/activities/import" },
@@ -178,11 +179,12 @@ export const invokeWeb = async <T>(
       break;
     }
     case "get_latest_valuations": {
-      const p = payload as { accountIds?: string[] };
+      const p = payload as { accountIds?: string[]; fxSide?: string };
       const params = new URLSearchParams();
       if (Array.isArray(p?.accountIds)) {
         for (const id of p.accountIds) params.append("accountIds[]", id);
       }
+      if (p?.fxSide) params.set("fxSide", p.fxSide);
       const qs = params.toString();
       if (qs) url += `?${qs}`;
       break;
@@ -6,6 +6,8 @@ import {
   ActivityDetails,
   ActivitySearchResponse,
   ActivityUpdate,
+  ActivityWithLimitWarnings,
+  TransferFxCost,
 } from "@/lib/types";
 import { getRunEnv, RUN_ENV, invokeTauri, invokeWeb, logger } from "@/adapters";
 
@@ -75,7 +77,9 @@ export const searchActivities = async (
   }
 };
 
-export const createActivity = async (activity: ActivityCreate): Promise<Activity> => {
+export const createActivity = async (
+  activity: ActivityCreate,
+): Promise<ActivityWithLimitWarnings> => {
   try {
     switch (getRunEnv()) {
       case RUN_ENV.DESKTOP:
@@ -145,3 +149,19 @@ export const deleteActivity = async (activityId: string): Promise<Activity> => {
     throw error;
   }
 };
+
+export const getTransferFxCosts = async (): Promise<TransferFxCost[]> => {
+  try {
+    switch (getRunEnv()) {
+      case RUN_ENV.DESKTOP:
+        return invokeTauri("get_transfer_fx_costs");
+      case RUN_ENV.WEB:
+        return invokeWeb("get_transfer_fx_costs");
+      default:
+        throw new Error(`Unsupported`);
+    }
+  } catch (error) {
+    logger.error("Error fetching transfer FX costs.");
+    throw error;
+  }
+};
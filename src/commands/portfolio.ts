@@ -3,6 +3,7 @@ import {
   Holding,
   IncomeSummary,
   AccountValuation,
+  FxRateSide,
   PerformanceMetrics,
   SimplePerformanceMetrics,
 } from "@/lib/types";
@@ -109,13 +110,16 @@ export const getHistoricalValuations = async (
   }
 };
 
-export const getLatestValuations = async (accountIds: string[]): Promise<AccountValuation[]> => {
+export const getLatestValuations = async (
+  accountIds: string[],
+  fxSide?: FxRateSide,
+): Promise<AccountValuation[]> => {
   try {
     switch (getRunEnv()) {
       case RUN_ENV.DESKTOP:
-        return invokeTauri("get_latest_valuations", { accountIds });
+        return invokeTauri("get_latest_valuations", { accountIds, fxSide });
       case RUN_ENV.WEB:
-        return invokeWeb("get_latest_valuations", { accountIds });
+        return invokeWeb("get_latest_valuations", { accountIds, fxSide });
       default:
         throw new Error(`Unsupported`);
     }
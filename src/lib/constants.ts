@@ -30,6 +30,7 @@ export const DataSource = {
   ALPHA_VANTAGE: "ALPHA_VANTAGE",
   METAL_PRICE_API: "METAL_PRICE_API",
   VN_MARKET: "VN_MARKET",
+  VN_FX: "VN_FX",
 } as const;
 
 export type DataSource = (typeof DataSource)[keyof typeof DataSource];
@@ -42,6 +43,7 @@ export const dataSourceSchema = z.enum([
   DataSource.ALPHA_VANTAGE,
   DataSource.METAL_PRICE_API,
   DataSource.VN_MARKET,
+  DataSource.VN_FX,
 ]);
 
 export const ImportFormat = {
@@ -38,6 +38,7 @@ export interface Activity {
   fee: number;
   isDraft: boolean;
   comment?: string | null;
+  fxRateSide?: FxRateSide | null;
   accountId?: string | null;
   createdAt: Date | string;
   symbolProfileId: string;
@@ -87,6 +88,7 @@ export interface ActivityCreate {
   fee?: number;
   isDraft: boolean;
   comment?: string | null;
+  fxRateSide?: FxRateSide | null;
 }
 
 export type ActivityUpdate = ActivityCreate & { id: string };
@@ -189,6 +191,131 @@ export interface Tag {
   activityId: string | null;
 }
 
+export type CompositeMemberType = "account" | "group";
+
+export interface CompositeMember {
+  memberType: CompositeMemberType;
+  memberId: string;
+}
+
+export interface CompositePortfolio {
+  id: string;
+  name: string;
+  description?: string | null;
+  members: CompositeMember[];
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface NewCompositePortfolio {
+  id?: string;
+  name: string;
+  description?: string | null;
+  members: CompositeMember[];
+}
+
+export type ReconciliationStatus = "matched" | "mismatched" | "broken";
+export type ReconciliationLineType = "cash" | "position";
+
+export interface BrokerStatement {
+  accountId: string;
+  statementDate: string;
+  cashBalances: Record<string, number>;
+  positions: Record<string, number>;
+  notes?: string | null;
+}
+
+export interface ReconciliationLine {
+  lineType: ReconciliationLineType;
+  code: string;
+  reported: number;
+  calculated: number;
+  difference: number;
+}
+
+export interface SuggestedActivity {
+  activityType: string;
+  assetId: string | null;
+  currency: string | null;
+  quantity: number | null;
+  amount: number | null;
+  reason: string;
+}
+
+export interface ReconciliationReport {
+  accountId: string;
+  statementDate: string;
+  lines: ReconciliationLine[];
+  suggestions: SuggestedActivity[];
+  isMatched: boolean;
+}
+
+export interface Reconciliation {
+  id: string;
+  accountId: string;
+  statementDate: string;
+  status: ReconciliationStatus;
+  notes: string | null;
+  lines: ReconciliationLine[];
+  brokenAt: string | null;
+  brokenByActivityId: string | null;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export type MarginStatus = "noLoan" | "safe" | "warning" | "call" | "forceSell";
+
+export interface MarginSettings {
+  accountId: string;
+  annualInterestRate: number;
+  callRatio: number;
+  forceSellRatio: number | null;
+  warningBuffer: number;
+  defaultHaircut: number;
+  haircuts: Record<string, number>;
+  interestStartDate: string;
+  accruedThrough: string | null;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface MarginSettingsUpdate {
+  accountId: string;
+  annualInterestRate: number;
+  callRatio: number;
+  forceSellRatio?: number | null;
+  warningBuffer?: number | null;
+  defaultHaircut?: number | null;
+  haircuts?: Record<string, number>;
+  interestStartDate?: string | null;
+}
+
+export interface MarginCollateral {
+  assetId: string;
+  quantity: number;
+  marketValue: number;
+  haircut: number;
+  collateralValue: number;
+}
+
+export interface MarginSummary {
+  accountId: string;
+  currency: string;
+  cashBalance: number;
+  loanBalance: number;
+  collateral: MarginCollateral[];
+  marketValue: number;
+  collateralValue: number;
+  equity: number;
+  marginRatio: number | null;
+  callRatio: number;
+  forceSellRatio: number | null;
+  status: MarginStatus;
+  warning: string | null;
+  unpostedInterest: number;
+  accruedThrough: string | null;
+}
+
 export interface ImportValidationResult {
   activities: ActivityImport[];
   validationSummary: {
@@ -347,6 +474,7 @@ export interface Settings {
   isPro: boolean;
   syncEnabled: boolean;
   language: string;
+  riskFreeRateSeries?: string;
 }
 
 export interface SettingsContextType {
@@ -368,6 +496,7 @@ export interface Goal {
   dueDate?: string;
   monthlyInvestment?: number;
   startDate?: string;
+  priority?: number | null;
   allocations?: GoalAllocation[];
 }
 
@@ -400,6 +529,174 @@ export interface GoalProgress {
   currency: string;
 }
 
+export interface GoalProjectionOptions {
+  expectedReturn?: number | null;
+  volatility?: number | null;
+  inflationRate?: number | null;
+  monthlyContribution?: number | null;
+  numPaths?: number | null;
+  seed?: number | null;
+}
+
+export type GoalReturnSource = "historical" | "assumed";
+
+export interface GoalProjectionPoint {
+  month: number;
+  date: string;
+  p10: number;
+  p50: number;
+  p90: number;
+}
+
+export interface GoalProjection {
+  goalId: string;
+  startDate: string;
+  dueDate: string;
+  targetAmount: number;
+  currentValue: number;
+  monthlyContribution: number;
+  returnSource: GoalReturnSource;
+  annualReturn: number;
+  annualVolatility: number;
+  inflationRate: number;
+  numPaths: number;
+  seed: number;
+  probabilityOfSuccess: number;
+  paths: GoalProjectionPoint[];
+}
+
+export interface GoalPlan {
+  goalId: string;
+  asOfDate: string;
+  dueDate: string | null;
+  monthsRemaining: number;
+  targetAmount: number;
+  currentValue: number;
+  expectedReturn: number;
+  monthlyInvestment: number;
+  requiredMonthlyContribution: number | null;
+  projectedValueAtDueDate: number | null;
+  projectedCompletionDate: string | null;
+  requiredReturn: number | null;
+  isOnTrack: boolean;
+}
+
+export type GoalHistoryInterval = "daily" | "monthly";
+
+export interface GoalAllocationDetail {
+  accountId: string;
+  percentAllocation: number;
+  accountValueAtGoalStart: number;
+  accountCurrentValue: number;
+  accountGrowth: number;
+  allocatedGrowth: number;
+}
+
+export interface GoalProgressSnapshot {
+  goalId: string;
+  goalTitle: string;
+  queryDate: string;
+  initValue: number;
+  currentValue: number;
+  growth: number;
+  allocationDetails: GoalAllocationDetail[];
+  targetValue: number | null;
+}
+
+export interface GoalProgressHistory {
+  goalId: string;
+  goalTitle: string;
+  startDate: string;
+  dueDate: string;
+  targetAmount: number;
+  interval: GoalHistoryInterval;
+  behindSince: string | null;
+  snapshots: GoalProgressSnapshot[];
+}
+
+export interface WaterfallShare {
+  accountId: string;
+  amount: number;
+  percentage: number;
+}
+
+export interface WaterfallGoal {
+  goalId: string;
+  title: string;
+  priority: number;
+  targetAmount: number;
+  fundedAmount: number;
+  isFunded: boolean;
+  shares: WaterfallShare[];
+}
+
+export interface WaterfallAllocation {
+  asOfDate: string;
+  availableValue: number;
+  unallocatedValue: number;
+  goals: WaterfallGoal[];
+  changedAllocationIds: string[];
+}
+
+export interface RetirementInputs {
+  currentAge: number;
+  retirementAge: number;
+  lifeExpectancy: number;
+  monthlySpending: number;
+  inflationRate: number;
+  monthlyPension?: number;
+  pensionStartAge?: number | null;
+  monthlyContribution?: number;
+  expectedReturn: number;
+  retirementReturn?: number | null;
+  volatility?: number;
+  currentValue?: number | null;
+  accountIds?: string[];
+  numPaths?: number | null;
+  seed?: number | null;
+}
+
+export interface RetirementProjectionPoint {
+  age: number;
+  year: number;
+  expectedValue: number;
+  p10: number;
+  p50: number;
+  p90: number;
+}
+
+export interface RetirementPlan {
+  currentValue: number;
+  retirementYear: number;
+  valueAtRetirement: number;
+  sustainableWithdrawalRate: number;
+  plannedWithdrawalRate: number | null;
+  depletionAge: number | null;
+  depletionYear: number | null;
+  successProbability: number;
+  simulatedDepletionAgeP10: number | null;
+  simulatedDepletionAgeP50: number | null;
+  simulatedDepletionYearP10: number | null;
+  simulatedDepletionYearP50: number | null;
+  extraMonthlySavingNeeded: number | null;
+  projection: RetirementProjectionPoint[];
+}
+
+export interface RetirementScenario {
+  id: string;
+  name: string;
+  inputs: RetirementInputs;
+  result: RetirementPlan | null;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface NewRetirementScenario {
+  id?: string;
+  name: string;
+  inputs: RetirementInputs;
+}
+
 export interface IncomeSummary {
   period: string;
   byMonth: Record<string, number>;
@@ -412,6 +709,71 @@ export interface IncomeSummary {
   yoyGrowth: number | null; // Changed from optional to nullable
 }
 
+export interface IncomeForecastEntry {
+  expectedDate: string;
+  accountId: string;
+  assetId: string;
+  incomeType: string;
+  sourceDate: string;
+  amountPerUnit: number | null;
+  quantity: number | null;
+  amount: number;
+  currency: string;
+  convertedAmount: number;
+}
+
+export interface IncomeForecast {
+  asOfDate: string;
+  currency: string;
+  byMonth: Record<string, number>;
+  byType: Record<string, number>;
+  bySymbol: Record<string, number>;
+  totalIncome: number;
+  monthlyAverage: number;
+  entries: IncomeForecastEntry[];
+}
+
+export type IncomeGrouping = "month" | "quarter" | "year";
+
+export interface IncomeReportFilter {
+  accountIds?: string[];
+  startDate?: string | null;
+  endDate?: string | null;
+  groupBy?: IncomeGrouping;
+  tagId?: string | null;
+}
+
+export interface IncomePeriod {
+  period: string;
+  startDate: string;
+  byType: Record<string, number>;
+  bySymbol: Record<string, number>;
+  totalIncome: number;
+}
+
+export interface IncomeSymbolYield {
+  symbol: string;
+  symbolName: string;
+  totalIncome: number;
+  trailingIncome: number;
+  costBasis: number;
+  marketValue: number;
+  yieldOnCost: number | null;
+  currentYield: number | null;
+}
+
+export interface IncomeReport {
+  currency: string;
+  startDate: string | null;
+  endDate: string;
+  groupBy: IncomeGrouping;
+  periods: IncomePeriod[];
+  byType: Record<string, number>;
+  byCurrency: Record<string, number>;
+  totalIncome: number;
+  symbols: IncomeSymbolYield[];
+}
+
 // Define custom DateRange type matching react-day-picker's
 export interface DateRange {
   from: Date | undefined;
@@ -476,11 +838,34 @@ export interface ExchangeRate {
   fromCurrencyName?: string;
   toCurrencyName?: string;
   rate: number;
+  bid?: number;
+  ask?: number;
   source: string;
   isLoading?: boolean;
   timestamp: string;
 }
 
+// Side of an FX quote: "bid" for liquidation, "ask" for purchases, "mid" for valuation
+export type FxRateSide = "bid" | "mid" | "ask";
+
+// FX spread paid on a cash transfer between accounts in different currencies
+export interface TransferFxCost {
+  pairId: string;
+  date: string;
+  currency: string;
+  amount: number;
+  fromAccountId: string;
+  fromCurrency: string;
+  toAccountId: string;
+  toCurrency: string;
+  amountSent: number;
+  amountReceived: number;
+  amountReceivedAtMid: number;
+  baseCurrency: string;
+  spreadCost: number; // In base currency
+  spreadPct: number;
+}
+
 export interface ContributionLimit {
   id: string;
   groupName: string;
@@ -489,10 +874,17 @@ export interface ContributionLimit {
   accountIds?: string | null;
   startDate?: string | null;
   endDate?: string | null;
+  limitType?: ContributionLimitType;
+  windowMonths?: number | null;
+  carryForward?: boolean;
+  restoreWithdrawals?: boolean;
+  lifetimeCap?: number | null;
   createdAt?: string;
   updatedAt?: string;
 }
 
+export type ContributionLimitType = "CALENDAR_YEAR" | "ROLLING";
+
 export type NewContributionLimit = Omit<ContributionLimit, "id" | "createdAt" | "updatedAt">;
 
 export interface AccountDeposit {
@@ -507,6 +899,51 @@ export interface DepositsCalculation {
   byAccount: Record<string, AccountDeposit>;
 }
 
+export interface ContributionYear {
+  year: number;
+  limitAmount: number;
+  carriedForward: number;
+  restoredWithdrawals: number;
+  availableRoom: number;
+  deposits: number;
+  withdrawals: number;
+  unusedRoom: number;
+}
+
+export interface ContributionRoom {
+  limitId: string;
+  groupName: string;
+  limitType: ContributionLimitType;
+  baseCurrency: string;
+  periodStart: string;
+  periodEnd: string;
+  limitAmount: number;
+  carriedForward: number;
+  restoredWithdrawals: number;
+  availableRoom: number;
+  contributed: number;
+  remainingRoom: number;
+  lifetimeCap: number | null;
+  lifetimeContributed: number;
+  lifetimeRemaining: number | null;
+  overContribution: number;
+  byAccount: Record<string, number>;
+  years: ContributionYear[];
+}
+
+export interface ContributionLimitWarning {
+  limitId: string;
+  groupName: string;
+  overContribution: number;
+  remainingRoom: number;
+  currency: string;
+  message: string;
+}
+
+export type ActivityWithLimitWarnings = Activity & {
+  contributionWarnings: ContributionLimitWarning[];
+};
+
 export const ACTIVITY_TYPE_PREFIX_LENGTH = 12;
 
 // Renamed from CumulativeReturn to match Rust struct ReturnData
@@ -529,8 +966,117 @@ export interface PerformanceMetrics {
   annualizedSimpleReturn: number; // Added field
   cumulativeMwr: number; // Added field, corresponds to MWR
   annualizedMwr: number; // Added field, corresponds to MWR
+  xirr?: number | null; // Annualized IRR of the dated cash flows
   volatility: number;
   maxDrawdown: number;
+  riskMetrics?: RiskMetrics | null;
+}
+
+export interface PeriodReturn {
+  startDate: string;
+  endDate: string;
+  value: number;
+}
+
+export interface RiskMetrics {
+  riskFreeSeries: string;
+  riskFreeRate?: number | null;
+  sharpeRatio?: number | null;
+  sortinoRatio?: number | null;
+  calmarRatio?: number | null;
+  downsideDeviation: number;
+  bestDay?: PeriodReturn | null;
+  worstDay?: PeriodReturn | null;
+  bestMonth?: PeriodReturn | null;
+  worstMonth?: PeriodReturn | null;
+  maxDrawdownPeakDate?: string | null;
+  maxDrawdownTroughDate?: string | null;
+  maxDrawdownRecoveryDate?: string | null; // Null while still below the peak
+}
+
+export type ReturnPeriodType = "month" | "quarter" | "year";
+
+export interface PeriodicReturn {
+  periodType: ReturnPeriodType;
+  year: number;
+  period: number; // Month (1-12) or quarter (1-4); 1 for years
+  startDate: string;
+  endDate: string;
+  value: number;
+  isPartial: boolean;
+}
+
+export interface PeriodicReturns {
+  id: string;
+  currency: string;
+  periodStartDate?: string | null;
+  periodEndDate?: string | null;
+  monthly: PeriodicReturn[];
+  quarterly: PeriodicReturn[];
+  yearly: PeriodicReturn[];
+}
+
+export interface HoldingContribution {
+  assetId: string;
+  symbol: string;
+  name?: string | null;
+  assetClass: string;
+  currency: string;
+  startValue: number;
+  endValue: number;
+  priceEffect: number; // Local price move
+  currencyEffect: number; // FX move against the reporting currency
+  totalContribution: number;
+}
+
+export interface AttributionGroup {
+  name: string;
+  priceEffect: number;
+  currencyEffect: number;
+  totalContribution: number;
+}
+
+export interface ReturnAttribution {
+  id: string;
+  currency: string;
+  periodStartDate?: string | null;
+  periodEndDate?: string | null;
+  portfolioReturn: number;
+  holdings: HoldingContribution[];
+  byAssetClass: AttributionGroup[];
+  bySector: AttributionGroup[];
+  byCurrency: AttributionGroup[];
+  residual: number; // Cash, income, fees and intraday trades
+}
+
+export interface RiskFreeRate {
+  id: string;
+  series: string;
+  effectiveDate: string;
+  annualRate: number; // Fraction, e.g. 0.048 for 4.8%
+}
+
+export interface BenchmarkValuePoint {
+  date: string;
+  portfolioValue: number;
+  benchmarkValue: number;
+}
+
+export interface BenchmarkComparison {
+  id: string;
+  benchmarkSymbol: string;
+  currency: string;
+  periodStartDate?: string | null;
+  periodEndDate?: string | null;
+  portfolioReturns: ReturnData[];
+  benchmarkReturns: ReturnData[];
+  whatIfValues: BenchmarkValuePoint[]; // Same cash flows invested in the benchmark
+  portfolioCumulativeReturn: number;
+  benchmarkCumulativeReturn: number;
+  excessReturn: number;
+  trackingError: number;
+  beta?: number | null;
+  correlation?: number | null;
 }
 
 export interface UpdateAssetProfile {
@@ -10,6 +10,7 @@ import {
   ActivityBulkMutationResult,
   ActivityCreate,
   ActivityUpdate,
+  ContributionLimitWarning,
 } from "@/lib/types";
 import { DataSource } from "@/lib/constants";
 import { updateQuote } from "@/commands/market-data";
@@ -68,6 +69,16 @@ export function useActivityMutations(
     }
   };
 
+  const warnAboutContributionLimits = (warnings: ContributionLimitWarning[]) => {
+    for (const warning of warnings) {
+      toast({
+        title: "Contribution limit exceeded.",
+        description: warning.message,
+        variant: "destructive",
+      });
+    }
+  };
+
   const createMutationOptions = (action: string) => ({
     onSuccess: (activity: { accountId?: string | null }) => {
       queryClient.invalidateQueries();
@@ -90,6 +101,7 @@ export function useActivityMutations(
       const { ...rest } = data;
       const activity = await createActivity(rest);
       await createQuoteFromActivity(data);
+      warnAboutContributionLimits(activity.contributionWarnings ?? []);
       return activity;
     },
     ...createMutationOptions("adding"),